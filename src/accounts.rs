@@ -0,0 +1,207 @@
+//! Named exchange accounts and per-account balances
+//!
+//! `exchange.accounts` lists extra API keys. [`AccountRouter`] decides which
+//! account a symbol's orders go to: an account listing the symbol wins, then
+//! one listing the running strategy, then [`DEFAULT_ACCOUNT`] (the top-level
+//! `api_key`). [`AccountBook`] keeps each account's capital and realized P&L,
+//! so one bot trading several sub-accounts can report them separately.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::config::AccountConfig;
+
+/// Account of symbols no `accounts` entry claims: the exchange section's own credentials
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+/// Which account each symbol trades on, for one strategy
+#[derive(Debug, Clone, Default)]
+pub struct AccountRouter {
+    by_symbol: HashMap<String, String>,
+    /// Account claiming the whole strategy
+    strategy_account: Option<String>,
+}
+
+impl AccountRouter {
+    pub fn new(accounts: &[AccountConfig], strategy: &str) -> Self {
+        let mut by_symbol = HashMap::new();
+        // First account to list a symbol keeps it
+        for account in accounts {
+            for symbol in &account.symbols {
+                by_symbol
+                    .entry(symbol.clone())
+                    .or_insert_with(|| account.name.clone());
+            }
+        }
+        Self {
+            by_symbol,
+            strategy_account: accounts
+                .iter()
+                .find(|a| a.strategies.iter().any(|s| s == strategy))
+                .map(|a| a.name.clone()),
+        }
+    }
+
+    /// Account `symbol` trades on
+    pub fn account_for(&self, symbol: &str) -> &str {
+        self.by_symbol
+            .get(symbol)
+            .or(self.strategy_account.as_ref())
+            .map_or(DEFAULT_ACCOUNT, String::as_str)
+    }
+}
+
+/// Capital and realized results of one account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountBalance {
+    pub account: String,
+    /// Starting capital set aside for the account
+    pub capital: f64,
+    /// Net of fees
+    pub realized_pnl: f64,
+    pub fees: f64,
+    pub trades: u32,
+}
+
+impl AccountBalance {
+    pub fn new(account: &str, capital: f64) -> Self {
+        Self {
+            account: account.to_string(),
+            capital,
+            realized_pnl: 0.0,
+            fees: 0.0,
+            trades: 0,
+        }
+    }
+
+    /// Capital plus realized P&L
+    pub fn balance(&self) -> f64 {
+        self.capital + self.realized_pnl
+    }
+}
+
+/// Balances of every account, by name
+#[derive(Debug, Clone, Default)]
+pub struct AccountBook {
+    balances: BTreeMap<String, AccountBalance>,
+}
+
+impl AccountBook {
+    /// One balance per configured account, plus [`DEFAULT_ACCOUNT`] with
+    /// whatever of `total_capital` no account claims
+    ///
+    /// Empty when no accounts are configured.
+    pub fn new(accounts: &[AccountConfig], total_capital: f64) -> Self {
+        if accounts.is_empty() {
+            return Self::default();
+        }
+        let mut balances: BTreeMap<String, AccountBalance> = accounts
+            .iter()
+            .map(|a| {
+                let balance = AccountBalance::new(&a.name, a.capital.unwrap_or(0.0));
+                (a.name.clone(), balance)
+            })
+            .collect();
+        let allocated: f64 = balances.values().map(|b| b.capital).sum();
+        balances
+            .entry(DEFAULT_ACCOUNT.to_string())
+            .or_insert_with(|| {
+                AccountBalance::new(DEFAULT_ACCOUNT, (total_capital - allocated).max(0.0))
+            });
+        Self { balances }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.balances.is_empty()
+    }
+
+    /// Book a closed trade's net P&L and fees against `account`
+    pub fn record_trade(&mut self, account: &str, net_pnl: f64, fees: f64) {
+        if self.is_empty() {
+            return;
+        }
+        let balance = self
+            .balances
+            .entry(account.to_string())
+            .or_insert_with(|| AccountBalance::new(account, 0.0));
+        balance.realized_pnl += net_pnl;
+        balance.fees += fees;
+        balance.trades += 1;
+    }
+
+    /// Take over saved results; configured capital wins over the saved one
+    pub fn restore(&mut self, saved: Vec<AccountBalance>) {
+        for saved in saved {
+            if let Some(balance) = self.balances.get_mut(&saved.account) {
+                balance.realized_pnl = saved.realized_pnl;
+                balance.fees = saved.fees;
+                balance.trades = saved.trades;
+            }
+        }
+    }
+
+    pub fn get(&self, account: &str) -> Option<&AccountBalance> {
+        self.balances.get(account)
+    }
+
+    pub fn balances(&self) -> impl Iterator<Item = &AccountBalance> {
+        self.balances.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(name: &str, symbols: &[&str], strategies: &[&str]) -> AccountConfig {
+        AccountConfig {
+            name: name.to_string(),
+            api_key: None,
+            api_secret: None,
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            strategies: strategies.iter().map(|s| s.to_string()).collect(),
+            capital: Some(20_000.0),
+        }
+    }
+
+    #[test]
+    fn test_symbols_route_before_strategies() {
+        let accounts = [
+            account("alts", &["SOLINR"], &[]),
+            account("swing", &["SOLINR", "XRPINR"], &["volatility_regime"]),
+        ];
+        let router = AccountRouter::new(&accounts, "volatility_regime");
+        assert_eq!(router.account_for("SOLINR"), "alts");
+        assert_eq!(router.account_for("XRPINR"), "swing");
+        assert_eq!(router.account_for("BTCINR"), "swing");
+        let other = AccountRouter::new(&accounts, "regime_grid");
+        assert_eq!(other.account_for("BTCINR"), DEFAULT_ACCOUNT);
+        assert_eq!(
+            AccountRouter::default().account_for("SOLINR"),
+            DEFAULT_ACCOUNT
+        );
+    }
+
+    #[test]
+    fn test_book_tracks_each_account() {
+        let accounts = [account("alts", &["SOLINR"], &[])];
+        let mut book = AccountBook::new(&accounts, 100_000.0);
+        assert_eq!(book.get(DEFAULT_ACCOUNT).unwrap().capital, 80_000.0);
+
+        book.record_trade("alts", 1_500.0, 40.0);
+        book.record_trade("alts", -500.0, 35.0);
+        book.record_trade(DEFAULT_ACCOUNT, 250.0, 10.0);
+        let alts = book.get("alts").unwrap();
+        assert_eq!(alts.balance(), 21_000.0);
+        assert_eq!((alts.fees, alts.trades), (75.0, 2));
+
+        let mut restarted = AccountBook::new(&accounts, 100_000.0);
+        restarted.restore(book.balances().cloned().collect());
+        assert_eq!(restarted.get("alts"), book.get("alts"));
+
+        // Without accounts nothing is tracked
+        let mut none = AccountBook::new(&[], 100_000.0);
+        none.record_trade(DEFAULT_ACCOUNT, 100.0, 1.0);
+        assert!(none.is_empty());
+    }
+}
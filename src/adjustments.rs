@@ -0,0 +1,217 @@
+//! Split / redenomination adjustments for historical candles
+//!
+//! Some tokens redenominate (e.g. 1000 old units become 1 new unit) or split,
+//! which shows up in raw history as a fake 99.9% crash or a 1000x jump. The
+//! loader rescales everything before the event so the series is continuous.
+//!
+//! Adjustments live in `<data_dir>/adjustments.json`:
+//!
+//! ```json
+//! [
+//!   { "symbol": "SHIBINR", "effective": "2024-06-01T00:00:00Z", "price_factor": 0.001,
+//!     "note": "1:1000 split" }
+//! ]
+//! ```
+//!
+//! Bars strictly before `effective` get prices multiplied by `price_factor` and
+//! volume divided by it (so notional is unchanged). A 1:1000 split (price drops
+//! 1000x) uses `0.001`; a 1000:1 redenomination (price jumps 1000x) uses `1000`.
+//!
+//! [`detect_discontinuities`] flags bar-to-bar moves no real market makes, so
+//! un-adjusted events are reported with a suggested `price_factor`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::warn;
+
+use crate::Candle;
+
+/// File name of the adjustments list inside the data directory
+pub const ADJUSTMENTS_FILE: &str = "adjustments.json";
+
+/// Bar-to-bar price ratio (either direction) treated as a likely redenomination
+pub const DISCONTINUITY_RATIO: f64 = 5.0;
+
+/// One split or redenomination event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Adjustment {
+    pub symbol: String,
+    /// First bar at the new denomination
+    pub effective: DateTime<Utc>,
+    /// Multiplier applied to prices before `effective`
+    pub price_factor: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Read `<data_dir>/adjustments.json` (empty when absent)
+pub fn load_adjustments(data_dir: impl AsRef<Path>) -> Result<Vec<Adjustment>> {
+    let path = data_dir.as_ref().join(ADJUSTMENTS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let adjustments: Vec<Adjustment> = serde_json::from_str(&text)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    for adj in &adjustments {
+        if !(adj.price_factor.is_finite() && adj.price_factor > 0.0) {
+            anyhow::bail!(
+                "{}: invalid price_factor {} for {}",
+                path.display(),
+                adj.price_factor,
+                adj.symbol
+            );
+        }
+    }
+    Ok(adjustments)
+}
+
+/// Rescale `candles` (oldest first) for every adjustment matching `symbol`
+///
+/// Returns the number of adjustments applied.
+pub fn apply_adjustments(
+    candles: &mut [Candle],
+    symbol: &str,
+    adjustments: &[Adjustment],
+) -> usize {
+    let mut applied = 0;
+    for adj in adjustments.iter().filter(|a| a.symbol == symbol) {
+        let before = candles.partition_point(|c| c.datetime < adj.effective);
+        if before == 0 {
+            continue;
+        }
+        for c in &mut candles[..before] {
+            c.open *= adj.price_factor;
+            c.high *= adj.price_factor;
+            c.low *= adj.price_factor;
+            c.close *= adj.price_factor;
+            c.volume /= adj.price_factor;
+        }
+        applied += 1;
+    }
+    applied
+}
+
+/// A suspicious jump between two consecutive bars
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discontinuity {
+    /// First bar after the jump (candidate `effective`)
+    pub at: DateTime<Utc>,
+    /// `open / previous close`
+    pub ratio: f64,
+    /// `price_factor` that would remove the jump, rounded to a power of ten
+    pub suggested_factor: f64,
+}
+
+/// Find bar-to-bar moves of at least `threshold`x in either direction
+pub fn detect_discontinuities(candles: &[Candle], threshold: f64) -> Vec<Discontinuity> {
+    candles
+        .windows(2)
+        .filter(|w| w[0].close > 0.0 && w[1].open > 0.0)
+        .filter_map(|w| {
+            let ratio = w[1].open / w[0].close;
+            (ratio >= threshold || ratio <= 1.0 / threshold).then(|| Discontinuity {
+                at: w[1].datetime,
+                ratio,
+                suggested_factor: 10f64.powi(ratio.log10().round() as i32),
+            })
+        })
+        .collect()
+}
+
+/// Apply the adjustments for `symbol`, then warn about any jump left over
+pub fn adjust_and_check(
+    candles: &mut [Candle],
+    symbol: &str,
+    timeframe: &str,
+    adjustments: &[Adjustment],
+) {
+    apply_adjustments(candles, symbol, adjustments);
+    for d in detect_discontinuities(candles, DISCONTINUITY_RATIO) {
+        warn!(
+            "{} {}: {:.4}x price jump at {} looks like a split/redenomination - add \
+             {{\"symbol\": \"{}\", \"effective\": \"{}\", \"price_factor\": {}}} to {}",
+            symbol,
+            timeframe,
+            d.ratio,
+            d.at.to_rfc3339(),
+            symbol,
+            d.at.to_rfc3339(),
+            d.suggested_factor,
+            ADJUSTMENTS_FILE
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    /// 10 bars at 1000, then 10 bars at ~1 (1:1000 split at bar 10)
+    fn split_series() -> Vec<Candle> {
+        let t0 = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..20)
+            .map(|i| {
+                let p = if i < 10 { 1000.0 } else { 1.0 };
+                Candle {
+                    datetime: t0 + Duration::days(i),
+                    open: p,
+                    high: p * 1.01,
+                    low: p * 0.99,
+                    close: p,
+                    volume: if i < 10 { 5.0 } else { 5000.0 },
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_and_apply_split() {
+        let mut candles = split_series();
+        let found = detect_discontinuities(&candles, DISCONTINUITY_RATIO);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].at, candles[10].datetime);
+        assert!((found[0].suggested_factor - 0.001).abs() < 1e-12);
+
+        let adjustments = vec![Adjustment {
+            symbol: "SHIBINR".to_string(),
+            effective: found[0].at,
+            price_factor: found[0].suggested_factor,
+            note: None,
+        }];
+        assert_eq!(apply_adjustments(&mut candles, "ETHINR", &adjustments), 0);
+        assert_eq!(apply_adjustments(&mut candles, "SHIBINR", &adjustments), 1);
+        assert!((candles[0].close - 1.0).abs() < 1e-9);
+        assert!((candles[0].volume - 5000.0).abs() < 1e-6);
+        assert!(detect_discontinuities(&candles, DISCONTINUITY_RATIO).is_empty());
+    }
+
+    #[test]
+    fn test_load_adjustments_file() {
+        let dir = std::env::temp_dir().join(format!("adjustments_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(load_adjustments(&dir).unwrap().is_empty());
+
+        std::fs::write(
+            dir.join(ADJUSTMENTS_FILE),
+            r#"[{"symbol": "SHIBINR", "effective": "2024-01-11T00:00:00Z", "price_factor": 0.001}]"#,
+        )
+        .unwrap();
+        let loaded = load_adjustments(&dir).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].price_factor, 0.001);
+
+        std::fs::write(
+            dir.join(ADJUSTMENTS_FILE),
+            r#"[{"symbol": "X", "effective": "2024-01-11T00:00:00Z", "price_factor": 0}]"#,
+        )
+        .unwrap();
+        assert!(load_adjustments(&dir).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
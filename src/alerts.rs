@@ -0,0 +1,347 @@
+//! Watchlist alerts
+//!
+//! Simple indicator conditions evaluated per symbol without running a
+//! strategy. Used by the `alerts` command, which polls candles and pushes
+//! an [`AlertEvent`] (log + webhooks) whenever a condition triggers.
+//!
+//! # Alerts file
+//!
+//! ```json
+//! {
+//!     "timeframe": "1h",
+//!     "poll_secs": 60,
+//!     "webhooks": [{ "url": "https://example.com/hooks/alerts" }],
+//!     "alerts": [
+//!         { "symbol": "BTCINR", "type": "rsi", "period": 14, "level": 30, "direction": "below" },
+//!         { "symbol": "ETHINR", "type": "ema", "period": 50, "direction": "above" },
+//!         { "symbol": "SOLINR", "type": "price", "level": 15000, "direction": "above" }
+//!     ]
+//! }
+//! ```
+//!
+//! Every condition is a cross: it fires on the bar where the value moves
+//! from one side of the reference to the other, at most once per bar.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::config::WebhookConfig;
+use crate::{indicators, Candle};
+
+/// Direction a value must cross its reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrossDirection {
+    Above,
+    Below,
+}
+
+/// Indicator condition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// RSI crossing a fixed level
+    Rsi {
+        period: usize,
+        level: f64,
+        direction: CrossDirection,
+    },
+    /// Close crossing a fixed price
+    Price {
+        level: f64,
+        direction: CrossDirection,
+    },
+    /// Close crossing its EMA
+    Ema {
+        period: usize,
+        direction: CrossDirection,
+    },
+}
+
+impl AlertCondition {
+    /// Candles needed before the condition can be evaluated reliably
+    pub fn lookback(&self) -> usize {
+        match self {
+            AlertCondition::Rsi { period, .. } => period * 3 + 2,
+            AlertCondition::Price { .. } => 2,
+            AlertCondition::Ema { period, .. } => period * 3 + 2,
+        }
+    }
+
+    /// Human-readable description (e.g., "RSI(14) crosses below 30")
+    pub fn describe(&self) -> String {
+        let dir = |d: &CrossDirection| match d {
+            CrossDirection::Above => "above",
+            CrossDirection::Below => "below",
+        };
+        match self {
+            AlertCondition::Rsi {
+                period,
+                level,
+                direction,
+            } => format!("RSI({}) crosses {} {}", period, dir(direction), level),
+            AlertCondition::Price { level, direction } => {
+                format!("Price crosses {} {}", dir(direction), level)
+            }
+            AlertCondition::Ema { period, direction } => {
+                format!("Price crosses {} EMA({})", dir(direction), period)
+            }
+        }
+    }
+
+    /// Check the last two bars for a cross. Returns `(value, reference)` on trigger.
+    pub fn evaluate(&self, candles: &[Candle]) -> Option<(f64, f64)> {
+        let closes: Vec<f64> = candles.iter().map(|c| c.close).collect();
+        let (values, references, direction): (Vec<Option<f64>>, Vec<Option<f64>>, _) = match self {
+            AlertCondition::Rsi {
+                period,
+                level,
+                direction,
+            } => (
+                indicators::rsi(&closes, *period),
+                vec![Some(*level); closes.len()],
+                direction,
+            ),
+            AlertCondition::Price { level, direction } => (
+                closes.iter().map(|c| Some(*c)).collect(),
+                vec![Some(*level); closes.len()],
+                direction,
+            ),
+            AlertCondition::Ema { period, direction } => (
+                closes.iter().map(|c| Some(*c)).collect(),
+                indicators::ema(&closes, *period),
+                direction,
+            ),
+        };
+
+        let n = values.len();
+        if n < 2 {
+            return None;
+        }
+        let (prev_v, prev_r) = (values[n - 2]?, references[n - 2]?);
+        let (cur_v, cur_r) = (values[n - 1]?, references[n - 1]?);
+
+        let crossed = match direction {
+            CrossDirection::Above => prev_v <= prev_r && cur_v > cur_r,
+            CrossDirection::Below => prev_v >= prev_r && cur_v < cur_r,
+        };
+        crossed.then_some((cur_v, cur_r))
+    }
+}
+
+/// A condition attached to a symbol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub symbol: String,
+    /// Optional label shown in notifications
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub condition: AlertCondition,
+}
+
+impl AlertRule {
+    pub fn label(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("{} {}", self.symbol, self.condition.describe()))
+    }
+}
+
+/// Alerts file contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertsConfig {
+    #[serde(default = "default_alert_timeframe")]
+    pub timeframe: String,
+    #[serde(default = "default_poll_secs")]
+    pub poll_secs: u64,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    pub alerts: Vec<AlertRule>,
+}
+
+fn default_alert_timeframe() -> String {
+    "1h".to_string()
+}
+
+fn default_poll_secs() -> u64 {
+    60
+}
+
+impl AlertsConfig {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = fs::read_to_string(path.as_ref()).context("Failed to read alerts file")?;
+        serde_json::from_str(&contents).context("Failed to parse alerts JSON")
+    }
+
+    /// Distinct symbols referenced by the rules, in first-seen order
+    pub fn symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = Vec::new();
+        for rule in &self.alerts {
+            if !symbols.contains(&rule.symbol) {
+                symbols.push(rule.symbol.clone());
+            }
+        }
+        symbols
+    }
+
+    /// Candles to fetch per symbol to cover the longest lookback
+    pub fn lookback(&self) -> usize {
+        self.alerts
+            .iter()
+            .map(|r| r.condition.lookback())
+            .max()
+            .unwrap_or(2)
+    }
+}
+
+/// Notification payload for a triggered alert
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub alert_id: String,
+    pub name: String,
+    pub symbol: String,
+    pub condition: String,
+    /// Indicator value that crossed
+    pub value: f64,
+    /// Level or indicator it crossed
+    pub reference: f64,
+    pub price: f64,
+    pub bar_time: DateTime<Utc>,
+    pub triggered_at: DateTime<Utc>,
+}
+
+/// Evaluates rules and suppresses repeat triggers on the same bar
+#[derive(Debug)]
+pub struct AlertMonitor {
+    rules: Vec<AlertRule>,
+    last_fired: HashMap<usize, DateTime<Utc>>,
+}
+
+impl AlertMonitor {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            rules,
+            last_fired: HashMap::new(),
+        }
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    /// Evaluate every rule for `symbol` against its latest candles
+    pub fn check(&mut self, symbol: &str, candles: &[Candle]) -> Vec<AlertEvent> {
+        let Some(last) = candles.last() else {
+            return Vec::new();
+        };
+
+        let mut events = Vec::new();
+        for (idx, rule) in self.rules.iter().enumerate() {
+            if rule.symbol != symbol || self.last_fired.get(&idx) == Some(&last.datetime) {
+                continue;
+            }
+            if let Some((value, reference)) = rule.condition.evaluate(candles) {
+                self.last_fired.insert(idx, last.datetime);
+                events.push(AlertEvent {
+                    alert_id: format!("alert-{}-{}", idx, last.datetime.timestamp_millis()),
+                    name: rule.label(),
+                    symbol: rule.symbol.clone(),
+                    condition: rule.condition.describe(),
+                    value,
+                    reference,
+                    price: last.close,
+                    bar_time: last.datetime,
+                    triggered_at: Utc::now(),
+                });
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn candles(closes: &[f64]) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                Candle::new_unchecked(start + Duration::hours(i as i64), c, c, c, c, 1.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_alerts_file() {
+        let json = r#"{
+            "alerts": [
+                { "symbol": "BTCINR", "type": "rsi", "period": 14, "level": 30, "direction": "below" },
+                { "symbol": "BTCINR", "type": "price", "level": 100, "direction": "above" },
+                { "symbol": "ETHINR", "type": "ema", "period": 50, "direction": "above", "name": "ETH trend" }
+            ]
+        }"#;
+        let config: AlertsConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.timeframe, "1h");
+        assert_eq!(config.symbols(), vec!["BTCINR", "ETHINR"]);
+        assert_eq!(config.lookback(), 152);
+        assert_eq!(config.alerts[2].label(), "ETH trend");
+    }
+
+    #[test]
+    fn test_price_cross_fires_once_per_bar() {
+        let rule = AlertRule {
+            symbol: "BTCINR".to_string(),
+            name: None,
+            condition: AlertCondition::Price {
+                level: 100.0,
+                direction: CrossDirection::Above,
+            },
+        };
+        let mut monitor = AlertMonitor::new(vec![rule]);
+
+        assert!(monitor.check("BTCINR", &candles(&[98.0, 99.0])).is_empty());
+
+        let bars = candles(&[98.0, 99.0, 101.0]);
+        let events = monitor.check("BTCINR", &bars);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].condition, "Price crosses above 100");
+        assert!(monitor.check("BTCINR", &bars).is_empty());
+        assert!(monitor.check("ETHINR", &bars).is_empty());
+    }
+
+    #[test]
+    fn test_rsi_and_ema_crosses() {
+        // Steady rally then a sharp drop pushes RSI through 50 and price under the EMA
+        let mut closes: Vec<f64> = (0..30).map(|i| 100.0 + i as f64).collect();
+        closes.push(100.0);
+        let bars = candles(&closes);
+
+        let rsi = AlertCondition::Rsi {
+            period: 14,
+            level: 50.0,
+            direction: CrossDirection::Below,
+        };
+        let (value, reference) = rsi.evaluate(&bars).unwrap();
+        assert!(value < 50.0 && reference == 50.0);
+
+        let ema = AlertCondition::Ema {
+            period: 10,
+            direction: CrossDirection::Below,
+        };
+        assert!(ema.evaluate(&bars).is_some());
+        let wrong_way = AlertCondition::Ema {
+            period: 10,
+            direction: CrossDirection::Above,
+        };
+        assert!(wrong_way.evaluate(&bars).is_none());
+    }
+}
@@ -0,0 +1,499 @@
+//! Counterfactual P&L attribution
+//!
+//! Splits a backtest's P&L between sizing, exits and entry selection by
+//! taking them away one at a time, cumulatively:
+//!
+//! 1. **Sizing**: every entry at the baseline's average notional instead of
+//!    being sized by risk
+//! 2. **Exits**: targets, trailing stops and the strategy's own exits replaced
+//!    by a time exit after the baseline's median holding period (the initial
+//!    stop stays as the protective exit)
+//! 3. **Entries**: the strategy's entries replaced by random ones at the
+//!    baseline's entry rate and long/short mix (mean of
+//!    [`RANDOM_ENTRY_RUNS`] seeds)
+//!
+//! Each bucket is the P&L its step gave up; **Random** is what remains once
+//! all three are gone (market drift net of costs). The buckets add up to the
+//! baseline P&L in every calendar quarter and in total. The steps are nested,
+//! so their order matters: an exit policy that only pays off with risk-based
+//! sizing shows up under sizing.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{BacktestResult, Backtester};
+use crate::oms::{OrderRequest, StrategyContext};
+use crate::strategies::combinators::{Combined, Layer};
+use crate::{strategies, Config, MultiSymbolMultiTimeframeData, Side, Strategy, Trade};
+
+/// Random-entry runs averaged for the entry counterfactual
+pub const RANDOM_ENTRY_RUNS: u64 = 5;
+/// Longest time exit; strategies see at most a 300-bar window
+const MAX_HOLD_BARS: usize = 250;
+
+/// Exit every position after `bars` primary bars
+///
+/// The wrapped strategy's exits, targets and trailing stops are dropped; its
+/// initial stop still applies.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedHold {
+    pub bars: usize,
+}
+
+impl Layer for FixedHold {
+    fn orders(&self, ctx: &StrategyContext, orders: Vec<OrderRequest>) -> Vec<OrderRequest> {
+        let Some(pos) = ctx.current_position else {
+            return orders;
+        };
+        let held = ctx
+            .candles
+            .iter()
+            .rev()
+            .take_while(|c| c.datetime > pos.first_entry_time)
+            .count();
+        if held < self.bars {
+            return Vec::new();
+        }
+        let quantity = pos.quantity.to_f64();
+        vec![match pos.side {
+            Side::Buy => OrderRequest::market_sell(ctx.symbol.clone(), quantity),
+            Side::Sell => OrderRequest::market_buy(ctx.symbol.clone(), quantity),
+        }]
+    }
+
+    fn take_profit(&self, entry_price: f64, side: Side, _target: f64) -> f64 {
+        match side {
+            Side::Buy => entry_price * 1e6,
+            Side::Sell => entry_price / 1e6,
+        }
+    }
+
+    fn trailing_stop(&self, _stop: Option<f64>) -> Option<f64> {
+        None
+    }
+}
+
+/// Replace the wrapped strategy's entries with random ones
+///
+/// While flat, each bar enters with probability `rate`, long with probability
+/// `long_share`. Draws depend only on the seed, symbol and bar time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RandomEntry {
+    pub rate: f64,
+    pub long_share: f64,
+    pub seed: u64,
+}
+
+impl Layer for RandomEntry {
+    fn orders(&self, ctx: &StrategyContext, orders: Vec<OrderRequest>) -> Vec<OrderRequest> {
+        if ctx.current_position.is_some() {
+            return orders;
+        }
+        let Some(candle) = ctx.candles.last() else {
+            return Vec::new();
+        };
+        let symbol_hash = ctx
+            .symbol
+            .as_str()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325u64, |h, b| {
+                (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+            });
+        let mut state = self.seed ^ symbol_hash ^ candle.datetime.timestamp() as u64;
+        if unit(&mut state) >= self.rate {
+            return Vec::new();
+        }
+        // The risk manager sizes the entry
+        if unit(&mut state) < self.long_share {
+            vec![OrderRequest::market_buy(ctx.symbol.clone(), 1.0)]
+        } else {
+            vec![OrderRequest::market_sell(ctx.symbol.clone(), 1.0)]
+        }
+    }
+}
+
+/// SplitMix64 step mapped to [0, 1)
+fn unit(state: &mut u64) -> f64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (z ^ (z >> 31)) as f64 / (u64::MAX as f64 + 1.0)
+}
+
+/// Baseline statistics the counterfactuals copy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryProfile {
+    pub entries: usize,
+    /// Mean notional per entry
+    pub notional: f64,
+    /// Median primary bars from entry to final exit
+    pub hold_bars: usize,
+    /// Entries per flat symbol-bar
+    pub rate: f64,
+    pub long_share: f64,
+}
+
+impl EntryProfile {
+    /// `None` when the baseline made no trades
+    pub fn from_trades(trades: &[Trade], data: &MultiSymbolMultiTimeframeData) -> Option<Self> {
+        // Exit-ladder tranches share their position's entry
+        let mut positions: HashMap<(&str, DateTime<Utc>), OpenedPosition> = HashMap::new();
+        for t in trades {
+            let entry = positions
+                .entry((t.symbol.as_str(), t.entry_time))
+                .or_insert(OpenedPosition {
+                    side: t.side,
+                    price: t.entry_price.to_f64(),
+                    quantity: 0.0,
+                    closed: t.exit_time,
+                });
+            entry.quantity += t.quantity.to_f64();
+            entry.closed = entry.closed.max(t.exit_time);
+        }
+        if positions.is_empty() {
+            return None;
+        }
+
+        let bars_between = |symbol: &str, from: DateTime<Utc>, to: DateTime<Utc>| {
+            data.iter()
+                .find(|(s, _)| s.as_str() == symbol)
+                .map_or(0, |(_, mtf)| {
+                    mtf.primary()
+                        .iter()
+                        .filter(|c| c.datetime > from && c.datetime <= to)
+                        .count()
+                })
+        };
+        let mut holds: Vec<usize> = positions
+            .iter()
+            .map(|((symbol, entry), p)| bars_between(symbol, *entry, p.closed))
+            .collect();
+        holds.sort_unstable();
+        let held: usize = holds.iter().sum();
+        let total_bars: usize = data.values().map(|mtf| mtf.primary().len()).sum();
+
+        let entries = positions.len();
+        let longs = positions.values().filter(|p| p.side == Side::Buy).count();
+        Some(Self {
+            entries,
+            notional: positions
+                .values()
+                .map(|p| p.price * p.quantity)
+                .sum::<f64>()
+                / entries as f64,
+            hold_bars: holds[holds.len() / 2].clamp(1, MAX_HOLD_BARS),
+            rate: (entries as f64 / total_bars.saturating_sub(held).max(1) as f64).min(1.0),
+            long_share: longs as f64 / entries as f64,
+        })
+    }
+}
+
+struct OpenedPosition {
+    side: Side,
+    price: f64,
+    quantity: f64,
+    /// Exit of the last tranche
+    closed: DateTime<Utc>,
+}
+
+/// P&L buckets for one period (or the whole run)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AttributionRow {
+    pub period: String,
+    pub entries: f64,
+    pub exits: f64,
+    pub sizing: f64,
+    pub random: f64,
+}
+
+impl AttributionRow {
+    /// The baseline's P&L
+    pub fn total(&self) -> f64 {
+        self.entries + self.exits + self.sizing + self.random
+    }
+}
+
+/// Baseline P&L split into entry, exit, sizing and random buckets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attribution {
+    pub profile: EntryProfile,
+    /// One row per calendar quarter
+    pub periods: Vec<AttributionRow>,
+    pub total: AttributionRow,
+}
+
+impl Attribution {
+    /// Run the counterfactual backtests against `baseline`
+    pub fn run(
+        config: &Config,
+        data: &MultiSymbolMultiTimeframeData,
+        baseline: &BacktestResult,
+    ) -> Result<Self> {
+        let profile = EntryProfile::from_trades(&baseline.trades, data)
+            .context("the baseline made no trades to attribute")?;
+        let counterfactual = |wrap: &dyn Fn(Box<dyn Strategy>) -> Box<dyn Strategy>| {
+            let strategy = wrap(strategies::create_strategy(config)?);
+            Ok::<_, anyhow::Error>(
+                Backtester::new(config.clone(), strategy)
+                    .with_fixed_entry_notional(profile.notional)
+                    .run(data),
+            )
+        };
+        let hold = FixedHold {
+            bars: profile.hold_bars,
+        };
+
+        let sized = counterfactual(&|s| s)?;
+        let held = counterfactual(&|s| Box::new(Combined::new(s, hold.clone())))?;
+        let random = (0..RANDOM_ENTRY_RUNS)
+            .map(|seed| {
+                counterfactual(&|s| {
+                    let held = Box::new(Combined::new(s, hold.clone()));
+                    Box::new(Combined::new(
+                        held,
+                        RandomEntry {
+                            rate: profile.rate,
+                            long_share: profile.long_share,
+                            seed,
+                        },
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let initial = config.trading.initial_capital;
+        Ok(Self::from_curves(
+            profile,
+            &quarterly_pnl(&baseline.equity_curve, initial),
+            &quarterly_pnl(&sized.equity_curve, initial),
+            &quarterly_pnl(&held.equity_curve, initial),
+            &random
+                .iter()
+                .map(|r| quarterly_pnl(&r.equity_curve, initial))
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    fn from_curves(
+        profile: EntryProfile,
+        baseline: &BTreeMap<String, f64>,
+        sized: &BTreeMap<String, f64>,
+        held: &BTreeMap<String, f64>,
+        random: &[BTreeMap<String, f64>],
+    ) -> Self {
+        // Runs stopped early by a trailing drawdown are flat afterwards
+        let at =
+            |pnl: &BTreeMap<String, f64>, quarter: &str| pnl.get(quarter).copied().unwrap_or(0.0);
+        let mut total = AttributionRow {
+            period: "Total".to_string(),
+            ..Default::default()
+        };
+        let periods = baseline
+            .keys()
+            .map(|quarter| {
+                let random =
+                    random.iter().map(|r| at(r, quarter)).sum::<f64>() / random.len().max(1) as f64;
+                let row = AttributionRow {
+                    period: quarter.clone(),
+                    sizing: at(baseline, quarter) - at(sized, quarter),
+                    exits: at(sized, quarter) - at(held, quarter),
+                    entries: at(held, quarter) - random,
+                    random,
+                };
+                total.sizing += row.sizing;
+                total.exits += row.exits;
+                total.entries += row.entries;
+                total.random += row.random;
+                row
+            })
+            .collect();
+        Self {
+            profile,
+            periods,
+            total,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("\n{}\n", "=".repeat(60)));
+        out.push_str("P&L ATTRIBUTION\n");
+        out.push_str(&format!("{}\n", "=".repeat(60)));
+        out.push_str(&format!(
+            "{:<9} {:>10} {:>10} {:>10} {:>10} {:>10}\n",
+            "Period", "Entries", "Exits", "Sizing", "Random", "Total"
+        ));
+        out.push_str(&format!("{}\n", "-".repeat(60)));
+        let row = |r: &AttributionRow| {
+            format!(
+                "{:<9} {:>10.2} {:>10.2} {:>10.2} {:>10.2} {:>10.2}\n",
+                r.period,
+                r.entries,
+                r.exits,
+                r.sizing,
+                r.random,
+                r.total()
+            )
+        };
+        for period in &self.periods {
+            out.push_str(&row(period));
+        }
+        out.push_str(&format!("{}\n", "-".repeat(60)));
+        out.push_str(&row(&self.total));
+        let total = self.total.total();
+        if total.abs() > f64::EPSILON {
+            out.push_str(&format!(
+                "{:<9} {:>9.0}% {:>9.0}% {:>9.0}% {:>9.0}%\n",
+                "Share",
+                self.total.entries / total * 100.0,
+                self.total.exits / total * 100.0,
+                self.total.sizing / total * 100.0,
+                self.total.random / total * 100.0
+            ));
+        }
+        out.push_str(&format!("{}\n", "-".repeat(60)));
+        out.push_str(&format!(
+            "Counterfactuals: {:.0} per entry, {}-bar time exit, random entries on {:.2}% of flat bars ({:.0}% long)\n",
+            self.profile.notional,
+            self.profile.hold_bars,
+            self.profile.rate * 100.0,
+            self.profile.long_share * 100.0
+        ));
+        out.push_str(&format!("{}\n", "=".repeat(60)));
+        out
+    }
+}
+
+/// Equity change per calendar quarter (`YYYY-Qn`)
+fn quarterly_pnl(curve: &[(DateTime<Utc>, f64)], initial: f64) -> BTreeMap<String, f64> {
+    let mut quarter_end: BTreeMap<String, f64> = BTreeMap::new();
+    for (t, equity) in curve {
+        let quarter = format!("{}-Q{}", t.year(), (t.month() - 1) / 3 + 1);
+        quarter_end.insert(quarter, *equity);
+    }
+    let mut previous = initial;
+    quarter_end
+        .into_iter()
+        .map(|(quarter, equity)| {
+            let pnl = equity - previous;
+            previous = equity;
+            (quarter, pnl)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oms::{Fill, Position};
+    use crate::{Candle, Symbol};
+    use chrono::{Duration, TimeZone};
+
+    fn candles(n: i64) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        (0..n)
+            .map(|i| {
+                Candle::new(start + Duration::days(i), 100.0, 101.0, 99.0, 100.0, 1.0).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_layers_time_exit_and_random_entries() {
+        let candles = candles(30);
+        let symbol = Symbol::new("BTCINR");
+        let fill = Fill::from_f64(1, 100.0, 2.0, candles[24].datetime, 0.0, false);
+        let position = Position::from_fill(fill, symbol.clone(), Side::Buy);
+        let hold = FixedHold { bars: 5 };
+        let signal = vec![OrderRequest::market_sell(symbol.clone(), 2.0)];
+
+        // Strategy exits are held back until the time exit
+        let ctx = StrategyContext::single_timeframe(
+            &symbol,
+            &candles[..29],
+            Some(&position),
+            &[],
+            1e5,
+            1e5,
+        );
+        assert!(hold.orders(&ctx, signal.clone()).is_empty());
+        let ctx =
+            StrategyContext::single_timeframe(&symbol, &candles, Some(&position), &[], 1e5, 1e5);
+        let exit = hold.orders(&ctx, Vec::new());
+        assert_eq!(exit.len(), 1);
+        assert_eq!(exit[0].side, Side::Sell);
+        assert_eq!(exit[0].quantity.to_f64(), 2.0);
+        assert_eq!(hold.trailing_stop(Some(99.0)), None);
+
+        let random = RandomEntry {
+            rate: 0.25,
+            long_share: 1.0,
+            seed: 7,
+        };
+        let entries: Vec<_> = (1..=400)
+            .map(|n| {
+                let candles: Vec<Candle> = (0..n)
+                    .map(|i| {
+                        let t =
+                            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap() + Duration::hours(i);
+                        Candle::new(t, 100.0, 101.0, 99.0, 100.0, 1.0).unwrap()
+                    })
+                    .collect();
+                let ctx = StrategyContext::single_timeframe(&symbol, &candles, None, &[], 1e5, 1e5);
+                random.orders(&ctx, signal.clone())
+            })
+            .collect();
+        let taken = entries.iter().filter(|o| !o.is_empty()).count();
+        assert!((60..140).contains(&taken), "{} entries", taken);
+        assert!(entries.iter().flatten().all(|o| o.side == Side::Buy));
+    }
+
+    #[test]
+    fn test_buckets_sum_to_baseline_each_quarter() {
+        let quarter = |pairs: &[(&str, f64)]| -> BTreeMap<String, f64> {
+            pairs.iter().map(|(m, v)| (m.to_string(), *v)).collect()
+        };
+        let profile = EntryProfile {
+            entries: 4,
+            notional: 1_000.0,
+            hold_bars: 5,
+            rate: 0.1,
+            long_share: 1.0,
+        };
+        let attribution = Attribution::from_curves(
+            profile,
+            &quarter(&[("2025-Q1", 500.0), ("2025-Q2", -100.0)]),
+            &quarter(&[("2025-Q1", 300.0), ("2025-Q2", -50.0)]),
+            &quarter(&[("2025-Q1", 200.0)]),
+            &[
+                quarter(&[("2025-Q1", 40.0)]),
+                quarter(&[("2025-Q1", -20.0), ("2025-Q2", 10.0)]),
+            ],
+        );
+
+        let q1 = &attribution.periods[0];
+        assert_eq!(
+            (q1.sizing, q1.exits, q1.entries, q1.random),
+            (200.0, 100.0, 190.0, 10.0)
+        );
+        // The time-exit run was stopped before the second quarter
+        let q2 = &attribution.periods[1];
+        assert_eq!(q2.exits, -50.0);
+        assert_eq!(q2.total(), -100.0);
+        assert_eq!(attribution.total.total(), 400.0);
+
+        let curve = [
+            (Utc.with_ymd_and_hms(2025, 1, 5, 0, 0, 0).unwrap(), 1_050.0),
+            (Utc.with_ymd_and_hms(2025, 3, 31, 0, 0, 0).unwrap(), 1_100.0),
+            (Utc.with_ymd_and_hms(2025, 4, 2, 0, 0, 0).unwrap(), 1_020.0),
+        ];
+        assert_eq!(
+            quarterly_pnl(&curve, 1_000.0),
+            quarter(&[("2025-Q1", 100.0), ("2025-Q2", -80.0)])
+        );
+    }
+}
@@ -0,0 +1,523 @@
+//! Order/fill audit trail for external risk systems
+//!
+//! With `exchange.audit_trail` set, live and paper sessions write every order
+//! event (accepted, filled, cancelled, rejected) to an append-only file in a
+//! normalized execution-report shape modelled on FIX: one JSON object per line
+//! with FIX-style field names, or `tag=value|...` lines using FIX tag numbers.
+//!
+//! Each record carries the hash of the record before it and its own SHA-256
+//! over everything else on the line, so an edited, dropped or reordered line
+//! breaks the chain; [`verify`] walks a session and reports the first break.
+//! Files rotate at `max_file_mb` and the chain continues across parts.
+//!
+//! | field           | tag  | notes                                      |
+//! |-----------------|------|--------------------------------------------|
+//! | `seq`           | 34   | 1-based, gap-free within a session         |
+//! | `session`       | 1    | account/session identifier                 |
+//! | `transact_time` | 60   | UTC                                        |
+//! | `exec_type`     | 150  | `0` new, `F` trade, `4` canceled, `8` rejected |
+//! | `ord_status`    | 39   | `0` new, `1` partial, `2` filled, `4`, `8` |
+//! | `cl_ord_id`     | 11   | intent ID when the order was staged        |
+//! | `order_id`      | 37   |                                            |
+//! | `symbol`        | 55   |                                            |
+//! | `side`          | 54   | `1` buy, `2` sell                          |
+//! | `ord_type`      | 40   | `1` market, `2` limit, `3` stop, `4` stop limit |
+//! | `order_qty`     | 38   |                                            |
+//! | `price`         | 44   | limit price                                |
+//! | `stop_px`       | 99   |                                            |
+//! | `last_qty`      | 32   | fills only                                 |
+//! | `last_px`       | 31   | fills only                                 |
+//! | `cum_qty`       | 14   |                                            |
+//! | `leaves_qty`    | 151  |                                            |
+//! | `commission`    | 12   | fills only                                 |
+//! | `text`          | 58   | reason for cancels and rejects             |
+//! | `mode`          | 9001 | `paper` or `live`                          |
+//! | `prev_hash`     | 9002 | hash of the previous record                |
+//! | `hash`          | 10   | SHA-256 of the line up to this field       |
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::config::{AuditFormat, AuditTrailConfig};
+use crate::oms::{Fill, Order, OrderState, OrderType};
+use crate::Side;
+
+/// `prev_hash` of a session's first record
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// What happened to the order (FIX ExecType)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecType {
+    #[serde(rename = "0")]
+    New,
+    #[serde(rename = "F")]
+    Trade,
+    #[serde(rename = "4")]
+    Canceled,
+    #[serde(rename = "8")]
+    Rejected,
+}
+
+impl ExecType {
+    fn code(self) -> &'static str {
+        match self {
+            ExecType::New => "0",
+            ExecType::Trade => "F",
+            ExecType::Canceled => "4",
+            ExecType::Rejected => "8",
+        }
+    }
+}
+
+/// One execution report
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub session: String,
+    pub mode: String,
+    pub transact_time: DateTime<Utc>,
+    pub exec_type: ExecType,
+    pub ord_status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cl_ord_id: Option<String>,
+    pub order_id: String,
+    pub symbol: String,
+    pub side: String,
+    pub ord_type: String,
+    pub order_qty: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_px: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_qty: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_px: Option<f64>,
+    pub cum_qty: f64,
+    pub leaves_qty: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commission: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    pub prev_hash: String,
+}
+
+impl AuditRecord {
+    /// Report for `order` in its current state; the trail fills in the
+    /// sequence, session, mode and chain fields when it is written
+    pub fn new(exec_type: ExecType, order: &Order) -> Self {
+        let ord_status = match (exec_type, order.state) {
+            (ExecType::Canceled, _) => "4",
+            (ExecType::Rejected, _) => "8",
+            (_, OrderState::Filled) => "2",
+            (_, OrderState::PartiallyFilled) => "1",
+            (_, OrderState::Cancelled | OrderState::Expired) => "4",
+            (_, OrderState::Rejected) => "8",
+            _ => "0",
+        };
+        Self {
+            seq: 0,
+            session: String::new(),
+            mode: String::new(),
+            transact_time: Utc::now(),
+            exec_type,
+            ord_status: ord_status.to_string(),
+            cl_ord_id: order.client_id.clone(),
+            order_id: order.id.to_string(),
+            symbol: order.symbol.as_str().to_string(),
+            side: match order.side {
+                Side::Buy => "1",
+                Side::Sell => "2",
+            }
+            .to_string(),
+            ord_type: match order.order_type {
+                OrderType::Market => "1",
+                OrderType::Limit => "2",
+                OrderType::Stop => "3",
+                OrderType::StopLimit => "4",
+            }
+            .to_string(),
+            order_qty: order.quantity.to_f64(),
+            price: order.limit_price.map(|p| p.to_f64()),
+            stop_px: order.stop_price.map(|p| p.to_f64()),
+            last_qty: None,
+            last_px: None,
+            cum_qty: order.filled_quantity.to_f64(),
+            leaves_qty: match exec_type {
+                ExecType::Canceled | ExecType::Rejected => 0.0,
+                _ => order.remaining_quantity.to_f64(),
+            },
+            commission: None,
+            text: None,
+            prev_hash: String::new(),
+        }
+    }
+
+    /// Fill report for `order` after `fill` was applied to it
+    pub fn fill(order: &Order, fill: &Fill) -> Self {
+        Self {
+            last_qty: Some(fill.quantity.to_f64()),
+            last_px: Some(fill.price.to_f64()),
+            commission: Some(fill.commission.to_f64()),
+            ..Self::new(ExecType::Trade, order)
+        }
+    }
+
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// `tag=value` pairs in a stable order, without the hash
+    fn fix_body(&self) -> String {
+        let mut fields: Vec<(u32, String)> = vec![
+            (34, self.seq.to_string()),
+            (1, self.session.clone()),
+            (
+                60,
+                self.transact_time.format("%Y%m%d-%H:%M:%S%.6f").to_string(),
+            ),
+            (150, self.exec_type.code().to_string()),
+            (39, self.ord_status.clone()),
+        ];
+        if let Some(id) = &self.cl_ord_id {
+            fields.push((11, id.clone()));
+        }
+        fields.extend([
+            (37, self.order_id.clone()),
+            (55, self.symbol.clone()),
+            (54, self.side.clone()),
+            (40, self.ord_type.clone()),
+            (38, self.order_qty.to_string()),
+        ]);
+        let optional = [
+            (44, self.price),
+            (99, self.stop_px),
+            (32, self.last_qty),
+            (31, self.last_px),
+        ];
+        fields.extend(
+            optional
+                .into_iter()
+                .filter_map(|(tag, v)| v.map(|v| (tag, v.to_string()))),
+        );
+        fields.push((14, self.cum_qty.to_string()));
+        fields.push((151, self.leaves_qty.to_string()));
+        if let Some(c) = self.commission {
+            fields.push((12, c.to_string()));
+        }
+        if let Some(text) = &self.text {
+            // Keep the delimiter out of free text
+            fields.push((58, text.replace('|', "/")));
+        }
+        fields.push((9001, self.mode.clone()));
+        fields.push((9002, self.prev_hash.clone()));
+        fields
+            .into_iter()
+            .map(|(tag, v)| format!("{}={}", tag, v))
+            .collect::<Vec<_>>()
+            .join("|")
+    }
+}
+
+fn sha256_hex(body: &str) -> String {
+    hex::encode(Sha256::digest(body.as_bytes()))
+}
+
+/// Line for `record` in `format` with its hash appended, and the hash
+fn encode(record: &AuditRecord, format: AuditFormat) -> Result<(String, String)> {
+    let body = match format {
+        AuditFormat::Jsonl => serde_json::to_string(record)?,
+        AuditFormat::Fix => record.fix_body(),
+    };
+    let hash = sha256_hex(&body);
+    let line = match format {
+        AuditFormat::Jsonl => format!("{},\"hash\":\"{}\"}}", &body[..body.len() - 1], hash),
+        AuditFormat::Fix => format!("{}|10={}", body, hash),
+    };
+    Ok((line, hash))
+}
+
+/// Check a line's hash; returns its (seq, prev_hash, hash)
+fn decode(line: &str, format: AuditFormat) -> Result<(u64, String, String)> {
+    let (body, hash) = match format {
+        AuditFormat::Jsonl => {
+            let idx = line.rfind(",\"hash\":\"").context("missing hash")?;
+            let hash = line[idx + 9..]
+                .strip_suffix("\"}")
+                .context("malformed hash")?;
+            (format!("{}}}", &line[..idx]), hash)
+        }
+        AuditFormat::Fix => {
+            let idx = line.rfind("|10=").context("missing hash")?;
+            (line[..idx].to_string(), &line[idx + 4..])
+        }
+    };
+    if sha256_hex(&body) != hash {
+        bail!("hash mismatch");
+    }
+    let (seq, prev) = match format {
+        AuditFormat::Jsonl => {
+            let record: AuditRecord = serde_json::from_str(&body)?;
+            (record.seq, record.prev_hash)
+        }
+        AuditFormat::Fix => {
+            let tag = |t: &str| {
+                body.split('|')
+                    .find_map(|f| f.strip_prefix(t))
+                    .map(str::to_string)
+                    .with_context(|| format!("missing tag {}", t))
+            };
+            (tag("34=")?.parse()?, tag("9002=")?)
+        }
+    };
+    Ok((seq, prev, hash.to_string()))
+}
+
+fn extension(format: AuditFormat) -> &'static str {
+    match format {
+        AuditFormat::Jsonl => "jsonl",
+        AuditFormat::Fix => "fix",
+    }
+}
+
+/// Path of part `part` of a session
+pub fn part_path(dir: &Path, session: &str, part: u32, format: AuditFormat) -> PathBuf {
+    dir.join(format!("{}-{:03}.{}", session, part, extension(format)))
+}
+
+/// Append-only writer for one session
+#[derive(Debug)]
+pub struct AuditTrail {
+    dir: PathBuf,
+    session: String,
+    mode: String,
+    format: AuditFormat,
+    max_bytes: u64,
+    part: u32,
+    written: u64,
+    file: File,
+    seq: u64,
+    last_hash: String,
+}
+
+impl AuditTrail {
+    /// Start a session; `paper` tags every record's mode
+    pub fn open(config: &AuditTrailConfig, session: &str, paper: bool) -> Result<Self> {
+        let dir = PathBuf::from(&config.output_dir);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create audit dir {}", dir.display()))?;
+        let path = part_path(&dir, session, 0, config.format);
+        let file = Self::open_part(&path)?;
+        Ok(Self {
+            dir,
+            session: session.to_string(),
+            mode: if paper { "paper" } else { "live" }.to_string(),
+            format: config.format,
+            max_bytes: config.max_file_mb.max(1) * 1024 * 1024,
+            part: 0,
+            written: file.metadata().map(|m| m.len()).unwrap_or(0),
+            file,
+            seq: 0,
+            last_hash: GENESIS_HASH.to_string(),
+        })
+    }
+
+    /// Rotate after `bytes` instead of the configured size
+    pub fn with_max_bytes(mut self, bytes: u64) -> Self {
+        self.max_bytes = bytes.max(1);
+        self
+    }
+
+    fn open_part(path: &Path) -> Result<File> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open audit file {}", path.display()))?;
+        if file.metadata()?.len() > 0 {
+            bail!("Audit file {} already exists", path.display());
+        }
+        Ok(file)
+    }
+
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    /// Path of the file being written
+    pub fn path(&self) -> PathBuf {
+        part_path(&self.dir, &self.session, self.part, self.format)
+    }
+
+    /// Chain, encode and append a record; flushed before returning
+    pub fn record(&mut self, mut record: AuditRecord) -> Result<()> {
+        record.seq = self.seq + 1;
+        record.session = self.session.clone();
+        record.mode = self.mode.clone();
+        record.prev_hash = self.last_hash.clone();
+        let (line, hash) = encode(&record, self.format)?;
+
+        if self.written > 0 && self.written + line.len() as u64 + 1 > self.max_bytes {
+            self.part += 1;
+            self.file = Self::open_part(&self.path())?;
+            self.written = 0;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.file.flush()?;
+
+        self.written += line.len() as u64 + 1;
+        self.seq = record.seq;
+        self.last_hash = hash;
+        Ok(())
+    }
+}
+
+/// Check a session's hash chain across all its parts; returns the record count
+pub fn verify(dir: &Path, session: &str, format: AuditFormat) -> Result<u64> {
+    let mut expected_prev = GENESIS_HASH.to_string();
+    let mut seq = 0;
+    for part in 0.. {
+        let path = part_path(dir, session, part, format);
+        if !path.exists() {
+            if part == 0 {
+                bail!("No audit file {}", path.display());
+            }
+            break;
+        }
+        let reader = BufReader::new(File::open(&path)?);
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            let at = || format!("{} line {}", path.display(), idx + 1);
+            let (line_seq, prev, hash) = decode(&line, format).with_context(at)?;
+            if line_seq != seq + 1 {
+                bail!("{}: expected seq {}, found {}", at(), seq + 1, line_seq);
+            }
+            if prev != expected_prev {
+                bail!("{}: chain broken (prev_hash does not match)", at());
+            }
+            seq = line_seq;
+            expected_prev = hash;
+        }
+    }
+    Ok(seq)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oms::{ExecutionEngine, TimeInForce};
+    use crate::Symbol;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("audit_{}_{}", name, std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    fn write_session(config: &AuditTrailConfig, max_bytes: u64) -> AuditTrail {
+        let mut trail = AuditTrail::open(config, "test", true)
+            .unwrap()
+            .with_max_bytes(max_bytes);
+        let mut order = Order::from_f64(
+            Symbol::new("BTCINR"),
+            Side::Buy,
+            OrderType::Limit,
+            2.0,
+            Some(100.0),
+            None,
+            TimeInForce::GTC,
+            Some("cs-1-1".into()),
+        );
+        trail
+            .record(AuditRecord::new(ExecType::New, &order))
+            .unwrap();
+        let fill = ExecutionEngine::new(0.001, 0.001, 0.0).execute_fill(
+            &mut order,
+            100.0,
+            true,
+            Utc::now(),
+        );
+        trail.record(AuditRecord::fill(&order, &fill)).unwrap();
+        trail
+            .record(
+                AuditRecord::new(ExecType::Rejected, &order).with_text("price_band: far | away"),
+            )
+            .unwrap();
+        trail
+    }
+
+    #[test]
+    fn test_chain_verifies_across_rotation_and_formats() {
+        for format in [AuditFormat::Jsonl, AuditFormat::Fix] {
+            let dir = temp_dir(extension(format));
+            let config = AuditTrailConfig {
+                output_dir: dir.to_string_lossy().to_string(),
+                format,
+                max_file_mb: 50,
+            };
+            // Tiny parts: every record rotates
+            let trail = write_session(&config, 10);
+            assert_eq!(trail.part, 2);
+            assert_eq!(verify(&dir, "test", format).unwrap(), 3);
+
+            let first = std::fs::read_to_string(part_path(&dir, "test", 0, format)).unwrap();
+            match format {
+                AuditFormat::Jsonl => {
+                    let record: AuditRecord = serde_json::from_str(first.trim_end()).unwrap();
+                    assert_eq!(record.exec_type, ExecType::New);
+                    assert_eq!(record.cl_ord_id.as_deref(), Some("cs-1-1"));
+                    assert_eq!(record.prev_hash, GENESIS_HASH);
+                }
+                AuditFormat::Fix => {
+                    assert!(first.starts_with("34=1|1=test|60="));
+                    assert!(first.contains("|150=0|39=0|11=cs-1-1|"));
+                }
+            }
+            let fill = std::fs::read_to_string(part_path(&dir, "test", 1, format)).unwrap();
+            assert!(fill.contains(if format == AuditFormat::Fix {
+                "|39=2|"
+            } else {
+                "\"ord_status\":\"2\""
+            }));
+            std::fs::remove_dir_all(&dir).ok();
+        }
+    }
+
+    #[test]
+    fn test_tampering_breaks_the_chain() {
+        let dir = temp_dir("tamper");
+        let config = AuditTrailConfig {
+            output_dir: dir.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        drop(write_session(&config, u64::MAX));
+        let path = part_path(&dir, "test", 0, AuditFormat::Jsonl);
+        let original = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(verify(&dir, "test", AuditFormat::Jsonl).unwrap(), 3);
+
+        // Edited quantity
+        std::fs::write(
+            &path,
+            original.replacen("\"last_qty\":2.0", "\"last_qty\":1.0", 1),
+        )
+        .unwrap();
+        let err = verify(&dir, "test", AuditFormat::Jsonl).unwrap_err();
+        assert!(format!("{:#}", err).contains("line 2"), "{:#}", err);
+
+        // Dropped record
+        let lines: Vec<&str> = original.lines().collect();
+        std::fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert!(verify(&dir, "test", AuditFormat::Jsonl).is_err());
+
+        // A session never overwrites an existing file
+        std::fs::write(&path, original).unwrap();
+        assert!(AuditTrail::open(&config, "test", true).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
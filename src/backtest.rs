@@ -13,14 +13,28 @@
 //! dimensionless numbers, requiring only that `initial_capital` (from config) and
 //! price data (from CSV files) are in the **same currency**.
 
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
-
-use crate::multi_timeframe::MultiTimeframeCandles;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::config::{EarlyStopConfig, ExitLadderConfig, LadderRung, SameBarPolicy};
+use crate::hedge::{self, Exposure, Hedger};
+use crate::multi_timeframe::{
+    sorted_multi_timeframe_data, MultiSymbolMultiTimeframeData, MultiTimeframeCandles,
+    MultiTimeframeData, Timeline,
+};
+use crate::oms::post_only::{self, PostOnlyDecision};
+use crate::oms::pre_trade::{PreTradeChain, PreTradeCheck, PreTradeContext};
 use crate::oms::{ExecutionEngine, Order, OrderBook, Position, PositionManager, StrategyContext};
-use crate::risk::RiskManager;
+use crate::risk::{EdgeCheck, MarketActivity, RiskManager, TrailingDrawdownEvent};
+use crate::risk_budget::{RiskBudget, SharedRiskBudget};
+use crate::strategies::context::{self, SignalContext};
+use crate::tail_risk::TailRiskMetrics;
 use crate::Strategy;
-use crate::{Config, Money, PerformanceMetrics, Side, Symbol, Trade};
+use crate::{
+    add_fee, Candle, Config, FeeLedger, FeeType, Money, PerformanceMetrics, Side, Symbol, Trade,
+};
 
 /// Backtest result container
 #[derive(Debug, Default)]
@@ -28,18 +42,113 @@ pub struct BacktestResult {
     pub trades: Vec<Trade>,
     pub equity_curve: Vec<(DateTime<Utc>, f64)>,
     pub metrics: PerformanceMetrics,
+    /// Set when the run was cut short by an [`EarlyStopConfig`] bound or a
+    /// trailing drawdown breach
+    pub truncated: Option<Truncation>,
+}
+
+/// Bound that ended a run early
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// Drawdown from peak exceeded `max_drawdown`
+    Drawdown,
+    /// Equity fell below `equity_floor` x initial capital
+    EquityFloor,
+    /// Equity hit the `trading.trailing_drawdown` floor and positions were flattened
+    TrailingDrawdown,
 }
 
+/// Where and why a run was stopped early
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Truncation {
+    pub reason: StopReason,
+    pub at: DateTime<Utc>,
+    /// Bars simulated, including the one that crossed the bound
+    pub bars_run: usize,
+    pub total_bars: usize,
+}
+
+impl Truncation {
+    /// Fraction of the data that was simulated
+    pub fn progress(&self) -> f64 {
+        if self.total_bars == 0 {
+            return 1.0;
+        }
+        self.bars_run as f64 / self.total_bars as f64
+    }
+}
+
+impl EarlyStopConfig {
+    /// First bound crossed by `equity`, if any
+    pub fn check(&self, equity: f64, peak: f64, initial_capital: f64) -> Option<StopReason> {
+        if let Some(max_dd) = self.max_drawdown {
+            if peak > 0.0 && (peak - equity) / peak > max_dd {
+                return Some(StopReason::Drawdown);
+            }
+        }
+        if let Some(floor) = self.equity_floor {
+            if equity < initial_capital * floor {
+                return Some(StopReason::EquityFloor);
+            }
+        }
+        None
+    }
+}
+
+/// Snapshot of a running backtest, see [`Backtester::with_progress`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestProgress {
+    /// Bars simulated so far
+    pub bar: usize,
+    pub total_bars: usize,
+    /// Time of the latest simulated bar
+    pub at: DateTime<Utc>,
+    /// Cash plus open positions at `at`
+    pub equity: f64,
+    pub peak_equity: f64,
+    /// Trades closed so far
+    pub trades: usize,
+    /// Trades closed since the previous snapshot
+    pub new_trades: Vec<Trade>,
+}
+
+impl BacktestProgress {
+    /// Share of the run completed, 0-100
+    pub fn percent(&self) -> f64 {
+        if self.total_bars == 0 {
+            return 100.0;
+        }
+        self.bar as f64 / self.total_bars as f64 * 100.0
+    }
+}
+
+/// Receives [`BacktestProgress`] snapshots while a run is in flight
+pub type ProgressSink = Box<dyn FnMut(BacktestProgress) + Send>;
+
 /// Production backtesting engine with OMS
 pub struct Backtester {
     config: Config,
     strategy: Box<dyn Strategy>,
     risk_manager: RiskManager,
     execution_engine: ExecutionEngine,
+    early_stop: Option<EarlyStopConfig>,
+    progress: Option<(usize, ProgressSink)>,
+    pre_trade: PreTradeChain,
+    /// `(start, end, multiplier)` windows of scaled slippage (stress tests)
+    slippage_windows: Vec<(DateTime<Utc>, DateTime<Utc>, f64)>,
+    /// Size every entry at this notional instead of by risk (attribution)
+    fixed_entry_notional: Option<f64>,
 }
 
 impl Backtester {
+    /// Panics on an invalid `trading.pre_trade_checks`; use [`Backtester::try_new`]
+    /// for configs that were not validated beforehand
     pub fn new(config: Config, strategy: Box<dyn Strategy>) -> Self {
+        Self::try_new(config, strategy).expect("invalid trading.pre_trade_checks")
+    }
+
+    pub fn try_new(config: Config, strategy: Box<dyn Strategy>) -> Result<Self> {
         let risk_manager = RiskManager::new(
             config.trading.initial_capital,
             config.trading.risk_per_trade,
@@ -53,20 +162,94 @@ impl Backtester {
             config.trading.drawdown_critical_multiplier,
             config.trading.consecutive_loss_limit,
             config.trading.consecutive_loss_multiplier,
-        );
+        )
+        .with_win_streak_scaling(
+            config.trading.win_streak_threshold,
+            config.trading.win_streak_step,
+            config.trading.win_streak_max_multiplier,
+        )
+        .with_group_limits(
+            &config.trading.symbol_groups,
+            &config.trading.group_exposure_caps,
+        )
+        .with_cvar_cap(config.trading.max_trade_cvar)
+        .with_trailing_drawdown(config.trading.trailing_drawdown.as_ref());
+        let risk_manager = match &config.trading.risk_budget {
+            Some(budget) => risk_manager.with_risk_budget(
+                RiskBudget::in_memory(budget.clone()).shared(),
+                strategy.name(),
+            ),
+            None => risk_manager,
+        };
 
         let execution_engine = ExecutionEngine::new(
-            config.exchange.maker_fee,
-            config.exchange.taker_fee,
+            config.exchange.effective_maker_fee(),
+            config.exchange.effective_taker_fee(),
             config.exchange.assumed_slippage,
         );
+        let pre_trade = PreTradeChain::from_config(&config.trading.pre_trade_checks)
+            .context("invalid trading.pre_trade_checks")?;
 
-        Self {
+        Ok(Self {
             config,
             strategy,
             risk_manager,
             execution_engine,
-        }
+            early_stop: None,
+            progress: None,
+            pre_trade,
+            slippage_windows: Vec::new(),
+            fixed_entry_notional: None,
+        })
+    }
+
+    /// Stop the run as soon as the equity curve crosses one of these bounds
+    /// (used by the optimizer; full backtests always run to the end)
+    pub fn with_early_stop(mut self, early_stop: Option<EarlyStopConfig>) -> Self {
+        self.early_stop = early_stop;
+        self
+    }
+
+    /// Draw entries from a risk budget shared with other backtesters
+    /// (replaces the private budget built from `trading.risk_budget`)
+    pub fn with_shared_risk_budget(mut self, budget: SharedRiskBudget) -> Self {
+        let name = self.strategy.name().to_string();
+        self.risk_manager = self.risk_manager.with_risk_budget(budget, &name);
+        self
+    }
+
+    /// Run every strategy order through `check` after the configured pre-trade checks
+    pub fn with_pre_trade_check(mut self, check: Box<dyn PreTradeCheck>) -> Self {
+        self.pre_trade = std::mem::take(&mut self.pre_trade).with_check(check);
+        self
+    }
+
+    /// Multiply slippage by `multiplier` on bars in `[start, end)`
+    pub fn with_slippage_window(
+        mut self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        multiplier: f64,
+    ) -> Self {
+        self.slippage_windows.push((start, end, multiplier));
+        self
+    }
+
+    /// Size every entry at `notional` instead of by stop distance and regime
+    ///
+    /// Group, CVaR and risk-budget limits and the cash check still apply.
+    pub fn with_fixed_entry_notional(mut self, notional: f64) -> Self {
+        self.fixed_entry_notional = Some(notional);
+        self
+    }
+
+    /// Report progress to `sink` every `every` bars, on the last bar and on an early stop
+    ///
+    /// Positions still open at the end are closed after the last snapshot;
+    /// their trades only appear in the [`BacktestResult`].
+    pub fn with_progress(mut self, every: usize, sink: ProgressSink) -> Self {
+        self.progress = Some((every.max(1), sink));
+        self
     }
 
     /// Unified backtest runner - handles both single-TF and MTF strategies
@@ -86,34 +269,26 @@ impl Backtester {
             tracing::debug!("Running MTF backtest with timeframes: {:?}", required_tfs);
         }
 
-        // Align data across symbols
-        let aligned = crate::multi_timeframe::align_multi_timeframe_data(data);
+        // Symbols keep their own histories; the timeline says which candle
+        // (if any) each symbol has at every bar
+        let aligned = sorted_multi_timeframe_data(data);
         if aligned.is_empty() {
-            tracing::error!("No aligned data after filtering");
+            tracing::error!("No primary timeframe data to backtest");
             return BacktestResult::default();
         }
+        let timeline = Timeline::new(&aligned);
 
         // Get primary timeframe info
         let primary_tf = aligned[0].1.primary_timeframe().to_string();
-        let primary_len = aligned
-            .iter()
-            .map(|(_, mtf)| mtf.primary().len())
-            .min()
-            .unwrap_or(0);
 
-        if primary_len == 0 {
-            return BacktestResult::default();
-        }
-
-        // Pre-collect dates for iteration
-        let dates: Vec<DateTime<Utc>> = aligned[0]
-            .1
-            .primary()
-            .iter()
-            .take(primary_len)
-            .map(|c| c.datetime)
+        // Late listings need their own warmup before taking entries
+        let warmup = self.config.backtest.listing_warmup_bars;
+        let listed_late: Vec<bool> = (0..aligned.len())
+            .map(|i| timeline.first_bar(i).is_some_and(|bar| bar > 0))
             .collect();
 
+        let dates = timeline.dates();
+
         // State - using OMS components
         let mut equity_curve = Vec::with_capacity(dates.len());
         let mut trades = Vec::new();
@@ -130,10 +305,45 @@ impl Backtester {
         // This cache fixes the stop/target at entry time like the main branch does
         // Format: (stop_price, target_price)
         let mut entry_levels: HashMap<Symbol, (f64, f64)> = HashMap::new();
+        // Strategy commentary of the latest accepted entry per symbol
+        let mut entry_notes: HashMap<Symbol, String> = HashMap::new();
 
         // Track trailing stops per symbol (matching main branch position.trailing_stop)
         let mut trailing_stops: HashMap<Symbol, f64> = HashMap::new();
 
+        // Partial take-profit ladder, tracked per open position
+        let exit_ladder = self.config.trading.exit_ladder.clone();
+        let mut ladder_states: HashMap<Symbol, LadderState> = HashMap::new();
+
+        // Exits decided by the same-bar policy (bar touched both stop and target)
+        let mut ambiguous_bars = 0usize;
+        // Entry signals whose target didn't clear round-trip costs
+        let mut edge_rejections = 0usize;
+        let mut budget_limited = 0usize;
+        let mut pre_trade_rejections = 0usize;
+        let mut activity_skips: BTreeMap<String, usize> = BTreeMap::new();
+        // Margin shorts pay borrow interest up to this time
+        let mut borrow_accrued: HashMap<Symbol, DateTime<Utc>> = HashMap::new();
+        // Portfolio hedge, traded on the hedge symbol's own candles
+        let mut hedger = self.config.trading.hedge.clone().and_then(|config| {
+            match aligned
+                .iter()
+                .position(|(s, _)| s.as_str() == config.symbol)
+            {
+                Some(idx) => Some((idx, Hedger::new(config, self.config.exchange.taker_fee))),
+                None => {
+                    tracing::warn!(
+                        "Hedge symbol {} has no data in this backtest - hedging disabled",
+                        config.symbol
+                    );
+                    None
+                }
+            }
+        });
+        let round_trip_cost = self.config.trading.min_edge.as_ref().map(|rules| {
+            rules.round_trip_cost(&self.config.exchange, &self.config.tax, rules.spread_bps)
+        });
+
         // Initialize orderbooks for each symbol
         for (symbol, _) in &aligned {
             orderbooks.insert(symbol.clone(), OrderBook::new());
@@ -142,18 +352,38 @@ impl Backtester {
         // Lookback window for indicators
         const LOOKBACK: usize = 300;
 
+        let mut truncated: Option<Truncation> = None;
+        // Trades already handed to the progress sink
+        let mut reported_trades = 0usize;
+
         // Main simulation loop
         for (bar_idx, current_date) in dates.iter().enumerate() {
-            let start_idx = bar_idx.saturating_sub(LOOKBACK - 1);
+            if !self.slippage_windows.is_empty() {
+                let multiplier: f64 = self
+                    .slippage_windows
+                    .iter()
+                    .filter(|(start, end, _)| start <= current_date && current_date < end)
+                    .map(|(_, _, m)| m)
+                    .product();
+                self.execution_engine
+                    .set_slippage(self.config.exchange.assumed_slippage * multiplier);
+            }
 
             // ================================================================
             // PHASE 0 (T+1 only): Execute orders queued from previous day
             // ================================================================
             if self.config.backtest.use_t1_execution && !t1_pending.is_empty() {
+                let mut deferred = Vec::new();
                 for (symbol, order_id) in t1_pending.drain(..) {
-                    if let Some((_, mtf_data)) = aligned.iter().find(|(s, _)| s == &symbol) {
-                        let primary = mtf_data.primary();
-                        let candle = &primary[bar_idx];
+                    if let Some(sym_idx) = aligned.iter().position(|(s, _)| s == &symbol) {
+                        let primary = aligned[sym_idx].1.primary();
+                        // No candle today: wait for the next one (delisted
+                        // symbols have no orders left)
+                        let Some(local) = timeline.local(sym_idx, bar_idx) else {
+                            deferred.push((symbol, order_id));
+                            continue;
+                        };
+                        let candle = &primary[local];
 
                         if let Some(orderbook) = orderbooks.get_mut(&symbol) {
                             if let Some(order) = orderbook.get_order_mut(order_id) {
@@ -161,7 +391,7 @@ impl Backtester {
                                     // Execute at open price with slippage
                                     let fill_price = candle.open
                                         * (1.0
-                                            + self.config.exchange.assumed_slippage
+                                            + self.execution_engine.slippage()
                                                 * if order.side == Side::Buy { 1.0 } else { -1.0 });
 
                                     // Check if we have enough cash for buy orders (matches main branch)
@@ -252,17 +482,21 @@ impl Backtester {
                                             position_manager.close_position(&symbol);
 
                                             // Use proper trade creation method
-                                            let trade = self.create_trade_from_position(
-                                                &prev,
-                                                fill.price.to_f64(),
-                                                candle.datetime,
+                                            let trade = self
+                                                .create_trade_from_position(
+                                                    &prev,
+                                                    fill.price.to_f64(),
+                                                    candle.datetime,
+                                                )
+                                                .with_rationale(
+                                                    entry_notes.get(&prev.symbol).cloned(),
+                                                );
+                                            let trade = tag_ladder_close(
+                                                trade,
+                                                ladder_states.remove(&symbol),
                                             );
 
-                                            if trade.net_pnl.is_positive() {
-                                                self.risk_manager.record_win();
-                                            } else {
-                                                self.risk_manager.record_loss();
-                                            }
+                                            self.risk_manager.record_trade(&trade);
 
                                             // Clear cached entry levels for closed position
                                             entry_levels.remove(&symbol);
@@ -285,16 +519,32 @@ impl Backtester {
                         }
                     }
                 }
+                t1_pending.extend(deferred);
             }
 
             // ================================================================
             // PHASE 1: Process fills - check all orders against current candle
             // ================================================================
-            for (symbol, mtf_data) in &aligned {
+            for (sym_idx, (symbol, mtf_data)) in aligned.iter().enumerate() {
+                let Some(local) = timeline.local(sym_idx, bar_idx) else {
+                    continue;
+                };
                 let primary = mtf_data.primary();
-                let candle = &primary[bar_idx];
+                let candle = &primary[local];
 
                 if let Some(orderbook) = orderbooks.get_mut(symbol) {
+                    // Good-till-date orders past their deadline leave the book unfilled
+                    for expired in orderbook.expire_orders(candle.datetime) {
+                        tracing::debug!(
+                            "{} EXPIRED {:?} {:?} {} qty={:.4}",
+                            candle.datetime.format("%Y-%m-%d %H:%M"),
+                            expired.order_type,
+                            expired.side,
+                            symbol,
+                            expired.remaining_quantity.to_f64()
+                        );
+                    }
+
                     let order_ids: Vec<u64> = orderbook.get_all_order_ids();
 
                     for order_id in order_ids {
@@ -402,18 +652,20 @@ impl Backtester {
                                 if had_position_before && (!has_position_after || side_changed) {
                                     // Position just closed or side reversed - create trade
                                     if let Some(closed_pos) = prev_pos {
-                                        let trade = self.create_trade_from_position(
-                                            &closed_pos,
-                                            fill.price.to_f64(),
-                                            candle.datetime,
-                                        );
+                                        let trade = self
+                                            .create_trade_from_position(
+                                                &closed_pos,
+                                                fill.price.to_f64(),
+                                                candle.datetime,
+                                            )
+                                            .with_rationale(
+                                                entry_notes.get(&closed_pos.symbol).cloned(),
+                                            );
+                                        let trade =
+                                            tag_ladder_close(trade, ladder_states.remove(symbol));
 
                                         // Record win/loss for risk manager
-                                        if trade.net_pnl.is_positive() {
-                                            self.risk_manager.record_win();
-                                        } else {
-                                            self.risk_manager.record_loss();
-                                        }
+                                        self.risk_manager.record_trade(&trade);
 
                                         tracing::debug!(
                                             "{} TRADE CLOSED {} (side_changed={}) PnL={:.2}",
@@ -470,22 +722,76 @@ impl Backtester {
             // ================================================================
             let mut total_value = cash;
 
-            for (symbol, mtf_data) in &aligned {
+            for (sym_idx, (symbol, mtf_data)) in aligned.iter().enumerate() {
                 let primary = mtf_data.primary();
-                let current_slice = &primary[start_idx..=bar_idx];
+                let Some(local) = timeline.local(sym_idx, bar_idx) else {
+                    // Gap in this symbol's history: hold at the last close
+                    if let (Some(pos), Some(last)) = (
+                        position_manager.get_position(symbol),
+                        timeline.latest(sym_idx, bar_idx),
+                    ) {
+                        total_value += pos.quantity.to_f64() * primary[last].close;
+                    }
+                    continue;
+                };
+                if listed_late[sym_idx] && local + 1 < warmup {
+                    continue;
+                }
+                let current_slice = &primary[local.saturating_sub(LOOKBACK - 1)..=local];
                 let candle = current_slice.last().unwrap();
                 let price = candle.close;
 
+                // Borrow interest on margin shorts, paid from cash as it accrues
+                if let (Some(borrow), Some(pos)) = (
+                    self.config.trading.borrow.as_ref(),
+                    position_manager.get_position_mut(symbol),
+                ) {
+                    if pos.side == Side::Sell {
+                        let from = borrow_accrued
+                            .get(symbol)
+                            .copied()
+                            .filter(|t| *t > pos.first_entry_time)
+                            .unwrap_or(pos.first_entry_time);
+                        let notional = pos.quantity.to_f64() * price;
+                        let interest =
+                            borrow.interest(symbol.as_str(), notional, from, candle.datetime);
+                        pos.accrue_borrow(interest);
+                        cash -= interest;
+                        total_value -= interest;
+                        borrow_accrued.insert(symbol.clone(), candle.datetime);
+                    }
+                }
+
+                // Delisting: liquidate at the last close and drop the symbol's orders
+                if timeline.delisted_at(sym_idx, bar_idx) {
+                    let cash_before = cash;
+                    self.delist(
+                        symbol,
+                        candle,
+                        &mut cash,
+                        &mut position_manager,
+                        &mut orderbooks,
+                        &mut trades,
+                        entry_notes.get(symbol).cloned(),
+                        ladder_states.remove(symbol),
+                    );
+                    total_value += cash - cash_before;
+                    t1_pending.retain(|(s, _)| s != symbol);
+                    entry_levels.remove(symbol);
+                    trailing_stops.remove(symbol);
+                    continue;
+                }
+
                 // Update position unrealized P&L first (before borrowing position)
                 let mut prices = HashMap::new();
                 prices.insert(symbol.clone(), price);
                 position_manager.update_unrealized_pnl(&prices);
 
                 // Get current position AFTER update (clone to allow mutation of manager later)
-                let position_data = position_manager.get_position(symbol).cloned();
+                let mut position_data = position_manager.get_position(symbol).cloned();
 
                 // Calculate total value
-                if let Some(pos) = &position_data {
+                if let Some(mut pos) = position_data.clone() {
                     total_value += pos.quantity.to_f64() * price;
 
                     // Use cached stop/target levels from entry time
@@ -543,16 +849,23 @@ impl Backtester {
                     // Main branch stores trailing_stop in position and uses:
                     //   active_stop = trailing_stop.unwrap_or(stop_price)
                     // We emulate this with a HashMap
+                    let trail_price = trailing_reference_price(
+                        pos.side,
+                        candle,
+                        self.config.trading.intrabar_trailing,
+                    );
                     let new_trailing =
                         self.strategy
-                            .update_trailing_stop(pos, price, current_slice);
+                            .update_trailing_stop(&pos, trail_price, current_slice);
 
                     // If strategy returns a new trailing stop, update our stored value
                     if let Some(new_stop) = new_trailing {
                         let current_stored = trailing_stops.get(symbol).copied();
-                        let best_stop = match current_stored {
-                            Some(stored) => new_stop.max(stored), // Never lower the trailing stop
-                            None => new_stop,
+                        // Never loosen the trailing stop: it only rises for longs, falls for shorts
+                        let best_stop = match (current_stored, pos.side) {
+                            (Some(stored), Side::Buy) => new_stop.max(stored),
+                            (Some(stored), Side::Sell) => new_stop.min(stored),
+                            (None, _) => new_stop,
                         };
                         trailing_stops.insert(symbol.clone(), best_stop);
                     }
@@ -560,16 +873,158 @@ impl Backtester {
                     // Use stored trailing stop if set, otherwise initial stop
                     let active_stop = trailing_stops.get(symbol).copied().unwrap_or(stop_price);
 
-                    // Match main branch: only check close price for stops
-                    let stopped = match pos.side {
-                        Side::Buy => price <= active_stop,
-                        Side::Sell => price >= active_stop,
-                    };
+                    // Exit ladder: take R-multiple tranches off before the runner's
+                    // stop/target check. Rungs fill on the bar's extreme like targets,
+                    // at once even in T+1 mode (resting limit orders).
+                    let mut target_price = target_price;
+                    if let Some(ladder) = &exit_ladder {
+                        if !ladder_states.contains_key(symbol) {
+                            if let Some(state) = LadderState::new(&pos, stop_price) {
+                                ladder_states.insert(symbol.clone(), state);
+                            }
+                        }
 
-                    let target_hit = match pos.side {
-                        Side::Buy => candle.high >= target_price,
-                        Side::Sell => candle.low <= target_price,
-                    };
+                        let mut ladder_closed = false;
+                        while let Some(state) = ladder_states.get_mut(symbol) {
+                            let Some((rung, qty)) = state.next_rung(ladder, pos.quantity.to_f64())
+                            else {
+                                break;
+                            };
+                            let rung_price = state.price_at(pos.side, rung.r);
+                            let rung_check = check_exit(
+                                pos.side,
+                                candle,
+                                active_stop,
+                                rung_price,
+                                self.config.trading.same_bar_policy,
+                            );
+                            if !rung_check.target_hit {
+                                break;
+                            }
+                            state.filled += 1;
+                            let state = *state;
+
+                            // Gapped past the rung: fill at the open
+                            let (exit_side, exec_price, slippage_factor) = match pos.side {
+                                Side::Buy => (
+                                    Side::Sell,
+                                    candle.open.max(rung_price),
+                                    1.0 - self.execution_engine.slippage(),
+                                ),
+                                Side::Sell => (
+                                    Side::Buy,
+                                    candle.open.min(rung_price),
+                                    1.0 + self.execution_engine.slippage(),
+                                ),
+                            };
+                            let mut rung_order = Order::new(
+                                symbol.clone(),
+                                exit_side,
+                                crate::oms::types::OrderType::Market,
+                                Money::from_f64(qty),
+                                None,
+                                None,
+                                crate::oms::types::TimeInForce::GTC,
+                                Some(format!("Ladder {}", rung_label(&rung))),
+                            );
+                            let fill = self.execution_engine.execute_fill(
+                                &mut rung_order,
+                                exec_price * slippage_factor,
+                                false,
+                                candle.datetime,
+                            );
+                            match exit_side {
+                                Side::Buy => {
+                                    cash -= (fill.price * fill.quantity + fill.commission).to_f64()
+                                }
+                                Side::Sell => {
+                                    cash += (fill.price * fill.quantity - fill.commission).to_f64()
+                                }
+                            }
+                            position_manager.add_fill(fill.clone(), symbol.clone(), exit_side);
+
+                            let trade = self
+                                .create_trade(
+                                    &pos,
+                                    fill.quantity.to_f64(),
+                                    fill.price.to_f64(),
+                                    candle.datetime,
+                                )
+                                .with_rationale(entry_notes.get(symbol).cloned())
+                                .with_tranche(
+                                    rung_label(&rung),
+                                    state.r_multiple(pos.side, fill.price.to_f64()),
+                                );
+                            tracing::info!(
+                                "{} {} LADDER {}: closed {:.6} @ {:.4} ({:.2}R)",
+                                candle.datetime.format("%Y-%m-%d"),
+                                symbol,
+                                rung_label(&rung),
+                                fill.quantity.to_f64(),
+                                fill.price.to_f64(),
+                                trade.r_multiple.unwrap_or(0.0)
+                            );
+                            self.risk_manager.record_trade(&trade);
+
+                            match position_manager.get_position(symbol) {
+                                Some(remaining) => {
+                                    pos = remaining.clone();
+                                    trades.push(trade);
+                                    self.strategy.on_order_filled(&fill, &pos);
+                                    // Lock in breakeven from the next bar
+                                    if ladder.breakeven_after_first && state.filled == 1 {
+                                        let stop = trailing_stops
+                                            .entry(symbol.clone())
+                                            .or_insert(state.entry_price);
+                                        *stop = match pos.side {
+                                            Side::Buy => stop.max(state.entry_price),
+                                            Side::Sell => stop.min(state.entry_price),
+                                        };
+                                    }
+                                }
+                                None => {
+                                    // Rungs took the whole position
+                                    position_manager.close_position(symbol);
+                                    entry_levels.remove(symbol);
+                                    trailing_stops.remove(symbol);
+                                    ladder_states.remove(symbol);
+                                    self.strategy.on_trade_closed(&trade);
+                                    trades.push(trade);
+                                    ladder_closed = true;
+                                    break;
+                                }
+                            }
+                        }
+                        if ladder_closed {
+                            continue;
+                        }
+                        position_data = Some(pos.clone());
+
+                        // The runner trails unless the strategy's target is kept
+                        if !ladder.keep_target {
+                            target_price = match pos.side {
+                                Side::Buy => f64::INFINITY,
+                                Side::Sell => f64::NEG_INFINITY,
+                            };
+                        }
+                    }
+
+                    // Stops on close, targets on the extreme; bars touching both
+                    // are resolved by the configured same-bar policy
+                    let ExitCheck {
+                        stopped,
+                        target_hit,
+                        ambiguous,
+                    } = check_exit(
+                        pos.side,
+                        candle,
+                        active_stop,
+                        target_price,
+                        self.config.trading.same_bar_policy,
+                    );
+                    if ambiguous {
+                        ambiguous_bars += 1;
+                    }
 
                     if stopped || target_hit {
                         let reason = if target_hit { "Target" } else { "Stop" };
@@ -652,8 +1107,8 @@ impl Backtester {
 
                         // Execute immediate fill with slippage
                         let slippage_factor = match close_order.side {
-                            Side::Sell => 1.0 - self.config.exchange.assumed_slippage,
-                            Side::Buy => 1.0 + self.config.exchange.assumed_slippage,
+                            Side::Sell => 1.0 - self.execution_engine.slippage(),
+                            Side::Buy => 1.0 + self.execution_engine.slippage(),
                         };
 
                         let fill = self.execution_engine.execute_fill(
@@ -703,17 +1158,16 @@ impl Backtester {
                                 position_manager.close_position(symbol);
                             }
 
-                            let trade = self.create_trade_from_position(
-                                pos,
-                                fill.price.to_f64(),
-                                candle.datetime,
-                            );
+                            let trade = self
+                                .create_trade_from_position(
+                                    &pos,
+                                    fill.price.to_f64(),
+                                    candle.datetime,
+                                )
+                                .with_rationale(entry_notes.get(&pos.symbol).cloned());
+                            let trade = tag_ladder_close(trade, ladder_states.remove(symbol));
 
-                            if trade.net_pnl.is_positive() {
-                                self.risk_manager.record_win();
-                            } else {
-                                self.risk_manager.record_loss();
-                            }
+                            self.risk_manager.record_trade(&trade);
 
                             self.strategy.on_trade_closed(&trade);
                             trades.push(trade);
@@ -729,7 +1183,7 @@ impl Backtester {
                         );
 
                         // Notify strategy
-                        self.strategy.on_order_filled(&fill, pos);
+                        self.strategy.on_order_filled(&fill, &pos);
 
                         continue;
                     }
@@ -792,9 +1246,33 @@ impl Backtester {
 
                 // Notify strategy of new bar (to update counters etc)
                 self.strategy.on_bar(&ctx);
+                let signal_ctx = SignalContext::new(&ctx).with_open_risk(context::open_risk(
+                    position_manager.get_all_positions().map(|(_, p)| p),
+                ));
+
+                // Dead markets get no new signals; open positions are still managed
+                let dead_reason = match &self.config.trading.activity_filter {
+                    Some(filter) if position_data.is_none() => {
+                        MarketActivity::measure(current_slice, filter.atr_period)
+                            .dead_reason(&filter.floors_for(symbol.as_str()))
+                    }
+                    _ => None,
+                };
 
                 // Get orders from strategy
-                let order_requests = self.strategy.generate_orders(&ctx);
+                let order_requests = match dead_reason {
+                    Some(reason) => {
+                        *activity_skips.entry(symbol.to_string()).or_default() += 1;
+                        tracing::trace!(
+                            "{} {} inactive: {}",
+                            candle.datetime.format("%Y-%m-%d"),
+                            symbol,
+                            reason
+                        );
+                        Vec::new()
+                    }
+                    None => self.strategy.generate_signal(&signal_ctx),
+                };
 
                 if !order_requests.is_empty() {
                     tracing::debug!(
@@ -807,8 +1285,13 @@ impl Backtester {
 
                 // Process each order request
                 for order_req in order_requests {
-                    let order = order_req.into_order();
                     let is_entry_order = position_data.is_none();
+                    let note = if is_entry_order {
+                        self.strategy.commentary(&signal_ctx, &order_req)
+                    } else {
+                        None
+                    };
+                    let order = order_req.into_order();
 
                     // CRITICAL FIX: Exit orders must be allowed even when trading is halted
                     // Otherwise positions can't close and drawdown stays above threshold
@@ -831,6 +1314,29 @@ impl Backtester {
                             continue;
                         }
 
+                        if let (Some(rules), Some(cost)) =
+                            (&self.config.trading.min_edge, round_trip_cost)
+                        {
+                            let target = self.strategy.calculate_take_profit(
+                                current_slice,
+                                price,
+                                order.side,
+                            );
+                            let edge = EdgeCheck::new(price, target, cost);
+                            if !edge.passes(rules.multiple) {
+                                tracing::debug!(
+                                    "{} {} expected move {:.2}% < {:.1}x cost {:.2}% - skipping entry",
+                                    candle.datetime.format("%Y-%m-%d"),
+                                    symbol,
+                                    edge.expected_move * 100.0,
+                                    rules.multiple,
+                                    edge.cost * 100.0
+                                );
+                                edge_rejections += 1;
+                                continue;
+                            }
+                        }
+
                         // Calculate position size based on risk
                         let regime_score = self.strategy.get_regime_score(current_slice);
 
@@ -840,13 +1346,40 @@ impl Backtester {
                             .map(|(_, p)| p)
                             .collect();
 
-                        let quantity = self.risk_manager.calculate_position_size_with_regime(
+                        let quantity = match self.fixed_entry_notional {
+                            Some(notional) => notional / price,
+                            None => self.risk_manager.calculate_position_size_with_regime(
+                                price,
+                                self.strategy
+                                    .calculate_stop_loss(current_slice, price, order.side),
+                                &all_positions,
+                                regime_score,
+                            ),
+                        };
+
+                        // Keep the symbol's group within its exposure cap
+                        let quantity = self.risk_manager.apply_group_limit(
+                            symbol.as_str(),
+                            price,
+                            quantity,
+                            all_positions.iter().copied(),
+                        );
+
+                        // Keep the trade's expected tail loss within the CVaR cap
+                        let quantity = self.risk_manager.apply_cvar_limit(price, quantity);
+
+                        // Stay within the day's risk budget shared with other strategies
+                        let budgeted = self.risk_manager.apply_risk_budget(
                             price,
                             self.strategy
                                 .calculate_stop_loss(current_slice, price, order.side),
-                            &all_positions,
-                            regime_score,
+                            quantity,
+                            candle.datetime,
                         );
+                        if budgeted < quantity {
+                            budget_limited += 1;
+                        }
+                        let quantity = budgeted;
 
                         if quantity <= 0.0 {
                             tracing::debug!("Risk manager returned zero quantity - skipping order");
@@ -864,14 +1397,16 @@ impl Backtester {
                         // NOTE: Only pre-cache if T+1 execution is enabled, otherwise let the
                         // lazy calculation handle it at position creation time
                         if self.config.backtest.use_t1_execution {
+                            // Limit entries fill at their limit, not at the close
+                            let entry_price = entry_order.limit_price.map_or(price, |p| p.to_f64());
                             let stop = self.strategy.calculate_stop_loss(
                                 current_slice,
-                                price,
+                                entry_price,
                                 entry_order.side,
                             );
                             let target = self.strategy.calculate_take_profit(
                                 current_slice,
-                                price,
+                                entry_price,
                                 entry_order.side,
                             );
                             entry_levels.insert(symbol.clone(), (stop, target));
@@ -884,12 +1419,40 @@ impl Backtester {
                             );
                         }
 
+                        // Kept until the next entry: partial exits of this position share it
+                        match note {
+                            Some(note) => entry_notes.insert(symbol.clone(), note),
+                            None => entry_notes.remove(symbol),
+                        };
+
                         entry_order
                     } else {
                         // Exit or grid order - use strategy's quantity as-is
                         order
                     };
 
+                    // Operational policy checks (`trading.pre_trade_checks`)
+                    if !self.pre_trade.is_empty() {
+                        let ctx = PreTradeContext {
+                            last_close: candle.close,
+                            now: candle.datetime,
+                            reduces_position: position_manager
+                                .get_position(symbol)
+                                .is_some_and(|p| p.side != final_order.side),
+                        };
+                        if let Err(rejection) = self.pre_trade.check(&final_order, &ctx) {
+                            tracing::debug!(
+                                "{} PRE-TRADE REJECT {:?} {}: {}",
+                                candle.datetime.format("%Y-%m-%d"),
+                                final_order.side,
+                                symbol,
+                                rejection
+                            );
+                            pre_trade_rejections += 1;
+                            continue;
+                        }
+                    }
+
                     // For T+1 mode: Queue market ENTRY orders for next bar's OPEN execution
                     // This matches main branch behavior where signals are generated at CLOSE,
                     // but orders execute at next bar's OPEN
@@ -927,8 +1490,8 @@ impl Backtester {
                     // This matches the behavior of signal-based backtesters
                     if final_order.order_type == crate::oms::types::OrderType::Market {
                         let slippage_factor = match final_order.side {
-                            Side::Buy => 1.0 + self.config.exchange.assumed_slippage,
-                            Side::Sell => 1.0 - self.config.exchange.assumed_slippage,
+                            Side::Buy => 1.0 + self.execution_engine.slippage(),
+                            Side::Sell => 1.0 - self.execution_engine.slippage(),
                         };
                         let fill_price = price * slippage_factor;
 
@@ -992,18 +1555,17 @@ impl Backtester {
                         if had_position_before && !has_position_after {
                             // Position just closed - create trade
                             if let Some(closed_pos) = prev_pos {
-                                let trade = self.create_trade_from_position(
-                                    &closed_pos,
-                                    fill.price.to_f64(),
-                                    candle.datetime,
-                                );
+                                let trade = self
+                                    .create_trade_from_position(
+                                        &closed_pos,
+                                        fill.price.to_f64(),
+                                        candle.datetime,
+                                    )
+                                    .with_rationale(entry_notes.get(&closed_pos.symbol).cloned());
+                                let trade = tag_ladder_close(trade, ladder_states.remove(symbol));
 
                                 // Record win/loss
-                                if trade.net_pnl.is_positive() {
-                                    self.risk_manager.record_win();
-                                } else {
-                                    self.risk_manager.record_loss();
-                                }
+                                self.risk_manager.record_trade(&trade);
 
                                 // Clear cached entry levels for closed position
                                 entry_levels.remove(symbol);
@@ -1035,6 +1597,29 @@ impl Backtester {
                             self.strategy.on_order_filled(&fill, pos);
                         }
                     } else {
+                        // Post-only limits that would cross the close are re-priced or dropped
+                        match self.config.exchange.post_only.check(
+                            &final_order,
+                            candle.close,
+                            candle.close,
+                        ) {
+                            PostOnlyDecision::Rest => {}
+                            PostOnlyDecision::Reprice { price, .. } => {
+                                post_only::reprice(&mut final_order, price)
+                            }
+                            PostOnlyDecision::Reject { price } => {
+                                tracing::debug!(
+                                    "{} POST-ONLY REJECT {:?} {} @ {:.2} would cross {:.2}",
+                                    candle.datetime.format("%Y-%m-%d"),
+                                    final_order.side,
+                                    symbol,
+                                    price,
+                                    candle.close
+                                );
+                                continue;
+                            }
+                        }
+
                         // Limit/Stop orders go to book for next execution
                         if let Some(orderbook) = orderbooks.get_mut(symbol) {
                             // Set created_bar_idx for look-ahead bias prevention
@@ -1061,6 +1646,44 @@ impl Backtester {
                 }
             }
 
+            // Size, resize or unwind the hedge against end-of-bar exposure
+            if let Some((hedge_idx, hedger)) = hedger.as_mut() {
+                if let Some(local) = timeline.latest(*hedge_idx, bar_idx) {
+                    let hedge_primary = aligned[*hedge_idx].1.primary();
+                    let closes: Vec<f64> = hedge_primary
+                        [local.saturating_sub(LOOKBACK - 1)..=local]
+                        .iter()
+                        .map(|c| c.close)
+                        .collect();
+                    let hedge_price = hedge_primary[local].close;
+                    let exposure = hedge_exposure(
+                        &aligned,
+                        &timeline,
+                        &position_manager,
+                        bar_idx,
+                        *hedge_idx,
+                        hedger.config().beta_lookback,
+                        total_value + hedger.unrealized_pnl(hedge_price),
+                    );
+                    let falling = hedge::deteriorating(&closes, hedger.config().regime_ema);
+                    if let Some(fill) = hedger.step(&exposure, hedge_price, falling, *current_date)
+                    {
+                        tracing::debug!(
+                            "{} HEDGE {:+.6} {} @ {:.2} (net exposure {:.0}%)",
+                            current_date.format("%Y-%m-%d"),
+                            -fill.quantity,
+                            hedger.config().symbol,
+                            hedge_price,
+                            exposure.net() * 100.0
+                        );
+                        cash += fill.cash_delta();
+                        total_value += fill.cash_delta();
+                        trades.extend(fill.trade);
+                    }
+                    total_value += hedger.unrealized_pnl(hedge_price);
+                }
+            }
+
             self.risk_manager.update_capital(total_value);
             equity_curve.push((*current_date, total_value));
 
@@ -1068,27 +1691,104 @@ impl Backtester {
             if total_value > peak_equity {
                 peak_equity = total_value;
             }
+
+            // A breach halts entries; with `flatten` the run ends here like an early stop
+            let mut breached = false;
+            match self.risk_manager.check_trailing_drawdown(total_value) {
+                Some(TrailingDrawdownEvent::Warning { floor, used, .. }) => tracing::warn!(
+                    "{} Trailing drawdown {:.0}% used (equity {:.2}, floor {:.2})",
+                    current_date.format("%Y-%m-%d"),
+                    used * 100.0,
+                    total_value,
+                    floor
+                ),
+                Some(TrailingDrawdownEvent::Breach { floor, .. }) => {
+                    tracing::warn!(
+                        "{} Trailing drawdown breached (equity {:.2} <= floor {:.2}) - trading halted",
+                        current_date.format("%Y-%m-%d"),
+                        total_value,
+                        floor
+                    );
+                    breached = self
+                        .config
+                        .trading
+                        .trailing_drawdown
+                        .as_ref()
+                        .is_some_and(|t| t.flatten);
+                }
+                None => {}
+            }
+
+            let stop_reason = if breached {
+                Some(StopReason::TrailingDrawdown)
+            } else {
+                self.early_stop.as_ref().and_then(|stop| {
+                    stop.check(
+                        total_value,
+                        peak_equity,
+                        self.config.trading.initial_capital,
+                    )
+                })
+            };
+
+            if let Some((every, sink)) = self.progress.as_mut() {
+                let bar = bar_idx + 1;
+                if bar % *every == 0 || bar == dates.len() || stop_reason.is_some() {
+                    sink(BacktestProgress {
+                        bar,
+                        total_bars: dates.len(),
+                        at: *current_date,
+                        equity: total_value,
+                        peak_equity,
+                        trades: trades.len(),
+                        new_trades: trades[reported_trades..].to_vec(),
+                    });
+                    reported_trades = trades.len();
+                }
+            }
+
+            if let Some(reason) = stop_reason {
+                tracing::debug!(
+                    "Early stop ({:?}) at {} after {}/{} bars",
+                    reason,
+                    current_date,
+                    bar_idx + 1,
+                    dates.len()
+                );
+                truncated = Some(Truncation {
+                    reason,
+                    at: *current_date,
+                    bars_run: bar_idx + 1,
+                    total_bars: dates.len(),
+                });
+                break;
+            }
         }
 
-        // Close remaining positions and convert to trades
-        for (symbol, mtf_data) in &aligned {
+        // Close remaining positions and convert to trades (at the stop bar if truncated)
+        let last_bar = truncated
+            .as_ref()
+            .map_or(dates.len() - 1, |t| t.bars_run - 1);
+        for (sym_idx, (symbol, mtf_data)) in aligned.iter().enumerate() {
             if let Some(pos) = position_manager.close_position(symbol) {
                 let primary = mtf_data.primary();
-                let last_candle = primary.last().unwrap();
+                let last_candle = match timeline.latest(sym_idx, last_bar) {
+                    Some(idx) => &primary[idx],
+                    None => primary.last().unwrap(),
+                };
                 let exit_price = last_candle.close;
 
                 // Clear cached entry levels for closed position
                 entry_levels.remove(symbol);
                 trailing_stops.remove(symbol);
 
-                let trade = self.create_trade_from_position(&pos, exit_price, last_candle.datetime);
+                let trade = self
+                    .create_trade_from_position(&pos, exit_price, last_candle.datetime)
+                    .with_rationale(entry_notes.get(&pos.symbol).cloned());
+                let trade = tag_ladder_close(trade, ladder_states.remove(symbol));
 
                 // Record win/loss for risk manager
-                if trade.net_pnl.is_positive() {
-                    self.risk_manager.record_win();
-                } else {
-                    self.risk_manager.record_loss();
-                }
+                self.risk_manager.record_trade(&trade);
 
                 // Notify strategy
                 self.strategy.on_trade_closed(&trade);
@@ -1097,12 +1797,115 @@ impl Backtester {
             }
         }
 
-        let metrics = self.calculate_metrics(&trades, &equity_curve, &primary_tf);
+        let hedger_stats = hedger.map(|(hedge_idx, mut hedger)| {
+            if let Some(local) = timeline.latest(hedge_idx, last_bar) {
+                let candle = &aligned[hedge_idx].1.primary()[local];
+                if let Some(fill) = hedger.close(candle.close, candle.datetime) {
+                    trades.extend(fill.trade);
+                }
+            }
+            hedger.stats()
+        });
+
+        let mut metrics = self.calculate_metrics(&trades, &equity_curve, &primary_tf);
+        metrics.hedge = hedger_stats;
+        metrics.ambiguous_bars = ambiguous_bars;
+        metrics.edge_rejections = edge_rejections;
+        metrics.budget_limited = budget_limited;
+        metrics.pre_trade_rejections = pre_trade_rejections;
+        metrics.activity_skips = activity_skips;
         BacktestResult {
             trades,
             equity_curve,
             metrics,
+            truncated,
+        }
+    }
+
+    /// Close a delisted symbol's position at `candle`'s close and cancel its orders
+    #[allow(clippy::too_many_arguments)]
+    fn delist(
+        &mut self,
+        symbol: &Symbol,
+        candle: &Candle,
+        cash: &mut f64,
+        position_manager: &mut PositionManager,
+        orderbooks: &mut HashMap<Symbol, OrderBook>,
+        trades: &mut Vec<Trade>,
+        rationale: Option<String>,
+        ladder: Option<LadderState>,
+    ) {
+        let mut cancelled = 0;
+        if let Some(orderbook) = orderbooks.get_mut(symbol) {
+            for order_id in orderbook.get_all_order_ids() {
+                if orderbook
+                    .get_order_mut(order_id)
+                    .is_some_and(|o| o.is_active())
+                {
+                    orderbook.cancel_order(order_id);
+                    cancelled += 1;
+                }
+            }
+        }
+
+        let Some(pos) = position_manager.get_position(symbol).cloned() else {
+            tracing::info!(
+                "{} {} DELISTED: no position, {} order(s) cancelled",
+                candle.datetime.format("%Y-%m-%d"),
+                symbol,
+                cancelled
+            );
+            return;
+        };
+
+        let exit_side = match pos.side {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        };
+        let mut close_order = Order::new(
+            symbol.clone(),
+            exit_side,
+            crate::oms::types::OrderType::Market,
+            pos.quantity,
+            None,
+            None,
+            crate::oms::types::TimeInForce::GTC,
+            Some("Delisted".to_string()),
+        );
+        let slippage_factor = match exit_side {
+            Side::Sell => 1.0 - self.execution_engine.slippage(),
+            Side::Buy => 1.0 + self.execution_engine.slippage(),
+        };
+        let fill = self.execution_engine.execute_fill(
+            &mut close_order,
+            candle.close * slippage_factor,
+            false,
+            candle.datetime,
+        );
+        match exit_side {
+            Side::Buy => *cash -= (fill.price * fill.quantity + fill.commission).to_f64(),
+            Side::Sell => *cash += (fill.price * fill.quantity - fill.commission).to_f64(),
         }
+        position_manager.add_fill(fill.clone(), symbol.clone(), exit_side);
+        position_manager.close_position(symbol);
+
+        let trade = self
+            .create_trade_from_position(&pos, fill.price.to_f64(), candle.datetime)
+            .with_rationale(rationale);
+        let trade = tag_ladder_close(trade, ladder);
+        tracing::info!(
+            "{} {} DELISTED: closed {:?} {:.6} @ {:.4} PnL={:.2}, {} order(s) cancelled",
+            candle.datetime.format("%Y-%m-%d"),
+            symbol,
+            pos.side,
+            fill.quantity.to_f64(),
+            fill.price.to_f64(),
+            trade.net_pnl.to_f64(),
+            cancelled
+        );
+        self.risk_manager.record_trade(&trade);
+        self.strategy.on_trade_closed(&trade);
+        trades.push(trade);
     }
 
     fn create_trade_from_position(
@@ -1110,17 +1913,58 @@ impl Backtester {
         pos: &Position,
         exit_price: f64,
         exit_time: DateTime<Utc>,
+    ) -> Trade {
+        self.create_trade(pos, pos.quantity.to_f64(), exit_price, exit_time)
+    }
+
+    /// Trade record for closing `quantity` of `pos`; entry fees are pro-rated
+    fn create_trade(
+        &self,
+        pos: &Position,
+        quantity: f64,
+        exit_price: f64,
+        exit_time: DateTime<Utc>,
     ) -> Trade {
         let entry_price = pos.average_entry_price.to_f64();
-        let quantity = pos.quantity.to_f64();
+        let share = Money::from_f64(match pos.quantity.to_f64() {
+            open if open > 0.0 => (quantity / open).min(1.0),
+            _ => 1.0,
+        });
 
         let pnl = match pos.side {
             Side::Buy => (exit_price - entry_price) * quantity,
             Side::Sell => (entry_price - exit_price) * quantity,
         };
 
-        let commission = pos.fills.iter().map(|f| f.commission.to_f64()).sum::<f64>()
-            + exit_price * quantity * self.config.exchange.taker_fee;
+        let gst_rate = self.config.exchange.gst_rate;
+        let mut fees = FeeLedger::new();
+        for fill in &pos.fills {
+            for (kind, amount) in fill.fee_ledger(gst_rate) {
+                add_fee(&mut fees, kind, amount * share);
+            }
+        }
+        let exit_fee = exit_price * quantity * self.config.exchange.taker_fee;
+        add_fee(&mut fees, FeeType::Taker, Money::from_f64(exit_fee));
+        add_fee(
+            &mut fees,
+            FeeType::Gst,
+            Money::from_f64(exit_fee * gst_rate),
+        );
+
+        // TDS is withheld on the sell leg: the exit for longs, the entry for shorts
+        let sell_price = match pos.side {
+            Side::Buy => exit_price,
+            Side::Sell => entry_price,
+        };
+        let tds = sell_price * quantity * self.config.tax.tds_rate;
+        add_fee(&mut fees, FeeType::Tds, Money::from_f64(tds));
+        add_fee(&mut fees, FeeType::Borrow, pos.borrow_cost * share);
+
+        let commission: f64 = fees
+            .iter()
+            .filter(|(kind, _)| kind.is_cost())
+            .map(|(_, amount)| amount.to_f64())
+            .sum();
 
         let net_pnl = pnl - commission;
 
@@ -1136,6 +1980,7 @@ impl Backtester {
             commission,
             net_pnl,
         )
+        .with_fees(fees)
     }
 
     fn calculate_metrics(
@@ -1258,7 +2103,12 @@ impl Backtester {
         let tax = taxable_gains * tax_rate;
         let post_tax_return = ((final_equity - initial_capital - tax) / initial_capital) * 100.0;
 
-        PerformanceMetrics::new(
+        let mut fees_by_type = std::collections::BTreeMap::new();
+        for (kind, amount) in trades.iter().flat_map(|t| t.fees.iter()) {
+            *fees_by_type.entry(*kind).or_insert(0.0) += amount.to_f64();
+        }
+
+        let mut metrics = PerformanceMetrics::new(
             total_return,
             post_tax_return,
             sharpe,
@@ -1276,6 +2126,304 @@ impl Backtester {
             largest_loss,
             total_commission,
             tax,
-        )
+        );
+        metrics.fees_by_type = fees_by_type
+            .into_iter()
+            .map(|(kind, amount)| (kind, (amount * 100.0).round() / 100.0))
+            .collect();
+        metrics.tail_risk = TailRiskMetrics::compute(equity_curve, trades);
+        metrics
+    }
+}
+
+/// Price used to ratchet trailing stops for a bar.
+///
+/// Bar-close mode uses the close; intrabar mode uses the most favourable
+/// extreme (high for longs, low for shorts), which tracks the peak more tightly.
+pub fn trailing_reference_price(side: Side, candle: &Candle, intrabar: bool) -> f64 {
+    match (intrabar, side) {
+        (false, _) => candle.close,
+        (true, Side::Buy) => candle.high,
+        (true, Side::Sell) => candle.low,
+    }
+}
+
+/// Outcome of the per-bar stop/target check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitCheck {
+    pub stopped: bool,
+    pub target_hit: bool,
+    /// The bar's range contained both levels and `policy` picked the winner
+    pub ambiguous: bool,
+}
+
+/// Check a position's stop and target against a bar.
+///
+/// Stops trigger on the close and targets on the bar's extreme. When the bar
+/// touches both levels the fill order is unknown and `policy` decides which
+/// exit is taken.
+pub fn check_exit(
+    side: Side,
+    candle: &Candle,
+    stop: f64,
+    target: f64,
+    policy: SameBarPolicy,
+) -> ExitCheck {
+    let (stopped, stop_touched, target_hit) = match side {
+        Side::Buy => (
+            candle.close <= stop,
+            candle.low <= stop,
+            candle.high >= target,
+        ),
+        Side::Sell => (
+            candle.close >= stop,
+            candle.high >= stop,
+            candle.low <= target,
+        ),
+    };
+
+    if !(stop_touched && target_hit) {
+        return ExitCheck {
+            stopped,
+            target_hit,
+            ambiguous: false,
+        };
+    }
+
+    let target_first = match policy {
+        SameBarPolicy::StopFirst => false,
+        SameBarPolicy::TargetFirst => true,
+        SameBarPolicy::Path => {
+            // The extreme nearer the open is assumed to print first
+            let high_first = candle.high - candle.open <= candle.open - candle.low;
+            match side {
+                Side::Buy => high_first,
+                Side::Sell => !high_first,
+            }
+        }
+    };
+    ExitCheck {
+        stopped: !target_first,
+        target_hit: target_first,
+        ambiguous: true,
+    }
+}
+
+/// Progress of one open position through the exit ladder
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LadderState {
+    pub entry_price: f64,
+    /// Quantity when the ladder started; rung fractions apply to this
+    pub initial_qty: f64,
+    /// One R: distance from entry to the initial stop
+    pub risk_per_unit: f64,
+    /// Rungs already taken, in config order
+    pub filled: usize,
+}
+
+impl LadderState {
+    /// `None` when the stop sits at the entry (no risk to measure R against)
+    pub fn new(pos: &Position, initial_stop: f64) -> Option<Self> {
+        let entry_price = pos.average_entry_price.to_f64();
+        let risk_per_unit = (entry_price - initial_stop).abs();
+        if risk_per_unit <= 0.0 || !risk_per_unit.is_finite() {
+            return None;
+        }
+        Some(Self {
+            entry_price,
+            initial_qty: pos.quantity.to_f64(),
+            risk_per_unit,
+            filled: 0,
+        })
+    }
+
+    /// Price `r` R in the position's favour
+    pub fn price_at(&self, side: Side, r: f64) -> f64 {
+        match side {
+            Side::Buy => self.entry_price + r * self.risk_per_unit,
+            Side::Sell => self.entry_price - r * self.risk_per_unit,
+        }
+    }
+
+    /// Exit distance from entry in R
+    pub fn r_multiple(&self, side: Side, exit_price: f64) -> f64 {
+        match side {
+            Side::Buy => (exit_price - self.entry_price) / self.risk_per_unit,
+            Side::Sell => (self.entry_price - exit_price) / self.risk_per_unit,
+        }
+    }
+
+    /// Next rung to take and the quantity it closes, capped at `remaining`
+    pub fn next_rung(
+        &self,
+        ladder: &ExitLadderConfig,
+        remaining: f64,
+    ) -> Option<(LadderRung, f64)> {
+        let rung = *ladder.rungs.get(self.filled)?;
+        let qty = (rung.fraction * self.initial_qty).min(remaining);
+        (qty > 0.0).then_some((rung, qty))
+    }
+
+    /// Attribute the close of whatever the rungs left: `"runner"`, or `"full"` if no rung filled
+    pub fn tag_close(&self, trade: Trade) -> Trade {
+        let r = self.r_multiple(trade.side, trade.exit_price.to_f64());
+        let tranche = if self.filled > 0 { "runner" } else { "full" };
+        trade.with_tranche(tranche, r)
+    }
+}
+
+/// Exposure of the open positions at `bar`, betas measured against the hedge symbol
+fn hedge_exposure(
+    aligned: &[(Symbol, MultiTimeframeData)],
+    timeline: &Timeline,
+    position_manager: &PositionManager,
+    bar: usize,
+    hedge_idx: usize,
+    beta_lookback: usize,
+    equity: f64,
+) -> Exposure {
+    let history = |sym_idx: usize| -> &[Candle] {
+        let primary = aligned[sym_idx].1.primary();
+        match timeline.latest(sym_idx, bar) {
+            Some(last) => &primary[(last + 1).saturating_sub(beta_lookback + 1)..=last],
+            None => &[],
+        }
+    };
+    let closes = |candles: &[Candle]| candles.iter().map(|c| c.close).collect::<Vec<_>>();
+    let market = closes(history(hedge_idx));
+    let positions: Vec<(f64, Vec<f64>)> = aligned
+        .iter()
+        .enumerate()
+        .filter_map(|(sym_idx, (symbol, _))| {
+            let pos = position_manager.get_position(symbol)?;
+            let quantity = match pos.side {
+                Side::Buy => pos.quantity.to_f64(),
+                Side::Sell => -pos.quantity.to_f64(),
+            };
+            Some((quantity, closes(history(sym_idx))))
+        })
+        .collect();
+    Exposure::measure(
+        positions.iter().map(|(q, c)| (*q, c.as_slice())),
+        &market,
+        beta_lookback,
+        equity,
+    )
+}
+
+/// Tranche label for a rung, e.g. `"1R"`, `"1.5R"`
+pub fn rung_label(rung: &LadderRung) -> String {
+    format!("{}R", rung.r)
+}
+
+/// Tag a full close with its ladder attribution, if the position had a ladder
+fn tag_ladder_close(trade: Trade, state: Option<LadderState>) -> Trade {
+    match state {
+        Some(state) => state.tag_close(trade),
+        None => trade,
+    }
+}
+
+/// Per-tranche summary of laddered trades
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrancheStats {
+    pub tranche: String,
+    pub trades: usize,
+    pub avg_r: f64,
+    pub net_pnl: f64,
+}
+
+/// Group trade records by exit-ladder tranche (rungs in R order, then runner/full)
+pub fn tranche_attribution(trades: &[Trade]) -> Vec<TrancheStats> {
+    let mut stats: Vec<TrancheStats> = Vec::new();
+    for trade in trades {
+        let Some(tranche) = &trade.tranche else {
+            continue;
+        };
+        let idx = match stats.iter().position(|s| &s.tranche == tranche) {
+            Some(idx) => idx,
+            None => {
+                stats.push(TrancheStats {
+                    tranche: tranche.clone(),
+                    ..Default::default()
+                });
+                stats.len() - 1
+            }
+        };
+        let entry = &mut stats[idx];
+        entry.trades += 1;
+        entry.avg_r += trade.r_multiple.unwrap_or(0.0);
+        entry.net_pnl += trade.net_pnl.to_f64();
+    }
+    for s in &mut stats {
+        s.avg_r /= s.trades as f64;
+    }
+    let order = |t: &str| {
+        t.strip_suffix('R')
+            .and_then(|r| r.parse::<f64>().ok())
+            .unwrap_or(f64::INFINITY)
+    };
+    stats.sort_by(|a, b| order(&a.tranche).total_cmp(&order(&b.tranche)));
+    stats
+}
+
+/// Open profit surrendered between a trade's best price and its exit
+#[derive(Debug, Clone, Default)]
+pub struct GiveBackStats {
+    pub trades: usize,
+    /// Average peak favourable excursion, % of entry price
+    pub avg_mfe_pct: f64,
+    /// Average distance from peak to exit, % of entry price
+    pub avg_give_back_pct: f64,
+    /// Share of the peak excursion kept at exit (1.0 = exited at the peak)
+    pub capture_ratio: f64,
+}
+
+/// Measure give-back per trade from the primary-timeframe candles it was held through
+pub fn give_back_stats(trades: &[Trade], data: &MultiSymbolMultiTimeframeData) -> GiveBackStats {
+    let mut mfe_sum = 0.0;
+    let mut give_back_sum = 0.0;
+    let mut count = 0;
+
+    for trade in trades {
+        let Some(mtf) = data.get(&trade.symbol) else {
+            continue;
+        };
+        let entry = trade.entry_price.to_f64();
+        let exit = trade.exit_price.to_f64();
+        if entry <= 0.0 {
+            continue;
+        }
+
+        let held = mtf
+            .primary()
+            .iter()
+            .filter(|c| c.datetime >= trade.entry_time && c.datetime <= trade.exit_time);
+        let peak = match trade.side {
+            Side::Buy => held.map(|c| c.high).fold(entry.max(exit), f64::max),
+            Side::Sell => held.map(|c| c.low).fold(entry.min(exit), f64::min),
+        };
+
+        let (mfe, give_back) = match trade.side {
+            Side::Buy => (peak - entry, peak - exit),
+            Side::Sell => (entry - peak, exit - peak),
+        };
+        mfe_sum += mfe / entry * 100.0;
+        give_back_sum += give_back / entry * 100.0;
+        count += 1;
+    }
+
+    if count == 0 {
+        return GiveBackStats::default();
+    }
+    GiveBackStats {
+        trades: count,
+        avg_mfe_pct: mfe_sum / count as f64,
+        avg_give_back_pct: give_back_sum / count as f64,
+        capture_ratio: if mfe_sum > 0.0 {
+            (mfe_sum - give_back_sum) / mfe_sum
+        } else {
+            0.0
+        },
     }
 }
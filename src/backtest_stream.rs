@@ -0,0 +1,73 @@
+//! Backtests as an async stream of progress snapshots
+//!
+//! Multi-year runs take a while; a GUI or terminal UI wants to draw the
+//! equity curve as it grows instead of waiting for the result. The run happens
+//! on a blocking thread and every [`BacktestProgress`] is forwarded through a
+//! channel:
+//!
+//! ```ignore
+//! let mut stream = BacktestStream::spawn(backtester, data, 50);
+//! while let Some(p) = stream.next().await {
+//!     println!("{:5.1}%  equity {:.0}  trades {}", p.percent(), p.equity, p.trades);
+//! }
+//! let result = stream.finish().await?;
+//! ```
+//!
+//! [`BacktestStream`] also implements [`futures_core::Stream`] for use with
+//! stream combinators.
+
+use anyhow::{Context, Result};
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::backtest::{BacktestProgress, BacktestResult, Backtester};
+use crate::MultiSymbolMultiTimeframeData;
+
+/// A backtest running in the background
+pub struct BacktestStream {
+    rx: mpsc::UnboundedReceiver<BacktestProgress>,
+    handle: JoinHandle<BacktestResult>,
+}
+
+impl BacktestStream {
+    /// Start `backtester` on `data`, reporting every `every` bars
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn spawn(
+        backtester: Backtester,
+        data: MultiSymbolMultiTimeframeData,
+        every: usize,
+    ) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut backtester = backtester.with_progress(
+            every,
+            Box::new(move |progress| {
+                // A dropped receiver only means nobody is watching
+                let _ = tx.send(progress);
+            }),
+        );
+        let handle = tokio::task::spawn_blocking(move || backtester.run(&data));
+        Self { rx, handle }
+    }
+
+    /// Next snapshot; `None` once the run has finished
+    pub async fn next(&mut self) -> Option<BacktestProgress> {
+        self.rx.recv().await
+    }
+
+    /// Wait for the run to finish (remaining snapshots are discarded)
+    pub async fn finish(self) -> Result<BacktestResult> {
+        drop(self.rx);
+        self.handle.await.context("Backtest task failed")
+    }
+}
+
+impl futures_core::Stream for BacktestStream {
+    type Item = BacktestProgress;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
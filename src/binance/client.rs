@@ -1,6 +1,7 @@
 //! Binance API client for fetching historical kline (candlestick) data
 //!
-//! No API key required for public market data endpoints.
+//! No API key required for public market data endpoints. Perp funding and
+//! premium index history come from the USDⓈ-M futures API (also public).
 //!
 //! # Example
 //! ```no_run
@@ -18,26 +19,65 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use std::time::Duration as StdDuration;
 use tracing::{debug, info, warn};
 
-use super::types::{BinanceKline, SymbolMapping};
+use super::types::{BinanceKline, BinanceTicker, FundingRate, SymbolMapping};
+use crate::common::{RateLimiter, RateLimiterConfig};
+use crate::positioning::PositioningSample;
 
 /// Base URL for Binance API
 const BINANCE_API_BASE: &str = "https://api.binance.com/api/v3";
 
+/// Base URL for Binance USDⓈ-M futures (funding and premium index)
+const BINANCE_FUTURES_BASE: &str = "https://fapi.binance.com/fapi/v1";
+
+/// Spot testnet (sandbox); its history is short and resets periodically
+const BINANCE_TESTNET_API_BASE: &str = "https://testnet.binance.vision/api/v3";
+
+/// USDⓈ-M futures testnet (sandbox)
+const BINANCE_TESTNET_FUTURES_BASE: &str = "https://testnet.binancefuture.com/fapi/v1";
+
 /// Maximum klines per request (Binance limit)
 const MAX_KLINES_PER_REQUEST: u32 = 1000;
 
 /// Rate limit delay between requests (ms)
 const RATE_LIMIT_DELAY_MS: u64 = 100;
 
+/// Local request budget; the `X-MBX-USED-WEIGHT-1M` header corrects it at runtime
+const MAX_REQUESTS_PER_SECOND: usize = 10;
+
+/// Times a rate-limited (429) request is sent again after the pause Binance asked for
+const RATE_LIMIT_RETRIES: u32 = 2;
+
+/// Body of a public GET: from the network, or a canned fault response
+enum PublicResponse {
+    Http(reqwest::Response),
+    #[cfg(feature = "fault-injection")]
+    Injected(serde_json::Value),
+}
+
+impl PublicResponse {
+    async fn json<T: serde::de::DeserializeOwned>(self) -> Result<T> {
+        match self {
+            PublicResponse::Http(response) => Ok(response.json().await?),
+            #[cfg(feature = "fault-injection")]
+            PublicResponse::Injected(body) => Ok(serde_json::from_value(body)?),
+        }
+    }
+}
+
 /// Binance API client
 #[derive(Debug, Clone)]
 pub struct BinanceClient {
     client: Client,
     symbol_mapping: SymbolMapping,
+    rate_limiter: RateLimiter,
+    api_base: &'static str,
+    futures_base: &'static str,
+    #[cfg(feature = "fault-injection")]
+    faults: crate::common::FaultInjector,
 }
 
 impl Default for BinanceClient {
@@ -49,15 +89,7 @@ impl Default for BinanceClient {
 impl BinanceClient {
     /// Create a new Binance client
     pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(StdDuration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
-
-        BinanceClient {
-            client,
-            symbol_mapping: SymbolMapping::default(),
-        }
+        Self::with_mapping(SymbolMapping::default())
     }
 
     /// Create with custom symbol mapping
@@ -70,9 +102,38 @@ impl BinanceClient {
         BinanceClient {
             client,
             symbol_mapping,
+            rate_limiter: RateLimiter::new(
+                RateLimiterConfig::default().with_rate(MAX_REQUESTS_PER_SECOND),
+            ),
+            api_base: BINANCE_API_BASE,
+            futures_base: BINANCE_FUTURES_BASE,
+            #[cfg(feature = "fault-injection")]
+            faults: crate::common::FaultInjector::default(),
         }
     }
 
+    /// Query the spot and futures testnets instead of production
+    pub fn with_sandbox(mut self, sandbox: bool) -> Self {
+        (self.api_base, self.futures_base) = if sandbox {
+            (BINANCE_TESTNET_API_BASE, BINANCE_TESTNET_FUTURES_BASE)
+        } else {
+            (BINANCE_API_BASE, BINANCE_FUTURES_BASE)
+        };
+        self
+    }
+
+    /// Replace requests with queued faults (see [`crate::common::faults`])
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: crate::common::FaultInjector) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    /// Most recent request weight reported by Binance
+    pub async fn used_weight(&self) -> Option<crate::common::ReportedUsage> {
+        self.rate_limiter.last_usage().await
+    }
+
     /// Get the symbol mapping
     pub fn symbol_mapping(&self) -> &SymbolMapping {
         &self.symbol_mapping
@@ -99,7 +160,7 @@ impl BinanceClient {
         end_time: Option<i64>,
         limit: Option<u32>,
     ) -> Result<Vec<BinanceKline>> {
-        let url = format!("{}/klines", BINANCE_API_BASE);
+        let url = format!("{}/klines", self.api_base);
 
         let mut params = vec![
             ("symbol", symbol.to_string()),
@@ -124,21 +185,9 @@ impl BinanceClient {
             symbol, interval, limit
         );
 
-        let response = self
-            .client
-            .get(&url)
-            .query(&params)
-            .send()
-            .await
-            .context("Failed to send request to Binance")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Binance API error {}: {}", status, body);
-        }
-
-        let raw_data: Vec<Vec<serde_json::Value>> = response
+        let raw_data: Vec<Vec<serde_json::Value>> = self
+            .get_public("klines", &url, &params)
+            .await?
             .json()
             .await
             .context("Failed to parse Binance response")?;
@@ -237,6 +286,178 @@ impl BinanceClient {
         Ok(all_klines)
     }
 
+    /// GET a public endpoint through the rate limiter
+    ///
+    /// A 429 pauses the limiter for `Retry-After`; the request is then sent
+    /// again once the pause is over, up to [`RATE_LIMIT_RETRIES`] times. A 418
+    /// (IP ban) is not retried.
+    async fn get_public(
+        &self,
+        op: &str,
+        url: &str,
+        params: &[(&str, String)],
+    ) -> Result<PublicResponse> {
+        #[cfg(feature = "fault-injection")]
+        if let Some(fault) = self.faults.take(op) {
+            warn!("Binance {}: injected {:?}", op, fault);
+            return fault.into_result().map(PublicResponse::Injected);
+        }
+
+        let mut attempt = 0;
+        let response = loop {
+            self.rate_limiter.acquire().await;
+            let response = self
+                .client
+                .get(url)
+                .query(params)
+                .send()
+                .await
+                .context("Failed to send request to Binance")?;
+            self.rate_limiter
+                .observe_response(response.status(), response.headers())
+                .await;
+            let status = response.status();
+            if status != StatusCode::TOO_MANY_REQUESTS || attempt == RATE_LIMIT_RETRIES {
+                break response;
+            }
+            attempt += 1;
+            warn!(
+                "Binance {}: rate limited ({}), retrying after the pause",
+                op, status
+            );
+        };
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("Binance API error {} ({}): {}", status, op, body);
+        }
+        Ok(PublicResponse::Http(response))
+    }
+
+    /// Funding rate history for a perpetual (e.g. "BTCUSDT"), oldest first
+    pub async fn get_funding_rates(
+        &self,
+        symbol: &str,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+    ) -> Result<Vec<FundingRate>> {
+        let mut params = vec![
+            ("symbol", symbol.to_string()),
+            ("limit", MAX_KLINES_PER_REQUEST.to_string()),
+        ];
+        if let Some(start) = start_time {
+            params.push(("startTime", start.to_string()));
+        }
+        if let Some(end) = end_time {
+            params.push(("endTime", end.to_string()));
+        }
+        let url = format!("{}/fundingRate", self.futures_base);
+        self.get_public("funding_rates", &url, &params)
+            .await?
+            .json()
+            .await
+            .context("Failed to parse Binance funding rates")
+    }
+
+    /// Premium index klines for a perpetual; `close` is mark / index - 1
+    pub async fn get_premium_index_klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+    ) -> Result<Vec<BinanceKline>> {
+        let mut params = vec![
+            ("symbol", symbol.to_string()),
+            ("interval", interval.to_string()),
+            ("limit", MAX_KLINES_PER_REQUEST.to_string()),
+        ];
+        if let Some(start) = start_time {
+            params.push(("startTime", start.to_string()));
+        }
+        if let Some(end) = end_time {
+            params.push(("endTime", end.to_string()));
+        }
+        let url = format!("{}/premiumIndexKlines", self.futures_base);
+        let raw: Vec<Vec<serde_json::Value>> = self
+            .get_public("premium_index", &url, &params)
+            .await?
+            .json()
+            .await
+            .context("Failed to parse Binance premium index")?;
+        Ok(raw
+            .iter()
+            .filter_map(|r| BinanceKline::from_raw(r))
+            .collect())
+    }
+
+    /// Basis and funding at each funding event over the last `days_back` days.
+    ///
+    /// `symbol` is converted like spot symbols ("BTCINR" -> "BTCUSDT" perp).
+    /// The basis of a sample is the premium index of the 8h bar that closed at
+    /// or before the funding time, so no sample looks ahead.
+    pub async fn fetch_positioning_history(
+        &self,
+        symbol: &str,
+        days_back: u32,
+    ) -> Result<Vec<PositioningSample>> {
+        let perp = self.to_binance_pair(symbol);
+        let end_time = Utc::now().timestamp_millis();
+        let start_time = (Utc::now() - Duration::days(days_back as i64)).timestamp_millis();
+        info!(
+            "Fetching {} funding and premium index from Binance futures ({} days back)",
+            perp, days_back
+        );
+
+        let mut funding = Vec::new();
+        let mut cursor = start_time;
+        while cursor < end_time {
+            let batch = self
+                .get_funding_rates(&perp, Some(cursor), Some(end_time))
+                .await?;
+            let Some(last) = batch.last() else { break };
+            cursor = last.funding_time + 1;
+            funding.extend(batch);
+            tokio::time::sleep(StdDuration::from_millis(RATE_LIMIT_DELAY_MS)).await;
+        }
+
+        // Start one bar early so the first funding event has a closed premium bar
+        let mut premium = Vec::new();
+        let mut cursor = start_time - 8 * 3_600_000;
+        while cursor < end_time {
+            let batch = self
+                .get_premium_index_klines(&perp, "8h", Some(cursor), Some(end_time))
+                .await?;
+            let Some(last) = batch.last() else { break };
+            cursor = last.open_time + 1;
+            premium.extend(batch);
+            tokio::time::sleep(StdDuration::from_millis(RATE_LIMIT_DELAY_MS)).await;
+        }
+        premium.sort_by_key(|k| k.close_time);
+        premium.dedup_by_key(|k| k.open_time);
+
+        let mut samples = Vec::with_capacity(funding.len());
+        for event in &funding {
+            let closed = premium.partition_point(|k| k.close_time <= event.funding_time);
+            let (Some(bar), Some(rate)) =
+                (closed.checked_sub(1).map(|i| &premium[i]), event.rate())
+            else {
+                continue;
+            };
+            let Some(datetime) = DateTime::from_timestamp_millis(event.funding_time) else {
+                continue;
+            };
+            samples.push(PositioningSample {
+                datetime,
+                basis: bar.close,
+                funding_rate: rate,
+            });
+        }
+        info!("Built {} positioning samples for {}", samples.len(), perp);
+        Ok(samples)
+    }
+
     /// Estimate the number of candles for a given interval and days
     fn estimate_candles(interval: &str, days: u32) -> u64 {
         let minutes_per_candle = match interval {
@@ -261,16 +482,26 @@ impl BinanceClient {
         total_minutes / minutes_per_candle
     }
 
+    /// 24-hour rolling ticker for a Binance pair (e.g. "BTCUSDT")
+    pub async fn get_24h_ticker(&self, symbol: &str) -> Result<BinanceTicker> {
+        let url = format!("{}/ticker/24hr", self.api_base);
+        self.get_public("ticker_24hr", &url, &[("symbol", symbol.to_string())])
+            .await?
+            .json()
+            .await
+            .context("Failed to parse Binance ticker")
+    }
+
     /// Check server connectivity
     pub async fn ping(&self) -> Result<bool> {
-        let url = format!("{}/ping", BINANCE_API_BASE);
+        let url = format!("{}/ping", self.api_base);
         let response = self.client.get(&url).send().await?;
         Ok(response.status().is_success())
     }
 
     /// Get server time
     pub async fn get_server_time(&self) -> Result<DateTime<Utc>> {
-        let url = format!("{}/time", BINANCE_API_BASE);
+        let url = format!("{}/time", self.api_base);
         let response = self.client.get(&url).send().await?;
 
         #[derive(serde::Deserialize)]
@@ -294,4 +525,15 @@ mod tests {
         assert_eq!(client.to_binance_pair("BTC"), "BTCUSDT");
         assert_eq!(client.to_binance_pair("BTCINR"), "BTCUSDT");
     }
+
+    #[test]
+    fn test_sandbox_uses_testnets() {
+        let client = BinanceClient::new();
+        assert_eq!(client.api_base, BINANCE_API_BASE);
+        assert_eq!(client.futures_base, BINANCE_FUTURES_BASE);
+
+        let client = client.with_sandbox(true);
+        assert_eq!(client.api_base, BINANCE_TESTNET_API_BASE);
+        assert_eq!(client.futures_base, BINANCE_TESTNET_FUTURES_BASE);
+    }
 }
@@ -1,8 +1,29 @@
 //! Binance API client for downloading historical OHLCV data
 //! No API key needed for public market data endpoints.
+//!
+//! [`ws::KlineStream`] streams closed bars for live trading.
 
 mod client;
 mod types;
+pub mod ws;
 
 pub use client::BinanceClient;
 pub use types::*;
+
+use crate::capabilities::{ExchangeCapabilities, PrecisionRule};
+
+/// Spot markets (used here for historical data only)
+pub const CAPABILITIES: ExchangeCapabilities = ExchangeCapabilities {
+    name: "binance",
+    order_types: &[
+        crate::oms::OrderType::Market,
+        crate::oms::OrderType::Limit,
+        crate::oms::OrderType::StopLimit,
+    ],
+    timeframes: BINANCE_INTERVALS,
+    quote_currencies: &["USDT", "USDC", "BTC"],
+    precision: PrecisionRule::PerMarket,
+    min_notional: None,
+    sandbox: true,
+    fill_reports: false,
+};
@@ -43,6 +43,34 @@ impl BinanceKline {
     }
 }
 
+/// One funding event from the USDⓈ-M futures `fundingRate` endpoint
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRate {
+    pub symbol: String,
+    pub funding_time: i64,
+    /// Rate per funding interval as a decimal string
+    pub funding_rate: String,
+}
+
+impl FundingRate {
+    pub fn rate(&self) -> Option<f64> {
+        self.funding_rate.parse().ok()
+    }
+}
+
+/// 24-hour rolling ticker (`ticker/24hr`); prices are decimal strings
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinanceTicker {
+    pub symbol: String,
+    pub last_price: String,
+    pub bid_price: String,
+    pub ask_price: String,
+    pub volume: String,
+    pub close_time: i64,
+}
+
 /// Symbol mapping from common names to Binance trading pairs
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolMapping {
@@ -81,17 +109,13 @@ impl Default for SymbolMapping {
 
 impl SymbolMapping {
     /// Get Binance pair from symbol (e.g., "BTC" -> "BTCUSDT", "BTCINR" -> "BTCUSDT")
+    ///
+    /// Bases without a mapping follow [`crate::symbols::binance_pair`].
     pub fn to_binance_pair(&self, symbol: &str) -> String {
-        let base = symbol
-            .trim()
-            .to_uppercase()
-            .replace("INR", "")
-            .replace("USDT", "");
-
         self.mappings
-            .get(&base)
+            .get(&crate::symbols::binance_base(symbol))
             .cloned()
-            .unwrap_or_else(|| format!("{}USDT", base))
+            .unwrap_or_else(|| crate::symbols::binance_pair(symbol))
     }
 }
 
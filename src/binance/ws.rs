@@ -0,0 +1,288 @@
+//! Streaming klines from Binance
+//!
+//! [`KlineStream`] subscribes to the `<pair>@kline_<interval>` channels of
+//! Binance's combined stream endpoint ([`STREAM_URL`]) and yields a
+//! [`ClosedKline`] each time a bar closes, keyed by the caller's symbol
+//! (`BTCINR`, not `btcusdt`) and interval. Updates to the forming bar are
+//! dropped.
+//!
+//! ```no_run
+//! use crypto_strategies::binance::ws::KlineStream;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let symbols = ["BTCUSDT".to_string()];
+//!     let intervals = ["1m".to_string(), "1h".to_string()];
+//!     let mut stream = KlineStream::connect(&symbols, &intervals).await?;
+//!     while let Some(bar) = stream.next().await {
+//!         println!("{} {} closed at {}", bar.symbol, bar.interval, bar.kline.close);
+//!     }
+//!     // Binance closes connections after 24 hours
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`KlineStream::reconnecting`] reconnects instead of ending and reports
+//! each outage, so the missed bars can be fetched over REST.
+
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::types::{BinanceKline, SymbolMapping};
+use crate::common::{ReconnectingStream, RetryPolicy};
+
+/// Combined stream endpoint for public market data
+pub const STREAM_URL: &str = "wss://stream.binance.com:9443";
+
+/// Combined stream endpoint of the spot testnet
+pub const TESTNET_STREAM_URL: &str = "wss://stream.testnet.binance.vision";
+
+/// A bar that has just closed
+#[derive(Debug, Clone)]
+pub struct ClosedKline {
+    /// Symbol as passed to [`KlineStream::connect`]
+    pub symbol: String,
+    /// Binance interval, e.g. `"1h"`
+    pub interval: String,
+    pub kline: BinanceKline,
+}
+
+/// `{"stream": "btcusdt@kline_1h", "data": {...}}` wrapper of the combined endpoint
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    data: KlineEvent,
+}
+
+#[derive(Debug, Deserialize)]
+struct KlineEvent {
+    #[serde(rename = "s")]
+    pair: String,
+    #[serde(rename = "k")]
+    kline: RawKline,
+}
+
+/// The `k` object of a kline event; prices and volumes are decimal strings
+#[derive(Debug, Deserialize)]
+struct RawKline {
+    #[serde(rename = "t")]
+    open_time: i64,
+    #[serde(rename = "T")]
+    close_time: i64,
+    #[serde(rename = "i")]
+    interval: String,
+    #[serde(rename = "o")]
+    open: String,
+    #[serde(rename = "h")]
+    high: String,
+    #[serde(rename = "l")]
+    low: String,
+    #[serde(rename = "c")]
+    close: String,
+    #[serde(rename = "v")]
+    volume: String,
+    #[serde(rename = "q")]
+    quote_volume: String,
+    #[serde(rename = "n")]
+    trades: u64,
+    #[serde(rename = "V")]
+    taker_buy_base: String,
+    #[serde(rename = "Q")]
+    taker_buy_quote: String,
+    /// Whether this is the bar's final update
+    #[serde(rename = "x")]
+    closed: bool,
+}
+
+impl RawKline {
+    fn parse(&self) -> Option<BinanceKline> {
+        Some(BinanceKline {
+            open_time: self.open_time,
+            open: self.open.parse().ok()?,
+            high: self.high.parse().ok()?,
+            low: self.low.parse().ok()?,
+            close: self.close.parse().ok()?,
+            volume: self.volume.parse().ok()?,
+            close_time: self.close_time,
+            quote_volume: self.quote_volume.parse().ok()?,
+            trades: self.trades,
+            taker_buy_base: self.taker_buy_base.parse().ok()?,
+            taker_buy_quote: self.taker_buy_quote.parse().ok()?,
+        })
+    }
+}
+
+/// Decode a combined-stream message; `None` for forming bars and anything else
+///
+/// `symbols` maps Binance pairs (`BTCUSDT`) to the caller's symbols.
+fn closed_kline(text: &str, symbols: &HashMap<String, String>) -> Option<ClosedKline> {
+    let event = serde_json::from_str::<Envelope>(text).ok()?.data;
+    if !event.kline.closed {
+        return None;
+    }
+    Some(ClosedKline {
+        symbol: symbols.get(&event.pair)?.clone(),
+        interval: event.kline.interval.clone(),
+        kline: event.kline.parse()?,
+    })
+}
+
+/// Closed bars for a set of markets and intervals
+///
+/// The stream ends when the connection drops. Dropping it closes the socket.
+pub struct KlineStream {
+    rx: mpsc::UnboundedReceiver<ClosedKline>,
+    task: JoinHandle<Result<()>>,
+}
+
+impl KlineStream {
+    /// Connect to [`STREAM_URL`] and subscribe to every symbol/interval pair
+    ///
+    /// Symbols go through the default [`SymbolMapping`], so `BTCINR` streams
+    /// `BTCUSDT` bars.
+    pub async fn connect(symbols: &[String], intervals: &[String]) -> Result<Self> {
+        Self::connect_to(STREAM_URL, symbols, intervals).await
+    }
+
+    /// Connect to a combined stream endpoint at `url` (e.g. a local test server)
+    pub async fn connect_to(url: &str, symbols: &[String], intervals: &[String]) -> Result<Self> {
+        let mapping = SymbolMapping::default();
+        let pairs: HashMap<String, String> = symbols
+            .iter()
+            .map(|s| (mapping.to_binance_pair(s), s.clone()))
+            .collect();
+        let streams: Vec<String> = pairs
+            .keys()
+            .flat_map(|pair| {
+                intervals
+                    .iter()
+                    .map(move |tf| format!("{}@kline_{}", pair.to_lowercase(), tf))
+            })
+            .collect();
+        let endpoint = format!(
+            "{}/stream?streams={}",
+            url.trim_end_matches('/'),
+            streams.join("/")
+        );
+        let (socket, _) = tokio_tungstenite::connect_async(&endpoint)
+            .await
+            .with_context(|| format!("Failed to connect to {}", url))?;
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(async move {
+            // Binance pings every few minutes; tungstenite answers while reading
+            let (_write, mut read) = socket.split();
+            while let Some(message) = read.next().await {
+                let text = match message? {
+                    Message::Text(text) => text,
+                    Message::Close(_) => break,
+                    _ => continue,
+                };
+                if let Some(bar) = closed_kline(&text, &pairs) {
+                    if tx.send(bar).is_err() {
+                        // Receiver dropped: nobody is listening
+                        return Ok(());
+                    }
+                }
+            }
+            Ok(())
+        });
+        tracing::debug!("Subscribed to {} Binance kline stream(s)", streams.len());
+        Ok(Self { rx, task })
+    }
+
+    /// Like [`KlineStream::connect_to`], reconnecting with `policy` whenever the
+    /// connection drops
+    ///
+    /// Bars are de-duplicated by symbol, interval and open time.
+    pub async fn reconnecting(
+        url: &str,
+        symbols: &[String],
+        intervals: &[String],
+        policy: RetryPolicy,
+    ) -> Result<ReconnectingStream<ClosedKline>> {
+        let (url, symbols, intervals) = (url.to_string(), symbols.to_vec(), intervals.to_vec());
+        ReconnectingStream::connect(
+            policy,
+            move || {
+                let (url, symbols, intervals) = (url.clone(), symbols.clone(), intervals.clone());
+                async move { Self::connect_to(&url, &symbols, &intervals).await }
+            },
+            |bar| {
+                Some(format!(
+                    "{}:{}:{}",
+                    bar.symbol, bar.interval, bar.kline.open_time
+                ))
+            },
+        )
+        .await
+    }
+
+    /// Next closed bar; `None` once the connection has closed
+    pub async fn next(&mut self) -> Option<ClosedKline> {
+        self.rx.recv().await
+    }
+
+    /// Every bar closed since the last call, without waiting
+    ///
+    /// Returns `None` once the connection has closed and nothing is left.
+    pub fn drain(&mut self) -> Option<Vec<ClosedKline>> {
+        let mut bars = Vec::new();
+        loop {
+            match self.rx.try_recv() {
+                Ok(bar) => bars.push(bar),
+                Err(mpsc::error::TryRecvError::Empty) => return Some(bars),
+                Err(mpsc::error::TryRecvError::Disconnected) if bars.is_empty() => return None,
+                Err(mpsc::error::TryRecvError::Disconnected) => return Some(bars),
+            }
+        }
+    }
+}
+
+impl futures_core::Stream for KlineStream {
+    type Item = ClosedKline;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl Drop for KlineStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_closed_klines_are_emitted() {
+        let symbols = HashMap::from([("BTCUSDT".to_string(), "BTCINR".to_string())]);
+        let message = |closed: bool| {
+            format!(
+                r#"{{"stream":"btcusdt@kline_1h","data":{{"e":"kline","E":1700003600005,"s":"BTCUSDT","k":{{"t":1700000000000,"T":1700003599999,"s":"BTCUSDT","i":"1h","f":100,"L":200,"o":"37000.10","c":"37100.50","h":"37250.00","l":"36950.00","v":"812.5","n":101,"x":{},"q":"30100000.0","V":"400.1","Q":"14800000.0","B":"0"}}}}}}"#,
+                closed
+            )
+        };
+
+        assert!(closed_kline(&message(false), &symbols).is_none());
+        let bar = closed_kline(&message(true), &symbols).unwrap();
+        assert_eq!(bar.symbol, "BTCINR");
+        assert_eq!(bar.interval, "1h");
+        assert_eq!(bar.kline.open_time, 1_700_000_000_000);
+        assert_eq!(bar.kline.close, 37100.5);
+        assert_eq!(bar.kline.trades, 101);
+
+        // Pairs nobody subscribed to and non-kline payloads are ignored
+        assert!(closed_kline(&message(true), &HashMap::new()).is_none());
+        assert!(closed_kline(r#"{"result":null,"id":1}"#, &symbols).is_none());
+    }
+}
@@ -0,0 +1,382 @@
+//! Orderbook snapshot recording
+//!
+//! Periodically captures the top `depth` levels of each configured symbol's
+//! CoinDCX orderbook so fill models and spread-based slippage can later be
+//! calibrated against real books instead of a flat `assumed_slippage`.
+//!
+//! # Layout
+//!
+//! ```text
+//! <output_dir>/<SYMBOL>/<YYYY-MM-DD>.jsonl.sz
+//! ```
+//!
+//! One JSON [`BookSnapshot`] per line, snappy frame-compressed. Every append
+//! writes a complete frame stream; concatenated streams decode as one, so a
+//! file is readable while the recorder is still writing to it.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use crate::coindcx::{self, types::OrderBook, CoinDCXClient};
+use crate::common::RequestPriority;
+use crate::config::OrderbookRecorderConfig;
+use crate::oms::spread_guard::spread_bps;
+
+/// File extension of snapshot files
+pub const SNAPSHOT_EXT: &str = "jsonl.sz";
+
+/// Top-of-book levels for one symbol at one instant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookSnapshot {
+    pub timestamp: DateTime<Utc>,
+    pub symbol: String,
+    /// `(price, quantity)`, best (highest) first
+    pub bids: Vec<(f64, f64)>,
+    /// `(price, quantity)`, best (lowest) first
+    pub asks: Vec<(f64, f64)>,
+}
+
+impl BookSnapshot {
+    /// Keep the best `depth` levels per side of an exchange orderbook
+    pub fn from_orderbook(
+        symbol: &str,
+        book: &OrderBook,
+        depth: usize,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        let levels = |entries: Vec<coindcx::types::OrderBookEntry>| {
+            entries
+                .into_iter()
+                .take(depth)
+                .map(|e| (e.price, e.quantity))
+                .collect()
+        };
+        Self {
+            timestamp,
+            symbol: symbol.to_string(),
+            bids: levels(book.sorted_bids()),
+            asks: levels(book.sorted_asks()),
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<f64> {
+        self.bids.first().map(|(p, _)| *p)
+    }
+
+    pub fn best_ask(&self) -> Option<f64> {
+        self.asks.first().map(|(p, _)| *p)
+    }
+
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.best_bid()? + self.best_ask()?) / 2.0)
+    }
+
+    /// Quoted spread in basis points of mid (None for an empty or crossed book)
+    pub fn spread_bps(&self) -> Option<f64> {
+        spread_bps(self.best_bid()?, self.best_ask()?)
+    }
+}
+
+/// `<dir>/<SYMBOL>/<YYYY-MM-DD>.jsonl.sz`
+pub fn snapshot_path(dir: &Path, symbol: &str, date: NaiveDate) -> PathBuf {
+    dir.join(symbol)
+        .join(format!("{}.{}", date.format("%Y-%m-%d"), SNAPSHOT_EXT))
+}
+
+/// Append a snapshot to its symbol's file for the day
+pub fn append_snapshot(dir: &Path, snapshot: &BookSnapshot) -> Result<PathBuf> {
+    let path = snapshot_path(dir, &snapshot.symbol, snapshot.timestamp.date_naive());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut line = serde_json::to_vec(snapshot)?;
+    line.push(b'\n');
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut encoder = snap::write::FrameEncoder::new(file);
+    encoder.write_all(&line)?;
+    encoder
+        .into_inner()
+        .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e.error()))?
+        .flush()?;
+    Ok(path)
+}
+
+/// Read every snapshot in one file
+///
+/// A torn trailing write (e.g. the recorder was killed mid-append) ends the
+/// read with a warning instead of failing the whole file.
+pub fn read_snapshot_file(path: &Path) -> Result<Vec<BookSnapshot>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let reader = BufReader::new(snap::read::FrameDecoder::new(file));
+    let mut snapshots = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let parsed = line
+            .map_err(anyhow::Error::from)
+            .and_then(|l| Ok(serde_json::from_str::<BookSnapshot>(&l)?));
+        match parsed {
+            Ok(s) => snapshots.push(s),
+            Err(e) => {
+                warn!("{}: stopped at line {}: {}", path.display(), i + 1, e);
+                break;
+            }
+        }
+    }
+    Ok(snapshots)
+}
+
+/// Load a symbol's snapshots within `[start, end]` (either bound optional), oldest first
+pub fn load_snapshots(
+    dir: &Path,
+    symbol: &str,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+) -> Result<Vec<BookSnapshot>> {
+    let symbol_dir = dir.join(symbol);
+    if !symbol_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let suffix = format!(".{}", SNAPSHOT_EXT);
+    let mut files: Vec<(NaiveDate, PathBuf)> = fs::read_dir(&symbol_dir)?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let date = NaiveDate::parse_from_str(name.strip_suffix(&suffix)?, "%Y-%m-%d").ok()?;
+            Some((date, e.path()))
+        })
+        .filter(|(date, _)| {
+            start.is_none_or(|s| *date >= s.date_naive())
+                && end.is_none_or(|e| *date <= e.date_naive())
+        })
+        .collect();
+    files.sort();
+
+    let mut snapshots = Vec::new();
+    for (_, path) in files {
+        snapshots.extend(read_snapshot_file(&path)?.into_iter().filter(|s| {
+            start.is_none_or(|t| s.timestamp >= t) && end.is_none_or(|t| s.timestamp <= t)
+        }));
+    }
+    snapshots.sort_by_key(|s| s.timestamp);
+    Ok(snapshots)
+}
+
+/// Recorded quoted spreads for one symbol, for spread-based slippage
+#[derive(Debug, Clone, Default)]
+pub struct SpreadHistory {
+    points: Vec<(DateTime<Utc>, f64)>,
+}
+
+impl SpreadHistory {
+    pub fn from_snapshots(snapshots: &[BookSnapshot]) -> Self {
+        let mut points: Vec<_> = snapshots
+            .iter()
+            .filter_map(|s| Some((s.timestamp, s.spread_bps()?)))
+            .collect();
+        points.sort_by_key(|(t, _)| *t);
+        Self { points }
+    }
+
+    pub fn load(
+        dir: &Path,
+        symbol: &str,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<Self> {
+        Ok(Self::from_snapshots(&load_snapshots(
+            dir, symbol, start, end,
+        )?))
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Spread (bps) of the latest snapshot at or before `at`, if no older than `max_age`
+    pub fn spread_at(&self, at: DateTime<Utc>, max_age: Duration) -> Option<f64> {
+        let idx = self.points.partition_point(|(t, _)| *t <= at);
+        let (t, bps) = *self.points.get(idx.checked_sub(1)?)?;
+        (at - t <= max_age).then_some(bps)
+    }
+
+    /// Median spread (bps) across all snapshots
+    pub fn median_bps(&self) -> Option<f64> {
+        let mut values: Vec<f64> = self.points.iter().map(|(_, bps)| *bps).collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        let mid = values.len() / 2;
+        Some(if values.len().is_multiple_of(2) {
+            (values[mid - 1] + values[mid]) / 2.0
+        } else {
+            values[mid]
+        })
+    }
+}
+
+/// Snapshots written and failures across a recording session
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecordStats {
+    pub rounds: u64,
+    pub written: u64,
+    pub failed: u64,
+}
+
+/// Take one snapshot of every symbol
+///
+/// Requests go out at `Low` priority so recording is the first traffic shed
+/// under rate-limit pressure.
+pub async fn record_once(
+    client: &CoinDCXClient,
+    config: &OrderbookRecorderConfig,
+    symbols: &[String],
+    stats: &mut RecordStats,
+) {
+    let dir = Path::new(&config.output_dir);
+    stats.rounds += 1;
+    for symbol in symbols {
+        let pair = coindcx::symbol_to_pair(symbol);
+        let result = match client
+            .get_orderbook_with_priority(&pair, RequestPriority::Low)
+            .await
+        {
+            Ok(book) => {
+                let snapshot =
+                    BookSnapshot::from_orderbook(symbol, &book, config.depth, Utc::now());
+                append_snapshot(dir, &snapshot)
+            }
+            Err(e) => Err(e.into()),
+        };
+        match result {
+            Ok(path) => {
+                stats.written += 1;
+                debug!("Orderbook snapshot {} -> {}", symbol, path.display());
+            }
+            Err(e) => {
+                stats.failed += 1;
+                warn!("Orderbook snapshot failed for {}: {}", symbol, e);
+            }
+        }
+    }
+}
+
+/// Record every `interval_secs` until `shutdown` is set or `max_rounds` is reached.
+///
+/// Rounds are skipped (not counted) while `paused` is set.
+pub async fn run(
+    client: CoinDCXClient,
+    config: OrderbookRecorderConfig,
+    symbols: Vec<String>,
+    shutdown: Arc<AtomicBool>,
+    paused: Option<Arc<AtomicBool>>,
+    max_rounds: Option<u64>,
+) -> RecordStats {
+    info!(
+        "📚 Recording top {} orderbook levels for {} symbols every {}s -> {}",
+        config.depth,
+        symbols.len(),
+        config.interval_secs,
+        config.output_dir
+    );
+    let mut stats = RecordStats::default();
+    let mut ticker =
+        tokio::time::interval(std::time::Duration::from_secs(config.interval_secs.max(1)));
+    while !shutdown.load(Ordering::Relaxed) && max_rounds.is_none_or(|max| stats.rounds < max) {
+        ticker.tick().await;
+        if paused.as_ref().is_some_and(|p| p.load(Ordering::Relaxed)) {
+            continue;
+        }
+        record_once(&client, &config, &symbols, &mut stats).await;
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn snapshot(ts: DateTime<Utc>, bid: f64, ask: f64) -> BookSnapshot {
+        BookSnapshot {
+            timestamp: ts,
+            symbol: "BTCINR".to_string(),
+            bids: vec![(bid, 1.0), (bid - 1.0, 2.0)],
+            asks: vec![(ask, 1.5)],
+        }
+    }
+
+    #[test]
+    fn test_append_and_load_round_trip() {
+        let dir = std::env::temp_dir().join(format!("book_recorder_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let t0 = Utc.with_ymd_and_hms(2024, 3, 1, 23, 59, 0).unwrap();
+        let snaps = [
+            snapshot(t0, 99.9, 100.1),
+            snapshot(t0 + Duration::seconds(30), 99.8, 100.2),
+            snapshot(t0 + Duration::minutes(2), 99.0, 101.0),
+        ];
+        for s in &snaps {
+            append_snapshot(&dir, s).unwrap();
+        }
+        // Crossed midnight -> two files
+        assert_eq!(fs::read_dir(dir.join("BTCINR")).unwrap().count(), 2);
+
+        let all = load_snapshots(&dir, "BTCINR", None, None).unwrap();
+        assert_eq!(all, snaps.to_vec());
+        let late = load_snapshots(&dir, "BTCINR", Some(t0 + Duration::seconds(10)), None).unwrap();
+        assert_eq!(late.len(), 2);
+        assert!(load_snapshots(&dir, "ETHINR", None, None)
+            .unwrap()
+            .is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_spread_history_lookup() {
+        let t0 = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let history = SpreadHistory::from_snapshots(&[
+            snapshot(t0, 99.9, 100.1),
+            snapshot(t0 + Duration::minutes(1), 99.0, 101.0),
+        ]);
+        assert_eq!(history.len(), 2);
+        let max_age = Duration::minutes(5);
+        assert_eq!(history.spread_at(t0 - Duration::seconds(1), max_age), None);
+        assert!(
+            (history
+                .spread_at(t0 + Duration::seconds(30), max_age)
+                .unwrap()
+                - 20.0)
+                .abs()
+                < 1e-9
+        );
+        assert!(
+            (history
+                .spread_at(t0 + Duration::minutes(3), max_age)
+                .unwrap()
+                - 200.0)
+                .abs()
+                < 1e-9
+        );
+        assert_eq!(history.spread_at(t0 + Duration::hours(1), max_age), None);
+        assert!((history.median_bps().unwrap() - 110.0).abs() < 1e-9);
+    }
+}
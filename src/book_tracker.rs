@@ -0,0 +1,246 @@
+//! Local orderbooks maintained from the CoinDCX depth stream
+//!
+//! [`OrderBookTracker`] subscribes to [`DepthStream`] and keeps a sorted
+//! [`LocalBook`] per symbol: snapshots replace a book, updates patch it
+//! level by level. The books answer the questions fill models and
+//! market-making strategies ask between REST calls: best bid/ask, a
+//! depth-weighted mid and the slippage of sweeping a given quantity.
+//!
+//! ```no_run
+//! use crypto_strategies::book_tracker::OrderBookTracker;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let mut tracker = OrderBookTracker::connect(&["BTCINR".to_string()], 20).await?;
+//!     while tracker.next().await.is_some() {
+//!         if let Some(book) = tracker.book("BTCINR") {
+//!             println!("mid {:?}, 5-level mid {:?}", book.mid(), book.weighted_mid(5));
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use anyhow::Result;
+use ordered_float::OrderedFloat;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::coindcx::ws::{DepthStream, DepthUpdate};
+use crate::Side;
+
+/// Price-sorted levels of one market
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LocalBook {
+    bids: BTreeMap<OrderedFloat<f64>, f64>,
+    asks: BTreeMap<OrderedFloat<f64>, f64>,
+    /// Exchange time of the last applied update (milliseconds)
+    pub updated_at: i64,
+}
+
+impl LocalBook {
+    /// Apply a depth event: snapshots replace the book, updates patch it
+    pub fn apply(&mut self, update: &DepthUpdate) {
+        if update.is_snapshot {
+            self.bids.clear();
+            self.asks.clear();
+        }
+        for (levels, book) in [
+            (&update.bids, &mut self.bids),
+            (&update.asks, &mut self.asks),
+        ] {
+            for &(price, quantity) in levels {
+                if quantity > 0.0 {
+                    book.insert(OrderedFloat(price), quantity);
+                } else {
+                    book.remove(&OrderedFloat(price));
+                }
+            }
+        }
+        self.updated_at = update.timestamp;
+    }
+
+    /// `(price, quantity)`, best (highest) first
+    pub fn bids(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.bids.iter().rev().map(|(p, q)| (p.0, *q))
+    }
+
+    /// `(price, quantity)`, best (lowest) first
+    pub fn asks(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.asks.iter().map(|(p, q)| (p.0, *q))
+    }
+
+    pub fn best_bid(&self) -> Option<f64> {
+        self.bids().next().map(|(p, _)| p)
+    }
+
+    pub fn best_ask(&self) -> Option<f64> {
+        self.asks().next().map(|(p, _)| p)
+    }
+
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.best_bid()? + self.best_ask()?) / 2.0)
+    }
+
+    /// Mid of the volume-weighted prices of the top `levels` on each side
+    pub fn weighted_mid(&self, levels: usize) -> Option<f64> {
+        let vwap = |side: &mut dyn Iterator<Item = (f64, f64)>| {
+            let (notional, quantity) =
+                side.take(levels).fold((0.0, 0.0), |(n, q), (price, qty)| {
+                    (n + price * qty, q + qty)
+                });
+            (quantity > 0.0).then(|| notional / quantity)
+        };
+        Some((vwap(&mut self.bids())? + vwap(&mut self.asks())?) / 2.0)
+    }
+
+    /// Average price of a market order of `quantity` on `side` (a buy
+    /// sweeps the asks), `None` if the book is too thin to fill it
+    pub fn sweep_price(&self, side: Side, quantity: f64) -> Option<f64> {
+        let levels: Box<dyn Iterator<Item = (f64, f64)>> = match side {
+            Side::Buy => Box::new(self.asks()),
+            Side::Sell => Box::new(self.bids()),
+        };
+        let mut remaining = quantity;
+        let mut notional = 0.0;
+        for (price, available) in levels {
+            let take = remaining.min(available);
+            notional += take * price;
+            remaining -= take;
+            if remaining <= 0.0 {
+                return Some(notional / quantity);
+            }
+        }
+        None
+    }
+
+    /// Cost of sweeping `quantity` against the mid, in basis points
+    pub fn slippage_bps(&self, side: Side, quantity: f64) -> Option<f64> {
+        let mid = self.mid()?;
+        let price = self.sweep_price(side, quantity)?;
+        let adverse = match side {
+            Side::Buy => price - mid,
+            Side::Sell => mid - price,
+        };
+        Some(adverse / mid * 10_000.0)
+    }
+}
+
+/// Local books for a set of symbols, fed by a [`DepthStream`]
+pub struct OrderBookTracker {
+    stream: DepthStream,
+    books: HashMap<String, LocalBook>,
+}
+
+impl OrderBookTracker {
+    /// Subscribe to the top `depth` levels (10, 20 or 50) of `symbols`
+    pub async fn connect(symbols: &[String], depth: usize) -> Result<Self> {
+        Ok(Self::from_stream(
+            DepthStream::connect(symbols, depth).await?,
+        ))
+    }
+
+    /// Track books from an already connected stream
+    pub fn from_stream(stream: DepthStream) -> Self {
+        Self {
+            stream,
+            books: HashMap::new(),
+        }
+    }
+
+    /// Wait for the next update and apply it; returns its symbol, or `None`
+    /// once the connection has closed
+    pub async fn next(&mut self) -> Option<String> {
+        let update = self.stream.next().await?;
+        self.apply(&update);
+        Some(update.symbol)
+    }
+
+    /// Apply every update received since the last call, without waiting
+    ///
+    /// Returns false once the connection has closed; the books keep their
+    /// last state.
+    pub fn refresh(&mut self) -> bool {
+        match self.stream.drain() {
+            Some(updates) => {
+                for update in &updates {
+                    self.apply(update);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn apply(&mut self, update: &DepthUpdate) {
+        self.books
+            .entry(update.symbol.clone())
+            .or_default()
+            .apply(update);
+    }
+
+    /// The local book of `symbol`, once its first update has arrived
+    pub fn book(&self, symbol: &str) -> Option<&LocalBook> {
+        self.books.get(symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(bids: &[(f64, f64)], asks: &[(f64, f64)], is_snapshot: bool) -> DepthUpdate {
+        DepthUpdate {
+            symbol: "BTCINR".to_string(),
+            bids: bids.to_vec(),
+            asks: asks.to_vec(),
+            timestamp: 1,
+            is_snapshot,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_then_patch() {
+        let mut book = LocalBook::default();
+        book.apply(&update(
+            &[(99.0, 1.0), (100.0, 2.0)],
+            &[(102.0, 1.0), (101.0, 1.0)],
+            true,
+        ));
+        assert_eq!(book.best_bid(), Some(100.0));
+        assert_eq!(book.best_ask(), Some(101.0));
+        assert_eq!(book.mid(), Some(100.5));
+
+        // Remove the best bid, add a better ask
+        book.apply(&update(&[(100.0, 0.0)], &[(100.5, 3.0)], false));
+        assert_eq!(book.best_bid(), Some(99.0));
+        assert_eq!(book.best_ask(), Some(100.5));
+        assert_eq!(book.asks().count(), 3);
+
+        // A snapshot drops levels it doesn't mention
+        book.apply(&update(&[(98.0, 1.0)], &[(103.0, 1.0)], true));
+        assert_eq!(book.bids().collect::<Vec<_>>(), vec![(98.0, 1.0)]);
+    }
+
+    #[test]
+    fn test_weighted_mid_and_slippage() {
+        let mut book = LocalBook::default();
+        book.apply(&update(
+            &[(100.0, 1.0), (98.0, 3.0)],
+            &[(102.0, 1.0), (104.0, 1.0)],
+            true,
+        ));
+        // bids vwap 98.5, asks vwap 103
+        assert!((book.weighted_mid(2).unwrap() - 100.75).abs() < 1e-9);
+        assert_eq!(book.weighted_mid(1), book.mid());
+
+        assert_eq!(book.sweep_price(Side::Buy, 2.0), Some(103.0));
+        assert_eq!(book.sweep_price(Side::Buy, 2.5), None);
+        // mid 101, sweep 103 => ~198 bps
+        let bps = book.slippage_bps(Side::Buy, 2.0).unwrap();
+        assert!((bps - 2.0 / 101.0 * 10_000.0).abs() < 1e-9);
+        assert_eq!(
+            book.slippage_bps(Side::Sell, 1.0),
+            Some(1.0 / 101.0 * 10_000.0)
+        );
+    }
+}
@@ -0,0 +1,609 @@
+//! Bybit API Client
+//!
+//! HTTP client for Bybit's v5 API, limited to USDT linear perpetuals, with
+//! the same resilience as the CoinDCX client:
+//! - Automatic retry with exponential backoff
+//! - Rate limiting with priority-based shedding (order calls first)
+//! - Circuit breaker pattern for fault tolerance
+//!
+//! # Example
+//!
+//! ```no_run
+//! use crypto_strategies::bybit::BybitClient;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let client = BybitClient::new("api_key", "api_secret");
+//!
+//!     let ticker = client.get_ticker("BTCUSDT").await?;
+//!     println!("BTC perp: {:?}, funding {:?}", ticker.last_price_f64(), ticker.funding_rate_f64());
+//!
+//!     for position in client.get_positions(None).await? {
+//!         println!("{}: {}", position.symbol, position.signed_size());
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{Duration as ChronoDuration, Utc};
+use reqwest::Client;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tracing::info;
+
+use super::auth::{Credentials, RECV_WINDOW_MS};
+use super::to_bybit_interval;
+use super::types::*;
+use crate::common::{
+    CircuitBreaker, CircuitBreakerConfig, NetworkConfig, RateLimiter, RateLimiterConfig,
+    RequestPriority,
+};
+use crate::symbols::{SymbolMapper, Venue};
+
+/// Base URL for the Bybit v5 API
+pub const API_BASE_URL: &str = "https://api.bybit.com";
+
+/// Base URL of the Bybit testnet (separate keys from api-testnet.bybit.com)
+pub const TESTNET_BASE_URL: &str = "https://api-testnet.bybit.com";
+
+/// Most klines returned per request
+const MAX_KLINES_PER_REQUEST: u32 = 1000;
+
+/// Most funding events returned per request
+const MAX_FUNDING_PER_REQUEST: u32 = 200;
+
+/// Convert a config symbol to a Bybit linear contract
+///
+/// Bare bases trade against USDT; see [`crate::symbols::bybit_contract`].
+pub fn symbol_to_contract(symbol: &str) -> String {
+    crate::symbols::bybit_contract(symbol)
+}
+
+/// Client configuration
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Maximum number of retry attempts
+    pub max_retries: u32,
+    /// Request timeout duration
+    pub timeout: Duration,
+    /// Rate limiter configuration
+    pub rate_limiter: RateLimiterConfig,
+    /// Circuit breaker configuration
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Send requests to [`TESTNET_BASE_URL`] instead of production
+    pub sandbox: bool,
+    /// Proxy, extra root certificates and connect timeout
+    pub network: NetworkConfig,
+    /// Venue identifiers of config symbols, with overrides
+    pub symbols: SymbolMapper,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            timeout: Duration::from_secs(30),
+            rate_limiter: RateLimiterConfig::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            sandbox: false,
+            network: NetworkConfig::default(),
+            symbols: SymbolMapper::default(),
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Set maximum retry attempts
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Set request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set proxy, extra root certificates and connect timeout
+    pub fn with_network(mut self, network: NetworkConfig) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Map symbols through `symbols`, e.g. for listings with overridden IDs
+    pub fn with_symbols(mut self, symbols: SymbolMapper) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Set rate limit (requests per second)
+    pub fn with_rate_limit(mut self, requests_per_second: usize) -> Self {
+        self.rate_limiter = self.rate_limiter.with_rate(requests_per_second);
+        self
+    }
+
+    /// Set circuit breaker failure threshold
+    pub fn with_circuit_breaker_threshold(mut self, threshold: u32) -> Self {
+        self.circuit_breaker = self.circuit_breaker.with_failure_threshold(threshold);
+        self
+    }
+
+    /// Use the testnet instead of production
+    pub fn with_sandbox(mut self, sandbox: bool) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+}
+
+/// Bybit Exchange API Client
+///
+/// Provides methods to interact with the Bybit API including:
+/// - Public endpoints (ticker, klines, funding history)
+/// - Authenticated endpoints (orders, wallet balance, positions)
+#[derive(Clone)]
+pub struct BybitClient {
+    credentials: Credentials,
+    http_client: Client,
+    circuit_breaker: Arc<Mutex<CircuitBreaker>>,
+    rate_limiter: RateLimiter,
+    max_retries: u32,
+    base_url: &'static str,
+    symbols: SymbolMapper,
+    #[cfg(feature = "fault-injection")]
+    faults: crate::common::FaultInjector,
+}
+
+impl BybitClient {
+    /// Create a new client with API credentials
+    pub fn new(api_key: impl Into<String>, api_secret: impl Into<String>) -> Self {
+        Self::with_config(api_key, api_secret, ClientConfig::default())
+    }
+
+    /// Create a new client with custom configuration
+    pub fn with_config(
+        api_key: impl Into<String>,
+        api_secret: impl Into<String>,
+        config: ClientConfig,
+    ) -> Self {
+        let builder = Client::builder()
+            .timeout(config.timeout)
+            .pool_max_idle_per_host(10)
+            .pool_idle_timeout(Duration::from_secs(90));
+        let http_client = config
+            .network
+            .apply(builder)
+            .and_then(|b| b.build())
+            .expect("invalid network settings (see NetworkConfig::validate)");
+
+        Self {
+            credentials: Credentials::new(api_key, api_secret),
+            http_client,
+            circuit_breaker: Arc::new(Mutex::new(CircuitBreaker::new(config.circuit_breaker))),
+            rate_limiter: RateLimiter::new(config.rate_limiter),
+            max_retries: config.max_retries,
+            base_url: if config.sandbox {
+                TESTNET_BASE_URL
+            } else {
+                API_BASE_URL
+            },
+            symbols: config.symbols,
+            #[cfg(feature = "fault-injection")]
+            faults: crate::common::FaultInjector::default(),
+        }
+    }
+
+    /// Symbol mapping used for every venue identifier
+    pub fn symbol_mapper(&self) -> &SymbolMapper {
+        &self.symbols
+    }
+
+    /// Replace attempts with queued faults (see [`crate::common::faults`])
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: crate::common::FaultInjector) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    /// Create a client from environment variables
+    ///
+    /// Expects `BYBIT_API_KEY` and `BYBIT_API_SECRET`
+    pub fn from_env() -> Result<Self> {
+        let credentials =
+            Credentials::from_env().context("Failed to load Bybit credentials from environment")?;
+        Ok(Self::with_config(
+            credentials.api_key(),
+            credentials.api_secret(),
+            ClientConfig::default(),
+        ))
+    }
+
+    /// Execute a request with retry logic, rate limiting, and circuit breaker
+    ///
+    /// `Low`/`Normal` requests fail with [`RequestShed`](crate::common::RequestShed)
+    /// instead of waiting when the rate limit is under pressure.
+    async fn execute_with_retry<F, Fut, T>(
+        &self,
+        op: &str,
+        priority: RequestPriority,
+        operation: F,
+    ) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+        T: serde::de::DeserializeOwned,
+    {
+        {
+            let mut cb = self.circuit_breaker.lock().await;
+            if !cb.can_attempt() {
+                return Err(anyhow!("Circuit breaker is open, rejecting request"));
+            }
+        }
+
+        self.rate_limiter.acquire_for(op, priority).await?;
+
+        let mut last_error = None;
+
+        for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                let delay = Duration::from_secs(2u64.pow(attempt - 1));
+                tracing::debug!("Retrying after {}ms", delay.as_millis());
+                sleep(delay).await;
+            }
+
+            #[cfg(feature = "fault-injection")]
+            let outcome = match self.faults.take(op) {
+                Some(fault) => fault.into_result(),
+                None => operation().await,
+            };
+            #[cfg(not(feature = "fault-injection"))]
+            let outcome = operation().await;
+
+            match outcome {
+                Ok(result) => {
+                    let mut cb = self.circuit_breaker.lock().await;
+                    cb.record_success();
+                    return Ok(result);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "{} failed (attempt {}/{}): {}",
+                        op,
+                        attempt + 1,
+                        self.max_retries + 1,
+                        e
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        {
+            let mut cb = self.circuit_breaker.lock().await;
+            cb.record_failure();
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!("Request failed after retries")))
+    }
+
+    /// Read a response and unwrap the envelope
+    async fn parse_response<R>(&self, response: reqwest::Response) -> Result<R>
+    where
+        R: serde::de::DeserializeOwned,
+    {
+        self.rate_limiter
+            .observe_response(response.status(), response.headers())
+            .await;
+
+        let status = response.status();
+        let text = response.text().await.context("Failed to read response")?;
+
+        if !status.is_success() {
+            return Err(anyhow!("API error ({}): {}", status, text));
+        }
+
+        let envelope: Response = serde_json::from_str(&text).context("Failed to parse response")?;
+        envelope.into_result()
+    }
+
+    /// Make a GET request, signed when `signed` is set
+    async fn get<R>(&self, endpoint: &str, query: &[(&str, String)], signed: bool) -> Result<R>
+    where
+        R: serde::de::DeserializeOwned,
+    {
+        let query = serde_urlencoded::to_string(query).context("Failed to encode query")?;
+        let url = format!("{}{}?{}", self.base_url, endpoint, query);
+        let mut request = self.http_client.get(&url);
+        if signed {
+            request = self.signed_headers(request, &query);
+        }
+        let response = request.send().await.context("Failed to send request")?;
+        self.parse_response(response).await
+    }
+
+    /// Make an authenticated POST request with a JSON body
+    async fn authenticated_post<T, R>(&self, endpoint: &str, body: &T) -> Result<R>
+    where
+        T: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        let url = format!("{}{}", self.base_url, endpoint);
+        let json_body = serde_json::to_string(body)?;
+        let request = self
+            .http_client
+            .post(&url)
+            .header("Content-Type", "application/json");
+        let response = self
+            .signed_headers(request, &json_body)
+            .body(json_body)
+            .send()
+            .await
+            .context("Failed to send request")?;
+        self.parse_response(response).await
+    }
+
+    fn signed_headers(
+        &self,
+        request: reqwest::RequestBuilder,
+        payload: &str,
+    ) -> reqwest::RequestBuilder {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        request
+            .header("X-BAPI-API-KEY", self.credentials.api_key())
+            .header("X-BAPI-TIMESTAMP", timestamp.to_string())
+            .header("X-BAPI-RECV-WINDOW", RECV_WINDOW_MS.to_string())
+            .header("X-BAPI-SIGN", self.credentials.sign(timestamp, payload))
+    }
+
+    // ==================== PUBLIC ENDPOINTS ====================
+
+    /// Get ticker (with mark price and funding) for a linear perpetual
+    pub async fn get_ticker(&self, symbol: &str) -> Result<Ticker> {
+        self.get_ticker_with_priority(symbol, RequestPriority::Normal)
+            .await
+    }
+
+    /// Get ticker with an explicit rate-limit priority
+    pub async fn get_ticker_with_priority(
+        &self,
+        symbol: &str,
+        priority: RequestPriority,
+    ) -> Result<Ticker> {
+        let contract = self.symbols.id(symbol, Venue::Bybit);
+        self.execute_with_retry("ticker", priority, || {
+            let query = [
+                ("category", "linear".to_string()),
+                ("symbol", contract.clone()),
+            ];
+            let this = self.clone();
+            let contract = contract.clone();
+
+            async move {
+                let tickers: List<Ticker> = this.get("/v5/market/tickers", &query, false).await?;
+                tickers
+                    .list
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| anyhow!("Ticker not found for {}", contract))
+            }
+        })
+        .await
+    }
+
+    /// Get klines between `start` and `end` (Unix ms), oldest first
+    ///
+    /// Bybit returns at most 1000 bars, the latest in the window.
+    pub async fn get_klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<i64>,
+        end: Option<i64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<Kline>> {
+        let bybit_interval = to_bybit_interval(interval)
+            .with_context(|| format!("Bybit does not serve {} candles", interval))?;
+        let limit = limit
+            .unwrap_or(MAX_KLINES_PER_REQUEST)
+            .min(MAX_KLINES_PER_REQUEST);
+        let mut query = vec![
+            ("category", "linear".to_string()),
+            ("symbol", self.symbols.id(symbol, Venue::Bybit)),
+            ("interval", bybit_interval.to_string()),
+            ("limit", limit.to_string()),
+        ];
+        if let Some(start) = start {
+            query.push(("start", start.to_string()));
+        }
+        if let Some(end) = end {
+            query.push(("end", end.to_string()));
+        }
+
+        let rows: List<Vec<String>> = self
+            .execute_with_retry("klines", RequestPriority::Normal, || {
+                let query = query.clone();
+                let this = self.clone();
+
+                async move { this.get("/v5/market/kline", &query, false).await }
+            })
+            .await?;
+
+        // Newest first on the wire
+        Ok(rows
+            .list
+            .iter()
+            .rev()
+            .filter_map(|row| Kline::from_raw(row))
+            .collect())
+    }
+
+    /// Klines for the last `days_back` days, oldest first, paging back from now
+    pub async fn fetch_full_history(
+        &self,
+        symbol: &str,
+        interval: &str,
+        days_back: u32,
+    ) -> Result<Vec<Kline>> {
+        let start = (Utc::now() - ChronoDuration::days(days_back as i64)).timestamp_millis();
+        let mut end = Utc::now().timestamp_millis();
+        info!(
+            "Fetching {} {} data from Bybit ({} days back)",
+            self.symbols.id(symbol, Venue::Bybit),
+            interval,
+            days_back
+        );
+
+        let mut klines = Vec::new();
+        while end > start {
+            let batch = self
+                .get_klines(symbol, interval, Some(start), Some(end), None)
+                .await?;
+            let Some(oldest) = batch.first().map(|k| k.start_time) else {
+                break;
+            };
+            klines.extend(batch);
+            end = oldest - 1;
+        }
+
+        klines.sort_by_key(|k| k.start_time);
+        klines.dedup_by_key(|k| k.start_time);
+        Ok(klines)
+    }
+
+    /// Funding events between `start` and `end` (Unix ms), newest first
+    pub async fn get_funding_history(
+        &self,
+        symbol: &str,
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<FundingRate>> {
+        let query = vec![
+            ("category", "linear".to_string()),
+            ("symbol", self.symbols.id(symbol, Venue::Bybit)),
+            ("startTime", start.to_string()),
+            ("endTime", end.to_string()),
+            ("limit", MAX_FUNDING_PER_REQUEST.to_string()),
+        ];
+        let rates: List<FundingRate> = self
+            .execute_with_retry("funding_history", RequestPriority::Low, || {
+                let query = query.clone();
+                let this = self.clone();
+
+                async move { this.get("/v5/market/funding/history", &query, false).await }
+            })
+            .await?;
+        Ok(rates.list)
+    }
+
+    /// Funding events for the last `days_back` days, oldest first
+    pub async fn fetch_funding_history(
+        &self,
+        symbol: &str,
+        days_back: u32,
+    ) -> Result<Vec<FundingRate>> {
+        let start = (Utc::now() - ChronoDuration::days(days_back as i64)).timestamp_millis();
+        let mut end = Utc::now().timestamp_millis();
+
+        let mut rates = Vec::new();
+        while end > start {
+            let batch = self.get_funding_history(symbol, start, end).await?;
+            let Some(oldest) = batch.last().and_then(FundingRate::timestamp_ms) else {
+                break;
+            };
+            rates.extend(batch);
+            end = oldest - 1;
+        }
+
+        rates.sort_by_key(|r| r.timestamp_ms());
+        rates.dedup_by_key(|r| r.timestamp_ms());
+        Ok(rates)
+    }
+
+    // ==================== AUTHENTICATED ENDPOINTS ====================
+
+    /// Get coin balances of the unified trading account
+    pub async fn get_balances(&self) -> Result<Vec<CoinBalance>> {
+        let wallets: List<WalletBalance> = self
+            .execute_with_retry("balances", RequestPriority::Normal, || {
+                let query = [("accountType", "UNIFIED".to_string())];
+                let this = self.clone();
+
+                async move { this.get("/v5/account/wallet-balance", &query, true).await }
+            })
+            .await?;
+        Ok(wallets.list.into_iter().flat_map(|w| w.coin).collect())
+    }
+
+    /// Get open linear positions, optionally for one symbol
+    pub async fn get_positions(&self, symbol: Option<&str>) -> Result<Vec<Position>> {
+        let mut query = vec![("category", "linear".to_string())];
+        match symbol {
+            Some(symbol) => query.push(("symbol", self.symbols.id(symbol, Venue::Bybit))),
+            None => query.push(("settleCoin", "USDT".to_string())),
+        }
+        let positions: List<Position> = self
+            .execute_with_retry("positions", RequestPriority::Normal, || {
+                let query = query.clone();
+                let this = self.clone();
+
+                async move { this.get("/v5/position/list", &query, true).await }
+            })
+            .await?;
+        Ok(positions.list)
+    }
+
+    /// Place a new order
+    pub async fn place_order(&self, order: &OrderRequest) -> Result<OrderResult> {
+        let order = order.clone();
+        self.execute_with_retry("place_order", RequestPriority::Critical, || {
+            let ord = order.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post("/v5/order/create", &ord).await }
+        })
+        .await
+    }
+
+    /// Cancel an order by ID
+    pub async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<()> {
+        let request = CancelOrderRequest::new(self.symbols.id(symbol, Venue::Bybit), order_id);
+        self.execute_with_retry("cancel_order", RequestPriority::Critical, || {
+            let req = request.clone();
+            let this = self.clone();
+
+            async move {
+                let _: OrderResult = this.authenticated_post("/v5/order/cancel", &req).await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_to_contract() {
+        assert_eq!(symbol_to_contract("BTCUSDT"), "BTCUSDT");
+        assert_eq!(symbol_to_contract("eth/usdt"), "ETHUSDT");
+        assert_eq!(symbol_to_contract("SOL"), "SOLUSDT");
+        assert_eq!(symbol_to_contract("BTCUSDC"), "BTCUSDC");
+    }
+
+    #[test]
+    fn test_sandbox_uses_testnet() {
+        assert_eq!(BybitClient::new("key", "secret").base_url, API_BASE_URL);
+        let config = ClientConfig::default().with_sandbox(true);
+        let client = BybitClient::with_config("key", "secret", config);
+        assert_eq!(client.base_url, TESTNET_BASE_URL);
+    }
+}
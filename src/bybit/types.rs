@@ -0,0 +1,360 @@
+//! Type definitions for Bybit v5 API requests and responses
+//!
+//! Responses are wrapped in `{"retCode": 0, "retMsg": "OK", "result": ...}`
+//! and carry prices, sizes and timestamps as strings.
+
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Response envelope shared by every endpoint
+///
+/// Errors still carry a (usually empty) `result` object, so it is only
+/// parsed once `retCode` says the call succeeded.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    pub ret_code: i64,
+    #[serde(default)]
+    pub ret_msg: String,
+    #[serde(default)]
+    pub result: serde_json::Value,
+}
+
+impl Response {
+    /// The result, or the error code Bybit reported
+    pub fn into_result<T: DeserializeOwned>(self) -> Result<T> {
+        if self.ret_code != 0 {
+            return Err(anyhow!("Bybit error {}: {}", self.ret_code, self.ret_msg));
+        }
+        serde_json::from_value(self.result).context("Failed to parse Bybit result")
+    }
+}
+
+/// `result` of the list endpoints
+#[derive(Debug, Clone, Deserialize)]
+pub struct List<T> {
+    pub list: Vec<T>,
+}
+
+fn parse(value: &str) -> Option<f64> {
+    value.parse().ok()
+}
+
+/// Linear perpetual ticker from `/v5/market/tickers`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ticker {
+    pub symbol: String,
+    pub last_price: String,
+    #[serde(default)]
+    pub bid1_price: String,
+    #[serde(default)]
+    pub ask1_price: String,
+    /// 24h volume in contracts (base asset)
+    #[serde(default)]
+    pub volume24h: String,
+    #[serde(default)]
+    pub mark_price: String,
+    #[serde(default)]
+    pub index_price: String,
+    /// Rate charged at the next funding time
+    #[serde(default)]
+    pub funding_rate: String,
+    /// Next funding time (Unix ms)
+    #[serde(default)]
+    pub next_funding_time: String,
+}
+
+impl Ticker {
+    pub fn last_price_f64(&self) -> Option<f64> {
+        parse(&self.last_price)
+    }
+
+    pub fn bid_f64(&self) -> Option<f64> {
+        parse(&self.bid1_price)
+    }
+
+    pub fn ask_f64(&self) -> Option<f64> {
+        parse(&self.ask1_price)
+    }
+
+    pub fn volume_f64(&self) -> Option<f64> {
+        parse(&self.volume24h)
+    }
+
+    pub fn funding_rate_f64(&self) -> Option<f64> {
+        parse(&self.funding_rate)
+    }
+
+    /// Premium of mark over index price (mark / index - 1)
+    pub fn basis(&self) -> Option<f64> {
+        Some(parse(&self.mark_price)? / parse(&self.index_price)? - 1.0)
+    }
+}
+
+/// One candle from `/v5/market/kline`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kline {
+    /// Bar open time (Unix ms)
+    pub start_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Volume in the base asset
+    pub volume: f64,
+    /// Volume in the quote asset
+    pub turnover: f64,
+}
+
+impl Kline {
+    /// Parse from the raw `[start, open, high, low, close, volume, turnover]` row
+    pub fn from_raw(raw: &[String]) -> Option<Self> {
+        if raw.len() < 7 {
+            return None;
+        }
+
+        Some(Kline {
+            start_time: raw[0].parse().ok()?,
+            open: raw[1].parse().ok()?,
+            high: raw[2].parse().ok()?,
+            low: raw[3].parse().ok()?,
+            close: raw[4].parse().ok()?,
+            volume: raw[5].parse().ok()?,
+            turnover: raw[6].parse().ok()?,
+        })
+    }
+}
+
+/// One funding event from `/v5/market/funding/history`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRate {
+    pub symbol: String,
+    /// Rate per funding interval as a decimal string
+    pub funding_rate: String,
+    /// Funding time (Unix ms) as a string
+    pub funding_rate_timestamp: String,
+}
+
+impl FundingRate {
+    pub fn rate(&self) -> Option<f64> {
+        parse(&self.funding_rate)
+    }
+
+    pub fn timestamp_ms(&self) -> Option<i64> {
+        self.funding_rate_timestamp.parse().ok()
+    }
+}
+
+/// Order side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+/// Order type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderType {
+    Market,
+    Limit,
+}
+
+/// Body of `/v5/order/create` for a linear perpetual
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderRequest {
+    pub category: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    /// Quantity in contracts (base asset)
+    pub qty: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_link_id: Option<String>,
+    /// Only reduce an open position, never open or flip one
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub reduce_only: bool,
+}
+
+impl OrderRequest {
+    /// Create a market order
+    pub fn market(side: OrderSide, symbol: impl Into<String>, qty: f64) -> Self {
+        Self {
+            category: "linear".to_string(),
+            symbol: symbol.into(),
+            side,
+            order_type: OrderType::Market,
+            qty: qty.to_string(),
+            price: None,
+            order_link_id: None,
+            reduce_only: false,
+        }
+    }
+
+    /// Create a limit order
+    pub fn limit(side: OrderSide, symbol: impl Into<String>, qty: f64, price: f64) -> Self {
+        Self {
+            order_type: OrderType::Limit,
+            price: Some(price.to_string()),
+            ..Self::market(side, symbol, qty)
+        }
+    }
+
+    /// Set client order ID
+    pub fn with_order_link_id(mut self, id: impl Into<String>) -> Self {
+        self.order_link_id = Some(id.into());
+        self
+    }
+
+    /// Mark the order reduce-only
+    pub fn with_reduce_only(mut self) -> Self {
+        self.reduce_only = true;
+        self
+    }
+}
+
+/// Result of `/v5/order/create` and `/v5/order/cancel`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderResult {
+    pub order_id: String,
+    #[serde(default)]
+    pub order_link_id: String,
+}
+
+/// Body of `/v5/order/cancel`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrderRequest {
+    pub category: String,
+    pub symbol: String,
+    pub order_id: String,
+}
+
+impl CancelOrderRequest {
+    pub fn new(symbol: impl Into<String>, order_id: impl Into<String>) -> Self {
+        Self {
+            category: "linear".to_string(),
+            symbol: symbol.into(),
+            order_id: order_id.into(),
+        }
+    }
+}
+
+/// One account from `/v5/account/wallet-balance`
+#[derive(Debug, Clone, Deserialize)]
+pub struct WalletBalance {
+    #[serde(default)]
+    pub coin: Vec<CoinBalance>,
+}
+
+/// One coin of a wallet
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinBalance {
+    pub coin: String,
+    #[serde(default)]
+    pub wallet_balance: String,
+    /// Held by open spot orders
+    #[serde(default)]
+    pub locked: String,
+    /// Initial margin of open positions
+    #[serde(default, rename = "totalPositionIM")]
+    pub total_position_im: String,
+}
+
+impl CoinBalance {
+    pub fn wallet_balance_f64(&self) -> f64 {
+        parse(&self.wallet_balance).unwrap_or_default()
+    }
+
+    /// Funds tied up in orders and position margin
+    pub fn locked_f64(&self) -> f64 {
+        parse(&self.locked).unwrap_or_default() + parse(&self.total_position_im).unwrap_or_default()
+    }
+}
+
+/// One open position from `/v5/position/list`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub symbol: String,
+    /// `Buy` (long), `Sell` (short) or empty when flat
+    pub side: String,
+    pub size: String,
+    #[serde(default)]
+    pub avg_price: String,
+    #[serde(default)]
+    pub mark_price: String,
+    #[serde(default)]
+    pub unrealised_pnl: String,
+    #[serde(default)]
+    pub leverage: String,
+}
+
+impl Position {
+    /// Signed size: positive long, negative short
+    pub fn signed_size(&self) -> f64 {
+        let size = parse(&self.size).unwrap_or_default();
+        if self.side == "Sell" {
+            -size
+        } else {
+            size
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_error_code() {
+        let err: Response =
+            serde_json::from_str(r#"{"retCode":10001,"retMsg":"params error","result":{}}"#)
+                .unwrap();
+        assert!(err
+            .into_result::<OrderResult>()
+            .unwrap_err()
+            .to_string()
+            .contains("10001"));
+    }
+
+    #[test]
+    fn test_ticker_and_kline_parsing() {
+        let tickers: Response = serde_json::from_str(
+            r#"{"retCode":0,"retMsg":"OK","result":{"category":"linear","list":[{
+                "symbol":"BTCUSDT","lastPrice":"30000.5","bid1Price":"30000","ask1Price":"30001",
+                "volume24h":"1234.5","markPrice":"30030","indexPrice":"30000",
+                "fundingRate":"0.0001","nextFundingTime":"1688716800000"}]}}"#,
+        )
+        .unwrap();
+        let ticker = &tickers.into_result::<List<Ticker>>().unwrap().list[0];
+        assert_eq!(ticker.last_price_f64(), Some(30000.5));
+        assert_eq!(ticker.funding_rate_f64(), Some(0.0001));
+        assert!((ticker.basis().unwrap() - 0.001).abs() < 1e-12);
+
+        let row: Vec<String> = ["1688671200000", "1", "2", "0.5", "1.5", "10", "15"]
+            .map(String::from)
+            .to_vec();
+        let kline = Kline::from_raw(&row).unwrap();
+        assert_eq!(kline.start_time, 1688671200000);
+        assert_eq!(kline.turnover, 15.0);
+    }
+
+    #[test]
+    fn test_order_request_json() {
+        let order = OrderRequest::limit(OrderSide::Sell, "BTCUSDT", 0.01, 31000.0)
+            .with_order_link_id("exit-1")
+            .with_reduce_only();
+        assert_eq!(
+            serde_json::to_string(&order).unwrap(),
+            r#"{"category":"linear","symbol":"BTCUSDT","side":"Sell","orderType":"Limit","qty":"0.01","price":"31000","orderLinkId":"exit-1","reduceOnly":true}"#
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Exchange capability matrix
+//!
+//! Venues differ in which order types, candle intervals and markets they
+//! support. Each exchange module publishes an [`ExchangeCapabilities`]
+//! descriptor, and [`assert_supported`] checks a config + strategy against it
+//! at startup so an unsupported order type or timeframe fails before the first
+//! trade instead of as a rejected order mid-session.
+
+use anyhow::Result;
+
+use crate::config::SpreadAction;
+use crate::oms::OrderType;
+use crate::strategies::Strategy;
+use crate::Config;
+
+/// How order quantities and prices must be rounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrecisionRule {
+    /// Step sizes come from the exchange's market details, per symbol
+    PerMarket,
+    /// Same decimals for every instrument
+    Fixed {
+        quantity_decimals: u32,
+        price_decimals: u32,
+    },
+}
+
+/// What a venue supports
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExchangeCapabilities {
+    pub name: &'static str,
+    /// Order types accepted by the order endpoint
+    pub order_types: &'static [OrderType],
+    /// Candle intervals served by the history endpoint
+    pub timeframes: &'static [&'static str],
+    /// Quote currencies of tradable symbols (empty = no suffix convention)
+    pub quote_currencies: &'static [&'static str],
+    pub precision: PrecisionRule,
+    /// Smallest accepted order value in the quote currency
+    pub min_notional: Option<f64>,
+    /// Whether the client can point at a testnet (`exchange.sandbox`)
+    pub sandbox: bool,
+    /// Whether the venue pushes order updates, so live fills are booked as reported
+    pub fill_reports: bool,
+}
+
+impl ExchangeCapabilities {
+    pub fn supports_order_type(&self, order_type: OrderType) -> bool {
+        self.order_types.contains(&order_type)
+    }
+
+    pub fn supports_timeframe(&self, timeframe: &str) -> bool {
+        self.timeframes.contains(&timeframe)
+    }
+
+    pub fn supports_symbol(&self, symbol: &str) -> bool {
+        let symbol = symbol.to_uppercase();
+        self.quote_currencies.is_empty()
+            || self.quote_currencies.iter().any(|q| symbol.ends_with(q))
+    }
+
+    /// Everything `config` and `strategy` rely on that this venue lacks
+    pub fn unsupported(&self, config: &Config, strategy: &dyn Strategy) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let primary = config.timeframe();
+        let mut timeframes = vec![primary.as_str()];
+        for tf in strategy.required_timeframes() {
+            if !timeframes.contains(&tf) {
+                timeframes.push(tf);
+            }
+        }
+        for tf in timeframes {
+            if !self.supports_timeframe(tf) {
+                problems.push(format!(
+                    "timeframe '{}' is not available (supported: {})",
+                    tf,
+                    self.timeframes.join(", ")
+                ));
+            }
+        }
+
+        let mut order_types = strategy.required_order_types();
+        // limit_at_mid turns wide-spread market orders into limits
+        if config
+            .exchange
+            .spread_guard
+            .as_ref()
+            .is_some_and(|g| g.action == SpreadAction::LimitAtMid)
+        {
+            order_types.push(OrderType::Limit);
+        }
+        let mut reported = Vec::new();
+        for ot in order_types {
+            if !self.supports_order_type(ot) && !reported.contains(&ot) {
+                reported.push(ot);
+                problems.push(format!(
+                    "strategy '{}' needs {:?} orders, which are not supported",
+                    strategy.name(),
+                    ot
+                ));
+            }
+        }
+
+        for symbol in &config.trading.symbols {
+            if !self.supports_symbol(symbol) {
+                problems.push(format!(
+                    "symbol '{}' is not quoted in {}",
+                    symbol,
+                    self.quote_currencies.join("/")
+                ));
+            }
+        }
+
+        if config.exchange.sandbox && !self.sandbox {
+            problems.push(format!(
+                "{} has no sandbox; use paper mode instead",
+                self.name
+            ));
+        }
+
+        if let Some(min) = self.min_notional {
+            let max_order = config.trading.initial_capital * config.trading.max_position_pct;
+            if max_order < min {
+                problems.push(format!(
+                    "largest position ({:.2} = initial_capital x max_position_pct) is below the \
+                     minimum order value of {:.2}",
+                    max_order, min
+                ));
+            }
+        }
+
+        problems
+    }
+}
+
+/// Fail with every unsupported capability listed
+pub fn assert_supported(
+    caps: &ExchangeCapabilities,
+    config: &Config,
+    strategy: &dyn Strategy,
+) -> Result<()> {
+    let problems = caps.unsupported(config, strategy);
+    if problems.is_empty() {
+        return Ok(());
+    }
+    anyhow::bail!(
+        "Config is not supported by {}:\n  - {}",
+        caps.name,
+        problems.join("\n  - ")
+    )
+}
+
+/// Fail when live orders on this venue would be booked from candles
+///
+/// Without order updates the trader can only guess fills from each bar, and
+/// positions drift from the exchange's after the first partial or missed fill.
+pub fn assert_live_fills(caps: &ExchangeCapabilities) -> Result<()> {
+    if caps.fill_reports {
+        return Ok(());
+    }
+    anyhow::bail!(
+        "{} does not report fills to the live trader; run it in paper mode",
+        caps.name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategies::regime_grid::{RegimeGridConfig, RegimeGridStrategy};
+    use crate::strategies::volatility_regime::{VolatilityRegimeConfig, VolatilityRegimeStrategy};
+
+    const SPOT: ExchangeCapabilities = ExchangeCapabilities {
+        name: "test",
+        order_types: &[OrderType::Market],
+        timeframes: &["1h", "1d"],
+        quote_currencies: &["INR"],
+        precision: PrecisionRule::PerMarket,
+        min_notional: Some(100.0),
+        sandbox: false,
+        fill_reports: false,
+    };
+
+    fn config(timeframe: &str) -> Config {
+        let mut config: Config =
+            serde_json::from_str(include_str!("../configs/sample_config.json")).unwrap();
+        config.set_timeframe(timeframe);
+        config.trading.symbols = vec!["BTCINR".to_string()];
+        config
+    }
+
+    #[test]
+    fn test_supported_config_passes() {
+        let mut config = config("1d");
+        let strategy = VolatilityRegimeStrategy::new(VolatilityRegimeConfig::default());
+        assert!(assert_supported(&SPOT, &config, &strategy).is_ok());
+
+        // Sandbox runs need a venue with a testnet
+        config.exchange.sandbox = true;
+        let problems = SPOT.unsupported(&config, &strategy);
+        assert_eq!(
+            problems,
+            vec!["test has no sandbox; use paper mode instead"]
+        );
+        let testnet = ExchangeCapabilities {
+            sandbox: true,
+            ..SPOT
+        };
+        assert!(assert_supported(&testnet, &config, &strategy).is_ok());
+    }
+
+    #[test]
+    fn test_live_trading_needs_fill_reports() {
+        let err = assert_live_fills(&SPOT).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "test does not report fills to the live trader; run it in paper mode"
+        );
+        let reporting = ExchangeCapabilities {
+            fill_reports: true,
+            ..SPOT
+        };
+        assert!(assert_live_fills(&reporting).is_ok());
+    }
+
+    #[test]
+    fn test_unsupported_capabilities_are_all_reported() {
+        let mut config = config("4h");
+        config.trading.symbols.push("ETHUSDT".to_string());
+        let strategy = RegimeGridStrategy::new(RegimeGridConfig::default());
+        let problems = SPOT.unsupported(&config, &strategy);
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems[0].contains("'4h'"));
+        assert!(problems[1].contains("Limit"));
+        assert!(problems[2].contains("ETHUSDT"));
+        assert!(assert_supported(&SPOT, &config, &strategy).is_err());
+    }
+}
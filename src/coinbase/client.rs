@@ -0,0 +1,586 @@
+//! Coinbase Advanced Trade API Client
+//!
+//! HTTP client for Coinbase spot markets with the same resilience as the
+//! Kraken client:
+//! - Automatic retry with exponential backoff
+//! - Rate limiting with priority-based shedding (order calls first)
+//! - Circuit breaker pattern for fault tolerance
+//!
+//! Market data comes from the public `/market/*` endpoints, so downloads
+//! and paper trading need no API key.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use crypto_strategies::coinbase::CoinbaseClient;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let client = CoinbaseClient::new("api_key", "api_secret");
+//!
+//!     let product = client.get_product("BTCUSD").await?;
+//!     println!("BTC-USD price: {:?}", product.price_f64());
+//!
+//!     for account in client.get_accounts().await? {
+//!         println!("{}: {}", account.currency, account.available_f64());
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{Duration as ChronoDuration, Utc};
+use reqwest::Client;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tracing::info;
+
+use super::auth::Credentials;
+use super::types::*;
+use super::{granularity_seconds, to_coinbase_granularity};
+use crate::common::{
+    CircuitBreaker, CircuitBreakerConfig, NetworkConfig, RateLimiter, RateLimiterConfig,
+    RequestPriority,
+};
+use crate::symbols::{SymbolMapper, Venue};
+
+/// Base URL for the Coinbase Advanced Trade REST API
+pub const API_BASE_URL: &str = "https://api.coinbase.com";
+
+/// Base URL of the Advanced Trade sandbox, which answers with static mock data
+pub const SANDBOX_BASE_URL: &str = "https://api-sandbox.coinbase.com";
+
+/// Most candles the candles endpoint returns per request
+const MAX_CANDLES_PER_REQUEST: i64 = 350;
+
+/// Convert a config symbol to a Coinbase product ID
+///
+/// `BTCUSD`, `btc/usd` and `BTC-USD` all become `BTC-USD`; see
+/// [`crate::symbols::coinbase_product`].
+pub fn symbol_to_product(symbol: &str) -> String {
+    crate::symbols::coinbase_product(symbol)
+}
+
+/// Client configuration
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Maximum number of retry attempts
+    pub max_retries: u32,
+    /// Request timeout duration
+    pub timeout: Duration,
+    /// Rate limiter configuration
+    pub rate_limiter: RateLimiterConfig,
+    /// Circuit breaker configuration
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Send requests to [`SANDBOX_BASE_URL`] instead of production
+    pub sandbox: bool,
+    /// Proxy, extra root certificates and connect timeout
+    pub network: NetworkConfig,
+    /// Venue identifiers of config symbols, with overrides
+    pub symbols: SymbolMapper,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            timeout: Duration::from_secs(30),
+            // Public endpoints allow 10 requests per second per IP
+            rate_limiter: RateLimiterConfig::default().with_rate(10),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            sandbox: false,
+            network: NetworkConfig::default(),
+            symbols: SymbolMapper::default(),
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Set maximum retry attempts
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Set request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set proxy, extra root certificates and connect timeout
+    pub fn with_network(mut self, network: NetworkConfig) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Map symbols through `symbols`, e.g. for listings with overridden IDs
+    pub fn with_symbols(mut self, symbols: SymbolMapper) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Set rate limit (requests per second)
+    pub fn with_rate_limit(mut self, requests_per_second: usize) -> Self {
+        self.rate_limiter = self.rate_limiter.with_rate(requests_per_second);
+        self
+    }
+
+    /// Set circuit breaker failure threshold
+    pub fn with_circuit_breaker_threshold(mut self, threshold: u32) -> Self {
+        self.circuit_breaker = self.circuit_breaker.with_failure_threshold(threshold);
+        self
+    }
+
+    /// Use the testnet instead of production
+    pub fn with_sandbox(mut self, sandbox: bool) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+}
+
+/// Coinbase Advanced Trade API Client
+///
+/// Provides methods to interact with the Coinbase API including:
+/// - Public endpoints (product, candles)
+/// - Authenticated endpoints (orders, accounts)
+#[derive(Clone)]
+pub struct CoinbaseClient {
+    credentials: Credentials,
+    http_client: Client,
+    circuit_breaker: Arc<Mutex<CircuitBreaker>>,
+    rate_limiter: RateLimiter,
+    max_retries: u32,
+    base_url: &'static str,
+    symbols: SymbolMapper,
+    #[cfg(feature = "fault-injection")]
+    faults: crate::common::FaultInjector,
+}
+
+impl CoinbaseClient {
+    /// Create a new client with API credentials
+    pub fn new(api_key: impl Into<String>, api_secret: impl Into<String>) -> Self {
+        Self::with_config(api_key, api_secret, ClientConfig::default())
+    }
+
+    /// Create a new client with custom configuration
+    pub fn with_config(
+        api_key: impl Into<String>,
+        api_secret: impl Into<String>,
+        config: ClientConfig,
+    ) -> Self {
+        let builder = Client::builder()
+            .timeout(config.timeout)
+            .pool_max_idle_per_host(10)
+            .pool_idle_timeout(Duration::from_secs(90));
+        let http_client = config
+            .network
+            .apply(builder)
+            .and_then(|b| b.build())
+            .expect("invalid network settings (see NetworkConfig::validate)");
+
+        Self {
+            credentials: Credentials::new(api_key, api_secret),
+            http_client,
+            circuit_breaker: Arc::new(Mutex::new(CircuitBreaker::new(config.circuit_breaker))),
+            rate_limiter: RateLimiter::new(config.rate_limiter),
+            max_retries: config.max_retries,
+            base_url: if config.sandbox {
+                SANDBOX_BASE_URL
+            } else {
+                API_BASE_URL
+            },
+            symbols: config.symbols,
+            #[cfg(feature = "fault-injection")]
+            faults: crate::common::FaultInjector::default(),
+        }
+    }
+
+    /// Symbol mapping used for every venue identifier
+    pub fn symbol_mapper(&self) -> &SymbolMapper {
+        &self.symbols
+    }
+
+    /// Replace attempts with queued faults (see [`crate::common::faults`])
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: crate::common::FaultInjector) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    /// Create a client from environment variables
+    ///
+    /// Expects `COINBASE_API_KEY` and `COINBASE_API_SECRET`
+    pub fn from_env() -> Result<Self> {
+        let credentials = Credentials::from_env()
+            .context("Failed to load Coinbase credentials from environment")?;
+        Ok(Self::with_config(
+            credentials.api_key(),
+            credentials.api_secret(),
+            ClientConfig::default(),
+        ))
+    }
+
+    /// Execute a request with retry logic, rate limiting, and circuit breaker
+    ///
+    /// `Low`/`Normal` requests fail with [`RequestShed`](crate::common::RequestShed)
+    /// instead of waiting when the rate limit is under pressure.
+    async fn execute_with_retry<F, Fut, T>(
+        &self,
+        op: &str,
+        priority: RequestPriority,
+        operation: F,
+    ) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+        T: serde::de::DeserializeOwned,
+    {
+        {
+            let mut cb = self.circuit_breaker.lock().await;
+            if !cb.can_attempt() {
+                return Err(anyhow!("Circuit breaker is open, rejecting request"));
+            }
+        }
+
+        self.rate_limiter.acquire_for(op, priority).await?;
+
+        let mut last_error = None;
+
+        for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                let delay = Duration::from_secs(2u64.pow(attempt - 1));
+                tracing::debug!("Retrying after {}ms", delay.as_millis());
+                sleep(delay).await;
+            }
+
+            #[cfg(feature = "fault-injection")]
+            let outcome = match self.faults.take(op) {
+                Some(fault) => fault.into_result(),
+                None => operation().await,
+            };
+            #[cfg(not(feature = "fault-injection"))]
+            let outcome = operation().await;
+
+            match outcome {
+                Ok(result) => {
+                    let mut cb = self.circuit_breaker.lock().await;
+                    cb.record_success();
+                    return Ok(result);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "{} failed (attempt {}/{}): {}",
+                        op,
+                        attempt + 1,
+                        self.max_retries + 1,
+                        e
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        {
+            let mut cb = self.circuit_breaker.lock().await;
+            cb.record_failure();
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!("Request failed after retries")))
+    }
+
+    /// Read a response and parse its JSON body
+    async fn parse_response<R>(&self, response: reqwest::Response) -> Result<R>
+    where
+        R: serde::de::DeserializeOwned,
+    {
+        self.rate_limiter
+            .observe_response(response.status(), response.headers())
+            .await;
+
+        let status = response.status();
+        let text = response.text().await.context("Failed to read response")?;
+
+        if !status.is_success() {
+            return Err(anyhow!("API error ({}): {}", status, text));
+        }
+
+        serde_json::from_str(&text).context("Failed to parse response")
+    }
+
+    /// Add the `CB-ACCESS-*` headers for a private request
+    fn signed_headers(
+        &self,
+        request: reqwest::RequestBuilder,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> reqwest::RequestBuilder {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        request
+            .header("CB-ACCESS-KEY", self.credentials.api_key())
+            .header("CB-ACCESS-TIMESTAMP", timestamp.to_string())
+            .header(
+                "CB-ACCESS-SIGN",
+                self.credentials.sign(timestamp, method, path, body),
+            )
+    }
+
+    /// Make a GET request, signed when `signed` is set
+    async fn get<R>(&self, endpoint: &str, query: &[(&str, String)], signed: bool) -> Result<R>
+    where
+        R: serde::de::DeserializeOwned,
+    {
+        let url = format!("{}{}", self.base_url, endpoint);
+        let mut request = self.http_client.get(&url).query(query);
+        if signed {
+            request = self.signed_headers(request, "GET", endpoint, "");
+        }
+        let response = request.send().await.context("Failed to send request")?;
+        self.parse_response(response).await
+    }
+
+    /// Make an authenticated POST request with a JSON body
+    async fn authenticated_post<T, R>(&self, endpoint: &str, body: &T) -> Result<R>
+    where
+        T: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        let url = format!("{}{}", self.base_url, endpoint);
+        let json_body = serde_json::to_string(body)?;
+        let request = self
+            .http_client
+            .post(&url)
+            .header("Content-Type", "application/json");
+        let response = self
+            .signed_headers(request, "POST", endpoint, &json_body)
+            .body(json_body)
+            .send()
+            .await
+            .context("Failed to send request")?;
+        self.parse_response(response).await
+    }
+
+    // ==================== PUBLIC ENDPOINTS ====================
+
+    /// Get a product's last price and 24h volume (`BTCUSD` or `BTC-USD`)
+    pub async fn get_product(&self, symbol: &str) -> Result<Product> {
+        self.get_product_with_priority(symbol, RequestPriority::Normal)
+            .await
+    }
+
+    /// Get a product with an explicit rate-limit priority
+    pub async fn get_product_with_priority(
+        &self,
+        symbol: &str,
+        priority: RequestPriority,
+    ) -> Result<Product> {
+        let endpoint = format!(
+            "/api/v3/brokerage/market/products/{}",
+            self.symbols.id(symbol, Venue::Coinbase)
+        );
+        self.execute_with_retry("ticker", priority, || {
+            let endpoint = endpoint.clone();
+            let this = self.clone();
+
+            async move { this.get(&endpoint, &[], false).await }
+        })
+        .await
+    }
+
+    /// Get up to 350 candles between `start` and `end` (Unix seconds), oldest first
+    ///
+    /// Without `start`, returns the latest 350 candles up to `end` (or now).
+    pub async fn get_candles(
+        &self,
+        symbol: &str,
+        interval: &str,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> Result<Vec<CoinbaseCandle>> {
+        let granularity = to_coinbase_granularity(interval)
+            .with_context(|| format!("Coinbase does not serve {} candles", interval))?;
+        let seconds = granularity_seconds(interval).unwrap_or(60);
+        let end = end.unwrap_or_else(|| Utc::now().timestamp());
+        let start = start
+            .unwrap_or(end - seconds * MAX_CANDLES_PER_REQUEST)
+            .max(end - seconds * MAX_CANDLES_PER_REQUEST);
+        let endpoint = format!(
+            "/api/v3/brokerage/market/products/{}/candles",
+            self.symbols.id(symbol, Venue::Coinbase)
+        );
+        let query = vec![
+            ("start", start.to_string()),
+            ("end", end.to_string()),
+            ("granularity", granularity.to_string()),
+        ];
+
+        let response: CandlesResponse = self
+            .execute_with_retry("candles", RequestPriority::Normal, || {
+                let endpoint = endpoint.clone();
+                let query = query.clone();
+                let this = self.clone();
+
+                async move { this.get(&endpoint, &query, false).await }
+            })
+            .await?;
+
+        // Newest first on the wire
+        let mut candles: Vec<CoinbaseCandle> = response
+            .candles
+            .iter()
+            .filter_map(CoinbaseCandle::from_raw)
+            .collect();
+        candles.sort_by_key(|c| c.start);
+        Ok(candles)
+    }
+
+    /// Fetch `days_back` days of candles, oldest first
+    pub async fn fetch_full_history(
+        &self,
+        symbol: &str,
+        interval: &str,
+        days_back: u32,
+    ) -> Result<Vec<CoinbaseCandle>> {
+        let seconds = granularity_seconds(interval)
+            .with_context(|| format!("Coinbase does not serve {} candles", interval))?;
+        let start = (Utc::now() - ChronoDuration::days(days_back as i64)).timestamp();
+        let mut end = Utc::now().timestamp();
+        info!(
+            "Fetching {} {} data from Coinbase ({} days back)",
+            self.symbols.id(symbol, Venue::Coinbase),
+            interval,
+            days_back
+        );
+
+        let mut candles = Vec::new();
+        while end > start {
+            let window_start = (end - seconds * (MAX_CANDLES_PER_REQUEST - 1)).max(start);
+            let batch = self
+                .get_candles(symbol, interval, Some(window_start), Some(end))
+                .await?;
+            candles.extend(batch);
+            end = window_start - 1;
+        }
+
+        candles.sort_by_key(|c| c.start);
+        candles.dedup_by_key(|c| c.start);
+        Ok(candles)
+    }
+
+    // ==================== AUTHENTICATED ENDPOINTS ====================
+
+    /// Get every account (one per currency), following pagination
+    pub async fn get_accounts(&self) -> Result<Vec<Account>> {
+        let mut accounts = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let mut query = vec![("limit", "250".to_string())];
+            if !cursor.is_empty() {
+                query.push(("cursor", cursor.clone()));
+            }
+            let page: AccountsPage = self
+                .execute_with_retry("balances", RequestPriority::Normal, || {
+                    let query = query.clone();
+                    let this = self.clone();
+
+                    async move { this.get("/api/v3/brokerage/accounts", &query, true).await }
+                })
+                .await?;
+            accounts.extend(page.accounts);
+            if !page.has_next || page.cursor.is_empty() {
+                return Ok(accounts);
+            }
+            cursor = page.cursor;
+        }
+    }
+
+    /// Place a new order
+    ///
+    /// Orders without a client order ID get one, so every retry carries the
+    /// same idempotency key and can't place the order twice.
+    pub async fn place_order(&self, order: &OrderRequest) -> Result<PlacedOrder> {
+        let mut order = order.clone();
+        if order.client_order_id.is_empty() {
+            order.client_order_id = format!(
+                "cs-{}",
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or_default()
+            );
+        }
+        let response: CreateOrderResponse = self
+            .execute_with_retry("place_order", RequestPriority::Critical, || {
+                let ord = order.clone();
+                let this = self.clone();
+
+                async move {
+                    this.authenticated_post("/api/v3/brokerage/orders", &ord)
+                        .await
+                }
+            })
+            .await?;
+        response.into_result()
+    }
+
+    /// Cancel an order by ID
+    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let body = serde_json::json!({ "order_ids": [order_id] });
+        let response: CancelOrdersResponse = self
+            .execute_with_retry("cancel_order", RequestPriority::Critical, || {
+                let body = body.clone();
+                let this = self.clone();
+
+                async move {
+                    this.authenticated_post("/api/v3/brokerage/orders/batch_cancel", &body)
+                        .await
+                }
+            })
+            .await?;
+        match response
+            .results
+            .into_iter()
+            .find(|r| r.order_id == order_id)
+        {
+            Some(result) if result.success => Ok(()),
+            Some(result) => Err(anyhow!(
+                "Coinbase could not cancel {}: {}",
+                order_id,
+                result.failure_reason
+            )),
+            None => Err(anyhow!(
+                "Coinbase did not report on cancelling {}",
+                order_id
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_to_product() {
+        assert_eq!(symbol_to_product("BTCUSD"), "BTC-USD");
+        assert_eq!(symbol_to_product("eth/usdc"), "ETH-USDC");
+        assert_eq!(symbol_to_product("SOL-USD"), "SOL-USD");
+        assert_eq!(symbol_to_product("USDCUSD"), "USDC-USD");
+        assert_eq!(symbol_to_product("USD"), "USD");
+    }
+
+    #[test]
+    fn test_sandbox_uses_sandbox_host() {
+        assert_eq!(CoinbaseClient::new("key", "secret").base_url, API_BASE_URL);
+        let config = ClientConfig::default().with_sandbox(true);
+        let client = CoinbaseClient::with_config("key", "secret", config);
+        assert_eq!(client.base_url, SANDBOX_BASE_URL);
+    }
+}
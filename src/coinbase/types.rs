@@ -0,0 +1,311 @@
+//! Type definitions for Coinbase Advanced Trade API requests and responses
+//!
+//! Coinbase sends prices, sizes and timestamps as strings and reports order
+//! failures in the body of a 200 response.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+fn parse_f64(value: &str) -> Option<f64> {
+    value.parse().ok()
+}
+
+/// One product from `/market/products/{product_id}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Product {
+    pub product_id: String,
+    /// Last trade price
+    #[serde(default)]
+    pub price: String,
+    /// Rolling 24h volume in the base asset
+    #[serde(default)]
+    pub volume_24h: String,
+    #[serde(default)]
+    pub base_increment: String,
+    #[serde(default)]
+    pub quote_increment: String,
+}
+
+impl Product {
+    pub fn price_f64(&self) -> Option<f64> {
+        parse_f64(&self.price)
+    }
+
+    pub fn volume_f64(&self) -> Option<f64> {
+        parse_f64(&self.volume_24h)
+    }
+}
+
+/// Body of `/market/products/{product_id}/candles`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CandlesResponse {
+    #[serde(default)]
+    pub candles: Vec<RawCandle>,
+}
+
+/// Candle as sent by Coinbase, every field a string
+#[derive(Debug, Clone, Deserialize)]
+pub struct RawCandle {
+    pub start: String,
+    pub low: String,
+    pub high: String,
+    pub open: String,
+    pub close: String,
+    pub volume: String,
+}
+
+/// One parsed candle
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoinbaseCandle {
+    /// Candle open time (Unix seconds)
+    pub start: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl CoinbaseCandle {
+    /// Parse the string fields; `None` if any is not a number
+    pub fn from_raw(raw: &RawCandle) -> Option<Self> {
+        Some(Self {
+            start: raw.start.parse().ok()?,
+            open: parse_f64(&raw.open)?,
+            high: parse_f64(&raw.high)?,
+            low: parse_f64(&raw.low)?,
+            close: parse_f64(&raw.close)?,
+            volume: parse_f64(&raw.volume)?,
+        })
+    }
+}
+
+/// Order side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+/// `order_configuration` of a new order; exactly one variant is sent
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderConfiguration {
+    /// Immediate-or-cancel market order sized in the base asset
+    MarketMarketIoc { base_size: String },
+    /// Good-till-cancelled limit order
+    LimitLimitGtc {
+        base_size: String,
+        limit_price: String,
+        post_only: bool,
+    },
+}
+
+/// JSON body of `POST /api/v3/brokerage/orders`
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderRequest {
+    /// Idempotency key; resending the same ID returns the existing order
+    pub client_order_id: String,
+    pub product_id: String,
+    pub side: OrderSide,
+    pub order_configuration: OrderConfiguration,
+}
+
+impl OrderRequest {
+    /// Create a market order; `product_id` is a Coinbase product (see
+    /// [`super::symbol_to_product`])
+    pub fn market(side: OrderSide, product_id: impl Into<String>, base_size: f64) -> Self {
+        Self {
+            client_order_id: String::new(),
+            product_id: product_id.into(),
+            side,
+            order_configuration: OrderConfiguration::MarketMarketIoc {
+                base_size: base_size.to_string(),
+            },
+        }
+    }
+
+    /// Create a limit order
+    pub fn limit(
+        side: OrderSide,
+        product_id: impl Into<String>,
+        base_size: f64,
+        price: f64,
+    ) -> Self {
+        Self {
+            order_configuration: OrderConfiguration::LimitLimitGtc {
+                base_size: base_size.to_string(),
+                limit_price: price.to_string(),
+                post_only: false,
+            },
+            ..Self::market(side, product_id, base_size)
+        }
+    }
+
+    /// Set client order ID (the client generates one when unset)
+    pub fn with_client_order_id(mut self, id: impl Into<String>) -> Self {
+        self.client_order_id = id.into();
+        self
+    }
+}
+
+/// Accepted order from `success_response`
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlacedOrder {
+    pub order_id: String,
+    #[serde(default)]
+    pub product_id: String,
+    #[serde(default)]
+    pub client_order_id: String,
+}
+
+/// Why an order was refused, from `error_response`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OrderError {
+    #[serde(default)]
+    pub error: String,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub error_details: String,
+}
+
+/// Response of `POST /api/v3/brokerage/orders`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateOrderResponse {
+    pub success: bool,
+    #[serde(default)]
+    pub success_response: Option<PlacedOrder>,
+    #[serde(default)]
+    pub error_response: Option<OrderError>,
+}
+
+impl CreateOrderResponse {
+    /// The placed order, or the reason Coinbase refused it
+    pub fn into_result(self) -> Result<PlacedOrder> {
+        match (self.success, self.success_response) {
+            (true, Some(order)) => Ok(order),
+            _ => {
+                let e = self.error_response.unwrap_or_default();
+                Err(anyhow!(
+                    "Coinbase rejected order: {} {} {}",
+                    e.error,
+                    e.message,
+                    e.error_details
+                ))
+            }
+        }
+    }
+}
+
+/// One result of `POST /api/v3/brokerage/orders/batch_cancel`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CancelResult {
+    pub success: bool,
+    #[serde(default)]
+    pub failure_reason: String,
+    #[serde(default)]
+    pub order_id: String,
+}
+
+/// Response of `POST /api/v3/brokerage/orders/batch_cancel`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CancelOrdersResponse {
+    #[serde(default)]
+    pub results: Vec<CancelResult>,
+}
+
+/// Amount of one currency
+#[derive(Debug, Clone, Deserialize)]
+pub struct Amount {
+    pub value: String,
+    pub currency: String,
+}
+
+impl Amount {
+    pub fn value_f64(&self) -> f64 {
+        parse_f64(&self.value).unwrap_or_default()
+    }
+}
+
+/// One wallet from `/api/v3/brokerage/accounts`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Account {
+    pub currency: String,
+    pub available_balance: Amount,
+    /// Funds held by open orders
+    #[serde(default)]
+    pub hold: Option<Amount>,
+}
+
+impl Account {
+    pub fn available_f64(&self) -> f64 {
+        self.available_balance.value_f64()
+    }
+
+    pub fn hold_f64(&self) -> f64 {
+        self.hold
+            .as_ref()
+            .map(Amount::value_f64)
+            .unwrap_or_default()
+    }
+}
+
+/// One page of `/api/v3/brokerage/accounts`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountsPage {
+    #[serde(default)]
+    pub accounts: Vec<Account>,
+    #[serde(default)]
+    pub has_next: bool,
+    #[serde(default)]
+    pub cursor: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_body_and_response() {
+        let order = OrderRequest::limit(OrderSide::Buy, "BTC-USD", 0.5, 30000.0)
+            .with_client_order_id("abc-1");
+        assert_eq!(
+            serde_json::to_string(&order).unwrap(),
+            r#"{"client_order_id":"abc-1","product_id":"BTC-USD","side":"BUY","order_configuration":{"limit_limit_gtc":{"base_size":"0.5","limit_price":"30000","post_only":false}}}"#
+        );
+
+        let ok: CreateOrderResponse = serde_json::from_str(
+            r#"{"success":true,"success_response":{"order_id":"11111-00000","product_id":"BTC-USD","client_order_id":"abc-1"}}"#,
+        )
+        .unwrap();
+        assert_eq!(ok.into_result().unwrap().order_id, "11111-00000");
+
+        let refused: CreateOrderResponse = serde_json::from_str(
+            r#"{"success":false,"error_response":{"error":"INSUFFICIENT_FUND","message":"Insufficient balance in source account"}}"#,
+        )
+        .unwrap();
+        assert!(refused
+            .into_result()
+            .unwrap_err()
+            .to_string()
+            .contains("INSUFFICIENT_FUND"));
+    }
+
+    #[test]
+    fn test_candle_from_raw() {
+        let raw: RawCandle = serde_json::from_str(
+            r#"{"start":"1700000000","low":"36500.1","high":"36900","open":"36600","close":"36800.5","volume":"12.5"}"#,
+        )
+        .unwrap();
+        let candle = CoinbaseCandle::from_raw(&raw).unwrap();
+        assert_eq!(candle.start, 1700000000);
+        assert_eq!(candle.close, 36800.5);
+        assert!(CoinbaseCandle::from_raw(&RawCandle {
+            volume: "n/a".into(),
+            ..raw
+        })
+        .is_none());
+    }
+}
@@ -5,6 +5,8 @@
 
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -92,6 +94,85 @@ impl Credentials {
     }
 }
 
+/// Offsets below this are within the 1s resolution of the `Date` header
+const MIN_SKEW_MS: i64 = 1_000;
+
+/// Offset of the local clock from CoinDCX's, applied to signed timestamps
+///
+/// CoinDCX rejects requests whose `timestamp` is too far from its own time,
+/// so a drifted machine clock turns every signed call into a signature error.
+/// The offset is measured from the `Date` header of an API response; clones
+/// share it.
+#[derive(Debug, Clone, Default)]
+pub struct ClockSync {
+    offset_ms: Arc<AtomicI64>,
+    /// Local time of the last sync attempt (0 = never)
+    synced_at_ms: Arc<AtomicI64>,
+}
+
+impl ClockSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Server time minus local time, in milliseconds
+    pub fn offset_ms(&self) -> i64 {
+        self.offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// Current time on the exchange's clock, in milliseconds
+    pub fn now_ms(&self) -> i64 {
+        chrono::Utc::now().timestamp_millis() + self.offset_ms()
+    }
+
+    /// Record a server time observed between `sent_ms` and `received_ms`
+    /// (local clock) and return the new offset
+    ///
+    /// The server stamped the response somewhere in that window, so its
+    /// midpoint is the best local estimate.
+    pub fn record(&self, server_ms: i64, sent_ms: i64, received_ms: i64) -> i64 {
+        let local = sent_ms + (received_ms - sent_ms) / 2;
+        let mut offset = server_ms - local;
+        if offset.abs() < MIN_SKEW_MS {
+            offset = 0;
+        }
+        self.offset_ms.store(offset, Ordering::Relaxed);
+        self.mark_attempted();
+        offset
+    }
+
+    /// Note a sync attempt that produced no server time
+    pub fn mark_attempted(&self) {
+        self.synced_at_ms
+            .store(chrono::Utc::now().timestamp_millis(), Ordering::Relaxed);
+    }
+
+    /// Force a re-sync before the next signed request
+    pub fn invalidate(&self) {
+        self.synced_at_ms.store(0, Ordering::Relaxed);
+    }
+
+    /// Whether the last sync attempt is older than `interval_ms`
+    pub fn is_stale(&self, interval_ms: i64) -> bool {
+        let synced = self.synced_at_ms.load(Ordering::Relaxed);
+        synced == 0 || chrono::Utc::now().timestamp_millis() - synced >= interval_ms
+    }
+
+    /// Overwrite a JSON body's `timestamp` field with the exchange's time
+    pub fn stamp(&self, body: &mut serde_json::Value) {
+        if let Some(timestamp) = body.get_mut("timestamp") {
+            *timestamp = self.now_ms().into();
+        }
+    }
+}
+
+/// Milliseconds since the epoch of an HTTP `Date` header
+pub fn parse_http_date(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|t| t.timestamp_millis())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,6 +266,28 @@ mod tests {
         assert_eq!(sig1, sig2);
     }
 
+    #[test]
+    fn test_clock_sync_offset_and_stamp() {
+        let clock = ClockSync::new();
+        assert!(clock.is_stale(60_000));
+
+        let server = parse_http_date("Sun, 19 Nov 2023 10:00:05 GMT").unwrap();
+        let sent = server - 5_200;
+        // 400ms round trip: server stamped the midpoint 5s ahead of us
+        assert_eq!(clock.record(server, sent, sent + 400), 5_000);
+        assert!(!clock.is_stale(60_000));
+
+        let mut body = serde_json::json!({ "timestamp": 0, "market": "BTCINR" });
+        clock.stamp(&mut body);
+        let stamped = body["timestamp"].as_i64().unwrap();
+        assert!((stamped - chrono::Utc::now().timestamp_millis() - 5_000).abs() < 1_000);
+
+        // Sub-second differences are below the header's resolution
+        assert_eq!(clock.record(server, server - 300, server - 100), 0);
+        clock.invalidate();
+        assert!(clock.is_stale(60_000));
+    }
+
     #[test]
     fn test_constant_time_eq() {
         assert!(constant_time_eq(b"hello", b"hello"));
@@ -2,7 +2,7 @@
 //!
 //! A production-grade HTTP client for the CoinDCX exchange with:
 //! - Automatic retry with exponential backoff
-//! - Rate limiting
+//! - Rate limiting with priority-based shedding (order calls first)
 //! - Circuit breaker pattern for fault tolerance
 //! - Comprehensive error handling
 //!
@@ -30,72 +30,117 @@
 //! }
 //! ```
 
-use anyhow::{anyhow, Context, Result};
-use reqwest::Client;
+use anyhow::{Context, Result};
+use reqwest::header::HeaderMap;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tokio::time::sleep;
 
-use super::auth::{sign_request, Credentials};
+use super::auth::{parse_http_date, sign_request, ClockSync, Credentials};
+use super::error::{CoinDCXError, CoinDCXResult};
+use super::markets::MarketCache;
 use super::types::*;
-use crate::common::{CircuitBreaker, CircuitBreakerConfig, RateLimiter, RateLimiterConfig};
+use crate::common::{
+    CircuitBreaker, CircuitBreakerConfig, LatencyHistogram, LatencyMetrics, Middleware,
+    NetworkConfig, PoolConfig, RateLimiter, RateLimiterConfig, RequestInfo, RequestPriority,
+    ResponseInfo, RetryPolicy,
+};
+use crate::symbols::{SymbolMapper, Venue};
 
 /// Base URL for CoinDCX API
 pub const API_BASE_URL: &str = "https://api.coindcx.com";
 
+/// Trades per trade-history request (the API maximum)
+pub const TRADE_HISTORY_PAGE: u32 = 5000;
+
 /// Base URL for public market data endpoints
 pub const PUBLIC_BASE_URL: &str = "https://public.coindcx.com";
 
-/// Convert simple symbol (e.g., "BTCINR") to CoinDCX pair format (e.g., "I-BTC_INR")
-///
-/// CoinDCX uses different formats:
-/// - INR markets: I-BTC_INR (from BTCINR)
-/// - USDT markets: B-BTC_USDT (from BTCUSDT)
-pub fn symbol_to_pair(symbol: &str) -> String {
-    let symbol = symbol.to_uppercase();
+/// First wait between [`CoinDCXClient::wait_for_order`] polls
+pub const ORDER_POLL_START: Duration = Duration::from_millis(250);
 
-    // Handle INR pairs (e.g., BTCINR -> I-BTC_INR)
-    if symbol.ends_with("INR") {
-        let base = &symbol[..symbol.len() - 3];
-        return format!("I-{}_INR", base);
-    }
+/// Longest wait between [`CoinDCXClient::wait_for_order`] polls
+pub const ORDER_POLL_MAX: Duration = Duration::from_secs(5);
 
-    // Handle USDT pairs (e.g., BTCUSDT -> B-BTC_USDT)
-    if symbol.ends_with("USDT") {
-        let base = &symbol[..symbol.len() - 4];
-        return format!("B-{}_USDT", base);
-    }
+/// Convert a config symbol to a CoinDCX pair (`BTCINR` -> `I-BTC_INR`,
+/// `BTCUSDT` -> `B-BTC_USDT`); see [`crate::symbols::coindcx_pair`]
+pub fn symbol_to_pair(symbol: &str) -> String {
+    crate::symbols::coindcx_pair(symbol)
+}
 
-    // If already in pair format or unknown, return as-is
-    if symbol.contains('-') && symbol.contains('_') {
-        return symbol;
-    }
+/// Whether sending `op` twice has the same effect as sending it once
+///
+/// Reads, cancels and leverage changes are; anything that creates an order,
+/// position exit, transfer or earn subscription/redemption is not.
+pub fn is_idempotent(op: &str) -> bool {
+    !matches!(
+        op,
+        "place_order"
+            | "place_orders"
+            | "place_margin_order"
+            | "place_futures_order"
+            | "exit_margin_order"
+            | "exit_futures_position"
+            | "transfer"
+            | "earn_subscribe"
+            | "earn_redeem"
+    )
+}
 
-    // Default: return as-is
-    symbol
+/// An injected fault, shaped like the client's own errors
+#[cfg(feature = "fault-injection")]
+fn injected<T: serde::de::DeserializeOwned>(fault: crate::common::Fault) -> CoinDCXResult<T> {
+    use crate::common::Fault;
+    match fault {
+        Fault::RateLimited => Err(CoinDCXError::RateLimited),
+        Fault::ServerError(status) => Err(CoinDCXError::from_response(status, "injected fault")),
+        Fault::Timeout => Err(CoinDCXError::Http {
+            status: None,
+            message: "operation timed out (injected fault)".to_string(),
+        }),
+        Fault::Respond(body) => Ok(serde_json::from_value(body)?),
+    }
 }
 
 /// Client configuration
 #[derive(Debug, Clone)]
 pub struct ClientConfig {
-    /// Maximum number of retry attempts
-    pub max_retries: u32,
+    /// Backoff, retry count and budget for failed requests
+    pub retry: RetryPolicy,
     /// Request timeout duration
     pub timeout: Duration,
     /// Rate limiter configuration
     pub rate_limiter: RateLimiterConfig,
     /// Circuit breaker configuration
     pub circuit_breaker: CircuitBreakerConfig,
+    /// How often signed requests re-measure the server clock offset
+    /// (`None` signs with the local clock as-is)
+    pub clock_sync_interval: Option<Duration>,
+    /// Hooks observing every request and response
+    pub middleware: Middleware,
+    /// Connection pool, keep-alive and DNS cache settings
+    pub pool: PoolConfig,
+    /// Proxy, extra root certificates and connect timeout
+    pub network: NetworkConfig,
+    /// Venue identifiers of config symbols, with overrides
+    pub symbols: SymbolMapper,
 }
 
 impl Default for ClientConfig {
     fn default() -> Self {
         Self {
-            max_retries: 3,
+            retry: RetryPolicy::default(),
             timeout: Duration::from_secs(30),
             rate_limiter: RateLimiterConfig::default(),
             circuit_breaker: CircuitBreakerConfig::default(),
+            clock_sync_interval: Some(Duration::from_secs(600)),
+            middleware: Middleware::default(),
+            pool: PoolConfig::default(),
+            network: NetworkConfig::default(),
+            symbols: SymbolMapper::default(),
         }
     }
 }
@@ -103,7 +148,13 @@ impl Default for ClientConfig {
 impl ClientConfig {
     /// Set maximum retry attempts
     pub fn with_max_retries(mut self, retries: u32) -> Self {
-        self.max_retries = retries;
+        self.retry = self.retry.with_max_retries(retries);
+        self
+    }
+
+    /// Replace the retry policy
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
         self
     }
 
@@ -113,17 +164,89 @@ impl ClientConfig {
         self
     }
 
+    /// Set proxy, extra root certificates and connect timeout
+    pub fn with_network(mut self, network: NetworkConfig) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Map symbols through `symbols`, e.g. for listings with overridden IDs
+    pub fn with_symbols(mut self, symbols: SymbolMapper) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
     /// Set rate limit (requests per second)
     pub fn with_rate_limit(mut self, requests_per_second: usize) -> Self {
         self.rate_limiter = self.rate_limiter.with_rate(requests_per_second);
         self
     }
 
+    /// Charge `weight` rate-limit tokens per `endpoint` request (e.g. `"place_order"`)
+    pub fn with_endpoint_weight(mut self, endpoint: &str, weight: usize) -> Self {
+        self.rate_limiter = self.rate_limiter.with_endpoint_weight(endpoint, weight);
+        self
+    }
+
     /// Set circuit breaker failure threshold
     pub fn with_circuit_breaker_threshold(mut self, threshold: u32) -> Self {
         self.circuit_breaker = self.circuit_breaker.with_failure_threshold(threshold);
         self
     }
+
+    /// Set how often the server clock offset is re-measured
+    pub fn with_clock_sync_interval(mut self, interval: Duration) -> Self {
+        self.clock_sync_interval = Some(interval);
+        self
+    }
+
+    /// Sign requests with the local clock, never querying server time
+    pub fn without_clock_sync(mut self) -> Self {
+        self.clock_sync_interval = None;
+        self
+    }
+
+    /// Call `hook` before every request is sent (signed ones included)
+    pub fn with_request_hook(
+        mut self,
+        hook: impl Fn(&RequestInfo) + Send + Sync + 'static,
+    ) -> Self {
+        self.middleware = self.middleware.on_request(hook);
+        self
+    }
+
+    /// Call `hook` with every response, or the transport error if none arrived
+    pub fn with_response_hook(
+        mut self,
+        hook: impl Fn(&ResponseInfo) + Send + Sync + 'static,
+    ) -> Self {
+        self.middleware = self.middleware.on_response(hook);
+        self
+    }
+
+    /// Keep up to `idle` connections per host open between requests
+    pub fn with_pool_size(mut self, idle: usize) -> Self {
+        self.pool.max_idle_per_host = idle;
+        self
+    }
+
+    /// Close pooled connections after `timeout` without use
+    pub fn with_pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Send TCP keep-alive probes every `interval` (`None` disables them)
+    pub fn with_tcp_keepalive(mut self, interval: Option<Duration>) -> Self {
+        self.pool.tcp_keepalive = interval;
+        self
+    }
+
+    /// Cache resolved exchange addresses for `ttl`
+    pub fn with_dns_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.pool.dns_cache_ttl = Some(ttl);
+        self
+    }
 }
 
 /// CoinDCX Exchange API Client
@@ -137,7 +260,21 @@ pub struct CoinDCXClient {
     http_client: Client,
     circuit_breaker: Arc<Mutex<CircuitBreaker>>,
     rate_limiter: RateLimiter,
-    max_retries: u32,
+    retry: RetryPolicy,
+    /// Server clock offset applied to signed timestamps
+    clock: ClockSync,
+    clock_sync_interval: Option<Duration>,
+    middleware: Middleware,
+    /// Latency of every request, by endpoint path
+    latency: LatencyMetrics,
+    /// Market limits orders are validated against (empty until `load_markets`)
+    markets: MarketCache,
+    /// Leverage of margin orders when [`ExchangeClient`](crate::exchange::ExchangeClient)
+    /// orders are routed through margin (None = spot)
+    margin_leverage: Option<f64>,
+    symbols: SymbolMapper,
+    #[cfg(feature = "fault-injection")]
+    faults: crate::common::FaultInjector,
 }
 
 impl CoinDCXClient {
@@ -152,22 +289,69 @@ impl CoinDCXClient {
         api_secret: impl Into<String>,
         config: ClientConfig,
     ) -> Self {
-        let http_client = Client::builder()
-            .timeout(config.timeout)
-            .pool_max_idle_per_host(10)
-            .pool_idle_timeout(Duration::from_secs(90))
-            .build()
-            .expect("Failed to build HTTP client");
+        let http_client = config
+            .pool
+            .build_client(config.timeout, &config.network)
+            .expect("invalid network settings (see NetworkConfig::validate)");
 
         Self {
             credentials: Credentials::new(api_key, api_secret),
             http_client,
             circuit_breaker: Arc::new(Mutex::new(CircuitBreaker::new(config.circuit_breaker))),
             rate_limiter: RateLimiter::new(config.rate_limiter),
-            max_retries: config.max_retries,
+            retry: config.retry,
+            clock: ClockSync::new(),
+            clock_sync_interval: config.clock_sync_interval,
+            middleware: config.middleware,
+            latency: LatencyMetrics::default(),
+            markets: MarketCache::default(),
+            margin_leverage: None,
+            symbols: config.symbols,
+            #[cfg(feature = "fault-injection")]
+            faults: crate::common::FaultInjector::default(),
         }
     }
 
+    /// Symbol mapping used for every venue identifier
+    pub fn symbol_mapper(&self) -> &SymbolMapper {
+        &self.symbols
+    }
+
+    /// Route [`ExchangeClient`](crate::exchange::ExchangeClient) orders through
+    /// margin at `leverage` instead of spot
+    pub fn with_margin(mut self, leverage: f64) -> Self {
+        self.margin_leverage = Some(leverage);
+        self
+    }
+
+    /// Share another client's market cache (e.g. one per API key on the same exchange)
+    pub fn with_markets(mut self, markets: MarketCache) -> Self {
+        self.markets = markets;
+        self
+    }
+
+    /// The cached market details, shared by clones of this client
+    pub fn markets(&self) -> &MarketCache {
+        &self.markets
+    }
+
+    /// Leverage of routed margin orders, if orders go through margin
+    pub fn margin_leverage(&self) -> Option<f64> {
+        self.margin_leverage
+    }
+
+    /// Replace attempts with queued faults (see [`crate::common::faults`])
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: crate::common::FaultInjector) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    /// Credentials the client signs requests with
+    pub fn credentials(&self) -> &Credentials {
+        &self.credentials
+    }
+
     /// Create a client from environment variables
     ///
     /// Expects `COINDCX_API_KEY` and `COINDCX_API_SECRET`
@@ -182,202 +366,350 @@ impl CoinDCXClient {
     }
 
     /// Execute a request with retry logic, rate limiting, and circuit breaker
-    async fn execute_with_retry<F, Fut, T>(&self, operation: F) -> Result<T>
+    ///
+    /// `Low`/`Normal` requests fail with [`CoinDCXError::RateLimited`] instead
+    /// of waiting when the rate limit is under pressure. Errors that aren't
+    /// [retryable](CoinDCXError::is_retryable) are returned at once, and
+    /// [non-idempotent](is_idempotent) operations are only retried after a
+    /// rate limit unless the [`RetryPolicy`] allows more.
+    async fn execute_with_retry<F, Fut, T>(
+        &self,
+        op: &str,
+        priority: RequestPriority,
+        operation: F,
+    ) -> CoinDCXResult<T>
     where
         F: Fn() -> Fut,
-        Fut: std::future::Future<Output = Result<T>>,
+        Fut: std::future::Future<Output = CoinDCXResult<T>>,
+        T: serde::de::DeserializeOwned,
     {
         // Check circuit breaker
         {
             let mut cb = self.circuit_breaker.lock().await;
             if !cb.can_attempt() {
-                return Err(anyhow!("Circuit breaker is open, rejecting request"));
+                return Err(CoinDCXError::CircuitBreakerOpen);
             }
         }
 
-        // Rate limiting
-        self.rate_limiter.acquire().await;
-
-        let mut last_error = None;
-
-        for attempt in 0..=self.max_retries {
-            if attempt > 0 {
-                // Exponential backoff: 1s, 2s, 4s, 8s...
-                let delay = Duration::from_secs(2u64.pow(attempt - 1));
-                tracing::debug!("Retrying after {}ms", delay.as_millis());
-                sleep(delay).await;
-            }
-
-            match operation().await {
+        // Rate limiting; low-priority calls are shed under pressure
+        self.rate_limiter
+            .acquire_for(op, priority)
+            .await
+            .map_err(|_| CoinDCXError::RateLimited)?;
+
+        let retry_any = self.retry.retry_non_idempotent || is_idempotent(op);
+        let mut spent = Duration::ZERO;
+        let mut attempt = 0;
+
+        let last_error = loop {
+            #[cfg(feature = "fault-injection")]
+            let outcome = match self.faults.take(op) {
+                Some(fault) => injected(fault),
+                None => operation().await,
+            };
+            #[cfg(not(feature = "fault-injection"))]
+            let outcome = operation().await;
+
+            match outcome {
                 Ok(result) => {
                     // Record success in circuit breaker
                     let mut cb = self.circuit_breaker.lock().await;
                     cb.record_success();
                     return Ok(result);
                 }
+                Err(e) if !e.is_retryable() => {
+                    tracing::warn!("{} failed: {}", op, e);
+                    return Err(e);
+                }
                 Err(e) => {
+                    attempt += 1;
                     tracing::warn!(
-                        "Request failed (attempt {}/{}): {}",
-                        attempt + 1,
-                        self.max_retries + 1,
+                        "{} failed (attempt {}/{}): {}",
+                        op,
+                        attempt,
+                        self.retry.max_retries + 1,
                         e
                     );
-                    last_error = Some(e);
+                    // May have gone through: don't risk sending it twice
+                    if !retry_any && !matches!(e, CoinDCXError::RateLimited) {
+                        break e;
+                    }
+                    let Some(delay) = self.retry.next_delay(attempt, spent) else {
+                        break e;
+                    };
+                    tracing::debug!("Retrying after {}ms", delay.as_millis());
+                    sleep(delay).await;
+                    spent += delay;
+                    // A retry is another request: it waits out any pause the
+                    // exchange asked for (Retry-After, reported usage) like any other
+                    if self.rate_limiter.acquire_for(op, priority).await.is_err() {
+                        break e;
+                    }
                 }
             }
-        }
+        };
 
-        // All retries exhausted, record failure
+        // Retries exhausted or not allowed, record failure
         {
             let mut cb = self.circuit_breaker.lock().await;
             cb.record_failure();
         }
 
-        Err(last_error.unwrap_or_else(|| anyhow!("Request failed after retries")))
+        Err(last_error)
+    }
+
+    /// Send a request, running the middleware hooks and rate-limit bookkeeping
+    ///
+    /// Returns the status, headers and body of any response, success or not;
+    /// only transport failures are errors.
+    async fn send_raw(
+        &self,
+        request: RequestBuilder,
+    ) -> CoinDCXResult<(StatusCode, HeaderMap, String)> {
+        let request = request.build()?;
+        let method = request.method().as_str().to_string();
+        let url = request.url().to_string();
+        let endpoint = request.url().path().to_string();
+        let body = request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .and_then(|b| std::str::from_utf8(b).ok())
+            .map(str::to_string);
+        if !self.middleware.is_empty() {
+            self.middleware.request(&RequestInfo {
+                method: &method,
+                url: &url,
+                body: body.as_deref(),
+            });
+        }
+
+        let started = Instant::now();
+        let result = async {
+            let response = self.http_client.execute(request).await?;
+            self.rate_limiter
+                .observe_response(response.status(), response.headers())
+                .await;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let text = response.text().await?;
+            Ok::<_, reqwest::Error>((status, headers, text))
+        }
+        .await;
+
+        let elapsed = started.elapsed();
+        let success = matches!(&result, Ok((status, _, _)) if status.is_success());
+        self.latency.record(&endpoint, elapsed, success);
+
+        if !self.middleware.is_empty() {
+            let error = result.as_ref().err().map(|e| e.to_string());
+            let (status, body) = match &result {
+                Ok((status, _, text)) => (Some(status.as_u16()), text.as_str()),
+                Err(_) => (None, error.as_deref().unwrap_or_default()),
+            };
+            self.middleware.response(&ResponseInfo {
+                method: &method,
+                url: &url,
+                status,
+                body,
+                elapsed,
+            });
+        }
+        Ok(result?)
+    }
+
+    /// Send a request and parse a successful response as `R`
+    async fn send<R: serde::de::DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> CoinDCXResult<R> {
+        let (status, _, text) = self.send_raw(request).await?;
+        if !status.is_success() {
+            return Err(CoinDCXError::from_response(status.as_u16(), &text));
+        }
+        Ok(serde_json::from_str(&text)?)
     }
 
     /// Make an authenticated POST request
-    async fn authenticated_post<T, R>(&self, endpoint: &str, body: &T) -> Result<R>
+    async fn authenticated_post<T, R>(&self, endpoint: &str, body: &T) -> CoinDCXResult<R>
     where
         T: serde::Serialize,
         R: serde::de::DeserializeOwned,
     {
         let url = format!("{}{}", API_BASE_URL, endpoint);
-        let json_body = serde_json::to_string(body)?;
+        // Stamped at send time, so retries and skewed clocks stay in the window
+        self.sync_clock_if_stale().await;
+        let mut body = serde_json::to_value(body)?;
+        self.clock.stamp(&mut body);
+        let json_body = body.to_string();
         let signature = sign_request(&json_body, self.credentials.api_secret());
 
-        let response = self
+        let request = self
             .http_client
             .post(&url)
             .header("Content-Type", "application/json")
             .header("X-AUTH-APIKEY", self.credentials.api_key())
             .header("X-AUTH-SIGNATURE", signature)
-            .body(json_body)
-            .send()
-            .await
-            .context("Failed to send request")?;
+            .body(json_body);
+        let result = self.send(request).await;
+        if let Err(CoinDCXError::InvalidSignature(_)) = &result {
+            // Possibly a clock jump since the last sync: re-measure before the next call
+            self.clock.invalidate();
+        }
+        result
+    }
 
-        let status = response.status();
-        let text = response.text().await.context("Failed to read response")?;
+    /// Measure the offset of the local clock from CoinDCX's, in milliseconds
+    ///
+    /// Signed requests call this on their own every `clock_sync_interval`.
+    pub async fn sync_clock(&self) -> CoinDCXResult<i64> {
+        self.rate_limiter
+            .acquire_with_priority(RequestPriority::High)
+            .await
+            .map_err(|_| CoinDCXError::RateLimited)?;
+        let sent = chrono::Utc::now().timestamp_millis();
+        let (_, headers, _) = self.send_raw(self.http_client.head(API_BASE_URL)).await?;
+        let received = chrono::Utc::now().timestamp_millis();
+
+        let server = headers
+            .get(reqwest::header::DATE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_http_date);
+        let Some(server) = server else {
+            self.clock.mark_attempted();
+            return Err(CoinDCXError::Decode(
+                "no Date header in server time response".to_string(),
+            ));
+        };
+        let offset = self.clock.record(server, sent, received);
+        if offset != 0 {
+            tracing::warn!(
+                "Local clock is {}ms {} CoinDCX; adjusting signed timestamps",
+                offset.abs(),
+                if offset > 0 { "behind" } else { "ahead of" }
+            );
+        }
+        Ok(offset)
+    }
 
-        if !status.is_success() {
-            return Err(anyhow!("API error ({}): {}", status, text));
+    async fn sync_clock_if_stale(&self) {
+        let Some(interval) = self.clock_sync_interval else {
+            return;
+        };
+        if self.clock.is_stale(interval.as_millis() as i64) {
+            if let Err(e) = self.sync_clock().await {
+                self.clock.mark_attempted();
+                tracing::warn!("Server time sync failed: {}", e);
+            }
         }
+    }
 
-        serde_json::from_str(&text).context("Failed to parse response")
+    /// Server time minus local time applied to signed requests, in milliseconds
+    pub fn clock_offset_ms(&self) -> i64 {
+        self.clock.offset_ms()
     }
 
     // ==================== PUBLIC ENDPOINTS ====================
 
     /// Get ticker information for all markets
-    pub async fn get_all_tickers(&self) -> Result<Vec<Ticker>> {
-        self.execute_with_retry(|| {
+    pub async fn get_all_tickers(&self) -> CoinDCXResult<Vec<Ticker>> {
+        self.execute_with_retry("tickers", RequestPriority::Low, || {
             let url = format!("{}/exchange/ticker", API_BASE_URL);
-            let client = self.http_client.clone();
-
-            async move {
-                let response = client
-                    .get(&url)
-                    .send()
-                    .await
-                    .context("Failed to fetch tickers")?;
+            let this = self.clone();
 
-                let text = response.text().await.context("Failed to read response")?;
-                serde_json::from_str(&text).context("Failed to parse tickers")
-            }
+            async move { this.send(this.http_client.get(&url)).await }
         })
         .await
     }
 
     /// Get ticker information for a specific market
-    pub async fn get_ticker(&self, symbol: &str) -> Result<Ticker> {
+    pub async fn get_ticker(&self, symbol: &str) -> CoinDCXResult<Ticker> {
+        self.get_ticker_with_priority(symbol, RequestPriority::Low)
+            .await
+    }
+
+    /// Get ticker information at a given priority (e.g. `High` for a quote before an order)
+    pub async fn get_ticker_with_priority(
+        &self,
+        symbol: &str,
+        priority: RequestPriority,
+    ) -> CoinDCXResult<Ticker> {
         let symbol = symbol.to_string();
-        self.execute_with_retry(|| {
+        self.execute_with_retry("ticker", priority, || {
             let url = format!("{}/exchange/ticker", API_BASE_URL);
-            let client = self.http_client.clone();
+            let this = self.clone();
             let symbol = symbol.clone();
 
             async move {
-                let response = client
-                    .get(&url)
-                    .send()
-                    .await
-                    .context("Failed to fetch ticker")?;
-
-                let text = response.text().await.context("Failed to read response")?;
-                let tickers: Vec<Ticker> =
-                    serde_json::from_str(&text).context("Failed to parse ticker JSON")?;
+                let tickers: Vec<Ticker> = this.send(this.http_client.get(&url)).await?;
 
                 tickers
                     .into_iter()
                     .find(|t| t.market == symbol)
-                    .ok_or_else(|| anyhow!("Ticker not found for {}", symbol))
+                    .ok_or_else(|| CoinDCXError::NotFound(format!("ticker for {}", symbol)))
             }
         })
         .await
     }
 
     /// Get list of all available markets
-    pub async fn get_markets(&self) -> Result<Vec<String>> {
-        self.execute_with_retry(|| {
+    pub async fn get_markets(&self) -> CoinDCXResult<Vec<String>> {
+        self.execute_with_retry("markets", RequestPriority::Low, || {
             let url = format!("{}/exchange/v1/markets", API_BASE_URL);
-            let client = self.http_client.clone();
-
-            async move {
-                let response = client
-                    .get(&url)
-                    .send()
-                    .await
-                    .context("Failed to fetch markets")?;
+            let this = self.clone();
 
-                let text = response.text().await.context("Failed to read response")?;
-                serde_json::from_str(&text).context("Failed to parse markets")
-            }
+            async move { this.send(this.http_client.get(&url)).await }
         })
         .await
     }
 
     /// Get detailed information for all markets
-    pub async fn get_markets_details(&self) -> Result<Vec<MarketDetails>> {
-        self.execute_with_retry(|| {
+    pub async fn get_markets_details(&self) -> CoinDCXResult<Vec<MarketDetails>> {
+        self.execute_with_retry("markets_details", RequestPriority::Low, || {
             let url = format!("{}/exchange/v1/markets_details", API_BASE_URL);
-            let client = self.http_client.clone();
-
-            async move {
-                let response = client
-                    .get(&url)
-                    .send()
-                    .await
-                    .context("Failed to fetch market details")?;
+            let this = self.clone();
 
-                let text = response.text().await.context("Failed to read response")?;
-                serde_json::from_str(&text).context("Failed to parse market details")
-            }
+            async move { this.send(this.http_client.get(&url)).await }
         })
         .await
     }
 
+    /// Fetch every market's details and validate later orders against them
+    ///
+    /// Returns the number of markets cached.
+    pub async fn load_markets(&self) -> CoinDCXResult<usize> {
+        let details = self.get_markets_details().await?;
+        let count = details.len();
+        self.markets.load(details);
+        Ok(count)
+    }
+
+    /// Cached details of a market (after [`load_markets`](Self::load_markets))
+    pub fn market_details(&self, market: &str) -> Option<MarketDetails> {
+        self.markets.get(market)
+    }
+
     /// Get order book for a market pair
-    pub async fn get_orderbook(&self, pair: &str) -> Result<OrderBook> {
+    pub async fn get_orderbook(&self, pair: &str) -> CoinDCXResult<OrderBook> {
+        self.get_orderbook_with_priority(pair, RequestPriority::High)
+            .await
+    }
+
+    /// Get order book at a given priority (e.g. `Low` for background recording)
+    pub async fn get_orderbook_with_priority(
+        &self,
+        pair: &str,
+        priority: RequestPriority,
+    ) -> CoinDCXResult<OrderBook> {
         let pair = pair.to_string();
-        self.execute_with_retry(|| {
+        self.execute_with_retry("orderbook", priority, || {
             let url = format!(
                 "{}/market_data/orderbook?pair={}",
                 PUBLIC_BASE_URL,
                 pair.clone()
             );
-            let client = self.http_client.clone();
-
-            async move {
-                let response = client
-                    .get(&url)
-                    .send()
-                    .await
-                    .context("Failed to fetch orderbook")?;
+            let this = self.clone();
 
-                let text = response.text().await.context("Failed to read response")?;
-                serde_json::from_str(&text).context("Failed to parse orderbook")
-            }
+            async move { this.send(this.http_client.get(&url)).await }
         })
         .await
     }
@@ -393,11 +725,11 @@ impl CoinDCXClient {
         symbol: &str,
         interval: &str,
         limit: Option<u32>,
-    ) -> Result<Vec<Candle>> {
+    ) -> CoinDCXResult<Vec<Candle>> {
         // Auto-convert symbol to CoinDCX pair format
-        let pair = symbol_to_pair(symbol);
+        let pair = self.symbols.id(symbol, Venue::CoinDCX);
         let interval = interval.to_string();
-        self.execute_with_retry(|| {
+        self.execute_with_retry("candles", RequestPriority::Normal, || {
             let mut url = format!(
                 "{}/market_data/candles?pair={}&interval={}",
                 PUBLIC_BASE_URL,
@@ -407,18 +739,9 @@ impl CoinDCXClient {
             if let Some(l) = limit {
                 url.push_str(&format!("&limit={}", l));
             }
-            let client = self.http_client.clone();
-
-            async move {
-                let response = client
-                    .get(&url)
-                    .send()
-                    .await
-                    .context("Failed to fetch candles")?;
+            let this = self.clone();
 
-                let text = response.text().await.context("Failed to read response")?;
-                serde_json::from_str(&text).context("Failed to parse candles")
-            }
+            async move { this.send(this.http_client.get(&url)).await }
         })
         .await
     }
@@ -426,9 +749,9 @@ impl CoinDCXClient {
     // ==================== AUTHENTICATED ENDPOINTS ====================
 
     /// Get user balances
-    pub async fn get_balances(&self) -> Result<Vec<Balance>> {
+    pub async fn get_balances(&self) -> CoinDCXResult<Vec<Balance>> {
         let request = TimestampRequest::new();
-        self.execute_with_retry(|| {
+        self.execute_with_retry("balances", RequestPriority::Normal, || {
             let endpoint = "/exchange/v1/users/balances";
             let req = request.clone();
             let this = self.clone();
@@ -439,9 +762,9 @@ impl CoinDCXClient {
     }
 
     /// Get user info
-    pub async fn get_user_info(&self) -> Result<Vec<UserInfo>> {
+    pub async fn get_user_info(&self) -> CoinDCXResult<Vec<UserInfo>> {
         let request = TimestampRequest::new();
-        self.execute_with_retry(|| {
+        self.execute_with_retry("user_info", RequestPriority::Low, || {
             let endpoint = "/exchange/v1/users/info";
             let req = request.clone();
             let this = self.clone();
@@ -451,10 +774,72 @@ impl CoinDCXClient {
         .await
     }
 
+    /// Get the maker/taker fees of the account's current tier
+    pub async fn get_fee_schedule(&self) -> CoinDCXResult<FeeTier> {
+        let request = TimestampRequest::new();
+        self.execute_with_retry("fee_schedule", RequestPriority::Low, || {
+            let endpoint = "/exchange/v1/users/fees";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post(endpoint, &req).await }
+        })
+        .await
+    }
+
     /// Place a new order
-    pub async fn place_order(&self, order: &OrderRequest) -> Result<OrdersResponse> {
+    ///
+    /// Once markets are loaded, an order that breaks its market's limits
+    /// fails with [`CoinDCXError::InvalidOrder`] without being sent.
+    ///
+    /// An order with a `client_order_id` whose request may have reached the
+    /// exchange (a timeout or 5xx) is looked up by that ID before it is sent
+    /// again: if the exchange has it, that order is returned instead of
+    /// placing a second one. It is resent at most `max_retries` times.
+    pub async fn place_order(&self, order: &OrderRequest) -> CoinDCXResult<OrdersResponse> {
+        self.markets.validate(order)?;
+        let mut resends = 0;
+        loop {
+            let error = match self.send_order(order).await {
+                Ok(placed) => return Ok(placed),
+                Err(e) => e,
+            };
+            let Some(client_order_id) = order.client_order_id.as_deref() else {
+                return Err(error);
+            };
+            if !error.is_retryable() {
+                return Err(error);
+            }
+            match self.get_order_status_by_client_id(client_order_id).await {
+                Ok(existing) => {
+                    tracing::info!(
+                        "place_order: {} already on the exchange as {}, not resent",
+                        client_order_id,
+                        existing.id
+                    );
+                    return Ok(OrdersResponse {
+                        orders: vec![existing],
+                    });
+                }
+                Err(CoinDCXError::NotFound(_)) if resends < self.retry.max_retries => {
+                    resends += 1;
+                    tracing::warn!(
+                        "place_order: {} never reached the exchange, resending ({}/{})",
+                        client_order_id,
+                        resends,
+                        self.retry.max_retries
+                    );
+                }
+                // Can't tell whether it went through: don't risk a second order
+                Err(_) => return Err(error),
+            }
+        }
+    }
+
+    /// One `orders/create` request, retried only as [`is_idempotent`] allows
+    async fn send_order(&self, order: &OrderRequest) -> CoinDCXResult<OrdersResponse> {
         let order = order.clone();
-        self.execute_with_retry(|| {
+        self.execute_with_retry("place_order", RequestPriority::Critical, || {
             let endpoint = "/exchange/v1/orders/create";
             let ord = order.clone();
             let this = self.clone();
@@ -464,10 +849,65 @@ impl CoinDCXClient {
         .await
     }
 
+    /// Place several orders, [`MAX_BATCH_ORDERS`] per request
+    ///
+    /// A rejected request fails only its own chunk; every order that wasn't
+    /// accepted is reported in [`BatchOutcome::failed`] with its index.
+    /// Orders failing local validation are reported there without being sent.
+    pub async fn place_orders(&self, orders: &[OrderRequest]) -> CoinDCXResult<BatchOutcome> {
+        let mut outcome = BatchOutcome::default();
+        // Caller's index of each order that passed validation
+        let mut valid = Vec::new();
+        for (index, order) in orders.iter().enumerate() {
+            match self.markets.validate(order) {
+                Ok(()) => valid.push(index),
+                Err(e) => outcome.failed.push(BatchFailure {
+                    index,
+                    client_order_id: order.client_order_id.clone(),
+                    error: e.to_string(),
+                }),
+            }
+        }
+        let rejected = outcome.failed.len();
+        let sendable: Vec<OrderRequest> = valid.iter().map(|&i| orders[i].clone()).collect();
+
+        for (n, chunk) in sendable.chunks(MAX_BATCH_ORDERS).enumerate() {
+            let offset = n * MAX_BATCH_ORDERS;
+            let request = BatchOrderRequest {
+                orders: chunk.to_vec(),
+            };
+            let result = self
+                .execute_with_retry("place_orders", RequestPriority::Critical, || {
+                    let endpoint = "/exchange/v1/orders/create_multiple";
+                    let req = request.clone();
+                    let this = self.clone();
+
+                    async move { this.authenticated_post(endpoint, &req).await }
+                })
+                .await;
+            match result {
+                Ok(response) => outcome.record_response(offset, chunk, response),
+                Err(e) => outcome.record_error(offset, chunk, &format!("{:#}", e)),
+            }
+        }
+        // Chunk offsets count sent orders only
+        for failure in &mut outcome.failed[rejected..] {
+            failure.index = valid[failure.index];
+        }
+        if !outcome.is_complete() {
+            tracing::warn!(
+                "Batch placement: {} of {} order(s) failed",
+                outcome.failed.len(),
+                orders.len()
+            );
+        }
+        Ok(outcome)
+    }
+
     /// Cancel an order by ID
-    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+    pub async fn cancel_order(&self, order_id: &str) -> CoinDCXResult<()> {
         let request = CancelOrderRequest::new(order_id);
-        self.execute_with_retry(|| {
+        self.execute_with_retry("cancel_order", RequestPriority::Critical, || {
             let endpoint = "/exchange/v1/orders/cancel";
             let req = request.clone();
             let this = self.clone();
@@ -481,9 +921,9 @@ impl CoinDCXClient {
     }
 
     /// Get order status
-    pub async fn get_order_status(&self, order_id: &str) -> Result<OrderResponse> {
+    pub async fn get_order_status(&self, order_id: &str) -> CoinDCXResult<OrderResponse> {
         let request = OrderStatusRequest::by_id(order_id);
-        self.execute_with_retry(|| {
+        self.execute_with_retry("order_status", RequestPriority::High, || {
             let endpoint = "/exchange/v1/orders/status";
             let req = request.clone();
             let this = self.clone();
@@ -493,10 +933,62 @@ impl CoinDCXClient {
         .await
     }
 
+    /// Get order status by the client order ID it was placed with
+    pub async fn get_order_status_by_client_id(
+        &self,
+        client_order_id: &str,
+    ) -> CoinDCXResult<OrderResponse> {
+        let request = OrderStatusRequest::by_client_id(client_order_id);
+        self.execute_with_retry("order_status", RequestPriority::High, || {
+            let endpoint = "/exchange/v1/orders/status";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post(endpoint, &req).await }
+        })
+        .await
+    }
+
+    /// Poll an order until it is [final](OrderResponse::is_final) and return it
+    ///
+    /// Polls start [`ORDER_POLL_START`] apart and double up to
+    /// [`ORDER_POLL_MAX`]. Failed polls that may succeed later are tried
+    /// again; after `timeout` it fails with [`CoinDCXError::Timeout`].
+    pub async fn wait_for_order(
+        &self,
+        order_id: &str,
+        timeout: Duration,
+    ) -> CoinDCXResult<OrderResponse> {
+        let deadline = Instant::now() + timeout;
+        let mut delay = ORDER_POLL_START;
+        let mut last_status = None;
+        loop {
+            match self.get_order_status(order_id).await {
+                Ok(order) if order.is_final() => return Ok(order),
+                Ok(order) => last_status = Some(order.status),
+                Err(e) if e.is_retryable() => {
+                    tracing::debug!("wait_for_order {}: poll failed: {}", order_id, e)
+                }
+                Err(e) => return Err(e),
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(CoinDCXError::Timeout(format!(
+                    "order {} still {} after {:?}",
+                    order_id,
+                    last_status.as_deref().unwrap_or("unknown"),
+                    timeout
+                )));
+            }
+            sleep(delay.min(remaining)).await;
+            delay = (delay * 2).min(ORDER_POLL_MAX);
+        }
+    }
+
     /// Get active orders for a market
-    pub async fn get_active_orders(&self, market: &str) -> Result<Vec<OrderResponse>> {
+    pub async fn get_active_orders(&self, market: &str) -> CoinDCXResult<Vec<OrderResponse>> {
         let request = ActiveOrdersRequest::new(market);
-        self.execute_with_retry(|| {
+        self.execute_with_retry("active_orders", RequestPriority::High, || {
             let endpoint = "/exchange/v1/orders/active_orders";
             let req = request.clone();
             let this = self.clone();
@@ -507,13 +999,17 @@ impl CoinDCXClient {
     }
 
     /// Cancel all orders for a market
-    pub async fn cancel_all_orders(&self, market: &str, side: Option<OrderSide>) -> Result<()> {
+    pub async fn cancel_all_orders(
+        &self,
+        market: &str,
+        side: Option<OrderSide>,
+    ) -> CoinDCXResult<()> {
         let mut request = ActiveOrdersRequest::new(market);
         if let Some(s) = side {
             request = request.with_side(s);
         }
 
-        self.execute_with_retry(|| {
+        self.execute_with_retry("cancel_all_orders", RequestPriority::Critical, || {
             let endpoint = "/exchange/v1/orders/cancel_all";
             let req = request.clone();
             let this = self.clone();
@@ -526,22 +1022,384 @@ impl CoinDCXClient {
         .await
     }
 
-    /// Get trade history
-    pub async fn get_trade_history(&self, limit: Option<u32>) -> Result<Vec<Trade>> {
-        #[derive(serde::Serialize, Clone)]
-        struct TradeHistoryRequest {
-            timestamp: i64,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            limit: Option<u32>,
+    /// Get one page of trade history, oldest first
+    pub async fn get_trade_history_page(
+        &self,
+        request: &TradeHistoryRequest,
+    ) -> CoinDCXResult<Vec<Trade>> {
+        self.execute_with_retry("trade_history", RequestPriority::Normal, || {
+            let endpoint = "/exchange/v1/orders/trade_history";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post(endpoint, &req).await }
+        })
+        .await
+    }
+
+    /// Get every fill between `from` and `to`, for one market or all of them
+    ///
+    /// Pages through the history [`TRADE_HISTORY_PAGE`] trades at a time by
+    /// trade ID, so the result is complete however busy the window was.
+    pub async fn get_trade_history(
+        &self,
+        market: Option<&str>,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> CoinDCXResult<Vec<Trade>> {
+        let mut request = TradeHistoryRequest::new(
+            market,
+            from.timestamp_millis(),
+            to.timestamp_millis(),
+            TRADE_HISTORY_PAGE,
+        );
+        let mut trades: Vec<Trade> = Vec::new();
+        loop {
+            let page = self.get_trade_history_page(&request).await?;
+            let full = page.len() >= TRADE_HISTORY_PAGE as usize;
+            let next_id = page.iter().map(|t| t.id + 1).max();
+            trades.extend(page);
+            match next_id {
+                // A server ignoring `from_id` would return the same page forever
+                Some(id) if full && request.from_id.is_none_or(|from| id > from) => {
+                    request.from_id = Some(id);
+                }
+                _ => break,
+            }
+        }
+        trades.sort_by_key(|t| t.id);
+        trades.dedup_by_key(|t| t.id);
+        Ok(trades)
+    }
+
+    // ==================== OCO ORDERS ====================
+
+    /// Park a stop-loss and take-profit as a linked pair
+    ///
+    /// If the stop leg is rejected the take-profit leg is cancelled again, so
+    /// either both legs rest or neither does.
+    pub async fn place_oco(&self, oco: &OcoOrder) -> CoinDCXResult<OcoHandle> {
+        let first_id = |response: OrdersResponse| {
+            response
+                .orders
+                .into_iter()
+                .next()
+                .map(|o| o.id)
+                .ok_or_else(|| CoinDCXError::Decode("order accepted without an ID".to_string()))
+        };
+
+        let (mut take_profit, mut stop_loss) = (oco.take_profit_leg(), oco.stop_loss_leg());
+        self.markets.quantize(&mut take_profit);
+        self.markets.quantize(&mut stop_loss);
+
+        let take_profit_id = first_id(self.place_order(&take_profit).await?)?;
+        match self.place_order(&stop_loss).await.and_then(first_id) {
+            Ok(stop_loss_id) => Ok(OcoHandle {
+                take_profit_id,
+                stop_loss_id,
+            }),
+            Err(e) => {
+                if let Err(cancel) = self.cancel_order(&take_profit_id).await {
+                    tracing::error!(
+                        "OCO {}: stop leg failed and take-profit {} is still live: {}",
+                        oco.client_order_id,
+                        take_profit_id,
+                        cancel
+                    );
+                }
+                tracing::warn!("OCO {}: stop leg rejected", oco.client_order_id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Check a parked pair and cancel the surviving leg once the other fills
+    ///
+    /// A partially filled leg counts as filled: the sibling is cancelled so
+    /// the two can't close more than the position.
+    pub async fn sync_oco(&self, handle: &OcoHandle) -> CoinDCXResult<OcoStatus> {
+        let take_profit = self.get_order_status(&handle.take_profit_id).await?;
+        let stop_loss = self.get_order_status(&handle.stop_loss_id).await?;
+        let filled = |o: &OrderResponse| {
+            matches!(
+                o.status.as_str(),
+                "filled" | "partially_filled" | "partially_cancelled"
+            )
+        };
+        let live = |o: &OrderResponse| matches!(o.status.as_str(), "open" | "init");
+
+        if filled(&take_profit) {
+            if live(&stop_loss) {
+                self.cancel_order(&handle.stop_loss_id).await?;
+            }
+            return Ok(OcoStatus::TakeProfitFilled(take_profit));
         }
+        if filled(&stop_loss) {
+            if live(&take_profit) {
+                self.cancel_order(&handle.take_profit_id).await?;
+            }
+            return Ok(OcoStatus::StopLossFilled(stop_loss));
+        }
+        if live(&take_profit) && live(&stop_loss) {
+            return Ok(OcoStatus::Open);
+        }
+        // One leg vanished without filling: don't leave the other one orphaned
+        for (leg, id) in [
+            (&take_profit, &handle.take_profit_id),
+            (&stop_loss, &handle.stop_loss_id),
+        ] {
+            if live(leg) {
+                self.cancel_order(id).await?;
+            }
+        }
+        Ok(OcoStatus::Closed)
+    }
+
+    /// Cancel both legs of a parked pair
+    pub async fn cancel_oco(&self, handle: &OcoHandle) -> CoinDCXResult<()> {
+        let take_profit = self.cancel_order(&handle.take_profit_id).await;
+        let stop_loss = self.cancel_order(&handle.stop_loss_id).await;
+        take_profit.and(stop_loss)
+    }
+
+    // ==================== FUTURES ENDPOINTS ====================
+
+    /// List active futures pairs margined in `margin_currency` ("USDT" or "INR")
+    pub async fn get_futures_instruments(
+        &self,
+        margin_currency: &str,
+    ) -> CoinDCXResult<Vec<String>> {
+        let margin_currency = margin_currency.to_string();
+        self.execute_with_retry("futures_instruments", RequestPriority::Low, || {
+            let url = format!(
+                "{}/exchange/v1/derivatives/futures/data/active_instruments",
+                API_BASE_URL
+            );
+            let this = self.clone();
+            let margin_currency = margin_currency.clone();
+
+            async move {
+                let request = this
+                    .http_client
+                    .get(&url)
+                    .query(&[("margin_currency_short_name[]", margin_currency)]);
+                this.send(request).await
+            }
+        })
+        .await
+    }
+
+    /// Get leverage limits and step sizes of a futures pair (e.g., "B-BTC_USDT")
+    pub async fn get_futures_instrument(
+        &self,
+        pair: &str,
+        margin_currency: &str,
+    ) -> CoinDCXResult<FuturesInstrument> {
+        let query = [
+            ("pair", pair.to_string()),
+            ("margin_currency_short_name", margin_currency.to_string()),
+        ];
+        let response: FuturesInstrumentResponse = self
+            .execute_with_retry("futures_instrument", RequestPriority::Low, || {
+                let url = format!(
+                    "{}/exchange/v1/derivatives/futures/data/instrument",
+                    API_BASE_URL
+                );
+                let this = self.clone();
+                let query = query.clone();
+
+                async move { this.send(this.http_client.get(&url).query(&query)).await }
+            })
+            .await?;
+        Ok(response.instrument)
+    }
+
+    /// Get futures positions margined in `margin_currency`
+    pub async fn get_futures_positions(
+        &self,
+        margin_currency: &str,
+    ) -> CoinDCXResult<Vec<FuturesPosition>> {
+        let request = FuturesPositionsRequest::new(margin_currency);
+        self.execute_with_retry("futures_positions", RequestPriority::Normal, || {
+            let endpoint = "/exchange/v1/derivatives/futures/positions";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post(endpoint, &req).await }
+        })
+        .await
+    }
+
+    /// Set the leverage used by new orders on a futures pair
+    pub async fn update_futures_leverage(&self, pair: &str, leverage: u32) -> CoinDCXResult<()> {
+        let request = FuturesLeverageRequest::new(pair, leverage);
+        self.execute_with_retry("futures_leverage", RequestPriority::High, || {
+            let endpoint = "/exchange/v1/derivatives/futures/positions/update_leverage";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move {
+                let _: serde_json::Value = this.authenticated_post(endpoint, &req).await?;
+                Ok(())
+            }
+        })
+        .await
+    }
 
-        let request = TradeHistoryRequest {
+    /// Place a futures order; a sell with no open long opens a short
+    pub async fn place_futures_order(
+        &self,
+        order: &FuturesOrderRequest,
+    ) -> CoinDCXResult<Vec<FuturesOrderResponse>> {
+        let order = order.clone();
+        self.execute_with_retry("place_futures_order", RequestPriority::Critical, || {
+            let endpoint = "/exchange/v1/derivatives/futures/orders/create";
+            let ord = order.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post(endpoint, &ord).await }
+        })
+        .await
+    }
+
+    /// Cancel a futures order by ID
+    pub async fn cancel_futures_order(&self, order_id: &str) -> CoinDCXResult<()> {
+        let request = CancelOrderRequest::new(order_id);
+        self.execute_with_retry("cancel_futures_order", RequestPriority::Critical, || {
+            let endpoint = "/exchange/v1/derivatives/futures/orders/cancel";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move {
+                let _: serde_json::Value = this.authenticated_post(endpoint, &req).await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    /// Close a futures position at market by position ID
+    pub async fn exit_futures_position(&self, position_id: &str) -> CoinDCXResult<()> {
+        let request = CancelOrderRequest::new(position_id);
+        self.execute_with_retry("exit_futures_position", RequestPriority::Critical, || {
+            let endpoint = "/exchange/v1/derivatives/futures/positions/exit";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move {
+                let _: serde_json::Value = this.authenticated_post(endpoint, &req).await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    // ==================== MARGIN ENDPOINTS ====================
+
+    /// Place a margin order; a sell opens a short on borrowed funds
+    pub async fn place_margin_order(
+        &self,
+        order: &MarginOrderRequest,
+    ) -> CoinDCXResult<Vec<MarginOrder>> {
+        let order = order.clone();
+        self.execute_with_retry("place_margin_order", RequestPriority::Critical, || {
+            let endpoint = "/exchange/v1/margin/create";
+            let ord = order.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post(endpoint, &ord).await }
+        })
+        .await
+    }
+
+    /// Cancel an unfilled margin order
+    pub async fn cancel_margin_order(&self, order_id: &str) -> CoinDCXResult<()> {
+        let request = CancelOrderRequest::new(order_id);
+        self.execute_with_retry("cancel_margin_order", RequestPriority::Critical, || {
+            let endpoint = "/exchange/v1/margin/cancel";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move {
+                let _: serde_json::Value = this.authenticated_post(endpoint, &req).await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    /// Close a filled margin order at market
+    pub async fn exit_margin_order(&self, order_id: &str) -> CoinDCXResult<()> {
+        let request = CancelOrderRequest::new(order_id);
+        self.execute_with_retry("exit_margin_order", RequestPriority::Critical, || {
+            let endpoint = "/exchange/v1/margin/exit";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move {
+                let _: serde_json::Value = this.authenticated_post(endpoint, &req).await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    /// List margin orders, optionally for one market
+    pub async fn get_margin_orders(&self, market: Option<&str>) -> CoinDCXResult<Vec<MarginOrder>> {
+        let request = MarginOrdersRequest {
+            details: true,
+            market: market.map(str::to_string),
             timestamp: chrono::Utc::now().timestamp_millis(),
-            limit,
         };
+        self.execute_with_retry("margin_orders", RequestPriority::Normal, || {
+            let endpoint = "/exchange/v1/margin/fetch_orders";
+            let req = request.clone();
+            let this = self.clone();
 
-        self.execute_with_retry(|| {
-            let endpoint = "/exchange/v1/orders/trade_history";
+            async move { this.authenticated_post(endpoint, &req).await }
+        })
+        .await
+    }
+
+    /// Balances of the margin (derivatives) wallet
+    pub async fn get_margin_balances(&self) -> CoinDCXResult<Vec<MarginBalance>> {
+        let request = TimestampRequest::new();
+        self.execute_with_retry("margin_balances", RequestPriority::Normal, || {
+            let endpoint = "/exchange/v1/derivatives/futures/wallets";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post(endpoint, &req).await }
+        })
+        .await
+    }
+
+    /// Deposits of `currency` (all currencies when `None`) created since `from`
+    pub async fn get_deposits(
+        &self,
+        currency: Option<&str>,
+        from: chrono::DateTime<chrono::Utc>,
+    ) -> CoinDCXResult<Vec<FundsTransfer>> {
+        let request = FundsHistoryRequest::new(currency, from.timestamp_millis());
+        self.execute_with_retry("deposits", RequestPriority::Low, || {
+            let endpoint = "/exchange/v1/wallets/deposits";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post(endpoint, &req).await }
+        })
+        .await
+    }
+
+    /// Withdrawals of `currency` (all currencies when `None`) created since `from`
+    pub async fn get_withdrawals(
+        &self,
+        currency: Option<&str>,
+        from: chrono::DateTime<chrono::Utc>,
+    ) -> CoinDCXResult<Vec<FundsTransfer>> {
+        let request = FundsHistoryRequest::new(currency, from.timestamp_millis());
+        self.execute_with_retry("withdrawals", RequestPriority::Low, || {
+            let endpoint = "/exchange/v1/wallets/withdrawals";
             let req = request.clone();
             let this = self.clone();
 
@@ -550,10 +1408,92 @@ impl CoinDCXClient {
         .await
     }
 
+    /// Move `amount` of `currency` between the spot and margin wallets
+    pub async fn transfer(
+        &self,
+        currency: &str,
+        amount: f64,
+        from: WalletType,
+        to: WalletType,
+    ) -> CoinDCXResult<()> {
+        let request = TransferRequest::new(currency, amount, from, to);
+        self.execute_with_retry("transfer", RequestPriority::High, || {
+            let endpoint = "/exchange/v1/wallets/transfer";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move {
+                let _: serde_json::Value = this.authenticated_post(endpoint, &req).await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    // ==================== EARN ====================
+
+    /// Earn products open for subscription
+    pub async fn get_earn_products(&self) -> CoinDCXResult<Vec<EarnProduct>> {
+        let request = TimestampRequest::new();
+        self.execute_with_retry("earn_products", RequestPriority::Low, || {
+            let endpoint = "/exchange/v1/earn/products";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post(endpoint, &req).await }
+        })
+        .await
+    }
+
+    /// Funds currently held in earn products
+    pub async fn get_earn_balances(&self) -> CoinDCXResult<Vec<EarnBalance>> {
+        let request = TimestampRequest::new();
+        self.execute_with_retry("earn_balances", RequestPriority::Normal, || {
+            let endpoint = "/exchange/v1/earn/balances";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post(endpoint, &req).await }
+        })
+        .await
+    }
+
+    /// Move `amount` from the spot wallet into earn product `product_id`
+    pub async fn earn_subscribe(&self, product_id: &str, amount: f64) -> CoinDCXResult<()> {
+        let request = EarnRequest::new(product_id, amount);
+        self.execute_with_retry("earn_subscribe", RequestPriority::Normal, || {
+            let endpoint = "/exchange/v1/earn/subscribe";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move {
+                let _: serde_json::Value = this.authenticated_post(endpoint, &req).await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+
+    /// Move `amount` out of earn product `product_id` back to the spot wallet
+    pub async fn earn_redeem(&self, product_id: &str, amount: f64) -> CoinDCXResult<()> {
+        let request = EarnRequest::new(product_id, amount);
+        self.execute_with_retry("earn_redeem", RequestPriority::High, || {
+            let endpoint = "/exchange/v1/earn/redeem";
+            let req = request.clone();
+            let this = self.clone();
+
+            async move {
+                let _: serde_json::Value = this.authenticated_post(endpoint, &req).await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+
     // ==================== UTILITY METHODS ====================
 
     /// Check if the API is reachable
-    pub async fn health_check(&self) -> Result<bool> {
+    pub async fn health_check(&self) -> CoinDCXResult<bool> {
         match self.get_markets().await {
             Ok(markets) => Ok(!markets.is_empty()),
             Err(_) => Ok(false),
@@ -566,6 +1506,33 @@ impl CoinDCXClient {
         cb.state()
     }
 
+    /// Latency histogram of every endpoint called so far, keyed by URL path
+    ///
+    /// Clones of the client share the histograms.
+    pub fn metrics(&self) -> BTreeMap<String, LatencyHistogram> {
+        self.latency.snapshot()
+    }
+
+    /// Clear the latency histograms (e.g. at the start of a cycle)
+    pub fn reset_metrics(&self) {
+        self.latency.reset();
+    }
+
+    /// Failure, open and probe counters of the circuit breaker
+    pub async fn circuit_breaker_metrics(&self) -> crate::common::CircuitBreakerMetrics {
+        self.circuit_breaker.lock().await.metrics()
+    }
+
+    /// Call `hook(from, to)` whenever the circuit breaker changes state
+    ///
+    /// Clones of the client share the breaker, so the hook sees every clone's calls.
+    pub async fn on_circuit_transition(
+        &self,
+        hook: impl Fn(crate::common::CircuitState, crate::common::CircuitState) + Send + Sync + 'static,
+    ) {
+        self.circuit_breaker.lock().await.on_transition(hook);
+    }
+
     /// Reset the circuit breaker
     pub async fn reset_circuit_breaker(&self) {
         let mut cb = self.circuit_breaker.lock().await;
@@ -576,6 +1543,11 @@ impl CoinDCXClient {
     pub fn available_rate_limit(&self) -> usize {
         self.rate_limiter.available_permits()
     }
+
+    /// Requests shed under rate-limit pressure since the client was created
+    pub fn shed_requests(&self) -> u64 {
+        self.rate_limiter.shed_count()
+    }
 }
 
 #[cfg(test)]
@@ -585,8 +1557,9 @@ mod tests {
     #[test]
     fn test_client_config_default() {
         let config = ClientConfig::default();
-        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.retry.max_retries, 3);
         assert_eq!(config.timeout, Duration::from_secs(30));
+        assert_eq!(config.clock_sync_interval, Some(Duration::from_secs(600)));
     }
 
     #[test]
@@ -595,26 +1568,29 @@ mod tests {
             .with_max_retries(5)
             .with_timeout(Duration::from_secs(60))
             .with_rate_limit(20)
-            .with_circuit_breaker_threshold(10);
+            .with_circuit_breaker_threshold(10)
+            .with_request_hook(|_| {})
+            .with_response_hook(|_| {});
 
-        assert_eq!(config.max_retries, 5);
+        assert_eq!(config.retry.max_retries, 5);
         assert_eq!(config.timeout, Duration::from_secs(60));
         assert_eq!(config.rate_limiter.max_requests_per_second, 20);
         assert_eq!(config.circuit_breaker.failure_threshold, 10);
+        assert_eq!(config.middleware.len(), 2);
     }
 
     #[test]
     fn test_client_creation() {
         let client = CoinDCXClient::new("test_key", "test_secret");
         // Should not panic
-        assert_eq!(client.max_retries, 3);
+        assert_eq!(client.retry.max_retries, 3);
     }
 
     #[test]
     fn test_client_with_config() {
         let config = ClientConfig::default().with_max_retries(5);
         let client = CoinDCXClient::with_config("test_key", "test_secret", config);
-        assert_eq!(client.max_retries, 5);
+        assert_eq!(client.retry.max_retries, 5);
     }
 
     #[tokio::test]
@@ -0,0 +1,159 @@
+//! CoinDCX-specific error types
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum CoinDCXError {
+    /// HTTP 429, or the request was shed locally under rate-limit pressure
+    RateLimited,
+    InsufficientBalance(String),
+    /// The exchange rejected the API key or request signature
+    InvalidSignature(String),
+    /// The market is not trading (inactive, suspended or delisted)
+    MarketClosed(String),
+    /// The market or order does not exist
+    NotFound(String),
+    /// Rejected locally: the order breaks its market's limits
+    InvalidOrder(String),
+    /// Any other HTTP failure; `status` is `None` when no response arrived
+    Http {
+        status: Option<u16>,
+        message: String,
+    },
+    /// The response body did not have the expected shape
+    Decode(String),
+    CircuitBreakerOpen,
+    /// Gave up waiting for the exchange to reach a state (not a request timeout)
+    Timeout(String),
+}
+
+impl CoinDCXError {
+    /// Classify a non-success response from its status and body
+    ///
+    /// CoinDCX reports failures as `{"code": 422, "message": "...", "status": "error"}`;
+    /// the message decides the variant where the status alone is ambiguous.
+    pub fn from_response(status: u16, body: &str) -> Self {
+        let message = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|v| v.get("message")?.as_str().map(str::to_string))
+            .unwrap_or_else(|| body.to_string());
+        let lower = message.to_lowercase();
+
+        if status == 429 {
+            Self::RateLimited
+        } else if status == 401 || lower.contains("signature") || lower.contains("api key") {
+            Self::InvalidSignature(message)
+        } else if lower.contains("insufficient") {
+            Self::InsufficientBalance(message)
+        } else if lower.contains("market")
+            && ["closed", "inactive", "not active", "suspended", "halted"]
+                .iter()
+                .any(|s| lower.contains(s))
+        {
+            Self::MarketClosed(message)
+        } else if status == 404 {
+            Self::NotFound(message)
+        } else {
+            Self::Http {
+                status: Some(status),
+                message,
+            }
+        }
+    }
+
+    /// Whether the same request may succeed if sent again later
+    ///
+    /// Rate limits, timeouts, 5xx and an open circuit clear up on their own;
+    /// everything else fails the same way until the caller changes something.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited | Self::CircuitBreakerOpen => true,
+            Self::Http { status, .. } => status.is_none_or(|s| s >= 500),
+            Self::InsufficientBalance(_)
+            | Self::InvalidSignature(_)
+            | Self::MarketClosed(_)
+            | Self::NotFound(_)
+            | Self::InvalidOrder(_)
+            | Self::Decode(_)
+            | Self::Timeout(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for CoinDCXError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::RateLimited => write!(f, "Rate limited"),
+            Self::InsufficientBalance(msg) => write!(f, "Insufficient balance: {}", msg),
+            Self::InvalidSignature(msg) => write!(f, "Invalid signature: {}", msg),
+            Self::MarketClosed(msg) => write!(f, "Market closed: {}", msg),
+            Self::NotFound(msg) => write!(f, "Not found: {}", msg),
+            Self::InvalidOrder(msg) => write!(f, "Invalid order: {}", msg),
+            Self::Http {
+                status: Some(status),
+                message,
+            } => write!(f, "API error ({}): {}", status, message),
+            Self::Http {
+                status: None,
+                message,
+            } => write!(f, "Network error: {}", message),
+            Self::Decode(msg) => write!(f, "Decode error: {}", msg),
+            Self::CircuitBreakerOpen => write!(f, "Circuit breaker is open, rejecting request"),
+            Self::Timeout(msg) => write!(f, "Timed out: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CoinDCXError {}
+
+impl From<reqwest::Error> for CoinDCXError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_decode() {
+            return Self::Decode(err.to_string());
+        }
+        Self::Http {
+            status: err.status().map(|s| s.as_u16()),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for CoinDCXError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Decode(err.to_string())
+    }
+}
+
+pub type CoinDCXResult<T> = Result<T, CoinDCXError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_responses() {
+        let err = |status, body| CoinDCXError::from_response(status, body);
+        let body = r#"{"code":422,"message":"Insufficient funds","status":"error"}"#;
+        assert!(
+            matches!(err(422, body), CoinDCXError::InsufficientBalance(m) if m == "Insufficient funds")
+        );
+        assert!(matches!(
+            err(401, r#"{"message":"Invalid request"}"#),
+            CoinDCXError::InvalidSignature(_)
+        ));
+        assert!(matches!(
+            err(400, r#"{"message":"Market is not active"}"#),
+            CoinDCXError::MarketClosed(_)
+        ));
+        assert!(matches!(err(429, ""), CoinDCXError::RateLimited));
+
+        let server = err(503, "<html>Service Unavailable</html>");
+        assert!(server.is_retryable());
+        assert_eq!(
+            server.to_string(),
+            "API error (503): <html>Service Unavailable</html>"
+        );
+        assert!(!err(400, r#"{"message":"Invalid quantity"}"#).is_retryable());
+        assert!(!err(422, body).is_retryable());
+    }
+}
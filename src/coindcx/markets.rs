@@ -0,0 +1,290 @@
+//! Market metadata cache and local order validation
+//!
+//! CoinDCX answers an order that breaks a market's limits with a 400 and a
+//! terse message. [`MarketCache`] keeps the `markets_details` of every
+//! market, loaded once at startup, so [`MarketCache::validate`] can reject
+//! such an order before it is sent and say which limit it broke.
+//!
+//! [`quantize`] rounds an order to the same limits first, so a size such as
+//! 0.0487213 BTC goes out as 0.04872 instead of being rejected.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use super::error::{CoinDCXError, CoinDCXResult};
+use super::types::{MarketDetails, OrderRequest};
+
+/// `MarketDetails` by market (`BTCINR`); clones share the same cache
+#[derive(Debug, Clone, Default)]
+pub struct MarketCache {
+    markets: Arc<RwLock<HashMap<String, MarketDetails>>>,
+}
+
+impl MarketCache {
+    /// Replace the cached markets
+    pub fn load(&self, details: Vec<MarketDetails>) {
+        let markets = details
+            .into_iter()
+            .map(|d| (d.coindcx_name.clone(), d))
+            .collect();
+        *self.markets.write().unwrap_or_else(|e| e.into_inner()) = markets;
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        !self
+            .markets
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
+    pub fn get(&self, market: &str) -> Option<MarketDetails> {
+        self.markets
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(market)
+            .cloned()
+    }
+
+    /// Check `order` against its market's limits
+    ///
+    /// Passes everything until the cache is loaded, so a client that never
+    /// loads markets behaves as before.
+    pub fn validate(&self, order: &OrderRequest) -> CoinDCXResult<()> {
+        if !self.is_loaded() {
+            return Ok(());
+        }
+        let details = self.get(&order.market).ok_or_else(|| {
+            CoinDCXError::InvalidOrder(format!("unknown market {}", order.market))
+        })?;
+        validate(&details, order)
+    }
+
+    /// Round `order` to its market's step and decimals; false if the market is unknown
+    pub fn quantize(&self, order: &mut OrderRequest) -> bool {
+        match self.get(&order.market) {
+            Some(details) => {
+                quantize(&details, order);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Round `value` to `decimals` places
+fn round_to(value: f64, decimals: u32) -> f64 {
+    let scale = 10f64.powi(decimals as i32);
+    (value * scale).round() / scale
+}
+
+/// Largest multiple of `unit` not above `value`
+///
+/// The nudge keeps exact multiples with float error (0.3 / 0.1 =
+/// 2.9999999999999996) on their own step.
+fn floor_to(value: f64, unit: f64) -> f64 {
+    ((value / unit) * (1.0 + 1e-12)).floor() * unit
+}
+
+impl MarketDetails {
+    /// `quantity` rounded down to the step and quantity decimals
+    ///
+    /// Rounding down keeps an order within the balance that sized it.
+    pub fn quantize_quantity(&self, quantity: f64) -> f64 {
+        let decimals = self.target_currency_precision;
+        let stepped = if self.step > 0.0 {
+            floor_to(quantity, self.step)
+        } else {
+            quantity
+        };
+        round_to(floor_to(stepped, 10f64.powi(-(decimals as i32))), decimals)
+    }
+
+    /// `price` rounded to the nearest tick of the price decimals
+    pub fn quantize_price(&self, price: f64) -> f64 {
+        round_to(price, self.base_currency_precision)
+    }
+}
+
+/// Round `order`'s quantity and prices to what `details` accepts
+///
+/// A quantity that rounds below the market minimum is left for
+/// [`validate`] to reject.
+pub fn quantize(details: &MarketDetails, order: &mut OrderRequest) {
+    order.total_quantity = details.quantize_quantity(order.total_quantity);
+    order.price_per_unit = order.price_per_unit.map(|p| details.quantize_price(p));
+    order.stop_price = order.stop_price.map(|p| details.quantize_price(p));
+}
+
+/// Whether `value` has more than `decimals` decimal places
+fn exceeds_precision(value: f64, decimals: u32) -> bool {
+    let scaled = value * 10f64.powi(decimals as i32);
+    (scaled - scaled.round()).abs() > 1e-12 * scaled.abs().max(1.0)
+}
+
+/// Whether `value` is a whole number of `step`s
+fn on_step(value: f64, step: f64) -> bool {
+    let steps = value / step;
+    (steps - steps.round()).abs() <= 1e-12 * steps.abs().max(1.0)
+}
+
+/// Check `order` against `details`
+///
+/// Quantities follow the traded asset's precision and `step`, prices the
+/// quote currency's precision. Market orders carry no price, so their
+/// notional is only checked when they have a trigger price.
+pub fn validate(details: &MarketDetails, order: &OrderRequest) -> CoinDCXResult<()> {
+    let market = &details.coindcx_name;
+    let invalid = |reason: String| {
+        Err(CoinDCXError::InvalidOrder(format!(
+            "{}: {}",
+            market, reason
+        )))
+    };
+
+    if !details.status.is_empty() && details.status != "active" {
+        return Err(CoinDCXError::MarketClosed(format!(
+            "{} is {}",
+            market, details.status
+        )));
+    }
+    if !details.order_types.is_empty() && !details.order_types.contains(&order.order_type) {
+        return invalid(format!(
+            "{} not accepted (accepted: {})",
+            order.order_type,
+            details.order_types.join(", ")
+        ));
+    }
+
+    let quantity = order.total_quantity;
+    if quantity < details.min_quantity {
+        return invalid(format!(
+            "quantity {} is below the minimum {}",
+            quantity, details.min_quantity
+        ));
+    }
+    if details.max_quantity > 0.0 && quantity > details.max_quantity {
+        return invalid(format!(
+            "quantity {} is above the maximum {}",
+            quantity, details.max_quantity
+        ));
+    }
+    if exceeds_precision(quantity, details.target_currency_precision) {
+        return invalid(format!(
+            "quantity {} has more than {} decimals",
+            quantity, details.target_currency_precision
+        ));
+    }
+    if details.step > 0.0 && !on_step(quantity, details.step) {
+        return invalid(format!(
+            "quantity {} is not a multiple of the step {}",
+            quantity, details.step
+        ));
+    }
+
+    for price in [order.price_per_unit, order.stop_price]
+        .into_iter()
+        .flatten()
+    {
+        if price < details.min_price || (details.max_price > 0.0 && price > details.max_price) {
+            return invalid(format!(
+                "price {} is outside {}..{}",
+                price, details.min_price, details.max_price
+            ));
+        }
+        if exceeds_precision(price, details.base_currency_precision) {
+            return invalid(format!(
+                "price {} has more than {} decimals",
+                price, details.base_currency_precision
+            ));
+        }
+    }
+
+    if let Some(price) = order.price_per_unit.or(order.stop_price) {
+        let notional = price * quantity;
+        if notional < details.min_notional {
+            return invalid(format!(
+                "order value {:.8} is below the minimum {}",
+                notional, details.min_notional
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coindcx::OrderSide;
+
+    fn btcinr() -> MarketDetails {
+        serde_json::from_str(
+            r#"{"coindcx_name":"BTCINR","base_currency_short_name":"INR","target_currency_short_name":"BTC",
+                "min_quantity":0.0001,"max_quantity":100,"min_price":1000,"max_price":100000000,
+                "min_notional":100,"base_currency_precision":2,"target_currency_precision":5,
+                "step":0.00001,"order_types":["market_order","limit_order"],"status":"active"}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_orders_checked_against_market_limits() {
+        let cache = MarketCache::default();
+        let tiny = OrderRequest::limit(OrderSide::Buy, "BTCINR", 0.00005, 5_000_000.0);
+        // Nothing is rejected before markets are loaded
+        assert!(cache.validate(&tiny).is_ok());
+
+        cache.load(vec![btcinr()]);
+        let ok = OrderRequest::limit(OrderSide::Buy, "BTCINR", 0.0012, 5_000_000.25);
+        assert!(cache.validate(&ok).is_ok());
+        assert!(cache
+            .validate(&OrderRequest::market(OrderSide::Sell, "BTCINR", 0.5))
+            .is_ok());
+
+        let reason = |order: OrderRequest| cache.validate(&order).unwrap_err().to_string();
+        assert!(reason(tiny).contains("below the minimum 0.0001"));
+        assert!(
+            reason(OrderRequest::limit(OrderSide::Buy, "BTCINR", 0.000123, 5e6))
+                .contains("more than 5 decimals")
+        );
+        assert!(reason(OrderRequest::limit(
+            OrderSide::Buy,
+            "BTCINR",
+            0.001,
+            5_000_000.125
+        ))
+        .contains("price 5000000.125"));
+        assert!(reason(OrderRequest::limit(
+            OrderSide::Buy,
+            "BTCINR",
+            0.0001,
+            50_000.0
+        ))
+        .contains("order value"));
+        assert!(
+            reason(OrderRequest::market(OrderSide::Buy, "DOGEINR", 10.0))
+                .contains("unknown market")
+        );
+    }
+
+    #[test]
+    fn test_quantize_rounds_to_market_precision() {
+        let details = btcinr();
+        let mut order = OrderRequest::limit(OrderSide::Buy, "BTCINR", 0.0487213, 5_000_000.126);
+        quantize(&details, &mut order);
+        assert_eq!(order.total_quantity, 0.04872);
+        assert_eq!(order.price_per_unit, Some(5_000_000.13));
+        assert!(validate(&details, &order).is_ok());
+
+        // Exact multiples survive float error; sizes are never rounded up
+        assert_eq!(details.quantize_quantity(0.1 + 0.2), 0.3);
+        assert_eq!(details.quantize_quantity(0.000019999), 0.00001);
+
+        let coarse = MarketDetails {
+            step: 0.5,
+            target_currency_precision: 1,
+            ..details
+        };
+        assert_eq!(coarse.quantize_quantity(2.99), 2.5);
+    }
+}
@@ -9,6 +9,17 @@
 //! - **Rate Limiting**: Token bucket algorithm to stay within API limits
 //! - **Circuit Breaker**: Fault tolerance pattern to prevent cascading failures
 //! - **Type-Safe**: Strongly typed request/response models
+//! - **Futures**: Perpetual instruments, positions, leverage and orders, so
+//!   short entries can be executed (pairs as `symbol_to_pair("BTCUSDT")`)
+//! - **Margin**: Margin orders, the margin wallet and spot/margin transfers
+//! - **OCO**: Stop-loss/take-profit pairs parked on the exchange
+//! - **Funding History**: Deposits and withdrawals, to tell top-ups from P&L
+//! - **Batch Orders**: Many orders per round trip with per-order failures
+//! - **Order Validation**: Orders checked against cached market limits
+//! - **Order Waits**: Poll an order with backoff until it is final
+//!   before sending (after [`CoinDCXClient::load_markets`])
+//! - **Fault Injection**: Simulated 429s, 5xxs, timeouts and canned responses
+//!   (feature `fault-injection`, see [`crate::common::faults`])
 //!
 //! # Quick Start
 //!
@@ -74,22 +85,49 @@
 //! - [`rate_limiter`]: Rate limiting with token bucket algorithm
 //! - [`types`]: Request and response type definitions
 //! - [`client`]: Main API client implementation
+//! - [`ws`]: WebSocket stream of ticker and trade events
 
 pub mod auth;
 pub mod client;
+pub mod error;
+pub mod markets;
 pub mod types;
+pub mod ws;
 
 // Re-export main types for convenience
 pub use crate::common::{
     CircuitBreaker, CircuitBreakerConfig, CircuitState, RateLimiter, RateLimiterConfig,
 };
-pub use auth::Credentials;
+pub use auth::{ClockSync, Credentials};
 pub use client::{symbol_to_pair, ClientConfig, CoinDCXClient, API_BASE_URL, PUBLIC_BASE_URL};
+pub use error::{CoinDCXError, CoinDCXResult};
+pub use markets::MarketCache;
 
 // Re-export commonly used types
 pub use types::{
-    Balance, Candle, MarketDetails, OrderBook, OrderRequest, OrderResponse, OrderSide, OrderStatus,
-    OrderType, Ticker, Trade, UserInfo,
+    Balance, BatchFailure, BatchOutcome, Candle, EarnBalance, EarnProduct, FeeTier, FundsTransfer,
+    FuturesInstrument, FuturesOrderRequest, FuturesOrderResponse, FuturesPosition, MarginBalance,
+    MarginOrder, MarginOrderRequest, MarketDetails, OcoHandle, OcoOrder, OcoStatus, OrderBook,
+    OrderRequest, OrderResponse, OrderSide, OrderStatus, OrderType, Ticker, Trade, UserInfo,
+    WalletType,
+};
+
+use crate::capabilities::{ExchangeCapabilities, PrecisionRule};
+
+/// Spot INR/USDT markets: market, limit and stop-limit orders, per-market step sizes
+pub const CAPABILITIES: ExchangeCapabilities = ExchangeCapabilities {
+    name: "coindcx",
+    order_types: &[
+        crate::oms::OrderType::Market,
+        crate::oms::OrderType::Limit,
+        crate::oms::OrderType::StopLimit,
+    ],
+    timeframes: crate::data::INTERVALS,
+    quote_currencies: &["INR", "USDT"],
+    precision: PrecisionRule::PerMarket,
+    min_notional: Some(100.0),
+    sandbox: false,
+    fill_reports: true,
 };
 
 #[cfg(test)]
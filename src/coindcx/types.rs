@@ -121,6 +121,9 @@ pub struct OrderRequest {
     /// Price per unit (required for limit orders)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub price_per_unit: Option<f64>,
+    /// Trigger price (stop-limit orders only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_price: Option<f64>,
     /// Total quantity to trade
     pub total_quantity: f64,
     /// Request timestamp in milliseconds
@@ -128,6 +131,9 @@ pub struct OrderRequest {
     /// Optional client order ID for tracking
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_order_id: Option<String>,
+    /// Reject the order instead of matching on arrival (maker only)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub post_only: bool,
 }
 
 impl OrderRequest {
@@ -138,9 +144,11 @@ impl OrderRequest {
             order_type: OrderType::MarketOrder.to_string(),
             market: market.into(),
             price_per_unit: None,
+            stop_price: None,
             total_quantity: quantity,
             timestamp: chrono::Utc::now().timestamp_millis(),
             client_order_id: None,
+            post_only: false,
         }
     }
 
@@ -151,9 +159,26 @@ impl OrderRequest {
             order_type: OrderType::LimitOrder.to_string(),
             market: market.into(),
             price_per_unit: Some(price),
+            stop_price: None,
             total_quantity: quantity,
             timestamp: chrono::Utc::now().timestamp_millis(),
             client_order_id: None,
+            post_only: false,
+        }
+    }
+
+    /// Create a stop-limit order: a limit at `price` once the market trades through `stop_price`
+    pub fn stop_limit(
+        side: OrderSide,
+        market: impl Into<String>,
+        quantity: f64,
+        stop_price: f64,
+        price: f64,
+    ) -> Self {
+        Self {
+            order_type: OrderType::StopLimit.to_string(),
+            stop_price: Some(stop_price),
+            ..Self::limit(side, market, quantity, price)
         }
     }
 
@@ -162,6 +187,178 @@ impl OrderRequest {
         self.client_order_id = Some(id.into());
         self
     }
+
+    /// Send as post-only (limit orders only)
+    pub fn with_post_only(mut self) -> Self {
+        self.post_only = true;
+        self
+    }
+}
+
+/// A stop-loss and take-profit parked on the exchange as a linked pair
+///
+/// CoinDCX spot has no native OCO, so the pair is a take-profit limit and a
+/// stop-limit sharing a client ID prefix (`<id>-tp`, `<id>-sl`);
+/// [`CoinDCXClient::sync_oco`](crate::coindcx::CoinDCXClient::sync_oco)
+/// cancels one leg once the other fills.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcoOrder {
+    /// Side of both legs (the side that closes the position)
+    pub side: OrderSide,
+    pub market: String,
+    pub quantity: f64,
+    /// Limit price of the take-profit leg
+    pub take_profit_price: f64,
+    /// Trigger of the stop leg
+    pub stop_price: f64,
+    /// Limit price of the stop leg once triggered (past the trigger, so it fills)
+    pub stop_limit_price: f64,
+    /// Client ID prefix of the legs
+    pub client_order_id: String,
+}
+
+impl OcoOrder {
+    /// Linked pair with the stop leg's limit `slippage` (fraction) past its trigger
+    pub fn new(
+        side: OrderSide,
+        market: impl Into<String>,
+        quantity: f64,
+        take_profit_price: f64,
+        stop_price: f64,
+        slippage: f64,
+        client_order_id: impl Into<String>,
+    ) -> Self {
+        let stop_limit_price = match side {
+            OrderSide::Sell => stop_price * (1.0 - slippage),
+            OrderSide::Buy => stop_price * (1.0 + slippage),
+        };
+        Self {
+            side,
+            market: market.into(),
+            quantity,
+            take_profit_price,
+            stop_price,
+            stop_limit_price,
+            client_order_id: client_order_id.into(),
+        }
+    }
+
+    /// The take-profit limit leg
+    pub fn take_profit_leg(&self) -> OrderRequest {
+        OrderRequest::limit(
+            self.side,
+            self.market.clone(),
+            self.quantity,
+            self.take_profit_price,
+        )
+        .with_client_order_id(format!("{}-tp", self.client_order_id))
+    }
+
+    /// The stop-limit leg
+    pub fn stop_loss_leg(&self) -> OrderRequest {
+        OrderRequest::stop_limit(
+            self.side,
+            self.market.clone(),
+            self.quantity,
+            self.stop_price,
+            self.stop_limit_price,
+        )
+        .with_client_order_id(format!("{}-sl", self.client_order_id))
+    }
+}
+
+/// Exchange IDs of a parked [`OcoOrder`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OcoHandle {
+    pub take_profit_id: String,
+    pub stop_loss_id: String,
+}
+
+/// State of a parked [`OcoOrder`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OcoStatus {
+    /// Both legs still resting
+    Open,
+    /// The take-profit leg filled; the stop leg was cancelled
+    TakeProfitFilled(OrderResponse),
+    /// The stop leg filled; the take-profit leg was cancelled
+    StopLossFilled(OrderResponse),
+    /// Neither leg is live any more and neither filled (e.g. cancelled by hand)
+    Closed,
+}
+
+/// Most orders CoinDCX accepts in one `create_multiple` request
+pub const MAX_BATCH_ORDERS: usize = 10;
+
+/// Request to create several orders in one round trip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchOrderRequest {
+    pub orders: Vec<OrderRequest>,
+}
+
+/// An order from a batch that the exchange did not accept
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchFailure {
+    /// Position of the order in the slice passed to `place_orders`
+    pub index: usize,
+    pub client_order_id: Option<String>,
+    pub error: String,
+}
+
+/// Outcome of a batch placement: accepted orders and per-order failures
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchOutcome {
+    pub placed: Vec<OrderResponse>,
+    pub failed: Vec<BatchFailure>,
+}
+
+impl BatchOutcome {
+    /// True when every order in the batch was accepted
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Record a chunk (starting at `offset`) that failed as a whole
+    pub fn record_error(&mut self, offset: usize, chunk: &[OrderRequest], error: &str) {
+        self.failed
+            .extend(chunk.iter().enumerate().map(|(i, order)| BatchFailure {
+                index: offset + i,
+                client_order_id: order.client_order_id.clone(),
+                error: error.to_string(),
+            }));
+    }
+
+    /// Match a chunk's acknowledgements to its requests.
+    ///
+    /// A full response is taken in request order; a short one is matched by
+    /// client order ID, and requests without one can't be confirmed.
+    pub fn record_response(
+        &mut self,
+        offset: usize,
+        chunk: &[OrderRequest],
+        response: OrdersResponse,
+    ) {
+        if response.orders.len() == chunk.len() {
+            self.placed.extend(response.orders);
+            return;
+        }
+        let mut acked = response.orders;
+        for (i, order) in chunk.iter().enumerate() {
+            let position = order.client_order_id.as_ref().and_then(|id| {
+                acked
+                    .iter()
+                    .position(|o| o.client_order_id.as_ref() == Some(id))
+            });
+            match position {
+                Some(p) => self.placed.push(acked.remove(p)),
+                None => self.failed.push(BatchFailure {
+                    index: offset + i,
+                    client_order_id: order.client_order_id.clone(),
+                    error: "not acknowledged in batch response".to_string(),
+                }),
+            }
+        }
+    }
 }
 
 /// Response from order creation
@@ -206,6 +403,16 @@ pub struct OrderResponse {
     pub updated_at: Option<String>,
 }
 
+impl OrderResponse {
+    /// Filled, cancelled or rejected: the order won't change again
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "filled" | "cancelled" | "rejected" | "partially_cancelled"
+        )
+    }
+}
+
 /// Response containing multiple orders
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrdersResponse {
@@ -262,9 +469,9 @@ impl Balance {
 pub struct MarketDetails {
     /// CoinDCX internal name
     pub coindcx_name: String,
-    /// Base currency short name (e.g., "BTC")
+    /// Quote currency short name (e.g., "INR" on BTCINR)
     pub base_currency_short_name: String,
-    /// Target currency short name (e.g., "INR")
+    /// Traded asset short name (e.g., "BTC" on BTCINR)
     pub target_currency_short_name: String,
     /// Minimum order quantity
     pub min_quantity: f64,
@@ -276,11 +483,11 @@ pub struct MarketDetails {
     pub max_price: f64,
     /// Minimum notional value
     pub min_notional: f64,
-    /// Base currency decimal precision
+    /// Decimals of prices (quote currency)
     pub base_currency_precision: u32,
-    /// Target currency decimal precision
+    /// Decimals of quantities (traded asset)
     pub target_currency_precision: u32,
-    /// Minimum price/quantity step
+    /// Quantity increment
     pub step: f64,
     /// Available order types
     #[serde(default)]
@@ -338,6 +545,17 @@ pub struct UserInfo {
     pub email: Option<String>,
 }
 
+/// The account's trading fee tier
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeTier {
+    /// Fee on orders that rest on the book, in percent (0.2 = 0.2%)
+    #[serde(deserialize_with = "deserialize_f64_or_string")]
+    pub maker_fee: f64,
+    /// Fee on orders that take liquidity, in percent
+    #[serde(deserialize_with = "deserialize_f64_or_string")]
+    pub taker_fee: f64,
+}
+
 /// Candle/OHLCV data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Candle {
@@ -467,6 +685,37 @@ impl ActiveOrdersRequest {
     }
 }
 
+/// Trade history request, one page of fills oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeHistoryRequest {
+    pub timestamp: i64,
+    /// Market symbol (e.g. "BTCINR"); all markets when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// Only trades with this ID or later
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_id: Option<i64>,
+    pub from_timestamp: i64,
+    pub to_timestamp: i64,
+    pub sort: String,
+    pub limit: u32,
+}
+
+impl TradeHistoryRequest {
+    /// First page of fills between `from` and `to` (milliseconds)
+    pub fn new(symbol: Option<&str>, from: i64, to: i64, limit: u32) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            symbol: symbol.map(str::to_string),
+            from_id: None,
+            from_timestamp: from,
+            to_timestamp: to,
+            sort: "asc".to_string(),
+            limit,
+        }
+    }
+}
+
 /// Order status request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderStatusRequest {
@@ -495,6 +744,496 @@ impl OrderStatusRequest {
     }
 }
 
+// ==================== FUTURES ====================
+
+/// A futures (perpetual) instrument
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuturesInstrument {
+    /// Futures pair (e.g., "B-BTC_USDT")
+    pub pair: String,
+    pub status: String,
+    #[serde(default)]
+    pub margin_currency_short_name: String,
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub max_leverage_long: f64,
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub max_leverage_short: f64,
+    /// Smallest order quantity
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub min_trade_size: f64,
+    /// Smallest order value in the margin currency
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub min_notional: f64,
+    /// Price tick size
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub price_increment: f64,
+    /// Quantity step size
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub quantity_increment: f64,
+}
+
+/// Response wrapper of the instrument details endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuturesInstrumentResponse {
+    pub instrument: FuturesInstrument,
+}
+
+/// An open (or recently closed) futures position
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuturesPosition {
+    /// Position ID (used by leverage updates and exits)
+    pub id: String,
+    pub pair: String,
+    /// Signed size: positive long, negative short
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub active_pos: f64,
+    /// Quantity of open buy orders on the pair
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub inactive_pos_buy: f64,
+    /// Quantity of open sell orders on the pair
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub inactive_pos_sell: f64,
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub avg_price: f64,
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub liquidation_price: f64,
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub locked_margin: f64,
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub mark_price: f64,
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub leverage: f64,
+    #[serde(default)]
+    pub updated_at: Option<i64>,
+}
+
+impl FuturesPosition {
+    pub fn is_short(&self) -> bool {
+        self.active_pos < 0.0
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.active_pos == 0.0
+    }
+
+    /// Mark-to-market P&L in the margin currency
+    pub fn unrealized_pnl(&self) -> f64 {
+        (self.mark_price - self.avg_price) * self.active_pos
+    }
+}
+
+/// Order fields of a futures order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuturesOrder {
+    /// Order side: "buy" or "sell"
+    pub side: String,
+    /// Futures pair (e.g., "B-BTC_USDT")
+    pub pair: String,
+    /// Order type: "market_order" or "limit_order"
+    pub order_type: String,
+    /// Limit price (required for limit orders)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    pub total_quantity: f64,
+    pub leverage: u32,
+    pub notification: String,
+    pub time_in_force: String,
+    pub hidden: bool,
+    pub post_only: bool,
+    /// Only reduce the open position, never open or flip one
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reduce_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_order_id: Option<String>,
+}
+
+/// Request to create a futures order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuturesOrderRequest {
+    pub timestamp: i64,
+    pub order: FuturesOrder,
+}
+
+impl FuturesOrderRequest {
+    /// Create a market order at 1x leverage; `pair` is a futures pair
+    pub fn market(side: OrderSide, pair: impl Into<String>, quantity: f64) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            order: FuturesOrder {
+                side: side.to_string(),
+                pair: pair.into(),
+                order_type: OrderType::MarketOrder.to_string(),
+                price: None,
+                total_quantity: quantity,
+                leverage: 1,
+                notification: "no_notification".to_string(),
+                time_in_force: "good_till_cancel".to_string(),
+                hidden: false,
+                post_only: false,
+                reduce_only: false,
+                client_order_id: None,
+            },
+        }
+    }
+
+    /// Create a limit order at 1x leverage
+    pub fn limit(side: OrderSide, pair: impl Into<String>, quantity: f64, price: f64) -> Self {
+        let mut request = Self::market(side, pair, quantity);
+        request.order.order_type = OrderType::LimitOrder.to_string();
+        request.order.price = Some(price);
+        request
+    }
+
+    /// Set leverage
+    pub fn with_leverage(mut self, leverage: u32) -> Self {
+        self.order.leverage = leverage;
+        self
+    }
+
+    /// Set a client order ID for tracking
+    pub fn with_client_order_id(mut self, id: impl Into<String>) -> Self {
+        self.order.client_order_id = Some(id.into());
+        self
+    }
+
+    /// Only reduce the open position
+    pub fn with_reduce_only(mut self) -> Self {
+        self.order.reduce_only = true;
+        self
+    }
+
+    /// Send as post-only (limit orders only)
+    pub fn with_post_only(mut self) -> Self {
+        self.order.post_only = true;
+        self
+    }
+}
+
+/// A futures order as reported by the exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuturesOrderResponse {
+    pub id: String,
+    pub pair: String,
+    pub side: String,
+    pub status: String,
+    #[serde(default)]
+    pub order_type: Option<String>,
+    #[serde(default)]
+    pub client_order_id: Option<String>,
+    #[serde(default)]
+    pub total_quantity: Option<f64>,
+    #[serde(default)]
+    pub remaining_quantity: Option<f64>,
+    #[serde(default)]
+    pub avg_price: Option<f64>,
+    #[serde(default)]
+    pub price: Option<f64>,
+    #[serde(default)]
+    pub leverage: Option<f64>,
+    #[serde(default)]
+    pub fee_amount: Option<f64>,
+    #[serde(default)]
+    pub created_at: Option<i64>,
+    #[serde(default)]
+    pub updated_at: Option<i64>,
+}
+
+/// Request for open positions in one margin currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuturesPositionsRequest {
+    pub timestamp: i64,
+    pub page: String,
+    pub size: String,
+    pub margin_currency_short_name: Vec<String>,
+}
+
+impl FuturesPositionsRequest {
+    pub fn new(margin_currency: impl Into<String>) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            page: "1".to_string(),
+            size: "100".to_string(),
+            margin_currency_short_name: vec![margin_currency.into()],
+        }
+    }
+}
+
+/// Request to change the leverage of a futures pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuturesLeverageRequest {
+    pub timestamp: i64,
+    pub pair: String,
+    /// Leverage as a string, as the endpoint expects
+    pub leverage: String,
+}
+
+impl FuturesLeverageRequest {
+    pub fn new(pair: impl Into<String>, leverage: u32) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            pair: pair.into(),
+            leverage: leverage.to_string(),
+        }
+    }
+}
+
+// ==================== MARGIN ====================
+
+/// Request to create a margin order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarginOrderRequest {
+    /// Order side: "buy" or "sell"
+    pub side: String,
+    /// Order type: "market_order" or "limit_order"
+    pub order_type: String,
+    /// Market pair (e.g., "XRPUSDT")
+    pub market: String,
+    /// Limit price (required for limit orders)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    pub quantity: f64,
+    /// Exchange code of the market ("B" for USDT pairs, "I" for INR)
+    pub ecode: String,
+    pub leverage: f64,
+    /// Take-profit price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_price: Option<f64>,
+    /// Stop-loss price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sl_price: Option<f64>,
+    pub timestamp: i64,
+}
+
+impl MarginOrderRequest {
+    /// Create a market order at 1x leverage
+    pub fn market(side: OrderSide, market: impl Into<String>, quantity: f64) -> Self {
+        let market = market.into();
+        Self {
+            side: side.to_string(),
+            order_type: OrderType::MarketOrder.to_string(),
+            ecode: if market.ends_with("INR") { "I" } else { "B" }.to_string(),
+            market,
+            price: None,
+            quantity,
+            leverage: 1.0,
+            target_price: None,
+            sl_price: None,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+
+    /// Create a limit order at 1x leverage
+    pub fn limit(side: OrderSide, market: impl Into<String>, quantity: f64, price: f64) -> Self {
+        Self {
+            order_type: OrderType::LimitOrder.to_string(),
+            price: Some(price),
+            ..Self::market(side, market, quantity)
+        }
+    }
+
+    /// Set leverage
+    pub fn with_leverage(mut self, leverage: f64) -> Self {
+        self.leverage = leverage;
+        self
+    }
+
+    /// Attach exchange-side take-profit and stop-loss prices
+    pub fn with_exits(mut self, target_price: Option<f64>, sl_price: Option<f64>) -> Self {
+        self.target_price = target_price;
+        self.sl_price = sl_price;
+        self
+    }
+}
+
+/// A margin order as reported by the exchange
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarginOrder {
+    pub id: String,
+    pub side: String,
+    pub status: String,
+    pub market: String,
+    #[serde(default)]
+    pub order_type: Option<String>,
+    #[serde(default)]
+    pub price: Option<f64>,
+    #[serde(default)]
+    pub quantity: Option<f64>,
+    #[serde(default)]
+    pub leverage: Option<f64>,
+    #[serde(default)]
+    pub avg_entry: Option<f64>,
+    #[serde(default)]
+    pub avg_exit: Option<f64>,
+    #[serde(default)]
+    pub created_at: Option<i64>,
+    #[serde(default)]
+    pub updated_at: Option<i64>,
+}
+
+/// Request to list margin orders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarginOrdersRequest {
+    pub details: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market: Option<String>,
+    pub timestamp: i64,
+}
+
+/// Balance of the margin (derivatives) wallet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarginBalance {
+    pub currency_short_name: String,
+    #[serde(deserialize_with = "deserialize_f64_or_string")]
+    pub balance: f64,
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub locked_balance: f64,
+}
+
+/// Wallets funds can move between
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WalletType {
+    Spot,
+    Futures,
+}
+
+/// Request to move funds between wallets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferRequest {
+    pub source_wallet_type: WalletType,
+    pub destination_wallet_type: WalletType,
+    pub currency_short_name: String,
+    pub amount: f64,
+    pub timestamp: i64,
+}
+
+impl TransferRequest {
+    pub fn new(currency: impl Into<String>, amount: f64, from: WalletType, to: WalletType) -> Self {
+        Self {
+            source_wallet_type: from,
+            destination_wallet_type: to,
+            currency_short_name: currency.into(),
+            amount,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+/// An earn (staking / savings) product open for subscription
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarnProduct {
+    #[serde(deserialize_with = "deserialize_string_or_number")]
+    pub id: String,
+    pub currency_short_name: String,
+    /// Annual yield as a fraction (0.05 = 5%)
+    #[serde(deserialize_with = "deserialize_f64_or_string")]
+    pub apy: f64,
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub min_amount: f64,
+    /// Days funds are locked after subscribing (0 = flexible, redeemable any time)
+    #[serde(default)]
+    pub lock_days: u32,
+}
+
+impl EarnProduct {
+    pub fn is_flexible(&self) -> bool {
+        self.lock_days == 0
+    }
+}
+
+/// Funds held in an earn product
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarnBalance {
+    #[serde(deserialize_with = "deserialize_string_or_number")]
+    pub product_id: String,
+    pub currency_short_name: String,
+    /// Principal currently subscribed
+    #[serde(deserialize_with = "deserialize_f64_or_string")]
+    pub amount: f64,
+    /// Interest earned and not yet paid out
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub accrued_interest: f64,
+}
+
+impl EarnBalance {
+    /// Principal plus accrued interest
+    pub fn value(&self) -> f64 {
+        self.amount + self.accrued_interest
+    }
+}
+
+/// Request to move funds into or out of an earn product
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarnRequest {
+    pub product_id: String,
+    pub amount: f64,
+    pub timestamp: i64,
+}
+
+impl EarnRequest {
+    pub fn new(product_id: impl Into<String>, amount: f64) -> Self {
+        Self {
+            product_id: product_id.into(),
+            amount,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+/// A deposit into or withdrawal out of the spot wallet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundsTransfer {
+    #[serde(deserialize_with = "deserialize_string_or_number")]
+    pub id: String,
+    pub currency_short_name: String,
+    /// Amount credited (deposits) or sent (withdrawals), before fees
+    #[serde(deserialize_with = "deserialize_f64_or_string")]
+    pub amount: f64,
+    /// Withdrawal fee charged on top of `amount`
+    #[serde(default, deserialize_with = "deserialize_f64_or_string")]
+    pub fee: f64,
+    /// `"success"` once the funds have moved; pending and failed transfers change nothing
+    #[serde(default)]
+    pub status: String,
+    /// Unix milliseconds
+    pub created_at: i64,
+}
+
+impl FundsTransfer {
+    pub fn is_complete(&self) -> bool {
+        matches!(self.status.as_str(), "success" | "completed" | "confirmed")
+    }
+
+    /// Still in flight; may complete later
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "pending" | "processing" | "initiated" | "submitted"
+        )
+    }
+}
+
+/// Request for deposit or withdrawal history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundsHistoryRequest {
+    pub timestamp: i64,
+    /// Only this currency; every currency when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency_short_name: Option<String>,
+    /// Only transfers created at or after this time (Unix ms)
+    pub from_timestamp: i64,
+}
+
+impl FundsHistoryRequest {
+    pub fn new(currency: Option<&str>, from: i64) -> Self {
+        Self {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            currency_short_name: currency.map(str::to_string),
+            from_timestamp: from,
+        }
+    }
+}
+
 // Custom deserializer for fields that can be string or number
 fn deserialize_string_or_number<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
@@ -616,6 +1355,36 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_batch_partial_failure() {
+        let chunk: Vec<_> = ["g1", "g2", "g3"]
+            .iter()
+            .map(|id| {
+                OrderRequest::limit(OrderSide::Buy, "BTCINR", 0.01, 4.0e6).with_client_order_id(*id)
+            })
+            .collect();
+        let ack = |id: &str| OrderResponse {
+            client_order_id: Some(id.to_string()),
+            ..serde_json::from_str(r#"{"id": "x", "status": "open"}"#).unwrap()
+        };
+        let mut outcome = BatchOutcome::default();
+        outcome.record_response(
+            10,
+            &chunk,
+            OrdersResponse {
+                orders: vec![ack("g3"), ack("g1")],
+            },
+        );
+        assert_eq!(outcome.placed.len(), 2);
+        assert_eq!(outcome.failed.len(), 1);
+        assert_eq!(outcome.failed[0].index, 11);
+        assert_eq!(outcome.failed[0].client_order_id.as_deref(), Some("g2"));
+
+        outcome.record_error(13, &chunk[..1], "429");
+        assert_eq!(outcome.failed[1].index, 13);
+        assert!(!outcome.is_complete());
+    }
+
     #[test]
     fn test_order_side_display() {
         assert_eq!(OrderSide::Buy.to_string(), "buy");
@@ -648,6 +1417,17 @@ mod tests {
         assert_eq!(order.price_per_unit, Some(5000000.0));
     }
 
+    #[test]
+    fn test_order_request_post_only() {
+        let plain = OrderRequest::limit(OrderSide::Buy, "BTCINR", 0.001, 5000000.0);
+        assert!(serde_json::to_value(&plain)
+            .unwrap()
+            .get("post_only")
+            .is_none());
+        let maker = plain.with_post_only();
+        assert_eq!(serde_json::to_value(&maker).unwrap()["post_only"], true);
+    }
+
     #[test]
     fn test_order_request_with_client_id() {
         let order = OrderRequest::market(OrderSide::Buy, "BTCINR", 0.001)
@@ -730,6 +1510,71 @@ mod tests {
         assert_eq!(by_client.client_order_id, Some("client-456".to_string()));
     }
 
+    #[test]
+    fn test_futures_order_request() {
+        let req = FuturesOrderRequest::limit(OrderSide::Sell, "B-BTC_USDT", 0.01, 60000.0)
+            .with_leverage(5)
+            .with_client_order_id("short-1");
+        let json = serde_json::to_value(&req).unwrap();
+        assert_eq!(json["order"]["side"], "sell");
+        assert_eq!(json["order"]["order_type"], "limit_order");
+        assert_eq!(json["order"]["leverage"], 5);
+        assert_eq!(json["order"]["client_order_id"], "short-1");
+        assert!(json["order"].get("reduce_only").is_none());
+    }
+
+    #[test]
+    fn test_futures_position_parsing() {
+        let json = r#"{
+            "id": "pos-1", "pair": "B-ETH_USDT", "active_pos": -2.0,
+            "inactive_pos_buy": 0, "inactive_pos_sell": 0, "avg_price": "3000",
+            "liquidation_price": 3400.5, "locked_margin": 1200, "mark_price": 2900,
+            "leverage": 5, "updated_at": 1717000000000
+        }"#;
+        let position: FuturesPosition = serde_json::from_str(json).unwrap();
+        assert!(position.is_short());
+        assert_eq!(position.unrealized_pnl(), 200.0);
+    }
+
+    #[test]
+    fn test_margin_order_request() {
+        let req = MarginOrderRequest::market(OrderSide::Sell, "XRPUSDT", 100.0).with_leverage(3.0);
+        assert_eq!(req.ecode, "B");
+        assert_eq!(req.leverage, 3.0);
+        assert_eq!(
+            MarginOrderRequest::limit(OrderSide::Buy, "BTCINR", 0.01, 5e6).ecode,
+            "I"
+        );
+        let transfer = TransferRequest::new("USDT", 50.0, WalletType::Spot, WalletType::Futures);
+        assert_eq!(
+            serde_json::to_value(&transfer).unwrap()["destination_wallet_type"],
+            "futures"
+        );
+    }
+
+    #[test]
+    fn test_oco_legs() {
+        let oco = OcoOrder::new(
+            OrderSide::Sell,
+            "BTCINR",
+            0.01,
+            5.5e6,
+            4.5e6,
+            0.01,
+            "exit-7",
+        );
+        let tp = oco.take_profit_leg();
+        assert_eq!(tp.order_type, "limit_order");
+        assert_eq!(tp.price_per_unit, Some(5.5e6));
+        assert_eq!(tp.client_order_id.as_deref(), Some("exit-7-tp"));
+
+        let sl = oco.stop_loss_leg();
+        assert_eq!(sl.order_type, "stop_limit");
+        assert_eq!(sl.stop_price, Some(4.5e6));
+        assert_eq!(sl.price_per_unit, Some(4.455e6));
+        assert_eq!(sl.client_order_id.as_deref(), Some("exit-7-sl"));
+    }
+
     #[test]
     fn test_order_book_operations() {
         let mut bids = std::collections::HashMap::new();
@@ -754,4 +1599,21 @@ mod tests {
         assert_eq!(sorted_asks[0].price, 101.0);
         assert_eq!(sorted_asks[1].price, 102.0);
     }
+
+    #[test]
+    fn test_earn_balance_parses_strings() {
+        let balance: EarnBalance = serde_json::from_str(
+            r#"{"product_id": 42, "currency_short_name": "INR", "amount": "5000.0", "accrued_interest": "1.25"}"#,
+        )
+        .unwrap();
+        assert_eq!(balance.product_id, "42");
+        assert_eq!(balance.value(), 5001.25);
+
+        let product: EarnProduct = serde_json::from_str(
+            r#"{"id": "inr-flex", "currency_short_name": "INR", "apy": "0.05"}"#,
+        )
+        .unwrap();
+        assert!(product.is_flexible());
+        assert_eq!(product.min_amount, 0.0);
+    }
 }
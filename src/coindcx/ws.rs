@@ -0,0 +1,914 @@
+//! Streaming market and account data from CoinDCX
+//!
+//! CoinDCX publishes trades and price changes over Socket.IO at
+//! [`STREAM_URL`]. [`MarketStream`] speaks the Engine.IO v4 framing directly
+//! over a WebSocket, joins the `<pair>@trades` and `<pair>@prices` channels
+//! for each market and yields [`MarketEvent`]s keyed by the caller's symbol
+//! (`BTCINR`, not `I-BTC_INR`).
+//!
+//! ```no_run
+//! use crypto_strategies::coindcx::ws::{MarketEvent, MarketStream};
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let mut stream = MarketStream::connect(&["BTCINR".to_string()]).await?;
+//!     while let Some(event) = stream.next().await {
+//!         if let MarketEvent::Trade(t) = event {
+//!             println!("{} {} @ {}", t.symbol, t.quantity, t.price);
+//!         }
+//!     }
+//!     // The connection closed; see `MarketStream::reconnecting`
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`UserStream`] joins the account's private channel and yields
+//! [`OrderUpdate`]s and [`BalanceUpdate`]s as the exchange reports them.
+//!
+//! [`TradeCandles`] turns the trade events into OHLCV bars, so a live trader
+//! can read the forming bar without polling REST candles.
+//!
+//! [`DepthStream`] joins the `<pair>@orderbook@<depth>` channels and yields
+//! [`DepthUpdate`]s; [`crate::book_tracker`] keeps local books from them.
+//!
+//! Each stream has a `reconnecting` constructor returning a
+//! [`ReconnectingStream`] that rejoins the same channels after a drop,
+//! skips replayed trades and order updates, and reports the outage as a
+//! [`StreamEvent::Gap`](crate::common::StreamEvent::Gap).
+
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::auth::Credentials;
+use super::client::symbol_to_pair;
+use super::types::{Candle, OrderResponse};
+use crate::common::{ReconnectingStream, RetryPolicy};
+
+/// Socket.IO endpoint for public market data
+pub const STREAM_URL: &str = "wss://stream.coindcx.com";
+
+/// Last traded price of a market (`price-change` event)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickerUpdate {
+    pub symbol: String,
+    pub price: f64,
+    /// Exchange time in milliseconds
+    pub timestamp: i64,
+}
+
+/// A public trade (`new-trade` event)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeUpdate {
+    pub symbol: String,
+    pub price: f64,
+    pub quantity: f64,
+    /// Exchange time in milliseconds
+    pub timestamp: i64,
+    /// True when the aggressor sold into a resting bid
+    pub buyer_is_maker: bool,
+}
+
+/// An event from the market data stream
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarketEvent {
+    Ticker(TickerUpdate),
+    Trade(TradeUpdate),
+}
+
+impl MarketEvent {
+    pub fn symbol(&self) -> &str {
+        match self {
+            MarketEvent::Ticker(t) => &t.symbol,
+            MarketEvent::Trade(t) => &t.symbol,
+        }
+    }
+}
+
+/// Orderbook levels for one market (`depth-snapshot` / `depth-update` events)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthUpdate {
+    pub symbol: String,
+    /// `(price, quantity)`; a zero quantity removes the level
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+    /// Exchange time in milliseconds
+    pub timestamp: i64,
+    /// True when the levels replace the whole book rather than patch it
+    pub is_snapshot: bool,
+}
+
+/// An Engine.IO text frame, as far as the streams care
+#[derive(Debug, PartialEq)]
+enum Frame {
+    /// Engine.IO open: send the Socket.IO connect
+    Open,
+    /// Socket.IO connect acknowledged: join channels
+    Connected,
+    /// Engine.IO ping: answer with a pong
+    Ping,
+    /// Socket.IO event: name, payload and the channel it came from
+    Event {
+        name: String,
+        data: Value,
+        channel: Option<String>,
+    },
+    /// Server closed the Engine.IO session
+    Close,
+    Other,
+}
+
+fn frame(text: &str) -> Frame {
+    match text {
+        "2" => return Frame::Ping,
+        "1" => return Frame::Close,
+        _ => {}
+    }
+    if text.starts_with('0') {
+        return Frame::Open;
+    }
+    let Some(payload) = text.strip_prefix("42") else {
+        return if text.starts_with("40") {
+            Frame::Connected
+        } else {
+            Frame::Other
+        };
+    };
+    let Ok(Value::Array(parts)) = serde_json::from_str::<Value>(payload) else {
+        return Frame::Other;
+    };
+    let (Some(name), Some(body)) = (parts.first().and_then(Value::as_str), parts.get(1)) else {
+        return Frame::Other;
+    };
+    // The event body wraps the data as a JSON string
+    let data = match body.get("data") {
+        Some(Value::String(s)) => serde_json::from_str(s).unwrap_or(Value::Null),
+        Some(v) => v.clone(),
+        None => body.clone(),
+    };
+    Frame::Event {
+        name: name.to_string(),
+        data,
+        channel: body
+            .get("channel")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    }
+}
+
+/// A market event; `markets` maps CoinDCX pairs back to symbols
+fn market_event(
+    name: &str,
+    data: &Value,
+    channel: Option<&str>,
+    markets: &HashMap<String, String>,
+) -> Option<MarketEvent> {
+    // Price changes carry no pair; it comes from the channel
+    let pair = data
+        .get("s")
+        .and_then(Value::as_str)
+        .or_else(|| channel?.split('@').next())?;
+    let symbol = markets.get(pair)?.clone();
+    let timestamp = data.get("T").and_then(Value::as_i64).unwrap_or_default();
+    let price = data.get("p").and_then(number)?;
+
+    match name {
+        "new-trade" => Some(MarketEvent::Trade(TradeUpdate {
+            symbol,
+            price,
+            quantity: data.get("q").and_then(number)?,
+            timestamp,
+            buyer_is_maker: data.get("m").and_then(Value::as_bool).unwrap_or(false),
+        })),
+        "price-change" => Some(MarketEvent::Ticker(TickerUpdate {
+            symbol,
+            price,
+            timestamp,
+        })),
+        _ => None,
+    }
+}
+
+/// A depth event; `markets` maps CoinDCX pairs back to symbols
+fn depth_event(
+    name: &str,
+    data: &Value,
+    channel: Option<&str>,
+    markets: &HashMap<String, String>,
+) -> Option<DepthUpdate> {
+    let is_snapshot = match name {
+        "depth-snapshot" => true,
+        "depth-update" => false,
+        _ => return None,
+    };
+    let pair = data
+        .get("s")
+        .and_then(Value::as_str)
+        .or_else(|| channel?.split('@').next())?;
+    // Levels arrive as a `{ "price": "quantity" }` map
+    let levels = |side: &str| -> Vec<(f64, f64)> {
+        data.get(side)
+            .and_then(Value::as_object)
+            .map(|levels| {
+                levels
+                    .iter()
+                    .filter_map(|(price, qty)| Some((price.parse().ok()?, number(qty)?)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    Some(DepthUpdate {
+        symbol: markets.get(pair)?.clone(),
+        bids: levels("bids"),
+        asks: levels("asks"),
+        timestamp: data
+            .get("ts")
+            .or_else(|| data.get("T"))
+            .and_then(Value::as_i64)
+            .unwrap_or_default(),
+        is_snapshot,
+    })
+}
+
+/// Account events; CoinDCX batches them in arrays
+fn user_events(name: &str, data: &Value) -> Vec<UserEvent> {
+    let items = match data {
+        Value::Array(items) => items.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+    match name {
+        "order-update" => items
+            .iter()
+            .filter_map(|v| serde_json::from_value(v.clone()).ok())
+            .map(UserEvent::Order)
+            .collect(),
+        "balance-update" => items
+            .iter()
+            .filter_map(|v| serde_json::from_value(v.clone()).ok())
+            .map(UserEvent::Balance)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// CoinDCX sends numbers both as JSON numbers and as strings
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn string_or_number<'de, D: serde::Deserializer<'de>>(d: D) -> Result<f64, D::Error> {
+    let value = Value::deserialize(d)?;
+    Ok(number(&value).unwrap_or_default())
+}
+
+/// Socket.IO `join` for a channel, with any extra fields (auth)
+fn join(channel: &str, extra: Value) -> String {
+    let mut body = serde_json::json!({ "channelName": channel });
+    if let (Some(body), Value::Object(extra)) = (body.as_object_mut(), extra) {
+        body.extend(extra);
+    }
+    format!("42{}", serde_json::json!(["join", body]))
+}
+
+/// Open a Socket.IO session at `url`, join `joins` and forward decoded events
+async fn session<E: Send + 'static>(
+    url: &str,
+    joins: Vec<String>,
+    decode: impl Fn(&str, &Value, Option<&str>) -> Vec<E> + Send + 'static,
+) -> Result<(mpsc::UnboundedReceiver<E>, JoinHandle<Result<()>>)> {
+    let endpoint = format!(
+        "{}/socket.io/?EIO=4&transport=websocket",
+        url.trim_end_matches('/')
+    );
+    let (socket, _) = tokio_tungstenite::connect_async(&endpoint)
+        .await
+        .with_context(|| format!("Failed to connect to {}", endpoint))?;
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    let task = tokio::spawn(async move {
+        let (mut write, mut read) = socket.split();
+        while let Some(message) = read.next().await {
+            let text = match message? {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            match frame(&text) {
+                Frame::Open => write.send(Message::Text("40".into())).await?,
+                Frame::Connected => {
+                    for join in &joins {
+                        write.send(Message::Text(join.clone())).await?;
+                    }
+                    tracing::debug!("Joined {} CoinDCX channel(s)", joins.len());
+                }
+                Frame::Ping => write.send(Message::Text("3".into())).await?,
+                Frame::Event {
+                    name,
+                    data,
+                    channel,
+                } => {
+                    for event in decode(&name, &data, channel.as_deref()) {
+                        if tx.send(event).is_err() {
+                            // Receiver dropped: nobody is listening
+                            return Ok(());
+                        }
+                    }
+                }
+                Frame::Close => break,
+                Frame::Other => {}
+            }
+        }
+        Ok(())
+    });
+    Ok((rx, task))
+}
+
+/// Every event received since the last call, without waiting
+///
+/// `None` once the connection has closed and nothing is left.
+fn drain<E>(rx: &mut mpsc::UnboundedReceiver<E>) -> Option<Vec<E>> {
+    let mut events = Vec::new();
+    loop {
+        match rx.try_recv() {
+            Ok(event) => events.push(event),
+            Err(mpsc::error::TryRecvError::Empty) => return Some(events),
+            Err(mpsc::error::TryRecvError::Disconnected) if events.is_empty() => return None,
+            Err(mpsc::error::TryRecvError::Disconnected) => return Some(events),
+        }
+    }
+}
+
+/// Live ticker and trade events for a set of markets
+///
+/// The stream ends when the connection drops. Dropping it closes the socket.
+pub struct MarketStream {
+    rx: mpsc::UnboundedReceiver<MarketEvent>,
+    task: JoinHandle<Result<()>>,
+}
+
+impl MarketStream {
+    /// Connect to [`STREAM_URL`] and subscribe to `symbols`
+    pub async fn connect(symbols: &[String]) -> Result<Self> {
+        Self::connect_to(STREAM_URL, symbols).await
+    }
+
+    /// Connect to a Socket.IO endpoint at `url` (e.g. a local test server)
+    pub async fn connect_to(url: &str, symbols: &[String]) -> Result<Self> {
+        let markets: HashMap<String, String> = symbols
+            .iter()
+            .map(|s| (symbol_to_pair(s), s.clone()))
+            .collect();
+        let joins = markets
+            .keys()
+            .flat_map(|pair| {
+                [
+                    join(&format!("{}@trades", pair), Value::Null),
+                    join(&format!("{}@prices", pair), Value::Null),
+                ]
+            })
+            .collect();
+        let (rx, task) = session(url, joins, move |name, data, channel| {
+            market_event(name, data, channel, &markets)
+                .into_iter()
+                .collect()
+        })
+        .await?;
+        Ok(Self { rx, task })
+    }
+
+    /// Like [`MarketStream::connect_to`], reconnecting with `policy` whenever
+    /// the connection drops
+    ///
+    /// Trades are de-duplicated by symbol, time, price and quantity; ticks by
+    /// symbol and time.
+    pub async fn reconnecting(
+        url: &str,
+        symbols: &[String],
+        policy: RetryPolicy,
+    ) -> Result<ReconnectingStream<MarketEvent>> {
+        let (url, symbols) = (url.to_string(), symbols.to_vec());
+        ReconnectingStream::connect(
+            policy,
+            move || {
+                let (url, symbols) = (url.clone(), symbols.clone());
+                async move { Self::connect_to(&url, &symbols).await }
+            },
+            |event| {
+                Some(match event {
+                    MarketEvent::Trade(t) => {
+                        format!("t:{}:{}:{}:{}", t.symbol, t.timestamp, t.price, t.quantity)
+                    }
+                    MarketEvent::Ticker(t) => format!("p:{}:{}", t.symbol, t.timestamp),
+                })
+            },
+        )
+        .await
+    }
+
+    /// Next event; `None` once the connection has closed
+    pub async fn next(&mut self) -> Option<MarketEvent> {
+        self.rx.recv().await
+    }
+
+    /// Every event received since the last call, without waiting
+    ///
+    /// Returns `None` once the connection has closed and nothing is left.
+    pub fn drain(&mut self) -> Option<Vec<MarketEvent>> {
+        drain(&mut self.rx)
+    }
+}
+
+impl futures_core::Stream for MarketStream {
+    type Item = MarketEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl Drop for MarketStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Orderbook depth events for a set of markets
+///
+/// The stream ends when the connection drops. Dropping it closes the socket.
+pub struct DepthStream {
+    rx: mpsc::UnboundedReceiver<DepthUpdate>,
+    task: JoinHandle<Result<()>>,
+}
+
+impl DepthStream {
+    /// Connect to [`STREAM_URL`] and subscribe to the top `depth` levels
+    /// (10, 20 or 50) of `symbols`
+    pub async fn connect(symbols: &[String], depth: usize) -> Result<Self> {
+        Self::connect_to(STREAM_URL, symbols, depth).await
+    }
+
+    /// Connect to a Socket.IO endpoint at `url` (e.g. a local test server)
+    pub async fn connect_to(url: &str, symbols: &[String], depth: usize) -> Result<Self> {
+        let markets: HashMap<String, String> = symbols
+            .iter()
+            .map(|s| (symbol_to_pair(s), s.clone()))
+            .collect();
+        let joins = markets
+            .keys()
+            .map(|pair| join(&format!("{}@orderbook@{}", pair, depth), Value::Null))
+            .collect();
+        let (rx, task) = session(url, joins, move |name, data, channel| {
+            depth_event(name, data, channel, &markets)
+                .into_iter()
+                .collect()
+        })
+        .await?;
+        Ok(Self { rx, task })
+    }
+
+    /// Like [`DepthStream::connect_to`], reconnecting with `policy` whenever
+    /// the connection drops
+    ///
+    /// Updates are never de-duplicated: the snapshot sent after rejoining
+    /// replaces the books.
+    pub async fn reconnecting(
+        url: &str,
+        symbols: &[String],
+        depth: usize,
+        policy: RetryPolicy,
+    ) -> Result<ReconnectingStream<DepthUpdate>> {
+        let (url, symbols) = (url.to_string(), symbols.to_vec());
+        ReconnectingStream::connect(
+            policy,
+            move || {
+                let (url, symbols) = (url.clone(), symbols.clone());
+                async move { Self::connect_to(&url, &symbols, depth).await }
+            },
+            |_| None,
+        )
+        .await
+    }
+
+    /// Next update; `None` once the connection has closed
+    pub async fn next(&mut self) -> Option<DepthUpdate> {
+        self.rx.recv().await
+    }
+
+    /// Every update received since the last call, without waiting
+    ///
+    /// Returns `None` once the connection has closed and nothing is left.
+    pub fn drain(&mut self) -> Option<Vec<DepthUpdate>> {
+        drain(&mut self.rx)
+    }
+}
+
+impl futures_core::Stream for DepthStream {
+    type Item = DepthUpdate;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl Drop for DepthStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// State of one of the account's orders (`order-update` event)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OrderUpdate {
+    pub id: String,
+    #[serde(default)]
+    pub client_order_id: Option<String>,
+    /// Market symbol (e.g. "BTCINR")
+    #[serde(default)]
+    pub market: String,
+    #[serde(default)]
+    pub side: String,
+    /// `open`, `partially_filled`, `filled`, `cancelled`, ...
+    pub status: String,
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub total_quantity: f64,
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub remaining_quantity: f64,
+    /// Average price of everything filled so far
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub avg_price: f64,
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub fee_amount: f64,
+    /// Exchange time in milliseconds
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+impl OrderUpdate {
+    /// Quantity filled so far
+    pub fn filled_quantity(&self) -> f64 {
+        (self.total_quantity - self.remaining_quantity).max(0.0)
+    }
+
+    /// No further fills will follow
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "filled" | "cancelled" | "rejected" | "partially_cancelled"
+        )
+    }
+}
+
+/// The same order as fetched over REST, e.g. to catch up after a stream outage
+impl From<&OrderResponse> for OrderUpdate {
+    fn from(order: &OrderResponse) -> Self {
+        Self {
+            id: order.id.clone(),
+            client_order_id: order.client_order_id.clone(),
+            market: order.market.clone().unwrap_or_default(),
+            side: order.side.clone().unwrap_or_default(),
+            status: order.status.clone(),
+            total_quantity: order.total_quantity.unwrap_or(0.0),
+            remaining_quantity: order.remaining_quantity.unwrap_or(0.0),
+            avg_price: order.avg_price.unwrap_or(0.0),
+            fee_amount: order.fee_amount.unwrap_or(0.0),
+            updated_at: order
+                .updated_at
+                .as_deref()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map_or(0, |t| t.timestamp_millis()),
+        }
+    }
+}
+
+/// A wallet balance after a change (`balance-update` event)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BalanceUpdate {
+    pub currency: String,
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub balance: f64,
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub locked_balance: f64,
+}
+
+/// An event from the account's private channel
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserEvent {
+    Order(OrderUpdate),
+    Balance(BalanceUpdate),
+}
+
+/// Order and balance updates for the account behind `credentials`
+///
+/// Joins CoinDCX's private `coindcx` channel, signed like a REST request.
+/// The stream ends when the connection drops. Dropping it closes the socket.
+pub struct UserStream {
+    rx: mpsc::UnboundedReceiver<UserEvent>,
+    task: JoinHandle<Result<()>>,
+}
+
+impl UserStream {
+    pub async fn connect(credentials: &Credentials) -> Result<Self> {
+        Self::connect_to(STREAM_URL, credentials).await
+    }
+
+    pub async fn connect_to(url: &str, credentials: &Credentials) -> Result<Self> {
+        let signature = credentials.sign(r#"{"channel":"coindcx"}"#);
+        let auth = serde_json::json!({
+            "authSignature": signature,
+            "apiKey": credentials.api_key(),
+        });
+        let (rx, task) = session(url, vec![join("coindcx", auth)], |name, data, _| {
+            user_events(name, data)
+        })
+        .await?;
+        Ok(Self { rx, task })
+    }
+
+    /// Like [`UserStream::connect_to`], reconnecting with `policy` whenever
+    /// the connection drops
+    ///
+    /// Order updates are de-duplicated by order, status and update time.
+    pub async fn reconnecting(
+        url: &str,
+        credentials: &Credentials,
+        policy: RetryPolicy,
+    ) -> Result<ReconnectingStream<UserEvent>> {
+        let (url, credentials) = (url.to_string(), credentials.clone());
+        ReconnectingStream::connect(
+            policy,
+            move || {
+                let (url, credentials) = (url.clone(), credentials.clone());
+                async move { Self::connect_to(&url, &credentials).await }
+            },
+            |event| match event {
+                UserEvent::Order(o) => Some(format!(
+                    "{}:{}:{}:{}",
+                    o.id, o.status, o.remaining_quantity, o.updated_at
+                )),
+                UserEvent::Balance(_) => None,
+            },
+        )
+        .await
+    }
+
+    /// Next event; `None` once the connection has closed
+    pub async fn next(&mut self) -> Option<UserEvent> {
+        self.rx.recv().await
+    }
+
+    /// Every event received since the last call, without waiting
+    ///
+    /// Returns `None` once the connection has closed and nothing is left.
+    pub fn drain(&mut self) -> Option<Vec<UserEvent>> {
+        drain(&mut self.rx)
+    }
+}
+
+impl futures_core::Stream for UserStream {
+    type Item = UserEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl Drop for UserStream {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// OHLCV bars built from [`TradeUpdate`]s
+///
+/// Only bars that opened after `started` are reported: an earlier bar would
+/// be missing the trades from before the stream connected.
+#[derive(Debug, Clone)]
+pub struct TradeCandles {
+    started: i64,
+    /// Latest bar per (symbol, bar length in seconds)
+    bars: HashMap<(String, u64), Candle>,
+    intervals: Vec<u64>,
+}
+
+impl TradeCandles {
+    /// Bars of each length in `intervals` (seconds), counting trades from `started` (ms)
+    pub fn new(intervals: &[u64], started: i64) -> Self {
+        Self {
+            started,
+            bars: HashMap::new(),
+            intervals: intervals.to_vec(),
+        }
+    }
+
+    /// Count trades from `started` (ms) again, e.g. after the stream was down
+    ///
+    /// Bars that opened earlier missed trades and are no longer reported.
+    pub fn restart(&mut self, started: i64) {
+        self.started = started;
+    }
+
+    pub fn record(&mut self, trade: &TradeUpdate) {
+        for &secs in &self.intervals {
+            let open_time = bar_open(trade.timestamp, secs);
+            let key = (trade.symbol.clone(), secs);
+            match self.bars.get_mut(&key) {
+                Some(bar) if bar.time == open_time => {
+                    bar.high = bar.high.max(trade.price);
+                    bar.low = bar.low.min(trade.price);
+                    bar.close = trade.price;
+                    bar.volume += trade.quantity;
+                }
+                // Late trades for a bar already replaced are dropped
+                Some(bar) if bar.time > open_time => {}
+                _ => {
+                    self.bars.insert(
+                        key,
+                        Candle {
+                            open: trade.price,
+                            high: trade.price,
+                            low: trade.price,
+                            close: trade.price,
+                            volume: trade.quantity,
+                            time: open_time,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    /// The bar of length `secs` containing `now` (ms), if fully covered by the stream
+    pub fn current(&self, symbol: &str, secs: u64, now: i64) -> Option<&Candle> {
+        let bar = self.bars.get(&(symbol.to_string(), secs))?;
+        (bar.time == bar_open(now, secs) && bar.time >= self.started).then_some(bar)
+    }
+}
+
+/// Open time (ms) of the `secs`-long bar containing `timestamp` (ms)
+fn bar_open(timestamp: i64, secs: u64) -> i64 {
+    let len = secs as i64 * 1000;
+    timestamp - timestamp.rem_euclid(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markets() -> HashMap<String, String> {
+        HashMap::from([
+            ("I-BTC_INR".to_string(), "BTCINR".to_string()),
+            ("B-ETH_USDT".to_string(), "ETHUSDT".to_string()),
+        ])
+    }
+
+    fn market(text: &str) -> Option<MarketEvent> {
+        match frame(text) {
+            Frame::Event {
+                name,
+                data,
+                channel,
+            } => market_event(&name, &data, channel.as_deref(), &markets()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_decode_socket_io_frames() {
+        assert_eq!(frame(r#"0{"sid":"abc","pingInterval":25000}"#), Frame::Open);
+        assert_eq!(frame(r#"40{"sid":"xyz"}"#), Frame::Connected);
+        assert_eq!(frame("2"), Frame::Ping);
+
+        let trade = r#"42["new-trade",{"channel":"I-BTC_INR@trades","data":"{\"T\":1700000000123,\"p\":\"5000000.5\",\"q\":\"0.002\",\"s\":\"I-BTC_INR\",\"m\":true}"}]"#;
+        assert_eq!(
+            market(trade),
+            Some(MarketEvent::Trade(TradeUpdate {
+                symbol: "BTCINR".to_string(),
+                price: 5_000_000.5,
+                quantity: 0.002,
+                timestamp: 1_700_000_000_123,
+                buyer_is_maker: true,
+            }))
+        );
+
+        // Price changes carry no pair; it comes from the channel
+        let price = r#"42["price-change",{"channel":"B-ETH_USDT@prices","data":{"T":1700000000000,"p":2500.25,"pr":"spot"}}]"#;
+        assert_eq!(
+            market(price),
+            Some(MarketEvent::Ticker(TickerUpdate {
+                symbol: "ETHUSDT".to_string(),
+                price: 2500.25,
+                timestamp: 1_700_000_000_000,
+            }))
+        );
+
+        // Markets we did not subscribe to are ignored
+        let other =
+            r#"42["new-trade",{"data":"{\"T\":1,\"p\":\"1\",\"q\":\"1\",\"s\":\"B-SOL_USDT\"}"}]"#;
+        assert_eq!(market(other), None);
+        assert_eq!(
+            join("I-BTC_INR@trades", Value::Null),
+            r#"42["join",{"channelName":"I-BTC_INR@trades"}]"#
+        );
+    }
+
+    #[test]
+    fn test_depth_events() {
+        let text = r#"42["depth-snapshot",{"channel":"I-BTC_INR@orderbook@20","data":"{\"ts\":1700000000000,\"vs\":42,\"bids\":{\"4999000\":\"0.5\",\"4998000\":\"1.2\"},\"asks\":{\"5001000\":\"0.3\"}}"}]"#;
+        let Frame::Event {
+            name,
+            data,
+            channel,
+        } = frame(text)
+        else {
+            panic!("not an event");
+        };
+        let update = depth_event(&name, &data, channel.as_deref(), &markets()).unwrap();
+        assert_eq!(update.symbol, "BTCINR");
+        assert!(update.is_snapshot);
+        assert_eq!(update.timestamp, 1_700_000_000_000);
+        assert_eq!(update.asks, vec![(5_001_000.0, 0.3)]);
+        assert_eq!(update.bids.len(), 2);
+
+        let patch = serde_json::json!({ "bids": { "4999000": 0 } });
+        let update = depth_event("depth-update", &patch, channel.as_deref(), &markets()).unwrap();
+        assert!(!update.is_snapshot);
+        assert_eq!(update.bids, vec![(4_999_000.0, 0.0)]);
+        assert!(depth_event("new-trade", &patch, channel.as_deref(), &markets()).is_none());
+    }
+
+    #[test]
+    fn test_user_events_from_batched_updates() {
+        let text = r#"42["order-update",{"data":"[{\"id\":\"ab-12\",\"client_order_id\":\"intent-7\",\"market\":\"BTCINR\",\"side\":\"buy\",\"status\":\"partially_filled\",\"total_quantity\":0.01,\"remaining_quantity\":\"0.004\",\"avg_price\":\"5000000\",\"fee_amount\":\"30\",\"updated_at\":1700000000000}]"}]"#;
+        let Frame::Event { name, data, .. } = frame(text) else {
+            panic!("not an event");
+        };
+        let events = user_events(&name, &data);
+        let [UserEvent::Order(order)] = events.as_slice() else {
+            panic!("expected one order update, got {:?}", events);
+        };
+        assert_eq!(order.client_order_id.as_deref(), Some("intent-7"));
+        assert!((order.filled_quantity() - 0.006).abs() < 1e-12);
+        assert_eq!(order.avg_price, 5_000_000.0);
+        assert!(!order.is_final());
+
+        let balances = serde_json::json!([
+            { "currency": "INR", "balance": "1000.5", "locked_balance": 0 },
+            { "currency": "BTC", "balance": 0.01, "locked_balance": "0.004" }
+        ]);
+        assert_eq!(
+            user_events("balance-update", &balances)[1],
+            UserEvent::Balance(BalanceUpdate {
+                currency: "BTC".to_string(),
+                balance: 0.01,
+                locked_balance: 0.004,
+            })
+        );
+
+        let auth = join("coindcx", serde_json::json!({ "apiKey": "k" }));
+        assert_eq!(auth, r#"42["join",{"apiKey":"k","channelName":"coindcx"}]"#);
+    }
+
+    #[test]
+    fn test_trade_candles_skip_partially_covered_bars() {
+        let minute = 60_000;
+        // Stream connected 10s into the first minute
+        let mut candles = TradeCandles::new(&[60], 10_000);
+        let trade = |timestamp: i64, price: f64| TradeUpdate {
+            symbol: "BTCINR".to_string(),
+            price,
+            quantity: 1.0,
+            timestamp,
+            buyer_is_maker: false,
+        };
+
+        candles.record(&trade(20_000, 100.0));
+        assert!(candles.current("BTCINR", 60, 30_000).is_none());
+
+        for (t, p) in [(minute + 1, 101.0), (minute + 5, 104.0), (minute + 9, 99.0)] {
+            candles.record(&trade(t, p));
+        }
+        candles.record(&trade(minute + 20, 102.0));
+        // A straggler from the previous bar changes nothing
+        candles.record(&trade(50_000, 500.0));
+
+        let bar = candles.current("BTCINR", 60, minute + 30).unwrap();
+        assert_eq!(bar.time, minute);
+        assert_eq!(
+            (bar.open, bar.high, bar.low, bar.close, bar.volume),
+            (101.0, 104.0, 99.0, 102.0, 4.0)
+        );
+        assert!(candles.current("BTCINR", 60, 2 * minute).is_none());
+        assert!(candles.current("ETHINR", 60, minute + 30).is_none());
+    }
+}
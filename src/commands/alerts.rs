@@ -0,0 +1,100 @@
+//! Alerts command - watchlist monitoring without a strategy
+//!
+//! Polls CoinDCX candles for every symbol in the alerts file, evaluates the
+//! configured indicator conditions and pushes triggered alerts to the log and
+//! any configured webhooks. No orders are placed.
+
+use anyhow::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{info, warn};
+
+use crypto_strategies::alerts::{AlertEvent, AlertMonitor, AlertsConfig};
+use crypto_strategies::coindcx::CoinDCXClient;
+use crypto_strategies::webhooks::{WebhookDispatcher, WebhookEventKind};
+use crypto_strategies::Candle;
+
+pub async fn run(alerts_path: String, once: bool) -> Result<()> {
+    let config = AlertsConfig::from_file(&alerts_path)?;
+    let symbols = config.symbols();
+    let limit = config.lookback().clamp(50, 1000) as u32;
+
+    info!("════════════════════════════════════════════════════════");
+    info!("🔔 WATCHLIST ALERTS");
+    info!("════════════════════════════════════════════════════════");
+    info!(
+        "Timeframe: {} (poll every {}s)",
+        config.timeframe, config.poll_secs
+    );
+    for rule in &config.alerts {
+        info!("  • {}", rule.label());
+    }
+
+    let exchange = CoinDCXClient::new("", "");
+    let webhooks = WebhookDispatcher::spawn(config.webhooks.clone());
+    let mut monitor = AlertMonitor::new(config.alerts.clone());
+
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let shutdown_clone = shutdown.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            shutdown_clone.store(true, Ordering::Relaxed);
+        }
+    });
+
+    let mut ticker = interval(Duration::from_secs(config.poll_secs.max(1)));
+    while !shutdown.load(Ordering::Relaxed) {
+        ticker.tick().await;
+
+        for symbol in &symbols {
+            let candles: Vec<Candle> = match exchange
+                .get_candles(symbol, &config.timeframe, Some(limit))
+                .await
+            {
+                Ok(raw) => raw.into_iter().filter_map(|c| c.try_into().ok()).collect(),
+                Err(e) => {
+                    warn!("Candle fetch failed for {}: {}", symbol, e);
+                    continue;
+                }
+            };
+
+            for event in monitor.check(symbol, &candles) {
+                report(&event);
+                if let Some(webhooks) = &webhooks {
+                    webhooks.notify_payload(
+                        WebhookEventKind::Alert,
+                        event.alert_id.clone(),
+                        &event,
+                    );
+                }
+            }
+        }
+
+        if once {
+            break;
+        }
+    }
+
+    // Give queued webhooks a moment to go out before exiting
+    if webhooks.is_some() {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+    info!("✓ Alerts stopped");
+    Ok(())
+}
+
+fn report(event: &AlertEvent) {
+    info!("🔔 ALERT: {}", event.name);
+    info!("   └─ Condition: {}", event.condition);
+    info!(
+        "   └─ Value:     {:.4} (reference {:.4})",
+        event.value, event.reference
+    );
+    info!("   └─ Price:     {:.2}", event.price);
+    info!(
+        "   └─ Bar:       {}",
+        event.bar_time.format("%Y-%m-%d %H:%M")
+    );
+}
@@ -2,12 +2,24 @@
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
+use crypto_strategies::attribution::Attribution;
+use crypto_strategies::backtest::{
+    give_back_stats, tranche_attribution, BacktestResult, Backtester, StopReason,
+};
+use crypto_strategies::config::{HomeCurrencyConfig, SameBarPolicy};
+use crypto_strategies::fx::{self, FxSeries, HedgedView, QuoteConverter};
 use crypto_strategies::monthly_pnl::MonthlyPnLMatrix;
+use crypto_strategies::multi_timeframe::MultiSymbolMultiTimeframeData;
 use crypto_strategies::multi_timeframe::MultiTimeframeData;
+use crypto_strategies::runs::{RunKind, RunMeta, RunRecord, RunRegistry, RunSummary};
 use crypto_strategies::strategies;
-use crypto_strategies::{backtest::Backtester, data, Config};
-use tracing::{debug, info};
+use crypto_strategies::stress::{self, StressOutcome};
+use crypto_strategies::{data, dataset, Config, PerformanceMetrics, Trade};
+use crypto_strategies::{fees, retention, universe};
+use std::collections::BTreeMap;
+use tracing::{debug, info, warn};
 
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     config_path: String,
     strategy_override: Option<String>,
@@ -16,12 +28,20 @@ pub fn run(
     end_override: Option<String>,
     no_risk_limits: bool,
     use_t1_execution: bool,
+    compare_trailing: bool,
+    compare_same_bar: bool,
+    attribution: bool,
+    meta: RunMeta,
 ) -> Result<()> {
     info!("Starting backtest");
 
     // Load configuration
-    let mut config = Config::from_file(&config_path)?;
+    let (mut config, resolved) = Config::from_file_resolved(&config_path)?;
     info!("Loaded configuration from: {}", config_path);
+    super::config::print_layers(&resolved);
+    universe::apply_universe_blocking(&mut config)?;
+    fees::apply_exchange_fees_blocking(&mut config)?;
+    retention::auto_prune(&config)?;
 
     // Apply overrides
     if let Some(strategy) = strategy_override {
@@ -129,6 +149,13 @@ pub fn run(
 
     info!("Loaded data for {} symbols", mtf_data.len());
 
+    if compare_trailing {
+        return compare_trailing_modes(&config, &mtf_data);
+    }
+    if compare_same_bar {
+        return compare_same_bar_policies(&config, &mtf_data);
+    }
+
     // Run backtest
     let mut backtester = Backtester::new(config.clone(), strategy);
     let result = backtester.run(&mtf_data);
@@ -159,18 +186,589 @@ pub fn run(
     println!("Average Loss:       ₹{:.2}", result.metrics.avg_loss);
     println!("Largest Win:        ₹{:.2}", result.metrics.largest_win);
     println!("Largest Loss:       ₹{:.2}", result.metrics.largest_loss);
+    if result.metrics.ambiguous_bars > 0 {
+        println!(
+            "Ambiguous Bars:     {} ({:?}; stop and target in one bar)",
+            result.metrics.ambiguous_bars, config.trading.same_bar_policy
+        );
+    }
+    if let Some(min_edge) = &config.trading.min_edge {
+        println!(
+            "Edge Rejections:    {} (target < {:.1}x round-trip cost)",
+            result.metrics.edge_rejections, min_edge.multiple
+        );
+    }
+    if !config.trading.pre_trade_checks.is_empty() {
+        println!(
+            "Pre-trade Rejects:  {}",
+            result.metrics.pre_trade_rejections
+        );
+    }
+    if config.trading.activity_filter.is_some() {
+        let skips = &result.metrics.activity_skips;
+        println!(
+            "Inactive Skips:     {} bar(s){}",
+            skips.values().sum::<usize>(),
+            if skips.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    " ({})",
+                    skips
+                        .iter()
+                        .map(|(symbol, bars)| format!("{} {}", symbol, bars))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+        );
+    }
+    if let Some(hedge) = &result.metrics.hedge {
+        println!(
+            "Hedge:              {} opened, {} resized, {} bar(s) hedged, net {:.2}",
+            hedge.opened, hedge.resized, hedge.hedged_bars, hedge.net_pnl
+        );
+    }
+    if config.trading.trailing_drawdown.is_some() {
+        match result
+            .truncated
+            .as_ref()
+            .filter(|t| t.reason == StopReason::TrailingDrawdown)
+        {
+            Some(t) => println!(
+                "Trailing DD:        BREACHED {} (positions closed, run stopped)",
+                t.at.format("%Y-%m-%d %H:%M")
+            ),
+            None => println!("Trailing DD:        held"),
+        }
+    }
+    if config.trading.risk_budget.is_some() {
+        println!(
+            "Budget Limited:     {} (entries shrunk or skipped by the daily risk budget)",
+            result.metrics.budget_limited
+        );
+    }
+    if config.trading.exit_ladder.is_some() {
+        println!("{}", "-".repeat(60));
+        println!("Exit Ladder         Trades    Avg R    Net P&L");
+        for t in tranche_attribution(&result.trades) {
+            println!(
+                "  {:<17}{:>7}  {:>7.2}  ₹{:.2}",
+                t.tranche, t.trades, t.avg_r, t.net_pnl
+            );
+        }
+    }
     println!("{}", "-".repeat(60));
     println!(
         "Total Commission:   ₹{:.2}",
         result.metrics.total_commission
     );
+    for (kind, amount) in &result.metrics.fees_by_type {
+        let label = format!("  {}:", kind.as_str().to_uppercase());
+        let note = if kind.is_cost() { "" } else { " (withheld)" };
+        println!("{:<20}₹{:.2}{}", label, amount, note);
+    }
     println!("Tax (30%):          ₹{:.2}", result.metrics.tax_amount);
+    println!("{}", "-".repeat(60));
+    println!("Tail Risk (loss %)   VaR95   CVaR95    VaR99   CVaR99");
+    let tail = &result.metrics.tail_risk;
+    for (label, est) in [
+        ("Daily (hist)", &tail.daily_historical),
+        ("Daily (boot)", &tail.daily_bootstrap),
+        ("Trade (hist)", &tail.trade_historical),
+        ("Trade (boot)", &tail.trade_bootstrap),
+    ] {
+        println!(
+            "  {:<17}{:>7.2}  {:>7.2}  {:>7.2}  {:>7.2}",
+            label, est.var_95, est.cvar_95, est.var_99, est.cvar_99
+        );
+    }
     println!("{}", "=".repeat(60));
 
+    if let Some(home) = &config.backtest.home_currency {
+        print_home_currency_view(&config, home, &primary_tf, &result.equity_curve);
+        print_combined_pnl(&config, home, &primary_tf, &result.trades);
+    }
+
+    let stress = print_stress_report(&config, &mtf_data, &result);
+    let attribution = if attribution {
+        match Attribution::run(&config, &mtf_data, &result) {
+            Ok(attribution) => {
+                print!("{}", attribution.render());
+                Some(attribution)
+            }
+            Err(e) => {
+                warn!("Skipping P&L attribution: {:#}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     // Monthly P&L matrix
     let monthly = MonthlyPnLMatrix::from_trades(&result.trades);
     print!("{}", monthly.render_colored());
 
+    record_run(
+        &config_path,
+        &config,
+        &result,
+        &stress,
+        attribution.as_ref(),
+        &mtf_data,
+        meta,
+    );
+
     info!("Backtest completed");
     Ok(())
 }
+
+/// Rate series of `fx_symbol` from `data_dir`, at the strategy's timeframe or else daily
+fn load_fx_series(config: &Config, fx_symbol: &str, primary_tf: &str) -> FxSeries {
+    [primary_tf, "1d"]
+        .iter()
+        .find_map(|tf| data::load_tiered(&config.backtest.data_dir, fx_symbol, tf).ok()?)
+        .map(|c| FxSeries::from_candles(&c))
+        .unwrap_or_default()
+}
+
+/// Print asset- vs home-currency performance using the configured FX series
+fn print_home_currency_view(
+    config: &Config,
+    home: &HomeCurrencyConfig,
+    primary_tf: &str,
+    equity_curve: &[(DateTime<Utc>, f64)],
+) {
+    let fx = load_fx_series(config, &home.fx_symbol, primary_tf);
+    if fx.is_empty() {
+        warn!(
+            "No {} data in {} - skipping {} view (download it with --source coindcx)",
+            home.fx_symbol, config.backtest.data_dir, home.currency
+        );
+        return;
+    }
+
+    let view = HedgedView::new(equity_curve, &fx);
+    println!("\n{}", "=".repeat(60));
+    println!(
+        "HOME CURRENCY VIEW ({} via {})",
+        home.currency, home.fx_symbol
+    );
+    println!("{}", "=".repeat(60));
+    println!("{:<20}{:>14}{:>14}", "", "Asset", home.currency);
+    let row = |label: &str, asset: f64, home: f64, unit: &str| {
+        println!("{:<20}{:>13.2}{}{:>13.2}{}", label, asset, unit, home, unit);
+    };
+    row(
+        "Total Return:",
+        view.asset.total_return,
+        view.home.total_return,
+        "%",
+    );
+    row(
+        "Max Drawdown:",
+        view.asset.max_drawdown,
+        view.home.max_drawdown,
+        "%",
+    );
+    row(
+        "Sharpe Ratio:",
+        view.asset.sharpe_ratio,
+        view.home.sharpe_ratio,
+        " ",
+    );
+    row(
+        "Calmar Ratio:",
+        view.asset.calmar_ratio,
+        view.home.calmar_ratio,
+        " ",
+    );
+    println!("{}", "-".repeat(60));
+    println!(
+        "{} move over period: {:+.2}% (unhedged exposure)",
+        home.fx_symbol, view.fx_change
+    );
+    println!("{}", "=".repeat(60));
+}
+
+/// Print trade P&L per quote currency and its total in the home currency
+///
+/// Only when the symbols are quoted in more than one currency; each trade is
+/// converted at the rate of its exit.
+fn print_combined_pnl(
+    config: &Config,
+    home: &HomeCurrencyConfig,
+    primary_tf: &str,
+    trades: &[Trade],
+) {
+    let symbols = config.trading.symbols.iter().map(String::as_str);
+    let mut quotes: Vec<&str> = symbols.clone().filter_map(fx::quote_currency).collect();
+    quotes.sort_unstable();
+    quotes.dedup();
+    if quotes.len() < 2 {
+        return;
+    }
+    let mut converter = QuoteConverter::new(&home.currency);
+    for quote in converter.foreign_quotes(symbols) {
+        let series = load_fx_series(config, &converter.fx_symbol(quote), primary_tf);
+        converter = converter.with_series(quote, series);
+    }
+
+    let mut by_quote: BTreeMap<&str, (f64, f64)> = BTreeMap::new();
+    let mut unconverted = 0;
+    for trade in trades {
+        let symbol = trade.symbol.as_str();
+        let net_pnl = trade.net_pnl.to_f64();
+        let quote = fx::quote_currency(symbol).unwrap_or(home.currency.as_str());
+        let entry = by_quote.entry(quote).or_default();
+        entry.0 += net_pnl;
+        match converter.to_home(symbol, net_pnl, trade.exit_time) {
+            Some(value) => entry.1 += value,
+            None => unconverted += 1,
+        }
+    }
+
+    println!("\n{}", "=".repeat(60));
+    println!("COMBINED P&L ({})", home.currency);
+    println!("{}", "=".repeat(60));
+    for (quote, (pnl, home_pnl)) in &by_quote {
+        println!(
+            "{:<20}{:>14.2} {:<5}{:>14.2} {}",
+            format!("{} pairs:", quote),
+            pnl,
+            quote,
+            home_pnl,
+            home.currency
+        );
+    }
+    let total: f64 = by_quote.values().map(|(_, home_pnl)| home_pnl).sum();
+    println!("{}", "-".repeat(60));
+    println!("{:<40}{:>14.2} {}", "Total:", total, home.currency);
+    if unconverted > 0 {
+        warn!(
+            "{} trade(s) left out of the {} total: no rate data (download {})",
+            unconverted,
+            home.currency,
+            quotes
+                .iter()
+                .filter(|q| **q != home.currency)
+                .map(|q| converter.fx_symbol(q))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    println!("{}", "=".repeat(60));
+}
+
+/// Rerun the backtest under each configured stress scenario and print how it held up
+fn print_stress_report(
+    config: &Config,
+    data: &MultiSymbolMultiTimeframeData,
+    baseline: &BacktestResult,
+) -> Vec<StressOutcome> {
+    let scenarios = &config.backtest.stress_scenarios;
+    if scenarios.is_empty() {
+        return Vec::new();
+    }
+    let default_at = stress::default_shock_time(&baseline.trades, data);
+
+    let mut outcomes = Vec::new();
+    for scenario in scenarios {
+        let at = match &scenario.at {
+            Some(at) => data::parse_date(at).map(Some),
+            None => Ok(default_at),
+        };
+        let outcome = at.and_then(|at| {
+            let at = at.ok_or_else(|| anyhow::anyhow!("no data to shock"))?;
+            stress::run(config, data, scenario, at, baseline)
+        });
+        match outcome {
+            Ok(outcome) => outcomes.push(outcome),
+            Err(e) => warn!("Skipping stress scenario {}: {:#}", scenario.name, e),
+        }
+    }
+    if outcomes.is_empty() {
+        return outcomes;
+    }
+
+    println!("\n{}", "=".repeat(60));
+    println!("STRESS SCENARIOS");
+    println!("{}", "=".repeat(60));
+    println!(
+        "{:<18} {:>8} {:>7} {:>11} {:>7} {:>6}",
+        "Scenario", "Return%", "MaxDD%", "Shock P&L", "Entries", "Recov"
+    );
+    println!("{}", "-".repeat(60));
+    println!(
+        "{:<18} {:>8.2} {:>7.2} {:>11} {:>7} {:>6}",
+        "Baseline", baseline.metrics.total_return, baseline.metrics.max_drawdown, "-", "-", "-"
+    );
+    for o in &outcomes {
+        let recovery = o
+            .recovery_days
+            .map_or("never".to_string(), |days| format!("{:.0}d", days));
+        println!(
+            "{:<18} {:>8.2} {:>7.2} {:>11.2} {:>7} {:>6}",
+            o.name,
+            o.total_return,
+            o.max_drawdown,
+            o.shock_pnl,
+            format!("{}/{}", o.entries, o.baseline_entries),
+            recovery
+        );
+    }
+    println!("{}", "-".repeat(60));
+    for o in &outcomes {
+        println!(
+            "{}: {} at {}; baseline P&L over window ₹{:.2}, worst trade ₹{:.2}",
+            o.name,
+            o.shock,
+            o.at.format("%Y-%m-%d %H:%M"),
+            o.baseline_shock_pnl,
+            o.worst_trade
+        );
+        if let Some(t) = &o.truncated {
+            println!(
+                "  {:?} stop {} (positions closed, run stopped)",
+                t.reason,
+                t.at.format("%Y-%m-%d %H:%M")
+            );
+        }
+    }
+    println!(
+        "Entries = shocked/baseline entries up to {}d after the window",
+        stress::RESPONSE_DAYS
+    );
+    println!("{}", "=".repeat(60));
+    outcomes
+}
+
+/// Add the run to the registry with its result as an artifact (failures are only logged)
+fn record_run(
+    config_path: &str,
+    config: &Config,
+    result: &BacktestResult,
+    stress: &[StressOutcome],
+    attribution: Option<&Attribution>,
+    data: &MultiSymbolMultiTimeframeData,
+    meta: RunMeta,
+) {
+    let primary = data.values().map(|mtf| mtf.primary());
+    let data_start = primary
+        .clone()
+        .filter_map(|c| c.first())
+        .map(|c| c.datetime)
+        .min();
+    let data_end = primary.filter_map(|c| c.last()).map(|c| c.datetime).max();
+
+    let mut data_hashes = BTreeMap::new();
+    for (symbol, mtf) in data {
+        let hashes = dataset::data_hashes(
+            &config.backtest.data_dir,
+            [symbol.as_str()],
+            &mtf.timeframes(),
+        );
+        match hashes {
+            Ok(hashes) => data_hashes.extend(hashes),
+            Err(e) => warn!("Failed to hash {} data: {:#}", symbol, e),
+        }
+    }
+
+    let mut record = RunRecord::new(
+        RunKind::Backtest,
+        config_path,
+        config,
+        RunSummary::from(&result.metrics),
+    )
+    .with_name(meta.name)
+    .with_data_range(data_start, data_end)
+    .with_data_hashes(data_hashes);
+    if let Some(log_file) = meta.log_file {
+        record = record.with_artifact(log_file);
+    }
+
+    let registry = RunRegistry::new(&config.backtest.results_dir);
+    let artifact = serde_json::json!({
+        "metrics": result.metrics,
+        "trades": result.trades,
+        "equity_curve": result.equity_curve,
+        "stress": stress,
+        "attribution": attribution,
+    });
+    let registered = registry
+        .write_artifact(&record.id, "result.json", &artifact)
+        .and_then(|path| registry.register(record.with_artifact(path)));
+    match registered {
+        Ok(run) => println!(
+            "Run ID: {} (crypto-strategies runs show {})",
+            run.id,
+            run.label()
+        ),
+        Err(e) => warn!("Failed to record run: {:#}", e),
+    }
+}
+
+/// Run the backtest with bar-close and intrabar trailing stops and compare give-back
+fn compare_trailing_modes(config: &Config, data: &MultiSymbolMultiTimeframeData) -> Result<()> {
+    println!("\n{}", "=".repeat(60));
+    println!("TRAILING STOP COMPARISON");
+    println!("{}", "=".repeat(60));
+    println!(
+        "{:<10} {:>7} {:>9} {:>8} {:>9} {:>10} {:>8}",
+        "Mode", "Trades", "Return%", "Sharpe", "MFE%", "GiveBack%", "Capture"
+    );
+    println!("{}", "-".repeat(60));
+
+    for (label, intrabar) in [("bar-close", false), ("intrabar", true)] {
+        let mut run_config = config.clone();
+        run_config.trading.intrabar_trailing = intrabar;
+        let strategy = strategies::create_strategy(&run_config)?;
+        let result = Backtester::new(run_config, strategy).run(data);
+        let give_back = give_back_stats(&result.trades, data);
+
+        println!(
+            "{:<10} {:>7} {:>9.2} {:>8.2} {:>9.2} {:>10.2} {:>7.0}%",
+            label,
+            result.metrics.total_trades,
+            result.metrics.total_return,
+            result.metrics.sharpe_ratio,
+            give_back.avg_mfe_pct,
+            give_back.avg_give_back_pct,
+            give_back.capture_ratio * 100.0
+        );
+    }
+    println!("{}", "=".repeat(60));
+    println!("GiveBack% = average drop from the trade's best price to its exit (% of entry)");
+
+    info!("Trailing comparison completed");
+    Ok(())
+}
+
+/// Run the backtest under each same-bar policy to show how much ambiguous bars move the result
+fn compare_same_bar_policies(config: &Config, data: &MultiSymbolMultiTimeframeData) -> Result<()> {
+    let runs = same_bar_policy_runs(config, data)?;
+
+    println!("\n{}", "=".repeat(60));
+    println!("SAME-BAR POLICY COMPARISON");
+    println!("{}", "=".repeat(60));
+    println!(
+        "{:<12} {:>7} {:>9} {:>8} {:>8} {:>10}",
+        "Policy", "Trades", "Return%", "Sharpe", "Win%", "Ambiguous"
+    );
+    println!("{}", "-".repeat(60));
+    for (label, metrics) in &runs {
+        println!(
+            "{:<12} {:>7} {:>9.2} {:>8.2} {:>8.2} {:>10}",
+            label,
+            metrics.total_trades,
+            metrics.total_return,
+            metrics.sharpe_ratio,
+            metrics.win_rate,
+            metrics.ambiguous_bars
+        );
+    }
+    println!("{}", "=".repeat(60));
+    println!("stop_first is the worst case and target_first the best; a wide gap means");
+    println!("the result depends on intrabar order the bars don't record");
+
+    info!("Same-bar comparison completed");
+    Ok(())
+}
+
+/// Metrics of the same backtest under each same-bar policy, worst case first
+fn same_bar_policy_runs(
+    config: &Config,
+    data: &MultiSymbolMultiTimeframeData,
+) -> Result<Vec<(&'static str, PerformanceMetrics)>> {
+    [
+        ("stop_first", SameBarPolicy::StopFirst),
+        ("path", SameBarPolicy::Path),
+        ("target_first", SameBarPolicy::TargetFirst),
+    ]
+    .into_iter()
+    .map(|(label, policy)| {
+        let mut run_config = config.clone();
+        run_config.trading.same_bar_policy = policy;
+        let strategy = strategies::create_strategy(&run_config)?;
+        let result = Backtester::new(run_config, strategy).run(data);
+        Ok((label, result.metrics))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crypto_strategies::oms::{OrderRequest, Position};
+    use crypto_strategies::strategies::{SignalContext, Strategy};
+    use crypto_strategies::{Candle, Side, Symbol};
+
+    /// Buys whenever flat; stop and target 5% away
+    struct BuyWhenFlat;
+
+    impl Strategy for BuyWhenFlat {
+        fn name(&self) -> &'static str {
+            "same_bar_buy_when_flat"
+        }
+        fn clone_boxed(&self) -> Box<dyn Strategy> {
+            Box::new(BuyWhenFlat)
+        }
+        fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
+            match ctx.current_position {
+                Some(_) => Vec::new(),
+                None => vec![OrderRequest::market_buy(ctx.symbol.clone(), 1.0)],
+            }
+        }
+        fn calculate_stop_loss(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+            entry_price * 0.95
+        }
+        fn calculate_take_profit(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+            entry_price * 1.05
+        }
+        fn update_trailing_stop(&self, _: &Position, _: f64, _: &[Candle]) -> Option<f64> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_same_bar_policies_order_returns() {
+        strategies::register_strategy("same_bar_buy_when_flat", |_| Ok(Box::new(BuyWhenFlat)));
+        let mut config: Config =
+            serde_json::from_str(include_str!("../../configs/sample_config.json")).unwrap();
+        config.trading.symbols = vec!["BTCINR".to_string()];
+        config.strategy =
+            serde_json::json!({ "name": "same_bar_buy_when_flat", "timeframe": "1d" });
+
+        // Every bar opens at 103, closes at 100 and spans the stop and target of
+        // an entry there; the high is nearer the open on every other pair of bars
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let candles: Vec<Candle> = (0..120)
+            .map(|i| {
+                let high = if i % 4 < 2 { 140.0 } else { 150.0 };
+                Candle::new(
+                    start + chrono::Duration::days(i),
+                    103.0,
+                    high,
+                    60.0,
+                    100.0,
+                    1_000.0,
+                )
+                .unwrap()
+            })
+            .collect();
+        let mut mtf = MultiTimeframeData::new("1d");
+        mtf.add_timeframe("1d", candles);
+        let data = MultiSymbolMultiTimeframeData::from([(Symbol::new("BTCINR"), mtf)]);
+
+        let runs = same_bar_policy_runs(&config, &data).unwrap();
+        let labels: Vec<_> = runs.iter().map(|(label, _)| *label).collect();
+        assert_eq!(labels, ["stop_first", "path", "target_first"]);
+        let [stop_first, path, target_first] = [0, 1, 2].map(|i| &runs[i].1);
+        assert!(stop_first.ambiguous_bars > 0);
+        assert!(stop_first.total_return <= path.total_return);
+        assert!(path.total_return <= target_first.total_return);
+        assert!(stop_first.total_return < target_first.total_return);
+    }
+}
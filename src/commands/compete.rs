@@ -0,0 +1,140 @@
+//! Compete command - paper-trade several configs side by side and rank them
+//!
+//! Each config runs as its own commission-free paper account (state under
+//! `<state-dir>/<name>/`), all reading the same live candles through one
+//! shared feed. Standings are exported to `leaderboard.csv` / `leaderboard.json`
+//! every `export_mins` and on shutdown; a restarted competition picks up the
+//! previous standings from the JSON.
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tracing::{error, info, warn};
+
+use crypto_strategies::leaderboard::{Leaderboard, SharedLeaderboard};
+use crypto_strategies::Config;
+
+use super::live::{LiveTrader, SharedFeed};
+
+pub async fn run(
+    configs: Vec<String>,
+    state_dir: String,
+    output_dir: Option<String>,
+    export_mins: u64,
+    feed_ttl_secs: u64,
+) -> Result<()> {
+    if configs.len() < 2 {
+        anyhow::bail!("A competition needs at least two configs");
+    }
+
+    let mut entrants = Vec::new();
+    let mut names = HashSet::new();
+    for path in &configs {
+        let name = Path::new(path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .with_context(|| format!("Invalid config path {}", path))?
+            .to_string();
+        if !names.insert(name.clone()) {
+            anyhow::bail!("Two configs are named {}; rename one of the files", name);
+        }
+        let (mut config, _) = Config::from_file_resolved(path)?;
+        crypto_strategies::universe::apply_universe(&mut config).await?;
+        // Commission-free, and nothing an entrant does leaves the process
+        config.exchange.maker_fee = 0.0;
+        config.exchange.taker_fee = 0.0;
+        config.exchange.orderbook_recorder = None;
+        config.exchange.reconcile = None;
+        config.exchange.audit_trail = None;
+        entrants.push((name, path.clone(), config));
+    }
+
+    let output_dir = PathBuf::from(
+        output_dir.unwrap_or_else(|| format!("{}/compete", entrants[0].2.backtest.results_dir)),
+    );
+    std::fs::create_dir_all(&output_dir)?;
+    let json_path = output_dir.join("leaderboard.json");
+    let board = match std::fs::read_to_string(&json_path) {
+        Ok(json) => {
+            info!("✓ Resuming standings from {}", json_path.display());
+            Leaderboard::from_json(&json)
+                .with_context(|| format!("Invalid leaderboard {}", json_path.display()))?
+        }
+        Err(_) => Leaderboard::default(),
+    }
+    .shared();
+
+    let feed = SharedFeed::new(Duration::from_secs(feed_ttl_secs));
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let mut traders = JoinSet::new();
+    for (name, path, config) in entrants {
+        let state_db = Path::new(&state_dir).join(&name).join("state.db");
+        std::fs::create_dir_all(state_db.parent().unwrap_or(Path::new(".")))?;
+        let mut trader = LiveTrader::new(
+            config,
+            PathBuf::from(&path),
+            &state_db.to_string_lossy(),
+            true,
+        )
+        .await?
+        .with_feed(feed.clone())
+        .with_competition(board.clone(), &name);
+        trader.recover_state().await?;
+        info!(
+            "🏁 Entrant {} ({}) from {}",
+            name,
+            trader.strategy_name(),
+            path
+        );
+
+        let shutdown = shutdown.clone();
+        traders.spawn(async move {
+            let result = trader.run(shutdown).await;
+            (name, result)
+        });
+    }
+
+    let shutdown_clone = shutdown.clone();
+    tokio::spawn(async move {
+        tokio::signal::ctrl_c().await.ok();
+        info!("🛑 Ctrl+C detected - stopping all entrants...");
+        shutdown_clone.store(true, Ordering::Relaxed);
+    });
+
+    let mut ticker = tokio::time::interval(Duration::from_secs(export_mins.max(1) * 60));
+    ticker.tick().await;
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => export(&board, &output_dir),
+            joined = traders.join_next() => match joined {
+                Some(Ok((name, Ok(())))) => info!("✓ Entrant {} stopped", name),
+                Some(Ok((name, Err(e)))) => error!("❌ Entrant {} failed: {}", name, e),
+                Some(Err(e)) => error!("❌ Entrant task panicked: {}", e),
+                None => break,
+            },
+        }
+    }
+
+    export(&board, &output_dir);
+    Ok(())
+}
+
+/// Write the leaderboard files and log the table
+fn export(board: &SharedLeaderboard, dir: &Path) {
+    let board = board.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    info!("🏆 Leaderboard\n{}", board.render());
+    let written = std::fs::write(dir.join("leaderboard.csv"), board.to_csv_string())
+        .map_err(anyhow::Error::from)
+        .and_then(|_| {
+            let json = board.to_json()?;
+            std::fs::write(dir.join("leaderboard.json"), json)?;
+            Ok(())
+        });
+    if let Err(e) = written {
+        warn!("⚠️  Failed to export leaderboard: {}", e);
+    }
+}
@@ -0,0 +1,67 @@
+//! Config command - show how a config resolves through `extends`, overlays and `--set`
+//!
+//! See `crypto_strategies::config_layers` for the merge rules.
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crypto_strategies::config_layers::ResolvedConfig;
+use crypto_strategies::Config;
+
+/// Widest value shown before truncating
+const MAX_VALUE_WIDTH: usize = 40;
+
+pub fn show(config_path: String) -> Result<()> {
+    let (_, resolved) = Config::from_file_resolved(&config_path)?;
+
+    print_sources(&resolved);
+    println!("{}", "-".repeat(60));
+    for (path, source) in &resolved.provenance {
+        print_field(&resolved, path, source);
+    }
+    println!("{}", "=".repeat(60));
+    Ok(())
+}
+
+/// Before a run: list the layers and every field the requested file didn't set itself
+pub fn print_layers(resolved: &ResolvedConfig) {
+    if !resolved.is_layered() {
+        return;
+    }
+    print_sources(resolved);
+    let inherited: Vec<_> = resolved.inherited().collect();
+    if !inherited.is_empty() {
+        println!("{}", "-".repeat(60));
+        for (path, source) in inherited {
+            print_field(resolved, path, source);
+        }
+    }
+    println!("{}", "=".repeat(60));
+}
+
+fn print_sources(resolved: &ResolvedConfig) {
+    println!("\n{}", "=".repeat(60));
+    println!("CONFIG LAYERS (later wins)");
+    println!("{}", "=".repeat(60));
+    for (i, source) in resolved.sources.iter().enumerate() {
+        println!("  {}. {}", i + 1, source);
+    }
+}
+
+fn print_field(resolved: &ResolvedConfig, path: &str, source: &str) {
+    let value = path
+        .split('.')
+        .try_fold(&resolved.value, |node, part| node.get(part))
+        .map(render)
+        .unwrap_or_default();
+    println!("  {:<36} {:<MAX_VALUE_WIDTH$}  {}", path, value, source);
+}
+
+fn render(value: &Value) -> String {
+    let text = value.to_string();
+    if text.chars().count() <= MAX_VALUE_WIDTH {
+        return text;
+    }
+    let cut: String = text.chars().take(MAX_VALUE_WIDTH - 3).collect();
+    format!("{}...", cut)
+}
@@ -0,0 +1,273 @@
+//! Data command - retention and cold-tier compaction for downloaded candles,
+//! orderbook snapshot recording, dataset export/import and perp positioning history
+//!
+//! See `crypto_strategies::retention` for how the warm (CSV) and cold
+//! (parquet) tiers are laid out, `crypto_strategies::book_recorder` for
+//! the snapshot format, `crypto_strategies::dataset` for the archive format
+//! and `crypto_strategies::positioning` for the basis/funding CSVs.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crypto_strategies::binance::BinanceClient;
+use crypto_strategies::book_recorder;
+use crypto_strategies::coindcx::{ClientConfig, CoinDCXClient};
+use crypto_strategies::dataset::{self, DatasetManifest};
+use crypto_strategies::positioning::{positioning_path, PositioningSeries};
+use crypto_strategies::retention;
+use crypto_strategies::Config;
+
+pub fn prune(config_path: String, dry_run: bool) -> Result<()> {
+    let config = Config::from_file(&config_path)?;
+    let data_dir = &config.backtest.data_dir;
+    let policy = &config.backtest.data_retention;
+
+    println!("\n{}", "=".repeat(60));
+    println!("DATA RETENTION{}", if dry_run { " (DRY RUN)" } else { "" });
+    println!("{}", "=".repeat(60));
+    println!("  Data dir:        {}", data_dir);
+
+    if policy.is_noop() {
+        println!("  No data_retention policy configured - all data is kept");
+        println!("{}", "=".repeat(60));
+        return Ok(());
+    }
+    print_policy("Max age (days):", &policy.max_age_days);
+    print_policy("Compact after:", &policy.compact_after_days);
+    println!();
+
+    let actions = retention::enforce(data_dir, policy, Utc::now(), dry_run)?;
+    if actions.is_empty() {
+        println!("  Nothing to do - data already within policy");
+        println!("{}", "=".repeat(60));
+        return Ok(());
+    }
+
+    println!(
+        "  {:<16} {:>4} {:>10} {:>10} {:>10} {:>10}",
+        "Symbol", "TF", "Warm", "Cold", "Compact", "Prune"
+    );
+    println!("  {}", "-".repeat(56));
+    for a in &actions {
+        println!(
+            "  {:<16} {:>4} {:>10} {:>10} {:>10} {:>10}",
+            a.symbol, a.timeframe, a.warm_rows, a.cold_rows, a.compacted_rows, a.pruned_rows
+        );
+    }
+
+    let compacted: usize = actions.iter().map(|a| a.compacted_rows).sum();
+    let pruned: usize = actions.iter().map(|a| a.pruned_rows).sum();
+    let bytes: u64 = actions.iter().map(|a| a.bytes_before).sum();
+    println!("  {}", "-".repeat(56));
+    println!(
+        "  {} series ({:.1} MB on disk): {} rows {}compacted, {} rows {}removed",
+        actions.len(),
+        bytes as f64 / 1_048_576.0,
+        compacted,
+        if dry_run { "would be " } else { "" },
+        pruned,
+        if dry_run { "would be " } else { "" },
+    );
+    println!("{}", "=".repeat(60));
+    if dry_run {
+        println!("Re-run without --dry-run to apply.");
+    }
+    Ok(())
+}
+
+pub async fn record_books(
+    config_path: String,
+    symbols: Option<Vec<String>>,
+    depth: Option<usize>,
+    interval_secs: Option<u64>,
+    rounds: Option<u64>,
+) -> Result<()> {
+    let config = Config::from_file(&config_path)?;
+    let mut recorder = config
+        .exchange
+        .orderbook_recorder
+        .clone()
+        .unwrap_or_default();
+    if let Some(depth) = depth {
+        recorder.depth = depth;
+    }
+    if let Some(secs) = interval_secs {
+        recorder.interval_secs = secs;
+    }
+    let symbols = symbols
+        .or_else(|| Some(recorder.symbols.clone()).filter(|s| !s.is_empty()))
+        .unwrap_or_else(|| config.trading.symbols.clone());
+    if symbols.is_empty() {
+        anyhow::bail!("No symbols to record (pass --symbols or set trading.symbols)");
+    }
+
+    let client_config = ClientConfig {
+        network: config.exchange.network()?,
+        ..Default::default()
+    }
+    .with_rate_limit(config.exchange.rate_limit as usize);
+    let client = CoinDCXClient::with_config("", "", client_config);
+
+    let shutdown = Arc::new(AtomicBool::new(false));
+    let flag = shutdown.clone();
+    tokio::spawn(async move {
+        let _ = tokio::signal::ctrl_c().await;
+        flag.store(true, Ordering::Relaxed);
+    });
+
+    println!("\n{}", "=".repeat(60));
+    println!("ORDERBOOK RECORDER");
+    println!("{}", "=".repeat(60));
+    println!("  Symbols:         {}", symbols.join(", "));
+    println!("  Depth:           {} levels", recorder.depth);
+    println!("  Interval:        {}s", recorder.interval_secs);
+    println!("  Output dir:      {}", recorder.output_dir);
+    println!("  Press Ctrl+C to stop");
+
+    let output_dir = recorder.output_dir.clone();
+    let stats = book_recorder::run(client, recorder, symbols, shutdown, None, rounds).await;
+    println!("{}", "-".repeat(60));
+    println!(
+        "  {} rounds: {} snapshots written, {} failed -> {}",
+        stats.rounds, stats.written, stats.failed, output_dir
+    );
+    println!("{}", "=".repeat(60));
+    Ok(())
+}
+
+pub fn export(
+    config_path: String,
+    symbols: Option<Vec<String>>,
+    timeframes: Option<Vec<String>>,
+    output: String,
+    source: String,
+    license: Option<String>,
+) -> Result<()> {
+    let config = Config::from_file(&config_path)?;
+    let symbols = symbols.unwrap_or_else(|| config.trading.symbols.clone());
+    let timeframes = timeframes.unwrap_or_else(|| vec![config.timeframe()]);
+    let pairs: Vec<(String, String)> = symbols
+        .iter()
+        .flat_map(|s| timeframes.iter().map(move |tf| (s.clone(), tf.clone())))
+        .collect();
+
+    let manifest =
+        dataset::export_dataset(&config.backtest.data_dir, &pairs, &output, &source, license)?;
+    print_manifest("DATASET EXPORTED", &output, &manifest);
+    Ok(())
+}
+
+pub fn import(archive: String, config_path: String, force: bool) -> Result<()> {
+    let config = Config::from_file(&config_path)?;
+    let manifest = dataset::import_dataset(&archive, &config.backtest.data_dir, force)?;
+    print_manifest("DATASET IMPORTED", &archive, &manifest);
+    println!("  Written to {}", config.backtest.data_dir);
+    Ok(())
+}
+
+pub fn verify(archive: String) -> Result<()> {
+    let manifest = dataset::verify_dataset(&archive)?;
+    print_manifest("DATASET VERIFIED", &archive, &manifest);
+    println!("  All {} checksums match", manifest.entries.len());
+    Ok(())
+}
+
+pub async fn fetch_positioning(
+    config_path: String,
+    symbols: Option<Vec<String>>,
+    days: u32,
+    output: String,
+) -> Result<()> {
+    let symbols = match symbols {
+        Some(s) => s,
+        None => Config::from_file(&config_path)?.trading.symbols,
+    };
+    if symbols.is_empty() {
+        anyhow::bail!("No symbols to fetch (pass --symbols or set trading.symbols)");
+    }
+    let client = BinanceClient::new();
+
+    println!("\n{}", "=".repeat(60));
+    println!("PERP POSITIONING HISTORY");
+    println!("{}", "=".repeat(60));
+    println!(
+        "  {:<12} {:>8} {:>20} {:>10} {:>10}",
+        "Symbol", "Samples", "Latest", "Basis", "Funding"
+    );
+    println!("  {}", "-".repeat(64));
+    for symbol in &symbols {
+        let path = positioning_path(&output, symbol);
+        // Keep older history already on disk; fresh samples win on overlap
+        let mut samples = client.fetch_positioning_history(symbol, days).await?;
+        if path.exists() {
+            samples.extend(PositioningSeries::load(&path)?.samples().iter().copied());
+        }
+        let series = PositioningSeries::new(samples);
+        series.save(&path)?;
+        match series.samples().last() {
+            Some(last) => println!(
+                "  {:<12} {:>8} {:>20} {:>9.3}% {:>9.4}%",
+                symbol,
+                series.samples().len(),
+                last.datetime.format("%Y-%m-%d %H:%M"),
+                last.basis * 100.0,
+                last.funding_rate * 100.0
+            ),
+            None => println!("  {:<12} {:>8}", symbol, 0),
+        }
+    }
+    println!("{}", "-".repeat(60));
+    println!("  Written to {}", output);
+    println!("{}", "=".repeat(60));
+    Ok(())
+}
+
+fn print_manifest(title: &str, archive: &str, manifest: &DatasetManifest) {
+    let date = |d: Option<chrono::DateTime<Utc>>| {
+        d.map(|d| d.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    println!("\n{}", "=".repeat(60));
+    println!("{}", title);
+    println!("{}", "=".repeat(60));
+    println!("  Archive:         {}", archive);
+    println!("  Dataset hash:    {}", &manifest.sha256[..16]);
+    println!("  Source:          {}", manifest.source);
+    if let Some(license) = &manifest.license {
+        println!("  License:         {}", license);
+    }
+    println!();
+    println!(
+        "  {:<12} {:>4} {:>8} {:>11} {:>11}  SHA-256",
+        "Symbol", "TF", "Rows", "Start", "End"
+    );
+    println!("  {}", "-".repeat(56));
+    for e in &manifest.entries {
+        println!(
+            "  {:<12} {:>4} {:>8} {:>11} {:>11}  {}",
+            e.symbol,
+            e.timeframe,
+            e.rows,
+            date(e.start),
+            date(e.end),
+            &e.sha256[..12]
+        );
+    }
+    println!("{}", "=".repeat(60));
+}
+
+fn print_policy(label: &str, days: &std::collections::HashMap<String, u32>) {
+    if days.is_empty() {
+        return;
+    }
+    let mut entries: Vec<_> = days.iter().collect();
+    entries.sort();
+    let text: Vec<String> = entries
+        .iter()
+        .map(|(tf, d)| format!("{}={}", tf, d))
+        .collect();
+    println!("  {:<16} {}", label, text.join(", "));
+}
@@ -1,8 +1,12 @@
-//! Download command - fetch historical data from Binance (default) or CoinDCX
+//! Download command - fetch historical data from Binance (default), CoinDCX, Kraken,
+//! Coinbase or Bybit (USDT perpetuals)
 //! Like Python's download_binance_data.py script
 
 use anyhow::Result;
-use crypto_strategies::data::{load_csv, BinanceDataFetcher, CoinDCXDataFetcher, DataSource};
+use crypto_strategies::data::{
+    load_csv, BinanceDataFetcher, BybitDataFetcher, CoinDCXDataFetcher, CoinbaseDataFetcher,
+    DataSource, KrakenDataFetcher,
+};
 use tracing::info;
 
 pub fn run(
@@ -24,6 +28,9 @@ pub fn run(
     let source_name = match source {
         DataSource::Binance => "BINANCE",
         DataSource::CoinDCX => "COINDCX",
+        DataSource::Kraken => "KRAKEN",
+        DataSource::Coinbase => "COINBASE",
+        DataSource::Bybit => "BYBIT",
     };
 
     println!("\n{}", "=".repeat(60));
@@ -68,6 +75,81 @@ pub fn run(
         DataSource::CoinDCX => {
             let fetcher = CoinDCXDataFetcher::new(&output);
 
+            for symbol in &symbols {
+                println!("\n{}:", symbol);
+
+                for interval in &intervals {
+                    total_downloads += 1;
+                    print!("  Downloading {} {}... ", symbol, interval);
+
+                    match rt.block_on(fetcher.download_pair(symbol, interval, days)) {
+                        Ok(filepath) => {
+                            if let Ok(candles) = load_csv(&filepath) {
+                                total_candles += candles.len();
+                                println!("✓ {} candles", candles.len());
+                                success_count += 1;
+                            }
+                        }
+                        Err(e) => {
+                            println!("✗ Error: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+        DataSource::Kraken => {
+            let fetcher = KrakenDataFetcher::new(&output);
+
+            for symbol in &symbols {
+                println!("\n{}:", symbol);
+
+                for interval in &intervals {
+                    total_downloads += 1;
+                    print!("  Downloading {} {}... ", symbol, interval);
+
+                    match rt.block_on(fetcher.download_pair(symbol, interval, days)) {
+                        Ok(filepath) => {
+                            if let Ok(candles) = load_csv(&filepath) {
+                                total_candles += candles.len();
+                                println!("✓ {} candles", candles.len());
+                                success_count += 1;
+                            }
+                        }
+                        Err(e) => {
+                            println!("✗ Error: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+        DataSource::Coinbase => {
+            let fetcher = CoinbaseDataFetcher::new(&output);
+
+            for symbol in &symbols {
+                println!("\n{}:", symbol);
+
+                for interval in &intervals {
+                    total_downloads += 1;
+                    print!("  Downloading {} {}... ", symbol, interval);
+
+                    match rt.block_on(fetcher.download_pair(symbol, interval, days)) {
+                        Ok(filepath) => {
+                            if let Ok(candles) = load_csv(&filepath) {
+                                total_candles += candles.len();
+                                println!("✓ {} candles", candles.len());
+                                success_count += 1;
+                            }
+                        }
+                        Err(e) => {
+                            println!("✗ Error: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+        DataSource::Bybit => {
+            let fetcher = BybitDataFetcher::new(&output);
+
             for symbol in &symbols {
                 println!("\n{}:", symbol);
 
@@ -10,25 +10,81 @@
 //! - Crash recovery from SQLite state
 //! - Risk management integration
 //! - Paper and live trading modes
+//! - Hot-adding symbols from config edits (backfill + warmup, no restart)
+//! - Operator kill switch (KILL file / env var) checked every cycle
+//! - Side-by-side paper entrants sharing one candle feed (`compete`)
 
 use anyhow::{Context, Result};
-use chrono::Utc;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
 use tokio::time::interval;
 use tracing::{debug, error, info, warn};
 
-use crypto_strategies::coindcx::{ClientConfig, CoinDCXClient};
+use crypto_strategies::accounts::{AccountBook, AccountRouter};
+use crypto_strategies::audit::{AuditRecord, AuditTrail, ExecType};
+use crypto_strategies::backtest::{
+    check_exit, rung_label, trailing_reference_price, ExitCheck, LadderState,
+};
+use crypto_strategies::binance::ws::{ClosedKline, KlineStream};
+use crypto_strategies::binance::{self, BinanceClient};
+use crypto_strategies::book_recorder;
+use crypto_strategies::bybit::{self, BybitClient};
+use crypto_strategies::capabilities;
+use crypto_strategies::coinbase::{self, CoinbaseClient};
+use crypto_strategies::coindcx::ws::{
+    self as coindcx_ws, MarketEvent, MarketStream, OrderUpdate, TradeCandles, UserEvent, UserStream,
+};
+use crypto_strategies::coindcx::{
+    ClientConfig, CoinDCXClient, CoinDCXError, FundsTransfer, OcoHandle, OcoOrder, OcoStatus,
+    OrderSide,
+};
+use crypto_strategies::common::{
+    reconnect, CircuitBreakerMetrics, CircuitState, Gap, LatencyHistogram, ReconnectingStream,
+    StreamEvent,
+};
+use crypto_strategies::config::{
+    CandleSource, DeadManSwitchConfig, EarnSweepConfig, SpreadGuardConfig, Venue,
+};
+use crypto_strategies::dead_man::DeadManSwitch;
+use crypto_strategies::degraded::{self, DataAction, DataHealth};
+use crypto_strategies::exchange::ExchangeClient;
+use crypto_strategies::fees;
+use crypto_strategies::fx::{self, QuoteConverter};
+use crypto_strategies::hedge::{self, Exposure, HedgeLeg, Hedger};
+use crypto_strategies::idle::{Phase, ResourceUsage};
+use crypto_strategies::intents;
+use crypto_strategies::kill_switch::KillState;
+use crypto_strategies::kraken::{self, KrakenClient};
+use crypto_strategies::leaderboard::{SharedLeaderboard, Standing};
 use crypto_strategies::multi_timeframe::{MultiTimeframeCandles, MultiTimeframeData};
-use crypto_strategies::oms::{ExecutionEngine, Fill, OrderBook, PositionManager, StrategyContext};
-use crypto_strategies::risk::RiskManager;
+use crypto_strategies::oms::post_only::{self, PostOnlyDecision};
+use crypto_strategies::oms::pre_trade::{PreTradeChain, PreTradeContext};
+use crypto_strategies::oms::spread_guard::{convert_to_limit, SpreadDecision};
+use crypto_strategies::oms::{
+    ExecutionEngine, Fill, Order, OrderBook, Position, PositionManager, StrategyContext,
+};
+use crypto_strategies::preflight::{self, Ack, PlannedOrder, PlannedPosition, PreflightPlan};
+use crypto_strategies::reconcile;
+use crypto_strategies::risk::{
+    EdgeCheck, MarketActivity, RiskManager, TrailingDrawdown, TrailingDrawdownEvent,
+};
+use crypto_strategies::risk_budget::RiskBudget;
+use crypto_strategies::service::{self, ConfigFault, PidFile};
 use crypto_strategies::state_manager::{
-    create_state_manager, Checkpoint, PendingOrder, Position as StatePosition, SqliteStateManager,
+    create_state_manager, CashFlow, Checkpoint, CheckpointPosition, FillRecord, IntentStatus,
+    PendingOrder, Position as StatePosition, SqliteStateManager, TradeRecord,
 };
+use crypto_strategies::state_writer::{self, StateEvent, StateWriter};
+use crypto_strategies::strategies::context::{self, SignalContext};
 use crypto_strategies::strategies::{self, Strategy};
-use crypto_strategies::{Config, Money, Side, Symbol, Trade};
+use crypto_strategies::webhooks::{WebhookDispatcher, WebhookEvent, WebhookEventKind};
+use crypto_strategies::zerodha::{self, ZerodhaClient};
+use crypto_strategies::{add_fee, Config, FeeLedger, FeeType, Money, Side, Symbol, Trade};
 
 /// Performance metrics for HFT monitoring
 #[derive(Debug, Default)]
@@ -37,6 +93,23 @@ struct PerformanceMetrics {
     total_orders_placed: u64,
     total_fills: u64,
     total_cancels: u64,
+    edge_rejections: u64,
+    budget_rejections: u64,
+    pre_trade_rejections: u64,
+    /// Cycles each symbol was left unsignalled by `trading.activity_filter`
+    activity_skips: BTreeMap<String, u64>,
+    idle_cycles: u64,
+    /// Symbol-cycles run under a degraded-data policy
+    degraded_cycles: u64,
+    /// CoinDCX circuit breaker state and counters as of the last cycle
+    circuit: Option<(CircuitState, CircuitBreakerMetrics)>,
+    /// CoinDCX request latency by endpoint path since start
+    endpoint_latency: BTreeMap<String, LatencyHistogram>,
+    // Per-cycle resource usage (idle mode with `report_usage`)
+    sampled_cycles: u64,
+    total_cycle_cpu_us: u64,
+    max_cycle_cpu_us: u64,
+    peak_rss_bytes: u64,
     avg_cycle_latency_us: u64,
     max_cycle_latency_us: u64,
     avg_order_latency_us: u64,
@@ -66,6 +139,14 @@ impl PerformanceMetrics {
         self.update_fill_ratio();
     }
 
+    fn record_usage(&mut self, cpu: Duration, usage: &ResourceUsage) {
+        let cpu_us = cpu.as_micros() as u64;
+        self.sampled_cycles += 1;
+        self.total_cycle_cpu_us += cpu_us;
+        self.max_cycle_cpu_us = self.max_cycle_cpu_us.max(cpu_us);
+        self.peak_rss_bytes = self.peak_rss_bytes.max(usage.rss_bytes);
+    }
+
     fn record_fill(&mut self) {
         self.total_fills += 1;
         self.update_fill_ratio();
@@ -89,10 +170,44 @@ impl PerformanceMetrics {
             self.fill_ratio * 100.0
         );
         info!("Orders cancelled:      {}", self.total_cancels);
+        info!("Edge rejections:       {}", self.edge_rejections);
+        info!("Budget rejections:     {}", self.budget_rejections);
+        info!("Pre-trade rejections:  {}", self.pre_trade_rejections);
+        if !self.activity_skips.is_empty() {
+            let skips: Vec<String> = self
+                .activity_skips
+                .iter()
+                .map(|(symbol, cycles)| format!("{} {}", symbol, cycles))
+                .collect();
+            info!("Inactive skips:        {}", skips.join(", "));
+        }
         info!("Avg cycle latency:     {} μs", self.avg_cycle_latency_us);
         info!("Max cycle latency:     {} μs", self.max_cycle_latency_us);
         info!("Avg order latency:     {} μs", self.avg_order_latency_us);
         info!("Max order latency:     {} μs", self.max_order_latency_us);
+        if self.idle_cycles > 0 {
+            info!("Idle cycles:           {}", self.idle_cycles);
+        }
+        if self.degraded_cycles > 0 {
+            info!("Degraded symbol-cycles: {}", self.degraded_cycles);
+        }
+        if let Some((state, cb)) = &self.circuit {
+            info!(
+                "Circuit breaker:       {:?} ({} opens, {} probes, {} failures, {} rejected)",
+                state, cb.opens, cb.half_open_probes, cb.failures, cb.rejected
+            );
+        }
+        for (endpoint, latency) in &self.endpoint_latency {
+            info!("HTTP {:<34} {}", endpoint, latency);
+        }
+        if let Some(avg_cpu_us) = self.total_cycle_cpu_us.checked_div(self.sampled_cycles) {
+            info!("Avg cycle CPU:         {} μs", avg_cpu_us);
+            info!("Max cycle CPU:         {} μs", self.max_cycle_cpu_us);
+            info!(
+                "Peak RSS:              {:.1} MB",
+                self.peak_rss_bytes as f64 / 1_048_576.0
+            );
+        }
         if self.max_cycle_latency_us > 10_000 {
             warn!("⚠️  Max latency > 10ms - consider optimization");
         }
@@ -103,13 +218,86 @@ impl PerformanceMetrics {
     }
 }
 
+/// Candles by (symbol, timeframe) with the time they were fetched
+type FeedCache = HashMap<(String, String), (Instant, Vec<crypto_strategies::Candle>)>;
+
+/// Latest candles shared by traders polling the same symbols
+///
+/// The first trader to ask for a symbol/timeframe fetches it; others asking
+/// within `ttl` get the same candles, so every entrant of a competition
+/// trades on identical data.
+#[derive(Clone)]
+pub(super) struct SharedFeed {
+    cache: Arc<tokio::sync::Mutex<FeedCache>>,
+    ttl: Duration,
+}
+
+impl SharedFeed {
+    pub(super) fn new(ttl: Duration) -> Self {
+        Self {
+            cache: Arc::default(),
+            ttl,
+        }
+    }
+
+    async fn get_candles(
+        &self,
+        exchange: &impl ExchangeClient,
+        symbol: &str,
+        tf: &str,
+    ) -> Result<Vec<crypto_strategies::Candle>> {
+        let mut cache = self.cache.lock().await;
+        let key = (symbol.to_string(), tf.to_string());
+        if let Some((at, candles)) = cache.get(&key) {
+            if at.elapsed() < self.ttl {
+                return Ok(candles.clone());
+            }
+        }
+        let candles = exchange.get_candles(symbol, tf, 2).await?;
+        cache.insert(key, (Instant::now(), candles.clone()));
+        Ok(candles)
+    }
+}
+
+/// Forming bars built from the exchange's trade stream (`exchange.market_stream`)
+struct TradeFeed {
+    stream: ReconnectingStream<MarketEvent>,
+    candles: TradeCandles,
+}
+
+/// Stop-limit legs of parked exits rest this far (fraction) past their trigger
+const OCO_STOP_SLIPPAGE: f64 = 0.005;
+
+/// Exit levels parked on the exchange as an OCO pair
+struct ParkedExit {
+    handle: OcoHandle,
+    stop: f64,
+    target: f64,
+}
+
+/// A trader's entry in a paper competition
+struct Competition {
+    board: SharedLeaderboard,
+    standing: Standing,
+}
+
 /// Live trader state with OMS integration
-struct LiveTrader {
+///
+/// Generic over the venue; CoinDCX-only features (order recovery, trade
+/// reconciliation, book recording, order streams) are skipped elsewhere.
+pub(super) struct LiveTrader<E = CoinDCXClient> {
     config: Config,
     strategy: Box<dyn Strategy>,
     risk_manager: RiskManager,
-    exchange: CoinDCXClient,
+    exchange: E,
+    // Named accounts (`exchange.accounts`): where each symbol trades, each
+    // account's results, and a CoinDCX client per extra API key
+    accounts: AccountRouter,
+    account_book: AccountBook,
+    account_clients: HashMap<String, CoinDCXClient>,
     state_manager: SqliteStateManager,
+    // Writes go through the background writer, batched per cycle
+    state_writer: StateWriter,
 
     // OMS components
     orderbooks: HashMap<Symbol, OrderBook>,
@@ -124,20 +312,284 @@ struct LiveTrader {
     // Trading state
     paper_mode: bool,
     cycle_count: u32,
+    /// Start of the current cycle (ms); seeds its orders' client order IDs
+    cycle_started_ms: i64,
     paper_cash: f64,
+    /// Realized P&L of symbols quoted in a foreign currency (`home_currency`),
+    /// held per quote currency until valued
+    quote_cash: HashMap<String, f64>,
 
     // Stop/Target tracking (matches backtest.rs pattern)
     // Format: (stop_price, target_price) - cached at entry time
     entry_levels: HashMap<Symbol, (f64, f64)>,
+    /// Strategy commentary of the entry behind each open position
+    entry_notes: HashMap<Symbol, String>,
     trailing_stops: HashMap<Symbol, f64>,
+    /// Exit-ladder progress per open position (`trading.exit_ladder`)
+    ladder_states: HashMap<Symbol, LadderState>,
+    /// Stop/target pairs resting on CoinDCX for open live positions
+    parked_exits: HashMap<Symbol, ParkedExit>,
+    /// Positions whose exits the exchange refused to park; monitored locally
+    unparkable: HashSet<Symbol>,
+    /// Positions restored from the last session that the exchange's balances
+    /// haven't shown yet; their exits are not parked until they do
+    unconfirmed: HashSet<Symbol>,
+    /// Borrow interest on margin shorts is paid up to this time (`trading.borrow`)
+    borrow_accrued: HashMap<Symbol, DateTime<Utc>>,
+    /// Portfolio hedge leg (`trading.hedge`)
+    hedger: Option<Hedger>,
 
     // Performance monitoring
     metrics: PerformanceMetrics,
     last_metrics_log: Instant,
+
+    // Fill/close notifications (None when no endpoints are configured)
+    webhooks: Option<WebhookDispatcher>,
+
+    // Last trade reconciliation against the exchange
+    last_reconcile: Instant,
+
+    // Idle cash parked in a CoinDCX earn product (`exchange.earn_sweep`)
+    earn_balance: f64,
+    last_earn_sweep: Instant,
+
+    // Rates into the home currency when symbols are quoted in several
+    // (`backtest.home_currency`)
+    fx: Option<QuoteConverter>,
+
+    // Deposits and withdrawals booked so far (`exchange.cash_flows`), and the
+    // creation time transfers are fetched from
+    net_cash_flows: f64,
+    cash_flows_since: DateTime<Utc>,
+    last_cash_flow_sync: Instant,
+
+    // Operator kill switch, refreshed at the top of every cycle
+    kill_state: KillState,
+
+    // Consecutive candle-feed failures per symbol
+    data_health: DataHealth,
+
+    // Config file watched for newly added symbols
+    config_path: PathBuf,
+    config_modified: Option<SystemTime>,
+    // Added to the config but not bootstrapped yet; retried every cycle
+    pending_symbols: Vec<String>,
+
+    // Compliance checks run on every strategy order
+    pre_trade: PreTradeChain,
+    // Hash-chained order/fill audit trail (exchange.audit_trail)
+    audit: Option<AuditTrail>,
+
+    // Candles shared with other traders (None = poll the exchange directly)
+    feed: Option<SharedFeed>,
+    // Trade stream for the forming bar (None = REST candles only)
+    trade_feed: Option<TradeFeed>,
+    // Closed Binance bars (exchange.candle_source = "binance"); replaces REST polling while up
+    kline_feed: Option<ReconnectingStream<ClosedKline>>,
+    // Symbols the kline stream subscribed to; symbols added later keep polling REST
+    kline_symbols: HashSet<String>,
+    // Outages of the kline stream whose bars still have to be fetched over REST
+    kline_gaps: Vec<Gap>,
+    // Exchange order updates (live mode); while connected, fills come from here
+    user_stream: Option<ReconnectingStream<UserEvent>>,
+    // The order stream was down; resting orders are re-read over REST
+    order_updates_missed: bool,
+    /// Latest exchange update per client order ID
+    order_updates: HashMap<String, OrderUpdate>,
+    /// Exchange order ID of every working live order, by client order ID
+    sent_orders: HashMap<String, String>,
+    // Standing published to a competition leaderboard
+    competition: Option<Competition>,
+    // Startup trade plan: None = trade straight away, Some(true) = pre-confirmed
+    preflight: Option<bool>,
+    // CoinDCX circuit breaker transitions not yet reported
+    circuit_events: Option<mpsc::UnboundedReceiver<(CircuitState, CircuitState)>>,
 }
 
 impl LiveTrader {
-    async fn new(config: Config, state_db_path: &str, paper_mode: bool) -> Result<Self> {
+    /// Trader on CoinDCX with the config's credentials and rate limit
+    pub(super) async fn new(
+        config: Config,
+        config_path: PathBuf,
+        state_db_path: &str,
+        paper_mode: bool,
+    ) -> Result<Self> {
+        let api_key = config.exchange.api_key.clone().unwrap_or_default();
+        let api_secret = config.exchange.api_secret.clone().unwrap_or_default();
+
+        let client_config = ClientConfig {
+            network: config.exchange.network()?,
+            ..Default::default()
+        }
+        .with_max_retries(3)
+        .with_rate_limit(config.exchange.rate_limit as usize)
+        .with_timeout(Duration::from_secs(30));
+
+        let mut exchange = CoinDCXClient::with_config(api_key, api_secret, client_config);
+        if let Some(margin) = &config.exchange.margin {
+            exchange = exchange.with_margin(margin.leverage);
+            info!("✓ Orders routed through margin ({}x)", margin.leverage);
+        }
+        info!(
+            "✓ Exchange client created (rate limit: {} req/s)",
+            config.exchange.rate_limit
+        );
+        // Orders are rounded to these limits (paper too, so sizes match live),
+        // and breaking them is caught before sending
+        match exchange.load_markets().await {
+            Ok(count) => info!("✓ Market limits cached for {} markets", count),
+            Err(e) => warn!(
+                "⚠️  Market details unavailable, orders not rounded or checked locally: {}",
+                e
+            ),
+        }
+        Self::with_exchange(config, config_path, state_db_path, paper_mode, exchange).await
+    }
+}
+
+impl LiveTrader<KrakenClient> {
+    /// Trader on Kraken with the config's credentials and rate limit
+    pub(super) async fn kraken(
+        config: Config,
+        config_path: PathBuf,
+        state_db_path: &str,
+        paper_mode: bool,
+    ) -> Result<Self> {
+        let api_key = config.exchange.api_key.clone().unwrap_or_default();
+        let api_secret = config.exchange.api_secret.clone().unwrap_or_default();
+
+        let client_config = kraken::ClientConfig {
+            network: config.exchange.network()?,
+            ..Default::default()
+        }
+        .with_max_retries(3)
+        .with_rate_limit(config.exchange.rate_limit as usize)
+        .with_timeout(Duration::from_secs(30));
+
+        let exchange = KrakenClient::with_config(api_key, api_secret, client_config);
+        info!(
+            "✓ Kraken client created (rate limit: {} req/s)",
+            config.exchange.rate_limit
+        );
+        Self::with_exchange(config, config_path, state_db_path, paper_mode, exchange).await
+    }
+}
+
+impl LiveTrader<CoinbaseClient> {
+    /// Trader on Coinbase Advanced Trade with the config's credentials and rate limit
+    pub(super) async fn coinbase(
+        config: Config,
+        config_path: PathBuf,
+        state_db_path: &str,
+        paper_mode: bool,
+    ) -> Result<Self> {
+        let api_key = config.exchange.api_key.clone().unwrap_or_default();
+        let api_secret = config.exchange.api_secret.clone().unwrap_or_default();
+
+        let client_config = coinbase::ClientConfig {
+            network: config.exchange.network()?,
+            ..Default::default()
+        }
+        .with_max_retries(3)
+        .with_rate_limit(config.exchange.rate_limit as usize)
+        .with_timeout(Duration::from_secs(30))
+        .with_sandbox(config.exchange.sandbox);
+
+        let exchange = CoinbaseClient::with_config(api_key, api_secret, client_config);
+        info!(
+            "✓ Coinbase client created (rate limit: {} req/s)",
+            config.exchange.rate_limit
+        );
+        Self::with_exchange(config, config_path, state_db_path, paper_mode, exchange).await
+    }
+}
+
+impl LiveTrader<BybitClient> {
+    /// Trader on Bybit USDT perpetuals with the config's credentials and rate limit
+    pub(super) async fn bybit(
+        config: Config,
+        config_path: PathBuf,
+        state_db_path: &str,
+        paper_mode: bool,
+    ) -> Result<Self> {
+        let api_key = config.exchange.api_key.clone().unwrap_or_default();
+        let api_secret = config.exchange.api_secret.clone().unwrap_or_default();
+
+        let client_config = bybit::ClientConfig {
+            network: config.exchange.network()?,
+            ..Default::default()
+        }
+        .with_max_retries(3)
+        .with_rate_limit(config.exchange.rate_limit as usize)
+        .with_timeout(Duration::from_secs(30))
+        .with_sandbox(config.exchange.sandbox);
+
+        let exchange = BybitClient::with_config(api_key, api_secret, client_config);
+        info!(
+            "✓ Bybit client created (rate limit: {} req/s)",
+            config.exchange.rate_limit
+        );
+        Self::with_exchange(config, config_path, state_db_path, paper_mode, exchange).await
+    }
+}
+
+impl LiveTrader<ZerodhaClient> {
+    /// Trader on Zerodha Kite with the config's credentials and the day's access token
+    pub(super) async fn zerodha(
+        config: Config,
+        config_path: PathBuf,
+        state_db_path: &str,
+        paper_mode: bool,
+    ) -> Result<Self> {
+        let api_key = config.exchange.api_key.clone().unwrap_or_default();
+        let api_secret = config.exchange.api_secret.clone().unwrap_or_default();
+        // Kite sessions expire daily; candles need one too, so paper mode as well
+        let access_token = std::env::var("ZERODHA_ACCESS_TOKEN")
+            .context("ZERODHA_ACCESS_TOKEN is not set (log in to Kite for today's token)")?;
+
+        let mut client_config = zerodha::ClientConfig {
+            network: config.exchange.network()?,
+            ..Default::default()
+        }
+        .with_max_retries(3)
+        .with_rate_limit(config.exchange.rate_limit as usize)
+        .with_timeout(Duration::from_secs(30));
+        for (symbol, token) in &config.exchange.instrument_tokens {
+            client_config = client_config.with_instrument_token(symbol, *token);
+        }
+        // Candles are requested by token, so every symbol needs one up front
+        let missing: Vec<&String> = config
+            .trading
+            .symbols
+            .iter()
+            .filter(|s| client_config.symbols.kite_token(s).is_none())
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No Kite instrument token for {:?}: add them to exchange.instrument_tokens",
+                missing
+            ))
+            .context(ConfigFault);
+        }
+
+        let exchange = ZerodhaClient::with_config(api_key, api_secret, client_config)
+            .with_access_token(access_token);
+        info!(
+            "✓ Zerodha client created (rate limit: {} req/s)",
+            config.exchange.rate_limit
+        );
+        Self::with_exchange(config, config_path, state_db_path, paper_mode, exchange).await
+    }
+}
+
+impl<E: ExchangeClient> LiveTrader<E> {
+    pub(super) async fn with_exchange(
+        mut config: Config,
+        config_path: PathBuf,
+        state_db_path: &str,
+        paper_mode: bool,
+        exchange: E,
+    ) -> Result<Self> {
         let start = Instant::now();
         info!("⚙️  Initializing trading engine...");
 
@@ -148,6 +600,28 @@ impl LiveTrader {
             start.elapsed().as_micros()
         );
 
+        // Fail now rather than on the first rejected order
+        capabilities::assert_supported(exchange.capabilities(), &config, strategy.as_ref())?;
+        if !paper_mode {
+            capabilities::assert_live_fills(exchange.capabilities())?;
+        }
+        info!("✓ Config supported by {}", exchange.capabilities().name);
+        if config.exchange.sandbox {
+            warn!(
+                "🧪 Sandbox mode: requests go to the {} testnet, balances are not real",
+                exchange.capabilities().name
+            );
+        }
+
+        // Before anything charges a commission
+        fees::apply_from(&mut config.exchange, &exchange).await;
+
+        let hedger = config
+            .trading
+            .hedge
+            .clone()
+            .map(|hedge| Hedger::new(hedge, config.exchange.taker_fee));
+
         let primary_timeframe = config.timeframe();
         let strategy_tfs = strategy.required_timeframes();
         let mut required_timeframes: Vec<String> =
@@ -174,35 +648,48 @@ impl LiveTrader {
             config.trading.drawdown_critical_multiplier,
             config.trading.consecutive_loss_limit,
             config.trading.consecutive_loss_multiplier,
-        );
+        )
+        .with_win_streak_scaling(
+            config.trading.win_streak_threshold,
+            config.trading.win_streak_step,
+            config.trading.win_streak_max_multiplier,
+        )
+        .with_group_limits(
+            &config.trading.symbol_groups,
+            &config.trading.group_exposure_caps,
+        )
+        .with_cvar_cap(config.trading.max_trade_cvar)
+        .with_trailing_drawdown(config.trading.trailing_drawdown.as_ref());
+        let risk_manager = match &config.trading.risk_budget {
+            Some(budget) => {
+                let ledger = budget.ledger.as_deref().unwrap_or("memory").to_string();
+                let budget = RiskBudget::open(budget.clone())?;
+                info!(
+                    "✓ Risk budget: {:.2}% of capital per day for {} ({})",
+                    budget.strategy_pct(strategy.name()) * 100.0,
+                    strategy.name(),
+                    ledger
+                );
+                risk_manager.with_risk_budget(budget.shared(), strategy.name())
+            }
+            None => risk_manager,
+        };
         info!(
             "✓ Risk manager initialized (capital: {:.2})",
             config.trading.initial_capital
         );
 
-        let api_key = config.exchange.api_key.clone().unwrap_or_default();
-        let api_secret = config.exchange.api_secret.clone().unwrap_or_default();
-
-        let client_config = ClientConfig::default()
-            .with_max_retries(3)
-            .with_rate_limit(config.exchange.rate_limit as usize)
-            .with_timeout(Duration::from_secs(30));
-
-        let exchange = CoinDCXClient::with_config(api_key, api_secret, client_config);
-        info!(
-            "✓ Exchange client connected (rate limit: {} req/s)",
-            config.exchange.rate_limit
-        );
-
         let state_dir = std::path::Path::new(state_db_path)
             .parent()
             .unwrap_or(std::path::Path::new("."));
         let state_manager = create_state_manager(state_dir, "sqlite")?;
+        let state_writer =
+            StateWriter::spawn(state_manager.try_clone()?, state_writer::DEFAULT_CAPACITY);
         info!("✓ State manager ready (path: {})", state_db_path);
 
         let execution_engine = ExecutionEngine::new(
-            config.exchange.maker_fee,
-            config.exchange.taker_fee,
+            config.exchange.effective_maker_fee(),
+            config.exchange.effective_taker_fee(),
             config.exchange.assumed_slippage,
         );
         info!(
@@ -212,6 +699,56 @@ impl LiveTrader {
             config.exchange.assumed_slippage * 100.0
         );
 
+        let pre_trade = PreTradeChain::from_config(&config.trading.pre_trade_checks)?;
+        if !pre_trade.is_empty() {
+            info!(
+                "✓ Pre-trade checks: {}",
+                config.trading.pre_trade_checks.len()
+            );
+        }
+
+        let webhooks = WebhookDispatcher::spawn(config.webhooks.clone());
+        if webhooks.is_some() {
+            info!("✓ Webhooks enabled ({} endpoint(s))", config.webhooks.len());
+        }
+
+        let audit = match &config.exchange.audit_trail {
+            Some(audit) => {
+                let session = format!("{}-{}", strategy.name(), Utc::now().format("%Y%m%d-%H%M%S"));
+                let trail = AuditTrail::open(audit, &session, paper_mode)?;
+                info!("✓ Audit trail: {}", trail.path().display());
+                Some(trail)
+            }
+            None => None,
+        };
+
+        let fx = config
+            .backtest
+            .home_currency
+            .as_ref()
+            .map(|home| QuoteConverter::new(&home.currency))
+            .filter(|fx| {
+                !fx.foreign_quotes(config.trading.symbols.iter().map(String::as_str))
+                    .is_empty()
+            });
+        let accounts = AccountRouter::new(&config.exchange.accounts, strategy.name());
+        let account_book =
+            AccountBook::new(&config.exchange.accounts, config.trading.initial_capital);
+        let account_clients = account_clients(&config, exchange.as_coindcx())?;
+        if !config.exchange.accounts.is_empty() {
+            info!(
+                "✓ Accounts: {} ({} with their own API key)",
+                config
+                    .exchange
+                    .accounts
+                    .iter()
+                    .map(|a| a.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                account_clients.len()
+            );
+        }
+
         info!(
             "⚡ Initialization complete ({} μs)",
             start.elapsed().as_micros()
@@ -222,7 +759,11 @@ impl LiveTrader {
             strategy,
             risk_manager,
             exchange,
+            accounts,
+            account_book,
+            account_clients,
             state_manager,
+            state_writer,
             orderbooks: HashMap::new(),
             position_manager: PositionManager::new(),
             execution_engine,
@@ -231,15 +772,110 @@ impl LiveTrader {
             primary_timeframe,
             paper_mode,
             cycle_count: 0,
+            cycle_started_ms: Utc::now().timestamp_millis(),
             paper_cash: 0.0,
+            quote_cash: HashMap::new(),
             entry_levels: HashMap::new(),
+            entry_notes: HashMap::new(),
             trailing_stops: HashMap::new(),
+            ladder_states: HashMap::new(),
+            parked_exits: HashMap::new(),
+            unparkable: HashSet::new(),
+            unconfirmed: HashSet::new(),
+            borrow_accrued: HashMap::new(),
+            hedger,
             metrics: PerformanceMetrics::default(),
             last_metrics_log: Instant::now(),
+            webhooks,
+            last_reconcile: Instant::now(),
+            earn_balance: 0.0,
+            fx,
+            last_earn_sweep: Instant::now(),
+            net_cash_flows: 0.0,
+            cash_flows_since: Utc::now(),
+            last_cash_flow_sync: Instant::now(),
+            kill_state: KillState::Off,
+            data_health: DataHealth::new(),
+            config_modified: modified_time(&config_path),
+            pending_symbols: Vec::new(),
+            config_path,
+            pre_trade,
+            audit,
+            feed: None,
+            trade_feed: None,
+            kline_feed: None,
+            kline_symbols: HashSet::new(),
+            kline_gaps: Vec::new(),
+            user_stream: None,
+            order_updates_missed: false,
+            order_updates: HashMap::new(),
+            sent_orders: HashMap::new(),
+            competition: None,
+            preflight: None,
+            circuit_events: None,
         })
     }
 
-    async fn recover_state(&mut self) -> Result<()> {
+    /// Read latest candles through a feed shared with other traders
+    pub(super) fn with_feed(mut self, feed: SharedFeed) -> Self {
+        self.feed = Some(feed);
+        self
+    }
+
+    /// Publish this trader's standing to a competition leaderboard under `name`
+    ///
+    /// Resumes from the board's existing entry for `name`, if any.
+    pub(super) fn with_competition(mut self, board: SharedLeaderboard, name: &str) -> Self {
+        let standing = board
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| {
+                Standing::new(
+                    name,
+                    self.strategy.name(),
+                    self.config.trading.initial_capital,
+                )
+            });
+        self.competition = Some(Competition { board, standing });
+        self
+    }
+
+    /// Show the first cycle's trade plan and wait for it to be acknowledged
+    /// (`confirmed` = already acknowledged with `--confirm`)
+    pub(super) fn with_preflight(mut self, confirmed: bool) -> Self {
+        self.preflight = Some(confirmed);
+        self
+    }
+
+    pub(super) fn strategy_name(&self) -> &str {
+        self.strategy.name()
+    }
+
+    /// Mark open positions to market and post the standing
+    fn publish_standing(&mut self) {
+        let Some(competition) = &mut self.competition else {
+            return;
+        };
+        let unrealized: f64 = self
+            .position_manager
+            .get_all_positions()
+            .map(|(_, p)| p.unrealized_pnl.to_f64())
+            .sum();
+        competition.standing.mark(
+            unrealized,
+            self.position_manager.open_position_count(),
+            Utc::now(),
+        );
+        competition
+            .board
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .update(competition.standing.clone());
+    }
+
+    pub(super) async fn recover_state(&mut self) -> Result<()> {
         let start = Instant::now();
         info!("🔄 Recovering state from previous session...");
 
@@ -254,7 +890,54 @@ impl LiveTrader {
             self.cycle_count = checkpoint.cycle_count as u32;
             self.paper_cash = checkpoint.cash;
             self.risk_manager.consecutive_losses = checkpoint.consecutive_losses as usize;
+            self.net_cash_flows = checkpoint
+                .metadata
+                .get("net_cash_flows")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0);
+            self.quote_cash = checkpoint
+                .metadata
+                .get("quote_cash")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default();
+            if self.net_cash_flows != 0.0 {
+                info!("  └─ Net deposits: {:+.2}", self.net_cash_flows);
+                self.risk_manager.apply_cash_flow(self.net_cash_flows);
+            }
+            // Transfers made while stopped are not in the checkpoint's cash yet
+            if let Ok(at) = DateTime::parse_from_rfc3339(&checkpoint.timestamp) {
+                self.cash_flows_since = at.with_timezone(&Utc);
+            }
             self.risk_manager.update_capital(checkpoint.portfolio_value);
+            if let Some(saved) = checkpoint
+                .metadata
+                .get("trailing_drawdown")
+                .filter(|_| self.risk_manager.trailing_drawdown.is_some())
+                .and_then(|v| serde_json::from_value::<TrailingDrawdown>(v.clone()).ok())
+            {
+                info!(
+                    "  └─ Trailing DD: high-water mark {:.2}, floor {:.2}{}",
+                    saved.high_water_mark,
+                    saved.floor(),
+                    if saved.breached { " (BREACHED)" } else { "" }
+                );
+                self.risk_manager.trailing_drawdown = Some(saved);
+            }
+            if let Some(hedger) = self.hedger.take() {
+                let leg = checkpoint
+                    .metadata
+                    .get("hedge_leg")
+                    .and_then(|v| serde_json::from_value::<HedgeLeg>(v.clone()).ok());
+                if let Some(leg) = &leg {
+                    info!(
+                        "  └─ Hedge: short {:.6} {} @ {:.2}",
+                        leg.quantity,
+                        hedger.config().symbol,
+                        leg.entry_price
+                    );
+                }
+                self.hedger = Some(hedger.with_leg(leg));
+            }
 
             let current_hash = self.config_hash();
             if !checkpoint.config_hash.is_empty() && checkpoint.config_hash != current_hash {
@@ -267,6 +950,19 @@ impl LiveTrader {
             self.paper_cash = self.config.trading.initial_capital;
         }
 
+        if !self.account_book.is_empty() {
+            self.account_book
+                .restore(self.state_manager.load_account_balances()?);
+            for account in self.account_book.balances() {
+                info!(
+                    "  └─ Account {}: {:.2} ({} trade(s))",
+                    account.account,
+                    account.balance(),
+                    account.trades
+                );
+            }
+        }
+
         let state_positions = self.state_manager.load_positions(Some("open"))?;
         info!("📦 Loading {} open position(s)...", state_positions.len());
 
@@ -290,6 +986,9 @@ impl LiveTrader {
             );
 
             self.position_manager.add_fill(fill, symbol.clone(), side);
+            if !self.paper_mode {
+                self.unconfirmed.insert(symbol.clone());
+            }
 
             // Restore stop/target levels if saved
             if sp.stop_loss > 0.0 || sp.take_profit > 0.0 {
@@ -309,6 +1008,30 @@ impl LiveTrader {
                 }
             }
 
+            // Restore exit-ladder progress so filled rungs aren't taken again
+            if let Some(ladder) = sp.metadata.get("exit_ladder") {
+                if let Ok(state) = serde_json::from_value::<LadderState>(ladder.clone()) {
+                    self.ladder_states.insert(symbol.clone(), state);
+                    info!("  └─ Restored exit ladder: {} rung(s) taken", state.filled);
+                }
+            }
+
+            // Restore borrow interest already paid on a margin short
+            if let Some(cost) = sp.metadata.get("borrow_cost").and_then(|v| v.as_f64()) {
+                if let Some(pos) = self.position_manager.get_position_mut(&symbol) {
+                    pos.accrue_borrow(cost);
+                }
+                if let Some(at) = sp
+                    .metadata
+                    .get("borrow_accrued_at")
+                    .and_then(|v| v.as_str())
+                    .and_then(|t| t.parse().ok())
+                {
+                    self.borrow_accrued.insert(symbol.clone(), at);
+                }
+                info!("  └─ Restored borrow interest: {:.2}", cost);
+            }
+
             info!(
                 "  ✓ {} {} {:.6} @ {:.2} (P&L: {:.2})",
                 symbol,
@@ -321,6 +1044,10 @@ impl LiveTrader {
 
         // Load pending orders and restore to orderbooks
         let pending_orders = self.state_manager.load_pending_orders()?;
+        let resting: HashSet<String> = pending_orders
+            .iter()
+            .filter_map(|po| po.client_id.clone())
+            .collect();
         if !pending_orders.is_empty() {
             info!("📋 Restoring {} pending order(s)...", pending_orders.len());
             for po in pending_orders {
@@ -370,6 +1097,48 @@ impl LiveTrader {
             // Clear from DB since they're now in memory
             self.state_manager.clear_pending_orders()?;
         }
+        // Restored live orders are cancelled on the exchange by their order ID
+        if !self.paper_mode {
+            for intent in self.state_manager.load_open_intents()? {
+                if let Some(id) = intent
+                    .exchange_order_id
+                    .filter(|_| resting.contains(&intent.intent_id))
+                {
+                    self.sent_orders.insert(intent.intent_id, id);
+                }
+            }
+        }
+
+        // Settle orders that were in flight when the last session stopped
+        let exchange = self.exchange.as_coindcx().filter(|_| !self.paper_mode);
+        match intents::recover(&self.state_manager, exchange, &resting).await {
+            Ok(resolutions) => {
+                for record in resolutions.iter().flat_map(|r| &r.missing_fills) {
+                    let symbol = Symbol::new(&record.symbol);
+                    let side = if record.side == "sell" {
+                        Side::Sell
+                    } else {
+                        Side::Buy
+                    };
+                    let time = record.timestamp.parse().unwrap_or_else(|_| Utc::now());
+                    let fill =
+                        Fill::from_f64(0, record.price, record.quantity, time, record.fee, false);
+                    if let Some(trade) =
+                        book_fill(&mut self.position_manager, &symbol, side, fill, record)
+                    {
+                        self.state_manager.record_trade(&trade)?;
+                    }
+                    info!(
+                        "  ✓ Applied missed fill {} {} {:.6} @ {:.2}",
+                        record.side.to_uppercase(),
+                        symbol,
+                        record.quantity,
+                        record.price
+                    );
+                }
+            }
+            Err(e) => warn!("⚠️  Order intent recovery failed: {}", e),
+        }
 
         info!(
             "⚡ State recovery complete ({} μs)",
@@ -379,33 +1148,33 @@ impl LiveTrader {
     }
 
     async fn bootstrap_candles(&mut self, symbol: &Symbol) -> Result<()> {
-        use crypto_strategies::Candle;
-
         let start = Instant::now();
         info!("📥 Bootstrapping historical data for {}...", symbol);
 
         let mut mtf_data = MultiTimeframeData::new(self.primary_timeframe.clone());
+        let binance = (self.config.exchange.candle_source == CandleSource::Binance)
+            .then(|| BinanceClient::new().with_sandbox(self.config.exchange.sandbox));
 
         for tf in &self.required_timeframes {
             let tf_start = Instant::now();
-            let raw_candles = self
-                .exchange
-                .get_candles(symbol.as_str(), tf, Some(500))
-                .await?;
-
-            if raw_candles.is_empty() {
-                warn!("  ⚠️  No {} candles received for {}", tf, symbol);
-                continue;
-            }
-
-            // Convert coindcx::Candle to crypto_strategies::Candle
-            let candles: Vec<Candle> = raw_candles
-                .into_iter()
-                .filter_map(|c| c.try_into().ok())
-                .collect();
+            let candles = match &binance {
+                Some(client) => client
+                    .get_klines(
+                        &client.to_binance_pair(symbol.as_str()),
+                        tf,
+                        None,
+                        None,
+                        Some(500),
+                    )
+                    .await?
+                    .into_iter()
+                    .filter_map(|k| crypto_strategies::Candle::try_from(k).ok())
+                    .collect(),
+                None => self.exchange.get_candles(symbol.as_str(), tf, 500).await?,
+            };
 
             if candles.is_empty() {
-                warn!("  ⚠️  Failed to convert {} candles for {}", tf, symbol);
+                warn!("  ⚠️  No {} candles received for {}", tf, symbol);
                 continue;
             }
 
@@ -436,7 +1205,7 @@ impl LiveTrader {
         Ok(())
     }
 
-    async fn run(&mut self, shutdown: Arc<AtomicBool>) -> Result<()> {
+    pub(super) async fn run(&mut self, shutdown: Arc<AtomicBool>) -> Result<()> {
         info!("════════════════════════════════════════════════════════");
         info!("🚀 LIVE TRADING ENGINE STARTED");
         info!("════════════════════════════════════════════════════════");
@@ -453,6 +1222,38 @@ impl LiveTrader {
         info!("Capital:  {:.2}", self.paper_cash);
         info!("════════════════════════════════════════════════════════");
 
+        let poll_secs = self.parse_tf_seconds(&self.primary_timeframe);
+        let idle = self
+            .config
+            .idle_mode
+            .clone()
+            .filter(|idle| idle.applies_to(poll_secs));
+        // Longest wait between two cycles
+        let longest_poll = idle
+            .as_ref()
+            .map_or(poll_secs, |i| i.idle_poll_secs.min(poll_secs));
+        // Only real orders rest on the exchange
+        let dead_man_switch = self
+            .config
+            .exchange
+            .dead_man_switch
+            .clone()
+            .filter(|_| !self.paper_mode);
+        if let Some(switch) = &dead_man_switch {
+            switch.validate(longest_poll)?;
+        }
+
+        if let Some(exchange) = self.exchange.as_coindcx() {
+            let (tx, rx) = mpsc::unbounded_channel();
+            exchange
+                .on_circuit_transition(move |from, to| {
+                    let _ = tx.send((from, to));
+                })
+                .await;
+            self.circuit_events = Some(rx);
+        }
+        self.sync_earn().await;
+
         // Bootstrap all symbols
         let bootstrap_start = Instant::now();
         for sym in &self.config.trading.symbols.clone() {
@@ -460,33 +1261,162 @@ impl LiveTrader {
             self.bootstrap_candles(&symbol).await?;
             self.orderbooks.insert(symbol.clone(), OrderBook::new());
         }
+        if let Some(hedge_symbol) = self
+            .hedger
+            .as_ref()
+            .map(|h| Symbol::new(&h.config().symbol))
+            .filter(|s| !self.candle_cache.contains_key(s))
+        {
+            self.bootstrap_candles(&hedge_symbol).await?;
+        }
         info!(
             "⚡ All symbols bootstrapped ({} ms)",
             bootstrap_start.elapsed().as_millis()
         );
 
+        if self.config.exchange.market_stream {
+            self.connect_trade_feed().await;
+        }
+        if self.config.exchange.candle_source == CandleSource::Binance {
+            self.connect_kline_feed().await;
+        }
+        if !self.paper_mode {
+            self.connect_user_stream().await;
+        }
+
+        if let Some(confirmed) = self.preflight {
+            if self.preflight(confirmed).await? == Ack::Aborted {
+                info!("🛑 Preflight plan aborted - not trading");
+                return Ok(());
+            }
+        }
+
         // Main event loop
-        let poll_secs = self.parse_tf_seconds(&self.primary_timeframe);
-        info!("⏱️  Polling interval: {} seconds", poll_secs);
+        // Set while idle so background recording stops too
+        let recorder_paused = Arc::new(AtomicBool::new(false));
+
+        // Background orderbook recording shares the client's rate limiter
+        if let (Some(recorder), Some(exchange)) = (
+            self.config.exchange.orderbook_recorder.clone(),
+            self.exchange.as_coindcx(),
+        ) {
+            let symbols = if recorder.symbols.is_empty() {
+                self.config.trading.symbols.clone()
+            } else {
+                recorder.symbols.clone()
+            };
+            tokio::spawn(book_recorder::run(
+                exchange.clone(),
+                recorder,
+                symbols,
+                shutdown.clone(),
+                Some(recorder_paused.clone()),
+                None,
+            ));
+        }
+
+        match &idle {
+            Some(idle) => info!(
+                "⏱️  Idle mode: every {}s for {}s after each bar close, else every {}s",
+                idle.active_poll_secs, idle.active_secs, idle.idle_poll_secs
+            ),
+            None => info!("⏱️  Polling interval: {} seconds", poll_secs),
+        }
         let mut ticker = interval(Duration::from_secs(poll_secs));
+        let mut phase = Phase::Active;
+
+        let dead_man = dead_man_switch
+            .as_ref()
+            .and_then(|switch| self.arm_dead_man(switch, longest_poll));
 
         while !shutdown.load(Ordering::Relaxed) {
-            ticker.tick().await;
+            match &idle {
+                Some(idle) => {
+                    if self.cycle_count > 0 {
+                        tokio::select! {
+                            _ = tokio::time::sleep(idle.next_poll(Utc::now(), poll_secs)) => {}
+                            _ = service::until_set(&shutdown) => break,
+                        }
+                    }
+                    let next = idle.phase(Utc::now(), poll_secs);
+                    if next != phase {
+                        debug!("💤 Entering {:?} phase", next);
+                    }
+                    phase = next;
+                    recorder_paused.store(phase == Phase::Idle, Ordering::Relaxed);
+                }
+                None => {
+                    // Don't sit out a long poll interval once shutdown is requested
+                    tokio::select! {
+                        _ = ticker.tick() => {}
+                        _ = service::until_set(&shutdown) => break,
+                    }
+                }
+            }
+            let idle_cycle = phase == Phase::Idle;
+            let usage_before = idle
+                .as_ref()
+                .filter(|idle| idle.report_usage)
+                .and_then(|_| ResourceUsage::sample());
             let cycle_start = Instant::now();
 
             self.cycle_count += 1;
+            self.cycle_started_ms = Utc::now().timestamp_millis();
             debug!(
                 "┌─ Cycle {} started at {}",
                 self.cycle_count,
                 Utc::now().format("%H:%M:%S%.3f")
             );
 
+            self.update_kill_switch();
+
+            // Idle cycles only manage fills, stops and the kill switch
+            if !idle_cycle {
+                if let Err(e) = self.check_config_symbols().await {
+                    warn!("⚠️  Config reload failed: {}", e);
+                }
+            }
+
             if let Err(e) = self.process_cycle().await {
                 error!("│  ❌ Cycle error: {}", e);
             }
+            // A cycle in which no symbol's data could be fetched doesn't count as a heartbeat
+            if let Some(switch) = &dead_man {
+                let reachable = self
+                    .config
+                    .trading
+                    .symbols
+                    .iter()
+                    .any(|s| self.data_health.failures(&Symbol::new(s)) == 0);
+                if reachable {
+                    switch.beat();
+                }
+            }
+            // One transaction for everything the cycle persisted
+            if let Err(e) = self.state_writer.commit().await {
+                error!("│  ❌ State writer unavailable: {}", e);
+            }
+            self.check_trailing_drawdown();
+            self.check_circuit_breaker().await;
+            self.publish_standing();
 
             let cycle_latency_us = cycle_start.elapsed().as_micros() as u64;
             self.metrics.update_cycle_latency(cycle_latency_us);
+            if idle_cycle {
+                self.metrics.idle_cycles += 1;
+            }
+            if let (Some(before), Some(after)) = (usage_before, ResourceUsage::sample()) {
+                let cpu = after.cpu_since(&before);
+                self.metrics.record_usage(cpu, &after);
+                info!(
+                    "📉 Cycle {} ({:?}): {} ms wall, {} ms CPU, {:.1} MB RSS",
+                    self.cycle_count,
+                    phase,
+                    cycle_latency_us / 1000,
+                    cpu.as_millis(),
+                    after.rss_bytes as f64 / 1_048_576.0
+                );
+            }
 
             debug!(
                 "└─ Cycle {} complete ({} μs)",
@@ -502,7 +1432,7 @@ impl LiveTrader {
             // Periodic checkpoint
             if self.cycle_count.is_multiple_of(10) {
                 let checkpoint_start = Instant::now();
-                if let Err(e) = self.save_checkpoint() {
+                if let Err(e) = self.save_checkpoint().await {
                     error!("Failed to save checkpoint: {}", e);
                 } else {
                     debug!(
@@ -512,9 +1442,40 @@ impl LiveTrader {
                 }
             }
 
+            if idle_cycle {
+                continue;
+            }
+
+            // Periodic reconciliation against exchange trade history
+            if let Some(recon) = &self.config.exchange.reconcile {
+                if self.last_reconcile.elapsed() >= Duration::from_secs(recon.interval_mins * 60) {
+                    self.reconcile_trades().await;
+                    self.last_reconcile = Instant::now();
+                }
+            }
+
+            // Book deposits and withdrawals as external cash flows
+            if let Some(flows) = &self.config.exchange.cash_flows {
+                if self.last_cash_flow_sync.elapsed()
+                    >= Duration::from_secs(flows.interval_mins * 60)
+                {
+                    self.sync_cash_flows().await;
+                    self.last_cash_flow_sync = Instant::now();
+                }
+            }
+
+            // Sweep idle cash into earn while flat
+            if let Some(earn) = &self.config.exchange.earn_sweep {
+                if self.last_earn_sweep.elapsed() >= Duration::from_secs(earn.interval_mins * 60) {
+                    self.sweep_earn().await;
+                    self.last_earn_sweep = Instant::now();
+                }
+            }
+
             // Log performance metrics every 5 minutes
             if self.last_metrics_log.elapsed() > Duration::from_secs(300) {
                 self.metrics.log_summary();
+                self.refresh_fx_rates().await;
                 self.log_portfolio_status();
                 self.last_metrics_log = Instant::now();
             }
@@ -523,29 +1484,751 @@ impl LiveTrader {
         info!("════════════════════════════════════════════════════════");
         info!("🛑 SHUTDOWN SIGNAL RECEIVED");
         info!("════════════════════════════════════════════════════════");
-        self.save_checkpoint()?;
+        self.save_checkpoint().await?;
+        // Everything queued before shutdown is on disk before we exit
+        self.state_writer.flush().await?;
         self.metrics.log_summary();
         info!("✓ Live trading stopped gracefully");
         Ok(())
     }
 
-    async fn process_cycle(&mut self) -> Result<()> {
-        for sym in &self.config.trading.symbols.clone() {
-            let symbol = Symbol::new(sym);
-
-            let update_start = Instant::now();
-            if let Err(e) = self.update_candles(&symbol).await {
-                warn!("│  ⚠️  Candle update failed for {}: {}", symbol, e);
-                continue;
+    /// Re-read the kill switch and log transitions
+    ///
+    /// A breached trailing drawdown holds the switch on regardless of the files.
+    fn update_kill_switch(&mut self) {
+        let mut state = self.config.kill_switch.check(self.strategy.name());
+        if self.risk_manager.trailing_drawdown_breached() && !state.flatten() {
+            state = self.trailing_drawdown_halt();
+        }
+        if state == self.kill_state {
+            return;
+        }
+        match &state {
+            KillState::Off => info!("✅ Kill switch cleared - resuming new entries"),
+            KillState::Halt { source } => {
+                error!("🛑 KILL SWITCH ({}) - no new positions", source)
             }
-            debug!(
-                "│  ✓ Candles updated for {} ({} μs)",
-                symbol,
-                update_start.elapsed().as_micros()
-            );
-
+            KillState::Flatten { source } => {
+                error!("🛑 KILL SWITCH ({}) - flattening all positions", source)
+            }
+        }
+        self.kill_state = state;
+    }
+
+    /// Kill state held after a trailing drawdown breach
+    fn trailing_drawdown_halt(&self) -> KillState {
+        let source = "trailing drawdown".to_string();
+        if self
+            .config
+            .trading
+            .trailing_drawdown
+            .as_ref()
+            .is_some_and(|t| t.flatten)
+        {
+            KillState::Flatten { source }
+        } else {
+            KillState::Halt { source }
+        }
+    }
+
+    /// Check equity against the trailing drawdown floor; alert on warnings and breaches
+    fn check_trailing_drawdown(&mut self) {
+        let equity = self.calculate_portfolio_value();
+        let Some(event) = self.risk_manager.check_trailing_drawdown(equity) else {
+            return;
+        };
+        match event {
+            TrailingDrawdownEvent::Warning { floor, used, .. } => warn!(
+                "⚠️  Trailing drawdown {:.0}% used (equity {:.2}, floor {:.2})",
+                used * 100.0,
+                equity,
+                floor
+            ),
+            TrailingDrawdownEvent::Breach { floor, .. } => {
+                error!(
+                    "🛑 TRAILING DRAWDOWN BREACHED (equity {:.2} <= floor {:.2}) - account halted",
+                    equity, floor
+                );
+                self.kill_state = self.trailing_drawdown_halt();
+            }
+        }
+        if let Some(webhooks) = &self.webhooks {
+            let now = Utc::now();
+            webhooks.notify_payload(
+                WebhookEventKind::Risk,
+                format!("trailing_drawdown-{}", now.timestamp_millis()),
+                &serde_json::json!({
+                    "event": "risk",
+                    "rule": "trailing_drawdown",
+                    "timestamp": now,
+                    "strategy": self.strategy.name(),
+                    "paper": self.paper_mode,
+                    "detail": event,
+                }),
+            );
+        }
+    }
+
+    /// Report circuit breaker transitions since the last cycle; alert when it opens
+    ///
+    /// Also snapshots the breaker counters and endpoint latencies for the summary.
+    /// Start the dead man's switch over every account's traded markets
+    ///
+    /// Needs CoinDCX's cancel-all; other venues only get a warning.
+    fn arm_dead_man(&self, config: &DeadManSwitchConfig, poll_secs: u64) -> Option<DeadManSwitch> {
+        let Some(default) = self.exchange.as_coindcx() else {
+            warn!("⚠️  Dead man's switch is only supported on CoinDCX - not armed");
+            return None;
+        };
+        let heartbeat = Duration::from_secs(config.heartbeat_secs(poll_secs));
+        let clients: Vec<CoinDCXClient> = std::iter::once(default)
+            .chain(self.account_clients.values())
+            .cloned()
+            .collect();
+        let markets = self.config.trading.symbols.clone();
+        info!(
+            "🪦 Dead man's switch armed: orders cancelled after {} missed {}s heartbeat(s)",
+            config.missed_heartbeats,
+            heartbeat.as_secs()
+        );
+        Some(DeadManSwitch::arm(
+            heartbeat,
+            config.missed_heartbeats,
+            move || {
+                let clients = clients.clone();
+                let markets = markets.clone();
+                async move {
+                    for client in &clients {
+                        for market in &markets {
+                            if let Err(e) = client.cancel_all_orders(market, None).await {
+                                error!(
+                                    "│  ❌ Dead man's switch: cancelling {} failed: {}",
+                                    market, e
+                                );
+                            }
+                        }
+                    }
+                }
+            },
+        ))
+    }
+
+    async fn check_circuit_breaker(&mut self) {
+        let (Some(events), Some(exchange)) = (&mut self.circuit_events, self.exchange.as_coindcx())
+        else {
+            return;
+        };
+        while let Ok((from, to)) = events.try_recv() {
+            match to {
+                CircuitState::Open => {
+                    error!(
+                        "🔌 CoinDCX circuit breaker OPEN ({:?} → Open) - requests rejected",
+                        from
+                    )
+                }
+                CircuitState::HalfOpen => info!("🔌 CoinDCX circuit breaker probing recovery"),
+                CircuitState::Closed => info!("✅ CoinDCX circuit breaker closed"),
+            }
+            if to != CircuitState::Open {
+                continue;
+            }
+            if let Some(webhooks) = &self.webhooks {
+                let now = Utc::now();
+                webhooks.notify_payload(
+                    WebhookEventKind::Risk,
+                    format!("circuit_breaker-{}", now.timestamp_millis()),
+                    &serde_json::json!({
+                        "event": "risk",
+                        "rule": "circuit_breaker",
+                        "timestamp": now,
+                        "strategy": self.strategy.name(),
+                        "paper": self.paper_mode,
+                        "detail": { "from": format!("{:?}", from), "to": "Open" },
+                    }),
+                );
+            }
+        }
+        self.metrics.circuit = Some((
+            exchange.circuit_breaker_state().await,
+            exchange.circuit_breaker_metrics().await,
+        ));
+        self.metrics.endpoint_latency = exchange.metrics();
+    }
+
+    /// Pick up symbols added to the config file since the last check.
+    ///
+    /// New symbols are backfilled and warmed up, then traded from the next cycle.
+    /// Existing symbols, positions and orders are untouched; removals and other
+    /// config edits still need a restart.
+    async fn check_config_symbols(&mut self) -> Result<()> {
+        let modified = modified_time(&self.config_path);
+        if modified.is_some() && modified != self.config_modified {
+            self.config_modified = modified;
+
+            let mut new_config = Config::from_file(&self.config_path)?;
+            crypto_strategies::universe::apply_universe(&mut new_config).await?;
+            let (added, removed) =
+                symbol_changes(&self.config.trading.symbols, &new_config.trading.symbols);
+
+            if !removed.is_empty() {
+                warn!(
+                    "⚠️  Config no longer lists {:?} - still trading them until restart",
+                    removed
+                );
+            }
+            if added.is_empty() {
+                info!("🔁 Config changed - no new symbols (other edits apply on restart)");
+            } else {
+                info!("➕ New symbol(s) in config: {:?}", added);
+            }
+            // A symbol the edit dropped again stops being retried
+            self.pending_symbols = added;
+        }
+
+        for sym in std::mem::take(&mut self.pending_symbols) {
+            let symbol = Symbol::new(&sym);
+            if let Err(e) = self.bootstrap_candles(&symbol).await {
+                // Kept out of the symbol list until a later cycle's backfill succeeds
+                warn!(
+                    "  ⚠️  Backfill failed for {} - retrying next cycle: {}",
+                    symbol, e
+                );
+                self.pending_symbols.push(sym);
+                continue;
+            }
+            self.warm_up(&symbol);
+            self.orderbooks.entry(symbol.clone()).or_default();
+            self.config.trading.symbols.push(sym);
+            info!("  ✓ {} is now live", symbol);
+        }
+        Ok(())
+    }
+
+    /// Print the dry-run plan and wait for the operator's answer
+    async fn preflight(&mut self, confirmed: bool) -> Result<Ack> {
+        self.update_kill_switch();
+        let plan = self.preflight_plan();
+        info!("🧭 PREFLIGHT\n{}", plan.render());
+        if confirmed {
+            info!("✓ Plan {} confirmed with --confirm", plan.id());
+            return Ok(Ack::Confirmed);
+        }
+        let Some(config) = self.config.preflight.clone() else {
+            return Err(anyhow::anyhow!(
+                "Preflight plan {} needs acknowledgment: rerun with --confirm or configure `preflight.listen`",
+                plan.id()
+            ))
+            .context(ConfigFault);
+        };
+        let listener = tokio::net::TcpListener::bind(&config.listen)
+            .await
+            .with_context(|| format!("Failed to listen on {}", config.listen))?;
+        info!(
+            "⏳ Waiting for acknowledgment: GET http://{}/plan, then POST /confirm?plan={} (or POST /abort)",
+            config.listen,
+            plan.id()
+        );
+        preflight::wait_for_ack(listener, &plan, Duration::from_secs(config.timeout_secs)).await
+    }
+
+    /// What the first cycle would do on the bootstrapped candles, without sending anything.
+    /// Entries are sized by the exposure and CVaR caps; the daily risk budget is only
+    /// drawn on by real orders.
+    fn preflight_plan(&self) -> PreflightPlan {
+        let equity = self.calculate_portfolio_value();
+        let mut orders = Vec::new();
+        let mut positions = Vec::new();
+
+        for sym in &self.config.trading.symbols {
+            let symbol = Symbol::new(sym);
+            let Some(mtf_data) = self.candle_cache.get(&symbol) else {
+                continue;
+            };
+            let Some(candles) = mtf_data
+                .get(&self.primary_timeframe)
+                .filter(|c| !c.is_empty())
+            else {
+                continue;
+            };
+            let close = candles[candles.len() - 1].close;
+            let position = self.position_manager.get_position(&symbol);
+
+            if let Some(pos) = position {
+                let (stop, target) = self.entry_levels.get(&symbol).copied().unwrap_or_else(|| {
+                    let entry = pos.average_entry_price.to_f64();
+                    (
+                        self.strategy.calculate_stop_loss(candles, entry, pos.side),
+                        self.strategy
+                            .calculate_take_profit(candles, entry, pos.side),
+                    )
+                });
+                positions.push(PlannedPosition {
+                    symbol: sym.clone(),
+                    side: pos.side,
+                    quantity: pos.quantity.to_f64(),
+                    entry_price: pos.average_entry_price.to_f64(),
+                    stop_loss: self.trailing_stops.get(&symbol).copied().unwrap_or(stop),
+                    take_profit: target,
+                });
+            }
+
+            let mtf_ref = MultiTimeframeCandles::from_data(mtf_data);
+            let open_orders: Vec<_> = self
+                .orderbooks
+                .get(&symbol)
+                .map(|ob| ob.get_all_orders().into_iter().cloned().collect())
+                .unwrap_or_default();
+            let ctx = StrategyContext {
+                symbol: &symbol,
+                candles,
+                mtf_candles: Some(&mtf_ref),
+                current_position: position,
+                open_orders: &open_orders,
+                cash_available: self.paper_cash,
+                equity,
+                peak_equity: self.risk_manager.peak_capital(),
+            };
+            let ctx = SignalContext::new(&ctx).with_open_risk(context::open_risk(
+                self.position_manager.get_all_positions().map(|(_, p)| p),
+            ));
+
+            let dead_reason = match &self.config.trading.activity_filter {
+                Some(filter) if position.is_none() => {
+                    MarketActivity::measure(candles, filter.atr_period)
+                        .dead_reason(&filter.floors_for(sym))
+                }
+                _ => None,
+            };
+
+            for req in self.strategy.generate_signal(&ctx) {
+                let note = self.strategy.commentary(&ctx, &req);
+                let mut order = req.to_order();
+                let price = order.limit_price.map(|p| p.to_f64()).unwrap_or(close);
+                let reduces_position = position.is_some_and(|p| p.side != order.side);
+                let mut blocked = dead_reason.as_ref().map(|r| format!("inactive: {}", r));
+                let (mut stop_loss, mut take_profit) = (None, None);
+
+                if position.is_none() {
+                    let stop = self
+                        .strategy
+                        .calculate_stop_loss(candles, price, order.side);
+                    let target = self
+                        .strategy
+                        .calculate_take_profit(candles, price, order.side);
+                    stop_loss = Some(stop);
+                    take_profit = Some(target);
+                    if let Some(rules) = &self.config.trading.min_edge {
+                        let cost = rules.round_trip_cost(
+                            &self.config.exchange,
+                            &self.config.tax,
+                            rules.spread_bps,
+                        );
+                        if !EdgeCheck::new(price, target, cost).passes(rules.multiple) {
+                            blocked = Some("min_edge".to_string());
+                        }
+                    }
+                    let allowed = self.risk_manager.apply_group_limit(
+                        sym,
+                        price,
+                        order.quantity.to_f64(),
+                        self.position_manager.get_all_positions().map(|(_, p)| p),
+                    );
+                    let allowed = self.risk_manager.apply_cvar_limit(price, allowed);
+                    if allowed <= 0.0 {
+                        blocked.get_or_insert_with(|| "exposure_cap".to_string());
+                    } else if allowed < order.quantity.to_f64() {
+                        order.quantity = Money::from_f64(allowed);
+                    }
+                }
+
+                if self.risk_manager.should_halt_trading() {
+                    blocked.get_or_insert_with(|| "risk halt".to_string());
+                }
+                if !reduces_position {
+                    if self.kill_state.is_active() {
+                        blocked.get_or_insert_with(|| "kill switch".to_string());
+                    }
+                    if !self
+                        .risk_manager
+                        .can_open_position_count(self.position_manager.open_position_count())
+                    {
+                        blocked.get_or_insert_with(|| "max positions".to_string());
+                    }
+                }
+                let pre_trade = PreTradeContext {
+                    last_close: close,
+                    now: Utc::now(),
+                    reduces_position,
+                };
+                if let Err(rejection) = self.pre_trade.check(&order, &pre_trade) {
+                    blocked.get_or_insert_with(|| rejection.to_string());
+                }
+
+                orders.push(PlannedOrder {
+                    symbol: sym.clone(),
+                    side: order.side,
+                    order_type: order.order_type,
+                    quantity: order.quantity.to_f64(),
+                    price,
+                    stop_loss,
+                    take_profit,
+                    reduces_position,
+                    blocked,
+                    note,
+                });
+            }
+        }
+
+        PreflightPlan {
+            strategy: self.strategy.name().to_string(),
+            paper: self.paper_mode,
+            generated_at: Utc::now(),
+            equity,
+            cash: self.paper_cash,
+            orders,
+            positions,
+        }
+    }
+
+    /// Replay backfilled bars through the strategy's per-bar hook so per-symbol
+    /// state (cooldowns, counters) starts where it would have been
+    fn warm_up(&mut self, symbol: &Symbol) {
+        let Some(candles) = self
+            .candle_cache
+            .get(symbol)
+            .and_then(|mtf| mtf.get(&self.primary_timeframe))
+        else {
+            return;
+        };
+        let equity = self.calculate_portfolio_value();
+        for end in 1..=candles.len() {
+            let ctx = StrategyContext {
+                symbol,
+                candles: &candles[..end],
+                mtf_candles: None,
+                current_position: None,
+                open_orders: &[],
+                cash_available: self.paper_cash,
+                equity,
+                peak_equity: self.risk_manager.peak_capital(),
+            };
+            self.strategy.on_bar(&ctx);
+        }
+        info!(
+            "  ✓ Warmed up {} on {} {} bars",
+            symbol,
+            candles.len(),
+            self.primary_timeframe
+        );
+    }
+
+    /// Subscribe to the trade stream; on failure REST candles carry on alone
+    async fn connect_trade_feed(&mut self) {
+        let symbols = self.config.trading.symbols.clone();
+        let url = coindcx_ws::STREAM_URL;
+        match MarketStream::reconnecting(url, &symbols, reconnect::reconnect_policy()).await {
+            Ok(stream) => {
+                let intervals: Vec<u64> = self
+                    .required_timeframes
+                    .iter()
+                    .map(|tf| self.parse_tf_seconds(tf))
+                    .collect();
+                let candles = TradeCandles::new(&intervals, Utc::now().timestamp_millis());
+                self.trade_feed = Some(TradeFeed { stream, candles });
+                info!("📡 Market stream connected for {} symbol(s)", symbols.len());
+            }
+            Err(e) => warn!("⚠️  Market stream unavailable, using REST candles: {:#}", e),
+        }
+    }
+
+    /// Fold trades received since the last cycle into the forming bars
+    fn drain_trade_feed(&mut self) {
+        let Some(feed) = self.trade_feed.as_mut() else {
+            return;
+        };
+        match feed.stream.drain() {
+            Some(events) => {
+                for event in events {
+                    match event {
+                        StreamEvent::Message(MarketEvent::Trade(trade)) => {
+                            feed.candles.record(&trade)
+                        }
+                        StreamEvent::Message(MarketEvent::Ticker(_)) => {}
+                        // Bars forming during the outage come from REST until the next one opens
+                        StreamEvent::Gap(gap) => {
+                            warn!(
+                                "⚠️  Market stream was down for {} s - REST candles until the next bar",
+                                (gap.to - gap.from) / 1000
+                            );
+                            feed.candles.restart(gap.to);
+                        }
+                    }
+                }
+            }
+            None => {
+                warn!("⚠️  Market stream gave up reconnecting - falling back to REST candles");
+                self.trade_feed = None;
+            }
+        }
+    }
+
+    /// Subscribe to Binance klines; on failure candles are polled from the venue
+    async fn connect_kline_feed(&mut self) {
+        let symbols = self.config.trading.symbols.clone();
+        let url = if self.config.exchange.sandbox {
+            binance::ws::TESTNET_STREAM_URL
+        } else {
+            binance::ws::STREAM_URL
+        };
+        let policy = reconnect::reconnect_policy();
+        match KlineStream::reconnecting(url, &symbols, &self.required_timeframes, policy).await {
+            Ok(stream) => {
+                self.kline_feed = Some(stream);
+                self.kline_symbols = symbols.iter().cloned().collect();
+                info!(
+                    "📡 Binance kline stream connected for {} symbol(s)",
+                    symbols.len()
+                );
+            }
+            Err(e) => warn!(
+                "⚠️  Binance kline stream unavailable, using REST candles: {:#}",
+                e
+            ),
+        }
+    }
+
+    /// Add bars closed since the last cycle to the candle cache
+    fn drain_kline_feed(&mut self) {
+        let Some(feed) = self.kline_feed.as_mut() else {
+            return;
+        };
+        let Some(bars) = feed.drain() else {
+            warn!("⚠️  Binance kline stream gave up reconnecting - falling back to REST candles");
+            self.kline_feed = None;
+            return;
+        };
+        for event in bars {
+            let bar = match event {
+                StreamEvent::Message(bar) => bar,
+                StreamEvent::Gap(gap) => {
+                    self.kline_gaps.push(gap);
+                    continue;
+                }
+            };
+            let candle = match crypto_strategies::Candle::try_from(bar.kline) {
+                Ok(candle) => candle,
+                Err(e) => {
+                    warn!(
+                        "⚠️  Invalid streamed {} {} bar: {}",
+                        bar.symbol, bar.interval, e
+                    );
+                    continue;
+                }
+            };
+            if let Some(candles) = self
+                .candle_cache
+                .get_mut(&Symbol::new(&bar.symbol))
+                .and_then(|mtf| mtf.get_mut(&bar.interval))
+            {
+                merge_candle(candles, candle);
+            }
+        }
+    }
+
+    /// Subscribe to the account's order updates; without them fills are simulated
+    async fn connect_user_stream(&mut self) {
+        let Some(credentials) = self
+            .exchange
+            .as_coindcx()
+            .map(|exchange| exchange.credentials())
+            .filter(|credentials| !credentials.api_key().is_empty())
+        else {
+            return;
+        };
+        let url = coindcx_ws::STREAM_URL;
+        match UserStream::reconnecting(url, credentials, reconnect::reconnect_policy()).await {
+            Ok(stream) => {
+                self.user_stream = Some(stream);
+                info!("📡 Order update stream connected - fills as reported by the exchange");
+            }
+            Err(e) => warn!(
+                "⚠️  Order update stream unavailable, simulating fills: {:#}",
+                e
+            ),
+        }
+    }
+
+    /// Keep the latest exchange update of every order
+    fn drain_user_stream(&mut self) {
+        let Some(stream) = self.user_stream.as_mut() else {
+            return;
+        };
+        let Some(events) = stream.drain() else {
+            warn!("⚠️  Order update stream gave up reconnecting - simulating fills");
+            self.user_stream = None;
+            return;
+        };
+        for event in events {
+            let event = match event {
+                StreamEvent::Message(event) => event,
+                StreamEvent::Gap(gap) => {
+                    warn!(
+                        "⚠️  Order update stream was down for {} s - re-reading resting orders",
+                        (gap.to - gap.from) / 1000
+                    );
+                    self.order_updates_missed = true;
+                    continue;
+                }
+            };
+            match event {
+                UserEvent::Order(update) => {
+                    let key = update
+                        .client_order_id
+                        .clone()
+                        .unwrap_or_else(|| update.id.clone());
+                    debug!(
+                        "│  📨 Order {} {}: {:.6}/{:.6} @ {:.2}",
+                        key,
+                        update.status,
+                        update.filled_quantity(),
+                        update.total_quantity,
+                        update.avg_price
+                    );
+                    self.order_updates.insert(key, update);
+                }
+                UserEvent::Balance(balance) => debug!(
+                    "│  💼 Balance {}: {:.6} ({:.6} locked)",
+                    balance.currency, balance.balance, balance.locked_balance
+                ),
+            }
+        }
+    }
+
+    /// Fetch over REST what the streams missed while they were reconnecting
+    async fn backfill_stream_gaps(&mut self) {
+        for gap in std::mem::take(&mut self.kline_gaps) {
+            self.backfill_klines(gap).await;
+        }
+        if std::mem::take(&mut self.order_updates_missed) {
+            self.backfill_order_updates().await;
+        }
+    }
+
+    /// Merge the Binance bars that closed while the kline stream was down
+    async fn backfill_klines(&mut self, gap: Gap) {
+        let client = BinanceClient::new().with_sandbox(self.config.exchange.sandbox);
+        let now = Utc::now().timestamp_millis();
+        let mut added = 0;
+        for sym in self.config.trading.symbols.clone() {
+            let symbol = Symbol::new(&sym);
+            for tf in self.required_timeframes.clone() {
+                // From the bar that was forming when the connection dropped
+                let from = gap.from - self.parse_tf_seconds(&tf) as i64 * 1000;
+                let pair = client.to_binance_pair(&sym);
+                let klines = match client
+                    .get_klines(&pair, &tf, Some(from), Some(gap.to), Some(1000))
+                    .await
+                {
+                    Ok(klines) => klines,
+                    Err(e) => {
+                        warn!("⚠️  Could not backfill {} {} bars: {:#}", sym, tf, e);
+                        continue;
+                    }
+                };
+                let Some(candles) = self
+                    .candle_cache
+                    .get_mut(&symbol)
+                    .and_then(|mtf| mtf.get_mut(&tf))
+                else {
+                    continue;
+                };
+                for kline in klines.into_iter().filter(|k| k.close_time < now) {
+                    if let Ok(candle) = crypto_strategies::Candle::try_from(kline) {
+                        added += insert_candle(candles, candle) as usize;
+                    }
+                }
+            }
+        }
+        info!(
+            "📡 Backfilled {} bar(s) missed during a {} s kline stream outage",
+            added,
+            (gap.to - gap.from) / 1000
+        );
+    }
+
+    /// Re-read every resting order, whose updates the order stream may have missed
+    async fn backfill_order_updates(&mut self) {
+        let Some(client) = self.exchange.as_coindcx() else {
+            return;
+        };
+        let ids: Vec<String> = self
+            .orderbooks
+            .values()
+            .flat_map(|book| book.get_all_orders())
+            .filter_map(|order| order.client_id.clone())
+            .collect();
+        for id in ids {
+            match client.get_order_status_by_client_id(&id).await {
+                Ok(order) => {
+                    self.order_updates.insert(id, OrderUpdate::from(&order));
+                }
+                Err(e) => debug!("│  Could not re-read order {}: {}", id, e),
+            }
+        }
+    }
+
+    async fn process_cycle(&mut self) -> Result<()> {
+        self.drain_trade_feed();
+        self.drain_kline_feed();
+        self.drain_user_stream();
+        self.backfill_stream_gaps().await;
+        for sym in &self.config.trading.symbols.clone() {
+            let symbol = Symbol::new(sym);
+
+            let update_start = Instant::now();
+            match self.update_candles(&symbol).await {
+                Ok(()) => {
+                    debug!(
+                        "│  ✓ Candles updated for {} ({} μs)",
+                        symbol,
+                        update_start.elapsed().as_micros()
+                    );
+                    if let Some(run) = self.data_health.record_success(&symbol) {
+                        info!(
+                            "│  ✅ Data recovered for {} after {} failed cycle(s) since {}",
+                            symbol,
+                            run.failures,
+                            run.since.format("%H:%M:%S")
+                        );
+                    }
+                }
+                Err(e) => {
+                    let failures =
+                        self.data_health
+                            .record_failure(&symbol, Utc::now(), e.to_string());
+                    warn!(
+                        "│  ⚠️  Candle update failed for {} ({} in a row): {}",
+                        symbol, failures, e
+                    );
+                }
+            }
+
+            let action = self
+                .config
+                .trading
+                .degraded_data
+                .action(sym, self.data_health.failures(&symbol));
+            match action {
+                DataAction::Trade => {}
+                DataAction::Skip => continue,
+                _ => {
+                    self.metrics.degraded_cycles += 1;
+                    warn!("│  🩹 {} degraded: {:?}", symbol, action);
+                }
+            }
+
             let process_start = Instant::now();
-            if let Err(e) = self.process_symbol(&symbol).await {
+            if let Err(e) = self.process_symbol(&symbol, action).await {
                 error!("│  ❌ Symbol processing failed for {}: {}", symbol, e);
             } else {
                 debug!(
@@ -555,46 +2238,75 @@ impl LiveTrader {
                 );
             }
         }
+        self.update_hedge().await;
         Ok(())
     }
 
     async fn update_candles(&mut self, symbol: &Symbol) -> Result<()> {
         use crypto_strategies::Candle;
 
-        for tf in &self.required_timeframes.clone() {
-            if let Ok(raw_candles) = self
-                .exchange
-                .get_candles(symbol.as_str(), tf, Some(2))
-                .await
-            {
-                if let Some(latest_raw) = raw_candles.last() {
-                    if let Ok(latest) = Candle::try_from(latest_raw.clone()) {
-                        if let Some(mtf_data) = self.candle_cache.get_mut(symbol) {
-                            if let Some(candles) = mtf_data.get_mut(tf) {
-                                // Update last candle or append if new
-                                if let Some(last) = candles.last() {
-                                    if last.datetime == latest.datetime {
-                                        // Update existing candle
-                                        if let Some(last_mut) = candles.last_mut() {
-                                            *last_mut = latest;
-                                        }
-                                    } else {
-                                        // New candle
-                                        candles.push(latest);
-                                    }
-                                } else {
-                                    candles.push(latest);
-                                }
-                            }
-                        }
+        // Closed bars from the kline stream were merged in drain_kline_feed
+        let on_stream = self.kline_feed.is_some() && self.kline_symbols.contains(symbol.as_str());
+        let polled = if on_stream {
+            Vec::new()
+        } else {
+            self.required_timeframes.clone()
+        };
+        for tf in &polled {
+            let streamed = self.trade_feed.as_ref().and_then(|feed| {
+                feed.candles
+                    .current(
+                        symbol.as_str(),
+                        self.parse_tf_seconds(tf),
+                        Utc::now().timestamp_millis(),
+                    )
+                    .cloned()
+            });
+            let latest = match (streamed, &self.feed) {
+                (Some(bar), _) => {
+                    Candle::try_from(bar).with_context(|| format!("invalid streamed {} bar", tf))?
+                }
+                (None, feed) => {
+                    let candles = match feed {
+                        Some(feed) => feed.get_candles(&self.exchange, symbol.as_str(), tf).await,
+                        None => self.exchange.get_candles(symbol.as_str(), tf, 2).await,
                     }
+                    .with_context(|| format!("{} candles", tf))?;
+                    candles
+                        .last()
+                        .cloned()
+                        .with_context(|| format!("no {} candles returned", tf))?
                 }
+            };
+            if let Some(candles) = self
+                .candle_cache
+                .get_mut(symbol)
+                .and_then(|mtf_data| mtf_data.get_mut(tf))
+            {
+                merge_candle(candles, latest);
+            }
+        }
+
+        // A feed that answers but stopped moving is as bad as one that errors
+        let bar_secs = self.parse_tf_seconds(&self.primary_timeframe);
+        if let Some(latest) = self
+            .candle_cache
+            .get(symbol)
+            .and_then(|mtf| mtf.get(&self.primary_timeframe))
+            .and_then(|candles| candles.last())
+        {
+            if degraded::is_stale(latest, bar_secs, Utc::now()) {
+                anyhow::bail!(
+                    "stale data: latest {} bar is from {}",
+                    self.primary_timeframe,
+                    latest.datetime.format("%Y-%m-%d %H:%M")
+                );
             }
         }
         Ok(())
     }
 
-    async fn process_symbol(&mut self, symbol: &Symbol) -> Result<()> {
+    async fn process_symbol(&mut self, symbol: &Symbol, data: DataAction) -> Result<()> {
         let mtf_data = self.candle_cache.get(symbol).context("MTF missing")?;
         let candles = mtf_data
             .get(&self.primary_timeframe)
@@ -612,7 +2324,9 @@ impl LiveTrader {
         // Calculate portfolio value before getting mutable orderbook reference
         // to avoid borrow checker conflicts
         let equity = self.calculate_portfolio_value();
-        let cash_available = self.paper_cash;
+        // Flexible earn funds count as cash; entries redeem them first
+        let cash_available = self.paper_cash + self.earn_balance;
+        let earn = self.earn();
 
         let orderbook = match self.orderbooks.get_mut(symbol) {
             Some(ob) => ob,
@@ -622,36 +2336,225 @@ impl LiveTrader {
             }
         };
 
+        // Drop limit orders whose time-in-force has run out (e.g. spread-guard limits)
+        for order in orderbook.expire_orders(Utc::now()) {
+            cancel_sent(&self.exchange, &mut self.sent_orders, &order).await;
+            audit_order(
+                self.audit.as_mut(),
+                AuditRecord::new(ExecType::Canceled, &order).with_text("expired"),
+            );
+            info!(
+                "│  ⌛ ORDER EXPIRED: {:?} {} {:.6} @ {:.2}",
+                order.side,
+                order.symbol,
+                order.remaining_quantity,
+                order.limit_price.map(|p| p.to_f64()).unwrap_or(0.0)
+            );
+        }
+
+        // Kill switch or degraded-data flatten: drop working orders and close the position at market
+        if self.kill_state.flatten() || data == DataAction::Flatten {
+            for id in orderbook.get_all_order_ids() {
+                if let Some(order) = orderbook.get_order(id).filter(|o| o.is_active()).cloned() {
+                    cancel_sent(&self.exchange, &mut self.sent_orders, &order).await;
+                    audit_order(
+                        self.audit.as_mut(),
+                        AuditRecord::new(ExecType::Canceled, &order).with_text("flatten"),
+                    );
+                }
+                orderbook.cancel_order(id);
+            }
+            if let Some(pos) = self.position_manager.get_position(symbol) {
+                unpark_exits(
+                    &self.exchange,
+                    self.account_clients
+                        .get(self.accounts.account_for(symbol.as_str())),
+                    &mut self.parked_exits,
+                    symbol,
+                )
+                .await;
+                let exit = match pos.side {
+                    Side::Buy => crypto_strategies::oms::OrderRequest::market_sell(
+                        symbol.clone(),
+                        pos.quantity.to_f64(),
+                    ),
+                    Side::Sell => crypto_strategies::oms::OrderRequest::market_buy(
+                        symbol.clone(),
+                        pos.quantity.to_f64(),
+                    ),
+                };
+                place_order(
+                    &self.exchange,
+                    &self.state_manager,
+                    self.audit.as_mut(),
+                    orderbook,
+                    self.cycle_started_ms,
+                    (!self.paper_mode).then_some(&mut self.sent_orders),
+                    exit.to_order(),
+                )
+                .await;
+                warn!("│  🛑 FLATTEN {} {:.6} @ market", symbol, pos.quantity);
+                self.entry_levels.remove(symbol);
+                self.trailing_stops.remove(symbol);
+                self.ladder_states.remove(symbol);
+            }
+        }
+
+        // Margin shorts pay borrow interest for the time they stay open
+        if let (Some(borrow), Some(pos)) = (
+            self.config.trading.borrow.as_ref(),
+            self.position_manager.get_position_mut(symbol),
+        ) {
+            if pos.side == Side::Sell {
+                let now = Utc::now();
+                let from = self
+                    .borrow_accrued
+                    .get(symbol)
+                    .copied()
+                    .filter(|t| *t > pos.first_entry_time)
+                    .unwrap_or(pos.first_entry_time);
+                let notional = pos.quantity.to_f64() * current_candle.close;
+                let interest = borrow.interest(symbol.as_str(), notional, from, now);
+                pos.accrue_borrow(interest);
+                book_cash(
+                    self.fx.as_ref(),
+                    &mut self.paper_cash,
+                    &mut self.quote_cash,
+                    symbol.as_str(),
+                    -interest,
+                );
+                self.borrow_accrued.insert(symbol.clone(), now);
+                debug!(
+                    "│  💸 Borrow interest {} {:.4} (total {:.2})",
+                    symbol, interest, pos.borrow_cost
+                );
+            }
+        }
+
+        // Best bid/ask, fetched at most once per cycle and only if the spread guard needs it
+        let mut quote: Option<Option<(f64, f64)>> = None;
+
         // Step 1: Check fills (microsecond precision)
         let fill_check_start = Instant::now();
         let mut orders: Vec<_> = orderbook.get_all_orders().into_iter().cloned().collect();
         let initial_order_count = orders.len();
 
         for order in &mut orders {
-            // Live trading passes None for bar_idx - no look-ahead bias concern in real-time
-            if let Some(fill_price) = self
-                .execution_engine
-                .check_fill(order, current_candle, None)
-            {
+            let reported = match &self.user_stream {
+                // The exchange says what filled; the candle is only a guess
+                Some(_) => reported_fill(&mut self.order_updates, order, orderbook),
+                // Live trading passes None for bar_idx - no look-ahead bias concern in real-time
+                None => self
+                    .execution_engine
+                    .check_fill(order, current_candle, None)
+                    .map(|f| (f.price, order.remaining_quantity.to_f64(), f.is_maker)),
+            };
+            if let Some((price, quantity, is_maker)) = reported {
                 let fill_latency = fill_check_start.elapsed().as_micros();
-                let is_maker = fill_price.is_maker;
-                let price = fill_price.price;
-                let fill = self.execution_engine.execute_fill(
+                let fill = self.execution_engine.execute_partial_fill(
                     order,
                     price,
+                    quantity,
                     is_maker,
                     current_candle.datetime,
                 );
 
-                self.position_manager
-                    .add_fill(fill.clone(), order.symbol.clone(), order.side);
+                let prior = self
+                    .position_manager
+                    .get_position(&order.symbol)
+                    .map(|p| (p.side, p.quantity.to_f64()));
                 self.metrics.record_fill();
+                audit_order(self.audit.as_mut(), AuditRecord::fill(order, &fill));
+
+                let intent_id = order
+                    .client_id
+                    .clone()
+                    .unwrap_or_else(|| order.id.to_string());
+                let record = FillRecord {
+                    id: None,
+                    order_id: order.id.to_string(),
+                    symbol: order.symbol.as_str().to_string(),
+                    side: format!("{:?}", order.side).to_lowercase(),
+                    quantity: fill.quantity.to_f64(),
+                    price: fill.price.to_f64(),
+                    fee: fill.commission.to_f64(),
+                    timestamp: reconcile::fill_timestamp(fill.timestamp),
+                    paper: self.paper_mode,
+                    fill_id: Some(intents::local_fill_id(
+                        &intent_id,
+                        order.filled_quantity.to_f64(),
+                    )),
+                };
+                let closing = book_fill(
+                    &mut self.position_manager,
+                    &order.symbol,
+                    order.side,
+                    fill.clone(),
+                    &record,
+                );
+
+                // Fill, intent status and closing trade commit in the same batch
+                let mut events = vec![
+                    StateEvent::Fill(record),
+                    StateEvent::Intent {
+                        intent_id,
+                        status: if order.remaining_quantity.is_zero() {
+                            IntentStatus::Filled
+                        } else {
+                            IntentStatus::Submitted
+                        },
+                        exchange_order_id: None,
+                    },
+                ];
+                events.extend(closing.map(StateEvent::Trade));
+                for event in events {
+                    if let Err(e) = self.state_writer.send(event).await {
+                        warn!("│  ⚠️  Failed to log fill for reconciliation: {}", e);
+                    }
+                }
+
+                if let Some(webhooks) = &self.webhooks {
+                    let rationale = self.entry_notes.get(&order.symbol).cloned();
+                    let opening = prior.is_none();
+                    webhooks.notify(
+                        WebhookEvent::fill(&fill, &order.symbol, order.side, self.paper_mode)
+                            .with_rationale(rationale.clone().filter(|_| opening)),
+                    );
+                    if let Some((prior_side, prior_qty)) = prior {
+                        let closed = self
+                            .position_manager
+                            .get_position_raw(&order.symbol)
+                            .filter(|p| prior_side != order.side && p.quantity.is_zero());
+                        if let Some(pos) = closed {
+                            webhooks.notify(
+                                WebhookEvent::close(
+                                    &order.symbol,
+                                    prior_side,
+                                    fill.price.to_f64(),
+                                    prior_qty,
+                                    pos.realized_pnl.to_f64(),
+                                    fill.commission.to_f64(),
+                                    fill.timestamp,
+                                    self.paper_mode,
+                                )
+                                .with_rationale(rationale),
+                            );
+                        }
+                    }
+                }
 
                 if let Some(pos) = self.position_manager.get_position(&order.symbol) {
                     self.strategy.on_order_filled(&fill, pos);
                 }
 
-                orderbook.mark_filled(order.id);
+                if order.remaining_quantity.is_zero() {
+                    orderbook.mark_filled(order.id);
+                    if let Some(client_id) = &order.client_id {
+                        self.sent_orders.remove(client_id);
+                    }
+                } else if let Some(resting) = orderbook.get_order_mut(order.id) {
+                    *resting = order.clone();
+                }
 
                 info!(
                     "│  💰 FILL #{} [{}μs latency]",
@@ -694,8 +2597,6 @@ impl LiveTrader {
         // Step 2: Check stop loss / take profit / trailing stops
         // This mirrors the backtest.rs logic for production parity
         if let Some(pos) = self.position_manager.get_position(symbol).cloned() {
-            let price = current_candle.close;
-
             // Get or calculate stop/target levels (cached at entry time)
             let (stop_price, target_price) =
                 self.entry_levels.entry(symbol.clone()).or_insert_with(|| {
@@ -711,37 +2612,184 @@ impl LiveTrader {
                     (stop, target)
                 });
             let stop_price = *stop_price;
-            let target_price = *target_price;
-
-            // Update trailing stop if strategy provides one
-            if let Some(new_trailing) = self.strategy.update_trailing_stop(&pos, price, candles) {
+            let mut target_price = *target_price;
+
+            // Update trailing stop if strategy provides one. The latest candle is still
+            // forming, so intrabar mode ratchets from its running high/low.
+            let trail_price = trailing_reference_price(
+                pos.side,
+                current_candle,
+                self.config.trading.intrabar_trailing,
+            );
+            if let Some(new_trailing) =
+                self.strategy
+                    .update_trailing_stop(&pos, trail_price, candles)
+            {
                 let current_stored = self.trailing_stops.get(symbol).copied();
-                let best_stop = match current_stored {
-                    Some(stored) => new_trailing.max(stored), // Never lower the trailing stop
-                    None => new_trailing,
+                // Never loosen the trailing stop: it only rises for longs, falls for shorts
+                let best_stop = match (current_stored, pos.side) {
+                    (Some(stored), Side::Buy) => new_trailing.max(stored),
+                    (Some(stored), Side::Sell) => new_trailing.min(stored),
+                    (None, _) => new_trailing,
                 };
                 self.trailing_stops.insert(symbol.clone(), best_stop);
             }
 
             // Use trailing stop if set, otherwise initial stop
-            let active_stop = self
+            let mut active_stop = self
                 .trailing_stops
                 .get(symbol)
                 .copied()
                 .unwrap_or(stop_price);
+            // Degraded data under `hold`: give stale prices more room
+            if let DataAction::Hold { stop_widen } = data {
+                let entry = pos.average_entry_price.to_f64();
+                active_stop = entry + (active_stop - entry) * stop_widen;
+            }
+
+            // Exit ladder: scale out one rung per cycle at market; the runner
+            // trails unless the strategy's target is kept
+            let mut exit_qty = pos.quantity.to_f64();
+            if let Some(ladder) = self.config.trading.exit_ladder.clone() {
+                if !self.ladder_states.contains_key(symbol) {
+                    if let Some(state) = LadderState::new(&pos, stop_price) {
+                        self.ladder_states.insert(symbol.clone(), state);
+                    }
+                }
+                if let Some(state) = self.ladder_states.get_mut(symbol) {
+                    if let Some((rung, qty)) = state.next_rung(&ladder, pos.quantity.to_f64()) {
+                        let rung_price = state.price_at(pos.side, rung.r);
+                        let rung_check = check_exit(
+                            pos.side,
+                            current_candle,
+                            active_stop,
+                            rung_price,
+                            self.config.trading.same_bar_policy,
+                        );
+                        if rung_check.target_hit {
+                            unpark_exits(
+                                &self.exchange,
+                                self.account_clients
+                                    .get(self.accounts.account_for(symbol.as_str())),
+                                &mut self.parked_exits,
+                                symbol,
+                            )
+                            .await;
+                            state.filled += 1;
+                            exit_qty -= qty;
+                            let exit = match pos.side {
+                                Side::Buy => crypto_strategies::oms::OrderRequest::market_sell(
+                                    symbol.clone(),
+                                    qty,
+                                ),
+                                Side::Sell => crypto_strategies::oms::OrderRequest::market_buy(
+                                    symbol.clone(),
+                                    qty,
+                                ),
+                            };
+                            place_order(
+                                &self.exchange,
+                                &self.state_manager,
+                                self.audit.as_mut(),
+                                orderbook,
+                                self.cycle_started_ms,
+                                (!self.paper_mode).then_some(&mut self.sent_orders),
+                                exit.to_order(),
+                            )
+                            .await;
+                            info!(
+                                "│  🪜 LADDER {} for {}: closing {:.6} @ market ({:.2}R, trigger {:.2})",
+                                rung_label(&rung),
+                                symbol,
+                                qty,
+                                state.r_multiple(pos.side, current_candle.close),
+                                rung_price
+                            );
+                            if ladder.breakeven_after_first && state.filled == 1 {
+                                let entry = state.entry_price;
+                                let stop =
+                                    self.trailing_stops.entry(symbol.clone()).or_insert(entry);
+                                *stop = match pos.side {
+                                    Side::Buy => stop.max(entry),
+                                    Side::Sell => stop.min(entry),
+                                };
+                            }
+                        }
+                    }
+                }
+                if !ladder.keep_target {
+                    target_price = match pos.side {
+                        Side::Buy => f64::INFINITY,
+                        Side::Sell => f64::NEG_INFINITY,
+                    };
+                }
+            }
 
-            // Check stop/target hit
-            let stopped = match pos.side {
-                Side::Buy => price <= active_stop,
-                Side::Sell => price >= active_stop,
+            // Live on venues with OCO orders the levels rest on the exchange, so a
+            // stop between cycles fills when it is hit rather than at the next close
+            let can_park = !self.paper_mode
+                && self.config.trading.exit_ladder.is_none()
+                && !self.unparkable.contains(symbol);
+            let levels = (active_stop, target_price);
+            let (parking, parked_fill) = match self
+                .account_clients
+                .get(self.accounts.account_for(symbol.as_str()))
+            {
+                _ if !can_park => (false, None),
+                Some(client) => {
+                    park_position(
+                        client,
+                        &mut self.unconfirmed,
+                        &mut self.unparkable,
+                        &mut self.parked_exits,
+                        symbol,
+                        &pos,
+                        levels,
+                    )
+                    .await
+                }
+                None if self.exchange.supports_oco() => {
+                    park_position(
+                        &self.exchange,
+                        &mut self.unconfirmed,
+                        &mut self.unparkable,
+                        &mut self.parked_exits,
+                        symbol,
+                        &pos,
+                        levels,
+                    )
+                    .await
+                }
+                None => (false, None),
             };
 
-            let target_hit = match pos.side {
-                Side::Buy => current_candle.high >= target_price,
-                Side::Sell => current_candle.low <= target_price,
+            // Check stop/target hit (same-bar conflicts resolved by policy)
+            let ExitCheck {
+                stopped,
+                target_hit,
+                ..
+            } = match (&parked_fill, parking) {
+                (Some((target_hit, _)), _) => ExitCheck {
+                    stopped: !target_hit,
+                    target_hit: *target_hit,
+                    ambiguous: false,
+                },
+                // The exchange watches the levels while the pair is parked
+                (None, true) if self.parked_exits.contains_key(symbol) => ExitCheck {
+                    stopped: false,
+                    target_hit: false,
+                    ambiguous: false,
+                },
+                _ => check_exit(
+                    pos.side,
+                    current_candle,
+                    active_stop,
+                    target_price,
+                    self.config.trading.same_bar_policy,
+                ),
             };
 
-            if stopped || target_hit {
+            if (stopped || target_hit) && exit_qty > 0.0 {
                 let reason = if target_hit { "TARGET" } else { "STOP" };
                 let trigger_price = if target_hit {
                     target_price
@@ -756,20 +2804,48 @@ impl LiveTrader {
 
                 // Create exit order - opposite side to close position
                 let exit_order = match pos.side {
-                    Side::Buy => crypto_strategies::oms::OrderRequest::market_sell(
-                        symbol.clone(),
-                        pos.quantity.to_f64(),
-                    ),
-                    Side::Sell => crypto_strategies::oms::OrderRequest::market_buy(
-                        symbol.clone(),
-                        pos.quantity.to_f64(),
-                    ),
+                    Side::Buy => {
+                        crypto_strategies::oms::OrderRequest::market_sell(symbol.clone(), exit_qty)
+                    }
+                    Side::Sell => {
+                        crypto_strategies::oms::OrderRequest::market_buy(symbol.clone(), exit_qty)
+                    }
                 };
 
                 // Add to orderbook for execution
-                let order = exit_order.to_order();
+                let mut order = exit_order.to_order();
                 let exit_side = order.side;
-                orderbook.add_order(order.clone());
+                // A parked leg that filled is already done on the exchange
+                let live = !self.paper_mode && parked_fill.is_none();
+                if live {
+                    unpark_exits(
+                        &self.exchange,
+                        self.account_clients
+                            .get(self.accounts.account_for(symbol.as_str())),
+                        &mut self.parked_exits,
+                        symbol,
+                    )
+                    .await;
+                }
+                if let Some((_, leg_id)) = parked_fill {
+                    // Already filled on the exchange: book it under the leg's ID
+                    order.client_id = Some(leg_id);
+                } else if let Some(guard) = &self.config.exchange.spread_guard {
+                    if quote.is_none() {
+                        quote = Some(fetch_quote(&self.exchange, symbol).await);
+                    }
+                    apply_spread_guard(guard, quote.flatten(), &mut order, true);
+                }
+                place_order(
+                    &self.exchange,
+                    &self.state_manager,
+                    self.audit.as_mut(),
+                    orderbook,
+                    self.cycle_started_ms,
+                    live.then_some(&mut self.sent_orders),
+                    order,
+                )
+                .await;
 
                 info!(
                     "│  📋 EXIT ORDER placed: {} {} @ market",
@@ -784,12 +2860,36 @@ impl LiveTrader {
                 // Clear cached levels for this position
                 self.entry_levels.remove(symbol);
                 self.trailing_stops.remove(symbol);
+                self.ladder_states.remove(symbol);
             }
+        } else {
+            // Flat (e.g. the last rung took the whole position): forget its ladder
+            self.ladder_states.remove(symbol);
+            self.unparkable.remove(symbol);
+            self.unconfirmed.remove(symbol);
+            unpark_exits(
+                &self.exchange,
+                self.account_clients
+                    .get(self.accounts.account_for(symbol.as_str())),
+                &mut self.parked_exits,
+                symbol,
+            )
+            .await;
         }
 
         // Step 3: Check closed positions
         if let Some(pos) = self.position_manager.get_position(symbol) {
             if pos.quantity.is_zero() && pos.fills.len() > 1 {
+                let mut fees = pos
+                    .fills
+                    .iter()
+                    .flat_map(|f| f.fee_ledger(self.config.exchange.gst_rate))
+                    .fold(FeeLedger::new(), |mut ledger, (kind, amount)| {
+                        add_fee(&mut ledger, kind, amount);
+                        ledger
+                    });
+                add_fee(&mut fees, FeeType::Borrow, pos.borrow_cost);
+                let commission = Money::from_f64(pos.total_commission()) + pos.borrow_cost;
                 let trade = Trade {
                     symbol: symbol.clone(),
                     side: pos.side,
@@ -799,16 +2899,33 @@ impl LiveTrader {
                     entry_time: pos.entry_time(),
                     exit_time: Utc::now(),
                     pnl: pos.realized_pnl,
-                    commission: Money::from_f64(pos.total_commission()),
-                    net_pnl: pos.realized_pnl - Money::from_f64(pos.total_commission()),
+                    commission,
+                    net_pnl: pos.realized_pnl - commission,
+                    fees,
+                    rationale: self.entry_notes.remove(symbol),
+                    tranche: None,
+                    r_multiple: None,
                 };
 
                 self.strategy.on_trade_closed(&trade);
 
-                if trade.net_pnl.is_positive() {
-                    self.risk_manager.record_win();
-                } else {
-                    self.risk_manager.record_loss();
+                self.risk_manager.record_trade(&trade);
+                // Realized P&L stays in the account's equity (borrow interest was paid as it accrued)
+                book_cash(
+                    self.fx.as_ref(),
+                    &mut self.paper_cash,
+                    &mut self.quote_cash,
+                    symbol.as_str(),
+                    trade.net_pnl.to_f64() + pos.borrow_cost.to_f64(),
+                );
+                self.account_book.record_trade(
+                    self.accounts.account_for(symbol.as_str()),
+                    trade.net_pnl.to_f64(),
+                    commission.to_f64(),
+                );
+                self.borrow_accrued.remove(symbol);
+                if let Some(competition) = &mut self.competition {
+                    competition.standing.record_trade(trade.net_pnl.to_f64());
                 }
 
                 let return_pct = trade.return_pct();
@@ -835,6 +2952,9 @@ impl LiveTrader {
                     "│    └─ Duration:    {}",
                     (trade.exit_time - trade.entry_time).num_seconds() / 3600
                 );
+                if let Some(rationale) = &trade.rationale {
+                    info!("│    └─ Rationale:   {}", rationale);
+                }
             }
         }
 
@@ -853,8 +2973,34 @@ impl LiveTrader {
             equity,
             peak_equity: self.risk_manager.peak_capital(),
         };
-
-        let requests = self.strategy.generate_orders(&ctx);
+        let ctx = SignalContext::new(&ctx).with_open_risk(context::open_risk(
+            self.position_manager.get_all_positions().map(|(_, p)| p),
+        ));
+
+        // Dead markets get no new signals; open positions are still managed above
+        let dead_reason = match &self.config.trading.activity_filter {
+            Some(filter) if ctx.current_position.is_none() => {
+                MarketActivity::measure(candles, filter.atr_period)
+                    .dead_reason(&filter.floors_for(symbol.as_str()))
+            }
+            _ => None,
+        };
+        let requests = match dead_reason {
+            Some(reason) => {
+                *self
+                    .metrics
+                    .activity_skips
+                    .entry(symbol.to_string())
+                    .or_default() += 1;
+                debug!("│  💤 {} inactive ({}) - no new signals", symbol, reason);
+                Vec::new()
+            }
+            None => self.strategy.generate_signal(&ctx),
+        };
+        let notes: Vec<Option<String>> = requests
+            .iter()
+            .map(|req| self.strategy.commentary(&ctx, req))
+            .collect();
         let strategy_latency = strategy_start.elapsed().as_micros();
 
         if !requests.is_empty() {
@@ -866,13 +3012,37 @@ impl LiveTrader {
         }
 
         // Step 4: Validate and place orders
+        let has_position = self.position_manager.get_position(symbol).is_some();
+        let entries_funded = has_position
+            || requests.is_empty()
+            || redeem_earn(earn, &mut self.earn_balance, &mut self.paper_cash).await;
         let mut placed_count = 0;
-        for req in requests {
+        for (req, note) in requests.into_iter().zip(notes) {
             if self.risk_manager.should_halt_trading() {
                 warn!("│  ⛔ Trading halted by risk manager - skipping order");
                 break;
             }
 
+            let reduces_position = self
+                .position_manager
+                .get_position(symbol)
+                .is_some_and(|p| p.side != req.side);
+            if self.kill_state.is_active() && !reduces_position {
+                warn!("│  🛑 Kill switch active - skipping {:?} entry", req.side);
+                continue;
+            }
+            if !data.allows_entries() && !reduces_position {
+                warn!("│  🩹 Degraded data - skipping {:?} entry", req.side);
+                continue;
+            }
+            if !entries_funded && !reduces_position {
+                warn!(
+                    "│  🏦 Earn redemption failed - skipping {:?} entry",
+                    req.side
+                );
+                continue;
+            }
+
             let pos_count = self.position_manager.open_position_count();
             if !self.risk_manager.can_open_position_count(pos_count) {
                 warn!(
@@ -883,53 +3053,572 @@ impl LiveTrader {
             }
 
             let order_start = Instant::now();
-            let order = req.to_order();
-
-            if self.paper_mode {
-                orderbook.add_order(order.clone());
-                let order_latency_us = order_start.elapsed().as_micros() as u64;
-                self.metrics.record_order(order_latency_us);
-                placed_count += 1;
+            let mut order = req.to_order();
+
+            // Entry orders must clear the minimum-edge gate, keep the symbol's group
+            // within its exposure cap and the expected tail loss within the CVaR cap
+            if self.position_manager.get_position(symbol).is_none() {
+                let price = order
+                    .limit_price
+                    .map(|p| p.to_f64())
+                    .unwrap_or(current_candle.close);
+                if let Some(rules) = &self.config.trading.min_edge {
+                    // Quoted spread if the spread guard already fetched one this cycle
+                    let spread_bps = match quote.flatten() {
+                        Some((bid, ask)) if bid > 0.0 && ask > bid => {
+                            (ask - bid) / ((ask + bid) / 2.0) * 10_000.0
+                        }
+                        _ => rules.spread_bps,
+                    };
+                    let cost =
+                        rules.round_trip_cost(&self.config.exchange, &self.config.tax, spread_bps);
+                    let target = self
+                        .strategy
+                        .calculate_take_profit(candles, price, order.side);
+                    let edge = EdgeCheck::new(price, target, cost);
+                    if !edge.passes(rules.multiple) {
+                        self.metrics.edge_rejections += 1;
+                        audit_order(
+                            self.audit.as_mut(),
+                            AuditRecord::new(ExecType::Rejected, &order).with_text("min_edge"),
+                        );
+                        warn!(
+                            "│  ⛔ Expected move {:.2}% < {:.1}x round-trip cost {:.2}% - skipping {} entry",
+                            edge.expected_move * 100.0,
+                            rules.multiple,
+                            edge.cost * 100.0,
+                            symbol
+                        );
+                        continue;
+                    }
+                }
+                let requested = order.quantity.to_f64();
+                let allowed = self.risk_manager.apply_group_limit(
+                    symbol.as_str(),
+                    price,
+                    requested,
+                    self.position_manager.get_all_positions().map(|(_, p)| p),
+                );
+                let allowed = self.risk_manager.apply_cvar_limit(price, allowed);
+                let budgeted = self.risk_manager.apply_risk_budget(
+                    price,
+                    self.strategy
+                        .calculate_stop_loss(candles, price, order.side),
+                    allowed,
+                    Utc::now(),
+                );
+                if budgeted <= 0.0 && allowed > 0.0 {
+                    self.metrics.budget_rejections += 1;
+                    audit_order(
+                        self.audit.as_mut(),
+                        AuditRecord::new(ExecType::Rejected, &order).with_text("risk_budget"),
+                    );
+                    warn!(
+                        "│  ⛔ Daily risk budget exhausted for {} - skipping {} entry",
+                        self.strategy.name(),
+                        symbol
+                    );
+                    continue;
+                }
+                let allowed = budgeted;
+                if allowed <= 0.0 {
+                    audit_order(
+                        self.audit.as_mut(),
+                        AuditRecord::new(ExecType::Rejected, &order).with_text("exposure_cap"),
+                    );
+                    warn!(
+                        "│  ⛔ Group exposure cap reached for {} - skipping order",
+                        symbol
+                    );
+                    continue;
+                }
+                if allowed < requested {
+                    order.quantity = Money::from_f64(allowed);
+                    order.remaining_quantity = Money::from_f64(allowed);
+                }
+            }
+            // Checks and logs see the size the exchange will get
+            self.exchange.quantize(&mut order);
 
-                info!(
-                    "│  📋 ORDER PLACED #{} [{}μs latency]",
-                    self.metrics.total_orders_placed, order_latency_us
+            let ctx = PreTradeContext {
+                last_close: current_candle.close,
+                now: Utc::now(),
+                reduces_position,
+            };
+            if let Err(rejection) = self.pre_trade.check(&order, &ctx) {
+                self.metrics.pre_trade_rejections += 1;
+                audit_order(
+                    self.audit.as_mut(),
+                    AuditRecord::new(ExecType::Rejected, &order).with_text(rejection.to_string()),
                 );
-                info!("│    └─ Symbol:   {}", order.symbol);
-                info!(
-                    "│    └─ Side:     {}",
-                    if order.side == Side::Buy {
-                        "BUY "
-                    } else {
-                        "SELL"
-                    }
+                warn!(
+                    "│  🚫 Pre-trade check failed for {:?} {} - {}",
+                    order.side, symbol, rejection
                 );
-                info!("│    └─ Type:     {:?}", order.order_type);
-                info!("│    └─ Quantity: {:.6}", order.quantity);
-                if let Some(price) = order.limit_price {
-                    info!("│    └─ Price:    {:.2}", price);
+                continue;
+            }
+
+            if let Some(guard) = &self.config.exchange.spread_guard {
+                if quote.is_none() {
+                    quote = Some(fetch_quote(&self.exchange, symbol).await);
+                }
+                let is_exit = self
+                    .position_manager
+                    .get_position(symbol)
+                    .is_some_and(|p| p.side != order.side);
+                if !apply_spread_guard(guard, quote.flatten(), &mut order, is_exit) {
+                    audit_order(
+                        self.audit.as_mut(),
+                        AuditRecord::new(ExecType::Rejected, &order).with_text("spread_guard"),
+                    );
+                    continue;
+                }
+            }
+
+            // Post-only limits must not cross the book on arrival
+            if post_only::is_post_only(&order) {
+                if quote.is_none() {
+                    quote = Some(fetch_quote(&self.exchange, symbol).await);
+                }
+                if let Some((bid, ask)) = quote.flatten() {
+                    match self.config.exchange.post_only.check(&order, bid, ask) {
+                        PostOnlyDecision::Rest => {}
+                        PostOnlyDecision::Reprice { from, price } => {
+                            info!(
+                                "│  ↩️  Post-only {:?} {} @ {:.2} would cross (bid {:.2} / ask {:.2}) - re-priced to {:.2}",
+                                order.side, symbol, from, bid, ask, price
+                            );
+                            post_only::reprice(&mut order, price);
+                        }
+                        PostOnlyDecision::Reject { price } => {
+                            audit_order(
+                                self.audit.as_mut(),
+                                AuditRecord::new(ExecType::Rejected, &order)
+                                    .with_text("post_only: would cross"),
+                            );
+                            warn!(
+                                "│  ⛔ Post-only {:?} {} @ {:.2} would cross (bid {:.2} / ask {:.2}) - skipping order",
+                                order.side, symbol, price, bid, ask
+                            );
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            if reduces_position {
+                unpark_exits(
+                    &self.exchange,
+                    self.account_clients
+                        .get(self.accounts.account_for(symbol.as_str())),
+                    &mut self.parked_exits,
+                    symbol,
+                )
+                .await;
+            }
+            if self.position_manager.get_position(symbol).is_none() {
+                match &note {
+                    Some(note) => self.entry_notes.insert(symbol.clone(), note.clone()),
+                    None => self.entry_notes.remove(symbol),
+                };
+            }
+            let Some(client_id) = place_order(
+                &self.exchange,
+                &self.state_manager,
+                self.audit.as_mut(),
+                orderbook,
+                self.cycle_started_ms,
+                (!self.paper_mode).then_some(&mut self.sent_orders),
+                order.clone(),
+            )
+            .await
+            else {
+                continue;
+            };
+            let order_latency_us = order_start.elapsed().as_micros() as u64;
+            self.metrics.record_order(order_latency_us);
+            placed_count += 1;
+
+            info!(
+                "│  📋 ORDER PLACED #{} [{}μs latency]",
+                self.metrics.total_orders_placed, order_latency_us
+            );
+            info!("│    └─ Symbol:   {}", order.symbol);
+            info!(
+                "│    └─ Side:     {}",
+                if order.side == Side::Buy {
+                    "BUY "
+                } else {
+                    "SELL"
+                }
+            );
+            info!("│    └─ Type:     {:?}", order.order_type);
+            info!("│    └─ Quantity: {:.6}", order.quantity);
+            if let Some(price) = order.limit_price {
+                info!("│    └─ Price:    {:.2}", price);
+            }
+            info!("│    └─ Order ID: {}", order.id);
+            if let Some(id) = self.sent_orders.get(&client_id) {
+                info!("│    └─ Exchange: {}", id);
+            }
+            if let Some(note) = &note {
+                info!("│    └─ Why:      {}", note);
+            }
+        }
+
+        if placed_count > 0 {
+            debug!("│  ✓ Placed {} order(s)", placed_count);
+        }
+
+        Ok(())
+    }
+
+    /// Compare recorded fills with the exchange's trade history and export the result
+    async fn reconcile_trades(&self) {
+        let Some(recon) = &self.config.exchange.reconcile else {
+            return;
+        };
+        if self.paper_mode {
+            debug!("Reconciliation skipped in paper mode");
+            return;
+        }
+
+        // Local fills still queued for the writer would show as missing
+        if let Err(e) = self.state_writer.flush().await {
+            warn!("⚠️  Could not flush state before reconciliation: {}", e);
+        }
+
+        let Some(exchange) = self.exchange.as_coindcx() else {
+            debug!(
+                "Reconciliation skipped: not supported on {}",
+                self.exchange.capabilities().name
+            );
+            return;
+        };
+
+        let since = Utc::now() - chrono::Duration::hours(recon.lookback_hours as i64);
+        let report = match reconcile::run(exchange, &self.state_manager, since).await {
+            Ok(report) => report,
+            Err(e) => {
+                warn!("⚠️  Trade reconciliation failed: {}", e);
+                return;
+            }
+        };
+        match reconcile::write_export(&report, &recon.output_dir, None) {
+            Ok((path, _)) if report.is_clean() => info!(
+                "🧾 Reconciled {} order(s) with exchange ({})",
+                report.lines.len(),
+                path.display()
+            ),
+            Ok((path, _)) => warn!(
+                "⚠️  Reconciliation: {} of {} order(s) do not match the exchange ({})",
+                report.discrepancies(),
+                report.lines.len(),
+                path.display()
+            ),
+            Err(e) => warn!("⚠️  Failed to write reconciliation export: {}", e),
+        }
+    }
+
+    /// Book deposits and withdrawals made since the last poll
+    ///
+    /// Each completed transfer moves cash and the risk manager's capital
+    /// baseline once, so a top-up shows as neither profit nor drawdown. The
+    /// state manager remembers which transfers were booked.
+    async fn sync_cash_flows(&mut self) {
+        let Some(flows) = &self.config.exchange.cash_flows else {
+            return;
+        };
+        if self.paper_mode {
+            return;
+        }
+        let Some(client) = self.exchange.as_coindcx() else {
+            debug!(
+                "Cash flow tracking skipped: not supported on {}",
+                self.exchange.capabilities().name
+            );
+            return;
+        };
+
+        let currency = flows.currency.clone();
+        let since = self.cash_flows_since;
+        let (deposits, withdrawals) = tokio::join!(
+            client.get_deposits(Some(&currency), since),
+            client.get_withdrawals(Some(&currency), since)
+        );
+        let mut transfers = Vec::new();
+        for (kind, result) in [("deposit", deposits), ("withdrawal", withdrawals)] {
+            match result {
+                Ok(list) => transfers.extend(list.into_iter().map(|t| (kind, t))),
+                Err(e) => {
+                    warn!("⚠️  Could not fetch {}s: {}", kind, e);
+                    return;
                 }
-                info!("│    └─ Order ID: {}", order.id);
-            } else {
-                warn!("│  ⚠️  Live trading not implemented - use paper mode");
             }
         }
+        transfers.sort_by_key(|(_, t)| t.created_at);
 
-        if placed_count > 0 {
-            debug!("│  ✓ Placed {} order(s)", placed_count);
+        for (kind, transfer) in &transfers {
+            if !transfer.is_complete()
+                || !transfer.currency_short_name.eq_ignore_ascii_case(&currency)
+            {
+                continue;
+            }
+            let flow = cash_flow(kind, transfer);
+            match self.state_manager.record_cash_flow(&flow) {
+                Ok(true) => {
+                    self.paper_cash += flow.amount;
+                    self.risk_manager.apply_cash_flow(flow.amount);
+                    self.net_cash_flows += flow.amount;
+                    info!(
+                        "💸 {} of {:.2} {} booked as an external cash flow",
+                        kind,
+                        flow.amount.abs(),
+                        currency
+                    );
+                }
+                Ok(false) => {}
+                Err(e) => warn!("⚠️  Could not record {} {}: {}", kind, transfer.id, e),
+            }
         }
 
-        Ok(())
+        // Keep re-reading from the oldest transfer still in flight
+        let next = transfers
+            .iter()
+            .filter(|(_, t)| t.is_pending())
+            .map(|(_, t)| t.created_at)
+            .min()
+            .or_else(|| transfers.iter().map(|(_, t)| t.created_at).max());
+        if let Some(at) = next.and_then(DateTime::from_timestamp_millis) {
+            self.cash_flows_since = at;
+        }
     }
 
     fn calculate_portfolio_value(&self) -> f64 {
-        let mut total = self.paper_cash;
+        let mut total = self.paper_cash + self.earn_balance + self.quote_cash.values().sum::<f64>();
         for (_sym, pos) in self.position_manager.get_all_positions() {
             total += pos.unrealized_pnl.to_f64();
         }
+        if let Some((hedger, price)) = self.hedger.as_ref().zip(self.hedge_mark()) {
+            total += hedger.unrealized_pnl(price);
+        }
         total
     }
 
+    /// Fetch the latest rate of every foreign quote currency into the home one
+    async fn refresh_fx_rates(&mut self) {
+        let Some(fx) = &mut self.fx else {
+            return;
+        };
+        let symbols = self.config.trading.symbols.iter().map(String::as_str);
+        for quote in fx.foreign_quotes(symbols) {
+            let fx_symbol = fx.fx_symbol(quote);
+            match fx::fetch_rate(&self.exchange, &fx_symbol).await {
+                Ok(rate) => fx.push_rate(quote, Utc::now(), rate),
+                Err(e) => warn!("│  ⚠️  {} rate unavailable: {:#}", fx_symbol, e),
+            }
+        }
+    }
+
+    /// Portfolio value with foreign-quoted positions converted into the home
+    /// currency; `None` until every rate is known
+    fn home_portfolio_value(&self) -> Option<f64> {
+        let fx = self.fx.as_ref()?;
+        let now = Utc::now();
+        let mut total =
+            fx.cash_to_home(self.paper_cash + self.earn_balance, &self.quote_cash, now)?;
+        for (symbol, pos) in self.position_manager.get_all_positions() {
+            total += fx.to_home(symbol.as_str(), pos.unrealized_pnl.to_f64(), now)?;
+        }
+        if let Some((hedger, price)) = self.hedger.as_ref().zip(self.hedge_mark()) {
+            total += fx.to_home(&hedger.config().symbol, hedger.unrealized_pnl(price), now)?;
+        }
+        Some(total)
+    }
+
+    /// Latest close of the hedge symbol
+    fn hedge_mark(&self) -> Option<f64> {
+        let hedger = self.hedger.as_ref()?;
+        self.candle_cache
+            .get(&Symbol::new(&hedger.config().symbol))?
+            .primary()
+            .last()
+            .map(|c| c.close)
+    }
+
+    /// CoinDCX client and sweep settings when idle cash is swept into earn
+    fn earn(&self) -> Option<(CoinDCXClient, EarnSweepConfig)> {
+        let earn = self.config.exchange.earn_sweep.clone()?;
+        let client = self.exchange.as_coindcx().filter(|_| !self.paper_mode)?;
+        Some((client.clone(), earn))
+    }
+
+    /// Read what is already in the earn product (e.g. swept before a restart)
+    async fn sync_earn(&mut self) {
+        let Some((client, earn)) = self.earn() else {
+            return;
+        };
+        match client.get_earn_balances().await {
+            Ok(balances) => {
+                self.earn_balance = balances
+                    .iter()
+                    .filter(|b| b.product_id == earn.product_id)
+                    .map(|b| b.value())
+                    .sum();
+                if self.earn_balance > 0.0 {
+                    info!(
+                        "🏦 {:.2} held in earn product {}",
+                        self.earn_balance, earn.product_id
+                    );
+                }
+            }
+            Err(e) => warn!("⚠️  Could not read earn balances: {}", e),
+        }
+    }
+
+    /// Subscribe cash above the reserve to the earn product while flat
+    async fn sweep_earn(&mut self) {
+        let Some((client, earn)) = self.earn() else {
+            return;
+        };
+        if self.position_manager.open_position_count() > 0
+            || self.orderbooks.values().any(|ob| !ob.is_empty())
+        {
+            return;
+        }
+        let reserve = self.calculate_portfolio_value() * earn.reserve_pct;
+        let amount = self.paper_cash - reserve;
+        if amount < earn.min_amount {
+            return;
+        }
+        match client.earn_subscribe(&earn.product_id, amount).await {
+            Ok(()) => {
+                self.paper_cash -= amount;
+                self.earn_balance += amount;
+                info!("🏦 Swept {:.2} idle cash into {}", amount, earn.product_id);
+            }
+            Err(e) => warn!("⚠️  Earn sweep failed: {}", e),
+        }
+    }
+
+    /// Size, resize or unwind the hedge against the book after a cycle
+    async fn update_hedge(&mut self) {
+        let Some(hedge_symbol) = self
+            .hedger
+            .as_ref()
+            .map(|h| Symbol::new(&h.config().symbol))
+        else {
+            return;
+        };
+        if !self
+            .config
+            .trading
+            .symbols
+            .iter()
+            .any(|s| s == hedge_symbol.as_str())
+        {
+            if let Err(e) = self.update_candles(&hedge_symbol).await {
+                warn!("│  ⚠️  Hedge candles for {} failed: {}", hedge_symbol, e);
+            }
+        }
+        let (Some(price), Some(market)) = (
+            self.hedge_mark(),
+            self.candle_cache
+                .get(&hedge_symbol)
+                .map(|d| d.primary().iter().map(|c| c.close).collect::<Vec<_>>()),
+        ) else {
+            return;
+        };
+
+        let equity = self.calculate_portfolio_value();
+        let positions: Vec<(f64, Vec<f64>)> = self
+            .position_manager
+            .get_all_positions()
+            .filter_map(|(symbol, pos)| {
+                let closes = self
+                    .candle_cache
+                    .get(symbol)?
+                    .primary()
+                    .iter()
+                    .map(|c| c.close)
+                    .collect();
+                let quantity = match pos.side {
+                    Side::Buy => pos.quantity.to_f64(),
+                    Side::Sell => -pos.quantity.to_f64(),
+                };
+                Some((quantity, closes))
+            })
+            .collect();
+        let Some(hedger) = self.hedger.as_mut() else {
+            return;
+        };
+        let exposure = Exposure::measure(
+            positions.iter().map(|(q, c)| (*q, c.as_slice())),
+            &market,
+            hedger.config().beta_lookback,
+            equity,
+        );
+        let falling = hedge::deteriorating(&market, hedger.config().regime_ema);
+        let Some(fill) = hedger.step(&exposure, price, falling, Utc::now()) else {
+            return;
+        };
+
+        self.paper_cash += fill.cash_delta();
+        info!(
+            "│  🛡️  HEDGE {} {:.6} {} @ {:.2} (net exposure {:.0}%, beta notional {:.2})",
+            if fill.quantity > 0.0 {
+                "SHORT"
+            } else {
+                "COVER"
+            },
+            fill.quantity.abs(),
+            hedge_symbol,
+            price,
+            exposure.net() * 100.0,
+            exposure.beta_notional
+        );
+        if let Some(trade) = fill.trade {
+            info!("│    └─ Net P&L:     {:.2}", trade.net_pnl);
+            if let Some(competition) = &mut self.competition {
+                competition.standing.record_trade(trade.net_pnl.to_f64());
+            }
+            let notional = trade.entry_price.to_f64() * trade.quantity.to_f64();
+            let record = TradeRecord {
+                id: None,
+                symbol: hedge_symbol.to_string(),
+                side: "sell".to_string(),
+                quantity: trade.quantity.to_f64(),
+                entry_price: trade.entry_price.to_f64(),
+                exit_price: trade.exit_price.to_f64(),
+                entry_time: trade.entry_time.to_rfc3339(),
+                exit_time: trade.exit_time.to_rfc3339(),
+                gross_pnl: trade.pnl.to_f64(),
+                fees: trade.commission.to_f64(),
+                tax: 0.0,
+                net_pnl: trade.net_pnl.to_f64(),
+                pnl_pct: if notional > 0.0 {
+                    trade.net_pnl.to_f64() / notional * 100.0
+                } else {
+                    0.0
+                },
+                status: "closed".to_string(),
+                exit_reason: "hedge".to_string(),
+                strategy_signal: "hedge".to_string(),
+                market_state_entry: "unknown".to_string(),
+                market_state_exit: "unknown".to_string(),
+                atr_at_entry: 0.0,
+                stop_loss: 0.0,
+                take_profit: 0.0,
+                risk_reward_actual: 0.0,
+                metadata: Default::default(),
+                fill_id: None,
+            };
+            if let Err(e) = self.state_writer.send(StateEvent::Trade(record)).await {
+                warn!("│  ⚠️  Failed to log hedge trade: {}", e);
+            }
+        }
+    }
+
     fn log_portfolio_status(&self) {
         let portfolio_value = self.calculate_portfolio_value();
         let drawdown = self.risk_manager.current_drawdown();
@@ -939,7 +3628,30 @@ impl LiveTrader {
         info!("📊 PORTFOLIO STATUS");
         info!("════════════════════════════════════════════════════════");
         info!("Cash:                  {:.2}", self.paper_cash);
+        if self.earn_balance > 0.0 {
+            info!("Earn:                  {:.2}", self.earn_balance);
+        }
         info!("Portfolio Value:       {:.2}", portfolio_value);
+        if let (Some(fx), Some(value)) = (&self.fx, self.home_portfolio_value()) {
+            info!("Value in {:<13} {:.2}", format!("{}:", fx.home()), value);
+        }
+        if let Some(leg) = self.hedger.as_ref().and_then(|h| h.leg()) {
+            info!(
+                "Hedge:                 short {:.6} @ {:.2} since {}",
+                leg.quantity,
+                leg.entry_price,
+                leg.opened_at.format("%Y-%m-%d %H:%M")
+            );
+        }
+        for account in self.account_book.balances() {
+            info!(
+                "Account {:<14} {:.2} ({:+.2} over {} trade(s))",
+                format!("{}:", account.account),
+                account.balance(),
+                account.realized_pnl,
+                account.trades
+            );
+        }
         info!("Drawdown:              {:.2}%", drawdown * 100.0);
         info!("Consecutive Losses:    {}", consecutive_losses);
         info!(
@@ -955,6 +3667,44 @@ impl LiveTrader {
             }
         );
 
+        let group_exposures = self
+            .risk_manager
+            .group_exposures(self.position_manager.get_all_positions().map(|(_, p)| p));
+        if !group_exposures.is_empty() {
+            info!("Group Exposure:");
+            for (group, pct) in &group_exposures {
+                match self.risk_manager.group_exposure_caps.get(group) {
+                    Some(cap) => info!(
+                        "  ├─ {:<10} {:>6.2}% (cap {:.0}%)",
+                        group,
+                        pct * 100.0,
+                        cap * 100.0
+                    ),
+                    None => info!("  ├─ {:<10} {:>6.2}%", group, pct * 100.0),
+                }
+            }
+        }
+
+        let failing = self.data_health.failing();
+        if !failing.is_empty() {
+            info!("Degraded Data:");
+            for (symbol, run) in failing {
+                let action = self
+                    .config
+                    .trading
+                    .degraded_data
+                    .action(symbol.as_str(), run.failures);
+                info!(
+                    "  ├─ {:<10} {:?} after {} failed cycle(s) since {} ({})",
+                    symbol.as_str(),
+                    action,
+                    run.failures,
+                    run.since.format("%Y-%m-%d %H:%M:%S"),
+                    run.last_error
+                );
+            }
+        }
+
         for (symbol, pos) in self.position_manager.get_all_positions() {
             info!(
                 "  ├─ {} {} {:.6} @ {:.2} (U-PnL: {:.2})",
@@ -972,11 +3722,25 @@ impl LiveTrader {
         info!("════════════════════════════════════════════════════════");
     }
 
-    fn save_checkpoint(&mut self) -> Result<()> {
+    /// Queue the checkpoint, open positions and pending orders as one batch
+    async fn save_checkpoint(&mut self) -> Result<()> {
         use std::collections::HashMap as MetadataMap;
 
         let value = self.calculate_portfolio_value();
         let positions_value = value - self.paper_cash;
+        // Point-in-time copy for `state diff`; the positions table only holds the latest
+        let mut snapshot: Vec<CheckpointPosition> = self
+            .position_manager
+            .get_all_positions()
+            .map(|(symbol, pos)| CheckpointPosition {
+                symbol: symbol.to_string(),
+                side: if pos.side == Side::Buy { "buy" } else { "sell" }.to_string(),
+                quantity: pos.quantity.to_f64(),
+                entry_price: pos.average_entry_price.to_f64(),
+                unrealized_pnl: pos.unrealized_pnl.to_f64(),
+            })
+            .collect();
+        snapshot.sort_by(|a, b| a.symbol.cmp(&b.symbol));
 
         let checkpoint = Checkpoint {
             timestamp: Utc::now().to_rfc3339(),
@@ -990,10 +3754,42 @@ impl LiveTrader {
             consecutive_losses: self.risk_manager.consecutive_losses as i32,
             paper_mode: self.paper_mode,
             config_hash: self.config_hash(),
-            metadata: MetadataMap::new(),
+            metadata: self
+                .risk_manager
+                .trailing_drawdown
+                .as_ref()
+                .map(|t| ("trailing_drawdown".to_string(), serde_json::json!(t)))
+                .into_iter()
+                .chain(
+                    self.hedger
+                        .as_ref()
+                        .and_then(|h| h.leg())
+                        .map(|leg| ("hedge_leg".to_string(), serde_json::json!(leg))),
+                )
+                .chain(
+                    (!self.quote_cash.is_empty())
+                        .then(|| ("quote_cash".to_string(), serde_json::json!(self.quote_cash))),
+                )
+                .chain((self.net_cash_flows != 0.0).then(|| {
+                    (
+                        "net_cash_flows".to_string(),
+                        serde_json::json!(self.net_cash_flows),
+                    )
+                }))
+                .chain(std::iter::once((
+                    "positions".to_string(),
+                    serde_json::json!(snapshot),
+                )))
+                .collect(),
         };
 
-        self.state_manager.save_checkpoint(&checkpoint)?;
+        let mut events = vec![StateEvent::Checkpoint(checkpoint)];
+        events.extend(
+            self.account_book
+                .balances()
+                .cloned()
+                .map(StateEvent::AccountBalance),
+        );
 
         for (symbol, pos) in self.position_manager.get_all_positions() {
             // Get cached stop/target levels if available
@@ -1012,6 +3808,21 @@ impl LiveTrader {
             if let Some(&trailing) = self.trailing_stops.get(symbol) {
                 metadata.insert("trailing_stop".to_string(), serde_json::json!(trailing));
             }
+            if let Some(ladder) = self.ladder_states.get(symbol) {
+                metadata.insert("exit_ladder".to_string(), serde_json::json!(ladder));
+            }
+            if !pos.borrow_cost.is_zero() {
+                metadata.insert(
+                    "borrow_cost".to_string(),
+                    serde_json::json!(pos.borrow_cost.to_f64()),
+                );
+                if let Some(at) = self.borrow_accrued.get(symbol) {
+                    metadata.insert(
+                        "borrow_accrued_at".to_string(),
+                        serde_json::json!(at.to_rfc3339()),
+                    );
+                }
+            }
 
             let sp = StatePosition {
                 symbol: symbol.to_string(),
@@ -1028,11 +3839,11 @@ impl LiveTrader {
                 exit_time: None,
                 metadata,
             };
-            self.state_manager.save_position(&sp)?;
+            events.push(StateEvent::Position(sp));
         }
 
         // Save pending orders from all orderbooks
-        self.state_manager.clear_pending_orders()?;
+        let mut pending = Vec::new();
         for (symbol, orderbook) in &self.orderbooks {
             for order in orderbook.get_all_orders() {
                 if order.state == crypto_strategies::oms::OrderState::Open
@@ -1059,12 +3870,16 @@ impl LiveTrader {
                         stop_price: order.stop_price.map(|p| p.to_f64()),
                         client_id: order.client_id.clone(),
                     };
-                    self.state_manager.save_pending_order(&po)?;
+                    pending.push(po);
                 }
             }
         }
+        events.push(StateEvent::ReplacePendingOrders(pending));
 
-        Ok(())
+        for event in events {
+            self.state_writer.send(event).await?;
+        }
+        self.state_writer.commit().await
     }
 
     fn config_hash(&self) -> String {
@@ -1090,18 +3905,873 @@ impl LiveTrader {
     }
 }
 
-pub async fn run(config: Config, state_db_path: String, paper_mode: bool) -> Result<()> {
-    let mut trader = LiveTrader::new(config, &state_db_path, paper_mode).await?;
+/// Fill of `order` the exchange has reported since the last one booked: (price, quantity, maker)
+///
+/// Orders the exchange ended without filling completely leave the book.
+fn reported_fill(
+    updates: &mut HashMap<String, OrderUpdate>,
+    order: &Order,
+    orderbook: &mut OrderBook,
+) -> Option<(f64, f64, bool)> {
+    let key = order
+        .client_id
+        .clone()
+        .unwrap_or_else(|| order.id.to_string());
+    let update = updates.get(&key)?;
+    let filled = order.filled_quantity.to_f64();
+    let quantity = update.filled_quantity() - filled;
+    let is_maker = order.order_type == crypto_strategies::oms::OrderType::Limit;
+    if quantity > 1e-12 {
+        // Price of the new quantity alone, backed out of the running average
+        let price = (update.avg_price * update.filled_quantity()
+            - order.average_fill_price.to_f64() * filled)
+            / quantity;
+        // An unfilled remainder the exchange cancelled leaves the book next cycle
+        if update.is_final() && update.remaining_quantity <= 0.0 {
+            updates.remove(&key);
+        }
+        return Some((price, quantity, is_maker));
+    }
+    if update.is_final() {
+        info!(
+            "│  ✖️  Order {} {} by the exchange after {:.6} filled",
+            key, update.status, filled
+        );
+        updates.remove(&key);
+        orderbook.cancel_order(order.id);
+    }
+    None
+}
+
+/// Round the order to the venue's precision, write its intent, send it, then
+/// book it; the client order ID it was booked under
+///
+/// Orders without a client order ID get one derived from the cycle, so a
+/// retried submission is recognised by the exchange. `sent` is given in live
+/// mode: the order goes out through [`intents::submit`] and its exchange
+/// order ID is kept there. An order whose intent can't be written, or that
+/// the exchange refuses, is not booked.
+async fn place_order(
+    exchange: &impl ExchangeClient,
+    state: &SqliteStateManager,
+    mut audit: Option<&mut AuditTrail>,
+    orderbook: &mut OrderBook,
+    cycle_ms: i64,
+    sent: Option<&mut HashMap<String, String>>,
+    mut order: Order,
+) -> Option<String> {
+    exchange.quantize(&mut order);
+    let client_id = order
+        .client_id
+        .get_or_insert_with(|| intents::cycle_client_id(cycle_ms, order.symbol.as_str(), order.id))
+        .clone();
+    if let Err(e) = intents::stage(state, &mut order) {
+        warn!(
+            "│  ⚠️  Could not write order intent - {:?} {} not placed: {}",
+            order.side, order.symbol, e
+        );
+        audit_order(
+            audit,
+            AuditRecord::new(ExecType::Rejected, &order).with_text(format!("intent: {}", e)),
+        );
+        return None;
+    }
+    audit_order(
+        audit.as_deref_mut(),
+        AuditRecord::new(ExecType::New, &order),
+    );
+    if let Some(sent) = sent {
+        match intents::submit(exchange, &order).await {
+            Ok(id) => {
+                if let Err(e) = state.update_intent(&client_id, IntentStatus::Submitted, Some(&id))
+                {
+                    warn!(
+                        "│  ⚠️  Could not mark intent {} submitted: {}",
+                        client_id, e
+                    );
+                }
+                sent.insert(client_id.clone(), id);
+            }
+            Err(e) => {
+                warn!(
+                    "│  ❌ {:?} {} refused by the exchange: {:#}",
+                    order.side, order.symbol, e
+                );
+                if let Err(e) = state.update_intent(&client_id, IntentStatus::Abandoned, None) {
+                    warn!(
+                        "│  ⚠️  Could not mark intent {} abandoned: {}",
+                        client_id, e
+                    );
+                }
+                audit_order(
+                    audit,
+                    AuditRecord::new(ExecType::Rejected, &order)
+                        .with_text(format!("exchange: {:#}", e)),
+                );
+                return None;
+            }
+        }
+    }
+    orderbook.add_order(order);
+    Some(client_id)
+}
+
+/// Cancel a working live order on the exchange as well; a failure is logged
+async fn cancel_sent(
+    exchange: &impl ExchangeClient,
+    sent: &mut HashMap<String, String>,
+    order: &Order,
+) {
+    let Some(id) = order.client_id.as_ref().and_then(|c| sent.remove(c)) else {
+        return;
+    };
+    if let Err(e) = exchange.cancel_order(order.symbol.as_str(), &id).await {
+        warn!("│  ⚠️  Cancelling {} on the exchange failed: {:#}", id, e);
+    }
+}
+
+/// Append to the audit trail; a failed write is logged, trading carries on
+fn audit_order(audit: Option<&mut AuditTrail>, record: AuditRecord) {
+    if let Some(trail) = audit {
+        if let Err(e) = trail.record(record) {
+            warn!("│  ⚠️  Failed to write audit record: {:#}", e);
+        }
+    }
+}
+
+/// Apply a fill to its position; the trade row when it reduced or closed one
+fn book_fill(
+    positions: &mut PositionManager,
+    symbol: &Symbol,
+    side: Side,
+    fill: Fill,
+    record: &FillRecord,
+) -> Option<TradeRecord> {
+    let prior = positions
+        .get_position(symbol)
+        .filter(|p| p.side != side)
+        .map(|p| {
+            (
+                p.side,
+                p.quantity.to_f64(),
+                p.average_entry_price.to_f64(),
+                p.first_entry_time,
+                p.realized_pnl.to_f64(),
+            )
+        });
+    positions.add_fill(fill, symbol.clone(), side);
+    let (pos_side, qty, entry_price, entry_time, realized) = prior?;
+    let realized_after = positions
+        .get_position_raw(symbol)
+        .map_or(realized, |p| p.realized_pnl.to_f64());
+    Some(intents::closing_trade(
+        record,
+        &format!("{:?}", pos_side).to_lowercase(),
+        entry_price,
+        entry_time,
+        record.quantity.min(qty),
+        realized_after - realized,
+    ))
+}
+
+/// Replace the last candle if `latest` is the same bar, append it otherwise
+fn merge_candle(candles: &mut Vec<crypto_strategies::Candle>, latest: crypto_strategies::Candle) {
+    match candles.last_mut() {
+        Some(last) if last.datetime == latest.datetime => *last = latest,
+        // A bar older than the last one arrived late; the cache already moved on
+        Some(last) if last.datetime > latest.datetime => {}
+        _ => candles.push(latest),
+    }
+}
+
+/// Put `candle` in time order, replacing a bar with the same time; true if it was new
+fn insert_candle(
+    candles: &mut Vec<crypto_strategies::Candle>,
+    candle: crypto_strategies::Candle,
+) -> bool {
+    match candles.binary_search_by_key(&candle.datetime, |c| c.datetime) {
+        Ok(i) => {
+            candles[i] = candle;
+            false
+        }
+        Err(i) => {
+            candles.insert(i, candle);
+            true
+        }
+    }
+}
+
+/// Symbols `listed` adds to and drops from `current`, in listed order
+fn symbol_changes(current: &[String], listed: &[String]) -> (Vec<String>, Vec<String>) {
+    let added = listed
+        .iter()
+        .filter(|s| !current.contains(s))
+        .cloned()
+        .collect();
+    let removed = current
+        .iter()
+        .filter(|s| !listed.contains(s))
+        .cloned()
+        .collect();
+    (added, removed)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Keep `pos`'s stop and target parked on CoinDCX as an OCO pair.
+///
+/// Re-parks when the levels move (e.g. a trailing stop ratchets). Returns
+/// `(target_hit, client_order_id)` of the leg that filled, if one did.
+async fn park_exits(
+    client: &impl ExchangeClient,
+    parked: &mut HashMap<Symbol, ParkedExit>,
+    symbol: &Symbol,
+    pos: &Position,
+    stop: f64,
+    target: f64,
+) -> Result<Option<(bool, String)>> {
+    if let Some(current) = parked.get(symbol) {
+        let filled = match client.sync_oco(&current.handle).await? {
+            OcoStatus::Open if current.stop == stop && current.target == target => return Ok(None),
+            OcoStatus::Open => {
+                client.cancel_oco(&current.handle).await?;
+                None
+            }
+            OcoStatus::TakeProfitFilled(leg) => Some((true, leg)),
+            OcoStatus::StopLossFilled(leg) => Some((false, leg)),
+            OcoStatus::Closed => None,
+        };
+        parked.remove(symbol);
+        if let Some((target_hit, leg)) = filled {
+            let id = leg.client_order_id.unwrap_or(leg.id);
+            return Ok(Some((target_hit, id)));
+        }
+    }
+
+    let side = match pos.side {
+        Side::Buy => OrderSide::Sell,
+        Side::Sell => OrderSide::Buy,
+    };
+    let oco = OcoOrder::new(
+        side,
+        symbol.as_str(),
+        pos.quantity.to_f64(),
+        target,
+        stop,
+        OCO_STOP_SLIPPAGE,
+        format!("oco-{}-{}", symbol, Utc::now().timestamp_millis()),
+    );
+    let handle = client.place_oco(&oco).await?;
+    info!(
+        "│  🅿️  Exits parked for {}: stop={:.2}, target={:.2}",
+        symbol, stop, target
+    );
+    parked.insert(
+        symbol.clone(),
+        ParkedExit {
+            handle,
+            stop,
+            target,
+        },
+    );
+    Ok(None)
+}
+
+/// Park `pos`'s exits on `venue` unless they must be watched locally: whether
+/// the venue watches them, and the leg that filled since the last cycle
+///
+/// A position restored from the last session is parked only once the
+/// venue's balances show it.
+async fn park_position(
+    venue: &impl ExchangeClient,
+    unconfirmed: &mut HashSet<Symbol>,
+    unparkable: &mut HashSet<Symbol>,
+    parked: &mut HashMap<Symbol, ParkedExit>,
+    symbol: &Symbol,
+    pos: &Position,
+    (stop, target): (f64, f64),
+) -> (bool, Option<(bool, String)>) {
+    // A restored position may have been closed while we were stopped
+    if unconfirmed.contains(symbol) {
+        match held_on_exchange(venue, symbol, pos).await {
+            Ok(true) => {
+                info!("│  ✓ {} position confirmed on the exchange", symbol);
+                unconfirmed.remove(symbol);
+            }
+            Ok(false) => {
+                warn!(
+                    "│  ⚠️  {} position not held on the exchange - monitoring locally",
+                    symbol
+                );
+                return (false, None);
+            }
+            Err(e) => {
+                warn!("│  ⚠️  Balances for {}: {:#} (retrying)", symbol, e);
+                return (false, None);
+            }
+        }
+    }
+    match park_exits(venue, parked, symbol, pos, stop, target).await {
+        Ok(fill) => (true, fill),
+        Err(e)
+            if e.downcast_ref::<CoinDCXError>()
+                .is_some_and(CoinDCXError::is_retryable) =>
+        {
+            warn!("│  ⚠️  Parked exits for {}: {:#} (retrying)", symbol, e);
+            (true, None)
+        }
+        // Refused outright: asking again every cycle won't help
+        Err(e) => {
+            warn!(
+                "│  ⚠️  Parked exits for {}: {:#} - monitoring locally",
+                symbol, e
+            );
+            unparkable.insert(symbol.clone());
+            (true, None)
+        }
+    }
+}
+
+/// Cancel `symbol`'s parked exits before the trader reduces the position
+/// itself: resting legs would hold the balance its exit needs, or close the
+/// position a second time
+///
+/// They rest with the symbol's account client if it has one, else on `exchange`.
+async fn unpark_exits(
+    exchange: &impl ExchangeClient,
+    account: Option<&CoinDCXClient>,
+    parked: &mut HashMap<Symbol, ParkedExit>,
+    symbol: &Symbol,
+) {
+    let Some(exit) = parked.remove(symbol) else {
+        return;
+    };
+    let cancelled = match account {
+        Some(client) => ExchangeClient::cancel_oco(client, &exit.handle).await,
+        None => exchange.cancel_oco(&exit.handle).await,
+    };
+    match cancelled {
+        Ok(()) => info!("│  🅿️  Parked exits for {} cancelled", symbol),
+        Err(e) => warn!("│  ⚠️  Cancel parked exits for {}: {:#}", symbol, e),
+    }
+}
+
+/// Whether the exchange's balances back `pos`: a long's base asset must cover
+/// its quantity; a short can't be seen in spot balances and is never confirmed
+async fn held_on_exchange(
+    exchange: &impl ExchangeClient,
+    symbol: &Symbol,
+    pos: &Position,
+) -> Result<bool> {
+    if pos.side == Side::Sell {
+        return Ok(false);
+    }
+    let base = crypto_strategies::symbols::base(symbol.as_str());
+    let held: f64 = exchange
+        .get_balances()
+        .await?
+        .iter()
+        .filter(|b| b.asset.eq_ignore_ascii_case(base))
+        .map(|b| b.free + b.locked)
+        .sum();
+    Ok(held >= pos.quantity.to_f64() * (1.0 - 1e-9))
+}
+
+/// Add `amount`, quoted in `symbol`'s currency, to cash; amounts in a foreign
+/// quote currency go to `quote_cash`, to be converted when valued
+fn book_cash(
+    fx: Option<&QuoteConverter>,
+    cash: &mut f64,
+    quote_cash: &mut HashMap<String, f64>,
+    symbol: &str,
+    amount: f64,
+) {
+    match fx.and_then(|fx| fx.foreign_quote(symbol)) {
+        Some(quote) => *quote_cash.entry(quote.to_string()).or_default() += amount,
+        None => *cash += amount,
+    }
+}
+
+/// Redeem everything held in earn into cash; false if the funds couldn't be freed
+async fn redeem_earn(
+    earn: Option<(CoinDCXClient, EarnSweepConfig)>,
+    earn_balance: &mut f64,
+    cash: &mut f64,
+) -> bool {
+    let Some((client, earn)) = earn.filter(|_| *earn_balance > 0.0) else {
+        return true;
+    };
+    match client.earn_redeem(&earn.product_id, *earn_balance).await {
+        Ok(()) => {
+            info!(
+                "🏦 Redeemed {:.2} from {} for an entry",
+                earn_balance, earn.product_id
+            );
+            *cash += *earn_balance;
+            *earn_balance = 0.0;
+            true
+        }
+        Err(e) => {
+            warn!("⚠️  Earn redemption failed: {}", e);
+            false
+        }
+    }
+}
+
+/// A CoinDCX client per account with its own API key, sharing `default`'s
+/// market cache and settings; empty on other venues
+fn account_clients(
+    config: &Config,
+    default: Option<&CoinDCXClient>,
+) -> Result<HashMap<String, CoinDCXClient>> {
+    let Some(default) = default else {
+        return Ok(HashMap::new());
+    };
+    let network = config.exchange.network()?;
+    Ok(config
+        .exchange
+        .accounts
+        .iter()
+        .filter_map(|account| {
+            let (key, secret) = account.api_key.clone().zip(account.api_secret.clone())?;
+            let client_config = ClientConfig {
+                network: network.clone(),
+                ..Default::default()
+            }
+            .with_max_retries(3)
+            .with_rate_limit(config.exchange.rate_limit as usize)
+            .with_timeout(Duration::from_secs(30));
+            let mut client = CoinDCXClient::with_config(key, secret, client_config)
+                .with_markets(default.markets().clone());
+            if let Some(leverage) = default.margin_leverage() {
+                client = client.with_margin(leverage);
+            }
+            Some((account.name.clone(), client))
+        })
+        .collect())
+}
+
+/// A completed deposit or withdrawal as a signed change to cash
+///
+/// Withdrawal fees come out of the account on top of the amount sent.
+fn cash_flow(kind: &str, transfer: &FundsTransfer) -> CashFlow {
+    let amount = match kind {
+        "withdrawal" => -(transfer.amount + transfer.fee),
+        _ => transfer.amount,
+    };
+    CashFlow {
+        flow_id: format!("{}:{}", kind, transfer.id),
+        currency: transfer.currency_short_name.clone(),
+        amount,
+        kind: kind.to_string(),
+        timestamp: DateTime::from_timestamp_millis(transfer.created_at)
+            .unwrap_or_default()
+            .to_rfc3339(),
+    }
+}
+
+async fn fetch_quote(exchange: &impl ExchangeClient, symbol: &Symbol) -> Option<(f64, f64)> {
+    match exchange.get_ticker(symbol.as_str()).await {
+        Ok(ticker) => ticker.bid.zip(ticker.ask),
+        Err(e) => {
+            warn!("│  ⚠️  Spread check: no quote for {} ({})", symbol, e);
+            None
+        }
+    }
+}
+
+/// Check a market order against the spread guard. Returns false if it must be skipped;
+/// may convert it to a limit at mid. Without a quote the order goes through unchanged.
+fn apply_spread_guard(
+    guard: &SpreadGuardConfig,
+    quote: Option<(f64, f64)>,
+    order: &mut Order,
+    is_exit: bool,
+) -> bool {
+    let Some((bid, ask)) = quote else {
+        return true;
+    };
+    match guard.evaluate(order, bid, ask, is_exit, Utc::now()) {
+        SpreadDecision::Proceed => true,
+        SpreadDecision::Skip { spread_bps } => {
+            warn!(
+                "│  ⛔ Spread {:.1} bps > {:.1} bps for {} (bid {:.2} / ask {:.2}) - skipping {:?} entry",
+                spread_bps, guard.max_spread_bps, order.symbol, bid, ask, order.side
+            );
+            false
+        }
+        SpreadDecision::LimitAtMid {
+            spread_bps,
+            price,
+            expires_at,
+        } => {
+            warn!(
+                "│  ⚠️  Spread {:.1} bps > {:.1} bps for {} (bid {:.2} / ask {:.2}) - {:?} limit @ {:.2} until {}",
+                spread_bps,
+                guard.max_spread_bps,
+                order.symbol,
+                bid,
+                ask,
+                order.side,
+                price,
+                expires_at.format("%H:%M:%S")
+            );
+            convert_to_limit(order, price, expires_at);
+            true
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    config: Config,
+    config_path: String,
+    state_db_path: String,
+    paper_mode: bool,
+    preflight: bool,
+    confirm: bool,
+    daemon: bool,
+    pid_file: Option<String>,
+) -> Result<()> {
+    // One instance per state directory; held until this function returns
+    let pid_file = pid_file.map(PathBuf::from).or_else(|| {
+        daemon.then(|| {
+            Path::new(&state_db_path)
+                .parent()
+                .unwrap_or(Path::new("."))
+                .join("live.pid")
+        })
+    });
+    let _pid_file = pid_file.map(PidFile::acquire).transpose()?;
+    if daemon {
+        info!("👻 Daemon mode (PID {})", std::process::id());
+    }
+
+    let config_path = PathBuf::from(config_path);
+    match config.exchange.venue {
+        Venue::CoinDCX => {
+            let trader = LiveTrader::new(config, config_path, &state_db_path, paper_mode).await?;
+            drive(trader, paper_mode, preflight, confirm).await
+        }
+        Venue::Kraken => {
+            let trader =
+                LiveTrader::kraken(config, config_path, &state_db_path, paper_mode).await?;
+            drive(trader, paper_mode, preflight, confirm).await
+        }
+        Venue::Coinbase => {
+            let trader =
+                LiveTrader::coinbase(config, config_path, &state_db_path, paper_mode).await?;
+            drive(trader, paper_mode, preflight, confirm).await
+        }
+        Venue::Bybit => {
+            let trader = LiveTrader::bybit(config, config_path, &state_db_path, paper_mode).await?;
+            drive(trader, paper_mode, preflight, confirm).await
+        }
+        Venue::Zerodha => {
+            let trader =
+                LiveTrader::zerodha(config, config_path, &state_db_path, paper_mode).await?;
+            drive(trader, paper_mode, preflight, confirm).await
+        }
+    }
+}
+
+/// Preflight, state recovery and the trading loop until a shutdown signal
+async fn drive<E: ExchangeClient>(
+    mut trader: LiveTrader<E>,
+    paper_mode: bool,
+    preflight: bool,
+    confirm: bool,
+) -> Result<()> {
+    // Real money always gets a preflight plan; paper mode on request
+    if !paper_mode || preflight || confirm {
+        trader = trader.with_preflight(confirm);
+    }
     trader.recover_state().await?;
 
     let shutdown = Arc::new(AtomicBool::new(false));
     let shutdown_clone = shutdown.clone();
 
     tokio::spawn(async move {
-        tokio::signal::ctrl_c().await.ok();
-        info!("🛑 Ctrl+C detected - initiating graceful shutdown...");
+        let signal = service::shutdown_signal().await;
+        info!("🛑 {} received - initiating graceful shutdown...", signal);
         shutdown_clone.store(true, Ordering::Relaxed);
     });
 
     trader.run(shutdown).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crypto_strategies::mock_exchange::MockExchangeClient;
+    use crypto_strategies::oms::OrderRequest;
+    use crypto_strategies::Candle;
+
+    /// `n` daily bars closing at `close(i)`, ending yesterday
+    fn daily(n: usize, close: impl Fn(usize) -> f64) -> Vec<Candle> {
+        let today = Utc::now().date_naive().and_hms_opt(0, 0, 0).unwrap();
+        let start = Utc.from_utc_datetime(&today) - chrono::Duration::days(n as i64);
+        (0..n)
+            .map(|i| {
+                let c = close(i);
+                Candle::new(
+                    start + chrono::Duration::days(i as i64),
+                    c,
+                    c * 1.01,
+                    c * 0.99,
+                    c,
+                    1_000.0,
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    /// Sample config trading only `symbols`
+    fn sample_config(symbols: &[&str]) -> Config {
+        let mut config: Config =
+            serde_json::from_str(include_str!("../../configs/sample_config.json")).unwrap();
+        config.trading.symbols = symbols.iter().map(|s| s.to_string()).collect();
+        config
+    }
+
+    /// Trader on `exchange`, its config file and state in a fresh directory
+    async fn trader(
+        name: &str,
+        config: Config,
+        exchange: MockExchangeClient,
+        paper_mode: bool,
+    ) -> LiveTrader<MockExchangeClient> {
+        let dir = std::env::temp_dir().join(format!("live_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.json");
+        std::fs::write(&config_path, serde_json::to_string(&config).unwrap()).unwrap();
+        let state_db = dir.join("state.db");
+        LiveTrader::with_exchange(
+            config,
+            config_path,
+            state_db.to_str().unwrap(),
+            paper_mode,
+            exchange,
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Buys 0.5 whenever flat
+    struct BuyWhenFlat;
+
+    impl Strategy for BuyWhenFlat {
+        fn name(&self) -> &'static str {
+            "buy_when_flat"
+        }
+        fn clone_boxed(&self) -> Box<dyn Strategy> {
+            Box::new(BuyWhenFlat)
+        }
+        fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
+            match ctx.current_position {
+                Some(_) => Vec::new(),
+                None => vec![OrderRequest::market_buy(ctx.symbol.clone(), 0.5)],
+            }
+        }
+        fn calculate_stop_loss(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+            entry_price * 0.9
+        }
+        fn calculate_take_profit(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+            entry_price * 1.2
+        }
+        fn update_trailing_stop(&self, _: &Position, _: f64, _: &[Candle]) -> Option<f64> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_symbol_changes() {
+        let current = ["BTCINR", "ETHINR"].map(String::from);
+        let listed = ["ETHINR", "SOLINR", "XRPINR"].map(String::from);
+        let (added, removed) = symbol_changes(&current, &listed);
+        assert_eq!(added, ["SOLINR", "XRPINR"]);
+        assert_eq!(removed, ["BTCINR"]);
+        assert_eq!(symbol_changes(&current, &current), (vec![], vec![]));
+    }
+
+    #[tokio::test]
+    async fn test_failed_backfill_of_added_symbol_is_retried_next_cycle() {
+        let exchange =
+            MockExchangeClient::new().with_candles("BTCINR", "1d", daily(60, |i| 100.0 + i as f64));
+        let mut trader = trader(
+            "added_symbol",
+            sample_config(&["BTCINR"]),
+            exchange.clone(),
+            true,
+        )
+        .await;
+
+        // The operator adds ETHINR; its first backfill fails
+        let edited = sample_config(&["BTCINR", "ETHINR"]);
+        std::fs::write(&trader.config_path, serde_json::to_string(&edited).unwrap()).unwrap();
+        trader.config_modified = None;
+        exchange.fail_next("candles", "service unavailable");
+        trader.check_config_symbols().await.unwrap();
+        assert_eq!(trader.config.trading.symbols, ["BTCINR"]);
+        assert_eq!(trader.pending_symbols, ["ETHINR"]);
+
+        // Next cycle: config unchanged, backfill retried and succeeds
+        let exchange = exchange.with_candles("ETHINR", "1d", daily(60, |i| 50.0 + i as f64));
+        trader.check_config_symbols().await.unwrap();
+        assert_eq!(trader.config.trading.symbols, ["BTCINR", "ETHINR"]);
+        assert!(trader.pending_symbols.is_empty());
+        assert!(trader.candle_cache.contains_key(&Symbol::new("ETHINR")));
+        assert_eq!(exchange.calls("candles"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_one_cycle_fetches_candles_signals_and_books_the_order() {
+        strategies::register_strategy("buy_when_flat", |_| Ok(Box::new(BuyWhenFlat)));
+        let exchange = MockExchangeClient::new()
+            .with_candles("BTCINR", "1d", daily(60, |i| 100.0 + i as f64))
+            .with_price("BTCINR", 160.0);
+        let mut config = sample_config(&["BTCINR"]);
+        config.strategy = serde_json::json!({ "name": "buy_when_flat", "timeframe": "1d" });
+        let mut trader = trader("one_cycle", config, exchange.clone(), false).await;
+        let btc = Symbol::new("BTCINR");
+        // As `run` does before its first cycle
+        trader.bootstrap_candles(&btc).await.unwrap();
+        trader.orderbooks.insert(btc.clone(), OrderBook::new());
+
+        trader.process_cycle().await.unwrap();
+
+        // Bootstrap plus the cycle's poll of the latest bars
+        assert_eq!(exchange.calls("candles"), 2);
+        let placed = exchange.orders();
+        assert_eq!(placed.len(), 1);
+        assert_eq!(placed[0].order.symbol, btc);
+        assert_eq!(placed[0].order.side, Side::Buy);
+        assert_eq!(placed[0].order.quantity.to_f64(), 0.5);
+
+        // Booked locally and linked to the exchange's id
+        let booked = trader.orderbooks[&btc].get_all_orders();
+        assert_eq!(booked.len(), 1);
+        let client_id = booked[0].client_id.as_ref().unwrap();
+        assert_eq!(trader.sent_orders[client_id], placed[0].id);
+    }
+
+    #[tokio::test]
+    async fn test_exits_park_only_once_the_position_is_confirmed() {
+        let btc = Symbol::new("BTCINR");
+        let mut positions = PositionManager::new();
+        positions.add_fill(
+            Fill::from_f64(0, 100.0, 0.5, Utc::now(), 0.0, true),
+            btc.clone(),
+            Side::Buy,
+        );
+        let pos = positions.get_position(&btc).unwrap().clone();
+
+        // Closed on the exchange while the trader was stopped
+        let exchange = MockExchangeClient::new().with_balance("BTC", 0.2);
+        assert!(!held_on_exchange(&exchange, &btc, &pos).await.unwrap());
+
+        let exchange = MockExchangeClient::new().with_balance("BTC", 0.5);
+        assert!(held_on_exchange(&exchange, &btc, &pos).await.unwrap());
+        let mut parked = HashMap::new();
+        let fill = park_exits(&exchange, &mut parked, &btc, &pos, 90.0, 120.0)
+            .await
+            .unwrap();
+        assert!(fill.is_none());
+
+        let ocos = exchange.ocos();
+        assert_eq!(ocos.len(), 1);
+        assert_eq!(ocos[0].side, OrderSide::Sell);
+        assert_eq!(ocos[0].market, "BTCINR");
+        assert_eq!(ocos[0].quantity, 0.5);
+        assert_eq!(ocos[0].take_profit_price, 120.0);
+        assert_eq!(ocos[0].stop_price, 90.0);
+        assert!((ocos[0].stop_limit_price - 90.0 * (1.0 - OCO_STOP_SLIPPAGE)).abs() < 1e-9);
+        assert!(parked.contains_key(&btc));
+
+        // Same levels next cycle: the resting pair is left alone
+        park_exits(&exchange, &mut parked, &btc, &pos, 90.0, 120.0)
+            .await
+            .unwrap();
+        assert_eq!(exchange.calls("place_oco"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_kill_switch_cancels_parked_exits_before_flattening() {
+        strategies::register_strategy("buy_when_flat", |_| Ok(Box::new(BuyWhenFlat)));
+        let exchange = MockExchangeClient::new()
+            .with_candles("BTCINR", "1d", daily(60, |i| 100.0 + i as f64))
+            .with_price("BTCINR", 160.0);
+        let mut config = sample_config(&["BTCINR"]);
+        config.strategy = serde_json::json!({ "name": "buy_when_flat", "timeframe": "1d" });
+        let mut trader = trader("kill_parked", config, exchange.clone(), false).await;
+        let btc = Symbol::new("BTCINR");
+        trader.bootstrap_candles(&btc).await.unwrap();
+        trader.orderbooks.insert(btc.clone(), OrderBook::new());
+
+        // An open long whose exits rest on the exchange
+        trader.position_manager.add_fill(
+            Fill::from_f64(0, 150.0, 0.5, Utc::now(), 0.0, true),
+            btc.clone(),
+            Side::Buy,
+        );
+        let pos = trader.position_manager.get_position(&btc).unwrap().clone();
+        park_exits(
+            &exchange,
+            &mut trader.parked_exits,
+            &btc,
+            &pos,
+            140.0,
+            200.0,
+        )
+        .await
+        .unwrap();
+
+        trader.kill_state = KillState::Flatten {
+            source: "test".to_string(),
+        };
+        trader.process_cycle().await.unwrap();
+
+        let history = exchange.history();
+        let cancelled = history.iter().position(|op| op == "cancel_oco").unwrap();
+        let flattened = history.iter().rposition(|op| op == "place_order").unwrap();
+        assert!(cancelled < flattened, "{:?}", history);
+        assert!(trader.parked_exits.is_empty());
+        let placed = exchange.orders();
+        assert_eq!(placed.len(), 1);
+        assert_eq!(placed[0].order.side, Side::Sell);
+        assert_eq!(placed[0].order.quantity.to_f64(), 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_dead_man_switch_shorter_than_a_cycle_is_rejected() {
+        strategies::register_strategy("buy_when_flat", |_| Ok(Box::new(BuyWhenFlat)));
+        let mut config = sample_config(&["BTCINR"]);
+        config.strategy = serde_json::json!({ "name": "buy_when_flat", "timeframe": "1d" });
+        // Three missed 60s heartbeats on a daily loop: would fire every cycle
+        config.exchange.dead_man_switch = Some(DeadManSwitchConfig {
+            missed_heartbeats: 3,
+            heartbeat_secs: 60,
+        });
+        let exchange = MockExchangeClient::new();
+        let mut trader = trader("dead_man", config, exchange.clone(), false).await;
+
+        let err = trader
+            .run(Arc::new(AtomicBool::new(false)))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("dead_man_switch"));
+        assert!(exchange.history().is_empty());
+
+        // 0 heartbeats at the polling interval is always long enough
+        let switch = DeadManSwitchConfig {
+            missed_heartbeats: 3,
+            heartbeat_secs: 0,
+        };
+        assert!(switch.validate(86_400).is_ok());
+    }
+}
@@ -1,6 +1,16 @@
 //! CLI command implementations
 
+pub mod alerts;
 pub mod backtest;
+pub mod compete;
+pub mod config;
+pub mod data;
 pub mod download;
 pub mod live;
 pub mod optimize;
+pub mod reconcile;
+pub mod regression;
+pub mod runs;
+pub mod service;
+pub mod signal_heatmap;
+pub mod state;
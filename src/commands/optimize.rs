@@ -2,15 +2,19 @@
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use crypto_strategies::{data, grid, optimizer::OptimizationResult, strategies, Config, Symbol};
+use crypto_strategies::optimizer::{OptimizationResult, ResultStream};
+use crypto_strategies::runs::{RunKind, RunMeta, RunRecord, RunRegistry, RunSummary};
+use crypto_strategies::{
+    data, fees, grid, retention, strategies, symbols, universe, Config, Symbol,
+};
 use indicatif::{ProgressBar, ProgressStyle};
 use itertools::Itertools;
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use tracing::info;
+use tracing::{info, warn};
 
 /// Format duration in human readable format
 fn format_duration(secs: f64) -> String {
@@ -71,12 +75,17 @@ pub fn run(
     overrides: Vec<String>,
     sequential: bool,
     no_update: bool,
+    meta: RunMeta,
 ) -> Result<()> {
     info!("Starting optimization");
 
     // Load configuration
-    let mut config = Config::from_file(&config_path)?;
+    let (mut config, resolved) = Config::from_file_resolved(&config_path)?;
     info!("Loaded configuration from: {}", config_path);
+    super::config::print_layers(&resolved);
+    universe::apply_universe_blocking(&mut config)?;
+    fees::apply_exchange_fees_blocking(&mut config)?;
+    retention::auto_prune(&config)?;
 
     // Parse date range filters
     let start_date: Option<DateTime<Utc>> =
@@ -220,7 +229,7 @@ pub fn run(
     for (group_idx, symbols_vec) in symbol_groups.iter().enumerate() {
         let group_name = symbols_vec
             .iter()
-            .map(|s| s.replace("INR", ""))
+            .map(|s| symbols::base(s))
             .collect::<Vec<_>>()
             .join("+");
         symbol_groups_flat.push(group_name.clone());
@@ -393,41 +402,42 @@ pub fn run(
     pb.set_message("0 valid");
 
     let valid_count = Arc::new(AtomicUsize::new(0));
-    let valid_count_clone = valid_count.clone();
 
-    // Run all backtests
-    let all_results: Vec<OptimizationResult> = if sequential {
-        all_runs
-            .iter()
-            .filter_map(|(task, param_config)| {
-                let result = run_single_backtest(task, param_config);
-                pb.inc(1);
-                if let Some(ref r) = result {
-                    if r.total_trades > 0 {
-                        let count = valid_count.fetch_add(1, Ordering::Relaxed) + 1;
-                        pb.set_message(format!("{} valid", count));
-                    }
-                }
-                result
-            })
-            .collect()
-    } else {
-        all_runs
-            .par_iter()
-            .filter_map(|(task, param_config)| {
-                let result = run_single_backtest(task, param_config);
-                pb.inc(1);
-                if let Some(ref r) = result {
-                    if r.total_trades > 0 {
-                        let count = valid_count_clone.fetch_add(1, Ordering::Relaxed) + 1;
-                        pb.set_message(format!("{} valid", count));
-                    }
+    // Every result is appended to the CSV as it completes; only the top `top` per
+    // objective are kept in memory
+    let csv_path = format!(
+        "{}/optimize_{}.csv",
+        config.backtest.results_dir,
+        Utc::now().format("%Y%m%d_%H%M%S")
+    );
+    let stream = Mutex::new(ResultStream::new(top.max(1)).with_csv(&csv_path)?);
+    let csv_failed = AtomicUsize::new(0);
+    let run_one = |(task, param_config): &(OptTask, Config)| {
+        let result = run_single_backtest(task, param_config);
+        pb.inc(1);
+        if let Some(r) = result {
+            if r.total_trades > 0 {
+                let count = valid_count.fetch_add(1, Ordering::Relaxed) + 1;
+                pb.set_message(format!("{} valid", count));
+            }
+            let pushed = stream.lock().unwrap_or_else(|e| e.into_inner()).push(r);
+            if let Err(e) = pushed {
+                if csv_failed.fetch_add(1, Ordering::Relaxed) == 0 {
+                    warn!("Failed to append to {}: {}", csv_path, e);
                 }
-                result
-            })
-            .collect()
+            }
+        }
     };
 
+    // Run all backtests
+    if sequential {
+        all_runs.iter().for_each(run_one);
+    } else {
+        all_runs.par_iter().for_each(run_one);
+    }
+    let mut stream = stream.into_inner().unwrap_or_else(|e| e.into_inner());
+    let csv_path = stream.finish()?;
+
     let elapsed = start_time.elapsed();
     let elapsed_secs = elapsed.as_secs_f64();
     let final_valid = valid_count.load(Ordering::Relaxed);
@@ -457,10 +467,22 @@ pub fn run(
         "  ║  ├─ Valid results:   {:>8} ({:>5.1}%)                                      ║",
         final_valid, valid_pct
     );
-    println!(
-        "  ║  └─ Invalid/Empty:   {:>8}                                               ║",
-        total_runs - final_valid
-    );
+    let truncated_count = stream.truncated();
+    if config.backtest.early_stop.is_some() {
+        println!(
+            "  ║  ├─ Invalid/Empty:   {:>8}                                               ║",
+            total_runs - final_valid
+        );
+        println!(
+            "  ║  └─ Stopped early:   {:>8}                                               ║",
+            truncated_count
+        );
+    } else {
+        println!(
+            "  ║  └─ Invalid/Empty:   {:>8}                                               ║",
+            total_runs - final_valid
+        );
+    }
     println!("  ╠{}╣", border);
     println!("  ║  ⏱  Performance                                                              ║");
     println!(
@@ -474,19 +496,22 @@ pub fn run(
     println!("  ╚{}╝", border);
     println!();
 
-    if all_results.is_empty() {
+    if stream.is_empty() {
         info!("No valid results found.");
         return Ok(());
     }
 
-    // Sort results
-    let mut all_results = all_results;
-    sort_results(&mut all_results, &sort_by);
+    let all_results = stream.top(&sort_by);
     info!(
-        "Total results: {}, sorted by: {}",
+        "Total results: {}, top {} by: {}",
+        stream.len(),
         all_results.len(),
         sort_by
     );
+    if let Some(path) = &csv_path {
+        println!("  All results: {}", path.display());
+        println!();
+    }
 
     // Get grid param keys for filtering display
     let grid_keys: Vec<String> = config
@@ -564,6 +589,15 @@ pub fn run(
             })
             .collect::<Vec<_>>()
             .join(" ");
+        let grid_params = match &result.truncated {
+            Some(t) => format!(
+                "{} [stopped at {:.0}%: {:?}]",
+                grid_params,
+                t.progress() * 100.0,
+                t.reason
+            ),
+            None => grid_params,
+        };
 
         // Add rank indicator for top 3
         let rank_indicator = match i {
@@ -593,7 +627,7 @@ pub fn run(
     // Update config file with best parameters (unless --no-update)
     if !no_update && !all_results.is_empty() {
         let best = &all_results[0];
-        let best_metric = get_metric_value(best, &sort_by);
+        let best_metric = best.objective(&sort_by);
 
         // Get best result's timeframe
         let tf_val = *best.params.get("_timeframe").unwrap_or(&0.0);
@@ -607,7 +641,11 @@ pub fn run(
         };
 
         // Check if we should update
-        if best_metric < 0.0 {
+        if best.truncated.is_some() {
+            // Sorting puts complete runs first, so every combo was stopped early
+            println!("  Skipping config update: every combo hit the early-stop bounds");
+            println!();
+        } else if best_metric < 0.0 {
             // Don't update with a losing strategy
             println!(
                 "  Skipping config update: best result has negative {} ({:.2})",
@@ -677,21 +715,68 @@ pub fn run(
         }
     }
 
+    record_run(
+        &config_path,
+        &config,
+        &stream,
+        &sort_by,
+        csv_path,
+        start_date,
+        end_date,
+        meta,
+    );
+
     info!("Optimization completed successfully");
 
     Ok(())
 }
 
-/// Get metric value from result based on sort key
-fn get_metric_value(result: &OptimizationResult, sort_by: &str) -> f64 {
-    match sort_by {
-        "sharpe" => result.sharpe_ratio,
-        "return" => result.total_return,
-        "calmar" => result.calmar_ratio,
-        "win_rate" => result.win_rate,
-        "profit_factor" => result.profit_factor,
-        "expectancy" => result.expectancy,
-        _ => result.sharpe_ratio,
+/// Add the run to the registry with the top results per objective and the full CSV
+/// as artifacts (failures are only logged)
+#[allow(clippy::too_many_arguments)]
+fn record_run(
+    config_path: &str,
+    config: &Config,
+    stream: &ResultStream,
+    sort_by: &str,
+    csv_path: Option<std::path::PathBuf>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    meta: RunMeta,
+) {
+    let results = stream.top(sort_by);
+    let mut summary = results.first().map(RunSummary::from).unwrap_or_default();
+    summary.combinations = Some(stream.len());
+
+    let mut record = RunRecord::new(RunKind::Optimize, config_path, config, summary)
+        .with_name(meta.name)
+        .with_data_range(start, end);
+    if let Some(log_file) = meta.log_file {
+        record = record.with_artifact(log_file);
+    }
+
+    if let Some(path) = csv_path {
+        record = record.with_artifact(path);
+    }
+
+    let registry = RunRegistry::new(&config.backtest.results_dir);
+    let registered = registry
+        .write_artifact(&record.id, "results.json", &results)
+        .and_then(|path| {
+            let by_objective = registry.write_artifact(
+                &record.id,
+                "top_by_objective.json",
+                &stream.top_by_objective(),
+            )?;
+            registry.register(record.with_artifact(path).with_artifact(by_objective))
+        });
+    match registered {
+        Ok(run) => println!(
+            "  Run ID: {} (crypto-strategies runs show {})",
+            run.id,
+            run.label()
+        ),
+        Err(e) => warn!("Failed to record run: {:#}", e),
     }
 }
 
@@ -818,10 +903,7 @@ fn update_config_with_best(
         .unwrap_or_default();
 
     // Update strategy params including timeframe
-    if let Some(obj) = config_json
-        .get_mut("strategy")
-        .and_then(|s| s.as_object_mut())
-    {
+    if let Some(obj) = section(&mut config_json, "strategy") {
         // Update timeframe
         obj.insert("timeframe".to_string(), serde_json::json!(timeframe));
 
@@ -842,10 +924,7 @@ fn update_config_with_best(
     }
 
     // Update trading.symbols with best symbols (also remove old "pairs" key if present)
-    if let Some(obj) = config_json
-        .get_mut("trading")
-        .and_then(|t| t.as_object_mut())
-    {
+    if let Some(obj) = section(&mut config_json, "trading") {
         obj.remove("pairs"); // Remove old key if present
         obj.insert("symbols".to_string(), serde_json::json!(symbols));
     }
@@ -869,10 +948,7 @@ fn update_config_with_best(
     }
 
     // Update backtest date range
-    if let Some(obj) = config_json
-        .get_mut("backtest")
-        .and_then(|b| b.as_object_mut())
-    {
+    if let Some(obj) = section(&mut config_json, "backtest") {
         match start_date {
             Some(d) => obj.insert(
                 "start_date".to_string(),
@@ -893,6 +969,18 @@ fn update_config_with_best(
     Ok(())
 }
 
+/// Object `key` of the config file, created when the file inherits it via `extends`
+fn section<'a>(
+    config_json: &'a mut serde_json::Value,
+    key: &str,
+) -> Option<&'a mut serde_json::Map<String, serde_json::Value>> {
+    config_json
+        .as_object_mut()?
+        .entry(key)
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+}
+
 #[derive(Clone)]
 struct OptTask {
     group_idx: usize,
@@ -959,7 +1047,8 @@ fn run_single_backtest(task: &OptTask, param_config: &Config) -> Option<Optimiza
             .collect()
     };
 
-    let mut backtester = Backtester::new(param_config.clone(), strategy);
+    let mut backtester = Backtester::new(param_config.clone(), strategy)
+        .with_early_stop(param_config.backtest.early_stop.clone());
     let result = backtester.run(&mtf_data);
 
     // Build params with metadata
@@ -992,31 +1081,6 @@ fn run_single_backtest(task: &OptTask, param_config: &Config) -> Option<Optimiza
         calmar_ratio: result.metrics.calmar_ratio,
         profit_factor: result.metrics.profit_factor,
         expectancy: result.metrics.expectancy,
+        truncated: result.truncated,
     })
 }
-
-fn sort_results(results: &mut [OptimizationResult], sort_by: &str) {
-    results.sort_by(|a, b| {
-        let val_a = match sort_by {
-            "sharpe" => a.sharpe_ratio,
-            "return" => a.total_return,
-            "calmar" => a.calmar_ratio,
-            "win_rate" => a.win_rate,
-            "profit_factor" => a.profit_factor,
-            "expectancy" => a.expectancy,
-            _ => a.sharpe_ratio,
-        };
-        let val_b = match sort_by {
-            "sharpe" => b.sharpe_ratio,
-            "return" => b.total_return,
-            "calmar" => b.calmar_ratio,
-            "win_rate" => b.win_rate,
-            "profit_factor" => b.profit_factor,
-            "expectancy" => b.expectancy,
-            _ => b.sharpe_ratio,
-        };
-        val_b
-            .partial_cmp(&val_a)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-}
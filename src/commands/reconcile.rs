@@ -0,0 +1,101 @@
+//! Reconcile-trades command - match live fills to the exchange's trade history
+//!
+//! See `crypto_strategies::reconcile` for the matching rules and export format.
+
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDate, TimeZone, Utc};
+use std::path::Path;
+
+use crypto_strategies::coindcx::{ClientConfig, CoinDCXClient};
+use crypto_strategies::reconcile::{self, MatchStatus};
+use crypto_strategies::state_manager::create_state_manager;
+use crypto_strategies::Config;
+
+pub async fn run(
+    config_path: String,
+    state_db: String,
+    since: Option<String>,
+    output_dir: Option<String>,
+    sign_off: Option<String>,
+) -> Result<()> {
+    let config = Config::from_file(&config_path)?;
+    let recon = config.exchange.reconcile.clone().unwrap_or_default();
+
+    let since = match since {
+        Some(date) => {
+            let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                .context("--since must be YYYY-MM-DD")?;
+            Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        }
+        None => Utc::now() - Duration::hours(recon.lookback_hours as i64),
+    };
+    let output_dir = output_dir.unwrap_or(recon.output_dir);
+
+    let client = CoinDCXClient::with_config(
+        config.exchange.api_key.clone().unwrap_or_default(),
+        config.exchange.api_secret.clone().unwrap_or_default(),
+        ClientConfig {
+            network: config.exchange.network()?,
+            ..Default::default()
+        },
+    );
+    let state_dir = Path::new(&state_db).parent().unwrap_or(Path::new("."));
+    let state = create_state_manager(state_dir, "sqlite")?;
+
+    let report = reconcile::run(&client, &state, since).await?;
+    let (csv_path, manifest) = reconcile::write_export(&report, &output_dir, sign_off.as_deref())?;
+
+    println!("\n{}", "=".repeat(90));
+    println!("TRADE RECONCILIATION");
+    println!("{}", "=".repeat(90));
+    println!(
+        "  Period:              {} → {}",
+        report.period_start.format("%Y-%m-%d %H:%M"),
+        report.period_end.format("%Y-%m-%d %H:%M")
+    );
+    println!("  Orders:              {}", manifest.orders);
+    println!("  Matched:             {}", manifest.matched);
+    println!("  Quantity mismatch:   {}", manifest.quantity_mismatch);
+    println!("  Missing locally:     {}", manifest.missing_locally);
+    println!("  Missing on exchange: {}", manifest.missing_on_exchange);
+    println!("  Buy value:           {:.2}", manifest.buy_value);
+    println!("  Sell value:          {:.2}", manifest.sell_value);
+    println!("  Fees:                {:.4}", manifest.fees);
+
+    if !report.is_clean() {
+        println!("{}", "-".repeat(90));
+        println!(
+            "{:<17} {:<20} {:<10} {:<5} {:>12} {:>12}  Status",
+            "Time", "Order ID", "Symbol", "Side", "Local Qty", "Exch Qty"
+        );
+        for line in report
+            .lines
+            .iter()
+            .filter(|l| l.status != MatchStatus::Matched)
+        {
+            println!(
+                "{:<17} {:<20} {:<10} {:<5} {:>12.6} {:>12.6}  {}",
+                line.time.format("%Y-%m-%d %H:%M"),
+                line.order_id,
+                line.symbol,
+                line.side,
+                line.local_quantity,
+                line.exchange_quantity,
+                line.status.as_str()
+            );
+        }
+    }
+
+    println!("{}", "-".repeat(90));
+    println!("  Export:              {}", csv_path.display());
+    println!("  SHA-256:             {}", manifest.csv_sha256);
+    match &manifest.signed_off_by {
+        Some(name) => println!("  Signed off by:       {}", name),
+        None if report.is_clean() => {
+            println!("  Not signed off (re-run with --sign-off NAME to accept)")
+        }
+        None => println!("  Not signed off: resolve the discrepancies above first"),
+    }
+    println!("{}", "=".repeat(90));
+    Ok(())
+}
@@ -0,0 +1,179 @@
+//! Regression-check command - re-run pinned backtests and compare against golden results
+//!
+//! See `crypto_strategies::regression` for the suite and golden file formats.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::collections::BTreeMap;
+use tracing::info;
+
+use crypto_strategies::backtest::Backtester;
+use crypto_strategies::regression::{
+    self, CaseStatus, GoldenMetrics, GoldenResult, RegressionCase, RegressionSuite,
+};
+use crypto_strategies::runs::config_hash;
+use crypto_strategies::{data, dataset, strategies, Config};
+
+/// Result of running one case
+struct CaseRun {
+    config_hash: String,
+    data_hashes: BTreeMap<String, String>,
+    metrics: GoldenMetrics,
+}
+
+pub fn run(suite_path: String, only: Option<Vec<String>>, update: bool) -> Result<()> {
+    let suite = RegressionSuite::from_file(&suite_path)?;
+    let golden_path = suite.golden_path(&suite_path);
+    let mut golden = regression::load_golden(&golden_path)?;
+
+    let cases: Vec<&RegressionCase> = suite
+        .cases
+        .iter()
+        .filter(|c| only.as_ref().is_none_or(|names| names.contains(&c.name)))
+        .collect();
+    if cases.is_empty() {
+        anyhow::bail!("No regression cases selected from {}", suite_path);
+    }
+
+    println!("\n{}", "=".repeat(60));
+    println!("REGRESSION CHECK{}", if update { " (UPDATE)" } else { "" });
+    println!("{}", "=".repeat(60));
+    println!("  Suite:           {}", suite_path);
+    println!("  Golden:          {}", golden_path.display());
+    println!();
+
+    let mut failed = Vec::new();
+    for case in &cases {
+        let outcome = run_case(case)
+            .with_context(|| format!("Regression case '{}' failed to run", case.name))?;
+        let status = regression::check_case(
+            golden.get(&case.name),
+            &outcome.data_hashes,
+            &outcome.metrics,
+            &suite.tolerances,
+        );
+
+        println!(
+            "  {:<32} {:>12}  return {:>8.2}%  trades {:>4}",
+            case.name,
+            if update && !status.is_pass() {
+                "UPDATED"
+            } else {
+                status.label()
+            },
+            outcome.metrics.total_return,
+            outcome.metrics.total_trades
+        );
+        match &status {
+            CaseStatus::Regressed(deviations) => {
+                for d in deviations {
+                    println!("      {}", d);
+                }
+            }
+            CaseStatus::DataChanged(series) => {
+                println!("      input data differs for {}", series.join(", "));
+            }
+            CaseStatus::Pass | CaseStatus::Missing => {}
+        }
+
+        if update {
+            if !status.is_pass() {
+                golden.insert(
+                    case.name.clone(),
+                    GoldenResult {
+                        recorded_at: Utc::now(),
+                        config_hash: outcome.config_hash,
+                        data_hashes: outcome.data_hashes,
+                        metrics: outcome.metrics,
+                    },
+                );
+            }
+        } else if !status.is_pass() {
+            failed.push(case.name.clone());
+        }
+    }
+    println!("{}", "=".repeat(60));
+
+    if update {
+        regression::save_golden(&golden_path, &golden)?;
+        println!("  Golden results written to {}", golden_path.display());
+        return Ok(());
+    }
+    if !failed.is_empty() {
+        anyhow::bail!(
+            "{} of {} regression cases failed: {} (re-run with --update to accept the new results)",
+            failed.len(),
+            cases.len(),
+            failed.join(", ")
+        );
+    }
+    println!("  All {} cases match", cases.len());
+    Ok(())
+}
+
+fn run_case(case: &RegressionCase) -> Result<CaseRun> {
+    info!("Running regression case {}", case.name);
+    let mut config = Config::from_file(&case.config)?;
+    let hash = config_hash(&config);
+
+    // A pinned dataset replaces the config's data directory
+    let scratch = match &case.dataset {
+        Some(archive) => {
+            let dir = std::env::temp_dir().join(format!(
+                "crypto-strategies-regression-{}-{}",
+                std::process::id(),
+                case.name
+            ));
+            let _ = std::fs::remove_dir_all(&dir);
+            dataset::import_dataset(archive, &dir, true)?;
+            config.backtest.data_dir = dir.display().to_string();
+            Some(dir)
+        }
+        None => None,
+    };
+
+    let start = case.start.as_deref().map(data::parse_date).transpose()?;
+    let end = case.end.as_deref().map(data::parse_date).transpose()?;
+
+    let symbols = config.trading.symbols();
+    let primary_tf = config.timeframe();
+    let strategy = strategies::create_strategy(&config)?;
+    let mut timeframes = strategy.required_timeframes();
+    if !timeframes.contains(&primary_tf.as_str()) {
+        timeframes.push(&primary_tf);
+    }
+
+    let mtf_data = data::load_multi_timeframe(
+        &config.backtest.data_dir,
+        &symbols,
+        &timeframes,
+        &primary_tf,
+        start,
+        end,
+    )?;
+    // Hash what the engine actually consumed (date-filtered, adjusted), so
+    // appending newer candles to the files doesn't count as a data change
+    let mut data_hashes = BTreeMap::new();
+    for (symbol, mtf) in &mtf_data {
+        for tf in mtf.timeframes() {
+            let candles = mtf.get(tf).unwrap_or_default();
+            data_hashes.insert(
+                format!("{}_{}", symbol.as_str(), tf),
+                dataset::candles_sha256(candles),
+            );
+        }
+    }
+
+    let mut backtester = Backtester::new(config.clone(), strategy);
+    let result = backtester.run(&mtf_data);
+
+    if let Some(dir) = scratch {
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    Ok(CaseRun {
+        config_hash: hash,
+        data_hashes,
+        metrics: GoldenMetrics::from(&result.metrics),
+    })
+}
@@ -0,0 +1,212 @@
+//! Runs command - browse the local run registry
+//!
+//! Backtest and optimize runs are recorded automatically; see
+//! `crypto_strategies::runs` for the on-disk layout.
+
+use anyhow::{bail, Result};
+use crypto_strategies::runs::{RunRecord, RunRegistry};
+use crypto_strategies::stability;
+
+pub fn list(results_dir: String, limit: usize) -> Result<()> {
+    let registry = RunRegistry::new(&results_dir);
+    let runs = registry.list()?;
+
+    println!("\n{}", "=".repeat(100));
+    println!("RECORDED RUNS ({} total)", runs.len());
+    println!("{}", "=".repeat(100));
+    if runs.is_empty() {
+        println!("  No runs recorded under {}", results_dir);
+        return Ok(());
+    }
+
+    println!(
+        "{:<23} {:<9} {:<18} {:<20} {:>4} {:>9} {:>7} {:>7} {:>6}",
+        "ID", "Kind", "Name", "Strategy", "TF", "Return", "Sharpe", "MaxDD", "Trades"
+    );
+    println!("{}", "-".repeat(100));
+    for run in runs.iter().rev().take(limit) {
+        println!(
+            "{:<23} {:<9} {:<18} {:<20} {:>4} {:>8.2}% {:>7.2} {:>6.2}% {:>6}",
+            run.id,
+            run.kind.as_str(),
+            truncate(run.name.as_deref().unwrap_or("-"), 18),
+            truncate(&run.strategy, 20),
+            run.timeframe,
+            run.summary.total_return,
+            run.summary.sharpe_ratio,
+            run.summary.max_drawdown,
+            run.summary.total_trades
+        );
+    }
+    if runs.len() > limit {
+        println!("  ... {} older runs (use --limit)", runs.len() - limit);
+    }
+    println!("{}", "=".repeat(100));
+    Ok(())
+}
+
+pub fn show(results_dir: String, id: String) -> Result<()> {
+    let run = RunRegistry::new(&results_dir).find(&id)?;
+    print_run(&run);
+    Ok(())
+}
+
+pub fn analyze(
+    results_dir: String,
+    strategy: Option<String>,
+    top: usize,
+    min_periods: usize,
+) -> Result<()> {
+    let registry = RunRegistry::new(&results_dir);
+    let strategy = match strategy {
+        Some(s) => s,
+        None => match registry.list()?.last() {
+            Some(run) => run.strategy.clone(),
+            None => bail!("No runs recorded under {}", results_dir),
+        },
+    };
+    let observations = registry.observations(&strategy)?;
+    let periods: std::collections::BTreeSet<&str> =
+        observations.iter().map(|o| o.period.as_str()).collect();
+
+    println!("\n{}", "=".repeat(100));
+    println!(
+        "PARAMETER STABILITY: {} ({} results over {} data periods)",
+        strategy,
+        observations.len(),
+        periods.len()
+    );
+    println!("{}", "=".repeat(100));
+    if periods.len() < min_periods {
+        println!(
+            "  Need runs over at least {} data periods (found {}); run optimize/backtest on other date ranges",
+            min_periods,
+            periods.len()
+        );
+        return Ok(());
+    }
+
+    let regions = stability::region_stability(&observations, min_periods);
+    if regions.is_empty() {
+        println!(
+            "  No parameter region is covered by {} periods",
+            min_periods
+        );
+        return Ok(());
+    }
+
+    println!("Most stable regions (point + adjacent grid values):");
+    println!(
+        "{:<4} {:>10} {:>8} {:>11} {:>12} {:>10}  Params",
+        "#", "Profitable", "Points", "Avg Sharpe", "Worst Sharpe", "Avg Ret"
+    );
+    println!("{}", "-".repeat(100));
+    for (i, region) in regions.iter().take(top).enumerate() {
+        let params: Vec<String> = region
+            .params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        println!(
+            "{:<4} {:>10} {:>8} {:>11.2} {:>12.2} {:>9.2}%  {}",
+            i + 1,
+            format!("{}/{}", region.profitable_periods, region.periods),
+            region.neighbors,
+            region.mean_sharpe,
+            region.worst_sharpe,
+            region.mean_return,
+            params.join(", ")
+        );
+    }
+
+    println!("\nPer-parameter view:");
+    println!(
+        "{:<24} {:>10} {:>10} {:>11}",
+        "Param", "Value", "Profitable", "Avg Sharpe"
+    );
+    println!("{}", "-".repeat(60));
+    for row in stability::param_value_stability(&observations) {
+        println!(
+            "{:<24} {:>10} {:>10} {:>11.2}",
+            truncate(&row.param, 24),
+            row.value,
+            format!("{}/{}", row.profitable_periods, row.periods),
+            row.mean_sharpe
+        );
+    }
+    println!("{}", "=".repeat(100));
+    Ok(())
+}
+
+pub fn delete(results_dir: String, ids: Vec<String>) -> Result<()> {
+    let registry = RunRegistry::new(&results_dir);
+    for id in ids {
+        let run = registry.delete(&id)?;
+        println!("Deleted run {} ({})", run.id, run.label());
+    }
+    Ok(())
+}
+
+fn print_run(run: &RunRecord) {
+    let date = |d: Option<chrono::DateTime<chrono::Utc>>| {
+        d.map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    println!("\n{}", "=".repeat(60));
+    println!("RUN {}", run.id);
+    println!("{}", "=".repeat(60));
+    if let Some(name) = &run.name {
+        println!("  Name:            {}", name);
+    }
+    println!("  Kind:            {}", run.kind.as_str());
+    println!(
+        "  Created:         {}",
+        run.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    println!("  Config:          {}", run.config_path);
+    println!(
+        "  Config hash:     {}",
+        &run.config_hash[..16.min(run.config_hash.len())]
+    );
+    println!("  Strategy:        {} ({})", run.strategy, run.timeframe);
+    println!("  Symbols:         {}", run.symbols.join(", "));
+    println!(
+        "  Data range:      {} → {}",
+        date(run.data_start),
+        date(run.data_end)
+    );
+    if !run.data_hashes.is_empty() {
+        println!("  Data hashes:");
+        for (series, hash) in &run.data_hashes {
+            println!("    {:<20} {}", series, &hash[..16.min(hash.len())]);
+        }
+    }
+    println!("{}", "-".repeat(60));
+    if let Some(combinations) = run.summary.combinations {
+        println!("  Combinations:    {} (best result below)", combinations);
+    }
+    println!("  Total Return:    {:.2}%", run.summary.total_return);
+    println!("  Sharpe Ratio:    {:.2}", run.summary.sharpe_ratio);
+    println!("  Max Drawdown:    {:.2}%", run.summary.max_drawdown);
+    println!("  Win Rate:        {:.2}%", run.summary.win_rate);
+    println!("  Total Trades:    {}", run.summary.total_trades);
+    if !run.artifacts.is_empty() {
+        println!("{}", "-".repeat(60));
+        println!("  Artifacts:");
+        for artifact in &run.artifacts {
+            println!("    {}", artifact);
+        }
+    }
+    println!("{}", "=".repeat(60));
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let mut out: String = s.chars().take(max - 1).collect();
+        out.push('…');
+        out
+    }
+}
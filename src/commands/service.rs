@@ -0,0 +1,77 @@
+//! Service command - systemd integration for `live --daemon`
+
+use anyhow::{Context, Result};
+use crypto_strategies::service::{SystemdUnit, EXIT_CONFIG};
+use std::path::{Path, PathBuf};
+
+#[allow(clippy::too_many_arguments)]
+pub fn install(
+    config: String,
+    state_db: String,
+    live: bool,
+    name: String,
+    user: Option<String>,
+    working_dir: Option<String>,
+    env_file: Option<String>,
+    output: Option<String>,
+) -> Result<()> {
+    let working_dir = match working_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::current_dir()?,
+    };
+    let working_dir = working_dir.canonicalize().unwrap_or(working_dir);
+    if !working_dir.join(&config).exists() {
+        eprintln!(
+            "Warning: {} not found under {}",
+            config,
+            working_dir.display()
+        );
+    }
+    let env_file = env_file
+        .map(PathBuf::from)
+        .or_else(|| Some(working_dir.join(".env")).filter(|p| p.exists()));
+    let executable =
+        std::env::current_exe().context("Cannot locate the crypto-strategies executable")?;
+
+    let unit = SystemdUnit {
+        name: name.clone(),
+        executable: executable.canonicalize().unwrap_or(executable),
+        working_dir,
+        config,
+        state_db,
+        live,
+        user,
+        env_file,
+    }
+    .render();
+
+    let Some(output) = output else {
+        print!("{}", unit);
+        return Ok(());
+    };
+    std::fs::write(&output, &unit).with_context(|| format!("Failed to write {}", output))?;
+
+    println!("\n{}", "=".repeat(60));
+    println!("SYSTEMD UNIT WRITTEN");
+    println!("{}", "=".repeat(60));
+    println!("  Unit:    {}", output);
+    println!(
+        "  Mode:    {}",
+        if live { "LIVE (real money)" } else { "paper" }
+    );
+    println!(
+        "  Restart: on failure, except exit {} (configuration error)",
+        EXIT_CONFIG
+    );
+    println!("{}\n", "=".repeat(60));
+    if !Path::new(&output).starts_with("/etc/systemd") {
+        println!(
+            "Next: sudo cp {} /etc/systemd/system/{}.service",
+            output, name
+        );
+    }
+    println!("  sudo systemctl daemon-reload");
+    println!("  sudo systemctl enable --now {}", name);
+    println!("  journalctl -u {} -f", name);
+    Ok(())
+}
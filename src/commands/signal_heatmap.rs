@@ -0,0 +1,124 @@
+//! Signal-heatmap command - scan a strategy's entry signals across downloaded data
+//!
+//! See `crypto_strategies::signal_heatmap` for how signals are counted.
+
+use anyhow::Result;
+use std::path::Path;
+use tracing::{info, warn};
+
+use crypto_strategies::signal_heatmap::{self, Period, SignalHeatmap};
+use crypto_strategies::{data, retention, strategies, Config, Symbol};
+
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    config_path: String,
+    strategy_override: Option<String>,
+    symbols: Option<Vec<String>>,
+    timeframes: Option<Vec<String>>,
+    start: Option<String>,
+    end: Option<String>,
+    period: String,
+    warmup: usize,
+    output: Option<String>,
+) -> Result<()> {
+    let mut config = Config::from_file(&config_path)?;
+    if let Some(strategy) = strategy_override {
+        if let Some(obj) = config.strategy.as_object_mut() {
+            obj.insert("name".to_string(), serde_json::json!(strategy));
+        }
+    }
+    let period: Period = period.parse()?;
+    let start = start.as_deref().map(data::parse_date).transpose()?;
+    let end = end.as_deref().map(data::parse_date).transpose()?;
+    let data_dir = config.backtest.data_dir.clone();
+
+    // Every downloaded series, narrowed by --symbols / --timeframes
+    let series: Vec<(String, String)> = retention::tiered_series(Path::new(&data_dir))?
+        .into_iter()
+        .filter(|(s, _)| symbols.as_ref().is_none_or(|list| list.contains(s)))
+        .filter(|(_, tf)| timeframes.as_ref().is_none_or(|list| list.contains(tf)))
+        .collect();
+    if series.is_empty() {
+        anyhow::bail!("No downloaded series match in {}", data_dir);
+    }
+    let strategy_name = strategies::create_strategy(&config)?.name().to_string();
+
+    println!("\n{}", "=".repeat(60));
+    println!("SIGNAL HEATMAP");
+    println!("{}", "=".repeat(60));
+    println!("  Strategy:        {}", strategy_name);
+    println!("  Data dir:        {}", data_dir);
+    println!("  Series:          {}", series.len());
+
+    let mut heatmap = SignalHeatmap::new(period);
+    for (symbol, tf) in &series {
+        let mut series_config = config.clone();
+        series_config.set_timeframe(tf);
+        let mut strategy = strategies::create_strategy(&series_config)?;
+        let mut tfs: Vec<&str> = strategy.required_timeframes();
+        if !tfs.contains(&tf.as_str()) {
+            tfs.push(tf);
+        }
+
+        let sym = Symbol::new(symbol);
+        let loaded = match data::load_multi_timeframe(
+            &data_dir,
+            std::slice::from_ref(&sym),
+            &tfs,
+            tf,
+            start,
+            end,
+        ) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                warn!("Skipping {} {}: {}", symbol, tf, e);
+                continue;
+            }
+        };
+        let Some(mtf) = loaded.get(&sym).filter(|m| !m.primary().is_empty()) else {
+            continue;
+        };
+        if tfs.iter().any(|t| !mtf.has_timeframe(t)) {
+            warn!(
+                "Skipping {} {}: strategy needs {:?} but only {:?} is downloaded",
+                symbol,
+                tf,
+                tfs,
+                mtf.timeframes()
+            );
+            continue;
+        }
+
+        let signals = signal_heatmap::scan_signals(
+            strategy.as_mut(),
+            &sym,
+            mtf,
+            config.trading.initial_capital,
+            warmup,
+        );
+        info!(
+            "{} {}: {} signal bars in {} bars",
+            symbol,
+            tf,
+            signals.len(),
+            mtf.primary().len()
+        );
+        let bar_times = mtf.primary().iter().skip(warmup).map(|c| c.datetime);
+        heatmap.add_series(symbol, tf, bar_times, &signals);
+    }
+
+    print!("{}", heatmap.render());
+
+    let output = output.unwrap_or_else(|| {
+        format!(
+            "{}/signal_heatmap_{}.csv",
+            config.backtest.results_dir, strategy_name
+        )
+    });
+    if let Some(parent) = Path::new(&output).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&output, heatmap.to_csv_string())?;
+    println!("  Counts written to {}", output);
+    Ok(())
+}
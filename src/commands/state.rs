@@ -0,0 +1,84 @@
+//! State command - export/import live trading state snapshots, diff checkpoints
+//!
+//! Export/import migrate a running bot between machines. See the
+//! "Migrating Between Machines" section of docs/ARCHITECTURE.md.
+
+use anyhow::{Context, Result};
+use crypto_strategies::state_diff::CheckpointDiff;
+use crypto_strategies::state_manager::{
+    create_state_manager, export_snapshot, import_snapshot, CheckpointRef, SnapshotManifest,
+};
+use std::path::Path;
+
+/// Resolve the state directory the same way `live` does (parent of --state-db)
+fn state_dir(state_db: &str) -> &Path {
+    Path::new(state_db).parent().unwrap_or(Path::new("."))
+}
+
+pub fn export(archive: String, state_db: String) -> Result<()> {
+    let manifest = export_snapshot(state_dir(&state_db), &archive)?;
+
+    print_manifest("STATE SNAPSHOT EXPORTED", &archive, &manifest);
+    println!("Next: copy {} to the target machine and run", archive);
+    println!("  crypto-strategies state import {}", archive);
+    Ok(())
+}
+
+pub fn import(archive: String, state_db: String, force: bool) -> Result<()> {
+    let manifest = import_snapshot(&archive, state_dir(&state_db), force)?;
+
+    print_manifest("STATE SNAPSHOT IMPORTED", &archive, &manifest);
+    println!(
+        "Next: resume trading with the same config and --state-db {}",
+        state_db
+    );
+    Ok(())
+}
+
+pub fn diff(from: String, to: String, state_db: String, json: bool) -> Result<()> {
+    let dir = state_dir(&state_db);
+    if !dir.join("trading_state.db").exists() {
+        anyhow::bail!("No state database in {}", dir.display());
+    }
+    let state = create_state_manager(dir, "sqlite")?;
+
+    let find = |spec: &str| -> Result<_> {
+        let at: CheckpointRef = spec.parse()?;
+        state
+            .find_checkpoint(at)?
+            .with_context(|| format!("No checkpoint matches {:?}", spec))
+    };
+    let (mut a, mut b) = (find(&from)?, find(&to)?);
+    // Always diff forward in time
+    if a.0 > b.0 {
+        std::mem::swap(&mut a, &mut b);
+    }
+    let trades = state.load_trades()?;
+    let diff = CheckpointDiff::between((a.0, &a.1), (b.0, &b.1), &trades);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        println!("\n{}\n", diff.render());
+    }
+    Ok(())
+}
+
+fn print_manifest(title: &str, archive: &str, manifest: &SnapshotManifest) {
+    println!("\n{}", "=".repeat(60));
+    println!("{}", title);
+    println!("{}", "=".repeat(60));
+    println!("  Archive:         {}", archive);
+    println!("  Created:         {}", manifest.created_at);
+    println!("  Open positions:  {}", manifest.open_positions);
+    println!("  Pending orders:  {}", manifest.pending_orders);
+    println!(
+        "  Last checkpoint: {}",
+        manifest.last_checkpoint.as_deref().unwrap_or("none")
+    );
+    if let Some(cycle) = manifest.cycle_count {
+        println!("  Cycle count:     {}", cycle);
+    }
+    println!("  Files:           {}", manifest.files.len());
+    println!("{}\n", "=".repeat(60));
+}
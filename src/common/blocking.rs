@@ -0,0 +1,31 @@
+//! Running async client calls from synchronous code
+
+use std::future::Future;
+
+/// Run `future` to completion on a fresh runtime
+///
+/// The runtime lives on its own thread, so this also works when the caller
+/// is already inside one. A panic in `future` resumes in the caller.
+pub fn block_on<F>(future: F) -> std::io::Result<F::Output>
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    std::thread::scope(|s| {
+        s.spawn(|| Ok(tokio::runtime::Runtime::new()?.block_on(future)))
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_blocks_inside_a_runtime() {
+        let mut value = 1;
+        block_on(async { value += 1 }).unwrap();
+        assert_eq!(value, 2);
+    }
+}
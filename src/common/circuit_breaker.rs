@@ -7,7 +7,13 @@
 //! - Closed: Normal operation, requests pass through
 //! - Open: Service is failing, requests are rejected
 //! - HalfOpen: Testing if service has recovered
+//!
+//! [`CircuitBreaker::on_transition`] registers callbacks run on every state
+//! change, and [`CircuitBreaker::metrics`] counts failures, opens, half-open
+//! probes and rejected attempts for monitoring.
 
+use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::Instant;
 
@@ -64,6 +70,21 @@ impl CircuitBreakerConfig {
     }
 }
 
+/// Lifetime counters of a circuit breaker
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CircuitBreakerMetrics {
+    /// Failures recorded, in any state
+    pub failures: u64,
+    /// Transitions to Open (from Closed or a failed probe)
+    pub opens: u64,
+    /// Transitions to HalfOpen after the open timeout
+    pub half_open_probes: u64,
+    /// Attempts refused while Open
+    pub rejected: u64,
+}
+
+type TransitionHook = Arc<dyn Fn(CircuitState, CircuitState) + Send + Sync>;
+
 /// Circuit breaker for managing service failures
 ///
 /// # Example
@@ -89,13 +110,27 @@ impl CircuitBreakerConfig {
 /// // Circuit is now open
 /// assert_eq!(cb.state(), CircuitState::Open);
 /// ```
-#[derive(Debug)]
 pub struct CircuitBreaker {
     state: CircuitState,
     failure_count: u32,
     success_count: u32,
     config: CircuitBreakerConfig,
     last_failure_time: Option<Instant>,
+    metrics: CircuitBreakerMetrics,
+    hooks: Vec<TransitionHook>,
+}
+
+impl fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("state", &self.state)
+            .field("failure_count", &self.failure_count)
+            .field("success_count", &self.success_count)
+            .field("config", &self.config)
+            .field("metrics", &self.metrics)
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
 }
 
 impl CircuitBreaker {
@@ -107,6 +142,8 @@ impl CircuitBreaker {
             success_count: 0,
             config,
             last_failure_time: None,
+            metrics: CircuitBreakerMetrics::default(),
+            hooks: Vec::new(),
         }
     }
 
@@ -115,6 +152,38 @@ impl CircuitBreaker {
         Self::new(CircuitBreakerConfig::default())
     }
 
+    /// Call `hook(from, to)` on every state change
+    ///
+    /// Hooks run synchronously while the breaker is borrowed, so they should
+    /// only record or forward the event (e.g. over a channel).
+    pub fn on_transition(
+        &mut self,
+        hook: impl Fn(CircuitState, CircuitState) + Send + Sync + 'static,
+    ) {
+        self.hooks.push(Arc::new(hook));
+    }
+
+    /// Lifetime counters
+    pub fn metrics(&self) -> CircuitBreakerMetrics {
+        self.metrics
+    }
+
+    fn transition(&mut self, to: CircuitState) {
+        let from = self.state;
+        if from == to {
+            return;
+        }
+        self.state = to;
+        match to {
+            CircuitState::Open => self.metrics.opens += 1,
+            CircuitState::HalfOpen => self.metrics.half_open_probes += 1,
+            CircuitState::Closed => {}
+        }
+        for hook in &self.hooks {
+            hook(from, to);
+        }
+    }
+
     /// Get the current state of the circuit breaker
     pub fn state(&self) -> CircuitState {
         self.state
@@ -132,11 +201,12 @@ impl CircuitBreaker {
                 if let Some(last_failure) = self.last_failure_time {
                     if last_failure.elapsed() >= self.config.timeout {
                         tracing::info!("Circuit breaker transitioning to HalfOpen state");
-                        self.state = CircuitState::HalfOpen;
+                        self.transition(CircuitState::HalfOpen);
                         self.failure_count = 0;
                         self.success_count = 0;
                         true
                     } else {
+                        self.metrics.rejected += 1;
                         false
                     }
                 } else {
@@ -161,7 +231,7 @@ impl CircuitBreaker {
                 self.success_count += 1;
                 if self.success_count >= self.config.success_threshold {
                     tracing::info!("Circuit breaker closed after successful recovery");
-                    self.state = CircuitState::Closed;
+                    self.transition(CircuitState::Closed);
                     self.failure_count = 0;
                     self.success_count = 0;
                 }
@@ -178,6 +248,7 @@ impl CircuitBreaker {
     /// In HalfOpen state: immediately reopens circuit
     pub fn record_failure(&mut self) {
         self.last_failure_time = Some(Instant::now());
+        self.metrics.failures += 1;
 
         match self.state {
             CircuitState::Closed => {
@@ -187,12 +258,12 @@ impl CircuitBreaker {
                         "Circuit breaker opened after {} failures",
                         self.failure_count
                     );
-                    self.state = CircuitState::Open;
+                    self.transition(CircuitState::Open);
                 }
             }
             CircuitState::HalfOpen => {
                 tracing::warn!("Circuit breaker re-opened due to failure in HalfOpen state");
-                self.state = CircuitState::Open;
+                self.transition(CircuitState::Open);
                 self.failure_count = 0;
                 self.success_count = 0;
             }
@@ -204,7 +275,7 @@ impl CircuitBreaker {
 
     /// Reset the circuit breaker to its initial closed state
     pub fn reset(&mut self) {
-        self.state = CircuitState::Closed;
+        self.transition(CircuitState::Closed);
         self.failure_count = 0;
         self.success_count = 0;
         self.last_failure_time = None;
@@ -364,6 +435,40 @@ mod tests {
         assert!(cb.can_attempt());
     }
 
+    #[test]
+    fn test_transition_hooks_and_metrics() {
+        let config = CircuitBreakerConfig::default()
+            .with_failure_threshold(2)
+            .with_success_threshold(1)
+            .with_timeout(Duration::from_millis(1));
+        let mut cb = CircuitBreaker::new(config);
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = seen.clone();
+        cb.on_transition(move |from, to| log.lock().unwrap().push((from, to)));
+
+        cb.record_failure();
+        cb.record_failure();
+        assert!(!cb.can_attempt());
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cb.can_attempt());
+        cb.record_success();
+
+        use CircuitState::*;
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![(Closed, Open), (Open, HalfOpen), (HalfOpen, Closed)]
+        );
+        assert_eq!(
+            cb.metrics(),
+            CircuitBreakerMetrics {
+                failures: 2,
+                opens: 1,
+                half_open_probes: 1,
+                rejected: 1,
+            }
+        );
+    }
+
     #[test]
     fn test_config_builder() {
         let config = CircuitBreakerConfig::default()
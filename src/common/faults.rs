@@ -0,0 +1,187 @@
+//! Fault injection for exchange clients (feature `fault-injection`)
+//!
+//! A [`FaultInjector`] holds per-operation queues of [`Fault`]s. Clients built
+//! with one consult it before every attempt: a queued fault replaces that
+//! attempt's network call, so retries, backoff and the circuit breaker see
+//! exactly what a real 429, 5xx or timeout would produce. [`Fault::Respond`]
+//! answers with a canned body instead, which is how partial fills (and any
+//! other exchange state) are staged without a network.
+//!
+//! ```no_run
+//! # #[cfg(feature = "fault-injection")]
+//! # async fn demo() -> anyhow::Result<()> {
+//! use crypto_strategies::coindcx::CoinDCXClient;
+//! use crypto_strategies::common::{Fault, FaultInjector};
+//!
+//! let faults = FaultInjector::new();
+//! faults.push("order_status", Fault::RateLimited);
+//! faults.push("order_status", Fault::partial_fill("ex-1", "cs-1", 1.0, 0.4, 100.0));
+//! let client = CoinDCXClient::new("key", "secret").with_faults(faults.clone());
+//! // First attempt is throttled, the retry sees the partial fill
+//! let order = client.get_order_status("ex-1").await?;
+//! assert_eq!(order.status, "partially_filled");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Operation names: CoinDCX uses `tickers`, `ticker`, `markets`,
+//! `markets_details`, `orderbook`, `candles`, `balances`, `user_info`,
+//! `place_order`, `cancel_order`, `order_status`, `active_orders`,
+//! `cancel_all_orders` and `trade_history`; Binance uses `klines`,
+//! `funding_rates` and `premium_index`.
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// What an injected attempt does instead of calling the exchange
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// HTTP 429 Too Many Requests
+    RateLimited,
+    /// HTTP error with the given status (500, 502, 503, ...)
+    ServerError(u16),
+    /// No response before the client timeout
+    Timeout,
+    /// Succeed with this body instead of the exchange's
+    Respond(serde_json::Value),
+}
+
+impl Fault {
+    /// Succeed with `body` serialized as the response
+    pub fn respond(body: impl serde::Serialize) -> Self {
+        Self::Respond(serde_json::to_value(body).unwrap_or_default())
+    }
+
+    /// CoinDCX order status for an order with `filled` of `total` executed
+    pub fn partial_fill(
+        order_id: &str,
+        client_order_id: &str,
+        total: f64,
+        filled: f64,
+        avg_price: f64,
+    ) -> Self {
+        Self::Respond(serde_json::json!({
+            "id": order_id,
+            "client_order_id": client_order_id,
+            "status": "partially_filled",
+            "total_quantity": total,
+            "remaining_quantity": total - filled,
+            "avg_price": avg_price,
+        }))
+    }
+
+    /// The attempt's outcome, shaped like the client's own errors
+    pub fn into_result<T: DeserializeOwned>(self) -> Result<T> {
+        let status = |code: u16| {
+            StatusCode::from_u16(code)
+                .map(|s| s.to_string())
+                .unwrap_or_else(|_| code.to_string())
+        };
+        match self {
+            Fault::RateLimited => Err(anyhow!("API error ({}): injected fault", status(429))),
+            Fault::ServerError(code) => {
+                Err(anyhow!("API error ({}): injected fault", status(code)))
+            }
+            Fault::Timeout => Err(anyhow!("operation timed out (injected fault)")),
+            Fault::Respond(body) => {
+                serde_json::from_value(body).context("Failed to parse injected response")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Queues {
+    pending: HashMap<String, VecDeque<Fault>>,
+    injected: usize,
+}
+
+/// Shared, per-operation fault queues; clones inject into the same queues
+#[derive(Debug, Clone, Default)]
+pub struct FaultInjector {
+    queues: Arc<Mutex<Queues>>,
+}
+
+impl FaultInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `fault` for the next attempt at `op`
+    pub fn push(&self, op: &str, fault: Fault) -> &Self {
+        self.push_n(op, fault, 1)
+    }
+
+    /// Queue `fault` for the next `n` attempts at `op`
+    pub fn push_n(&self, op: &str, fault: Fault, n: usize) -> &Self {
+        let mut queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        let queue = queues.pending.entry(op.to_string()).or_default();
+        queue.extend(std::iter::repeat_n(fault, n));
+        self
+    }
+
+    /// Next fault for an attempt at `op`, if one is queued
+    pub fn take(&self, op: &str) -> Option<Fault> {
+        let mut queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        let fault = queues.pending.get_mut(op)?.pop_front()?;
+        queues.injected += 1;
+        Some(fault)
+    }
+
+    /// Faults still queued for `op`
+    pub fn pending(&self, op: &str) -> usize {
+        let queues = self.queues.lock().unwrap_or_else(|e| e.into_inner());
+        queues.pending.get(op).map_or(0, VecDeque::len)
+    }
+
+    /// Faults consumed so far, across all operations
+    pub fn injected(&self) -> usize {
+        self.queues
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .injected
+    }
+
+    /// Drop every queued fault
+    pub fn clear(&self) {
+        self.queues
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pending
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queues_drain_in_order_per_operation() {
+        let faults = FaultInjector::new();
+        faults
+            .push_n("candles", Fault::ServerError(503), 2)
+            .push("candles", Fault::respond(vec![1, 2, 3]));
+        faults.push("ticker", Fault::Timeout);
+
+        let err = faults.take("candles").unwrap().into_result::<Vec<i32>>();
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .contains("503 Service Unavailable"));
+        assert_eq!(faults.pending("candles"), 2);
+        faults.take("candles");
+        let body: Vec<i32> = faults.take("candles").unwrap().into_result().unwrap();
+        assert_eq!(body, vec![1, 2, 3]);
+        assert!(faults.take("candles").is_none());
+
+        let rate_limited = Fault::RateLimited.into_result::<()>().unwrap_err();
+        assert!(rate_limited.to_string().contains("429 Too Many Requests"));
+        assert_eq!(faults.injected(), 3);
+        faults.clear();
+        assert_eq!(faults.pending("ticker"), 0);
+    }
+}
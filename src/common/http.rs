@@ -0,0 +1,217 @@
+//! HTTP connection pool settings shared by exchange clients
+//!
+//! [`PoolConfig`] holds the knobs that decide how often a client pays for a
+//! new connection: how many idle connections it keeps per host, how long
+//! they stay open, TCP keep-alive probes so NATs and load balancers don't
+//! drop them silently, and an optional DNS cache. A trading loop that polls
+//! every few seconds should almost never open a fresh connection.
+//!
+//! [`NetworkConfig`] holds what a restricted network needs instead: a proxy,
+//! extra root certificates for a TLS-inspecting gateway, and a connect
+//! timeout separate from the request timeout.
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Connection reuse settings for a client's HTTP pool
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Idle connections kept open per host
+    pub max_idle_per_host: usize,
+    /// Close idle connections after this long (`None` = never)
+    pub idle_timeout: Option<Duration>,
+    /// Interval of TCP keep-alive probes (`None` = off)
+    pub tcp_keepalive: Option<Duration>,
+    /// Reuse resolved addresses for this long (`None` = resolve every connect)
+    pub dns_cache_ttl: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_idle_per_host: 10,
+            idle_timeout: Some(Duration::from_secs(90)),
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            dns_cache_ttl: None,
+        }
+    }
+}
+
+impl PoolConfig {
+    /// Build an HTTP client with these pool settings, a request timeout and
+    /// `network`'s proxy and TLS settings
+    pub fn build_client(
+        &self,
+        timeout: Duration,
+        network: &NetworkConfig,
+    ) -> reqwest::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(timeout)
+            .pool_max_idle_per_host(self.max_idle_per_host)
+            .pool_idle_timeout(self.idle_timeout)
+            .tcp_keepalive(self.tcp_keepalive);
+        if let Some(ttl) = self.dns_cache_ttl {
+            builder = builder.dns_resolver(Arc::new(CachingResolver::new(ttl)));
+        }
+        network.apply(builder)?.build()
+    }
+}
+
+/// Proxy, TLS trust and connect timeout of a client
+#[derive(Debug, Clone, Default)]
+pub struct NetworkConfig {
+    /// Proxy for every request, e.g. `http://proxy.corp:3128` (`None` = the
+    /// `HTTP(S)_PROXY` environment variables, as before)
+    pub proxy: Option<String>,
+    /// PEM root certificates trusted on top of the system's
+    pub root_certificates: Vec<Vec<u8>>,
+    /// Give up on establishing a connection after this long (`None` = only
+    /// the request timeout applies)
+    pub connect_timeout: Option<Duration>,
+}
+
+impl NetworkConfig {
+    /// Send every request through the proxy at `url` (`http://`, `https://`)
+    pub fn with_proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Trust a PEM root certificate on top of the system's
+    pub fn with_root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /// Give up on establishing a connection after `timeout`
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Check that a client can be built with these settings
+    ///
+    /// Client constructors panic on settings that fail this, so check
+    /// settings from outside the program first.
+    pub fn validate(&self) -> reqwest::Result<()> {
+        self.apply(reqwest::Client::builder())?.build().map(drop)
+    }
+
+    /// Add these settings to `builder`; fails on a malformed proxy URL or certificate
+    pub fn apply(
+        &self,
+        mut builder: reqwest::ClientBuilder,
+    ) -> reqwest::Result<reqwest::ClientBuilder> {
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        for pem in &self.root_certificates {
+            builder = builder.add_root_certificate(reqwest::Certificate::from_pem(pem)?);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        Ok(builder)
+    }
+}
+
+type Cache = HashMap<String, (Instant, Vec<SocketAddr>)>;
+
+/// System resolver with a fixed-TTL cache in front
+#[derive(Debug, Clone)]
+pub struct CachingResolver {
+    ttl: Duration,
+    cache: Arc<Mutex<Cache>>,
+}
+
+impl CachingResolver {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            cache: Arc::default(),
+        }
+    }
+
+    /// Cached addresses for `host`, if resolved within the TTL
+    fn cached(&self, host: &str) -> Option<Vec<SocketAddr>> {
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .get(host)
+            .filter(|(at, _)| at.elapsed() < self.ttl)
+            .map(|(_, addrs)| addrs.clone())
+    }
+
+    fn store(&self, host: &str, addrs: Vec<SocketAddr>) {
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache.insert(host.to_string(), (Instant::now(), addrs));
+    }
+}
+
+impl Resolve for CachingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let this = self.clone();
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs = match this.cached(host) {
+                Some(addrs) => addrs,
+                None => {
+                    // Port is a placeholder; the connector sets the real one
+                    let addrs: Vec<SocketAddr> =
+                        tokio::net::lookup_host((host, 0)).await?.collect();
+                    this.store(host, addrs.clone());
+                    addrs
+                }
+            };
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dns_cache_expires() {
+        let resolver = CachingResolver::new(Duration::from_millis(20));
+        let addr: SocketAddr = "10.0.0.1:0".parse().unwrap();
+        resolver.store("api.example.com", vec![addr]);
+        assert_eq!(resolver.cached("api.example.com"), Some(vec![addr]));
+        assert_eq!(resolver.cached("other.example.com"), None);
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(resolver.cached("api.example.com"), None);
+        assert!(PoolConfig::default()
+            .build_client(Duration::from_secs(1), &NetworkConfig::default())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_network_settings_are_checked() {
+        let build = |network: NetworkConfig| {
+            PoolConfig::default().build_client(Duration::from_secs(1), &network)
+        };
+        let network = NetworkConfig::default()
+            .with_proxy("http://proxy.example.com:3128")
+            .with_connect_timeout(Duration::from_secs(5));
+        assert!(network.validate().is_ok());
+        assert!(build(network).is_ok());
+        assert!(NetworkConfig::default()
+            .with_proxy("not a url")
+            .validate()
+            .is_err());
+        assert!(build(NetworkConfig {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(build(NetworkConfig {
+            root_certificates: vec![b"not a certificate".to_vec()],
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
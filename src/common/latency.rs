@@ -0,0 +1,153 @@
+//! Per-endpoint request latency histograms
+//!
+//! [`LatencyMetrics`] keeps one [`LatencyHistogram`] per endpoint path,
+//! filled by the client as responses arrive. Buckets are fixed, so
+//! recording is cheap and percentiles are upper bounds: a p99 of 250ms
+//! means 99% of requests finished within 250ms. Clones share the same
+//! histograms.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Upper bounds of the histogram buckets, in milliseconds
+pub const BUCKETS_MS: [u64; 12] = [5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000, 30000];
+
+/// Latency distribution of one endpoint
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyHistogram {
+    /// Requests per bucket; the last counts everything above the largest bound
+    pub buckets: [u64; BUCKETS_MS.len() + 1],
+    pub count: u64,
+    /// Requests that failed or got a non-2xx status
+    pub errors: u64,
+    pub total: Duration,
+    pub max: Duration,
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, elapsed: Duration, success: bool) {
+        let ms = elapsed.as_millis() as u64;
+        let bucket = BUCKETS_MS
+            .iter()
+            .position(|&bound| ms <= bound)
+            .unwrap_or(BUCKETS_MS.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        if !success {
+            self.errors += 1;
+        }
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+    }
+
+    pub fn mean(&self) -> Duration {
+        match self.count {
+            0 => Duration::ZERO,
+            n => self.total / n as u32,
+        }
+    }
+
+    /// Bucket bound under which a fraction `q` (0.0..=1.0) of requests finished
+    ///
+    /// Requests slower than the largest bucket report the observed maximum.
+    pub fn percentile(&self, q: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        let target = ((q.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (i, n) in self.buckets.iter().enumerate() {
+            seen += n;
+            if seen >= target {
+                return BUCKETS_MS
+                    .get(i)
+                    .map_or(self.max, |&ms| Duration::from_millis(ms).min(self.max));
+            }
+        }
+        self.max
+    }
+}
+
+impl fmt::Display for LatencyHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "n={} err={} mean={}ms p50≤{}ms p99≤{}ms max={}ms",
+            self.count,
+            self.errors,
+            self.mean().as_millis(),
+            self.percentile(0.5).as_millis(),
+            self.percentile(0.99).as_millis(),
+            self.max.as_millis()
+        )
+    }
+}
+
+/// Latency histograms keyed by endpoint path
+#[derive(Debug, Clone, Default)]
+pub struct LatencyMetrics {
+    endpoints: Arc<Mutex<BTreeMap<String, LatencyHistogram>>>,
+}
+
+impl LatencyMetrics {
+    pub fn record(&self, endpoint: &str, elapsed: Duration, success: bool) {
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        endpoints
+            .entry(endpoint.to_string())
+            .or_default()
+            .record(elapsed, success);
+    }
+
+    /// Copy of every endpoint's histogram so far
+    pub fn snapshot(&self) -> BTreeMap<String, LatencyHistogram> {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Forget everything recorded so far
+    pub fn reset(&self) {
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_percentiles() {
+        let metrics = LatencyMetrics::default();
+        let handle = metrics.clone();
+        for ms in [3, 8, 20, 40, 40, 90, 120, 400, 900, 60_000] {
+            metrics.record("/exchange/ticker", Duration::from_millis(ms), ms < 1000);
+        }
+        metrics.record(
+            "/exchange/v1/orders/create",
+            Duration::from_millis(70),
+            true,
+        );
+
+        let snapshot = handle.snapshot();
+        let ticker = &snapshot["/exchange/ticker"];
+        assert_eq!(ticker.count, 10);
+        assert_eq!(ticker.errors, 1);
+        assert_eq!(ticker.percentile(0.5), Duration::from_millis(50));
+        assert_eq!(ticker.percentile(0.9), Duration::from_millis(1000));
+        assert_eq!(ticker.percentile(1.0), Duration::from_secs(60));
+        assert_eq!(ticker.buckets[BUCKETS_MS.len()], 1);
+        assert_eq!(
+            snapshot["/exchange/v1/orders/create"].percentile(0.99),
+            Duration::from_millis(70)
+        );
+
+        handle.reset();
+        assert!(metrics.snapshot().is_empty());
+    }
+}
@@ -0,0 +1,153 @@
+//! Request/response hooks for exchange clients
+//!
+//! A [`Middleware`] is a set of closures a client calls around every HTTP
+//! exchange: `on_request` just before a request is sent, `on_response` once
+//! its body has been read (or the send failed). Hooks observe; they can't
+//! change or block a request. Typical uses are structured logging, latency
+//! metrics and capturing traffic for replay tests.
+//!
+//! Authentication headers are never passed to hooks. Signed request bodies
+//! are, so a hook that persists them should treat them as sensitive.
+//!
+//! ```
+//! use crypto_strategies::common::Middleware;
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::sync::Arc;
+//!
+//! let errors = Arc::new(AtomicUsize::new(0));
+//! let counter = errors.clone();
+//! let middleware = Middleware::default()
+//!     .on_request(|req| tracing::debug!("→ {} {}", req.method, req.url))
+//!     .on_response(move |res| {
+//!         if !res.is_success() {
+//!             counter.fetch_add(1, Ordering::Relaxed);
+//!         }
+//!     });
+//! assert_eq!(middleware.len(), 2);
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// An outgoing request
+#[derive(Debug, Clone, Copy)]
+pub struct RequestInfo<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    /// The request body, if it has one and it is text
+    pub body: Option<&'a str>,
+}
+
+/// The outcome of a request
+#[derive(Debug, Clone, Copy)]
+pub struct ResponseInfo<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    /// `None` when no response arrived (connect error, timeout)
+    pub status: Option<u16>,
+    /// The response body, or the transport error when there was no response
+    pub body: &'a str,
+    /// From sending the request to reading the whole body
+    pub elapsed: Duration,
+}
+
+impl ResponseInfo<'_> {
+    pub fn is_success(&self) -> bool {
+        self.status.is_some_and(|s| (200..300).contains(&s))
+    }
+}
+
+type RequestHook = Arc<dyn Fn(&RequestInfo) + Send + Sync>;
+type ResponseHook = Arc<dyn Fn(&ResponseInfo) + Send + Sync>;
+
+/// Hooks called around every request, in registration order
+#[derive(Clone, Default)]
+pub struct Middleware {
+    request: Vec<RequestHook>,
+    response: Vec<ResponseHook>,
+}
+
+impl Middleware {
+    /// Call `hook` before every request is sent
+    pub fn on_request(mut self, hook: impl Fn(&RequestInfo) + Send + Sync + 'static) -> Self {
+        self.request.push(Arc::new(hook));
+        self
+    }
+
+    /// Call `hook` after every response (or failed send)
+    pub fn on_response(mut self, hook: impl Fn(&ResponseInfo) + Send + Sync + 'static) -> Self {
+        self.response.push(Arc::new(hook));
+        self
+    }
+
+    /// Number of registered hooks
+    pub fn len(&self) -> usize {
+        self.request.len() + self.response.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn request(&self, info: &RequestInfo) {
+        for hook in &self.request {
+            hook(info);
+        }
+    }
+
+    pub fn response(&self, info: &ResponseInfo) {
+        for hook in &self.response {
+            hook(info);
+        }
+    }
+}
+
+impl fmt::Debug for Middleware {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Middleware")
+            .field("request_hooks", &self.request.len())
+            .field("response_hooks", &self.response.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_hooks_run_in_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (a, b, c) = (log.clone(), log.clone(), log.clone());
+        let middleware = Middleware::default()
+            .on_request(move |req| a.lock().unwrap().push(format!("1 {}", req.method)))
+            .on_request(move |req| b.lock().unwrap().push(format!("2 {}", req.url)))
+            .on_response(move |res| c.lock().unwrap().push(format!("{:?}", res.status)));
+
+        let req = RequestInfo {
+            method: "POST",
+            url: "https://x/orders",
+            body: Some("{}"),
+        };
+        middleware.request(&req);
+        let res = ResponseInfo {
+            method: "POST",
+            url: "https://x/orders",
+            status: Some(429),
+            body: "",
+            elapsed: Duration::from_millis(5),
+        };
+        middleware.response(&res);
+        assert!(!res.is_success());
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["1 POST", "2 https://x/orders", "Some(429)"]
+        );
+        assert_eq!(
+            format!("{:?}", middleware),
+            "Middleware { request_hooks: 2, response_hooks: 1 }"
+        );
+    }
+}
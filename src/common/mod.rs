@@ -3,10 +3,35 @@
 //! This module contains reusable components for all exchange integrations:
 //! - Circuit breaker pattern for fault tolerance
 //! - Rate limiter using token bucket algorithm
-//! - Retry logic with exponential backoff
+//! - Retry policy with jittered exponential backoff
+//! - Request/response middleware hooks
+//! - HTTP connection pool settings and per-endpoint latency histograms
+//! - Reconnect supervisor for WebSocket streams
+//! - Fault injection for exercising error paths (feature `fault-injection`)
+//! - Blocking bridge for calling clients from synchronous commands
 
+pub mod blocking;
 pub mod circuit_breaker;
+#[cfg(feature = "fault-injection")]
+pub mod faults;
+pub mod http;
+pub mod latency;
+pub mod middleware;
 pub mod rate_limiter;
+pub mod reconnect;
+pub mod retry;
 
-pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
-pub use rate_limiter::{RateLimiter, RateLimiterConfig};
+pub use blocking::block_on;
+pub use circuit_breaker::{
+    CircuitBreaker, CircuitBreakerConfig, CircuitBreakerMetrics, CircuitState,
+};
+#[cfg(feature = "fault-injection")]
+pub use faults::{Fault, FaultInjector};
+pub use http::{NetworkConfig, PoolConfig};
+pub use latency::{LatencyHistogram, LatencyMetrics};
+pub use middleware::{Middleware, RequestInfo, ResponseInfo};
+pub use rate_limiter::{
+    RateLimiter, RateLimiterConfig, ReportedUsage, RequestPriority, RequestShed,
+};
+pub use reconnect::{Gap, ReconnectingStream, StreamEvent};
+pub use retry::RetryPolicy;
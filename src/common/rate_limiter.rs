@@ -2,11 +2,136 @@
 //!
 //! Provides rate limiting to prevent API abuse and stay within
 //! exchange rate limits.
-
+//!
+//! The local token bucket is corrected by usage the exchange reports in
+//! response headers (see [`ReportedUsage`]): when reported usage crosses the
+//! backoff threshold, requests pause until the exchange window resets instead
+//! of waiting for a 429. Past the lower slowdown threshold, requests are
+//! spaced out so what is left of the allowance lasts until the reset.
+//!
+//! Requests carry a [`RequestPriority`]. Under pressure (a reported-usage
+//! pause, or few permits left) low-priority calls such as ticker refreshes are
+//! shed first so order placement and stop monitoring keep their budget.
+//!
+//! Endpoints can cost more than one token. [`RateLimiterConfig::with_endpoint_weight`]
+//! gives an operation (e.g. `place_order`) a weight matching the exchange's
+//! own accounting, and [`RateLimiter::acquire_for`] takes that many tokens.
+//! Unlisted endpoints cost one.
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{Mutex, Semaphore};
 use tokio::time::Instant;
+use tracing::warn;
+
+/// Binance spot `REQUEST_WEIGHT` limit per minute
+pub const BINANCE_WEIGHT_LIMIT_1M: u64 = 6000;
+
+/// Rate-limit usage reported by an exchange in response headers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReportedUsage {
+    /// Units consumed in the current window (requests or weight)
+    pub used: u64,
+    /// Units allowed per window
+    pub limit: u64,
+    /// Time until the window resets
+    pub reset_after: Duration,
+}
+
+impl ReportedUsage {
+    /// Fraction of the window's allowance consumed (0.0 - 1.0+)
+    pub fn fraction(&self) -> f64 {
+        if self.limit == 0 {
+            return 0.0;
+        }
+        self.used as f64 / self.limit as f64
+    }
+
+    /// Units left in the current window
+    pub fn remaining(&self) -> u64 {
+        self.limit.saturating_sub(self.used)
+    }
+
+    /// Parse usage headers.
+    ///
+    /// Understands Binance's `X-MBX-USED-WEIGHT-1M` and the common
+    /// `X-RateLimit-Limit` / `X-RateLimit-Remaining` / `X-RateLimit-Reset` set.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        if let Some(used) = header_u64(headers, "x-mbx-used-weight-1m") {
+            let secs_into_minute = chrono::Utc::now().timestamp().rem_euclid(60) as u64;
+            return Some(Self {
+                used,
+                limit: BINANCE_WEIGHT_LIMIT_1M,
+                reset_after: Duration::from_secs(60 - secs_into_minute),
+            });
+        }
+
+        let limit = header_u64(headers, "x-ratelimit-limit")?;
+        let remaining = header_u64(headers, "x-ratelimit-remaining")?;
+        let reset_after = header_u64(headers, "x-ratelimit-reset")
+            .map(|reset| {
+                // Either seconds-until-reset or an epoch timestamp
+                if reset > 1_000_000_000 {
+                    let now = chrono::Utc::now().timestamp() as u64;
+                    Duration::from_secs(reset.saturating_sub(now))
+                } else {
+                    Duration::from_secs(reset)
+                }
+            })
+            .unwrap_or(Duration::from_secs(1));
+
+        Some(Self {
+            used: limit.saturating_sub(remaining),
+            limit,
+            reset_after,
+        })
+    }
+}
+
+/// `Retry-After` header in seconds
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    header_u64(headers, "retry-after").map(Duration::from_secs)
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// How important a request is when the rate limit is under pressure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RequestPriority {
+    /// Analytics, ticker refreshes, market lists: shed first
+    Low,
+    /// Candles, balances, history
+    Normal,
+    /// Order status checks, stop monitoring, quotes before an order
+    High,
+    /// Placing and cancelling orders: never shed
+    Critical,
+}
+
+/// Error returned for a request dropped under rate-limit pressure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestShed {
+    pub priority: RequestPriority,
+}
+
+impl fmt::Display for RequestShed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} priority request shed under rate-limit pressure",
+            self.priority
+        )
+    }
+}
+
+impl std::error::Error for RequestShed {}
 
 /// Configuration for the rate limiter
 #[derive(Debug, Clone)]
@@ -15,6 +140,16 @@ pub struct RateLimiterConfig {
     pub max_requests_per_second: usize,
     /// Refill interval for tokens
     pub refill_interval: Duration,
+    /// Pause until the exchange window resets once reported usage reaches this fraction
+    pub backoff_threshold: f64,
+    /// Spread the remaining allowance over the window once reported usage reaches this fraction
+    pub slowdown_threshold: f64,
+    /// Shed `Normal` requests when fewer than this fraction of permits are left
+    pub normal_reserve: f64,
+    /// Shed `Low` requests when fewer than this fraction of permits are left
+    pub low_reserve: f64,
+    /// Tokens per request by endpoint (operation name); unlisted endpoints cost 1
+    pub endpoint_weights: HashMap<String, usize>,
 }
 
 impl Default for RateLimiterConfig {
@@ -22,6 +157,11 @@ impl Default for RateLimiterConfig {
         Self {
             max_requests_per_second: 10,
             refill_interval: Duration::from_secs(1),
+            backoff_threshold: 0.8,
+            slowdown_threshold: 0.5,
+            normal_reserve: 0.2,
+            low_reserve: 0.5,
+            endpoint_weights: HashMap::new(),
         }
     }
 }
@@ -38,6 +178,31 @@ impl RateLimiterConfig {
         self.refill_interval = interval;
         self
     }
+
+    /// Set the reported-usage fraction that triggers proactive backoff
+    pub fn with_backoff_threshold(mut self, threshold: f64) -> Self {
+        self.backoff_threshold = threshold;
+        self
+    }
+
+    /// Set the reported-usage fraction from which requests are spaced out
+    pub fn with_slowdown_threshold(mut self, threshold: f64) -> Self {
+        self.slowdown_threshold = threshold;
+        self
+    }
+
+    /// Set the permit fractions kept back from `Normal` and `Low` requests
+    pub fn with_priority_reserves(mut self, normal: f64, low: f64) -> Self {
+        self.normal_reserve = normal;
+        self.low_reserve = low;
+        self
+    }
+
+    /// Charge `weight` tokens for each request to `endpoint`
+    pub fn with_endpoint_weight(mut self, endpoint: impl Into<String>, weight: usize) -> Self {
+        self.endpoint_weights.insert(endpoint.into(), weight);
+        self
+    }
 }
 
 /// Rate limiter using token bucket algorithm
@@ -63,6 +228,16 @@ pub struct RateLimiter {
     max_permits: usize,
     last_refill: Arc<Mutex<Instant>>,
     refill_interval: Duration,
+    backoff_threshold: f64,
+    slowdown_threshold: f64,
+    normal_reserve: f64,
+    low_reserve: f64,
+    paused_until: Arc<Mutex<Option<Instant>>>,
+    /// Least time between requests, and when the next one may go
+    pace: Arc<Mutex<(Duration, Instant)>>,
+    last_usage: Arc<Mutex<Option<ReportedUsage>>>,
+    shed: Arc<AtomicU64>,
+    endpoint_weights: Arc<HashMap<String, usize>>,
 }
 
 impl RateLimiter {
@@ -73,6 +248,15 @@ impl RateLimiter {
             max_permits: config.max_requests_per_second,
             last_refill: Arc::new(Mutex::new(Instant::now())),
             refill_interval: config.refill_interval,
+            backoff_threshold: config.backoff_threshold,
+            slowdown_threshold: config.slowdown_threshold,
+            normal_reserve: config.normal_reserve,
+            low_reserve: config.low_reserve,
+            paused_until: Arc::new(Mutex::new(None)),
+            pace: Arc::new(Mutex::new((Duration::ZERO, Instant::now()))),
+            last_usage: Arc::new(Mutex::new(None)),
+            shed: Arc::new(AtomicU64::new(0)),
+            endpoint_weights: Arc::new(config.endpoint_weights),
         }
     }
 
@@ -94,16 +278,97 @@ impl RateLimiter {
     ///
     /// The permit is consumed (not returned to the pool).
     pub async fn acquire(&self) {
+        self.acquire_many(1).await;
+    }
+
+    /// Tokens a request to `endpoint` costs
+    ///
+    /// Capped at the bucket size, so a heavy endpoint waits for a full
+    /// bucket rather than forever.
+    pub fn weight(&self, endpoint: &str) -> usize {
+        self.endpoint_weights
+            .get(endpoint)
+            .copied()
+            .unwrap_or(1)
+            .clamp(1, self.max_permits.max(1))
+    }
+
+    /// Acquire the permits a request to `endpoint` costs, or shed it under pressure
+    ///
+    /// Same as [`acquire_with_priority`](Self::acquire_with_priority), with
+    /// the endpoint's [`weight`](Self::weight) in place of one permit.
+    pub async fn acquire_for(
+        &self,
+        endpoint: &str,
+        priority: RequestPriority,
+    ) -> Result<(), RequestShed> {
+        self.acquire_weighted(self.weight(endpoint), priority).await
+    }
+
+    async fn acquire_many(&self, weight: usize) {
+        // Honour any exchange-driven backoff first
+        self.wait_if_paused().await;
+        self.wait_for_pace().await;
+
         // Try to refill permits
         self.try_refill().await;
 
-        // Wait for a permit and consume it
-        let permit = self
+        // Wait for the permits and consume them
+        let permits = self
             .permits
-            .acquire()
+            .acquire_many(weight as u32)
             .await
             .expect("Semaphore should not be closed");
-        permit.forget(); // Consume the permit (don't return it to the pool)
+        permits.forget(); // Consume the permits (don't return them to the pool)
+    }
+
+    /// Acquire a permit for a request of `priority`, or shed it under pressure.
+    ///
+    /// `High` and `Critical` requests always wait like [`acquire`](Self::acquire).
+    /// `Normal` and `Low` requests are shed while the limiter is paused, or
+    /// when taking a permit would dip into the reserve kept for higher
+    /// priorities.
+    pub async fn acquire_with_priority(
+        &self,
+        priority: RequestPriority,
+    ) -> Result<(), RequestShed> {
+        self.acquire_weighted(1, priority).await
+    }
+
+    async fn acquire_weighted(
+        &self,
+        weight: usize,
+        priority: RequestPriority,
+    ) -> Result<(), RequestShed> {
+        let reserve = match priority {
+            RequestPriority::Critical | RequestPriority::High => {
+                self.acquire_many(weight).await;
+                return Ok(());
+            }
+            RequestPriority::Normal => self.normal_reserve,
+            RequestPriority::Low => self.low_reserve,
+        };
+        self.wait_for_pace().await;
+
+        if self.paused_for().await.is_none() {
+            self.try_refill().await;
+            let reserved = (self.max_permits as f64 * reserve).ceil() as usize;
+            if self.permits.available_permits() >= reserved + weight {
+                if let Ok(permit) = self.permits.try_acquire_many(weight as u32) {
+                    permit.forget();
+                    return Ok(());
+                }
+            }
+        }
+
+        self.shed.fetch_add(1, Ordering::Relaxed);
+        tracing::debug!("Shedding {:?} priority request", priority);
+        Err(RequestShed { priority })
+    }
+
+    /// Requests shed by [`acquire_with_priority`](Self::acquire_with_priority) so far
+    pub fn shed_count(&self) -> u64 {
+        self.shed.load(Ordering::Relaxed)
     }
 
     /// Try to acquire a permit without blocking
@@ -111,6 +376,9 @@ impl RateLimiter {
     /// Returns `true` if a permit was acquired, `false` otherwise.
     /// The permit is consumed (not returned to the pool).
     pub async fn try_acquire(&self) -> bool {
+        if self.paused_for().await.is_some() {
+            return false;
+        }
         self.try_refill().await;
         match self.permits.try_acquire() {
             Ok(permit) => {
@@ -131,6 +399,106 @@ impl RateLimiter {
         self.max_permits
     }
 
+    /// Update bucket state from an exchange response.
+    ///
+    /// - 429/418: pause for `Retry-After` (or one refill interval)
+    /// - usage at/above the backoff threshold: pause until the window resets
+    /// - otherwise: clamp local permits to what the exchange says is left, and
+    ///   past the slowdown threshold space requests evenly until the reset
+    pub async fn observe_response(&self, status: StatusCode, headers: &HeaderMap) {
+        if status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418 {
+            let wait = retry_after(headers).unwrap_or(self.refill_interval);
+            warn!("Rate limited by exchange ({}), pausing {:?}", status, wait);
+            self.pause_for(wait).await;
+            return;
+        }
+
+        let Some(usage) = ReportedUsage::from_headers(headers) else {
+            return;
+        };
+        *self.last_usage.lock().await = Some(usage);
+
+        if usage.fraction() >= self.backoff_threshold {
+            warn!(
+                "Exchange rate limit {:.0}% used ({}/{}), pausing {:?}",
+                usage.fraction() * 100.0,
+                usage.used,
+                usage.limit,
+                usage.reset_after
+            );
+            self.pause_for(usage.reset_after).await;
+        } else {
+            self.drain_to(usage.remaining() as usize);
+            let interval = if usage.fraction() >= self.slowdown_threshold {
+                usage.reset_after / usage.remaining().max(1) as u32
+            } else {
+                Duration::ZERO
+            };
+            self.pace.lock().await.0 = interval;
+        }
+    }
+
+    /// Least time between requests set by reported usage (zero when not slowed down)
+    pub async fn pace(&self) -> Duration {
+        self.pace.lock().await.0
+    }
+
+    /// Wait for this request's slot when requests are being spaced out
+    async fn wait_for_pace(&self) {
+        let slot = {
+            let mut pace = self.pace.lock().await;
+            if pace.0.is_zero() {
+                return;
+            }
+            let slot = pace.1.max(Instant::now());
+            pace.1 = slot + pace.0;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+
+    /// Stop handing out permits for `duration`
+    pub async fn pause_for(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut paused = self.paused_until.lock().await;
+        if paused.is_none_or(|current| current < until) {
+            *paused = Some(until);
+        }
+        self.drain_to(0);
+    }
+
+    /// Remaining backoff, if paused
+    pub async fn paused_for(&self) -> Option<Duration> {
+        let paused = self.paused_until.lock().await;
+        paused
+            .map(|until| until.saturating_duration_since(Instant::now()))
+            .filter(|d| !d.is_zero())
+    }
+
+    /// Most recent usage reported by the exchange
+    pub async fn last_usage(&self) -> Option<ReportedUsage> {
+        *self.last_usage.lock().await
+    }
+
+    async fn wait_if_paused(&self) {
+        if let Some(wait) = self.paused_for().await {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Drop local permits above `max_available`
+    fn drain_to(&self, max_available: usize) {
+        let excess = self
+            .permits
+            .available_permits()
+            .saturating_sub(max_available);
+        if excess > 0 {
+            if let Ok(permits) = self.permits.try_acquire_many(excess as u32) {
+                permits.forget();
+            }
+        }
+    }
+
     /// Try to refill permits if the refill interval has elapsed
     async fn try_refill(&self) {
         let mut last_refill = self.last_refill.lock().await;
@@ -161,6 +529,15 @@ impl Clone for RateLimiter {
             max_permits: self.max_permits,
             last_refill: Arc::clone(&self.last_refill),
             refill_interval: self.refill_interval,
+            backoff_threshold: self.backoff_threshold,
+            slowdown_threshold: self.slowdown_threshold,
+            normal_reserve: self.normal_reserve,
+            low_reserve: self.low_reserve,
+            paused_until: Arc::clone(&self.paused_until),
+            pace: Arc::clone(&self.pace),
+            last_usage: Arc::clone(&self.last_usage),
+            shed: Arc::clone(&self.shed),
+            endpoint_weights: Arc::clone(&self.endpoint_weights),
         }
     }
 }
@@ -281,6 +658,206 @@ mod tests {
         // All 5 permits should be consumed
     }
 
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_parse_binance_weight_header() {
+        let usage =
+            ReportedUsage::from_headers(&headers(&[("x-mbx-used-weight-1m", "4800")])).unwrap();
+        assert_eq!(usage.limit, BINANCE_WEIGHT_LIMIT_1M);
+        assert_eq!(usage.remaining(), 1200);
+        assert!((usage.fraction() - 0.8).abs() < 1e-9);
+        assert!(usage.reset_after <= Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_parse_standard_headers() {
+        let usage = ReportedUsage::from_headers(&headers(&[
+            ("x-ratelimit-limit", "100"),
+            ("x-ratelimit-remaining", "3"),
+            ("x-ratelimit-reset", "2"),
+        ]))
+        .unwrap();
+        assert_eq!(usage.used, 97);
+        assert_eq!(usage.reset_after, Duration::from_secs(2));
+
+        assert!(ReportedUsage::from_headers(&HeaderMap::new()).is_none());
+        assert_eq!(
+            retry_after(&headers(&[("retry-after", "7")])),
+            Some(Duration::from_secs(7))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_observe_clamps_permits_to_reported_remaining() {
+        let limiter = RateLimiter::with_rate(10);
+        let reported = headers(&[
+            ("x-ratelimit-limit", "100"),
+            ("x-ratelimit-remaining", "60"),
+        ]);
+        limiter.observe_response(StatusCode::OK, &reported).await;
+        assert_eq!(limiter.available_permits(), 10);
+        assert!(limiter.paused_for().await.is_none());
+
+        let reported = headers(&[
+            ("x-ratelimit-limit", "100"),
+            ("x-ratelimit-remaining", "25"),
+        ]);
+        let limiter = RateLimiter::new(RateLimiterConfig::default().with_rate(50));
+        limiter.observe_response(StatusCode::OK, &reported).await;
+        assert_eq!(limiter.available_permits(), 25);
+        assert_eq!(limiter.last_usage().await.unwrap().used, 75);
+    }
+
+    #[tokio::test]
+    async fn test_observe_spaces_requests_past_slowdown() {
+        let limiter = RateLimiter::with_rate(50);
+        let reported = |remaining: &str| {
+            headers(&[
+                ("x-ratelimit-limit", "100"),
+                ("x-ratelimit-remaining", remaining),
+                ("x-ratelimit-reset", "1"),
+            ])
+        };
+        limiter
+            .observe_response(StatusCode::OK, &reported("40"))
+            .await;
+        assert_eq!(limiter.pace().await, Duration::from_millis(25));
+
+        let started = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(started.elapsed() >= Duration::from_millis(50));
+
+        // Usage back under the slowdown threshold lifts the spacing
+        limiter
+            .observe_response(StatusCode::OK, &reported("90"))
+            .await;
+        assert_eq!(limiter.pace().await, Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_observe_backs_off_near_limit() {
+        let limiter = RateLimiter::with_rate(5);
+        let reported = headers(&[
+            ("x-ratelimit-limit", "100"),
+            ("x-ratelimit-remaining", "10"),
+            ("x-ratelimit-reset", "30"),
+        ]);
+        limiter.observe_response(StatusCode::OK, &reported).await;
+
+        assert!(limiter.paused_for().await.unwrap() > Duration::from_secs(25));
+        assert!(!limiter.try_acquire().await);
+    }
+
+    #[tokio::test]
+    async fn test_429_pauses_for_retry_after() {
+        let limiter = RateLimiter::with_rate(5);
+        limiter
+            .observe_response(
+                StatusCode::TOO_MANY_REQUESTS,
+                &headers(&[("retry-after", "1")]),
+            )
+            .await;
+        let paused = limiter.paused_for().await.unwrap();
+        assert!(paused <= Duration::from_secs(1) && paused > Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_priority_shedding_keeps_reserve() {
+        let config = RateLimiterConfig::default()
+            .with_rate(10)
+            .with_refill_interval(Duration::from_secs(60));
+        let limiter = RateLimiter::new(config);
+
+        // Low requests stop at the 50% reserve
+        for _ in 0..5 {
+            assert!(limiter
+                .acquire_with_priority(RequestPriority::Low)
+                .await
+                .is_ok());
+        }
+        let shed = limiter
+            .acquire_with_priority(RequestPriority::Low)
+            .await
+            .unwrap_err();
+        assert_eq!(shed.priority, RequestPriority::Low);
+
+        // Normal requests may use permits down to the 20% reserve
+        for _ in 0..3 {
+            assert!(limiter
+                .acquire_with_priority(RequestPriority::Normal)
+                .await
+                .is_ok());
+        }
+        assert!(limiter
+            .acquire_with_priority(RequestPriority::Normal)
+            .await
+            .is_err());
+
+        // Critical requests get the rest
+        limiter
+            .acquire_with_priority(RequestPriority::Critical)
+            .await
+            .unwrap();
+        limiter
+            .acquire_with_priority(RequestPriority::Critical)
+            .await
+            .unwrap();
+        assert_eq!(limiter.available_permits(), 0);
+        assert_eq!(limiter.shed_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pause_sheds_low_priority_immediately() {
+        let limiter = RateLimiter::with_rate(10);
+        limiter.pause_for(Duration::from_secs(30)).await;
+        assert!(limiter
+            .acquire_with_priority(RequestPriority::Normal)
+            .await
+            .is_err());
+        assert!(limiter
+            .acquire_with_priority(RequestPriority::Low)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_weights() {
+        let config = RateLimiterConfig::default()
+            .with_rate(10)
+            .with_refill_interval(Duration::from_secs(60))
+            .with_endpoint_weight("place_order", 5)
+            .with_endpoint_weight("orderbook", 50);
+        let limiter = RateLimiter::new(config);
+        assert_eq!(limiter.weight("ticker"), 1);
+        assert_eq!(limiter.weight("orderbook"), 10);
+
+        limiter
+            .acquire_for("place_order", RequestPriority::Critical)
+            .await
+            .unwrap();
+        assert_eq!(limiter.available_permits(), 5);
+
+        // Normal requests keep 2 permits back: another order no longer fits, a ticker does
+        assert!(limiter
+            .acquire_for("place_order", RequestPriority::Normal)
+            .await
+            .is_err());
+        limiter
+            .acquire_for("ticker", RequestPriority::Normal)
+            .await
+            .unwrap();
+        assert_eq!(limiter.available_permits(), 4);
+    }
+
     #[tokio::test]
     async fn test_with_defaults() {
         let limiter = RateLimiter::with_defaults();
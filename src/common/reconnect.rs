@@ -0,0 +1,280 @@
+//! Reconnect supervisor for streaming clients
+//!
+//! The WebSocket streams in this crate end when their connection drops.
+//! [`ReconnectingStream`] wraps one and, when it ends, connects again through
+//! the same `connect` closure after a [`RetryPolicy`] backoff, so the new
+//! connection joins the channels the old one had. Messages the venue
+//! replays after a reconnect are dropped by key ([`Dedup`]), and each outage
+//! is reported as a [`StreamEvent::Gap`] so candle aggregation can backfill
+//! the missed bars over REST.
+//!
+//! ```no_run
+//! use crypto_strategies::binance::ws::KlineStream;
+//! use crypto_strategies::common::{reconnect, StreamEvent};
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let symbols = ["BTCUSDT".to_string()];
+//!     let intervals = ["1m".to_string()];
+//!     let mut stream = KlineStream::reconnecting(
+//!         "wss://stream.binance.com:9443",
+//!         &symbols,
+//!         &intervals,
+//!         reconnect::reconnect_policy(),
+//!     )
+//!     .await?;
+//!     while let Some(event) = stream.next().await {
+//!         match event {
+//!             StreamEvent::Message(bar) => println!("{} {}", bar.symbol, bar.kline.close),
+//!             StreamEvent::Gap(gap) => println!("missed {}..{}", gap.from, gap.to),
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use anyhow::Result;
+use chrono::Utc;
+use futures_util::StreamExt;
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::retry::RetryPolicy;
+
+/// Message keys remembered for de-duplication
+pub const DEDUP_WINDOW: usize = 4096;
+
+/// Backoff for reconnecting: doubling up to a minute, never giving up
+pub fn reconnect_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: u32::MAX,
+        max_delay: Duration::from_secs(60),
+        budget: None,
+        ..RetryPolicy::default()
+    }
+}
+
+/// Time the stream was down, in milliseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gap {
+    /// When the connection dropped
+    pub from: i64,
+    /// When the new connection was up
+    pub to: i64,
+    /// Connection attempts it took
+    pub attempts: u32,
+}
+
+/// What a [`ReconnectingStream`] yields
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent<T> {
+    Message(T),
+    /// The connection dropped and came back; messages in between were missed
+    Gap(Gap),
+}
+
+/// Keys of the last `capacity` messages seen
+#[derive(Debug, Clone)]
+pub struct Dedup {
+    seen: HashSet<String>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl Dedup {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Remember `key`; false if it was already among the recent keys
+    pub fn first_seen(&mut self, key: String) -> bool {
+        if self.seen.contains(&key) {
+            return false;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.seen.insert(key.clone());
+        self.order.push_back(key);
+        true
+    }
+}
+
+/// A stream that reconnects itself
+///
+/// Ends only when `policy` runs out of attempts. Dropping it closes the socket.
+pub struct ReconnectingStream<T> {
+    rx: mpsc::UnboundedReceiver<StreamEvent<T>>,
+    task: JoinHandle<()>,
+}
+
+impl<T: Send + 'static> ReconnectingStream<T> {
+    /// Connect once, then keep reconnecting whenever the stream ends
+    ///
+    /// Fails if the first connection does. `key` names a message for
+    /// de-duplication; messages it returns `None` for are always passed on.
+    pub async fn connect<S, F, Fut>(
+        policy: RetryPolicy,
+        mut connect: F,
+        key: fn(&T) -> Option<String>,
+    ) -> Result<Self>
+    where
+        S: futures_core::Stream<Item = T> + Unpin + Send + 'static,
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<S>> + Send,
+    {
+        let mut stream = connect().await?;
+        let (tx, rx) = mpsc::unbounded_channel();
+        let task = tokio::spawn(async move {
+            let mut dedup = Dedup::new(DEDUP_WINDOW);
+            loop {
+                while let Some(message) = stream.next().await {
+                    if key(&message).is_some_and(|k| !dedup.first_seen(k)) {
+                        continue;
+                    }
+                    if tx.send(StreamEvent::Message(message)).is_err() {
+                        // Receiver dropped: nobody is listening
+                        return;
+                    }
+                }
+
+                let from = Utc::now().timestamp_millis();
+                let mut attempts = 0;
+                let mut spent = Duration::ZERO;
+                stream = loop {
+                    attempts += 1;
+                    let Some(delay) = policy.next_delay(attempts, spent) else {
+                        tracing::warn!(
+                            "Stream still down after {} attempt(s); giving up",
+                            attempts - 1
+                        );
+                        return;
+                    };
+                    tokio::time::sleep(delay).await;
+                    spent += delay;
+                    match connect().await {
+                        Ok(stream) => break stream,
+                        Err(e) => tracing::debug!("Reconnect attempt {} failed: {:#}", attempts, e),
+                    }
+                };
+                let gap = Gap {
+                    from,
+                    to: Utc::now().timestamp_millis(),
+                    attempts,
+                };
+                tracing::info!("Stream reconnected after {} ms", gap.to - gap.from);
+                if tx.send(StreamEvent::Gap(gap)).is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(Self { rx, task })
+    }
+
+    /// Next message or gap; `None` once reconnecting has given up
+    pub async fn next(&mut self) -> Option<StreamEvent<T>> {
+        self.rx.recv().await
+    }
+
+    /// Everything received since the last call, without waiting
+    ///
+    /// Returns `None` once reconnecting has given up and nothing is left.
+    pub fn drain(&mut self) -> Option<Vec<StreamEvent<T>>> {
+        let mut events = Vec::new();
+        loop {
+            match self.rx.try_recv() {
+                Ok(event) => events.push(event),
+                Err(mpsc::error::TryRecvError::Empty) => return Some(events),
+                Err(mpsc::error::TryRecvError::Disconnected) if events.is_empty() => return None,
+                Err(mpsc::error::TryRecvError::Disconnected) => return Some(events),
+            }
+        }
+    }
+}
+
+impl<T> futures_core::Stream for ReconnectingStream<T> {
+    type Item = StreamEvent<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl<T> Drop for ReconnectingStream<T> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_dedup_forgets_oldest_keys() {
+        let mut dedup = Dedup::new(2);
+        assert!(dedup.first_seen("a".into()));
+        assert!(!dedup.first_seen("a".into()));
+        assert!(dedup.first_seen("b".into()));
+        assert!(dedup.first_seen("c".into()));
+        // "a" fell out of the window
+        assert!(dedup.first_seen("a".into()));
+        assert!(!dedup.first_seen("c".into()));
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_with_gap_and_drops_replays() {
+        let connects = Arc::new(AtomicU32::new(0));
+        let counter = connects.clone();
+        // Each connection replays the last message of the one before
+        let connect = move || {
+            let n = counter.fetch_add(1, Ordering::Relaxed);
+            async move {
+                match n {
+                    0 => Ok(futures_util::stream::iter(vec![1, 2])),
+                    1 => anyhow::bail!("refused"),
+                    2 => Ok(futures_util::stream::iter(vec![2, 3])),
+                    _ => anyhow::bail!("refused"),
+                }
+            }
+        };
+        let policy = RetryPolicy::default()
+            .with_max_retries(3)
+            .with_base_delay(Duration::from_millis(1))
+            .without_jitter();
+        let mut stream =
+            ReconnectingStream::connect(policy, connect, |n: &u32| Some(n.to_string()))
+                .await
+                .unwrap();
+
+        let mut events = Vec::new();
+        while let Some(event) = stream.next().await {
+            events.push(event);
+        }
+        assert_eq!(
+            events[..2],
+            [StreamEvent::Message(1), StreamEvent::Message(2)]
+        );
+        let StreamEvent::Gap(gap) = events[2] else {
+            panic!("expected a gap, got {:?}", events[2]);
+        };
+        assert_eq!(gap.attempts, 2);
+        assert!(gap.to >= gap.from);
+        assert_eq!(events[3..], [StreamEvent::Message(3)]);
+        // Three refused attempts after the second connection dropped
+        assert_eq!(connects.load(Ordering::Relaxed), 6);
+    }
+}
@@ -0,0 +1,151 @@
+//! Retry policy: exponential backoff with full jitter and a time budget
+//!
+//! The delay before retry `n` is drawn uniformly from
+//! `0..=min(max_delay, base_delay * 2^(n-1))` ("full jitter"), so clients
+//! that failed together don't retry in lockstep. A request stops retrying
+//! once `max_retries` is reached or the next delay would take the total time
+//! spent backing off past `budget`.
+//!
+//! Requests that create something (orders, transfers) are not idempotent: a
+//! timeout or 5xx may have been processed anyway, and sending it again could
+//! double it. By default those are only retried after a rate limit, which the
+//! exchange rejects before acting on.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// When and how often a failed request is sent again
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Backoff ceiling of the first retry; doubles on each one after
+    pub base_delay: Duration,
+    /// Upper bound on any single backoff
+    pub max_delay: Duration,
+    /// Draw each delay from `0..=ceiling` instead of waiting the full ceiling
+    pub jitter: bool,
+    /// Most time a request may spend backing off in total (`None` = no limit)
+    pub budget: Option<Duration>,
+    /// Also retry non-idempotent requests after errors they may have survived
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            budget: Some(Duration::from_secs(30)),
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self::default().with_max_retries(0)
+    }
+
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    pub fn with_base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Wait the full backoff ceiling every time
+    pub fn without_jitter(mut self) -> Self {
+        self.jitter = false;
+        self
+    }
+
+    /// Cap the total time one request spends backing off
+    pub fn with_budget(mut self, budget: Duration) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Retry order placement and other non-idempotent requests like any other
+    pub fn with_non_idempotent_retries(mut self) -> Self {
+        self.retry_non_idempotent = true;
+        self
+    }
+
+    /// Backoff ceiling before retry `attempt` (1-based)
+    pub fn ceiling(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Delay before retry `attempt`, given `spent` backing off so far
+    ///
+    /// `None` when the retry count or the budget is exhausted.
+    pub fn next_delay(&self, attempt: u32, spent: Duration) -> Option<Duration> {
+        if attempt == 0 || attempt > self.max_retries {
+            return None;
+        }
+        let ceiling = self.ceiling(attempt);
+        let delay = if self.jitter {
+            ceiling.mul_f64(random_fraction())
+        } else {
+            ceiling
+        };
+        match self.budget {
+            Some(budget) if spent + delay > budget => None,
+            _ => Some(delay),
+        }
+    }
+}
+
+/// Uniform-ish value in `0.0..1.0`, seeded per call by the std hasher
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_jitter_and_budget() {
+        let policy = RetryPolicy::default()
+            .with_max_retries(5)
+            .with_max_delay(Duration::from_secs(4))
+            .without_jitter()
+            .with_budget(Duration::from_secs(10));
+        let delays: Vec<_> = (1..=4)
+            .map(|n| policy.next_delay(n, Duration::ZERO).unwrap().as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 4]);
+        assert!(policy.next_delay(6, Duration::ZERO).is_none());
+        // 1 + 2 + 4 spent; another 4 would overrun the 10s budget
+        assert!(policy.next_delay(4, Duration::from_secs(7)).is_none());
+
+        let jittered = RetryPolicy::default();
+        for _ in 0..100 {
+            let delay = jittered.next_delay(3, Duration::ZERO).unwrap();
+            assert!(delay <= Duration::from_secs(4));
+        }
+        assert!(RetryPolicy::none().next_delay(1, Duration::ZERO).is_none());
+    }
+}
@@ -4,13 +4,18 @@
 //! variable support for API credentials.
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
 use std::path::Path;
+use std::sync::OnceLock;
 
+use crate::config_layers::{self, ConfigOverrides, ResolvedConfig};
 use crate::Symbol;
 
+/// `--env` / `--set` from the command line, applied by every [`Config::from_file`]
+static CLI_OVERRIDES: OnceLock<ConfigOverrides> = OnceLock::new();
+
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -23,24 +28,85 @@ pub struct Config {
     /// Each key is a strategy param name, value is array of values to test
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub grid: Option<HashMap<String, Vec<serde_json::Value>>>,
+    /// Endpoints notified of fills/closes in live mode (optional)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+    /// Operator kill switch checked every live cycle
+    #[serde(default)]
+    pub kill_switch: KillSwitchConfig,
+    /// Live mode: slow down between bars on high timeframes (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_mode: Option<IdleModeConfig>,
+    /// Live mode: REST acknowledgment of the startup trade plan (see `live --confirm`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preflight: Option<PreflightConfig>,
 }
 
 impl Config {
-    /// Load configuration from JSON file
+    /// Load configuration from JSON file, resolving `extends` and any CLI overrides
     pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
-        let contents = fs::read_to_string(path.as_ref()).context("Failed to read config file")?;
-        let mut config: Config =
-            serde_json::from_str(&contents).context("Failed to parse config JSON")?;
+        Ok(Self::from_file_resolved(path)?.0)
+    }
+
+    /// [`Config::from_file`], also returning where each field came from
+    pub fn from_file_resolved(path: impl AsRef<Path>) -> Result<(Self, ResolvedConfig)> {
+        let overrides = CLI_OVERRIDES.get().cloned().unwrap_or_default();
+        Self::from_file_with(path, &overrides)
+    }
+
+    /// Load with explicit overlay/override layers (see [`crate::config_layers`])
+    pub fn from_file_with(
+        path: impl AsRef<Path>,
+        overrides: &ConfigOverrides,
+    ) -> Result<(Self, ResolvedConfig)> {
+        let resolved = config_layers::resolve(path, overrides)?;
+        let mut config: Config = serde_json::from_value(resolved.value.clone())
+            .context("Failed to parse config JSON")?;
+
+        // Catch typos: a --set path the config doesn't have would otherwise be ignored
+        if !overrides.set.is_empty() {
+            let effective = serde_json::to_value(&config)?;
+            for assignment in &overrides.set {
+                let (key, value) = config_layers::parse_assignment(assignment)?;
+                if !value.is_null() && !config_layers::has_path(&effective, &key) {
+                    anyhow::bail!("Unknown config field '{}' in --set", key);
+                }
+            }
+        }
+
+        // Bad compliance settings fail here rather than on the first order
+        crate::oms::pre_trade::PreTradeChain::from_config(&config.trading.pre_trade_checks)?;
 
         // Load API credentials from environment if not set
-        if let Ok(api_key) = std::env::var("COINDCX_API_KEY") {
+        let (key_var, secret_var) = match config.exchange.venue {
+            Venue::CoinDCX => ("COINDCX_API_KEY", "COINDCX_API_SECRET"),
+            Venue::Kraken => ("KRAKEN_API_KEY", "KRAKEN_API_SECRET"),
+            Venue::Coinbase => ("COINBASE_API_KEY", "COINBASE_API_SECRET"),
+            Venue::Bybit => ("BYBIT_API_KEY", "BYBIT_API_SECRET"),
+            Venue::Zerodha => ("ZERODHA_API_KEY", "ZERODHA_API_SECRET"),
+        };
+        if let Ok(api_key) = std::env::var(key_var) {
             config.exchange.api_key = Some(api_key);
         }
-        if let Ok(api_secret) = std::env::var("COINDCX_API_SECRET") {
+        if let Ok(api_secret) = std::env::var(secret_var) {
             config.exchange.api_secret = Some(api_secret);
         }
+        for account in &mut config.exchange.accounts {
+            let suffix = account.name.to_uppercase().replace('-', "_");
+            if account.api_key.is_none() {
+                account.api_key = std::env::var(format!("{}_{}", key_var, suffix)).ok();
+            }
+            if account.api_secret.is_none() {
+                account.api_secret = std::env::var(format!("{}_{}", secret_var, suffix)).ok();
+            }
+        }
+
+        Ok((config, resolved))
+    }
 
-        Ok(config)
+    /// Apply `--env` / `--set` to every config loaded by this process (first call wins)
+    pub fn set_cli_overrides(overrides: ConfigOverrides) {
+        let _ = CLI_OVERRIDES.set(overrides);
     }
 
     /// Get strategy name from strategy config
@@ -71,32 +137,703 @@ impl Config {
     }
 }
 
+/// Webhook endpoint for fill/close notifications
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// HMAC-SHA256 signing secret; the signature is sent in `X-Webhook-Signature`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Delivery attempts after the first failure before the event is dropped
+    #[serde(default = "default_webhook_max_retries")]
+    pub max_retries: u32,
+    /// Base retry delay in milliseconds (doubles per attempt, capped at 60s)
+    #[serde(default = "default_webhook_retry_backoff_ms")]
+    pub retry_backoff_ms: u64,
+    /// Per-request timeout in seconds
+    #[serde(default = "default_webhook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_webhook_max_retries() -> u32 {
+    5
+}
+
+fn default_webhook_retry_backoff_ms() -> u64 {
+    1_000
+}
+
+fn default_webhook_timeout_secs() -> u64 {
+    10
+}
+
+impl WebhookConfig {
+    pub fn new(url: impl Into<String>) -> Self {
+        WebhookConfig {
+            url: url.into(),
+            secret: None,
+            max_retries: default_webhook_max_retries(),
+            retry_backoff_ms: default_webhook_retry_backoff_ms(),
+            timeout_secs: default_webhook_timeout_secs(),
+        }
+    }
+}
+
+/// Kill switch: a `KILL` file (or env var) stops new entries in live mode
+///
+/// ```json
+/// "kill_switch": { "dir": "/var/run/bot", "flatten": true, "env_var": "CRYPTO_KILL" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KillSwitchConfig {
+    /// Directory watched for `KILL` (all strategies) and `KILL.<strategy>` files
+    #[serde(default = "default_kill_dir")]
+    pub dir: String,
+    /// Also close open positions when triggered (a file containing `flatten` forces this)
+    #[serde(default)]
+    pub flatten: bool,
+    /// Environment variable checked alongside the files
+    #[serde(default = "default_kill_env_var")]
+    pub env_var: String,
+}
+
+impl Default for KillSwitchConfig {
+    fn default() -> Self {
+        Self {
+            dir: default_kill_dir(),
+            flatten: false,
+            env_var: default_kill_env_var(),
+        }
+    }
+}
+
+/// Idle mode for low-power hosts (live mode)
+///
+/// ```json
+/// "idle_mode": { "min_timeframe": "1h", "active_secs": 300, "active_poll_secs": 30, "idle_poll_secs": 900 }
+/// ```
+///
+/// For the `active_secs` after each bar close the loop runs every
+/// `active_poll_secs` with all tasks. The rest of the bar is idle: cycles only
+/// manage fills, stops and the kill switch, at most every `idle_poll_secs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdleModeConfig {
+    /// Only strategies on this timeframe or higher go idle
+    #[serde(default = "default_idle_min_timeframe")]
+    pub min_timeframe: String,
+    #[serde(default = "default_idle_active_secs")]
+    pub active_secs: u64,
+    #[serde(default = "default_idle_active_poll_secs")]
+    pub active_poll_secs: u64,
+    #[serde(default = "default_idle_poll_secs")]
+    pub idle_poll_secs: u64,
+    /// Log CPU time and resident memory for every cycle
+    #[serde(default = "default_report_usage")]
+    pub report_usage: bool,
+}
+
+impl Default for IdleModeConfig {
+    fn default() -> Self {
+        Self {
+            min_timeframe: default_idle_min_timeframe(),
+            active_secs: default_idle_active_secs(),
+            active_poll_secs: default_idle_active_poll_secs(),
+            idle_poll_secs: default_idle_poll_secs(),
+            report_usage: true,
+        }
+    }
+}
+
+fn default_idle_min_timeframe() -> String {
+    "1h".to_string()
+}
+
+fn default_idle_active_secs() -> u64 {
+    300
+}
+
+fn default_idle_active_poll_secs() -> u64 {
+    30
+}
+
+fn default_idle_poll_secs() -> u64 {
+    900
+}
+
+fn default_report_usage() -> bool {
+    true
+}
+
+/// Acknowledging the preflight trade plan over HTTP (live mode)
+///
+/// ```json
+/// "preflight": { "listen": "127.0.0.1:8787", "timeout_secs": 900 }
+/// ```
+///
+/// Without `--confirm` the trader serves the plan at `GET /plan` and waits for
+/// `POST /confirm?plan=<id>` (or `POST /abort`) before its first cycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreflightConfig {
+    #[serde(default = "default_preflight_listen")]
+    pub listen: String,
+    /// Abort when nobody answers within this many seconds
+    #[serde(default = "default_preflight_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for PreflightConfig {
+    fn default() -> Self {
+        Self {
+            listen: default_preflight_listen(),
+            timeout_secs: default_preflight_timeout_secs(),
+        }
+    }
+}
+
+fn default_preflight_listen() -> String {
+    "127.0.0.1:8787".to_string()
+}
+
+fn default_preflight_timeout_secs() -> u64 {
+    900
+}
+
+fn default_kill_dir() -> String {
+    ".".to_string()
+}
+
+fn default_kill_env_var() -> String {
+    "CRYPTO_KILL".to_string()
+}
+
 /// Exchange configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExchangeConfig {
+    /// Exchange or broker the live loop trades on (`"broker"` is accepted too)
+    #[serde(default, alias = "broker")]
+    pub venue: Venue,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_secret: Option<String>,
     pub maker_fee: f64,
     pub taker_fee: f64,
+    /// Replace `maker_fee`/`taker_fee` with the account's fee tier read from the exchange
+    #[serde(default)]
+    pub fees_from_exchange: bool,
     pub assumed_slippage: f64,
     pub rate_limit: u32,
+    /// GST charged on exchange fees (e.g., 0.18 in India), added on top of maker/taker fees
+    #[serde(default)]
+    pub gst_rate: f64,
+    /// Bid/ask spread check before market orders in live mode (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spread_guard: Option<SpreadGuardConfig>,
+    /// Periodic trade reconciliation in live mode (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconcile: Option<ReconcileConfig>,
+    /// Periodic top-of-book snapshots for research (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orderbook_recorder: Option<OrderbookRecorderConfig>,
+    /// Handling of post-only limit orders that would cross the book
+    #[serde(default)]
+    pub post_only: PostOnlyConfig,
+    /// Hash-chained order/fill audit trail in live and paper mode (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit_trail: Option<AuditTrailConfig>,
+    /// Build the forming bar from the exchange's trade stream in live mode
+    /// instead of polling REST candles (REST remains the fallback)
+    #[serde(default)]
+    pub market_stream: bool,
+    /// Trade against the venue's testnet instead of production (venues without
+    /// one refuse to start)
+    #[serde(default)]
+    pub sandbox: bool,
+    /// Where the live trader gets candles (the venue's REST API by default;
+    /// `"source"` is accepted too)
+    #[serde(default, alias = "source")]
+    pub candle_source: CandleSource,
+    /// Route CoinDCX orders through margin instead of spot (spot when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub margin: Option<MarginConfig>,
+    /// Park idle cash in a CoinDCX earn product while flat (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub earn_sweep: Option<EarnSweepConfig>,
+    /// Track CoinDCX deposits and withdrawals as external cash flows (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cash_flows: Option<CashFlowConfig>,
+    /// Extra named accounts that strategies or symbols are routed to (see [`crate::accounts`])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<AccountConfig>,
+    /// Cancel all open orders when the live loop stops checking in (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dead_man_switch: Option<DeadManSwitchConfig>,
+    /// Proxy, extra root certificates and connect timeout of exchange clients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<HttpConfig>,
+    /// Kite instrument token of each Zerodha symbol, e.g. `{"NSE:INFY": 408065}`;
+    /// Kite serves candles by token only
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub instrument_tokens: HashMap<String, u64>,
+}
+
+/// Exchange for live and paper trading (`"venue"` in the exchange config)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Venue {
+    /// INR and USDT markets; credentials from `COINDCX_API_KEY` / `COINDCX_API_SECRET`
+    #[default]
+    CoinDCX,
+    /// USD and EUR markets; credentials from `KRAKEN_API_KEY` / `KRAKEN_API_SECRET`
+    Kraken,
+    /// USD and USDC markets (Advanced Trade); credentials from `COINBASE_API_KEY` /
+    /// `COINBASE_API_SECRET`
+    Coinbase,
+    /// USDT linear perpetuals; credentials from `BYBIT_API_KEY` / `BYBIT_API_SECRET`
+    Bybit,
+    /// NSE/BSE equities and ETFs via Kite; credentials from `ZERODHA_API_KEY` /
+    /// `ZERODHA_API_SECRET`, session from `ZERODHA_ACCESS_TOKEN`
+    Zerodha,
+}
+
+/// Candle feed of the live trader (`"candle_source"` in the exchange config)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CandleSource {
+    /// The venue's REST candles
+    #[default]
+    Exchange,
+    /// Closed bars from Binance's kline WebSocket, bootstrapped from Binance REST
+    Binance,
+}
+
+/// Which fee side a strategy's limit orders aim for (`"fee_mode"` in the strategy config)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeMode {
+    /// Orders as the strategy builds them; limits may take liquidity
+    #[default]
+    Any,
+    /// Limit orders are sent post-only and only ever pay the maker fee
+    Maker,
+}
+
+/// Post-only orders that would match on arrival
+///
+/// ```json
+/// "post_only": { "reprice": true, "offset_bps": 1.0 }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostOnlyConfig {
+    /// Move a crossing order just inside the book instead of dropping it
+    #[serde(default = "default_post_only_reprice")]
+    pub reprice: bool,
+    /// Distance behind the opposite best quote for a re-priced order, in basis points
+    #[serde(default = "default_post_only_offset_bps")]
+    pub offset_bps: f64,
+}
+
+impl Default for PostOnlyConfig {
+    fn default() -> Self {
+        Self {
+            reprice: default_post_only_reprice(),
+            offset_bps: default_post_only_offset_bps(),
+        }
+    }
+}
+
+fn default_post_only_reprice() -> bool {
+    true
+}
+
+fn default_post_only_offset_bps() -> f64 {
+    1.0
+}
+
+/// What to do with a market order when the spread is too wide
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpreadAction {
+    /// Drop entry orders (exits are still sent)
+    #[default]
+    Skip,
+    /// Replace with a limit order at mid that expires after `limit_ttl_secs`
+    LimitAtMid,
+}
+
+/// Max-spread guard for live market orders
+///
+/// ```json
+/// "spread_guard": { "max_spread_bps": 25, "action": "limit_at_mid", "limit_ttl_secs": 120 }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpreadGuardConfig {
+    /// Maximum (ask - bid) / mid, in basis points
+    pub max_spread_bps: f64,
+    #[serde(default)]
+    pub action: SpreadAction,
+    #[serde(default = "default_limit_ttl_secs")]
+    pub limit_ttl_secs: u64,
+}
+
+fn default_limit_ttl_secs() -> u64 {
+    60
+}
+
+/// Margin routing for CoinDCX orders
+///
+/// ```json
+/// "margin": { "leverage": 2.0 }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarginConfig {
+    #[serde(default = "default_margin_leverage")]
+    pub leverage: f64,
+}
+
+fn default_margin_leverage() -> f64 {
+    1.0
+}
+
+/// Sweep of idle cash into a CoinDCX earn product between trades (live mode)
+///
+/// ```json
+/// "earn_sweep": { "product_id": "inr-flexible", "reserve_pct": 0.2, "min_amount": 1000, "interval_mins": 60 }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarnSweepConfig {
+    /// Earn product to subscribe; should be flexible so entries can redeem at once
+    pub product_id: String,
+    /// Fraction of equity always left as cash
+    #[serde(default = "default_earn_reserve_pct")]
+    pub reserve_pct: f64,
+    /// Smallest amount worth subscribing
+    #[serde(default = "default_earn_min_amount")]
+    pub min_amount: f64,
+    #[serde(default = "default_earn_interval_mins")]
+    pub interval_mins: u64,
+}
+
+fn default_earn_reserve_pct() -> f64 {
+    0.2
+}
+
+fn default_earn_min_amount() -> f64 {
+    1000.0
+}
+
+fn default_earn_interval_mins() -> u64 {
+    60
+}
+
+/// Exchange credentials that some strategies or symbols trade on instead of the default ones
+///
+/// ```json
+/// "accounts": [
+///     { "name": "alts", "symbols": ["SOLINR", "XRPINR"], "capital": 20000 },
+///     { "name": "swing", "strategies": ["volatility_regime"] }
+/// ]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountConfig {
+    pub name: String,
+    /// Falls back to `<VENUE>_API_KEY_<NAME>` (e.g. `COINDCX_API_KEY_ALTS`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_secret: Option<String>,
+    /// Symbols traded on this account; these win over `strategies`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<String>,
+    /// Strategies whose symbols trade on this account
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strategies: Vec<String>,
+    /// Capital held in the account, for its balance; the default account gets the rest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capital: Option<f64>,
+}
+
+/// Network settings for corporate proxies and TLS-inspecting gateways
+///
+/// `root_certificates` are PEM files trusted on top of the system's roots.
+///
+/// ```json
+/// "http": { "proxy": "http://proxy.corp:3128", "root_certificates": ["certs/corp-ca.pem"], "connect_timeout_secs": 10 }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HttpConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub root_certificates: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_secs: Option<u64>,
+}
+
+/// Dead man's switch: cancel every open exchange order once the live loop has
+/// missed `missed_heartbeats` heartbeats
+///
+/// The loop beats once per cycle that reached the exchange. `heartbeat_secs`
+/// of 0 uses the polling interval. `heartbeat_secs` x `missed_heartbeats` must
+/// cover the polling interval, or the switch would fire between two cycles.
+///
+/// ```json
+/// "dead_man_switch": { "missed_heartbeats": 3, "heartbeat_secs": 0 }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadManSwitchConfig {
+    #[serde(default = "default_missed_heartbeats")]
+    pub missed_heartbeats: u32,
+    #[serde(default)]
+    pub heartbeat_secs: u64,
+}
+
+impl DeadManSwitchConfig {
+    /// Heartbeat period on a loop that runs every `poll_secs`
+    pub fn heartbeat_secs(&self, poll_secs: u64) -> u64 {
+        match self.heartbeat_secs {
+            0 => poll_secs,
+            secs => secs,
+        }
+    }
+
+    /// Fail when the switch would go off before the next cycle is due
+    pub fn validate(&self, poll_secs: u64) -> Result<()> {
+        let window = self.heartbeat_secs(poll_secs) * u64::from(self.missed_heartbeats);
+        if window < poll_secs {
+            anyhow::bail!(
+                "dead_man_switch fires after {}s ({} x {}s heartbeats) but the loop polls every \
+                 {}s; raise heartbeat_secs or missed_heartbeats",
+                window,
+                self.missed_heartbeats,
+                self.heartbeat_secs(poll_secs),
+                poll_secs
+            );
+        }
+        Ok(())
+    }
+}
+
+fn default_missed_heartbeats() -> u32 {
+    3
+}
+
+/// Live-mode polling of deposits and withdrawals, so manual top-ups move cash
+/// without showing up as P&L
+///
+/// ```json
+/// "cash_flows": { "currency": "INR", "interval_mins": 15 }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CashFlowConfig {
+    /// Currency the account trades in; transfers of other currencies are ignored
+    pub currency: String,
+    #[serde(default = "default_cash_flow_interval_mins")]
+    pub interval_mins: u64,
+}
+
+fn default_cash_flow_interval_mins() -> u64 {
+    15
+}
+
+/// Live-mode reconciliation of local fills against the exchange's trade history
+///
+/// ```json
+/// "reconcile": { "interval_mins": 60, "lookback_hours": 24, "output_dir": "results/reconciliation" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconcileConfig {
+    #[serde(default = "default_reconcile_interval_mins")]
+    pub interval_mins: u64,
+    /// Window of fills compared on each run
+    #[serde(default = "default_reconcile_lookback_hours")]
+    pub lookback_hours: u64,
+    /// Where accounting exports are written
+    #[serde(default = "default_reconcile_output_dir")]
+    pub output_dir: String,
+}
+
+impl Default for ReconcileConfig {
+    fn default() -> Self {
+        Self {
+            interval_mins: default_reconcile_interval_mins(),
+            lookback_hours: default_reconcile_lookback_hours(),
+            output_dir: default_reconcile_output_dir(),
+        }
+    }
+}
+
+fn default_reconcile_interval_mins() -> u64 {
+    60
+}
+
+fn default_reconcile_lookback_hours() -> u64 {
+    24
+}
+
+fn default_reconcile_output_dir() -> String {
+    "results/reconciliation".to_string()
+}
+
+/// Orderbook snapshot recording (live mode and `data record-books`)
+///
+/// ```json
+/// "orderbook_recorder": { "depth": 10, "interval_secs": 60, "output_dir": "data/orderbook" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderbookRecorderConfig {
+    /// Levels kept per side
+    #[serde(default = "default_recorder_depth")]
+    pub depth: usize,
+    #[serde(default = "default_recorder_interval_secs")]
+    pub interval_secs: u64,
+    /// Snapshots go to `<output_dir>/<SYMBOL>/<YYYY-MM-DD>.jsonl.sz`
+    #[serde(default = "default_recorder_output_dir")]
+    pub output_dir: String,
+    /// Symbols to record (empty = `trading.symbols`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub symbols: Vec<String>,
+}
+
+impl Default for OrderbookRecorderConfig {
+    fn default() -> Self {
+        Self {
+            depth: default_recorder_depth(),
+            interval_secs: default_recorder_interval_secs(),
+            output_dir: default_recorder_output_dir(),
+            symbols: Vec::new(),
+        }
+    }
+}
+
+fn default_recorder_depth() -> usize {
+    10
+}
+
+fn default_recorder_interval_secs() -> u64 {
+    60
+}
+
+fn default_recorder_output_dir() -> String {
+    "data/orderbook".to_string()
+}
+
+/// Line format of the audit trail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditFormat {
+    /// One JSON object per line with FIX-style field names
+    #[default]
+    Jsonl,
+    /// `tag=value` pairs separated by `|`, FIX tag numbers
+    Fix,
+}
+
+/// Order/fill audit trail for external risk systems
+///
+/// ```json
+/// "audit_trail": { "output_dir": "logs/audit", "format": "jsonl", "max_file_mb": 50 }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditTrailConfig {
+    /// One file set per session: `<output_dir>/<session>-<part>.jsonl` (or `.fix`)
+    #[serde(default = "default_audit_output_dir")]
+    pub output_dir: String,
+    #[serde(default)]
+    pub format: AuditFormat,
+    /// Start a new part once the current file reaches this size
+    #[serde(default = "default_audit_max_file_mb")]
+    pub max_file_mb: u64,
+}
+
+impl Default for AuditTrailConfig {
+    fn default() -> Self {
+        Self {
+            output_dir: default_audit_output_dir(),
+            format: AuditFormat::default(),
+            max_file_mb: default_audit_max_file_mb(),
+        }
+    }
+}
+
+fn default_audit_output_dir() -> String {
+    "logs/audit".to_string()
+}
+
+fn default_audit_max_file_mb() -> u64 {
+    50
 }
 
 impl Default for ExchangeConfig {
     fn default() -> Self {
         ExchangeConfig {
+            venue: Venue::default(),
             api_key: None,
             api_secret: None,
             maker_fee: 0.001, // 0.1%
             taker_fee: 0.001, // 0.1%
+            fees_from_exchange: false,
             assumed_slippage: 0.001,
             rate_limit: 10,
+            gst_rate: 0.0,
+            spread_guard: None,
+            reconcile: None,
+            orderbook_recorder: None,
+            post_only: PostOnlyConfig::default(),
+            audit_trail: None,
+            market_stream: false,
+            sandbox: false,
+            candle_source: CandleSource::default(),
+            margin: None,
+            earn_sweep: None,
+            cash_flows: None,
+            accounts: Vec::new(),
+            dead_man_switch: None,
+            http: None,
+            instrument_tokens: HashMap::new(),
         }
     }
 }
 
+impl ExchangeConfig {
+    /// Maker fee including GST
+    pub fn effective_maker_fee(&self) -> f64 {
+        self.maker_fee * (1.0 + self.gst_rate)
+    }
+
+    /// Taker fee including GST
+    pub fn effective_taker_fee(&self) -> f64 {
+        self.taker_fee * (1.0 + self.gst_rate)
+    }
+
+    /// Network settings of exchange clients, with certificates read from disk;
+    /// fails on a malformed proxy URL or certificate
+    #[cfg(feature = "native")]
+    pub fn network(&self) -> Result<crate::common::NetworkConfig> {
+        let Some(http) = &self.http else {
+            return Ok(Default::default());
+        };
+        let root_certificates = http
+            .root_certificates
+            .iter()
+            .map(|path| {
+                std::fs::read(path).with_context(|| format!("Failed to read certificate {}", path))
+            })
+            .collect::<Result<_>>()?;
+        let network = crate::common::NetworkConfig {
+            proxy: http.proxy.clone(),
+            root_certificates,
+            connect_timeout: http
+                .connect_timeout_secs
+                .map(std::time::Duration::from_secs),
+        };
+        network
+            .validate()
+            .context("Invalid exchange.http settings (proxy or root certificate)")?;
+        Ok(network)
+    }
+}
+
 /// Trading configuration
 ///
 /// # Currency Handling
@@ -117,7 +854,12 @@ impl Default for ExchangeConfig {
 /// Always verify your data source's actual currency denomination.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradingConfig {
+    /// Explicit symbol list (also the fallback when `universe` can't be resolved)
+    #[serde(default)]
     pub symbols: Vec<String>,
+    /// Named universe preset resolved at runtime from exchange volume (e.g., "inr_top10_liquid")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub universe: Option<String>,
     /// Initial trading capital in the same currency as your price data.
     /// No currency conversion is performed - ensure this matches your CSV data currency.
     pub initial_capital: f64,
@@ -132,6 +874,548 @@ pub struct TradingConfig {
     pub drawdown_critical_multiplier: f64,
     pub consecutive_loss_limit: usize,
     pub consecutive_loss_multiplier: f64,
+    /// Anti-martingale: consecutive wins required before size is scaled up (0 = disabled)
+    #[serde(default)]
+    pub win_streak_threshold: usize,
+    /// Size increase per win at or beyond the threshold (e.g., 0.10 = +10%)
+    #[serde(default = "default_win_streak_step")]
+    pub win_streak_step: f64,
+    /// Upper bound on the win-streak size multiplier
+    #[serde(default = "default_win_streak_max_multiplier")]
+    pub win_streak_max_multiplier: f64,
+    /// Symbol groups for exposure limits, e.g. `{"L1": ["BTCINR", "ETHINR"], "meme": ["DOGEINR"]}`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub symbol_groups: HashMap<String, Vec<String>>,
+    /// Max notional exposure per group as a fraction of capital, e.g. `{"L1": 0.5}`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub group_exposure_caps: HashMap<String, f64>,
+    /// Cap size so the 99% CVaR of a trade (from closed-trade returns) stays under
+    /// this fraction of equity, e.g. 0.01 = 1% (omit to disable)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_trade_cvar: Option<f64>,
+    /// Ratchet trailing stops from the bar's high (longs) / low (shorts) instead of its close
+    #[serde(default)]
+    pub intrabar_trailing: bool,
+    /// Which exit wins when one bar spans both the stop and the target
+    #[serde(default)]
+    pub same_bar_policy: SameBarPolicy,
+    /// Skip entries whose move to target doesn't clear round-trip costs (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_edge: Option<MinEdgeConfig>,
+    /// Skip signals on symbols whose market has gone quiet (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub activity_filter: Option<ActivityFilterConfig>,
+    /// Scale out of positions in R-multiple tranches (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_ladder: Option<ExitLadderConfig>,
+    /// Live mode: what to do with a symbol while its candle feed is failing or stale
+    #[serde(default)]
+    pub degraded_data: DegradedDataConfig,
+    /// Daily risk budget shared by every strategy trading the account (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_budget: Option<RiskBudgetConfig>,
+    /// Prop-firm style trailing max drawdown (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trailing_drawdown: Option<TrailingDrawdownConfig>,
+    /// Compliance checks every strategy order must pass, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_trade_checks: Vec<PreTradeCheckConfig>,
+    /// Interest on margin-borrowed assets held short (no charge when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub borrow: Option<BorrowConfig>,
+    /// Offset net long exposure with a beta-sized short on a liquid asset (disabled when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hedge: Option<HedgeConfig>,
+}
+
+/// One built-in pre-trade check (see `oms::pre_trade`)
+///
+/// ```json
+/// "pre_trade_checks": [
+///   { "type": "symbols", "deny": ["DOGEINR"] },
+///   { "type": "max_order_value", "max": 250000 },
+///   { "type": "restricted_hours", "windows": [{ "start": "23:30", "end": "00:30" }] },
+///   { "type": "price_band", "max_deviation_pct": 0.05 }
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PreTradeCheckConfig {
+    /// Only trade `allow` (when non-empty), never trade `deny`
+    Symbols {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        allow: Vec<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deny: Vec<String>,
+    },
+    /// Largest notional (quantity x price) a single order may carry
+    MaxOrderValue { max: f64 },
+    /// UTC windows during which no new exposure is taken
+    RestrictedHours { windows: Vec<TimeWindow> },
+    /// Limit/stop prices must lie within this fraction of the last close
+    PriceBand { max_deviation_pct: f64 },
+}
+
+/// Daily UTC window, `"HH:MM"` to `"HH:MM"`; wraps past midnight when `end` < `start`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    pub start: String,
+    pub end: String,
+}
+
+/// Trailing max drawdown measured the way prop firms do
+///
+/// ```json
+/// "trailing_drawdown": { "max_loss_pct": 0.06, "lock_at_initial": true, "warn_at": 0.8 }
+/// ```
+///
+/// The account may lose at most `max_loss_pct` x initial capital from its
+/// equity high-water mark, unrealized P&L included. Unlike `max_drawdown`
+/// (a fraction of the peak that lifts once equity recovers), a breach halts
+/// the account for good: positions are closed, no further entries are taken,
+/// and in live mode the halt survives restarts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrailingDrawdownConfig {
+    /// Allowed loss from the high-water mark, as a fraction of initial capital
+    pub max_loss_pct: f64,
+    /// Stop trailing once the floor reaches initial capital
+    #[serde(default = "default_trailing_lock")]
+    pub lock_at_initial: bool,
+    /// Alert once this fraction of the allowance is used
+    #[serde(default = "default_trailing_warn_at")]
+    pub warn_at: f64,
+    /// Close open positions on a breach (otherwise only entries stop)
+    #[serde(default = "default_trailing_flatten")]
+    pub flatten: bool,
+}
+
+fn default_trailing_lock() -> bool {
+    true
+}
+
+fn default_trailing_warn_at() -> f64 {
+    0.8
+}
+
+fn default_trailing_flatten() -> bool {
+    true
+}
+
+/// Borrow interest for spot shorts executed on margin
+///
+/// ```json
+/// "borrow": { "default_apr": 0.10, "assets": { "BTC": 0.05, "DOGE": 0.25 } }
+/// ```
+///
+/// A short sells borrowed coins, and the lender charges interest on them every
+/// hour the position is open. Interest accrues on the short's notional at the
+/// latest close and is paid from cash as it accrues. `assets` is keyed by base
+/// asset (`BTC`) or full symbol (`BTCINR`); others pay `default_apr`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BorrowConfig {
+    #[serde(default = "default_borrow_apr")]
+    pub default_apr: f64,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub assets: HashMap<String, f64>,
+}
+
+impl Default for BorrowConfig {
+    fn default() -> Self {
+        Self {
+            default_apr: default_borrow_apr(),
+            assets: HashMap::new(),
+        }
+    }
+}
+
+fn default_borrow_apr() -> f64 {
+    0.10
+}
+
+impl BorrowConfig {
+    /// Annual rate for borrowing `symbol`'s base asset
+    pub fn apr(&self, symbol: &str) -> f64 {
+        let base = crate::symbols::base(symbol);
+        self.assets
+            .get(symbol)
+            .or_else(|| self.assets.get(base))
+            .copied()
+            .unwrap_or(self.default_apr)
+    }
+
+    /// Interest on `notional` of `symbol` borrowed from `from` to `to`
+    pub fn interest(
+        &self,
+        symbol: &str,
+        notional: f64,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> f64 {
+        let hours = (to - from).num_seconds().max(0) as f64 / 3600.0;
+        notional.abs() * self.apr(symbol) * hours / (365.0 * 24.0)
+    }
+}
+
+/// Portfolio hedge on a correlated, liquid instrument
+///
+/// ```json
+/// "hedge": {
+///   "symbol": "BTCINR",
+///   "max_net_exposure": 0.6,
+///   "unwind_below": 0.4,
+///   "hedge_ratio": 0.5
+/// }
+/// ```
+///
+/// The hedge opens when net long exposure (longs minus shorts, as a fraction
+/// of equity) exceeds `max_net_exposure` while `symbol` closes below its
+/// `regime_ema`. It shorts `hedge_ratio` of the portfolio's beta-weighted
+/// notional (betas against `symbol` over `beta_lookback` bars), capped at
+/// `max_hedge_pct` of equity, resizes when the target drifts by more than
+/// `rebalance_band`, and unwinds once exposure drops below `unwind_below` or
+/// the regime recovers. The leg is margined like a perp: only its fees and
+/// P&L touch cash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HedgeConfig {
+    pub symbol: String,
+    #[serde(default = "default_hedge_max_net_exposure")]
+    pub max_net_exposure: f64,
+    #[serde(default = "default_hedge_unwind_below")]
+    pub unwind_below: f64,
+    #[serde(default = "default_hedge_ratio")]
+    pub hedge_ratio: f64,
+    #[serde(default = "default_hedge_max_pct")]
+    pub max_hedge_pct: f64,
+    #[serde(default = "default_hedge_beta_lookback")]
+    pub beta_lookback: usize,
+    #[serde(default = "default_hedge_regime_ema")]
+    pub regime_ema: usize,
+    #[serde(default = "default_hedge_rebalance_band")]
+    pub rebalance_band: f64,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        Self {
+            symbol: "BTCINR".to_string(),
+            max_net_exposure: default_hedge_max_net_exposure(),
+            unwind_below: default_hedge_unwind_below(),
+            hedge_ratio: default_hedge_ratio(),
+            max_hedge_pct: default_hedge_max_pct(),
+            beta_lookback: default_hedge_beta_lookback(),
+            regime_ema: default_hedge_regime_ema(),
+            rebalance_band: default_hedge_rebalance_band(),
+        }
+    }
+}
+
+fn default_hedge_max_net_exposure() -> f64 {
+    0.6
+}
+
+fn default_hedge_unwind_below() -> f64 {
+    0.4
+}
+
+fn default_hedge_ratio() -> f64 {
+    0.5
+}
+
+fn default_hedge_max_pct() -> f64 {
+    0.5
+}
+
+fn default_hedge_beta_lookback() -> usize {
+    60
+}
+
+fn default_hedge_regime_ema() -> usize {
+    50
+}
+
+fn default_hedge_rebalance_band() -> f64 {
+    0.25
+}
+
+/// Daily risk budget shared across strategies
+///
+/// ```json
+/// "risk_budget": {
+///   "global_pct": 0.06,
+///   "default_strategy_pct": 0.03,
+///   "strategies": { "regime_grid": 0.02 },
+///   "ledger": "state/risk_budget.db"
+/// }
+/// ```
+///
+/// Each entry asks for its initial risk (quantity x distance to stop). Grants
+/// are capped by the strategy's share and by the global share of capital,
+/// counted over the current UTC day. Live processes pointing at the same
+/// `ledger` file draw from one budget.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskBudgetConfig {
+    /// Risk all strategies together may take per day, as a fraction of capital
+    #[serde(default = "default_budget_global_pct")]
+    pub global_pct: f64,
+    /// Per-day share of a strategy not listed in `strategies`
+    #[serde(default = "default_budget_strategy_pct")]
+    pub default_strategy_pct: f64,
+    /// Strategy name -> per-day share of capital
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub strategies: HashMap<String, f64>,
+    /// SQLite file shared by live processes (in memory when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ledger: Option<String>,
+}
+
+impl Default for RiskBudgetConfig {
+    fn default() -> Self {
+        Self {
+            global_pct: default_budget_global_pct(),
+            default_strategy_pct: default_budget_strategy_pct(),
+            strategies: HashMap::new(),
+            ledger: None,
+        }
+    }
+}
+
+fn default_budget_global_pct() -> f64 {
+    0.06
+}
+
+fn default_budget_strategy_pct() -> f64 {
+    0.03
+}
+
+/// Minimum-edge gate on entry signals
+///
+/// ```json
+/// "min_edge": { "multiple": 2.0, "spread_bps": 10 }
+/// ```
+///
+/// The round trip costs two taker fees (with GST), slippage on both fills,
+/// one spread crossing and TDS on the sell leg. An entry is taken only when
+/// the distance to the strategy's target is at least `multiple` times that.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MinEdgeConfig {
+    #[serde(default = "default_min_edge_multiple")]
+    pub multiple: f64,
+    /// Expected bid/ask spread in basis points (live runs use the quoted spread when available)
+    #[serde(default)]
+    pub spread_bps: f64,
+}
+
+impl Default for MinEdgeConfig {
+    fn default() -> Self {
+        Self {
+            multiple: default_min_edge_multiple(),
+            spread_bps: 0.0,
+        }
+    }
+}
+
+fn default_min_edge_multiple() -> f64 {
+    2.0
+}
+
+impl MinEdgeConfig {
+    /// Round-trip cost as a fraction of entry notional, for a spread of `spread_bps`
+    pub fn round_trip_cost(
+        &self,
+        exchange: &ExchangeConfig,
+        tax: &TaxConfig,
+        spread_bps: f64,
+    ) -> f64 {
+        2.0 * exchange.effective_taker_fee()
+            + 2.0 * exchange.assumed_slippage
+            + spread_bps / 10_000.0
+            + tax.tds_rate
+    }
+}
+
+/// Activity floors below which a symbol's market counts as dead
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ActivityFloors {
+    /// Minimum ATR as a percent of the close (`0.5` = 0.5%)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_atr_pct: Option<f64>,
+    /// Minimum traded value (close × volume) over the 24 hours up to the bar
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_volume_24h: Option<f64>,
+}
+
+/// Pre-signal filter for dead or illiquid markets
+///
+/// ```json
+/// "activity_filter": {
+///   "min_atr_pct": 0.8,
+///   "min_volume_24h": 2000000,
+///   "symbols": { "BTCINR": { "min_atr_pct": 0.4 } }
+/// }
+/// ```
+///
+/// While a symbol's ATR% or 24h traded value is under its floor, the strategy
+/// is not asked for new orders on it. Open positions are still managed. Floors
+/// in `symbols` replace the defaults for that symbol, field by field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityFilterConfig {
+    #[serde(flatten)]
+    pub floors: ActivityFloors,
+    #[serde(default = "default_activity_atr_period")]
+    pub atr_period: usize,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub symbols: HashMap<String, ActivityFloors>,
+}
+
+impl Default for ActivityFilterConfig {
+    fn default() -> Self {
+        Self {
+            floors: ActivityFloors::default(),
+            atr_period: default_activity_atr_period(),
+            symbols: HashMap::new(),
+        }
+    }
+}
+
+fn default_activity_atr_period() -> usize {
+    14
+}
+
+impl ActivityFilterConfig {
+    /// Floors that apply to `symbol`
+    pub fn floors_for(&self, symbol: &str) -> ActivityFloors {
+        match self.symbols.get(symbol) {
+            Some(own) => ActivityFloors {
+                min_atr_pct: own.min_atr_pct.or(self.floors.min_atr_pct),
+                min_volume_24h: own.min_volume_24h.or(self.floors.min_volume_24h),
+            },
+            None => self.floors,
+        }
+    }
+}
+
+/// Partial take-profit ladder applied by the engine to every strategy
+///
+/// ```json
+/// "exit_ladder": {
+///   "rungs": [ { "r": 1.0, "fraction": 0.33 }, { "r": 2.0, "fraction": 0.33 } ],
+///   "breakeven_after_first": true
+/// }
+/// ```
+///
+/// R is the distance from entry to the initial stop. Each rung closes
+/// `fraction` of the original quantity once price moves `r` R in favour; the
+/// rest (the runner) rides the strategy's trailing stop. The strategy's own
+/// target still applies to the runner only with `keep_target`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExitLadderConfig {
+    pub rungs: Vec<LadderRung>,
+    /// Move the stop to entry once the first rung fills
+    #[serde(default)]
+    pub breakeven_after_first: bool,
+    /// Keep the strategy's take-profit on the runner
+    #[serde(default)]
+    pub keep_target: bool,
+}
+
+/// One tranche of an [`ExitLadderConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LadderRung {
+    /// Profit in multiples of initial risk
+    pub r: f64,
+    /// Fraction of the original quantity to close
+    pub fraction: f64,
+}
+
+/// Live handling of symbols whose candle data can't be refreshed
+///
+/// ```json
+/// "degraded_data": {
+///   "policy": "exits_only",
+///   "symbols": { "SOLINR": "flatten" },
+///   "flatten_after": 5
+/// }
+/// ```
+///
+/// A symbol is degraded once `after_failures` consecutive cycles failed to
+/// fetch its candles or left the latest bar more than two bars old. Degraded
+/// symbols never open positions; open ones are managed on the last known
+/// candles according to the policy, until a fetch succeeds again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DegradedDataConfig {
+    #[serde(default)]
+    pub policy: DegradedPolicy,
+    /// Per-symbol policy overrides
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub symbols: HashMap<String, DegradedPolicy>,
+    #[serde(default = "default_degraded_after_failures")]
+    pub after_failures: u32,
+    /// `hold`: multiply the stop's distance from entry by this factor
+    #[serde(default = "default_degraded_stop_widen")]
+    pub stop_widen: f64,
+    /// Failed cycles before the `flatten` policy closes the position
+    #[serde(default = "default_degraded_flatten_after")]
+    pub flatten_after: u32,
+}
+
+impl Default for DegradedDataConfig {
+    fn default() -> Self {
+        Self {
+            policy: DegradedPolicy::default(),
+            symbols: HashMap::new(),
+            after_failures: default_degraded_after_failures(),
+            stop_widen: default_degraded_stop_widen(),
+            flatten_after: default_degraded_flatten_after(),
+        }
+    }
+}
+
+fn default_degraded_after_failures() -> u32 {
+    1
+}
+
+fn default_degraded_stop_widen() -> f64 {
+    1.5
+}
+
+fn default_degraded_flatten_after() -> u32 {
+    3
+}
+
+/// What a degraded symbol's open position is allowed to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DegradedPolicy {
+    /// Keep the position with a wider stop so stale prices don't shake it out
+    Hold,
+    /// Keep managing stops and targets on the last known candles
+    #[default]
+    ExitsOnly,
+    /// Manage exits, then close at market after `flatten_after` failed cycles
+    Flatten,
+}
+
+/// Resolution of bars whose range contains both the stop and the target.
+///
+/// OHLC bars don't record which extreme came first, so the order has to be assumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SameBarPolicy {
+    /// Worst case: the stop is assumed to fill first
+    #[default]
+    StopFirst,
+    /// Best case: the target is assumed to fill first (optimistic, for comparison only)
+    TargetFirst,
+    /// Simulate the path O→H→L→C or O→L→H→C, whichever extreme is nearer the open first
+    Path,
+}
+
+fn default_win_streak_step() -> f64 {
+    0.10
+}
+
+fn default_win_streak_max_multiplier() -> f64 {
+    1.5
 }
 
 impl Default for TradingConfig {
@@ -144,6 +1428,7 @@ impl Default for TradingConfig {
                 "BNBINR".to_string(),
                 "XRPINR".to_string(),
             ],
+            universe: None,
             initial_capital: 100_000.0,
             risk_per_trade: 0.15,
             max_positions: 5,
@@ -156,6 +1441,23 @@ impl Default for TradingConfig {
             drawdown_critical_multiplier: 0.25,
             consecutive_loss_limit: 3,
             consecutive_loss_multiplier: 0.75,
+            win_streak_threshold: 0,
+            win_streak_step: default_win_streak_step(),
+            win_streak_max_multiplier: default_win_streak_max_multiplier(),
+            symbol_groups: HashMap::new(),
+            group_exposure_caps: HashMap::new(),
+            max_trade_cvar: None,
+            intrabar_trailing: false,
+            same_bar_policy: SameBarPolicy::default(),
+            min_edge: None,
+            activity_filter: None,
+            exit_ladder: None,
+            degraded_data: DegradedDataConfig::default(),
+            risk_budget: None,
+            trailing_drawdown: None,
+            pre_trade_checks: Vec::new(),
+            borrow: None,
+            hedge: None,
         }
     }
 }
@@ -197,6 +1499,46 @@ pub struct BacktestConfig {
     /// Default is false (intra-candle execution for realistic algo trading)
     #[serde(default)]
     pub use_t1_execution: bool,
+    /// Warm/cold retention policy for files under `data_dir`
+    #[serde(default)]
+    pub data_retention: DataRetentionConfig,
+    /// Also report performance in a home currency (e.g. INR for USDT pairs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_currency: Option<HomeCurrencyConfig>,
+    /// Optimizer only: abandon a combo once its equity curve crosses these bounds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub early_stop: Option<EarlyStopConfig>,
+    /// Bars of its own history a symbol listed after the start of the run
+    /// needs before it may take entries
+    #[serde(default = "default_listing_warmup_bars")]
+    pub listing_warmup_bars: usize,
+    /// Synthetic shocks replayed in the backtest report (`[]` to skip)
+    #[serde(default = "default_stress_scenarios")]
+    pub stress_scenarios: Vec<StressScenario>,
+}
+
+fn default_listing_warmup_bars() -> usize {
+    50
+}
+
+fn default_stress_scenarios() -> Vec<StressScenario> {
+    vec![
+        StressScenario::new("Crash", Shock::Gap { pct: -0.30 }),
+        StressScenario::new(
+            "Volatility spike",
+            Shock::Volatility {
+                multiplier: 3.0,
+                days: 3.0,
+            },
+        ),
+        StressScenario::new(
+            "Liquidity drought",
+            Shock::Liquidity {
+                slippage_multiplier: 2.0,
+                days: 7.0,
+            },
+        ),
+    ]
 }
 
 impl Default for BacktestConfig {
@@ -206,6 +1548,119 @@ impl Default for BacktestConfig {
             results_dir: "results".to_string(),
             commission: 0.001,
             use_t1_execution: false, // Default to realistic intra-candle
+            data_retention: DataRetentionConfig::default(),
+            home_currency: None,
+            early_stop: None,
+            listing_warmup_bars: default_listing_warmup_bars(),
+            stress_scenarios: default_stress_scenarios(),
         }
     }
 }
+
+/// Synthetic shock injected into a copy of the backtest data (see [`crate::stress`])
+///
+/// ```json
+/// "stress_scenarios": [
+///   { "name": "Crash", "shock": "gap", "pct": -0.3 },
+///   { "name": "Volatility spike", "shock": "volatility", "multiplier": 3.0, "days": 3 },
+///   { "name": "Liquidity drought", "shock": "liquidity", "slippage_multiplier": 2.0, "days": 7,
+///     "at": "2025-03-01" }
+/// ]
+/// ```
+///
+/// Without `at`, the shock lands on the bar after the baseline run had the
+/// most capital deployed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StressScenario {
+    pub name: String,
+    #[serde(flatten)]
+    pub shock: Shock,
+    /// Shock start (`YYYY-MM-DD` or `YYYY-MM-DD HH:MM:SS`, UTC)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<String>,
+}
+
+impl StressScenario {
+    pub fn new(name: impl Into<String>, shock: Shock) -> Self {
+        Self {
+            name: name.into(),
+            shock,
+            at: None,
+        }
+    }
+}
+
+/// Shape of a [`StressScenario`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shock", rename_all = "snake_case")]
+pub enum Shock {
+    /// Every price from the shock bar on moves by `pct` (e.g. -0.3), opening with a gap
+    Gap { pct: f64 },
+    /// Bar-to-bar moves and ranges scaled by `multiplier` for `days`
+    Volatility { multiplier: f64, days: f64 },
+    /// Slippage scaled by `slippage_multiplier` for `days`
+    Liquidity { slippage_multiplier: f64, days: f64 },
+}
+
+/// Early termination of optimizer runs whose equity curve is clearly hopeless.
+///
+/// ```json
+/// "early_stop": { "max_drawdown": 0.35, "equity_floor": 0.7 }
+/// ```
+///
+/// Either bound may be omitted. Truncated runs are reported and ranked below
+/// complete ones, and are never written back as the best config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EarlyStopConfig {
+    /// Stop once drawdown from the running peak exceeds this fraction (e.g., 0.35 = 35%)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_drawdown: Option<f64>,
+    /// Stop once equity falls below this fraction of initial capital (e.g., 0.7)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equity_floor: Option<f64>,
+}
+
+/// Home-currency view for strategies quoted in another currency.
+///
+/// ```json
+/// "home_currency": { "currency": "INR", "fx_symbol": "USDTINR" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HomeCurrencyConfig {
+    /// Currency label used in reports (e.g. "INR")
+    pub currency: String,
+    /// Rate series in `data_dir` giving home currency per unit of the trading currency
+    pub fx_symbol: String,
+}
+
+/// Retention policy for downloaded market data, keyed by timeframe.
+///
+/// Timeframes without an entry are kept forever, so the default policy
+/// never touches any data.
+///
+/// ```json
+/// "data_retention": {
+///     "max_age_days": { "1m": 90, "5m": 365 },
+///     "compact_after_days": { "1m": 30, "5m": 90, "15m": 180 },
+///     "auto_prune": false
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DataRetentionConfig {
+    /// Delete candles older than this many days (warm and cold tiers)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub max_age_days: HashMap<String, u32>,
+    /// Move candles older than this many days from CSV into the parquet cold tier
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub compact_after_days: HashMap<String, u32>,
+    /// Apply the policy automatically before backtests and optimization runs
+    #[serde(default)]
+    pub auto_prune: bool,
+}
+
+impl DataRetentionConfig {
+    /// True when the policy would never prune or compact anything
+    pub fn is_noop(&self) -> bool {
+        self.max_age_days.is_empty() && self.compact_after_days.is_empty()
+    }
+}
@@ -0,0 +1,357 @@
+//! Layered configuration
+//!
+//! A config file can inherit from others and be adjusted per environment
+//! without copying the whole file:
+//!
+//! ```json
+//! { "extends": "base.json", "trading": { "initial_capital": 50000 } }
+//! ```
+//!
+//! Layers are deep-merged in this order, later layers winning:
+//!
+//! 1. `extends` targets (a path or list of paths, relative to the file; may chain)
+//! 2. The config file itself
+//! 3. The environment overlay `<stem>.<env>.json` next to it (`--env paper`)
+//! 4. `--set trading.risk_per_trade=0.01` overrides (value parsed as JSON, else string)
+//!
+//! Objects merge key by key; arrays and scalars are replaced whole. Every leaf
+//! of the result remembers which layer set it, see [`ResolvedConfig::provenance`].
+
+use anyhow::{bail, Context, Result};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Key naming the parent config(s)
+pub const EXTENDS_KEY: &str = "extends";
+
+/// Provenance label for `--set` overrides
+pub const CLI_SOURCE: &str = "--set";
+
+/// Layers applied on top of a config file
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    /// Environment overlay name (`<stem>.<env>.json`)
+    pub env: Option<String>,
+    /// `path.to.field=value` assignments
+    pub set: Vec<String>,
+}
+
+impl ConfigOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.env.is_none() && self.set.is_empty()
+    }
+}
+
+/// Merged config JSON plus where each field came from
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    pub value: Value,
+    /// Dotted leaf path -> source (file path or `--set`)
+    pub provenance: BTreeMap<String, String>,
+    /// Layers applied, first to last
+    pub sources: Vec<String>,
+    /// Source label of the file that was asked for
+    pub entry: String,
+}
+
+impl ResolvedConfig {
+    /// More than the requested file contributed
+    pub fn is_layered(&self) -> bool {
+        self.sources.len() > 1
+    }
+
+    /// Fields not set by the requested file itself
+    pub fn inherited(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.provenance
+            .iter()
+            .filter(move |(_, source)| **source != self.entry)
+            .map(|(path, source)| (path.as_str(), source.as_str()))
+    }
+}
+
+/// Resolve `path` with its parents, environment overlay and `--set` overrides
+pub fn resolve(path: impl AsRef<Path>, overrides: &ConfigOverrides) -> Result<ResolvedConfig> {
+    let path = path.as_ref();
+    let mut resolved = ResolvedConfig {
+        value: Value::Object(Map::new()),
+        provenance: BTreeMap::new(),
+        sources: Vec::new(),
+        entry: path.display().to_string(),
+    };
+    apply_file(path, &mut resolved, &mut Vec::new())?;
+
+    if let Some(env) = &overrides.env {
+        let overlay = overlay_path(path, env);
+        if !overlay.exists() {
+            bail!(
+                "No '{}' overlay for {} (expected {})",
+                env,
+                path.display(),
+                overlay.display()
+            );
+        }
+        apply_file(&overlay, &mut resolved, &mut Vec::new())?;
+    }
+
+    if !overrides.set.is_empty() {
+        let mut layer = Value::Object(Map::new());
+        for assignment in &overrides.set {
+            let (key, value) = parse_assignment(assignment)?;
+            insert_path(&mut layer, &key, value)?;
+        }
+        merge(
+            &mut resolved.value,
+            layer,
+            CLI_SOURCE,
+            "",
+            &mut resolved.provenance,
+        );
+        resolved.sources.push(CLI_SOURCE.to_string());
+    }
+
+    Ok(resolved)
+}
+
+/// `configs/live.json` + `paper` -> `configs/live.paper.json`
+pub fn overlay_path(path: &Path, env: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_else(|| "json".to_string());
+    path.with_file_name(format!("{}.{}.{}", stem, env, ext))
+}
+
+fn apply_file(path: &Path, resolved: &mut ResolvedConfig, chain: &mut Vec<PathBuf>) -> Result<()> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if chain.contains(&canonical) {
+        bail!("Config inheritance cycle at {}", path.display());
+    }
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    let mut value: Value = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse config JSON: {}", path.display()))?;
+    let Some(obj) = value.as_object_mut() else {
+        bail!("Config {} is not a JSON object", path.display());
+    };
+
+    let parents = match obj.remove(EXTENDS_KEY) {
+        None => Vec::new(),
+        Some(Value::String(p)) => vec![p],
+        Some(Value::Array(items)) => items
+            .into_iter()
+            .map(|v| match v {
+                Value::String(p) => Ok(p),
+                other => bail!("'extends' entries must be paths, got {}", other),
+            })
+            .collect::<Result<_>>()?,
+        Some(other) => bail!("'extends' must be a path or list of paths, got {}", other),
+    };
+
+    chain.push(canonical);
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    for parent in parents {
+        apply_file(&dir.join(parent), resolved, chain)?;
+    }
+    chain.pop();
+
+    let source = path.display().to_string();
+    merge(
+        &mut resolved.value,
+        value,
+        &source,
+        "",
+        &mut resolved.provenance,
+    );
+    resolved.sources.push(source);
+    Ok(())
+}
+
+/// Deep-merge `layer` into `base`, recording `source` for every leaf it sets
+fn merge(
+    base: &mut Value,
+    layer: Value,
+    source: &str,
+    prefix: &str,
+    provenance: &mut BTreeMap<String, String>,
+) {
+    match (base, layer) {
+        (Value::Object(base), Value::Object(layer)) => {
+            for (key, value) in layer {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                match base.get_mut(&key) {
+                    Some(existing) if existing.is_object() && value.is_object() => {
+                        merge(existing, value, source, &path, provenance);
+                    }
+                    _ => {
+                        // Whole subtree replaced: forget what the earlier layer set below it
+                        let nested = format!("{}.", path);
+                        provenance.retain(|k, _| *k != path && !k.starts_with(&nested));
+                        record_leaves(&value, &path, source, provenance);
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, layer) => {
+            record_leaves(&layer, prefix, source, provenance);
+            *base = layer;
+        }
+    }
+}
+
+fn record_leaves(
+    value: &Value,
+    path: &str,
+    source: &str,
+    provenance: &mut BTreeMap<String, String>,
+) {
+    match value {
+        Value::Object(obj) if !obj.is_empty() => {
+            for (key, child) in obj {
+                record_leaves(child, &format!("{}.{}", path, key), source, provenance);
+            }
+        }
+        _ => {
+            provenance.insert(path.to_string(), source.to_string());
+        }
+    }
+}
+
+/// `a.b=1` -> ("a.b", 1); values that aren't valid JSON are taken as strings
+pub fn parse_assignment(assignment: &str) -> Result<(String, Value)> {
+    let Some((key, raw)) = assignment.split_once('=') else {
+        bail!("--set expects path=value, got '{}'", assignment);
+    };
+    let key = key.trim();
+    if key.is_empty() || key.split('.').any(str::is_empty) {
+        bail!("Invalid config path '{}' in --set", key);
+    }
+    let value = serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+    Ok((key.to_string(), value))
+}
+
+fn insert_path(root: &mut Value, key: &str, value: Value) -> Result<()> {
+    let mut node = root;
+    let mut parts = key.split('.').peekable();
+    while let Some(part) = parts.next() {
+        let Some(obj) = node.as_object_mut() else {
+            bail!(
+                "--set {}: conflicts with another --set on a parent path",
+                key
+            );
+        };
+        if parts.peek().is_none() {
+            obj.insert(part.to_string(), value);
+            return Ok(());
+        }
+        node = obj
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    Ok(())
+}
+
+/// Whether a dotted path exists in `value`
+pub fn has_path(value: &Value, key: &str) -> bool {
+    key.split('.')
+        .try_fold(value, |node, part| node.get(part))
+        .is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("config_layers_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_extends_overlay_and_set() {
+        let dir = temp_dir("layers");
+        fs::write(
+            dir.join("base.json"),
+            json!({
+                "trading": { "initial_capital": 100000, "risk_per_trade": 0.02, "symbols": ["BTCINR", "ETHINR"] },
+                "strategy": { "name": "volatility_regime", "timeframe": "1d" }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("live.json"),
+            json!({ "extends": "base.json", "trading": { "symbols": ["BTCINR"] } }).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("live.paper.json"),
+            json!({ "trading": { "initial_capital": 5000 } }).to_string(),
+        )
+        .unwrap();
+
+        let overrides = ConfigOverrides {
+            env: Some("paper".to_string()),
+            set: vec![
+                "trading.risk_per_trade=0.01".to_string(),
+                "strategy.timeframe=4h".to_string(),
+            ],
+        };
+        let resolved = resolve(dir.join("live.json"), &overrides).unwrap();
+        let v = &resolved.value;
+        assert_eq!(v["trading"]["initial_capital"], json!(5000));
+        assert_eq!(v["trading"]["risk_per_trade"], json!(0.01));
+        assert_eq!(v["trading"]["symbols"], json!(["BTCINR"]));
+        assert_eq!(v["strategy"]["name"], json!("volatility_regime"));
+        assert_eq!(v["strategy"]["timeframe"], json!("4h"));
+        assert!(v.get(EXTENDS_KEY).is_none());
+
+        let source = |k: &str| resolved.provenance[k].clone();
+        assert!(source("strategy.name").ends_with("base.json"));
+        assert!(source("trading.symbols").ends_with("live.json"));
+        assert!(source("trading.initial_capital").ends_with("live.paper.json"));
+        assert_eq!(source("trading.risk_per_trade"), CLI_SOURCE);
+        assert_eq!(resolved.sources.len(), 4);
+        assert!(resolved.inherited().all(|(k, _)| k != "trading.symbols"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cycles_and_bad_assignments_are_rejected() {
+        let dir = temp_dir("cycle");
+        fs::write(
+            dir.join("a.json"),
+            json!({ "extends": "b.json" }).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("b.json"),
+            json!({ "extends": "a.json" }).to_string(),
+        )
+        .unwrap();
+        let err = resolve(dir.join("a.json"), &ConfigOverrides::default()).unwrap_err();
+        assert!(err.to_string().contains("cycle"), "{}", err);
+
+        assert!(parse_assignment("trading.risk_per_trade").is_err());
+        assert!(parse_assignment("trading..x=1").is_err());
+        assert_eq!(
+            parse_assignment("exchange.name=coindcx").unwrap().1,
+            json!("coindcx")
+        );
+        assert_eq!(parse_assignment("a.b=[1,2]").unwrap().1, json!([1, 2]));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
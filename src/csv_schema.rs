@@ -0,0 +1,464 @@
+//! Tolerant OHLCV CSV parsing
+//!
+//! Candle files don't only come from our own downloader: exchange exports,
+//! Binance's bulk kline dumps and TradingView chart exports all carry the same
+//! data in slightly different shapes. [`read_candles`] copes with that drift:
+//!
+//! - the layout is inferred from the header (any column order, extra columns
+//!   ignored, missing volume read as zero); files without a header are read as
+//!   `time,open,high,low,close,volume,...` like Binance kline dumps
+//! - `,`, `;` and tab delimiters are detected from the first line
+//! - timestamps may be epoch seconds, milliseconds, microseconds or
+//!   nanoseconds, or ISO-8601 text; offsets are normalized to UTC and text
+//!   without an offset is taken as UTC
+//! - rows that can't be parsed or fail candle validation are quarantined with
+//!   their reason instead of failing the whole file
+//! - rows are sorted by time and duplicate timestamps keep the last row
+//!
+//! The [`CsvLoadReport`] says what was dropped and why.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use std::fmt;
+use std::io::Read;
+
+use crate::Candle;
+
+/// Column positions of the OHLCV fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvSchema {
+    pub time: usize,
+    pub open: usize,
+    pub high: usize,
+    pub low: usize,
+    pub close: usize,
+    /// Missing volume columns load as zero volume
+    pub volume: Option<usize>,
+    /// First row holds column names
+    pub has_header: bool,
+}
+
+const TIME_NAMES: &[&str] = &[
+    "datetime",
+    "date_time",
+    "timestamp",
+    "time",
+    "date",
+    "open_time",
+    "opentime",
+    "open time",
+    "unix",
+    "t",
+];
+const OPEN_NAMES: &[&str] = &["open", "o", "open_price"];
+const HIGH_NAMES: &[&str] = &["high", "h", "high_price"];
+const LOW_NAMES: &[&str] = &["low", "l", "low_price"];
+const CLOSE_NAMES: &[&str] = &["close", "c", "close_price"];
+const VOLUME_NAMES: &[&str] = &["volume", "vol", "v", "base_volume", "volume_base"];
+
+impl CsvSchema {
+    /// The downloader's own `datetime,open,high,low,close,volume` layout
+    pub const DEFAULT: CsvSchema = CsvSchema {
+        time: 0,
+        open: 1,
+        high: 2,
+        low: 3,
+        close: 4,
+        volume: Some(5),
+        has_header: true,
+    };
+
+    /// Infer the layout from the first row of a file
+    pub fn infer(first_row: &csv::StringRecord) -> Result<Self> {
+        // A first row that starts with a timestamp is data, not a header
+        if first_row
+            .get(0)
+            .is_some_and(|field| parse_timestamp(field).is_ok())
+        {
+            if first_row.len() < 5 {
+                bail!(
+                    "Headerless file has {} columns, need at least time,open,high,low,close",
+                    first_row.len()
+                );
+            }
+            return Ok(Self {
+                volume: (first_row.len() > 5).then_some(5),
+                has_header: false,
+                ..Self::DEFAULT
+            });
+        }
+
+        let names: Vec<String> = first_row.iter().map(normalize_name).collect();
+        let find = |aliases: &[&str]| {
+            // Earlier aliases win, so "datetime" beats a later "date" column
+            aliases
+                .iter()
+                .find_map(|alias| names.iter().position(|n| n == alias))
+        };
+        let required = |aliases: &[&str], what: &str| {
+            find(aliases)
+                .with_context(|| format!("No {} column in header {:?}", what, first_row.as_slice()))
+        };
+
+        Ok(Self {
+            time: required(TIME_NAMES, "time")?,
+            open: required(OPEN_NAMES, "open")?,
+            high: required(HIGH_NAMES, "high")?,
+            low: required(LOW_NAMES, "low")?,
+            close: required(CLOSE_NAMES, "close")?,
+            volume: find(VOLUME_NAMES),
+            has_header: true,
+        })
+    }
+
+    /// Candle from one data row; `Err` holds the quarantine reason
+    pub fn parse_row(&self, record: &csv::StringRecord) -> std::result::Result<Candle, String> {
+        let field = |idx: usize, what: &str| {
+            record
+                .get(idx)
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| format!("missing {}", what))
+        };
+        let number = |idx: usize, what: &str| {
+            let raw = field(idx, what)?;
+            raw.parse::<f64>()
+                .map_err(|_| format!("invalid {} {:?}", what, raw))
+        };
+
+        let datetime = parse_timestamp(field(self.time, "time")?)?;
+        let volume = match self.volume {
+            Some(idx) => number(idx, "volume")?,
+            None => 0.0,
+        };
+        Candle::new(
+            datetime,
+            number(self.open, "open")?,
+            number(self.high, "high")?,
+            number(self.low, "low")?,
+            number(self.close, "close")?,
+            volume,
+        )
+        .map_err(|e| e.to_string())
+    }
+}
+
+/// Lowercase, trimmed header name without quotes, BOM or unit suffixes
+fn normalize_name(name: &str) -> String {
+    let name = name
+        .trim_start_matches('\u{feff}')
+        .trim()
+        .trim_matches('"')
+        .to_lowercase();
+    // "Volume (BTC)" / "volume[base]" -> "volume"
+    match name.find(['(', '[']) {
+        Some(idx) => name[..idx].trim().to_string(),
+        None => name,
+    }
+}
+
+/// Parse an epoch number or ISO-8601 text timestamp, normalized to UTC
+///
+/// Epoch units are told apart by magnitude (seconds up to ~5138 AD, then
+/// milliseconds, microseconds, nanoseconds).
+pub fn parse_timestamp(raw: &str) -> std::result::Result<DateTime<Utc>, String> {
+    let raw = raw.trim().trim_matches('"');
+    if let Ok(epoch) = raw.parse::<f64>() {
+        if !epoch.is_finite() || epoch < 0.0 {
+            return Err(format!("invalid timestamp {:?}", raw));
+        }
+        let nanos = if epoch < 1e11 {
+            epoch * 1e9
+        } else if epoch < 1e14 {
+            epoch * 1e6
+        } else if epoch < 1e17 {
+            epoch * 1e3
+        } else {
+            epoch
+        };
+        return Ok(DateTime::from_timestamp_nanos(nanos.round() as i64));
+    }
+
+    if let Ok(dt) = raw.parse::<DateTime<FixedOffset>>() {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S%.f %z", "%Y-%m-%d %H:%M:%S%.f%z"] {
+        if let Ok(dt) = DateTime::parse_from_str(raw, format) {
+            return Ok(dt.with_timezone(&Utc));
+        }
+    }
+    for format in [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+        "%Y/%m/%d %H:%M:%S",
+        "%Y/%m/%d %H:%M",
+    ] {
+        if let Ok(ndt) = NaiveDateTime::parse_from_str(raw, format) {
+            return Ok(ndt.and_utc());
+        }
+    }
+    for format in ["%Y-%m-%d", "%Y/%m/%d"] {
+        if let Ok(date) = NaiveDate::parse_from_str(raw, format) {
+            return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+        }
+    }
+    Err(format!("unrecognized timestamp {:?}", raw))
+}
+
+/// A row set aside instead of loaded
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantinedRow {
+    /// 1-based line number in the file
+    pub line: usize,
+    pub raw: String,
+    pub reason: String,
+}
+
+/// What a tolerant load kept and dropped
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvLoadReport {
+    pub schema: CsvSchema,
+    pub delimiter: u8,
+    pub rows: usize,
+    pub loaded: usize,
+    pub quarantined: Vec<QuarantinedRow>,
+    /// Rows dropped because a later row had the same timestamp
+    pub duplicates: usize,
+    /// Rows were out of time order and have been sorted
+    pub reordered: bool,
+}
+
+impl CsvLoadReport {
+    /// Nothing was dropped or reordered
+    pub fn is_clean(&self) -> bool {
+        self.quarantined.is_empty() && self.duplicates == 0 && !self.reordered
+    }
+
+    /// Quarantined rows as CSV (`line,reason,raw`)
+    pub fn quarantine_csv(&self) -> String {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        let _ = writer.write_record(["line", "reason", "raw"]);
+        for row in &self.quarantined {
+            let _ = writer.write_record([row.line.to_string().as_str(), &row.reason, &row.raw]);
+        }
+        String::from_utf8(writer.into_inner().unwrap_or_default()).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for CsvLoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "loaded {}/{} rows, {} quarantined, {} duplicate(s)",
+            self.loaded,
+            self.rows,
+            self.quarantined.len(),
+            self.duplicates
+        )?;
+        if self.reordered {
+            write!(f, ", re-sorted by time")?;
+        }
+        if let Some(first) = self.quarantined.first() {
+            write!(f, " (first bad row: line {}: {})", first.line, first.reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Delimiter used most in the first line
+fn sniff_delimiter(text: &str) -> u8 {
+    let first_line = text.lines().next().unwrap_or_default();
+    [b',', b';', b'\t']
+        .into_iter()
+        .max_by_key(|&d| first_line.bytes().filter(|&b| b == d).count())
+        .unwrap_or(b',')
+}
+
+/// Parse candles from any OHLCV CSV variant
+///
+/// Fails only when the layout can't be inferred or no row at all is usable.
+pub fn read_candles<R: Read>(mut input: R) -> Result<(Vec<Candle>, CsvLoadReport)> {
+    let mut text = String::new();
+    input
+        .read_to_string(&mut text)
+        .context("Failed to read CSV")?;
+    let delimiter = sniff_delimiter(&text);
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .delimiter(delimiter)
+        .from_reader(text.as_bytes());
+    let mut records = reader.records();
+
+    let Some(first) = records.next() else {
+        bail!("CSV is empty");
+    };
+    let first = first.context("Failed to read first row")?;
+    let schema = CsvSchema::infer(&first)?;
+
+    let mut rows: Vec<(usize, csv::StringRecord)> = Vec::new();
+    if !schema.has_header {
+        rows.push((1, first));
+    }
+    let mut quarantined = Vec::new();
+    for (idx, record) in records.enumerate() {
+        let line = idx + 2;
+        match record {
+            Ok(record) if record.iter().all(str::is_empty) => {}
+            Ok(record) => rows.push((line, record)),
+            Err(e) => quarantined.push(QuarantinedRow {
+                line,
+                raw: String::new(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    let total = rows.len() + quarantined.len();
+    let mut candles = Vec::with_capacity(rows.len());
+    for (line, record) in rows {
+        match schema.parse_row(&record) {
+            Ok(candle) => candles.push(candle),
+            Err(reason) => quarantined.push(QuarantinedRow {
+                line,
+                raw: record
+                    .iter()
+                    .collect::<Vec<_>>()
+                    .join(&(delimiter as char).to_string()),
+                reason,
+            }),
+        }
+    }
+    quarantined.sort_by_key(|row| row.line);
+
+    if candles.is_empty() && total > 0 {
+        bail!(
+            "No usable rows out of {} (line {}: {})",
+            total,
+            quarantined[0].line,
+            quarantined[0].reason
+        );
+    }
+
+    let reordered = candles.windows(2).any(|w| w[0].datetime > w[1].datetime);
+    // Stable sort keeps file order within a timestamp, so the last row wins
+    candles.sort_by_key(|c| c.datetime);
+    let before = candles.len();
+    let mut deduped: Vec<Candle> = Vec::with_capacity(before);
+    for candle in candles {
+        match deduped.last_mut() {
+            Some(last) if last.datetime == candle.datetime => *last = candle,
+            _ => deduped.push(candle),
+        }
+    }
+
+    let report = CsvLoadReport {
+        schema,
+        delimiter,
+        rows: total,
+        loaded: deduped.len(),
+        quarantined,
+        duplicates: before - deduped.len(),
+        reordered,
+    };
+    Ok((deduped, report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, h, 0, 0).unwrap()
+    }
+
+    type Row = (DateTime<Utc>, f64, f64, f64, f64, f64);
+
+    fn rows(candles: &[Candle]) -> Vec<Row> {
+        candles
+            .iter()
+            .map(|c| (c.datetime, c.open, c.high, c.low, c.close, c.volume))
+            .collect()
+    }
+
+    fn expected() -> Vec<Row> {
+        vec![
+            (at(0), 100.0, 110.0, 95.0, 105.0, 12.5),
+            (at(1), 105.0, 112.0, 101.0, 108.0, 7.0),
+        ]
+    }
+
+    #[test]
+    fn test_round_trips_known_export_formats() {
+        // Our downloader / CoinDCX export
+        let coindcx = "datetime,open,high,low,close,volume\n\
+                       2024-01-01 00:00:00,100,110,95,105,12.5\n\
+                       2024-01-01 01:00:00,105,112,101,108,7\n";
+        // Binance kline dump: no header, millisecond open times, extra columns
+        let binance = "1704067200000,100,110,95,105,12.5,1704070799999,1250,10,6,600,0\n\
+                       1704070800000,105,112,101,108,7,1704074399999,756,8,3,320,0\n";
+        // Binance's newer dumps use microseconds
+        let binance_us = "1704067200000000,100,110,95,105,12.5,1704070799999999,1250,10,6,600,0\n\
+                          1704070800000000,105,112,101,108,7,1704074399999999,756,8,3,320,0\n";
+        // TradingView: epoch seconds, indicator columns after the prices
+        let tradingview = "time,open,high,low,close,Volume,Volume MA\n\
+                           1704067200,100,110,95,105,12.5,\n\
+                           1704070800,105,112,101,108,7,9.75\n";
+        // TradingView with ISO times in the chart's timezone
+        let tradingview_iso = "time,open,high,low,close,Volume\n\
+                               2024-01-01T05:30:00+05:30,100,110,95,105,12.5\n\
+                               2024-01-01T06:30:00+05:30,105,112,101,108,7\n";
+        // Reordered columns, semicolons, a unit suffix and a BOM
+        let shuffled = "\u{feff}Close;Volume (BTC);Open;Low;High;Timestamp\n\
+                        105;12.5;100;95;110;2024-01-01T00:00:00Z\n\
+                        108;7;105;101;112;2024-01-01T01:00:00Z\n";
+
+        for (name, text) in [
+            ("coindcx", coindcx),
+            ("binance", binance),
+            ("binance_us", binance_us),
+            ("tradingview", tradingview),
+            ("tradingview_iso", tradingview_iso),
+            ("shuffled", shuffled),
+        ] {
+            let (candles, report) = read_candles(text.as_bytes()).unwrap();
+            assert_eq!(rows(&candles), expected(), "{}", name);
+            assert!(report.is_clean(), "{}: {}", name, report);
+        }
+    }
+
+    #[test]
+    fn test_bad_rows_are_quarantined() {
+        let text = "timestamp,open,high,low,close\n\
+                    2024-01-01 01:00:00,105,112,101,108\n\
+                    not-a-date,1,2,0.5,1.5\n\
+                    2024-01-01 00:00:00,100,110,95,105\n\
+                    2024-01-01 02:00:00,100,90,95,105\n\
+                    2024-01-01 01:00:00,106,112,101,109\n\
+                    2024-01-01 03:00:00,abc,110,95,105\n";
+        let (candles, report) = read_candles(text.as_bytes()).unwrap();
+
+        assert_eq!(report.schema.volume, None);
+        assert_eq!(report.rows, 6);
+        assert_eq!(report.loaded, 2);
+        assert_eq!(report.duplicates, 1);
+        assert!(report.reordered);
+        let lines: Vec<usize> = report.quarantined.iter().map(|r| r.line).collect();
+        assert_eq!(lines, [3, 5, 7]);
+        assert!(report.quarantined[0].reason.contains("timestamp"));
+        assert!(report.quarantine_csv().lines().count() == 4);
+
+        // Sorted, and the later duplicate replaced the earlier one
+        assert_eq!(candles[0].datetime, at(0));
+        assert_eq!(candles[1].close, 109.0);
+        assert_eq!(candles[1].volume, 0.0);
+
+        assert!(read_candles("date,price\n2024-01-01,1\n".as_bytes()).is_err());
+        assert!(read_candles("datetime,open,high,low,close\nx,1,1,1,1\n".as_bytes()).is_err());
+    }
+}
@@ -1,7 +1,8 @@
 //! Data loading and management
 //!
 //! Handles loading OHLCV data from CSV files and live data fetching from exchange APIs.
-//! Supports both Binance (default) and CoinDCX data sources.
+//! Supports Binance (default), CoinDCX, Kraken, Coinbase and Bybit (USDT perpetuals) data
+//! sources.
 //! Similar to Python's data_fetcher.py and download_binance_data.py
 
 use anyhow::{Context, Result};
@@ -13,7 +14,13 @@ use std::path::{Path, PathBuf};
 use tracing::{info, warn};
 
 use crate::binance::{self, BinanceClient};
+use crate::bybit::{self, BybitClient};
+use crate::coinbase::{self, CoinbaseClient};
 use crate::coindcx::{self, CoinDCXClient};
+use crate::kraken::{self, KrakenClient};
+use crate::okx;
+use crate::symbols;
+use crate::zerodha;
 use crate::{Candle, CandleValidationError, Symbol};
 
 // =============================================================================
@@ -61,6 +68,9 @@ pub enum DataSource {
     #[default]
     Binance,
     CoinDCX,
+    Kraken,
+    Coinbase,
+    Bybit,
 }
 
 impl std::str::FromStr for DataSource {
@@ -70,8 +80,11 @@ impl std::str::FromStr for DataSource {
         match s.to_lowercase().as_str() {
             "binance" => Ok(DataSource::Binance),
             "coindcx" => Ok(DataSource::CoinDCX),
+            "kraken" => Ok(DataSource::Kraken),
+            "coinbase" => Ok(DataSource::Coinbase),
+            "bybit" => Ok(DataSource::Bybit),
             _ => Err(format!(
-                "Unknown data source: {}. Use 'binance' or 'coindcx'",
+                "Unknown data source: {}. Use 'binance', 'coindcx', 'kraken', 'coinbase' or 'bybit'",
                 s
             )),
         }
@@ -83,6 +96,9 @@ impl std::fmt::Display for DataSource {
         match self {
             DataSource::Binance => write!(f, "binance"),
             DataSource::CoinDCX => write!(f, "coindcx"),
+            DataSource::Kraken => write!(f, "kraken"),
+            DataSource::Coinbase => write!(f, "coinbase"),
+            DataSource::Bybit => write!(f, "bybit"),
         }
     }
 }
@@ -123,81 +139,182 @@ impl TryFrom<binance::BinanceKline> for Candle {
     }
 }
 
+/// Convert from Kraken OHLC bar to internal Candle type with validation
+impl TryFrom<kraken::Ohlc> for Candle {
+    type Error = CandleValidationError;
+
+    fn try_from(o: kraken::Ohlc) -> Result<Self, Self::Error> {
+        Candle::new(
+            DateTime::from_timestamp(o.time, 0).unwrap_or_else(Utc::now),
+            o.open,
+            o.high,
+            o.low,
+            o.close,
+            o.volume,
+        )
+    }
+}
+
+/// Convert from Coinbase candle to internal Candle type with validation
+impl TryFrom<coinbase::CoinbaseCandle> for Candle {
+    type Error = CandleValidationError;
+
+    fn try_from(c: coinbase::CoinbaseCandle) -> Result<Self, Self::Error> {
+        Candle::new(
+            DateTime::from_timestamp(c.start, 0).unwrap_or_else(Utc::now),
+            c.open,
+            c.high,
+            c.low,
+            c.close,
+            c.volume,
+        )
+    }
+}
+
+/// Convert from OKX candle to internal Candle type with validation
+impl TryFrom<okx::OkxCandle> for Candle {
+    type Error = CandleValidationError;
+
+    fn try_from(c: okx::OkxCandle) -> Result<Self, Self::Error> {
+        Candle::new(
+            DateTime::from_timestamp_millis(c.ts).unwrap_or_else(Utc::now),
+            c.open,
+            c.high,
+            c.low,
+            c.close,
+            c.volume,
+        )
+    }
+}
+
+/// Convert from Bybit kline to internal Candle type with validation
+impl TryFrom<bybit::Kline> for Candle {
+    type Error = CandleValidationError;
+
+    fn try_from(k: bybit::Kline) -> Result<Self, Self::Error> {
+        Candle::new(
+            DateTime::from_timestamp_millis(k.start_time).unwrap_or_else(Utc::now),
+            k.open,
+            k.high,
+            k.low,
+            k.close,
+            k.volume,
+        )
+    }
+}
+
+/// Convert from Zerodha historical candle to internal Candle type with validation
+impl TryFrom<zerodha::Candle> for Candle {
+    type Error = CandleValidationError;
+
+    fn try_from(c: zerodha::Candle) -> Result<Self, Self::Error> {
+        Candle::new(c.datetime, c.open, c.high, c.low, c.close, c.volume)
+    }
+}
+
 // =============================================================================
 // CSV Data Loading
 // =============================================================================
 
 /// Load OHLCV data from CSV file with validation
+///
+/// Parsing is tolerant of format drift (see [`crate::csv_schema`]). Rows that
+/// can't be loaded are written to `<file>.quarantine` next to the file.
 pub fn load_csv(path: impl AsRef<Path>) -> Result<Vec<Candle>> {
     let path = path.as_ref();
-    let mut reader = csv::Reader::from_path(path).context("Failed to open CSV file")?;
-
-    let mut candles = Vec::new();
-    let mut invalid_count = 0;
-
-    for (row_idx, result) in reader.records().enumerate() {
-        let record = result.context(format!("Failed to read row {}", row_idx + 1))?;
-
-        let dt_str = record.get(0).context("Missing datetime column")?;
-        let datetime = dt_str
-            .parse::<DateTime<Utc>>()
-            .or_else(|_| {
-                // Try parsing without timezone and assume UTC
-                chrono::NaiveDateTime::parse_from_str(dt_str, "%Y-%m-%d %H:%M:%S")
-                    .map(|ndt| DateTime::<Utc>::from_naive_utc_and_offset(ndt, Utc))
-            })
-            .context(format!("Failed to parse datetime: {}", dt_str))?;
-
-        let open: f64 = record
-            .get(1)
-            .context("Missing open column")?
-            .parse()
-            .context("Failed to parse open")?;
-        let high: f64 = record
-            .get(2)
-            .context("Missing high column")?
-            .parse()
-            .context("Failed to parse high")?;
-        let low: f64 = record
-            .get(3)
-            .context("Missing low column")?
-            .parse()
-            .context("Failed to parse low")?;
-        let close: f64 = record
-            .get(4)
-            .context("Missing close column")?
-            .parse()
-            .context("Failed to parse close")?;
-        let volume: f64 = record
-            .get(5)
-            .context("Missing volume column")?
-            .parse()
-            .context("Failed to parse volume")?;
-
-        match Candle::new(datetime, open, high, low, close, volume) {
-            Ok(candle) => candles.push(candle),
-            Err(e) => {
-                invalid_count += 1;
-                warn!(
-                    "Skipping invalid candle at row {} in {:?}: {}",
-                    row_idx + 2, // +2 for 1-indexed and header row
-                    path.file_name().unwrap_or_default(),
-                    e
-                );
-            }
+    let file = File::open(path).context("Failed to open CSV file")?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let (candles, report) = read_csv_report(file, &name)?;
+
+    if !report.quarantined.is_empty() {
+        let mut quarantine = path.as_os_str().to_owned();
+        quarantine.push(".quarantine");
+        let quarantine = PathBuf::from(quarantine);
+        match std::fs::write(&quarantine, report.quarantine_csv()) {
+            Ok(()) => warn!(
+                "Quarantined rows of {:?} written to {}",
+                name,
+                quarantine.display()
+            ),
+            Err(e) => warn!("Failed to write {}: {}", quarantine.display(), e),
         }
     }
+    Ok(candles)
+}
+
+/// Parse candles from any reader; `name` labels warnings
+pub fn read_csv<R: std::io::Read>(input: R, name: &str) -> Result<Vec<Candle>> {
+    Ok(read_csv_report(input, name)?.0)
+}
 
-    if invalid_count > 0 {
+/// [`read_csv`] plus the load report; anything dropped is logged
+pub fn read_csv_report<R: std::io::Read>(
+    input: R,
+    name: &str,
+) -> Result<(Vec<Candle>, crate::csv_schema::CsvLoadReport)> {
+    let (candles, report) = crate::csv_schema::read_candles(input)
+        .with_context(|| format!("Failed to parse {:?}", name))?;
+    for row in &report.quarantined {
         warn!(
-            "Skipped {} invalid candles out of {} in {:?}",
-            invalid_count,
-            invalid_count + candles.len(),
-            path.file_name().unwrap_or_default()
+            "Skipping invalid candle at line {} in {:?}: {}",
+            row.line, name, row.reason
         );
     }
+    if !report.is_clean() {
+        warn!("{:?}: {}", name, report);
+    }
+    Ok((candles, report))
+}
 
-    Ok(candles)
+/// Load a symbol/timeframe from both data tiers (cold parquet + warm CSV).
+///
+/// Returns `Ok(None)` when neither tier has a file. Overlapping bars are
+/// resolved in favour of the CSV. Split/redenomination adjustments from
+/// `<data_dir>/adjustments.json` are applied (see [`crate::adjustments`]).
+pub fn load_tiered(
+    data_dir: impl AsRef<Path>,
+    symbol: &str,
+    timeframe: &str,
+) -> Result<Option<Vec<Candle>>> {
+    let Some(mut candles) = load_tiered_raw(&data_dir, symbol, timeframe)? else {
+        return Ok(None);
+    };
+    let adjustments = crate::adjustments::load_adjustments(&data_dir)?;
+    crate::adjustments::adjust_and_check(&mut candles, symbol, timeframe, &adjustments);
+    Ok(Some(candles))
+}
+
+/// [`load_tiered`] without adjustments
+pub(crate) fn load_tiered_raw(
+    data_dir: impl AsRef<Path>,
+    symbol: &str,
+    timeframe: &str,
+) -> Result<Option<Vec<Candle>>> {
+    let data_dir = data_dir.as_ref();
+    let csv_path = data_dir.join(format!("{}_{}.csv", symbol, timeframe));
+    let cold_path = crate::retention::cold_path(data_dir, symbol, timeframe);
+
+    let mut candles = if cold_path.exists() {
+        crate::retention::read_parquet(&cold_path)?
+    } else {
+        Vec::new()
+    };
+    let has_cold = !candles.is_empty() || cold_path.exists();
+
+    if csv_path.exists() {
+        let warm = load_csv(&csv_path)?;
+        if candles.is_empty() {
+            return Ok(Some(warm));
+        }
+        if let Some(first_warm) = warm.first().map(|c| c.datetime) {
+            candles.retain(|c| c.datetime < first_warm);
+        }
+        candles.extend(warm);
+    } else if !has_cold {
+        return Ok(None);
+    }
+
+    Ok(Some(candles))
 }
 
 /// Filter candles by date range
@@ -263,12 +380,12 @@ pub fn load_multi_symbol_with_range(
         let filename = format!("{}_{}.csv", symbol.as_str(), timeframe);
         let path = data_dir.as_ref().join(&filename);
 
-        if !path.exists() {
+        let Some(candles) = load_tiered(&data_dir, symbol.as_str(), timeframe)
+            .context(format!("Failed to load data for {}", symbol))?
+        else {
             warn!("Data file not found: {}", path.display());
             continue;
-        }
-
-        let candles = load_csv(&path).context(format!("Failed to load data for {}", symbol))?;
+        };
         let original_len = candles.len();
 
         // Apply date filtering
@@ -347,18 +464,16 @@ pub fn load_multi_timeframe(
                 let filename = format!("{}_{}.csv", symbol.as_str(), timeframe);
                 let path = data_path.join(&filename);
 
-                if !path.exists() {
-                    warn!(
-                        "Data file not found: {} (symbol: {}, timeframe: {})",
-                        path.display(),
-                        symbol,
-                        timeframe
-                    );
-                    continue;
-                }
-
-                match load_csv(&path) {
-                    Ok(candles) => {
+                match load_tiered(&data_path, symbol.as_str(), timeframe) {
+                    Ok(None) => {
+                        warn!(
+                            "Data file not found: {} (symbol: {}, timeframe: {})",
+                            path.display(),
+                            symbol,
+                            timeframe
+                        );
+                    }
+                    Ok(Some(candles)) => {
                         let original_len = candles.len();
                         let candles = filter_candles_by_date(candles, start, end);
 
@@ -440,16 +555,21 @@ pub fn check_data_coverage(
 
     for symbol in symbols {
         for timeframe in timeframes {
-            let filename = format!("{}_{}.csv", symbol.as_str(), timeframe);
-            let path = data_dir.join(&filename);
-
-            if !path.exists() {
-                missing_files.push((symbol.clone(), timeframe.clone()));
-                continue;
-            }
+            // Compacted history in the cold tier counts as coverage
+            let range = match load_tiered_raw(data_dir, symbol.as_str(), timeframe) {
+                Ok(None) => {
+                    missing_files.push((symbol.clone(), timeframe.clone()));
+                    continue;
+                }
+                Ok(Some(candles)) => candles
+                    .first()
+                    .zip(candles.last())
+                    .map(|(first, last)| (first.datetime, last.datetime)),
+                Err(_) => None,
+            };
 
             // Check date range
-            if let Ok(Some((data_start, data_end))) = get_data_date_range(&path) {
+            if let Some((data_start, data_end)) = range {
                 if let Some(req_start) = start {
                     // Only flag as needing earlier data if there's a significant gap (>7 days)
                     // This prevents repeated attempts when Binance simply doesn't have older data
@@ -483,8 +603,9 @@ pub fn find_missing_data(
         for timeframe in timeframes {
             let filename = format!("{}_{}.csv", symbol.as_str(), timeframe);
             let path = data_dir.join(&filename);
+            let cold = crate::retention::cold_path(data_dir, symbol.as_str(), timeframe);
 
-            if !path.exists() {
+            if !path.exists() && !cold.exists() {
                 missing.push((symbol.clone(), timeframe.clone()));
             }
         }
@@ -667,10 +788,8 @@ pub async fn ensure_data_for_range(
                     }
 
                     // Merge: new candles + existing candles, deduplicate
-                    let mut all_candles: Vec<Candle> = new_candles
-                        .into_iter()
-                        .chain(existing_candles.into_iter())
-                        .collect();
+                    let mut all_candles: Vec<Candle> =
+                        new_candles.into_iter().chain(existing_candles).collect();
 
                     // Sort by datetime and deduplicate
                     all_candles.sort_by_key(|c| c.datetime);
@@ -770,10 +889,8 @@ pub async fn ensure_data_for_range(
                     }
 
                     // Merge and deduplicate
-                    let mut all_candles: Vec<Candle> = existing_candles
-                        .into_iter()
-                        .chain(new_candles.into_iter())
-                        .collect();
+                    let mut all_candles: Vec<Candle> =
+                        existing_candles.into_iter().chain(new_candles).collect();
 
                     all_candles.sort_by_key(|c| c.datetime);
                     all_candles.dedup_by_key(|c| c.datetime);
@@ -983,11 +1100,12 @@ impl CoinDCXDataFetcher {
     }
 
     /// Convert symbol to CoinDCX pair format: BTCINR -> I-BTC_INR
+    ///
+    /// Bare bases (`BTC`) are taken as INR markets.
     pub fn to_pair(symbol: &str) -> String {
-        if let Some(base) = symbol.strip_suffix("INR") {
-            format!("I-{}_INR", base)
-        } else {
-            format!("I-{}_INR", symbol)
+        match symbols::quote(symbol) {
+            Some(_) => symbols::coindcx_pair(symbol),
+            None => symbols::coindcx_pair(&format!("{}INR", symbol)),
         }
     }
 
@@ -1026,7 +1144,7 @@ impl CoinDCXDataFetcher {
 
     /// Get list of available markets
     pub async fn list_markets(&self) -> Result<Vec<coindcx::types::MarketDetails>> {
-        self.client.get_markets_details().await
+        Ok(self.client.get_markets_details().await?)
     }
 
     /// Get list of available INR trading pairs
@@ -1128,10 +1246,9 @@ impl CoinDCXDataFetcher {
             anyhow::bail!("No data fetched for {}", symbol);
         }
 
-        let symbol_name = if symbol.ends_with("INR") {
-            symbol.to_string()
-        } else {
-            format!("{}INR", symbol)
+        let symbol_name = match symbols::quote(symbol) {
+            Some(_) => symbol.to_string(),
+            None => format!("{}INR", symbol),
         };
 
         let filename = format!("{}_{}.csv", symbol_name, interval);
@@ -1285,12 +1402,7 @@ impl BinanceDataFetcher {
         }
 
         // Extract base symbol and add INR suffix for filename compatibility
-        let base = symbol
-            .trim()
-            .to_uppercase()
-            .replace("INR", "")
-            .replace("USDT", "");
-        let symbol_name = format!("{}INR", base);
+        let symbol_name = format!("{}INR", symbols::binance_base(symbol));
 
         let filename = format!("{}_{}.csv", symbol_name, interval);
         self.save_to_csv(&candles, &filename)
@@ -1313,6 +1425,324 @@ impl BinanceDataFetcher {
     }
 }
 
+// =============================================================================
+// Kraken Data Fetcher (USD/EUR pairs, no API key required)
+// =============================================================================
+
+/// Fetch historical OHLCV data from Kraken's public OHLC endpoint
+///
+/// Kraken only serves the latest 720 bars of each interval, so long
+/// histories need a coarser timeframe. Files keep the symbol as given
+/// (e.g., `BTCEUR_1h.csv`).
+pub struct KrakenDataFetcher {
+    client: KrakenClient,
+    pub data_dir: PathBuf,
+}
+
+impl KrakenDataFetcher {
+    /// Create a new Kraken data fetcher
+    pub fn new(data_dir: impl AsRef<Path>) -> Self {
+        let data_dir = data_dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&data_dir).ok();
+
+        KrakenDataFetcher {
+            client: KrakenClient::new("", ""),
+            data_dir,
+        }
+    }
+
+    /// Fetch up to `days_back` days of candles, oldest first
+    pub async fn fetch_full_history(
+        &self,
+        symbol: &str,
+        interval: &str,
+        days_back: u32,
+    ) -> Result<Vec<Candle>> {
+        let start_time = Utc::now() - Duration::days(days_back as i64);
+        let bars = self
+            .client
+            .get_ohlc(symbol, interval, Some(start_time.timestamp()))
+            .await?;
+
+        let total = bars.len();
+        let mut candles: Vec<Candle> = bars
+            .into_iter()
+            .filter_map(|bar| match Candle::try_from(bar) {
+                Ok(candle) => Some(candle),
+                Err(e) => {
+                    warn!("Skipping invalid candle for {}: {}", symbol, e);
+                    None
+                }
+            })
+            .filter(|c| c.datetime >= start_time)
+            .collect();
+        candles.sort_by_key(|c| c.datetime);
+        candles.dedup_by_key(|c| c.datetime);
+
+        if let Some(first) = candles.first() {
+            if first.datetime > start_time + Duration::days(1) {
+                warn!(
+                    "Kraken returned {} {} bars from {} only; use a coarser timeframe for {} days",
+                    total,
+                    interval,
+                    first.datetime.format("%Y-%m-%d %H:%M"),
+                    days_back
+                );
+            }
+        }
+
+        Ok(candles)
+    }
+
+    /// Download historical data for a symbol and save to CSV
+    pub async fn download_pair(
+        &self,
+        symbol: &str,
+        interval: &str,
+        days_back: u32,
+    ) -> Result<PathBuf> {
+        let candles = self.fetch_full_history(symbol, interval, days_back).await?;
+
+        if candles.is_empty() {
+            anyhow::bail!("No data fetched for {}", symbol);
+        }
+
+        let symbol_name = symbol.trim().to_uppercase().replace('/', "");
+        let filename = format!("{}_{}.csv", symbol_name, interval);
+        self.save_to_csv(&candles, &filename)
+    }
+
+    /// Save candles to CSV file
+    pub fn save_to_csv(&self, candles: &[Candle], filename: &str) -> Result<PathBuf> {
+        let filepath = self.data_dir.join(filename);
+        let mut file = File::create(&filepath).context("Failed to create output file")?;
+
+        writeln!(file, "datetime,open,high,low,close,volume")?;
+
+        for candle in candles {
+            writeln!(
+                file,
+                "{},{},{},{},{},{}",
+                candle.datetime.format("%Y-%m-%d %H:%M:%S"),
+                candle.open,
+                candle.high,
+                candle.low,
+                candle.close,
+                candle.volume
+            )?;
+        }
+
+        info!("Saved {} rows to {}", candles.len(), filepath.display());
+        Ok(filepath)
+    }
+}
+
+// =============================================================================
+// Coinbase Data Fetcher (USD/USDC pairs, no API key required)
+// =============================================================================
+
+/// Fetch historical OHLCV data from Coinbase's public candles endpoint
+///
+/// Files keep the symbol as given without separators (e.g., `BTCUSD_1h.csv`).
+pub struct CoinbaseDataFetcher {
+    client: CoinbaseClient,
+    pub data_dir: PathBuf,
+}
+
+impl CoinbaseDataFetcher {
+    /// Create a new Coinbase data fetcher
+    pub fn new(data_dir: impl AsRef<Path>) -> Self {
+        let data_dir = data_dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&data_dir).ok();
+
+        CoinbaseDataFetcher {
+            client: CoinbaseClient::new("", ""),
+            data_dir,
+        }
+    }
+
+    /// Fetch `days_back` days of candles, oldest first
+    pub async fn fetch_full_history(
+        &self,
+        symbol: &str,
+        interval: &str,
+        days_back: u32,
+    ) -> Result<Vec<Candle>> {
+        let bars = self
+            .client
+            .fetch_full_history(symbol, interval, days_back)
+            .await?;
+
+        let total = bars.len();
+        let candles: Vec<Candle> = bars
+            .into_iter()
+            .filter_map(|bar| match Candle::try_from(bar) {
+                Ok(candle) => Some(candle),
+                Err(e) => {
+                    warn!("Skipping invalid candle for {}: {}", symbol, e);
+                    None
+                }
+            })
+            .collect();
+
+        if candles.len() < total {
+            warn!(
+                "Skipped {} invalid candles out of {} for {}",
+                total - candles.len(),
+                total,
+                symbol
+            );
+        }
+
+        Ok(candles)
+    }
+
+    /// Download historical data for a symbol and save to CSV
+    pub async fn download_pair(
+        &self,
+        symbol: &str,
+        interval: &str,
+        days_back: u32,
+    ) -> Result<PathBuf> {
+        let candles = self.fetch_full_history(symbol, interval, days_back).await?;
+
+        if candles.is_empty() {
+            anyhow::bail!("No data fetched for {}", symbol);
+        }
+
+        let symbol_name = symbol.trim().to_uppercase().replace(['/', '-'], "");
+        let filename = format!("{}_{}.csv", symbol_name, interval);
+        self.save_to_csv(&candles, &filename)
+    }
+
+    /// Save candles to CSV file
+    pub fn save_to_csv(&self, candles: &[Candle], filename: &str) -> Result<PathBuf> {
+        let filepath = self.data_dir.join(filename);
+        let mut file = File::create(&filepath).context("Failed to create output file")?;
+
+        writeln!(file, "datetime,open,high,low,close,volume")?;
+
+        for candle in candles {
+            writeln!(
+                file,
+                "{},{},{},{},{},{}",
+                candle.datetime.format("%Y-%m-%d %H:%M:%S"),
+                candle.open,
+                candle.high,
+                candle.low,
+                candle.close,
+                candle.volume
+            )?;
+        }
+
+        info!("Saved {} rows to {}", candles.len(), filepath.display());
+        Ok(filepath)
+    }
+}
+
+// =============================================================================
+// Bybit Data Fetcher (USDT perpetuals, no API key required)
+// =============================================================================
+
+/// Fetch historical OHLCV data for Bybit USDT linear perpetuals
+///
+/// Files are named after the contract (e.g., `BTCUSDT_1h.csv`), so perp
+/// data sits next to spot data without overwriting it.
+pub struct BybitDataFetcher {
+    client: BybitClient,
+    pub data_dir: PathBuf,
+}
+
+impl BybitDataFetcher {
+    /// Create a new Bybit data fetcher
+    pub fn new(data_dir: impl AsRef<Path>) -> Self {
+        let data_dir = data_dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&data_dir).ok();
+
+        BybitDataFetcher {
+            client: BybitClient::new("", ""),
+            data_dir,
+        }
+    }
+
+    /// Fetch `days_back` days of candles, oldest first
+    pub async fn fetch_full_history(
+        &self,
+        symbol: &str,
+        interval: &str,
+        days_back: u32,
+    ) -> Result<Vec<Candle>> {
+        let klines = self
+            .client
+            .fetch_full_history(symbol, interval, days_back)
+            .await?;
+
+        let total = klines.len();
+        let candles: Vec<Candle> = klines
+            .into_iter()
+            .filter_map(|kline| match Candle::try_from(kline) {
+                Ok(candle) => Some(candle),
+                Err(e) => {
+                    warn!("Skipping invalid candle for {}: {}", symbol, e);
+                    None
+                }
+            })
+            .collect();
+
+        if candles.len() < total {
+            warn!(
+                "Skipped {} invalid candles out of {} for {}",
+                total - candles.len(),
+                total,
+                symbol
+            );
+        }
+
+        Ok(candles)
+    }
+
+    /// Download historical data for a symbol and save to CSV
+    pub async fn download_pair(
+        &self,
+        symbol: &str,
+        interval: &str,
+        days_back: u32,
+    ) -> Result<PathBuf> {
+        let candles = self.fetch_full_history(symbol, interval, days_back).await?;
+
+        if candles.is_empty() {
+            anyhow::bail!("No data fetched for {}", symbol);
+        }
+
+        let filename = format!("{}_{}.csv", bybit::symbol_to_contract(symbol), interval);
+        self.save_to_csv(&candles, &filename)
+    }
+
+    /// Save candles to CSV file
+    pub fn save_to_csv(&self, candles: &[Candle], filename: &str) -> Result<PathBuf> {
+        let filepath = self.data_dir.join(filename);
+        let mut file = File::create(&filepath).context("Failed to create output file")?;
+
+        writeln!(file, "datetime,open,high,low,close,volume")?;
+
+        for candle in candles {
+            writeln!(
+                file,
+                "{},{},{},{},{},{}",
+                candle.datetime.format("%Y-%m-%d %H:%M:%S"),
+                candle.open,
+                candle.high,
+                candle.low,
+                candle.close,
+                candle.volume
+            )?;
+        }
+
+        info!("Saved {} rows to {}", candles.len(), filepath.display());
+        Ok(filepath)
+    }
+}
+
 // =============================================================================
 // Data Cache
 // =============================================================================
@@ -0,0 +1,466 @@
+//! Portable candle datasets
+//!
+//! A dataset archive bundles candle series in one canonical format so they can
+//! be shared, archived and re-imported without trusting whoever produced them:
+//!
+//! ```text
+//! btc_eth_1d.tar
+//! ├── dataset.json                # DatasetManifest: source, license, hashes
+//! └── candles/
+//!     ├── BTCINR_1d.csv.sz        # snappy-framed canonical CSV
+//!     └── ETHINR_1d.csv.sz
+//! ```
+//!
+//! Each entry's `sha256` covers the *uncompressed* canonical CSV (same layout
+//! the downloaders write), so the hash identifies the data rather than the
+//! compression. [`file_hash`] computes the same hash from `data_dir`, which is
+//! what the run registry records so a backtest can be traced to the exact
+//! dataset it ran on.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use tracing::info;
+
+use crate::Candle;
+
+/// Bumped when the archive layout or canonical CSV changes incompatibly
+pub const DATASET_FORMAT_VERSION: u32 = 1;
+
+/// Manifest entry name inside the archive
+pub const DATASET_MANIFEST: &str = "dataset.json";
+
+const CANDLES_DIR: &str = "candles";
+
+/// One symbol/timeframe series in an archive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetEntry {
+    pub symbol: String,
+    pub timeframe: String,
+    /// Path of the compressed CSV inside the archive
+    pub file: String,
+    pub rows: usize,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    /// SHA-256 of the uncompressed canonical CSV, hex
+    pub sha256: String,
+}
+
+impl DatasetEntry {
+    /// `SYMBOL_tf`, matching the CSV file stem in `data_dir`
+    pub fn key(&self) -> String {
+        format!("{}_{}", self.symbol, self.timeframe)
+    }
+}
+
+/// Written as `dataset.json` at the top of the archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetManifest {
+    pub format_version: u32,
+    pub created_at: String,
+    /// Where the candles came from (e.g. "binance", "coindcx")
+    pub source: String,
+    /// Terms the data is distributed under, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    pub entries: Vec<DatasetEntry>,
+    /// SHA-256 over the sorted entry hashes: one id for the whole dataset
+    pub sha256: String,
+}
+
+/// Render candles as canonical CSV
+pub fn canonical_csv(candles: &[Candle]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(64 * (candles.len() + 1));
+    out.extend_from_slice(b"datetime,open,high,low,close,volume\n");
+    for candle in candles {
+        // Writing into a Vec cannot fail
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            candle.datetime.format("%Y-%m-%d %H:%M:%S"),
+            candle.open,
+            candle.high,
+            candle.low,
+            candle.close,
+            candle.volume
+        );
+    }
+    out
+}
+
+/// SHA-256 of the canonical CSV for `candles`, hex
+pub fn candles_sha256(candles: &[Candle]) -> String {
+    hex::encode(Sha256::digest(canonical_csv(candles)))
+}
+
+/// Hash of a series as stored under `data_dir` (both tiers, unadjusted)
+pub fn file_hash(
+    data_dir: impl AsRef<Path>,
+    symbol: &str,
+    timeframe: &str,
+) -> Result<Option<String>> {
+    Ok(crate::data::load_tiered_raw(data_dir, symbol, timeframe)?.map(|c| candles_sha256(&c)))
+}
+
+/// Hashes for every symbol/timeframe present in `data_dir`, keyed `SYMBOL_tf`
+pub fn data_hashes<'a>(
+    data_dir: impl AsRef<Path>,
+    symbols: impl IntoIterator<Item = &'a str>,
+    timeframes: &[&str],
+) -> Result<BTreeMap<String, String>> {
+    let mut hashes = BTreeMap::new();
+    for symbol in symbols {
+        for tf in timeframes {
+            if let Some(hash) = file_hash(&data_dir, symbol, tf)? {
+                hashes.insert(format!("{}_{}", symbol, tf), hash);
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+fn combined_hash(entries: &[DatasetEntry]) -> String {
+    let mut hashes: Vec<&str> = entries.iter().map(|e| e.sha256.as_str()).collect();
+    hashes.sort_unstable();
+    let mut hasher = Sha256::new();
+    for hash in hashes {
+        hasher.update(hash.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+fn append_bytes<W: Write>(builder: &mut tar::Builder<W>, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+/// Write `pairs` (symbol, timeframe) from `data_dir` to a dataset archive
+pub fn export_dataset(
+    data_dir: impl AsRef<Path>,
+    pairs: &[(String, String)],
+    archive_path: impl AsRef<Path>,
+    source: &str,
+    license: Option<String>,
+) -> Result<DatasetManifest> {
+    let data_dir = data_dir.as_ref();
+    let archive_path = archive_path.as_ref();
+    if pairs.is_empty() {
+        bail!("Nothing to export - no symbol/timeframe pairs selected");
+    }
+
+    let mut entries = Vec::new();
+    let mut payloads = Vec::new();
+    for (symbol, timeframe) in pairs {
+        let candles =
+            crate::data::load_tiered_raw(data_dir, symbol, timeframe)?.with_context(|| {
+                format!("No {} {} data in {}", symbol, timeframe, data_dir.display())
+            })?;
+        let csv = canonical_csv(&candles);
+        let file = format!("{}/{}_{}.csv.sz", CANDLES_DIR, symbol, timeframe);
+
+        let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+        encoder.write_all(&csv)?;
+        let compressed = encoder
+            .into_inner()
+            .map_err(|e| anyhow::anyhow!("Failed to compress {}: {}", file, e))?;
+
+        entries.push(DatasetEntry {
+            symbol: symbol.clone(),
+            timeframe: timeframe.clone(),
+            file: file.clone(),
+            rows: candles.len(),
+            start: candles.first().map(|c| c.datetime),
+            end: candles.last().map(|c| c.datetime),
+            sha256: hex::encode(Sha256::digest(&csv)),
+        });
+        payloads.push((file, compressed));
+    }
+
+    let manifest = DatasetManifest {
+        format_version: DATASET_FORMAT_VERSION,
+        created_at: Utc::now().to_rfc3339(),
+        source: source.to_string(),
+        license,
+        sha256: combined_hash(&entries),
+        entries,
+    };
+
+    if let Some(parent) = archive_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = File::create(archive_path)
+        .with_context(|| format!("Failed to create archive: {}", archive_path.display()))?;
+    let mut builder = tar::Builder::new(file);
+    append_bytes(
+        &mut builder,
+        DATASET_MANIFEST,
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    for (name, data) in &payloads {
+        append_bytes(&mut builder, name, data)?;
+    }
+    builder.finish()?;
+
+    info!(
+        "Exported {} series to {} (dataset {})",
+        manifest.entries.len(),
+        archive_path.display(),
+        &manifest.sha256[..12]
+    );
+    Ok(manifest)
+}
+
+/// Manifest plus every series, decompressed and checked against its hash
+struct VerifiedDataset {
+    manifest: DatasetManifest,
+    /// (entry, canonical CSV bytes, parsed candles)
+    series: Vec<(DatasetEntry, Vec<u8>, Vec<Candle>)>,
+}
+
+fn read_verified(archive_path: &Path) -> Result<VerifiedDataset> {
+    let file = File::open(archive_path)
+        .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
+    let mut archive = tar::Archive::new(file);
+
+    let mut manifest: Option<DatasetManifest> = None;
+    let mut files: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().replace('\\', "/");
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        if name == DATASET_MANIFEST {
+            manifest =
+                Some(serde_json::from_slice(&data).context("Failed to parse dataset manifest")?);
+        } else {
+            files.insert(name, data);
+        }
+    }
+
+    let manifest = manifest.context("Archive has no dataset.json - not a dataset archive")?;
+    if manifest.format_version > DATASET_FORMAT_VERSION {
+        bail!(
+            "Dataset format version {} is newer than supported ({})",
+            manifest.format_version,
+            DATASET_FORMAT_VERSION
+        );
+    }
+    if combined_hash(&manifest.entries) != manifest.sha256 {
+        bail!("Dataset hash does not match its entries - manifest was modified");
+    }
+
+    let mut series = Vec::new();
+    for entry in &manifest.entries {
+        let compressed = files
+            .get(&entry.file)
+            .with_context(|| format!("{} is listed in the manifest but missing", entry.file))?;
+        let mut csv = Vec::new();
+        snap::read::FrameDecoder::new(compressed.as_slice())
+            .read_to_end(&mut csv)
+            .with_context(|| format!("Failed to decompress {}", entry.file))?;
+
+        let actual = hex::encode(Sha256::digest(&csv));
+        if actual != entry.sha256 {
+            bail!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                entry.key(),
+                entry.sha256,
+                actual
+            );
+        }
+        let candles = crate::data::read_csv(csv.as_slice(), &entry.file)?;
+        if candles.len() != entry.rows {
+            bail!(
+                "{} has {} valid rows, manifest says {}",
+                entry.key(),
+                candles.len(),
+                entry.rows
+            );
+        }
+        series.push((entry.clone(), csv, candles));
+    }
+
+    Ok(VerifiedDataset { manifest, series })
+}
+
+/// Read an archive's manifest without checking the series
+pub fn read_manifest(archive_path: impl AsRef<Path>) -> Result<DatasetManifest> {
+    let archive_path = archive_path.as_ref();
+    let file = File::open(archive_path)
+        .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
+    let mut archive = tar::Archive::new(file);
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if entry.path()?.to_string_lossy() == DATASET_MANIFEST {
+            return serde_json::from_reader(entry).context("Failed to parse dataset manifest");
+        }
+    }
+    bail!("Archive has no dataset.json - not a dataset archive")
+}
+
+/// Check every series in an archive against its checksum and row count
+pub fn verify_dataset(archive_path: impl AsRef<Path>) -> Result<DatasetManifest> {
+    Ok(read_verified(archive_path.as_ref())?.manifest)
+}
+
+/// Verify an archive, then write its series to `data_dir` as CSV.
+///
+/// Nothing is written unless every series verifies. Existing files with
+/// different contents are only replaced with `force`.
+pub fn import_dataset(
+    archive_path: impl AsRef<Path>,
+    data_dir: impl AsRef<Path>,
+    force: bool,
+) -> Result<DatasetManifest> {
+    let data_dir = data_dir.as_ref();
+    let dataset = read_verified(archive_path.as_ref())?;
+
+    if !force {
+        let mut conflicts = Vec::new();
+        for (entry, _, _) in &dataset.series {
+            if let Some(existing) = file_hash(data_dir, &entry.symbol, &entry.timeframe)? {
+                if existing != entry.sha256 {
+                    conflicts.push(entry.key());
+                }
+            }
+        }
+        if !conflicts.is_empty() {
+            bail!(
+                "{} already has different data for {} (use --force to replace)",
+                data_dir.display(),
+                conflicts.join(", ")
+            );
+        }
+    }
+
+    fs::create_dir_all(data_dir)?;
+    for (entry, csv, _) in &dataset.series {
+        let path = data_dir.join(format!("{}.csv", entry.key()));
+        let tmp = path.with_extension("csv.tmp");
+        fs::write(&tmp, csv)?;
+        fs::rename(&tmp, &path)?;
+        // A cold tier would shadow the imported history
+        let cold = crate::retention::cold_path(data_dir, &entry.symbol, &entry.timeframe);
+        if cold.exists() {
+            fs::remove_file(&cold)?;
+        }
+    }
+
+    info!(
+        "Imported {} series into {} (dataset {})",
+        dataset.series.len(),
+        data_dir.display(),
+        &dataset.manifest.sha256[..12]
+    );
+    Ok(dataset.manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn candles(n: usize) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..n)
+            .map(|i| {
+                let p = 100.0 + i as f64 * 0.5;
+                Candle::new_unchecked(
+                    start + chrono::Duration::days(i as i64),
+                    p,
+                    p + 2.0,
+                    p - 1.0,
+                    p + 1.0,
+                    10.0 + i as f64,
+                )
+            })
+            .collect()
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("dataset_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let src = temp_dir("src");
+        let series = candles(30);
+        fs::write(src.join("BTCINR_1d.csv"), canonical_csv(&series)).unwrap();
+
+        let archive = src.join("btc.tar");
+        let pairs = vec![("BTCINR".to_string(), "1d".to_string())];
+        let exported = export_dataset(&src, &pairs, &archive, "test", None).unwrap();
+        assert_eq!(exported.entries[0].rows, 30);
+        assert_eq!(exported.entries[0].sha256, candles_sha256(&series));
+        assert_eq!(
+            read_manifest(&archive).unwrap().sha256,
+            verify_dataset(&archive).unwrap().sha256
+        );
+
+        let dst = temp_dir("dst");
+        import_dataset(&archive, &dst, false).unwrap();
+        assert_eq!(
+            file_hash(&dst, "BTCINR", "1d").unwrap(),
+            Some(exported.entries[0].sha256.clone())
+        );
+
+        // Same data again is fine; different data needs --force
+        import_dataset(&archive, &dst, false).unwrap();
+        fs::write(dst.join("BTCINR_1d.csv"), canonical_csv(&candles(5))).unwrap();
+        assert!(import_dataset(&archive, &dst, false).is_err());
+        import_dataset(&archive, &dst, true).unwrap();
+
+        fs::remove_dir_all(&src).unwrap();
+        fs::remove_dir_all(&dst).unwrap();
+    }
+
+    #[test]
+    fn test_tampered_manifest_is_rejected() {
+        let dir = temp_dir("tamper");
+        fs::write(dir.join("ETHINR_1d.csv"), canonical_csv(&candles(10))).unwrap();
+        let archive = dir.join("eth.tar");
+        let pairs = vec![("ETHINR".to_string(), "1d".to_string())];
+        let mut manifest = export_dataset(&dir, &pairs, &archive, "test", None).unwrap();
+
+        // Point the entry at other data and fix up the dataset hash
+        manifest.entries[0].sha256 = candles_sha256(&candles(9));
+        manifest.sha256 = combined_hash(&manifest.entries);
+        let payload = {
+            let mut a = tar::Archive::new(File::open(&archive).unwrap());
+            let mut e = a.entries().unwrap().nth(1).unwrap().unwrap();
+            let mut data = Vec::new();
+            e.read_to_end(&mut data).unwrap();
+            data
+        };
+        let mut builder = tar::Builder::new(File::create(&archive).unwrap());
+        append_bytes(
+            &mut builder,
+            DATASET_MANIFEST,
+            &serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        append_bytes(&mut builder, &manifest.entries[0].file, &payload).unwrap();
+        builder.finish().unwrap();
+
+        let err = verify_dataset(&archive).unwrap_err().to_string();
+        assert!(err.contains("Checksum mismatch"), "{}", err);
+        let dst = dir.join("out");
+        assert!(import_dataset(&archive, &dst, false).is_err());
+        assert!(!dst.join("ETHINR_1d.csv").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,125 @@
+//! Dead man's switch for resting exchange orders
+//!
+//! Limit orders left on the book keep executing after the bot that placed
+//! them has hung or lost its connection. [`DeadManSwitch`] runs a watcher
+//! task beside the live loop: the loop calls [`DeadManSwitch::beat`] after
+//! each cycle that reached the exchange, and once a set number of heartbeats
+//! has gone by without one, the watcher cancels every open order. It fires
+//! once per outage and re-arms on the next heartbeat.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Whole heartbeat intervals elapsed since the beat at `last_ms`
+pub fn missed_beats(last_ms: i64, now_ms: i64, interval: Duration) -> u32 {
+    let interval_ms = (interval.as_millis() as i64).max(1);
+    ((now_ms - last_ms).max(0) / interval_ms) as u32
+}
+
+/// Watcher that cancels orders when heartbeats stop
+///
+/// Dropping it stops the watcher without cancelling anything.
+pub struct DeadManSwitch {
+    last_beat: Arc<AtomicI64>,
+    task: JoinHandle<()>,
+}
+
+impl DeadManSwitch {
+    /// Start watching; `cancel_all` runs once `max_missed` heartbeats of
+    /// `interval` have been missed
+    pub fn arm<F, Fut>(interval: Duration, max_missed: u32, cancel_all: F) -> Self
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let last_beat = Arc::new(AtomicI64::new(Utc::now().timestamp_millis()));
+        let beats = last_beat.clone();
+        let task = tokio::spawn(async move {
+            let mut tripped = false;
+            loop {
+                tokio::time::sleep(interval / 4).await;
+                let missed = missed_beats(
+                    beats.load(Ordering::Relaxed),
+                    Utc::now().timestamp_millis(),
+                    interval,
+                );
+                if missed >= max_missed.max(1) && !tripped {
+                    warn!(
+                        "🪦 Dead man's switch: {} heartbeat(s) missed - cancelling all open orders",
+                        missed
+                    );
+                    cancel_all().await;
+                    tripped = true;
+                } else if missed == 0 && tripped {
+                    info!("🪦 Dead man's switch re-armed: heartbeats resumed");
+                    tripped = false;
+                }
+            }
+        });
+        Self { last_beat, task }
+    }
+
+    /// The loop is alive and reached the exchange
+    pub fn beat(&self) {
+        self.last_beat
+            .store(Utc::now().timestamp_millis(), Ordering::Relaxed);
+    }
+}
+
+impl Drop for DeadManSwitch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    #[test]
+    fn test_missed_beats_counts_whole_intervals() {
+        let interval = Duration::from_secs(60);
+        assert_eq!(missed_beats(0, 59_999, interval), 0);
+        assert_eq!(missed_beats(0, 60_000, interval), 1);
+        assert_eq!(missed_beats(0, 185_000, interval), 3);
+        // A clock step backwards is not an outage
+        assert_eq!(missed_beats(10_000, 0, interval), 0);
+    }
+
+    #[tokio::test]
+    async fn test_switch_fires_once_per_outage() {
+        let cancels = Arc::new(AtomicU32::new(0));
+        let counter = cancels.clone();
+        let switch = DeadManSwitch::arm(Duration::from_millis(40), 2, move || {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+
+        // Beating keeps orders alive
+        for _ in 0..4 {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            switch.beat();
+        }
+        assert_eq!(cancels.load(Ordering::Relaxed), 0);
+
+        // Silence: cancelled once, not on every check
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(cancels.load(Ordering::Relaxed), 1);
+
+        // Heartbeats resume, then stop again
+        switch.beat();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        switch.beat();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(cancels.load(Ordering::Relaxed), 2);
+    }
+}
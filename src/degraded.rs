@@ -0,0 +1,176 @@
+//! Degraded-data handling for the live loop
+//!
+//! A candle fetch can fail (exchange outage, rate limiting, a delisted pair)
+//! or keep returning a bar that stopped updating. [`DataHealth`] counts
+//! consecutive bad cycles per symbol and [`DegradedDataConfig::action`] turns
+//! that count into what the live loop may do with the symbol this cycle:
+//!
+//! | policy       | entries | open position                                  |
+//! |--------------|---------|------------------------------------------------|
+//! | `hold`       | no      | stop widened by `stop_widen`                    |
+//! | `exits_only` | no      | stops/targets on the last known candles         |
+//! | `flatten`    | no      | as `exits_only`, closed after `flatten_after`   |
+//!
+//! Failures short of `after_failures` just skip the symbol for the cycle. The
+//! status clears on the first good fetch.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::config::{DegradedDataConfig, DegradedPolicy};
+use crate::{Candle, Symbol};
+
+/// What the live loop may do with a symbol this cycle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DataAction {
+    /// Data is fine: trade normally
+    Trade,
+    /// Failed, but fewer than `after_failures` times: sit this cycle out
+    Skip,
+    /// No entries; manage exits on the last known candles
+    ExitsOnly,
+    /// No entries; stop distance from entry multiplied by the factor
+    Hold { stop_widen: f64 },
+    /// Close any open position at market
+    Flatten,
+}
+
+impl DataAction {
+    pub fn allows_entries(&self) -> bool {
+        matches!(self, DataAction::Trade)
+    }
+}
+
+/// A run of failed cycles for one symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct Degradation {
+    pub failures: u32,
+    pub since: DateTime<Utc>,
+    pub last_error: String,
+}
+
+/// Consecutive data failures per symbol
+#[derive(Debug, Clone, Default)]
+pub struct DataHealth {
+    failing: HashMap<Symbol, Degradation>,
+}
+
+impl DataHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a failed cycle; returns the consecutive failure count
+    pub fn record_failure(
+        &mut self,
+        symbol: &Symbol,
+        now: DateTime<Utc>,
+        error: impl Into<String>,
+    ) -> u32 {
+        let entry = self
+            .failing
+            .entry(symbol.clone())
+            .or_insert_with(|| Degradation {
+                failures: 0,
+                since: now,
+                last_error: String::new(),
+            });
+        entry.failures += 1;
+        entry.last_error = error.into();
+        entry.failures
+    }
+
+    /// Clear the symbol's failures; returns the run that just ended, if any
+    pub fn record_success(&mut self, symbol: &Symbol) -> Option<Degradation> {
+        self.failing.remove(symbol)
+    }
+
+    pub fn failures(&self, symbol: &Symbol) -> u32 {
+        self.failing.get(symbol).map_or(0, |d| d.failures)
+    }
+
+    /// Symbols with at least one failed cycle, sorted by symbol
+    pub fn failing(&self) -> Vec<(&Symbol, &Degradation)> {
+        let mut failing: Vec<_> = self.failing.iter().collect();
+        failing.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+        failing
+    }
+}
+
+/// Latest bar is more than two bars old
+pub fn is_stale(latest: &Candle, bar_secs: u64, now: DateTime<Utc>) -> bool {
+    (now - latest.datetime).num_seconds() > 2 * bar_secs as i64
+}
+
+impl DegradedDataConfig {
+    pub fn policy_for(&self, symbol: &str) -> DegradedPolicy {
+        self.symbols.get(symbol).copied().unwrap_or(self.policy)
+    }
+
+    /// Action for a symbol after `failures` consecutive bad cycles
+    pub fn action(&self, symbol: &str, failures: u32) -> DataAction {
+        if failures == 0 {
+            return DataAction::Trade;
+        }
+        if failures < self.after_failures {
+            return DataAction::Skip;
+        }
+        match self.policy_for(symbol) {
+            DegradedPolicy::Hold => DataAction::Hold {
+                stop_widen: self.stop_widen,
+            },
+            DegradedPolicy::ExitsOnly => DataAction::ExitsOnly,
+            DegradedPolicy::Flatten if failures >= self.flatten_after => DataAction::Flatten,
+            DegradedPolicy::Flatten => DataAction::ExitsOnly,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_policy_escalation() {
+        let mut config = DegradedDataConfig {
+            after_failures: 2,
+            ..Default::default()
+        };
+        config
+            .symbols
+            .insert("SOLINR".to_string(), DegradedPolicy::Flatten);
+        config
+            .symbols
+            .insert("ETHINR".to_string(), DegradedPolicy::Hold);
+
+        assert_eq!(config.action("BTCINR", 0), DataAction::Trade);
+        assert_eq!(config.action("BTCINR", 1), DataAction::Skip);
+        assert_eq!(config.action("BTCINR", 2), DataAction::ExitsOnly);
+        assert_eq!(
+            config.action("ETHINR", 2),
+            DataAction::Hold { stop_widen: 1.5 }
+        );
+        assert_eq!(config.action("SOLINR", 2), DataAction::ExitsOnly);
+        assert_eq!(config.action("SOLINR", 3), DataAction::Flatten);
+        assert!(!config.action("SOLINR", 2).allows_entries());
+    }
+
+    #[test]
+    fn test_health_tracks_runs_and_staleness() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let sym = Symbol::new("BTCINR");
+        let mut health = DataHealth::new();
+        assert_eq!(health.record_failure(&sym, now, "timeout"), 1);
+        assert_eq!(health.record_failure(&sym, now, "timeout"), 2);
+        assert_eq!(health.failing().len(), 1);
+        let run = health.record_success(&sym).unwrap();
+        assert_eq!((run.failures, run.since), (2, now));
+        assert_eq!(health.failures(&sym), 0);
+        assert!(health.record_success(&sym).is_none());
+
+        let bar = Candle::new_unchecked(now - chrono::Duration::hours(3), 1.0, 1.0, 1.0, 1.0, 1.0);
+        assert!(is_stale(&bar, 3_600, now));
+        assert!(!is_stale(&bar, 14_400, now));
+    }
+}
@@ -0,0 +1,746 @@
+//! One client interface over every venue
+//!
+//! [`ExchangeClient`] covers what the live loop needs from an exchange:
+//! quotes, candles, placing and cancelling orders, and balances. It is
+//! implemented by [`CoinDCXClient`], [`KrakenClient`], [`CoinbaseClient`],
+//! [`OkxClient`] (spot or swaps), [`BybitClient`] (USDT perpetuals),
+//! [`BinanceClient`] (market data only; order calls fail) and
+//! [`ZerodhaClient`], so a new venue plugs into `live` by implementing this
+//! trait instead of copying the loop. [`crate::mock_exchange`] has an
+//! in-memory implementation for tests.
+//!
+//! ```no_run
+//! use crypto_strategies::coindcx::CoinDCXClient;
+//! use crypto_strategies::exchange::ExchangeClient;
+//!
+//! async fn spread(exchange: &impl ExchangeClient, symbol: &str) -> anyhow::Result<f64> {
+//!     let ticker = exchange.get_ticker(symbol).await?;
+//!     Ok(ticker.ask.zip(ticker.bid).map_or(0.0, |(ask, bid)| ask - bid))
+//! }
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let client = CoinDCXClient::new("", "");
+//! println!("{}: {:.2}", client.capabilities().name, spread(&client, "BTCINR").await?);
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{Context, Result};
+use std::future::Future;
+
+use crate::binance::BinanceClient;
+use crate::bybit::{self, BybitClient};
+use crate::capabilities::ExchangeCapabilities;
+use crate::coinbase::{self, CoinbaseClient};
+use crate::coindcx::{self, CoinDCXClient, OcoHandle, OcoOrder, OcoStatus};
+use crate::fees::FeeSchedule;
+use crate::kraken::{self, KrakenClient};
+use crate::okx::{self, OkxClient};
+use crate::oms::{Order, OrderType};
+use crate::symbols::{kite_instrument, Venue};
+use crate::zerodha::{self, ZerodhaClient};
+use crate::{Candle, Money, Side};
+
+/// Latest prices of a market
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticker {
+    pub symbol: String,
+    pub last: f64,
+    /// Best bid/ask, when the venue reports them
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    /// 24h volume in the base asset
+    pub volume: f64,
+}
+
+/// Funds held in one asset
+#[derive(Debug, Clone, PartialEq)]
+pub struct Balance {
+    pub asset: String,
+    /// Available to trade
+    pub free: f64,
+    /// Held by open orders or margin
+    pub locked: f64,
+}
+
+/// Venue-neutral exchange client
+///
+/// Symbols are the config's (`BTCINR`, `NSE:RELIANCE`); each client maps them
+/// to its own format. Candles come back oldest first, invalid ones dropped.
+pub trait ExchangeClient: Send + Sync {
+    /// Order types, timeframes and precision the venue supports
+    fn capabilities(&self) -> &'static ExchangeCapabilities;
+
+    fn get_ticker(&self, symbol: &str) -> impl Future<Output = Result<Ticker>> + Send;
+
+    /// The latest `limit` candles of `timeframe`
+    fn get_candles(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        limit: u32,
+    ) -> impl Future<Output = Result<Vec<Candle>>> + Send;
+
+    /// Submit `order`; returns the exchange's order ID
+    fn place_order(&self, order: &Order) -> impl Future<Output = Result<String>> + Send;
+
+    fn cancel_order(&self, symbol: &str, order_id: &str)
+        -> impl Future<Output = Result<()>> + Send;
+
+    fn get_balances(&self) -> impl Future<Output = Result<Vec<Balance>>> + Send;
+
+    /// Exchange order ID of the order sent as `client_order_id`, `None` if the
+    /// exchange has no such order
+    ///
+    /// Venues that can't look orders up by client ID fail, so an order whose
+    /// placement went unanswered is never sent a second time.
+    fn find_order(
+        &self,
+        _symbol: &str,
+        client_order_id: &str,
+    ) -> impl Future<Output = Result<Option<String>>> + Send {
+        let venue = self.capabilities().name;
+        let id = client_order_id.to_string();
+        async move { anyhow::bail!("{}: orders can't be looked up by client ID ({})", venue, id) }
+    }
+
+    /// Maker/taker fees the account pays now; `None` where the venue doesn't report them
+    fn get_fee_schedule(&self) -> impl Future<Output = Result<Option<FeeSchedule>>> + Send {
+        async { Ok(None) }
+    }
+
+    /// Round a new order's quantity and prices to what the venue accepts
+    ///
+    /// Venues without cached market details leave the order as it is.
+    fn quantize(&self, _order: &mut Order) {}
+
+    /// Whether [`place_oco`](Self::place_oco) can rest a position's exits on the venue
+    fn supports_oco(&self) -> bool {
+        false
+    }
+
+    /// Rest a stop-loss and take-profit as a linked pair; either both legs
+    /// rest or neither does
+    fn place_oco(&self, oco: &OcoOrder) -> impl Future<Output = Result<OcoHandle>> + Send {
+        let venue = self.capabilities().name;
+        let id = oco.client_order_id.clone();
+        async move { anyhow::bail!("{}: no OCO orders ({})", venue, id) }
+    }
+
+    /// State of a parked pair; the surviving leg is cancelled once the other fills
+    fn sync_oco(&self, _handle: &OcoHandle) -> impl Future<Output = Result<OcoStatus>> + Send {
+        let venue = self.capabilities().name;
+        async move { anyhow::bail!("{}: no OCO orders", venue) }
+    }
+
+    /// Cancel both legs of a parked pair
+    fn cancel_oco(&self, _handle: &OcoHandle) -> impl Future<Output = Result<()>> + Send {
+        let venue = self.capabilities().name;
+        async move { anyhow::bail!("{}: no OCO orders", venue) }
+    }
+
+    /// The CoinDCX client behind this one, for what only CoinDCX offers
+    /// (order recovery, trade reconciliation, book recording, order streams)
+    fn as_coindcx(&self) -> Option<&CoinDCXClient> {
+        None
+    }
+}
+
+/// Candles converted to [`Candle`]; invalid ones are dropped with a warning
+fn validated<T>(raw: Vec<T>, symbol: &str, timeframe: &str) -> Vec<Candle>
+where
+    Candle: TryFrom<T>,
+{
+    let total = raw.len();
+    let candles: Vec<Candle> = raw
+        .into_iter()
+        .filter_map(|c| Candle::try_from(c).ok())
+        .collect();
+    if candles.len() < total {
+        tracing::warn!(
+            "Dropped {} invalid {} {} candle(s)",
+            total - candles.len(),
+            symbol,
+            timeframe
+        );
+    }
+    candles
+}
+
+impl ExchangeClient for CoinDCXClient {
+    fn capabilities(&self) -> &'static ExchangeCapabilities {
+        &coindcx::CAPABILITIES
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker> {
+        // Quotes gate orders, so they go ahead of background requests
+        let t = self
+            .get_ticker_with_priority(symbol, crate::common::RequestPriority::High)
+            .await?;
+        Ok(Ticker {
+            symbol: symbol.to_string(),
+            last: t.last_price_f64().context("ticker has no last price")?,
+            bid: t.bid_f64(),
+            ask: t.ask_f64(),
+            volume: t.volume_f64().unwrap_or_default(),
+        })
+    }
+
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: u32) -> Result<Vec<Candle>> {
+        let raw = CoinDCXClient::get_candles(self, symbol, timeframe, Some(limit)).await?;
+        Ok(validated(raw, symbol, timeframe))
+    }
+
+    fn quantize(&self, order: &mut Order) {
+        let Some(details) = self.market_details(order.symbol.as_str()) else {
+            return;
+        };
+        if order.filled_quantity.is_zero() {
+            order.quantity = Money::from_f64(details.quantize_quantity(order.quantity.to_f64()));
+            order.remaining_quantity = order.quantity;
+        }
+        for price in [&mut order.limit_price, &mut order.stop_price]
+            .into_iter()
+            .flatten()
+        {
+            *price = Money::from_f64(details.quantize_price(price.to_f64()));
+        }
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<String> {
+        let mut order = order.clone();
+        ExchangeClient::quantize(self, &mut order);
+        let side = match order.side {
+            Side::Buy => coindcx::OrderSide::Buy,
+            Side::Sell => coindcx::OrderSide::Sell,
+        };
+        let quantity = order.quantity.to_f64();
+        if let Some(leverage) = self.margin_leverage() {
+            let request = match (order.order_type, order.limit_price) {
+                (OrderType::Market, _) => {
+                    coindcx::MarginOrderRequest::market(side, order.symbol.as_str(), quantity)
+                }
+                (OrderType::Limit, Some(price)) => coindcx::MarginOrderRequest::limit(
+                    side,
+                    order.symbol.as_str(),
+                    quantity,
+                    price.to_f64(),
+                ),
+                (other, _) => {
+                    anyhow::bail!(
+                        "coindcx: cannot place {:?} margin order {}",
+                        other,
+                        order.id
+                    )
+                }
+            };
+            let placed = self
+                .place_margin_order(&request.with_leverage(leverage))
+                .await?;
+            return placed
+                .first()
+                .map(|o| o.id.clone())
+                .context("coindcx: margin order accepted without an ID");
+        }
+        let request = match (order.order_type, order.limit_price) {
+            (OrderType::Market, _) => {
+                coindcx::OrderRequest::market(side, order.symbol.as_str(), quantity)
+            }
+            (OrderType::Limit, Some(price)) => {
+                coindcx::OrderRequest::limit(side, order.symbol.as_str(), quantity, price.to_f64())
+            }
+            (other, _) => anyhow::bail!("coindcx: cannot place {:?} order {}", other, order.id),
+        };
+        let request = match &order.client_id {
+            Some(id) => request.with_client_order_id(id.clone()),
+            None => request,
+        };
+        let response = CoinDCXClient::place_order(self, &request).await?;
+        response
+            .orders
+            .first()
+            .map(|o| o.id.clone())
+            .context("coindcx: order accepted without an ID")
+    }
+
+    async fn find_order(&self, _symbol: &str, client_order_id: &str) -> Result<Option<String>> {
+        match self.get_order_status_by_client_id(client_order_id).await {
+            Ok(order) => Ok(Some(order.id)),
+            Err(coindcx::CoinDCXError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn cancel_order(&self, _symbol: &str, order_id: &str) -> Result<()> {
+        if self.margin_leverage().is_some() {
+            return Ok(self.cancel_margin_order(order_id).await?);
+        }
+        Ok(CoinDCXClient::cancel_order(self, order_id).await?)
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        Ok(CoinDCXClient::get_balances(self)
+            .await?
+            .into_iter()
+            .map(|b| Balance {
+                asset: b.currency,
+                free: b.balance,
+                locked: b.locked_balance,
+            })
+            .collect())
+    }
+
+    async fn get_fee_schedule(&self) -> Result<Option<FeeSchedule>> {
+        let tier = CoinDCXClient::get_fee_schedule(self).await?;
+        Ok(Some(FeeSchedule::from_percent(
+            tier.maker_fee,
+            tier.taker_fee,
+        )))
+    }
+
+    fn supports_oco(&self) -> bool {
+        true
+    }
+
+    async fn place_oco(&self, oco: &OcoOrder) -> Result<OcoHandle> {
+        Ok(CoinDCXClient::place_oco(self, oco).await?)
+    }
+
+    async fn sync_oco(&self, handle: &OcoHandle) -> Result<OcoStatus> {
+        Ok(CoinDCXClient::sync_oco(self, handle).await?)
+    }
+
+    async fn cancel_oco(&self, handle: &OcoHandle) -> Result<()> {
+        Ok(CoinDCXClient::cancel_oco(self, handle).await?)
+    }
+
+    fn as_coindcx(&self) -> Option<&CoinDCXClient> {
+        Some(self)
+    }
+}
+
+impl ExchangeClient for KrakenClient {
+    fn capabilities(&self) -> &'static ExchangeCapabilities {
+        &kraken::CAPABILITIES
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker> {
+        let t = self
+            .get_ticker_with_priority(symbol, crate::common::RequestPriority::High)
+            .await?;
+        Ok(Ticker {
+            symbol: symbol.to_string(),
+            last: t.last_price_f64().context("ticker has no last price")?,
+            bid: t.bid_f64(),
+            ask: t.ask_f64(),
+            volume: t.volume_f64().unwrap_or_default(),
+        })
+    }
+
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: u32) -> Result<Vec<Candle>> {
+        // OHLC always returns the latest 720 bars; keep the last `limit`
+        let raw = self.get_ohlc(symbol, timeframe, None).await?;
+        let mut candles = validated(raw, symbol, timeframe);
+        let skip = candles.len().saturating_sub(limit as usize);
+        candles.drain(..skip);
+        Ok(candles)
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<String> {
+        let side = match order.side {
+            Side::Buy => kraken::OrderSide::Buy,
+            Side::Sell => kraken::OrderSide::Sell,
+        };
+        let pair = self
+            .symbol_mapper()
+            .id(order.symbol.as_str(), Venue::Kraken);
+        let quantity = order.quantity.to_f64();
+        let request = match (order.order_type, order.limit_price) {
+            (OrderType::Market, _) => kraken::OrderRequest::market(side, pair, quantity),
+            (OrderType::Limit, Some(price)) => {
+                kraken::OrderRequest::limit(side, pair, quantity, price.to_f64())
+            }
+            (other, _) => anyhow::bail!("kraken: cannot place {:?} order {}", other, order.id),
+        };
+        let request = match &order.client_id {
+            Some(id) => request.with_client_order_id(id.clone()),
+            None => request,
+        };
+        let placed = KrakenClient::place_order(self, &request).await?;
+        placed
+            .txid
+            .into_iter()
+            .next()
+            .context("kraken: order accepted without a txid")
+    }
+
+    async fn cancel_order(&self, _symbol: &str, order_id: &str) -> Result<()> {
+        KrakenClient::cancel_order(self, order_id).await
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        Ok(KrakenClient::get_balances(self)
+            .await?
+            .into_iter()
+            .map(|(asset, b)| Balance {
+                asset: kraken::asset_name(&asset),
+                free: b.total_f64() - b.held_f64(),
+                locked: b.held_f64(),
+            })
+            .collect())
+    }
+}
+
+impl ExchangeClient for CoinbaseClient {
+    fn capabilities(&self) -> &'static ExchangeCapabilities {
+        &coinbase::CAPABILITIES
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker> {
+        let p = self
+            .get_product_with_priority(symbol, crate::common::RequestPriority::High)
+            .await?;
+        Ok(Ticker {
+            symbol: symbol.to_string(),
+            last: p.price_f64().context("product has no last price")?,
+            bid: None,
+            ask: None,
+            volume: p.volume_f64().unwrap_or_default(),
+        })
+    }
+
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: u32) -> Result<Vec<Candle>> {
+        // One request covers the latest 350 candles; keep the last `limit`
+        let raw = CoinbaseClient::get_candles(self, symbol, timeframe, None, None).await?;
+        let mut candles = validated(raw, symbol, timeframe);
+        let skip = candles.len().saturating_sub(limit as usize);
+        candles.drain(..skip);
+        Ok(candles)
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<String> {
+        let side = match order.side {
+            Side::Buy => coinbase::OrderSide::Buy,
+            Side::Sell => coinbase::OrderSide::Sell,
+        };
+        let product = self
+            .symbol_mapper()
+            .id(order.symbol.as_str(), Venue::Coinbase);
+        let quantity = order.quantity.to_f64();
+        let request = match (order.order_type, order.limit_price) {
+            (OrderType::Market, _) => coinbase::OrderRequest::market(side, product, quantity),
+            (OrderType::Limit, Some(price)) => {
+                coinbase::OrderRequest::limit(side, product, quantity, price.to_f64())
+            }
+            (other, _) => anyhow::bail!("coinbase: cannot place {:?} order {}", other, order.id),
+        };
+        let request = match &order.client_id {
+            Some(id) => request.with_client_order_id(id.clone()),
+            None => request,
+        };
+        Ok(CoinbaseClient::place_order(self, &request).await?.order_id)
+    }
+
+    async fn cancel_order(&self, _symbol: &str, order_id: &str) -> Result<()> {
+        CoinbaseClient::cancel_order(self, order_id).await
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        Ok(self
+            .get_accounts()
+            .await?
+            .into_iter()
+            .map(|a| Balance {
+                free: a.available_f64(),
+                locked: a.hold_f64(),
+                asset: a.currency,
+            })
+            .collect())
+    }
+}
+
+impl ExchangeClient for OkxClient {
+    fn capabilities(&self) -> &'static ExchangeCapabilities {
+        &okx::CAPABILITIES
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker> {
+        let t = self
+            .get_ticker_with_priority(symbol, self.market(), crate::common::RequestPriority::High)
+            .await?;
+        Ok(Ticker {
+            symbol: symbol.to_string(),
+            last: t.last_price_f64().context("ticker has no last price")?,
+            bid: t.bid_f64(),
+            ask: t.ask_f64(),
+            volume: t.volume_f64().unwrap_or_default(),
+        })
+    }
+
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: u32) -> Result<Vec<Candle>> {
+        let raw = OkxClient::get_candles(self, symbol, self.market(), timeframe, None, Some(limit))
+            .await?;
+        Ok(validated(raw, symbol, timeframe))
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<String> {
+        let side = match order.side {
+            Side::Buy => okx::OrderSide::Buy,
+            Side::Sell => okx::OrderSide::Sell,
+        };
+        let market = self.market();
+        let inst_id = self.inst_id(order.symbol.as_str(), market);
+        let quantity = order.quantity.to_f64();
+        let request = match (order.order_type, order.limit_price) {
+            (OrderType::Market, _) => okx::OrderRequest::market(side, inst_id, market, quantity),
+            (OrderType::Limit, Some(price)) => {
+                okx::OrderRequest::limit(side, inst_id, market, quantity, price.to_f64())
+            }
+            (other, _) => anyhow::bail!("okx: cannot place {:?} order {}", other, order.id),
+        };
+        let request = match &order.client_id {
+            Some(id) => request.with_client_order_id(id.clone()),
+            None => request,
+        };
+        Ok(OkxClient::place_order(self, &request).await?.ord_id)
+    }
+
+    async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<()> {
+        OkxClient::cancel_order(self, symbol, self.market(), order_id).await
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        Ok(OkxClient::get_balances(self)
+            .await?
+            .into_iter()
+            .map(|b| Balance {
+                free: b.available_f64(),
+                locked: b.frozen_f64(),
+                asset: b.ccy,
+            })
+            .collect())
+    }
+}
+
+impl ExchangeClient for BybitClient {
+    fn capabilities(&self) -> &'static ExchangeCapabilities {
+        &bybit::CAPABILITIES
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker> {
+        let t = self
+            .get_ticker_with_priority(symbol, crate::common::RequestPriority::High)
+            .await?;
+        Ok(Ticker {
+            symbol: symbol.to_string(),
+            last: t.last_price_f64().context("ticker has no last price")?,
+            bid: t.bid_f64(),
+            ask: t.ask_f64(),
+            volume: t.volume_f64().unwrap_or_default(),
+        })
+    }
+
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: u32) -> Result<Vec<Candle>> {
+        let raw = self
+            .get_klines(symbol, timeframe, None, None, Some(limit))
+            .await?;
+        Ok(validated(raw, symbol, timeframe))
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<String> {
+        let side = match order.side {
+            Side::Buy => bybit::OrderSide::Buy,
+            Side::Sell => bybit::OrderSide::Sell,
+        };
+        let contract = self.symbol_mapper().id(order.symbol.as_str(), Venue::Bybit);
+        let quantity = order.quantity.to_f64();
+        let request = match (order.order_type, order.limit_price) {
+            (OrderType::Market, _) => bybit::OrderRequest::market(side, contract, quantity),
+            (OrderType::Limit, Some(price)) => {
+                bybit::OrderRequest::limit(side, contract, quantity, price.to_f64())
+            }
+            (other, _) => anyhow::bail!("bybit: cannot place {:?} order {}", other, order.id),
+        };
+        let request = match &order.client_id {
+            Some(id) => request.with_order_link_id(id.clone()),
+            None => request,
+        };
+        Ok(BybitClient::place_order(self, &request).await?.order_id)
+    }
+
+    async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<()> {
+        BybitClient::cancel_order(self, symbol, order_id).await
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        Ok(BybitClient::get_balances(self)
+            .await?
+            .into_iter()
+            .map(|c| Balance {
+                free: c.wallet_balance_f64() - c.locked_f64(),
+                locked: c.locked_f64(),
+                asset: c.coin,
+            })
+            .collect())
+    }
+}
+
+impl ExchangeClient for BinanceClient {
+    fn capabilities(&self) -> &'static ExchangeCapabilities {
+        &crate::binance::CAPABILITIES
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker> {
+        let t = self.get_24h_ticker(&self.to_binance_pair(symbol)).await?;
+        Ok(Ticker {
+            symbol: symbol.to_string(),
+            last: t.last_price.parse().context("ticker has no last price")?,
+            bid: t.bid_price.parse().ok(),
+            ask: t.ask_price.parse().ok(),
+            volume: t.volume.parse().unwrap_or_default(),
+        })
+    }
+
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: u32) -> Result<Vec<Candle>> {
+        let pair = self.to_binance_pair(symbol);
+        let raw = self
+            .get_klines(&pair, timeframe, None, None, Some(limit))
+            .await?;
+        Ok(validated(raw, symbol, timeframe))
+    }
+
+    async fn place_order(&self, _order: &Order) -> Result<String> {
+        anyhow::bail!("binance: market data only, orders are not supported")
+    }
+
+    async fn cancel_order(&self, _symbol: &str, _order_id: &str) -> Result<()> {
+        anyhow::bail!("binance: market data only, orders are not supported")
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        anyhow::bail!("binance: market data only, balances are not supported")
+    }
+}
+
+impl ExchangeClient for ZerodhaClient {
+    fn capabilities(&self) -> &'static ExchangeCapabilities {
+        &zerodha::CAPABILITIES
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker> {
+        let instrument = self.symbol_mapper().id(symbol, Venue::Zerodha);
+        let quote = self.get_quote(&instrument).await?;
+        Ok(Ticker {
+            symbol: symbol.to_string(),
+            last: quote.last_price,
+            bid: None,
+            ask: None,
+            volume: quote.volume,
+        })
+    }
+
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: u32) -> Result<Vec<Candle>> {
+        let interval = zerodha::to_kite_interval(timeframe)
+            .with_context(|| format!("zerodha: unsupported timeframe {}", timeframe))?;
+        let bar_secs = crate::idle::timeframe_secs(timeframe).unwrap_or(86_400);
+        // Calendar days covering `limit` bars, with room for weekends and holidays
+        let days = (limit as u64 * bar_secs).div_ceil(86_400) * 2 + 4;
+        // Kite serves history by instrument token only
+        let token = self.symbol_mapper().kite_token(symbol).with_context(|| {
+            format!(
+                "zerodha: no instrument token for {} (set exchange.instrument_tokens)",
+                symbol
+            )
+        })?;
+        let raw = self
+            .get_historical_data(token, interval, days as u32)
+            .await?;
+        let mut candles = validated(raw, symbol, timeframe);
+        let skip = candles.len().saturating_sub(limit as usize);
+        candles.drain(..skip);
+        Ok(candles)
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<String> {
+        let instrument = self
+            .symbol_mapper()
+            .id(order.symbol.as_str(), Venue::Zerodha);
+        let (exchange, tradingsymbol) = kite_instrument(&instrument);
+        let side = match order.side {
+            Side::Buy => "BUY",
+            Side::Sell => "SELL",
+        };
+        let quantity = order.quantity.to_f64().round() as i32;
+        if quantity < 1 {
+            anyhow::bail!("zerodha: order {} is below one share", order.id);
+        }
+        let price = match order.order_type {
+            OrderType::Market => None,
+            OrderType::Limit => order.limit_price.map(|p| p.to_f64()),
+            other => anyhow::bail!("zerodha: cannot place {:?} order {}", other, order.id),
+        };
+        let placed =
+            ZerodhaClient::place_order(self, exchange, tradingsymbol, side, quantity, price)
+                .await?;
+        Ok(placed.order_id)
+    }
+
+    async fn cancel_order(&self, _symbol: &str, order_id: &str) -> Result<()> {
+        Ok(ZerodhaClient::cancel_order(self, order_id).await?)
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        let margins = self.get_margins().await?;
+        let equity = margins.equity.unwrap_or_default();
+        Ok(vec![Balance {
+            asset: "INR".to_string(),
+            free: equity.net,
+            locked: equity.utilised.debits,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kite_instrument_defaults_to_nse() {
+        assert_eq!(kite_instrument("NSE:RELIANCE"), ("NSE", "RELIANCE"));
+        assert_eq!(kite_instrument("BSE:TCS"), ("BSE", "TCS"));
+        assert_eq!(kite_instrument("INFY"), ("NSE", "INFY"));
+    }
+
+    #[tokio::test]
+    async fn test_zerodha_candles_need_an_instrument_token() {
+        let client = ZerodhaClient::new("key", "secret");
+        let err = ExchangeClient::get_candles(&client, "NSE:INFY", "1d", 10)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exchange.instrument_tokens"));
+
+        let config = zerodha::ClientConfig::default().with_instrument_token("NSE:INFY", 408065);
+        assert_eq!(config.symbols.kite_token("NSE:INFY"), Some(408065));
+    }
+
+    #[tokio::test]
+    async fn test_binance_refuses_orders() {
+        let client = BinanceClient::new();
+        let order = Order::from_f64(
+            crate::Symbol::new("BTCUSDT"),
+            Side::Buy,
+            OrderType::Market,
+            0.1,
+            None,
+            None,
+            crate::oms::TimeInForce::GTC,
+            None,
+        );
+        let err = ExchangeClient::place_order(&client, &order)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("market data only"));
+        assert_eq!(client.capabilities().name, "binance");
+        assert!(client.as_coindcx().is_none());
+    }
+}
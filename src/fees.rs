@@ -0,0 +1,132 @@
+//! Maker/taker fees read from the exchange
+//!
+//! `maker_fee` and `taker_fee` in the exchange config are a guess at the
+//! account's fee tier. With `"fees_from_exchange": true`, the tier the venue
+//! reports replaces them before anything is built from the config, so the
+//! backtester's commission model and live P&L charge what the account
+//! actually pays. If the schedule can't be fetched, the configured fees stay.
+
+use anyhow::Result;
+use tracing::{info, warn};
+
+use crate::coindcx::{ClientConfig, CoinDCXClient};
+use crate::config::{Config, ExchangeConfig, Venue};
+use crate::exchange::ExchangeClient;
+
+/// Fees as fractions of notional (0.001 = 0.1%), before GST
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeSchedule {
+    pub maker: f64,
+    pub taker: f64,
+}
+
+impl FeeSchedule {
+    /// From fees quoted in percent (0.2 = 0.2%)
+    pub fn from_percent(maker: f64, taker: f64) -> Self {
+        Self {
+            maker: maker / 100.0,
+            taker: taker / 100.0,
+        }
+    }
+
+    /// Write these fees into `exchange`, logging when they differ from the configured ones
+    pub fn apply(&self, exchange: &mut ExchangeConfig) {
+        if self.maker != exchange.maker_fee || self.taker != exchange.taker_fee {
+            info!(
+                "Exchange fee tier: maker {:.4}%, taker {:.4}% (configured {:.4}% / {:.4}%)",
+                self.maker * 100.0,
+                self.taker * 100.0,
+                exchange.maker_fee * 100.0,
+                exchange.taker_fee * 100.0
+            );
+        }
+        exchange.maker_fee = self.maker;
+        exchange.taker_fee = self.taker;
+    }
+}
+
+/// Replace the configured fees with the schedule `client` reports
+///
+/// Does nothing unless `fees_from_exchange` is set. A failed or empty answer
+/// keeps the configured fees.
+pub async fn apply_from(exchange: &mut ExchangeConfig, client: &impl ExchangeClient) {
+    if !exchange.fees_from_exchange {
+        return;
+    }
+    match client.get_fee_schedule().await {
+        Ok(Some(schedule)) => schedule.apply(exchange),
+        Ok(None) => warn!(
+            "{} does not report fees - using configured fees",
+            client.capabilities().name
+        ),
+        Err(e) => warn!(
+            "Could not fetch the fee schedule ({:#}) - using configured fees",
+            e
+        ),
+    }
+}
+
+/// [`apply_from`] with a client built from the config's venue and credentials
+///
+/// Only CoinDCX reports a fee schedule; other venues keep the configured fees.
+pub async fn apply_exchange_fees(config: &mut Config) {
+    if !config.exchange.fees_from_exchange {
+        return;
+    }
+    match config.exchange.venue {
+        Venue::CoinDCX => {
+            let network = match config.exchange.network() {
+                Ok(network) => network,
+                Err(e) => {
+                    warn!("Fee schedule unavailable ({:#}) - using configured fees", e);
+                    return;
+                }
+            };
+            let client = CoinDCXClient::with_config(
+                config.exchange.api_key.clone().unwrap_or_default(),
+                config.exchange.api_secret.clone().unwrap_or_default(),
+                ClientConfig {
+                    network,
+                    ..Default::default()
+                },
+            );
+            apply_from(&mut config.exchange, &client).await;
+        }
+        venue => warn!(
+            "Fee schedule not available for {:?} - using configured fees",
+            venue
+        ),
+    }
+}
+
+/// Blocking wrapper for [`apply_exchange_fees`], usable from sync commands
+pub fn apply_exchange_fees_blocking(config: &mut Config) -> Result<()> {
+    if !config.exchange.fees_from_exchange {
+        return Ok(());
+    }
+    crate::common::block_on(apply_exchange_fees(config))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_exchange::MockExchangeClient;
+
+    #[tokio::test]
+    async fn test_schedule_replaces_configured_fees() {
+        let mut exchange = ExchangeConfig {
+            gst_rate: 0.18,
+            ..ExchangeConfig::default()
+        };
+        FeeSchedule::from_percent(0.1, 0.25).apply(&mut exchange);
+        assert_eq!(exchange.maker_fee, 0.001);
+        assert_eq!(exchange.taker_fee, 0.0025);
+        assert!((exchange.effective_taker_fee() - 0.00295).abs() < 1e-12);
+
+        // Venues without a schedule leave the config alone
+        exchange.fees_from_exchange = true;
+        apply_from(&mut exchange, &MockExchangeClient::new()).await;
+        assert_eq!(exchange.taker_fee, 0.0025);
+    }
+}
@@ -0,0 +1,353 @@
+//! Currency conversion for performance reporting
+//!
+//! A strategy trading USDT pairs keeps its books in USDT, but a user funding
+//! in INR also carries USDT/INR risk. [`FxSeries`] holds a quote→home rate
+//! series (e.g. USDTINR closes) and converts an equity curve so performance
+//! can be reported in both currencies.
+//!
+//! A portfolio mixing `BTCINR` and `ETHUSDT` holds values in two currencies.
+//! [`QuoteConverter`] keeps one rate series per foreign quote currency and
+//! converts each symbol's values into the home currency, so they add up to a
+//! single portfolio value. [`fetch_rate`] reads the latest rate from an
+//! exchange for live use.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::Candle;
+
+/// Quote currency of `symbol` (`ETHUSDT` → `USDT`), if it ends in a known one
+pub fn quote_currency(symbol: &str) -> Option<&'static str> {
+    crate::symbols::quote(symbol)
+}
+
+/// Time series of conversion rates (home currency per unit of asset currency)
+#[derive(Debug, Clone, Default)]
+pub struct FxSeries {
+    rates: Vec<(DateTime<Utc>, f64)>,
+}
+
+impl FxSeries {
+    /// Build from `(time, rate)` points; non-positive rates are dropped
+    pub fn new(mut rates: Vec<(DateTime<Utc>, f64)>) -> Self {
+        rates.retain(|(_, r)| r.is_finite() && *r > 0.0);
+        rates.sort_by_key(|(t, _)| *t);
+        Self { rates }
+    }
+
+    /// Build from candle closes (e.g. a USDTINR series)
+    pub fn from_candles(candles: &[Candle]) -> Self {
+        Self::new(candles.iter().map(|c| (c.datetime, c.close)).collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// Append a newer rate (e.g. one just fetched); older or invalid ones are ignored
+    pub fn push(&mut self, t: DateTime<Utc>, rate: f64) {
+        let newer = self.rates.last().is_none_or(|(last, _)| t >= *last);
+        if newer && rate.is_finite() && rate > 0.0 {
+            self.rates.push((t, rate));
+        }
+    }
+
+    /// Last known rate at or before `t`; the first rate if `t` precedes the series
+    pub fn rate_at(&self, t: DateTime<Utc>) -> Option<f64> {
+        let idx = self.rates.partition_point(|(ts, _)| *ts <= t);
+        let point = if idx == 0 {
+            self.rates.first()
+        } else {
+            self.rates.get(idx - 1)
+        };
+        point.map(|(_, r)| *r)
+    }
+
+    /// Convert an equity curve into home currency
+    pub fn convert_curve(&self, curve: &[(DateTime<Utc>, f64)]) -> Vec<(DateTime<Utc>, f64)> {
+        curve
+            .iter()
+            .filter_map(|(t, v)| self.rate_at(*t).map(|r| (*t, v * r)))
+            .collect()
+    }
+}
+
+/// Converts values of symbols quoted in different currencies into one home currency
+#[derive(Debug, Clone)]
+pub struct QuoteConverter {
+    home: String,
+    /// Home currency per unit of each foreign quote currency
+    rates: HashMap<String, FxSeries>,
+}
+
+impl QuoteConverter {
+    pub fn new(home: impl Into<String>) -> Self {
+        Self {
+            home: home.into(),
+            rates: HashMap::new(),
+        }
+    }
+
+    /// Use `series` for symbols quoted in `quote`
+    pub fn with_series(mut self, quote: &str, series: FxSeries) -> Self {
+        self.rates.insert(quote.to_string(), series);
+        self
+    }
+
+    /// Record the latest rate of `quote`
+    pub fn push_rate(&mut self, quote: &str, t: DateTime<Utc>, rate: f64) {
+        self.rates
+            .entry(quote.to_string())
+            .or_default()
+            .push(t, rate);
+    }
+
+    pub fn home(&self) -> &str {
+        &self.home
+    }
+
+    /// Symbol of the rate series for `quote` (`USDT` → `USDTINR`)
+    pub fn fx_symbol(&self, quote: &str) -> String {
+        format!("{}{}", quote, self.home)
+    }
+
+    /// Quote currencies of `symbols` other than the home one, deduplicated
+    pub fn foreign_quotes<'a>(
+        &self,
+        symbols: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<&'static str> {
+        let mut quotes: Vec<&'static str> = symbols
+            .into_iter()
+            .filter_map(quote_currency)
+            .filter(|quote| *quote != self.home)
+            .collect();
+        quotes.sort_unstable();
+        quotes.dedup();
+        quotes
+    }
+
+    /// `value`, quoted in `symbol`'s currency, in the home currency at `t`
+    ///
+    /// Symbols quoted in the home currency, or in none recognised, pass
+    /// through unchanged. `None` when the quote currency has no rate yet.
+    pub fn to_home(&self, symbol: &str, value: f64, t: DateTime<Utc>) -> Option<f64> {
+        match self.foreign_quote(symbol) {
+            None => Some(value),
+            Some(quote) => Some(value * self.rates.get(quote)?.rate_at(t)?),
+        }
+    }
+
+    /// Quote currency of `symbol` if it isn't the home one
+    pub fn foreign_quote(&self, symbol: &str) -> Option<&'static str> {
+        quote_currency(symbol).filter(|quote| *quote != self.home)
+    }
+
+    /// Home cash plus cash held in foreign quote currencies, in the home
+    /// currency at `t`; `None` when a held currency has no rate yet
+    pub fn cash_to_home(
+        &self,
+        home_cash: f64,
+        quote_cash: &HashMap<String, f64>,
+        t: DateTime<Utc>,
+    ) -> Option<f64> {
+        quote_cash
+            .iter()
+            .try_fold(home_cash, |total, (quote, amount)| {
+                Some(total + amount * self.rates.get(quote)?.rate_at(t)?)
+            })
+    }
+}
+
+/// Latest price of `fx_symbol` (e.g. `USDTINR`) on `exchange`
+#[cfg(feature = "native")]
+pub async fn fetch_rate(
+    exchange: &impl crate::exchange::ExchangeClient,
+    fx_symbol: &str,
+) -> anyhow::Result<f64> {
+    let ticker = exchange.get_ticker(fx_symbol).await?;
+    anyhow::ensure!(
+        ticker.last.is_finite() && ticker.last > 0.0,
+        "{} has no usable price ({})",
+        fx_symbol,
+        ticker.last
+    );
+    Ok(ticker.last)
+}
+
+/// Return/risk summary of an equity curve
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CurveStats {
+    /// Total return, percent
+    pub total_return: f64,
+    /// Max peak-to-trough drawdown, percent
+    pub max_drawdown: f64,
+    /// Annualized Sharpe (per-bar returns, 5% risk-free, same as backtest metrics)
+    pub sharpe_ratio: f64,
+    /// Annualized return over max drawdown
+    pub calmar_ratio: f64,
+}
+
+impl CurveStats {
+    pub fn from_curve(curve: &[(DateTime<Utc>, f64)]) -> Self {
+        let (Some(first), Some(last)) = (curve.first(), curve.last()) else {
+            return Self::default();
+        };
+        if first.1 <= 0.0 {
+            return Self::default();
+        }
+        let total_return = (last.1 / first.1 - 1.0) * 100.0;
+
+        let returns: Vec<f64> = curve
+            .windows(2)
+            .map(|w| (w[1].1 - w[0].1) / w[0].1)
+            .collect();
+        let sharpe_ratio = if returns.len() > 1 {
+            let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+            let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>()
+                / (returns.len() - 1) as f64;
+            let std = variance.sqrt();
+            if std > 0.0 {
+                (mean - 0.05 / 365.0) / std * 365.0_f64.sqrt()
+            } else {
+                0.0
+            }
+        } else {
+            0.0
+        };
+
+        let mut peak = first.1;
+        let mut max_dd: f64 = 0.0;
+        for (_, equity) in curve {
+            peak = peak.max(*equity);
+            max_dd = max_dd.max((peak - equity) / peak);
+        }
+
+        let years = (last.0 - first.0).num_days() as f64 / 365.0;
+        let calmar_ratio = if max_dd > 0.0 && years > 0.0 {
+            ((1.0 + total_return / 100.0).powf(1.0 / years) - 1.0) / max_dd
+        } else {
+            0.0
+        };
+
+        Self {
+            total_return,
+            max_drawdown: max_dd * 100.0,
+            sharpe_ratio,
+            calmar_ratio,
+        }
+    }
+}
+
+/// Performance in the asset currency and converted to the home currency
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HedgedView {
+    /// As traded (e.g. USDT)
+    pub asset: CurveStats,
+    /// Unhedged, converted at each bar's rate (e.g. INR)
+    pub home: CurveStats,
+    /// Rate move over the curve's span, percent
+    pub fx_change: f64,
+}
+
+impl HedgedView {
+    pub fn new(curve: &[(DateTime<Utc>, f64)], fx: &FxSeries) -> Self {
+        let fx_change = match (
+            curve.first().and_then(|(t, _)| fx.rate_at(*t)),
+            curve.last().and_then(|(t, _)| fx.rate_at(*t)),
+        ) {
+            (Some(start), Some(end)) => (end / start - 1.0) * 100.0,
+            _ => 0.0,
+        };
+        Self {
+            asset: CurveStats::from_curve(curve),
+            home: CurveStats::from_curve(&fx.convert_curve(curve)),
+            fx_change,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_rate_lookup_uses_last_known_rate() {
+        let t0 = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let fx = FxSeries::new(vec![
+            (t0 + Duration::days(2), 84.0),
+            (t0, 83.0),
+            (t0 + Duration::days(1), -1.0),
+        ]);
+        assert_eq!(fx.rate_at(t0 - Duration::days(5)), Some(83.0));
+        assert_eq!(fx.rate_at(t0 + Duration::hours(36)), Some(83.0));
+        assert_eq!(fx.rate_at(t0 + Duration::days(9)), Some(84.0));
+        assert!(FxSeries::default().rate_at(t0).is_none());
+    }
+
+    #[test]
+    fn test_mixed_quotes_convert_to_home() {
+        let t0 = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(quote_currency("ETHUSDT"), Some("USDT"));
+        assert_eq!(quote_currency("BTCINR"), Some("INR"));
+        assert_eq!(quote_currency("USDT"), None);
+
+        let mut fx = QuoteConverter::new("INR");
+        assert_eq!(
+            fx.foreign_quotes(["BTCINR", "ETHUSDT", "SOLUSDT"]),
+            ["USDT"]
+        );
+        assert_eq!(fx.fx_symbol("USDT"), "USDTINR");
+        // No rate yet: foreign values can't be added up
+        assert_eq!(fx.to_home("ETHUSDT", 10.0, t0), None);
+
+        fx.push_rate("USDT", t0, 84.0);
+        fx.push_rate("USDT", t0 - Duration::days(1), 90.0);
+        let total: f64 = [("BTCINR", 1_000.0), ("ETHUSDT", 10.0)]
+            .iter()
+            .map(|(symbol, value)| fx.to_home(symbol, *value, t0).unwrap())
+            .sum();
+        assert_eq!(total, 1_840.0);
+    }
+
+    #[test]
+    fn test_cash_in_mixed_currencies_converts_to_home() {
+        let t0 = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut fx = QuoteConverter::new("INR");
+        // BTCINR profits land in INR cash, ETHUSDT profits stay in USDT
+        assert_eq!(fx.foreign_quote("BTCINR"), None);
+        assert_eq!(fx.foreign_quote("ETHUSDT"), Some("USDT"));
+
+        let home_cash = 100_000.0 + 1_000.0;
+        let quote_cash = HashMap::from([("USDT".to_string(), 10.0)]);
+        assert_eq!(fx.cash_to_home(home_cash, &quote_cash, t0), None);
+
+        fx.push_rate("USDT", t0, 84.0);
+        assert_eq!(fx.cash_to_home(home_cash, &quote_cash, t0), Some(101_840.0));
+        assert_eq!(
+            fx.cash_to_home(home_cash, &HashMap::new(), t0),
+            Some(home_cash)
+        );
+    }
+
+    #[test]
+    fn test_hedged_view_adds_currency_move() {
+        let t0 = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let curve: Vec<_> = (0..=10)
+            .map(|i| (t0 + Duration::days(i), 1000.0 + 10.0 * i as f64))
+            .collect();
+        // Rupee weakens 5% over the period
+        let fx = FxSeries::new(
+            (0..=10)
+                .map(|i| (t0 + Duration::days(i), 80.0 * (1.0 + 0.005 * i as f64)))
+                .collect(),
+        );
+
+        let view = HedgedView::new(&curve, &fx);
+        assert!((view.asset.total_return - 10.0).abs() < 1e-9);
+        assert!((view.fx_change - 5.0).abs() < 1e-9);
+        // (1.10 * 1.05 - 1) = 15.5%
+        assert!((view.home.total_return - 15.5).abs() < 1e-9);
+        assert_eq!(view.asset.max_drawdown, 0.0);
+    }
+}
@@ -0,0 +1,383 @@
+//! Portfolio hedge via an offsetting short on a correlated asset
+//!
+//! When the book leans too far long while the market turns down, a
+//! [`Hedger`] shorts a liquid proxy (BTC) sized by the portfolio's beta to it,
+//! then resizes or unwinds the leg as exposure and regime change. See
+//! [`HedgeConfig`] for the rules.
+//!
+//! The leg is margined like a perp: opening it costs only the taker fee, and
+//! its P&L is realized into cash when it shrinks or closes. Closed slices are
+//! reported as ordinary [`Trade`]s with a `hedge` rationale.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::HedgeConfig;
+use crate::types::{add_fee, FeeLedger, FeeType};
+use crate::{Money, Side, Symbol, Trade};
+
+/// Beta of `asset` to `market` from simple returns over their common tail
+///
+/// Both series are closes, newest last; `None` when fewer than two returns
+/// overlap or the market didn't move.
+pub fn beta(asset: &[f64], market: &[f64]) -> Option<f64> {
+    let n = asset.len().min(market.len());
+    if n < 3 {
+        return None;
+    }
+    let returns = |closes: &[f64]| -> Vec<f64> {
+        closes[closes.len() - n..]
+            .windows(2)
+            .map(|w| if w[0] > 0.0 { w[1] / w[0] - 1.0 } else { 0.0 })
+            .collect()
+    };
+    let (a, m) = (returns(asset), returns(market));
+    let mean = |xs: &[f64]| xs.iter().sum::<f64>() / xs.len() as f64;
+    let (mean_a, mean_m) = (mean(&a), mean(&m));
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (ra, rm) in a.iter().zip(&m) {
+        cov += (ra - mean_a) * (rm - mean_m);
+        var += (rm - mean_m).powi(2);
+    }
+    (var > 0.0).then(|| cov / var)
+}
+
+/// Latest close sits below its EMA
+pub fn deteriorating(closes: &[f64], ema_period: usize) -> bool {
+    let ema = crate::indicators::ema(closes, ema_period);
+    match (closes.last(), ema.last().copied().flatten()) {
+        (Some(close), Some(ema)) => *close < ema,
+        _ => false,
+    }
+}
+
+/// Portfolio exposure the hedge is sized against (hedge leg excluded)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Exposure {
+    pub long_notional: f64,
+    pub short_notional: f64,
+    /// Sum of signed notional x beta to the hedge asset
+    pub beta_notional: f64,
+    pub equity: f64,
+}
+
+impl Exposure {
+    /// Exposure of `(signed quantity, closes)` positions, betas over the last
+    /// `beta_lookback` returns against `market` closes (1.0 when unmeasurable)
+    pub fn measure<'a>(
+        positions: impl IntoIterator<Item = (f64, &'a [f64])>,
+        market: &[f64],
+        beta_lookback: usize,
+        equity: f64,
+    ) -> Self {
+        let tail = |closes: &'a [f64]| &closes[closes.len().saturating_sub(beta_lookback + 1)..];
+        let market = &market[market.len().saturating_sub(beta_lookback + 1)..];
+        let mut exposure = Self {
+            equity,
+            ..Default::default()
+        };
+        for (quantity, closes) in positions {
+            let Some(&price) = closes.last() else {
+                continue;
+            };
+            let beta = beta(tail(closes), market).unwrap_or(1.0);
+            exposure.add(quantity, price, beta);
+        }
+        exposure
+    }
+
+    /// Add a position (`quantity` negative for shorts)
+    pub fn add(&mut self, quantity: f64, price: f64, beta: f64) {
+        let notional = quantity * price;
+        if notional >= 0.0 {
+            self.long_notional += notional;
+        } else {
+            self.short_notional -= notional;
+        }
+        self.beta_notional += notional * beta;
+    }
+
+    /// Longs minus shorts as a fraction of equity
+    pub fn net(&self) -> f64 {
+        if self.equity <= 0.0 {
+            return 0.0;
+        }
+        (self.long_notional - self.short_notional) / self.equity
+    }
+}
+
+/// Open hedge short
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HedgeLeg {
+    pub quantity: f64,
+    pub entry_price: f64,
+    /// Taker fees paid on the open quantity, not yet attributed to a trade
+    pub entry_fees: f64,
+    pub opened_at: DateTime<Utc>,
+}
+
+/// One adjustment of the hedge leg
+#[derive(Debug, Clone)]
+pub struct HedgeFill {
+    /// Quantity shorted (negative when covering)
+    pub quantity: f64,
+    pub price: f64,
+    /// Taker fee on this fill, paid from cash
+    pub fee: f64,
+    /// P&L on the covered quantity, credited to cash
+    pub realized_pnl: f64,
+    /// Record of the covered slice
+    pub trade: Option<Trade>,
+}
+
+impl HedgeFill {
+    /// Net effect on cash
+    pub fn cash_delta(&self) -> f64 {
+        self.realized_pnl - self.fee
+    }
+}
+
+/// Hedge activity over a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HedgeStats {
+    /// Times the hedge went from flat to short
+    pub opened: usize,
+    /// Resizes of an open leg
+    pub resized: usize,
+    /// Bars ending with the leg open
+    pub hedged_bars: usize,
+    /// Realized P&L after fees
+    pub net_pnl: f64,
+}
+
+/// Hedge state machine shared by the backtester and the live loop
+#[derive(Debug, Clone)]
+pub struct Hedger {
+    config: HedgeConfig,
+    fee_rate: f64,
+    leg: Option<HedgeLeg>,
+    stats: HedgeStats,
+}
+
+impl Hedger {
+    pub fn new(config: HedgeConfig, fee_rate: f64) -> Self {
+        Self {
+            config,
+            fee_rate,
+            leg: None,
+            stats: HedgeStats::default(),
+        }
+    }
+
+    /// Resume with a leg restored from saved state
+    pub fn with_leg(mut self, leg: Option<HedgeLeg>) -> Self {
+        self.leg = leg;
+        self
+    }
+
+    pub fn config(&self) -> &HedgeConfig {
+        &self.config
+    }
+
+    pub fn leg(&self) -> Option<&HedgeLeg> {
+        self.leg.as_ref()
+    }
+
+    pub fn stats(&self) -> HedgeStats {
+        self.stats
+    }
+
+    /// Mark-to-market P&L of the open leg
+    pub fn unrealized_pnl(&self, price: f64) -> f64 {
+        self.leg
+            .map_or(0.0, |leg| (leg.entry_price - price) * leg.quantity)
+    }
+
+    /// Short quantity the hedge should hold right now
+    pub fn target_quantity(&self, exposure: &Exposure, price: f64, deteriorating: bool) -> f64 {
+        if price <= 0.0 || !deteriorating {
+            return 0.0;
+        }
+        let threshold = if self.leg.is_some() {
+            self.config.unwind_below
+        } else {
+            self.config.max_net_exposure
+        };
+        if exposure.net() <= threshold {
+            return 0.0;
+        }
+        let notional = (exposure.beta_notional.max(0.0) * self.config.hedge_ratio)
+            .min(exposure.equity.max(0.0) * self.config.max_hedge_pct);
+        notional / price
+    }
+
+    /// Move the leg toward its target; `None` when nothing trades
+    pub fn step(
+        &mut self,
+        exposure: &Exposure,
+        price: f64,
+        deteriorating: bool,
+        now: DateTime<Utc>,
+    ) -> Option<HedgeFill> {
+        let target = self.target_quantity(exposure, price, deteriorating);
+        let current = self.leg.map_or(0.0, |leg| leg.quantity);
+        let fill = if current <= 0.0 {
+            (target > 0.0).then(|| {
+                self.stats.opened += 1;
+                self.short(target, price, now)
+            })
+        } else if target <= 0.0 {
+            self.cover(current, price, now)
+        } else if (target - current).abs() > current * self.config.rebalance_band {
+            self.stats.resized += 1;
+            if target > current {
+                Some(self.short(target - current, price, now))
+            } else {
+                self.cover(current - target, price, now)
+            }
+        } else {
+            None
+        };
+        if self.leg.is_some() {
+            self.stats.hedged_bars += 1;
+        }
+        fill
+    }
+
+    /// Cover the whole leg (end of run, shutdown)
+    pub fn close(&mut self, price: f64, now: DateTime<Utc>) -> Option<HedgeFill> {
+        let quantity = self.leg?.quantity;
+        self.cover(quantity, price, now)
+    }
+
+    fn short(&mut self, quantity: f64, price: f64, now: DateTime<Utc>) -> HedgeFill {
+        let fee = quantity * price * self.fee_rate;
+        self.leg = Some(match self.leg {
+            Some(leg) => {
+                let total = leg.quantity + quantity;
+                HedgeLeg {
+                    quantity: total,
+                    entry_price: (leg.entry_price * leg.quantity + price * quantity) / total,
+                    entry_fees: leg.entry_fees + fee,
+                    opened_at: leg.opened_at,
+                }
+            }
+            None => HedgeLeg {
+                quantity,
+                entry_price: price,
+                entry_fees: fee,
+                opened_at: now,
+            },
+        });
+        self.stats.net_pnl -= fee;
+        HedgeFill {
+            quantity,
+            price,
+            fee,
+            realized_pnl: 0.0,
+            trade: None,
+        }
+    }
+
+    fn cover(&mut self, quantity: f64, price: f64, now: DateTime<Utc>) -> Option<HedgeFill> {
+        let leg = self.leg?;
+        let quantity = quantity.min(leg.quantity);
+        let share = quantity / leg.quantity;
+        let fee = quantity * price * self.fee_rate;
+        let entry_fee = leg.entry_fees * share;
+        let pnl = (leg.entry_price - price) * quantity;
+
+        let mut fees = FeeLedger::new();
+        add_fee(&mut fees, FeeType::Taker, Money::from_f64(entry_fee + fee));
+        let trade = Trade::from_f64(
+            Symbol::new(&self.config.symbol),
+            Side::Sell,
+            leg.entry_price,
+            price,
+            quantity,
+            leg.opened_at,
+            now,
+            pnl,
+            entry_fee + fee,
+            pnl - entry_fee - fee,
+        )
+        .with_fees(fees)
+        .with_rationale(Some("hedge".to_string()));
+
+        self.leg = (quantity < leg.quantity).then_some(HedgeLeg {
+            quantity: leg.quantity - quantity,
+            entry_fees: leg.entry_fees - entry_fee,
+            ..leg
+        });
+        self.stats.net_pnl += pnl - fee;
+        Some(HedgeFill {
+            quantity: -quantity,
+            price,
+            fee,
+            realized_pnl: pnl,
+            trade: Some(trade),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_beta_of_levered_series() {
+        let market = [100.0, 102.0, 99.0, 103.0, 101.0];
+        let levered: Vec<f64> = market
+            .windows(2)
+            .scan(50.0, |p, w| {
+                *p *= 1.0 + 2.0 * (w[1] / w[0] - 1.0);
+                Some(*p)
+            })
+            .collect();
+        let mut asset = vec![50.0];
+        asset.extend(levered);
+        assert!((beta(&asset, &market).unwrap() - 2.0).abs() < 1e-9);
+        assert_eq!(beta(&[1.0, 1.0], &[1.0, 1.0]), None);
+        assert_eq!(beta(&[1.0, 2.0, 3.0], &[5.0, 5.0, 5.0]), None);
+    }
+
+    #[test]
+    fn test_hedge_opens_resizes_and_unwinds() {
+        let t = |h| Utc.with_ymd_and_hms(2024, 1, 1, h, 0, 0).unwrap();
+        let mut hedger = Hedger::new(HedgeConfig::default(), 0.001);
+        let mut exposure = Exposure {
+            equity: 10_000.0,
+            ..Default::default()
+        };
+        exposure.add(80.0, 100.0, 1.5);
+
+        // Heavy but the market is fine: no hedge
+        assert!(hedger.step(&exposure, 200.0, false, t(0)).is_none());
+
+        // 80% net long in a downtrend: 0.5 x 12k beta notional, capped at 50% of equity
+        let open = hedger.step(&exposure, 200.0, true, t(1)).unwrap();
+        assert!((open.quantity - 25.0).abs() < 1e-9);
+        assert!((open.cash_delta() + 5.0).abs() < 1e-9);
+
+        // Below the open threshold but above unwind: hold
+        let mut lighter = Exposure {
+            equity: 10_000.0,
+            ..Default::default()
+        };
+        lighter.add(42.0, 100.0, 1.5);
+        let resize = hedger.step(&lighter, 180.0, true, t(2)).unwrap();
+        assert!(resize.quantity < 0.0);
+        let leg = *hedger.leg().unwrap();
+        assert!((leg.quantity - 3150.0 / 180.0).abs() < 1e-9);
+
+        // Regime recovers: the rest unwinds as a hedge trade
+        let close = hedger.step(&lighter, 190.0, false, t(3)).unwrap();
+        let trade = close.trade.unwrap();
+        assert_eq!(trade.rationale.as_deref(), Some("hedge"));
+        assert!((close.realized_pnl - (200.0 - 190.0) * leg.quantity).abs() < 1e-9);
+        assert!(hedger.leg().is_none());
+        let stats = hedger.stats();
+        assert_eq!((stats.opened, stats.resized, stats.hedged_bars), (1, 1, 2));
+    }
+}
@@ -0,0 +1,164 @@
+//! Idle mode and resource reporting for the live loop
+//!
+//! On a high-timeframe strategy almost nothing happens between bar closes,
+//! yet a Raspberry Pi or small VPS pays for every poll. With
+//! [`IdleModeConfig`] set, the live loop decides per cycle whether it is in
+//! the active window after a bar close or idle, and sleeps accordingly:
+//!
+//! ```text
+//! bar close ─┬─ active_secs ─┬──────────── idle ────────────┬─ next close
+//!            │ every active_ │ every idle_poll_secs (capped  │
+//!            │ poll_secs     │ at the next close)            │
+//! ```
+//!
+//! Idle cycles skip non-essential work (config reloads, reconciliation,
+//! orderbook recording, metric summaries). [`ResourceUsage::sample`] reads the
+//! process's CPU time and resident memory so each cycle's cost can be logged.
+
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+use crate::config::IdleModeConfig;
+
+/// Phase of the current bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Within `active_secs` of the last bar close
+    Active,
+    Idle,
+}
+
+/// `"15m"` -> 900; `None` for unknown units
+pub fn timeframe_secs(tf: &str) -> Option<u64> {
+    let tf = tf.trim();
+    let split = tf.len().checked_sub(1)?;
+    let (n, unit) = tf.split_at(split);
+    let n: u64 = n.parse().ok()?;
+    let unit_secs = match unit {
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return None,
+    };
+    Some(n * unit_secs)
+}
+
+impl IdleModeConfig {
+    /// Whether a strategy on `bar_secs` bars goes idle at all
+    pub fn applies_to(&self, bar_secs: u64) -> bool {
+        timeframe_secs(&self.min_timeframe).is_some_and(|min| bar_secs >= min)
+    }
+
+    /// Bars are assumed to close on UTC multiples of their length
+    pub fn phase(&self, now: DateTime<Utc>, bar_secs: u64) -> Phase {
+        if since_close(now, bar_secs) < self.active_secs {
+            Phase::Active
+        } else {
+            Phase::Idle
+        }
+    }
+
+    /// Time to sleep before the next cycle
+    ///
+    /// Idle sleeps never run past the next bar close, so the active window
+    /// always starts on time.
+    pub fn next_poll(&self, now: DateTime<Utc>, bar_secs: u64) -> Duration {
+        let elapsed = since_close(now, bar_secs);
+        let secs = if elapsed < self.active_secs {
+            self.active_poll_secs
+        } else {
+            self.idle_poll_secs.min(bar_secs.max(1) - elapsed)
+        };
+        Duration::from_secs(secs.max(1))
+    }
+}
+
+/// Seconds since the last bar close
+fn since_close(now: DateTime<Utc>, bar_secs: u64) -> u64 {
+    now.timestamp().rem_euclid(bar_secs.max(1) as i64) as u64
+}
+
+/// Process CPU time and resident memory at one point in time
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResourceUsage {
+    /// User + system CPU time since process start
+    pub cpu_time: Duration,
+    pub rss_bytes: u64,
+}
+
+impl ResourceUsage {
+    /// Read from `/proc/self`; `None` where that isn't available
+    pub fn sample() -> Option<Self> {
+        let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        Some(Self {
+            cpu_time: parse_cpu_time(&stat)?,
+            rss_bytes: parse_rss_bytes(&status)?,
+        })
+    }
+
+    /// CPU time spent since `earlier`
+    pub fn cpu_since(&self, earlier: &ResourceUsage) -> Duration {
+        self.cpu_time.saturating_sub(earlier.cpu_time)
+    }
+}
+
+/// Kernel clock ticks per second used by `/proc/<pid>/stat` on Linux
+const CLOCK_TICKS_PER_SEC: u64 = 100;
+
+/// utime + stime (fields 14 and 15) from `/proc/self/stat`
+fn parse_cpu_time(stat: &str) -> Option<Duration> {
+    // The command name may contain spaces; fields restart after its ')'
+    let rest = &stat[stat.rfind(')')? + 1..];
+    let mut fields = rest.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some(Duration::from_millis(
+        (utime + stime) * 1_000 / CLOCK_TICKS_PER_SEC,
+    ))
+}
+
+/// `VmRSS:   12345 kB` from `/proc/self/status`
+fn parse_rss_bytes(status: &str) -> Option<u64> {
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_phase_and_next_poll() {
+        let idle = IdleModeConfig::default();
+        let hour = timeframe_secs("1h").unwrap();
+        assert!(idle.applies_to(timeframe_secs("4h").unwrap()));
+        assert!(!idle.applies_to(timeframe_secs("15m").unwrap()));
+
+        let close = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let active = close + chrono::Duration::seconds(60);
+        assert_eq!(idle.phase(active, hour), Phase::Active);
+        assert_eq!(idle.next_poll(active, hour), Duration::from_secs(30));
+
+        let quiet = close + chrono::Duration::minutes(20);
+        assert_eq!(idle.phase(quiet, hour), Phase::Idle);
+        assert_eq!(idle.next_poll(quiet, hour), Duration::from_secs(900));
+
+        // Idle sleep stops at the next close
+        let late = close + chrono::Duration::minutes(55);
+        assert_eq!(idle.next_poll(late, hour), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn test_parse_proc_fields() {
+        let stat = "1234 (crypto strat) S 1 1234 1234 0 -1 4194560 500 0 0 0 250 50 0 0 20 0 4 0";
+        assert_eq!(parse_cpu_time(stat), Some(Duration::from_secs(3)));
+        let status = "Name:\tcrypto\nVmRSS:\t   20480 kB\nThreads:\t4\n";
+        assert_eq!(parse_rss_bytes(status), Some(20 * 1024 * 1024));
+        assert_eq!(timeframe_secs("1d"), Some(86_400));
+        assert_eq!(timeframe_secs("x"), None);
+    }
+}
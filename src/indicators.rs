@@ -44,6 +44,12 @@ pub fn make_data_item(open: f64, high: f64, low: f64, close: f64, volume: f64) -
         .unwrap()
 }
 
+/// Indicator output with its warm-up gaps as NaN, for array consumers
+/// (the Python and WASM bindings)
+pub fn to_nan(values: Vec<Option<f64>>) -> Vec<f64> {
+    values.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect()
+}
+
 // =============================================================================
 // Moving Averages
 // =============================================================================
@@ -779,6 +785,7 @@ pub struct IndicatorCache {
     ema_cache: HashMap<(usize, usize), Vec<Option<f64>>>,
     atr_cache: HashMap<(usize, usize), Vec<Option<f64>>>,
     rsi_cache: HashMap<(usize, usize), Vec<Option<f64>>>,
+    adx_cache: HashMap<(usize, usize), Vec<Option<f64>>>,
 }
 
 impl IndicatorCache {
@@ -788,6 +795,7 @@ impl IndicatorCache {
             ema_cache: HashMap::new(),
             atr_cache: HashMap::new(),
             rsi_cache: HashMap::new(),
+            adx_cache: HashMap::new(),
         }
     }
 
@@ -825,11 +833,25 @@ impl IndicatorCache {
             .or_insert_with(|| rsi(values, period))
     }
 
+    pub fn get_adx(
+        &mut self,
+        high: &[f64],
+        low: &[f64],
+        close: &[f64],
+        period: usize,
+    ) -> &Vec<Option<f64>> {
+        let key = (close.len(), period);
+        self.adx_cache
+            .entry(key)
+            .or_insert_with(|| adx(high, low, close, period))
+    }
+
     pub fn clear(&mut self) {
         self.sma_cache.clear();
         self.ema_cache.clear();
         self.atr_cache.clear();
         self.rsi_cache.clear();
+        self.adx_cache.clear();
     }
 }
 
@@ -1152,4 +1174,12 @@ mod tests {
         assert!(upper[last_idx].unwrap() > middle[last_idx].unwrap());
         assert!(middle[last_idx].unwrap() > lower[last_idx].unwrap());
     }
+
+    #[test]
+    fn test_to_nan_keeps_positions() {
+        let out = to_nan(vec![None, Some(1.5), None]);
+        assert_eq!(out.len(), 3);
+        assert!(out[0].is_nan() && out[2].is_nan());
+        assert_eq!(out[1], 1.5);
+    }
 }
@@ -0,0 +1,585 @@
+//! Order intents and exactly-once fill recording
+//!
+//! A crash between an exchange fill and the state write used to leave recovery
+//! guessing: replaying the order double-counted it, skipping it lost the fill.
+//! Every order now follows a write-ahead sequence:
+//!
+//! 1. An [`OrderIntent`] is written synchronously before the order is booked
+//!    or sent ([`stage`]); its ID doubles as the client order ID.
+//! 2. Each fill carries an idempotency key ([`FillRecord::fill_id`]) and is
+//!    committed in one transaction with the intent's new status and, when it
+//!    reduces a position, the [`TradeRecord`] keyed by the same fill.
+//! 3. At startup, [`recover`] matches intents still pending or submitted
+//!    against the exchange: fills it never recorded are applied once, intents
+//!    the exchange never saw are abandoned.
+//!
+//! Unique indexes on `fills.fill_id` and `trades.fill_id` turn a replayed fill
+//! into a no-op, so recovery can run any number of times.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use tracing::{info, warn};
+
+use crate::coindcx::CoinDCXClient;
+use crate::exchange::ExchangeClient;
+use crate::oms::{Order, OrderType};
+use crate::reconcile::{self, ExchangeTrade};
+use crate::state_manager::{
+    FillRecord, IntentStatus, OrderIntent, SqliteStateManager, TradeRecord,
+};
+use crate::state_writer::StateEvent;
+
+/// Quantities closer than this count as equal
+const QTY_TOLERANCE: f64 = 1e-8;
+
+/// Client order ID for a new intent
+///
+/// Order IDs restart at 1 with every process; the timestamp keeps intents
+/// unique across restarts.
+pub fn new_intent_id(order_id: u64) -> String {
+    format!("cs-{}-{}", Utc::now().timestamp_millis(), order_id)
+}
+
+/// Client order ID of an order placed in the cycle that started at `cycle_ms`
+///
+/// The same cycle, symbol and order always give the same ID, so a placement
+/// retried after a timeout carries the ID the exchange may already hold.
+/// Cycle start times keep IDs unique across restarts; the symbol hash groups
+/// a cycle's orders by market.
+pub fn cycle_client_id(cycle_ms: i64, symbol: &str, order_id: u64) -> String {
+    format!("cs-{}-{:08x}-{}", cycle_ms, symbol_hash(symbol), order_id)
+}
+
+/// 32-bit FNV-1a of `symbol`, stable across builds and platforms
+fn symbol_hash(symbol: &str) -> u32 {
+    symbol.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// Key of a locally executed fill: the intent plus its cumulative filled quantity
+pub fn local_fill_id(intent_id: &str, filled_quantity: f64) -> String {
+    format!("{}:{}", intent_id, filled_quantity)
+}
+
+/// Key of a fill reported by the exchange
+pub fn exchange_fill_id(trade_id: &str) -> String {
+    format!("exchange:{}", trade_id)
+}
+
+/// Write `order`'s intent, assigning it a client order ID if it has none
+///
+/// Must succeed before the order is booked or sent.
+pub fn stage(state: &SqliteStateManager, order: &mut Order) -> Result<OrderIntent> {
+    let intent_id = order
+        .client_id
+        .get_or_insert_with(|| new_intent_id(order.id))
+        .clone();
+    let intent = OrderIntent {
+        intent_id,
+        symbol: order.symbol.as_str().to_string(),
+        side: format!("{:?}", order.side).to_lowercase(),
+        order_type: match order.order_type {
+            OrderType::Limit => "limit",
+            OrderType::Stop => "stop",
+            OrderType::StopLimit => "stop_limit",
+            OrderType::Market => "market",
+        }
+        .to_string(),
+        quantity: order.quantity.to_f64(),
+        limit_price: order.limit_price.map(|p| p.to_f64()),
+        status: IntentStatus::Pending,
+        exchange_order_id: None,
+        created_at: reconcile::fill_timestamp(Utc::now()),
+    };
+    state.record_intent(&intent)?;
+    Ok(intent)
+}
+
+/// Send a staged `order` to the exchange; the exchange's order ID
+///
+/// A placement that fails may still have reached the exchange (a timeout or
+/// a 5xx), so the client order ID is looked up before anything is resent.
+/// If the exchange holds the order it is used as is; only an order the
+/// exchange reports it doesn't have is sent again, once, under the same ID.
+/// When the lookup fails too, the original error is returned.
+pub async fn submit(exchange: &impl ExchangeClient, order: &Order) -> Result<String> {
+    let error = match exchange.place_order(order).await {
+        Ok(id) => return Ok(id),
+        Err(e) => e,
+    };
+    let Some(client_id) = order.client_id.as_deref() else {
+        return Err(error);
+    };
+    match exchange.find_order(order.symbol.as_str(), client_id).await {
+        Ok(Some(id)) => {
+            info!(
+                "Order {} already on the exchange as {} ({:#}), not resent",
+                client_id, id, error
+            );
+            Ok(id)
+        }
+        Ok(None) => {
+            warn!(
+                "Order {} never reached the exchange ({:#}), resending",
+                client_id, error
+            );
+            exchange.place_order(order).await
+        }
+        Err(_) => Err(error),
+    }
+}
+
+/// Trade row for a fill that reduced or closed a position, keyed by that fill
+pub fn closing_trade(
+    fill: &FillRecord,
+    position_side: &str,
+    entry_price: f64,
+    entry_time: DateTime<Utc>,
+    quantity: f64,
+    gross_pnl: f64,
+) -> TradeRecord {
+    let net_pnl = gross_pnl - fill.fee;
+    let notional = entry_price * quantity;
+    TradeRecord {
+        id: None,
+        symbol: fill.symbol.clone(),
+        side: position_side.to_string(),
+        quantity,
+        entry_price,
+        exit_price: fill.price,
+        entry_time: entry_time.to_rfc3339(),
+        exit_time: fill.timestamp.clone(),
+        gross_pnl,
+        fees: fill.fee,
+        tax: 0.0,
+        net_pnl,
+        pnl_pct: if notional > 0.0 {
+            net_pnl / notional * 100.0
+        } else {
+            0.0
+        },
+        status: "closed".to_string(),
+        exit_reason: "fill".to_string(),
+        strategy_signal: "flat".to_string(),
+        market_state_entry: "unknown".to_string(),
+        market_state_exit: "unknown".to_string(),
+        atr_at_entry: 0.0,
+        stop_loss: 0.0,
+        take_profit: 0.0,
+        risk_reward_actual: 0.0,
+        metadata: Default::default(),
+        fill_id: fill.fill_id.clone(),
+    }
+}
+
+/// What recovery decided for one open intent
+#[derive(Debug, Clone)]
+pub struct Resolution {
+    pub intent: OrderIntent,
+    pub status: IntentStatus,
+    pub exchange_order_id: Option<String>,
+    /// Exchange fills missing from the state database, to be applied exactly once
+    pub missing_fills: Vec<FillRecord>,
+}
+
+impl Resolution {
+    /// Events that record the outcome (one transaction via [`SqliteStateManager::write_batch`])
+    pub fn events(&self) -> Vec<StateEvent> {
+        let mut events: Vec<StateEvent> = self
+            .missing_fills
+            .iter()
+            .cloned()
+            .map(StateEvent::Fill)
+            .collect();
+        events.push(StateEvent::Intent {
+            intent_id: self.intent.intent_id.clone(),
+            status: self.status,
+            exchange_order_id: self.exchange_order_id.clone(),
+        });
+        events
+    }
+}
+
+/// Decide an open intent's fate from the recorded fills and the exchange's view
+///
+/// `exchange` is the exchange order ID and its trades, or `None` when the
+/// exchange has no order for the intent (never submitted, or a paper run).
+/// Exchange trades already covered by a recorded fill, under their exchange
+/// key or the intent's local key, are not returned again.
+pub fn resolve(
+    intent: &OrderIntent,
+    recorded: &[FillRecord],
+    exchange: Option<(&str, &[ExchangeTrade])>,
+) -> Resolution {
+    let keys: HashSet<&str> = recorded
+        .iter()
+        .filter_map(|f| f.fill_id.as_deref())
+        .collect();
+    let prefix = format!("{}:", intent.intent_id);
+    let local_qty: f64 = recorded
+        .iter()
+        .filter(|f| f.fill_id.as_deref().is_some_and(|k| k.starts_with(&prefix)))
+        .map(|f| f.quantity)
+        .sum();
+
+    let Some((order_id, trades)) = exchange else {
+        let status = if local_qty + QTY_TOLERANCE >= intent.quantity {
+            IntentStatus::Filled
+        } else if local_qty > QTY_TOLERANCE {
+            // Partly filled, and the remainder is resting nowhere
+            IntentStatus::Cancelled
+        } else {
+            IntentStatus::Abandoned
+        };
+        return Resolution {
+            intent: intent.clone(),
+            status,
+            exchange_order_id: intent.exchange_order_id.clone(),
+            missing_fills: Vec::new(),
+        };
+    };
+
+    let mut trades: Vec<&ExchangeTrade> = trades.iter().collect();
+    trades.sort_by_key(|t| t.timestamp);
+    let mut covered = local_qty;
+    let mut exchange_qty = 0.0;
+    let mut missing_fills = Vec::new();
+    for t in trades {
+        exchange_qty += t.quantity;
+        let key = exchange_fill_id(&t.trade_id);
+        if keys.contains(key.as_str()) {
+            continue;
+        }
+        if covered + QTY_TOLERANCE >= t.quantity {
+            // Booked under the intent's local key before the crash
+            covered -= t.quantity;
+            continue;
+        }
+        missing_fills.push(FillRecord {
+            id: None,
+            order_id: order_id.to_string(),
+            symbol: t.symbol.clone(),
+            side: t.side.clone(),
+            quantity: t.quantity,
+            price: t.price,
+            fee: t.fee,
+            timestamp: reconcile::fill_timestamp(t.timestamp),
+            paper: false,
+            fill_id: Some(key),
+        });
+    }
+
+    Resolution {
+        intent: intent.clone(),
+        status: if exchange_qty + QTY_TOLERANCE >= intent.quantity {
+            IntentStatus::Filled
+        } else {
+            IntentStatus::Submitted
+        },
+        exchange_order_id: Some(order_id.to_string()),
+        missing_fills,
+    }
+}
+
+/// Start of the trade history that can hold fills for intents created at
+/// `created_at`, with a minute's slack for the exchange's clock
+fn history_start(created_at: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(created_at)
+        .map(|t| t.with_timezone(&Utc) - chrono::Duration::minutes(1))
+        .unwrap_or_else(|_| Utc::now() - chrono::Duration::days(7))
+}
+
+/// Resolve every open intent at startup and record the outcomes
+///
+/// Intents in `resting` belong to orders restored to the book and stay open.
+/// With no exchange (paper mode) the remaining intents never reached one. An
+/// exchange lookup that fails for another reason than an unknown order leaves
+/// the intent open for the next start. The returned resolutions' missing
+/// fills still have to be applied to positions.
+pub async fn recover(
+    state: &SqliteStateManager,
+    exchange: Option<&CoinDCXClient>,
+    resting: &HashSet<String>,
+) -> Result<Vec<Resolution>> {
+    let open: Vec<OrderIntent> = state
+        .load_open_intents()?
+        .into_iter()
+        .filter(|i| !resting.contains(&i.intent_id))
+        .collect();
+    if open.is_empty() {
+        return Ok(Vec::new());
+    }
+    let since = open
+        .iter()
+        .map(|i| i.created_at.as_str())
+        .min()
+        .unwrap_or_default()
+        .to_string();
+    let recorded = state.load_fills(&since)?;
+    info!("🧾 Resolving {} open order intent(s)...", open.len());
+
+    let history: Vec<ExchangeTrade> = match exchange {
+        Some(client) => client
+            .get_trade_history(None, history_start(&since), Utc::now())
+            .await?
+            .iter()
+            .map(ExchangeTrade::from)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut resolutions = Vec::new();
+    for intent in &open {
+        let resolution = match exchange {
+            None => resolve(intent, &recorded, None),
+            Some(client) => match client
+                .get_order_status_by_client_id(&intent.intent_id)
+                .await
+            {
+                Ok(order) => {
+                    let trades: Vec<ExchangeTrade> = history
+                        .iter()
+                        .filter(|t| t.order_id == order.id)
+                        .cloned()
+                        .collect();
+                    resolve(intent, &recorded, Some((&order.id, &trades)))
+                }
+                Err(e) if e.to_string().to_lowercase().contains("not found") => {
+                    resolve(intent, &recorded, None)
+                }
+                Err(e) => {
+                    warn!(
+                        "⚠️  Could not look up intent {} on the exchange: {}",
+                        intent.intent_id, e
+                    );
+                    continue;
+                }
+            },
+        };
+        state.write_batch(&resolution.events())?;
+        info!(
+            "  └─ {} {} {} {:.6}: {} ({} missing fill(s))",
+            intent.intent_id,
+            intent.side.to_uppercase(),
+            intent.symbol,
+            intent.quantity,
+            resolution.status.as_str(),
+            resolution.missing_fills.len()
+        );
+        resolutions.push(resolution);
+    }
+    Ok(resolutions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oms::TimeInForce;
+    use crate::{Side, Symbol};
+    use chrono::TimeZone;
+
+    fn temp_state(name: &str) -> (SqliteStateManager, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("intents_{}_{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let backup = path.with_extension("json");
+        (
+            SqliteStateManager::new(&path, &backup, false).unwrap(),
+            path,
+        )
+    }
+
+    fn staged(state: &SqliteStateManager, qty: f64) -> (Order, OrderIntent) {
+        let mut order = Order::from_f64(
+            Symbol::new("BTCINR"),
+            Side::Buy,
+            OrderType::Market,
+            qty,
+            None,
+            None,
+            TimeInForce::GTC,
+            None,
+        );
+        let intent = stage(state, &mut order).unwrap();
+        (order, intent)
+    }
+
+    fn exchange_trade(id: &str, qty: f64) -> ExchangeTrade {
+        ExchangeTrade {
+            trade_id: id.to_string(),
+            order_id: "ex-1".to_string(),
+            symbol: "BTCINR".to_string(),
+            side: "buy".to_string(),
+            quantity: qty,
+            price: 100.0,
+            fee: 0.1,
+            timestamp: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+        }
+    }
+
+    fn fill_count(state: &SqliteStateManager) -> usize {
+        state.load_fills("").unwrap().len()
+    }
+
+    #[test]
+    fn test_cycle_client_ids_are_deterministic() {
+        let id = cycle_client_id(1_700_000_000_000, "BTCINR", 7);
+        assert_eq!(id, cycle_client_id(1_700_000_000_000, "BTCINR", 7));
+        assert!(id.starts_with("cs-1700000000000-"));
+        assert!(id.ends_with("-7"));
+        assert_ne!(id, cycle_client_id(1_700_000_000_000, "ETHINR", 7));
+        assert_ne!(id, cycle_client_id(1_700_000_060_000, "BTCINR", 7));
+
+        // An ID set by the caller is kept when the intent is written
+        let (state, path) = temp_state("cycle_id");
+        let mut order = Order::from_f64(
+            Symbol::new("BTCINR"),
+            Side::Sell,
+            OrderType::Market,
+            1.0,
+            None,
+            None,
+            TimeInForce::GTC,
+            Some(id.clone()),
+        );
+        assert_eq!(stage(&state, &mut order).unwrap().intent_id, id);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_submit_is_not_sent_twice() {
+        use crate::mock_exchange::{MockExchangeClient, OrderOutcome};
+
+        let exchange = MockExchangeClient::new().with_price("BTCINR", 100.0);
+        let order = |id: &str| {
+            Order::from_f64(
+                Symbol::new("BTCINR"),
+                Side::Buy,
+                OrderType::Market,
+                1.0,
+                None,
+                None,
+                TimeInForce::GTC,
+                Some(id.to_string()),
+            )
+        };
+
+        // The exchange took the order but the response was lost: found, not resent
+        exchange.script_order(OrderOutcome::Lost { price: None });
+        let id = submit(&exchange, &order("cs-1-abc-1")).await.unwrap();
+        assert_eq!(id, "mock-1");
+        assert_eq!(exchange.calls("place_order"), 1);
+        assert_eq!(exchange.calls("find_order"), 1);
+
+        // The exchange never saw it: sent again under the same client ID
+        exchange.fail_next("place_order", "503 service unavailable");
+        let id = submit(&exchange, &order("cs-1-abc-2")).await.unwrap();
+        assert_eq!(id, "mock-2");
+        assert_eq!(exchange.calls("place_order"), 3);
+
+        // Outcome unknown: the original error, nothing resent
+        exchange.fail_next("place_order", "operation timed out");
+        exchange.fail_next("find_order", "operation timed out");
+        assert!(submit(&exchange, &order("cs-1-abc-3")).await.is_err());
+        assert_eq!(exchange.calls("place_order"), 4);
+
+        let orders = exchange.orders();
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[1].order.client_id.as_deref(), Some("cs-1-abc-2"));
+    }
+
+    #[test]
+    fn test_crash_before_submit_abandons_intent() {
+        let (state, path) = temp_state("abandon");
+        let (order, intent) = staged(&state, 1.0);
+        assert_eq!(order.client_id.as_deref(), Some(intent.intent_id.as_str()));
+
+        // Crash: intent on disk, order never reached the book or exchange
+        let open = state.load_open_intents().unwrap();
+        assert_eq!(open.len(), 1);
+        let resolution = resolve(&open[0], &[], None);
+        assert_eq!(resolution.status, IntentStatus::Abandoned);
+        state.write_batch(&resolution.events()).unwrap();
+        assert!(state.load_open_intents().unwrap().is_empty());
+        assert_eq!(fill_count(&state), 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_crash_after_exchange_fill_applies_it_once() {
+        let (state, path) = temp_state("missing");
+        let (_, intent) = staged(&state, 1.0);
+        state
+            .update_intent(&intent.intent_id, IntentStatus::Submitted, Some("ex-1"))
+            .unwrap();
+
+        // Crash: the exchange filled in two parts, nothing was written locally
+        let trades = vec![exchange_trade("t1", 0.4), exchange_trade("t2", 0.6)];
+        for _ in 0..2 {
+            // Recovery replayed (e.g. a second crash before the next checkpoint)
+            let open = state.load_intents().unwrap();
+            let recorded = state.load_fills("").unwrap();
+            let resolution = resolve(&open[0], &recorded, Some(("ex-1", &trades)));
+            state.write_batch(&resolution.events()).unwrap();
+            for fill in &resolution.missing_fills {
+                let trade = closing_trade(fill, "sell", 90.0, Utc::now(), fill.quantity, 1.0);
+                state.record_trade(&trade).unwrap();
+                state.record_trade(&trade).unwrap();
+            }
+        }
+
+        assert_eq!(fill_count(&state), 2);
+        assert!(state.load_open_intents().unwrap().is_empty());
+        assert_eq!(state.trades_for_fill("exchange:t1").unwrap(), 1);
+        assert_eq!(state.trades_for_fill("exchange:t2").unwrap(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_fill_recorded_before_crash_is_not_double_counted() {
+        let (state, path) = temp_state("recorded");
+        let (mut order, intent) = staged(&state, 1.0);
+
+        // Fill and intent status committed together, then the process died
+        order.filled_quantity = order.quantity;
+        let fill = FillRecord {
+            id: None,
+            order_id: order.id.to_string(),
+            symbol: "BTCINR".to_string(),
+            side: "buy".to_string(),
+            quantity: 1.0,
+            price: 100.0,
+            fee: 0.1,
+            timestamp: reconcile::fill_timestamp(Utc::now()),
+            paper: false,
+            fill_id: Some(local_fill_id(&intent.intent_id, 1.0)),
+        };
+        state
+            .write_batch(&[
+                StateEvent::Fill(fill.clone()),
+                StateEvent::Fill(fill.clone()),
+            ])
+            .unwrap();
+        assert!(!state.record_fill(&fill).unwrap());
+        assert_eq!(fill_count(&state), 1);
+
+        // The exchange reports the same fill under its own trade ID
+        let recorded = state.load_fills("").unwrap();
+        let trades = vec![exchange_trade("t9", 1.0)];
+        let resolution = resolve(&intent, &recorded, Some(("ex-1", &trades)));
+        assert!(resolution.missing_fills.is_empty());
+        assert_eq!(resolution.status, IntentStatus::Filled);
+
+        // Without an exchange the local fill alone settles it
+        assert_eq!(
+            resolve(&intent, &recorded, None).status,
+            IntentStatus::Filled
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
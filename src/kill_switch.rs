@@ -0,0 +1,139 @@
+//! Operator kill switch
+//!
+//! Checked at the top of every live cycle. Triggers, in order:
+//!
+//! - `<dir>/KILL`: all strategies
+//! - `<dir>/KILL.<strategy>`: one strategy (e.g. `KILL.volatility_regime`)
+//! - the env var (default `CRYPTO_KILL`): `1`/`all`, `flatten`, or a
+//!   comma-separated list of strategy names, each optionally `name:flatten`
+//!
+//! A triggered switch stops new entries. It also flattens open positions when
+//! `kill_switch.flatten` is set, or when the file contains `flatten`. Deleting
+//! the file (or clearing the variable) resumes trading.
+
+use std::fs;
+use std::path::Path;
+
+use crate::config::KillSwitchConfig;
+
+/// Name of the global kill file
+pub const KILL_FILE: &str = "KILL";
+
+/// What the kill switch asks the live loop to do
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum KillState {
+    #[default]
+    Off,
+    /// Stop opening positions; manage existing ones as usual
+    Halt { source: String },
+    /// Stop opening positions and close everything
+    Flatten { source: String },
+}
+
+impl KillState {
+    pub fn is_active(&self) -> bool {
+        !matches!(self, KillState::Off)
+    }
+
+    pub fn flatten(&self) -> bool {
+        matches!(self, KillState::Flatten { .. })
+    }
+
+    fn new(source: String, flatten: bool) -> Self {
+        if flatten {
+            KillState::Flatten { source }
+        } else {
+            KillState::Halt { source }
+        }
+    }
+}
+
+impl KillSwitchConfig {
+    /// Current state for `strategy`, reading the kill files and env var
+    pub fn check(&self, strategy: &str) -> KillState {
+        let env = std::env::var(&self.env_var).ok();
+        self.check_with(strategy, env.as_deref())
+    }
+
+    /// Same as [`check`](Self::check) with the env var's value passed in
+    pub fn check_with(&self, strategy: &str, env: Option<&str>) -> KillState {
+        let dir = Path::new(&self.dir);
+        for name in [KILL_FILE.to_string(), format!("{}.{}", KILL_FILE, strategy)] {
+            let path = dir.join(&name);
+            if path.exists() {
+                // Unreadable file still counts: the operator asked to stop
+                let contents = fs::read_to_string(&path).unwrap_or_default();
+                let flatten = self.flatten || contents.to_lowercase().contains("flatten");
+                return KillState::new(path.display().to_string(), flatten);
+            }
+        }
+
+        let Some(value) = env.map(str::trim).filter(|v| !v.is_empty() && *v != "0") else {
+            return KillState::Off;
+        };
+        let source = format!("${}={}", self.env_var, value);
+        for entry in value.split(',').map(|e| e.trim().to_lowercase()) {
+            let (target, mode) = entry.split_once(':').unwrap_or((&entry, ""));
+            let flatten = self.flatten || mode == "flatten";
+            match target {
+                "1" | "all" | "true" => return KillState::new(source, flatten),
+                "flatten" => return KillState::new(source, true),
+                t if t == strategy.to_lowercase() => return KillState::new(source, flatten),
+                _ => {}
+            }
+        }
+        KillState::Off
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(dir: &Path) -> KillSwitchConfig {
+        KillSwitchConfig {
+            dir: dir.display().to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_kill_files() {
+        let dir = std::env::temp_dir().join(format!("kill_switch_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let cfg = config(&dir);
+        assert_eq!(cfg.check_with("volatility_regime", None), KillState::Off);
+
+        fs::write(dir.join("KILL.momentum_scalper"), "").unwrap();
+        assert!(!cfg.check_with("volatility_regime", None).is_active());
+        let state = cfg.check_with("momentum_scalper", None);
+        assert!(state.is_active() && !state.flatten());
+
+        fs::write(dir.join("KILL"), "flatten\n").unwrap();
+        assert!(cfg.check_with("volatility_regime", None).flatten());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_env_trigger() {
+        let cfg = config(Path::new("/nonexistent-kill-dir"));
+        assert_eq!(cfg.check_with("quick_flip", Some("0")), KillState::Off);
+        assert_eq!(cfg.check_with("quick_flip", Some("")), KillState::Off);
+        assert!(cfg.check_with("quick_flip", Some("all")).is_active());
+        assert!(cfg.check_with("quick_flip", Some("flatten")).flatten());
+
+        let env = Some("range_breakout, quick_flip:flatten");
+        assert!(cfg.check_with("range_breakout", env).is_active());
+        assert!(!cfg.check_with("range_breakout", env).flatten());
+        assert!(cfg.check_with("quick_flip", env).flatten());
+        assert!(!cfg.check_with("volatility_regime", env).is_active());
+
+        let always_flatten = KillSwitchConfig {
+            flatten: true,
+            ..cfg
+        };
+        assert!(always_flatten.check_with("quick_flip", Some("1")).flatten());
+    }
+}
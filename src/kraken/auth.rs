@@ -0,0 +1,109 @@
+//! Authentication utilities for Kraken API
+//!
+//! Implements the `API-Sign` header as per the official Kraken REST
+//! documentation: HMAC-SHA512 over the URI path followed by
+//! SHA256(nonce + POST data), keyed with the base64-decoded secret.
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256, Sha512};
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Generate the `API-Sign` header for a private endpoint
+///
+/// `post_data` is the URL-encoded form body, which must include the same
+/// `nonce`.
+///
+/// # Example
+///
+/// ```
+/// use crypto_strategies::kraken::auth::sign_request;
+///
+/// let body = "nonce=1616492376594&pair=XBTUSD";
+/// let signature = sign_request("/0/private/Balance", 1616492376594, body, "c2VjcmV0");
+/// assert!(signature.is_ok());
+/// ```
+pub fn sign_request(path: &str, nonce: u64, post_data: &str, secret: &str) -> Result<String> {
+    let key = STANDARD
+        .decode(secret)
+        .context("Kraken API secret is not valid base64")?;
+
+    let mut sha = Sha256::new();
+    sha.update(nonce.to_string().as_bytes());
+    sha.update(post_data.as_bytes());
+
+    let mut mac = HmacSha512::new_from_slice(&key).expect("HMAC can take key of any size");
+    mac.update(path.as_bytes());
+    mac.update(&sha.finalize());
+    Ok(STANDARD.encode(mac.finalize().into_bytes()))
+}
+
+/// API credentials container
+#[derive(Debug, Clone)]
+pub struct Credentials {
+    api_key: String,
+    api_secret: String,
+}
+
+impl Credentials {
+    /// Create new credentials from API key and (base64) secret
+    pub fn new(api_key: impl Into<String>, api_secret: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            api_secret: api_secret.into(),
+        }
+    }
+
+    /// Create credentials from environment variables
+    ///
+    /// Looks for `KRAKEN_API_KEY` and `KRAKEN_API_SECRET`
+    pub fn from_env() -> Result<Self, std::env::VarError> {
+        let api_key = std::env::var("KRAKEN_API_KEY")?;
+        let api_secret = std::env::var("KRAKEN_API_SECRET")?;
+        Ok(Self::new(api_key, api_secret))
+    }
+
+    /// Get the API key
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+
+    /// Get the API secret
+    pub fn api_secret(&self) -> &str {
+        &self.api_secret
+    }
+
+    /// Sign a private request
+    pub fn sign(&self, path: &str, nonce: u64, post_data: &str) -> Result<String> {
+        sign_request(path, nonce, post_data, &self.api_secret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_request_matches_documented_example() {
+        // Example from Kraken's REST authentication guide
+        let secret = "kQH5HW/8p1uGOVjbgWA7FunAmGO8lsSUXNsu3eow76sz84Q18fWxnyRzBHCd3pd5nE9qa99HAZtuZuj6F1huXg==";
+        let nonce = 1616492376594;
+        let body =
+            "nonce=1616492376594&ordertype=limit&pair=XBTUSD&price=37500&type=buy&volume=1.25";
+
+        let signature = sign_request("/0/private/AddOrder", nonce, body, secret).unwrap();
+
+        assert_eq!(
+            signature,
+            "4/dpxb3iT4tp/ZCVEwSnEsLxx0bqyhLpdfOpc6fn7OR8+UClSV5n9E6aSS8MPtnRfp32bAb0nmbRn6H8ndwLUQ=="
+        );
+    }
+
+    #[test]
+    fn test_invalid_secret_is_an_error() {
+        assert!(sign_request("/0/private/Balance", 1, "nonce=1", "not base64!").is_err());
+    }
+}
@@ -0,0 +1,522 @@
+//! Kraken API Client
+//!
+//! HTTP client for the Kraken spot exchange with the same resilience as
+//! the CoinDCX client:
+//! - Automatic retry with exponential backoff
+//! - Rate limiting with priority-based shedding (order calls first)
+//! - Circuit breaker pattern for fault tolerance
+//!
+//! # Example
+//!
+//! ```no_run
+//! use crypto_strategies::kraken::KrakenClient;
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let client = KrakenClient::new("api_key", "api_secret");
+//!
+//!     let ticker = client.get_ticker("BTCEUR").await?;
+//!     println!("BTC/EUR price: {:?}", ticker.last_price_f64());
+//!
+//!     for (asset, balance) in client.get_balances().await? {
+//!         println!("{}: {}", asset, balance.balance);
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+use super::auth::Credentials;
+use super::to_kraken_interval;
+use super::types::*;
+use crate::common::{
+    CircuitBreaker, CircuitBreakerConfig, NetworkConfig, RateLimiter, RateLimiterConfig,
+    RequestPriority,
+};
+use crate::symbols::{SymbolMapper, Venue};
+
+/// Base URL for the Kraken REST API
+pub const API_BASE_URL: &str = "https://api.kraken.com";
+
+/// Convert a config symbol to a Kraken pair
+///
+/// Kraken names bitcoin `XBT` and dogecoin `XDG`; see [`crate::symbols::kraken_pair`].
+pub fn symbol_to_pair(symbol: &str) -> String {
+    crate::symbols::kraken_pair(symbol)
+}
+
+/// Convert a Kraken asset code to the usual ticker (`XXBT` -> `BTC`, `ZEUR` -> `EUR`)
+pub fn asset_name(asset: &str) -> String {
+    // Assets listed before 2018 carry an X (crypto) or Z (fiat) prefix
+    const LEGACY: &[&str] = &[
+        "XXBT", "XETH", "XXRP", "XLTC", "XXLM", "XXDG", "XETC", "XXMR", "XZEC", "XREP", "XMLN",
+        "ZUSD", "ZEUR", "ZGBP", "ZCAD", "ZJPY", "ZAUD", "ZCHF",
+    ];
+    let asset = if LEGACY.contains(&asset) {
+        &asset[1..]
+    } else {
+        asset
+    };
+    match asset {
+        "XBT" => "BTC".to_string(),
+        "XDG" => "DOGE".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Client configuration
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Maximum number of retry attempts
+    pub max_retries: u32,
+    /// Request timeout duration
+    pub timeout: Duration,
+    /// Rate limiter configuration
+    pub rate_limiter: RateLimiterConfig,
+    /// Circuit breaker configuration
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Proxy, extra root certificates and connect timeout
+    pub network: NetworkConfig,
+    /// Venue identifiers of config symbols, with overrides
+    pub symbols: SymbolMapper,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            timeout: Duration::from_secs(30),
+            // Public endpoints allow about one call per second
+            rate_limiter: RateLimiterConfig::default().with_rate(1),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            network: NetworkConfig::default(),
+            symbols: SymbolMapper::default(),
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Set maximum retry attempts
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Set request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set proxy, extra root certificates and connect timeout
+    pub fn with_network(mut self, network: NetworkConfig) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Map symbols through `symbols`, e.g. for listings with overridden IDs
+    pub fn with_symbols(mut self, symbols: SymbolMapper) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Set rate limit (requests per second)
+    pub fn with_rate_limit(mut self, requests_per_second: usize) -> Self {
+        self.rate_limiter = self.rate_limiter.with_rate(requests_per_second);
+        self
+    }
+
+    /// Set circuit breaker failure threshold
+    pub fn with_circuit_breaker_threshold(mut self, threshold: u32) -> Self {
+        self.circuit_breaker = self.circuit_breaker.with_failure_threshold(threshold);
+        self
+    }
+}
+
+/// Kraken Exchange API Client
+///
+/// Provides methods to interact with the Kraken API including:
+/// - Public endpoints (ticker, OHLC)
+/// - Authenticated endpoints (orders, balances)
+#[derive(Clone)]
+pub struct KrakenClient {
+    credentials: Credentials,
+    http_client: Client,
+    circuit_breaker: Arc<Mutex<CircuitBreaker>>,
+    rate_limiter: RateLimiter,
+    max_retries: u32,
+    /// Last nonce sent; Kraken rejects nonces that don't increase
+    nonce: Arc<AtomicU64>,
+    symbols: SymbolMapper,
+    #[cfg(feature = "fault-injection")]
+    faults: crate::common::FaultInjector,
+}
+
+impl KrakenClient {
+    /// Create a new client with API credentials
+    pub fn new(api_key: impl Into<String>, api_secret: impl Into<String>) -> Self {
+        Self::with_config(api_key, api_secret, ClientConfig::default())
+    }
+
+    /// Create a new client with custom configuration
+    pub fn with_config(
+        api_key: impl Into<String>,
+        api_secret: impl Into<String>,
+        config: ClientConfig,
+    ) -> Self {
+        let builder = Client::builder()
+            .timeout(config.timeout)
+            .pool_max_idle_per_host(10)
+            .pool_idle_timeout(Duration::from_secs(90));
+        let http_client = config
+            .network
+            .apply(builder)
+            .and_then(|b| b.build())
+            .expect("invalid network settings (see NetworkConfig::validate)");
+
+        Self {
+            credentials: Credentials::new(api_key, api_secret),
+            http_client,
+            circuit_breaker: Arc::new(Mutex::new(CircuitBreaker::new(config.circuit_breaker))),
+            rate_limiter: RateLimiter::new(config.rate_limiter),
+            max_retries: config.max_retries,
+            nonce: Arc::new(AtomicU64::new(0)),
+            symbols: config.symbols,
+            #[cfg(feature = "fault-injection")]
+            faults: crate::common::FaultInjector::default(),
+        }
+    }
+
+    /// Symbol mapping used for every venue identifier
+    pub fn symbol_mapper(&self) -> &SymbolMapper {
+        &self.symbols
+    }
+
+    /// Replace attempts with queued faults (see [`crate::common::faults`])
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: crate::common::FaultInjector) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    /// Create a client from environment variables
+    ///
+    /// Expects `KRAKEN_API_KEY` and `KRAKEN_API_SECRET`
+    pub fn from_env() -> Result<Self> {
+        let credentials = Credentials::from_env()
+            .context("Failed to load Kraken credentials from environment")?;
+        Ok(Self::with_config(
+            credentials.api_key(),
+            credentials.api_secret(),
+            ClientConfig::default(),
+        ))
+    }
+
+    /// Milliseconds since the epoch, bumped past the previous nonce if the
+    /// clock hasn't moved
+    fn next_nonce(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        let previous = self
+            .nonce
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+                Some(now.max(last + 1))
+            })
+            .unwrap_or_default();
+        now.max(previous + 1)
+    }
+
+    /// Execute a request with retry logic, rate limiting, and circuit breaker
+    ///
+    /// `Low`/`Normal` requests fail with [`RequestShed`](crate::common::RequestShed)
+    /// instead of waiting when the rate limit is under pressure.
+    async fn execute_with_retry<F, Fut, T>(
+        &self,
+        op: &str,
+        priority: RequestPriority,
+        operation: F,
+    ) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+        T: serde::de::DeserializeOwned,
+    {
+        {
+            let mut cb = self.circuit_breaker.lock().await;
+            if !cb.can_attempt() {
+                return Err(anyhow!("Circuit breaker is open, rejecting request"));
+            }
+        }
+
+        self.rate_limiter.acquire_for(op, priority).await?;
+
+        let mut last_error = None;
+
+        for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                let delay = Duration::from_secs(2u64.pow(attempt - 1));
+                tracing::debug!("Retrying after {}ms", delay.as_millis());
+                sleep(delay).await;
+            }
+
+            #[cfg(feature = "fault-injection")]
+            let outcome = match self.faults.take(op) {
+                Some(fault) => fault.into_result(),
+                None => operation().await,
+            };
+            #[cfg(not(feature = "fault-injection"))]
+            let outcome = operation().await;
+
+            match outcome {
+                Ok(result) => {
+                    let mut cb = self.circuit_breaker.lock().await;
+                    cb.record_success();
+                    return Ok(result);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "{} failed (attempt {}/{}): {}",
+                        op,
+                        attempt + 1,
+                        self.max_retries + 1,
+                        e
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        {
+            let mut cb = self.circuit_breaker.lock().await;
+            cb.record_failure();
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!("Request failed after retries")))
+    }
+
+    /// Make a public GET request and unwrap the response envelope
+    async fn public_get<R>(&self, endpoint: &str, query: &[(&str, String)]) -> Result<R>
+    where
+        R: serde::de::DeserializeOwned,
+    {
+        let url = format!("{}{}", API_BASE_URL, endpoint);
+        let response = self
+            .http_client
+            .get(&url)
+            .query(query)
+            .send()
+            .await
+            .context("Failed to send request")?;
+        self.rate_limiter
+            .observe_response(response.status(), response.headers())
+            .await;
+
+        let status = response.status();
+        let text = response.text().await.context("Failed to read response")?;
+
+        if !status.is_success() {
+            return Err(anyhow!("API error ({}): {}", status, text));
+        }
+
+        let envelope: Response<R> =
+            serde_json::from_str(&text).context("Failed to parse response")?;
+        envelope.into_result()
+    }
+
+    /// Make an authenticated POST request and unwrap the response envelope
+    ///
+    /// A fresh nonce is drawn per call, so retries are never rejected as
+    /// replays.
+    async fn authenticated_post<T, R>(&self, endpoint: &str, params: &T) -> Result<R>
+    where
+        T: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        let url = format!("{}{}", API_BASE_URL, endpoint);
+        let nonce = self.next_nonce();
+        let params = serde_urlencoded::to_string(params).context("Failed to encode request")?;
+        let body = if params.is_empty() {
+            format!("nonce={}", nonce)
+        } else {
+            format!("nonce={}&{}", nonce, params)
+        };
+        let signature = self.credentials.sign(endpoint, nonce, &body)?;
+
+        let response = self
+            .http_client
+            .post(&url)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .header("API-Key", self.credentials.api_key())
+            .header("API-Sign", signature)
+            .body(body)
+            .send()
+            .await
+            .context("Failed to send request")?;
+        self.rate_limiter
+            .observe_response(response.status(), response.headers())
+            .await;
+
+        let status = response.status();
+        let text = response.text().await.context("Failed to read response")?;
+
+        if !status.is_success() {
+            return Err(anyhow!("API error ({}): {}", status, text));
+        }
+
+        let envelope: Response<R> =
+            serde_json::from_str(&text).context("Failed to parse response")?;
+        envelope.into_result()
+    }
+
+    // ==================== PUBLIC ENDPOINTS ====================
+
+    /// Get ticker for a symbol (`BTCEUR` or a Kraken pair)
+    pub async fn get_ticker(&self, symbol: &str) -> Result<Ticker> {
+        self.get_ticker_with_priority(symbol, RequestPriority::Normal)
+            .await
+    }
+
+    /// Get ticker with an explicit rate-limit priority
+    pub async fn get_ticker_with_priority(
+        &self,
+        symbol: &str,
+        priority: RequestPriority,
+    ) -> Result<Ticker> {
+        let pair = self.symbols.id(symbol, Venue::Kraken);
+        self.execute_with_retry("ticker", priority, || {
+            let pair = pair.clone();
+            let this = self.clone();
+
+            async move {
+                // Keyed by Kraken's canonical name (`XXBTZEUR`), not the one we sent
+                let tickers: HashMap<String, Ticker> = this
+                    .public_get("/0/public/Ticker", &[("pair", pair.clone())])
+                    .await?;
+                tickers
+                    .into_values()
+                    .next()
+                    .ok_or_else(|| anyhow!("Ticker not found for {}", pair))
+            }
+        })
+        .await
+    }
+
+    /// Get OHLC bars, oldest first
+    ///
+    /// Kraken serves at most the latest 720 bars of an interval; `since`
+    /// (Unix seconds) only trims that window.
+    pub async fn get_ohlc(
+        &self,
+        symbol: &str,
+        interval: &str,
+        since: Option<i64>,
+    ) -> Result<Vec<Ohlc>> {
+        let minutes = to_kraken_interval(interval)
+            .with_context(|| format!("Kraken does not serve {} candles", interval))?;
+        let mut query = vec![
+            ("pair", self.symbols.id(symbol, Venue::Kraken)),
+            ("interval", minutes.to_string()),
+        ];
+        if let Some(since) = since {
+            query.push(("since", since.to_string()));
+        }
+
+        let rows: Vec<Vec<serde_json::Value>> = self
+            .execute_with_retry("ohlc", RequestPriority::Normal, || {
+                let query = query.clone();
+                let this = self.clone();
+
+                async move {
+                    // `{"<pair>": [[...], ...], "last": <id>}`
+                    let result: HashMap<String, serde_json::Value> =
+                        this.public_get("/0/public/OHLC", &query).await?;
+                    let rows = result
+                        .into_iter()
+                        .find(|(key, _)| key != "last")
+                        .map(|(_, rows)| rows)
+                        .ok_or_else(|| anyhow!("OHLC response has no bars"))?;
+                    serde_json::from_value(rows).context("Failed to parse OHLC bars")
+                }
+            })
+            .await?;
+
+        Ok(rows.iter().filter_map(|row| Ohlc::from_raw(row)).collect())
+    }
+
+    // ==================== AUTHENTICATED ENDPOINTS ====================
+
+    /// Get balances keyed by Kraken asset code (see [`asset_name`])
+    pub async fn get_balances(&self) -> Result<HashMap<String, Balance>> {
+        self.execute_with_retry("balances", RequestPriority::Normal, || {
+            let this = self.clone();
+
+            async move { this.authenticated_post("/0/private/BalanceEx", &()).await }
+        })
+        .await
+    }
+
+    /// Place a new order
+    pub async fn place_order(&self, order: &OrderRequest) -> Result<AddOrderResult> {
+        let order = order.clone();
+        self.execute_with_retry("place_order", RequestPriority::Critical, || {
+            let ord = order.clone();
+            let this = self.clone();
+
+            async move { this.authenticated_post("/0/private/AddOrder", &ord).await }
+        })
+        .await
+    }
+
+    /// Cancel an order by transaction ID (or client order ID)
+    pub async fn cancel_order(&self, txid: &str) -> Result<()> {
+        let txid = txid.to_string();
+        self.execute_with_retry("cancel_order", RequestPriority::Critical, || {
+            let params = [("txid", txid.clone())];
+            let this = self.clone();
+
+            async move {
+                let _: CancelOrderResult = this
+                    .authenticated_post("/0/private/CancelOrder", &params)
+                    .await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_and_asset_mapping() {
+        assert_eq!(symbol_to_pair("BTCEUR"), "XBTEUR");
+        assert_eq!(symbol_to_pair("eth/usd"), "ETHUSD");
+        assert_eq!(symbol_to_pair("DOGEUSD"), "XDGUSD");
+        assert_eq!(asset_name("XXBT"), "BTC");
+        assert_eq!(asset_name("ZEUR"), "EUR");
+        assert_eq!(asset_name("XXDG"), "DOGE");
+        assert_eq!(asset_name("USDT"), "USDT");
+        assert_eq!(asset_name("XTZ"), "XTZ");
+    }
+
+    #[test]
+    fn test_nonces_increase() {
+        let client = KrakenClient::new("key", "c2VjcmV0");
+        let first = client.next_nonce();
+        let second = client.clone().next_nonce();
+        assert!(second > first);
+    }
+}
@@ -0,0 +1,88 @@
+//! Kraken Exchange API Library
+//!
+//! Spot client for Kraken, mirroring [`crate::coindcx`]: retries with
+//! exponential backoff, token-bucket rate limiting, a circuit breaker and
+//! typed request/response models. Kraken lists USD and EUR pairs, so the
+//! same backtest and live pipeline can run outside INR markets.
+//!
+//! # Quick Start
+//!
+//! ```no_run
+//! use crypto_strategies::kraken::{KrakenClient, OrderRequest, OrderSide};
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     // Expects KRAKEN_API_KEY and KRAKEN_API_SECRET
+//!     let client = KrakenClient::from_env()?;
+//!
+//!     let bars = client.get_ohlc("BTCEUR", "1h", None).await?;
+//!     println!("{} hourly bars", bars.len());
+//!
+//!     let order = OrderRequest::limit(OrderSide::Buy, "XBTEUR", 0.001, 25000.0);
+//!     let placed = client.place_order(&order).await?;
+//!     println!("Order placed: {:?}", placed.txid);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! # Modules
+//!
+//! - [`auth`]: `API-Sign` generation (HMAC-SHA512)
+//! - [`types`]: Request and response type definitions
+//! - [`client`]: Main API client implementation
+
+pub mod auth;
+pub mod client;
+pub mod types;
+
+pub use auth::Credentials;
+pub use client::{asset_name, symbol_to_pair, ClientConfig, KrakenClient, API_BASE_URL};
+pub use types::{
+    AddOrderResult, Balance, Ohlc, OrderRequest, OrderSide, OrderType, Response, Ticker,
+};
+
+use crate::capabilities::{ExchangeCapabilities, PrecisionRule};
+
+/// Intervals served by `/0/public/OHLC`
+pub const KRAKEN_INTERVALS: &[&str] = &["1m", "5m", "15m", "30m", "1h", "4h", "1d", "1w"];
+
+/// OHLC `interval` parameter (minutes) for a timeframe
+pub fn to_kraken_interval(timeframe: &str) -> Option<u32> {
+    match timeframe {
+        "1m" => Some(1),
+        "5m" => Some(5),
+        "15m" => Some(15),
+        "30m" => Some(30),
+        "1h" => Some(60),
+        "4h" => Some(240),
+        "1d" => Some(1440),
+        "1w" => Some(10080),
+        _ => None,
+    }
+}
+
+/// Spot USD/EUR markets: market and limit orders, per-pair step sizes
+pub const CAPABILITIES: ExchangeCapabilities = ExchangeCapabilities {
+    name: "kraken",
+    order_types: &[crate::oms::OrderType::Market, crate::oms::OrderType::Limit],
+    timeframes: KRAKEN_INTERVALS,
+    quote_currencies: &["USD", "EUR", "USDT"],
+    precision: PrecisionRule::PerMarket,
+    min_notional: None,
+    sandbox: false,
+    fill_reports: false,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_timeframe_has_an_interval() {
+        for tf in KRAKEN_INTERVALS {
+            assert!(to_kraken_interval(tf).is_some(), "{}", tf);
+        }
+        assert_eq!(to_kraken_interval("2h"), None);
+    }
+}
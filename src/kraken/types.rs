@@ -0,0 +1,248 @@
+//! Type definitions for Kraken API requests and responses
+//!
+//! Kraken wraps every response in `{"error": [...], "result": ...}` and
+//! sends prices and volumes as strings.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// Response envelope shared by every endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct Response<T> {
+    #[serde(default)]
+    pub error: Vec<String>,
+    pub result: Option<T>,
+}
+
+impl<T> Response<T> {
+    /// The result, or the errors Kraken reported
+    pub fn into_result(self) -> Result<T> {
+        if !self.error.is_empty() {
+            return Err(anyhow!("Kraken error: {}", self.error.join(", ")));
+        }
+        self.result
+            .ok_or_else(|| anyhow!("Kraken response has neither result nor error"))
+    }
+}
+
+/// Ticker of one pair from `/0/public/Ticker`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Ticker {
+    /// Ask `[price, whole lot volume, lot volume]`
+    #[serde(rename = "a")]
+    pub ask: Vec<String>,
+    /// Bid `[price, whole lot volume, lot volume]`
+    #[serde(rename = "b")]
+    pub bid: Vec<String>,
+    /// Last trade `[price, lot volume]`
+    #[serde(rename = "c")]
+    pub last: Vec<String>,
+    /// Volume `[today, last 24 hours]`
+    #[serde(rename = "v")]
+    pub volume: Vec<String>,
+}
+
+fn nth_f64(values: &[String], index: usize) -> Option<f64> {
+    values.get(index)?.parse().ok()
+}
+
+impl Ticker {
+    pub fn ask_f64(&self) -> Option<f64> {
+        nth_f64(&self.ask, 0)
+    }
+
+    pub fn bid_f64(&self) -> Option<f64> {
+        nth_f64(&self.bid, 0)
+    }
+
+    pub fn last_price_f64(&self) -> Option<f64> {
+        nth_f64(&self.last, 0)
+    }
+
+    /// Rolling 24h volume in the base asset
+    pub fn volume_f64(&self) -> Option<f64> {
+        nth_f64(&self.volume, 1)
+    }
+}
+
+/// One OHLC bar from `/0/public/OHLC`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ohlc {
+    /// Bar open time (Unix seconds)
+    pub time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub vwap: f64,
+    pub volume: f64,
+    pub count: u64,
+}
+
+impl Ohlc {
+    /// Parse from the raw `[time, open, high, low, close, vwap, volume, count]` row
+    pub fn from_raw(raw: &[serde_json::Value]) -> Option<Self> {
+        if raw.len() < 8 {
+            return None;
+        }
+
+        Some(Ohlc {
+            time: raw[0].as_i64()?,
+            open: raw[1].as_str()?.parse().ok()?,
+            high: raw[2].as_str()?.parse().ok()?,
+            low: raw[3].as_str()?.parse().ok()?,
+            close: raw[4].as_str()?.parse().ok()?,
+            vwap: raw[5].as_str()?.parse().ok()?,
+            volume: raw[6].as_str()?.parse().ok()?,
+            count: raw[7].as_u64()?,
+        })
+    }
+}
+
+/// Order side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+/// Order type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderType {
+    Market,
+    Limit,
+}
+
+/// Form body of `/0/private/AddOrder`
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderRequest {
+    pub pair: String,
+    #[serde(rename = "type")]
+    pub side: OrderSide,
+    pub ordertype: OrderType,
+    /// Quantity in the base asset
+    pub volume: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cl_ord_id: Option<String>,
+}
+
+impl OrderRequest {
+    /// Create a market order; `pair` is a Kraken pair (see [`super::symbol_to_pair`])
+    pub fn market(side: OrderSide, pair: impl Into<String>, volume: f64) -> Self {
+        Self {
+            pair: pair.into(),
+            side,
+            ordertype: OrderType::Market,
+            volume: volume.to_string(),
+            price: None,
+            cl_ord_id: None,
+        }
+    }
+
+    /// Create a limit order
+    pub fn limit(side: OrderSide, pair: impl Into<String>, volume: f64, price: f64) -> Self {
+        Self {
+            price: Some(price.to_string()),
+            ordertype: OrderType::Limit,
+            ..Self::market(side, pair, volume)
+        }
+    }
+
+    /// Set client order ID
+    pub fn with_client_order_id(mut self, id: impl Into<String>) -> Self {
+        self.cl_ord_id = Some(id.into());
+        self
+    }
+}
+
+/// Human-readable summary of an accepted order
+#[derive(Debug, Clone, Deserialize)]
+pub struct OrderDescription {
+    pub order: String,
+}
+
+/// Result of `/0/private/AddOrder`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddOrderResult {
+    pub descr: OrderDescription,
+    /// Transaction IDs of the placed order(s)
+    #[serde(default)]
+    pub txid: Vec<String>,
+}
+
+/// Result of `/0/private/CancelOrder`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CancelOrderResult {
+    /// Number of orders cancelled
+    pub count: u32,
+}
+
+/// One asset from `/0/private/BalanceEx`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Balance {
+    /// Total balance, including held funds
+    pub balance: String,
+    /// Funds held by open orders
+    #[serde(default)]
+    pub hold_trade: Option<String>,
+}
+
+impl Balance {
+    pub fn total_f64(&self) -> f64 {
+        self.balance.parse().unwrap_or_default()
+    }
+
+    pub fn held_f64(&self) -> f64 {
+        self.hold_trade
+            .as_deref()
+            .and_then(|h| h.parse().ok())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_errors_take_precedence() {
+        let ok: Response<CancelOrderResult> =
+            serde_json::from_str(r#"{"error":[],"result":{"count":1}}"#).unwrap();
+        assert_eq!(ok.into_result().unwrap().count, 1);
+
+        let err: Response<CancelOrderResult> =
+            serde_json::from_str(r#"{"error":["EOrder:Unknown order"]}"#).unwrap();
+        assert!(err
+            .into_result()
+            .unwrap_err()
+            .to_string()
+            .contains("EOrder:Unknown order"));
+    }
+
+    #[test]
+    fn test_ohlc_from_raw() {
+        let row: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[1688671200,"30306.1","30306.2","30305.7","30305.7","30306.1","3.39243896",23]"#,
+        )
+        .unwrap();
+        let bar = Ohlc::from_raw(&row).unwrap();
+        assert_eq!(bar.time, 1688671200);
+        assert_eq!(bar.high, 30306.2);
+        assert_eq!(bar.count, 23);
+        assert!(Ohlc::from_raw(&row[..7]).is_none());
+    }
+
+    #[test]
+    fn test_limit_order_form() {
+        let order = OrderRequest::limit(OrderSide::Buy, "XBTUSD", 1.25, 37500.0)
+            .with_client_order_id("abc-1");
+        assert_eq!(
+            serde_urlencoded::to_string(&order).unwrap(),
+            "pair=XBTUSD&type=buy&ordertype=limit&volume=1.25&price=37500&cl_ord_id=abc-1"
+        );
+    }
+}
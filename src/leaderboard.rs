@@ -0,0 +1,238 @@
+//! Paper competition standings
+//!
+//! `compete` runs several configs side by side in paper mode on the same live
+//! candles, each as its own commission-free virtual account. Every entrant
+//! keeps a [`Standing`] (realized P&L from closed trades, mark-to-market of
+//! open positions, drawdown from its own equity peak) and publishes it to a
+//! shared [`Leaderboard`], which ranks entrants by return and is exported on
+//! an interval as CSV and JSON.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// Leaderboard shared by every entrant of a competition
+pub type SharedLeaderboard = Arc<Mutex<Leaderboard>>;
+
+/// One entrant's virtual account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Standing {
+    /// Entrant name (config file stem)
+    pub name: String,
+    pub strategy: String,
+    pub initial_capital: f64,
+    /// Net P&L of closed trades
+    pub realized_pnl: f64,
+    /// Mark-to-market of open positions at the last update
+    pub unrealized_pnl: f64,
+    pub trades: usize,
+    pub wins: usize,
+    pub open_positions: usize,
+    pub peak_equity: f64,
+    /// Largest fall from the equity peak, as a fraction
+    pub max_drawdown: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Standing {
+    pub fn new(name: &str, strategy: &str, initial_capital: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            strategy: strategy.to_string(),
+            initial_capital,
+            realized_pnl: 0.0,
+            unrealized_pnl: 0.0,
+            trades: 0,
+            wins: 0,
+            open_positions: 0,
+            peak_equity: initial_capital,
+            max_drawdown: 0.0,
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Count a closed trade
+    pub fn record_trade(&mut self, net_pnl: f64) {
+        self.trades += 1;
+        if net_pnl > 0.0 {
+            self.wins += 1;
+        }
+        self.realized_pnl += net_pnl;
+    }
+
+    /// Mark open positions to market and update the drawdown
+    pub fn mark(&mut self, unrealized_pnl: f64, open_positions: usize, at: DateTime<Utc>) {
+        self.unrealized_pnl = unrealized_pnl;
+        self.open_positions = open_positions;
+        self.updated_at = at;
+        let equity = self.equity();
+        self.peak_equity = self.peak_equity.max(equity);
+        if self.peak_equity > 0.0 {
+            self.max_drawdown = self
+                .max_drawdown
+                .max((self.peak_equity - equity) / self.peak_equity);
+        }
+    }
+
+    pub fn equity(&self) -> f64 {
+        self.initial_capital + self.realized_pnl + self.unrealized_pnl
+    }
+
+    /// Return on initial capital, percent
+    pub fn return_pct(&self) -> f64 {
+        if self.initial_capital <= 0.0 {
+            return 0.0;
+        }
+        (self.equity() / self.initial_capital - 1.0) * 100.0
+    }
+
+    /// Share of closed trades that made money, percent
+    pub fn win_rate(&self) -> f64 {
+        if self.trades == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.trades as f64 * 100.0
+    }
+}
+
+/// Standings of every entrant, ranked by return
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    standings: BTreeMap<String, Standing>,
+}
+
+impl Leaderboard {
+    /// Load a previous export so a restarted competition keeps its results
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let standings: Vec<Standing> = serde_json::from_str(json)?;
+        Ok(Self {
+            standings: standings.into_iter().map(|s| (s.name.clone(), s)).collect(),
+        })
+    }
+
+    pub fn shared(self) -> SharedLeaderboard {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// Replace an entrant's standing
+    pub fn update(&mut self, standing: Standing) {
+        self.standings.insert(standing.name.clone(), standing);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Standing> {
+        self.standings.get(name)
+    }
+
+    /// Best return first; ties go to the smaller drawdown
+    pub fn ranked(&self) -> Vec<&Standing> {
+        let mut ranked: Vec<&Standing> = self.standings.values().collect();
+        ranked.sort_by(|a, b| {
+            b.return_pct()
+                .total_cmp(&a.return_pct())
+                .then(a.max_drawdown.total_cmp(&b.max_drawdown))
+        });
+        ranked
+    }
+
+    pub fn to_csv_string(&self) -> String {
+        let mut out = String::from(
+            "rank,name,strategy,equity,return_pct,realized_pnl,unrealized_pnl,trades,win_rate,max_drawdown_pct,open_positions,updated_at\n",
+        );
+        for (i, s) in self.ranked().into_iter().enumerate() {
+            out.push_str(&format!(
+                "{},{},{},{:.2},{:.2},{:.2},{:.2},{},{:.1},{:.2},{},{}\n",
+                i + 1,
+                s.name,
+                s.strategy,
+                s.equity(),
+                s.return_pct(),
+                s.realized_pnl,
+                s.unrealized_pnl,
+                s.trades,
+                s.win_rate(),
+                s.max_drawdown * 100.0,
+                s.open_positions,
+                s.updated_at.to_rfc3339()
+            ));
+        }
+        out
+    }
+
+    /// Ranked standings as a JSON array (read back by [`Leaderboard::from_json`])
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.ranked())
+    }
+
+    /// Fixed-width table for the console
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "{:<4} {:<24} {:<18} {:>14} {:>9} {:>7} {:>7} {:>8}\n",
+            "#", "Entrant", "Strategy", "Equity", "Return", "Trades", "Win%", "MaxDD"
+        );
+        for (i, s) in self.ranked().into_iter().enumerate() {
+            out.push_str(&format!(
+                "{:<4} {:<24} {:<18} {:>14.2} {:>8.2}% {:>7} {:>6.1}% {:>7.2}%\n",
+                i + 1,
+                s.name,
+                s.strategy,
+                s.equity(),
+                s.return_pct(),
+                s.trades,
+                s.win_rate(),
+                s.max_drawdown * 100.0
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standing_tracks_equity_and_drawdown() {
+        let at = Utc::now();
+        let mut s = Standing::new("a", "grid", 10_000.0);
+        s.record_trade(500.0);
+        s.mark(0.0, 0, at);
+        assert_eq!(s.peak_equity, 10_500.0);
+        s.record_trade(-200.0);
+        s.mark(-350.0, 1, at);
+        assert!((s.equity() - 9_950.0).abs() < 1e-9);
+        assert!((s.max_drawdown - 550.0 / 10_500.0).abs() < 1e-12);
+        assert_eq!(s.win_rate(), 50.0);
+
+        // Recovering doesn't shrink the recorded drawdown
+        s.mark(1_000.0, 1, at);
+        assert!((s.max_drawdown - 550.0 / 10_500.0).abs() < 1e-12);
+        assert!((s.return_pct() - 13.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_leaderboard_ranks_and_round_trips() {
+        let at = Utc::now();
+        let mut board = Leaderboard::default();
+        for (name, pnl) in [("slow", 100.0), ("fast", 900.0), ("flat", 0.0)] {
+            let mut s = Standing::new(name, "grid", 10_000.0);
+            s.record_trade(pnl);
+            s.mark(0.0, 0, at);
+            board.update(s);
+        }
+        let names: Vec<&str> = board.ranked().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["fast", "slow", "flat"]);
+
+        let csv = board.to_csv_string();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("1,fast,grid,10900.00,9.00,"));
+
+        let restored = Leaderboard::from_json(&board.to_json().unwrap()).unwrap();
+        assert_eq!(restored.get("fast"), board.get("fast"));
+        assert_eq!(restored.ranked().len(), 3);
+    }
+}
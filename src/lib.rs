@@ -13,6 +13,13 @@
 //!
 //! All clients include circuit breaker, rate limiting, and retry logic.
 //!
+//! # Feature Flags
+//!
+//! - `native` (default): exchange clients, data download, optimizer, live state and the CLI
+//! - `wasm`: indicators and backtest core only, exported to JavaScript via `wasm-bindgen`
+//!   (`wasm-pack build --target web --no-default-features --features wasm`)
+//! - `python`: PyO3 bindings for the backtester and indicators (`maturin develop`)
+//!
 //! ## Binance Example (Market Data)
 //! ```no_run
 //! use crypto_strategies::binance::BinanceClient;
@@ -53,22 +60,108 @@
 //! }
 //! ```
 
+pub mod accounts;
+#[cfg(feature = "native")]
+pub mod adjustments;
+pub mod alerts;
+pub mod attribution;
+#[cfg(feature = "native")]
+pub mod audit;
 pub mod backtest;
+#[cfg(feature = "native")]
+pub mod backtest_stream;
+#[cfg(feature = "native")]
 pub mod binance;
+#[cfg(feature = "native")]
+pub mod book_recorder;
+#[cfg(feature = "native")]
+pub mod book_tracker;
+#[cfg(feature = "native")]
+pub mod bybit;
+pub mod capabilities;
+#[cfg(feature = "native")]
+pub mod coinbase;
+#[cfg(feature = "native")]
 pub mod coindcx;
+#[cfg(feature = "native")]
 pub mod common;
 pub mod config;
+pub mod config_layers;
+#[cfg(feature = "native")]
+pub mod csv_schema;
+#[cfg(feature = "native")]
 pub mod data;
+#[cfg(feature = "native")]
+pub mod dataset;
+#[cfg(feature = "native")]
+pub mod dead_man;
+#[cfg(feature = "native")]
+pub mod degraded;
+#[cfg(feature = "native")]
+pub mod exchange;
+#[cfg(feature = "native")]
+pub mod fees;
+pub mod fx;
 pub mod grid;
+pub mod hedge;
+#[cfg(feature = "native")]
+pub mod idle;
 pub mod indicators;
+#[cfg(feature = "native")]
+pub mod intents;
+#[cfg(feature = "native")]
+pub mod kill_switch;
+#[cfg(feature = "native")]
+pub mod kraken;
+#[cfg(feature = "native")]
+pub mod leaderboard;
+#[cfg(feature = "native")]
+pub mod mock_exchange;
 pub mod monthly_pnl;
 pub mod multi_timeframe;
+#[cfg(feature = "native")]
+pub mod okx;
 pub mod oms;
+#[cfg(feature = "native")]
 pub mod optimizer;
+pub mod positioning;
+#[cfg(feature = "native")]
+pub mod preflight;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "native")]
+pub mod reconcile;
+#[cfg(feature = "native")]
+pub mod regression;
+#[cfg(feature = "native")]
+pub mod retention;
 pub mod risk;
+pub mod risk_budget;
+#[cfg(feature = "native")]
+pub mod runs;
+#[cfg(feature = "native")]
+pub mod service;
+#[cfg(feature = "native")]
+pub mod signal_heatmap;
+pub mod stability;
+#[cfg(feature = "native")]
+pub mod state_diff;
+#[cfg(feature = "native")]
 pub mod state_manager;
+#[cfg(feature = "native")]
+pub mod state_writer;
 pub mod strategies;
+pub mod stress;
+pub mod symbols;
+pub mod tail_risk;
 pub mod types;
+#[cfg(feature = "native")]
+pub mod universe;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "native")]
+pub mod webhooks;
+#[cfg(feature = "native")]
 pub mod zerodha;
 
 pub use config::Config;
@@ -80,10 +173,13 @@ pub use oms::{
     ExecutionEngine, Fill, Order, OrderBook, OrderId, OrderRequest, OrderState, OrderType,
     Position, PositionManager, StrategyContext, TimeInForce,
 };
-pub use strategies::Strategy;
+pub use strategies::{SignalContext, Strategy};
 pub use types::*;
 
 // Re-export exchange clients for convenience
+#[cfg(feature = "native")]
 pub use binance::BinanceClient;
+#[cfg(feature = "native")]
 pub use coindcx::CoinDCXClient;
+#[cfg(feature = "native")]
 pub use zerodha::ZerodhaClient;
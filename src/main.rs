@@ -1,13 +1,23 @@
 //! Crypto trading strategies - main entry point
 //!
-//! This binary provides four subcommands:
+//! This binary provides the following subcommands:
 //! - backtest: Run strategy backtests
 //! - optimize: Run parameter optimization
 //! - live: Run live trading (paper or real)
 //! - download: Download historical data from Binance (default) or CoinDCX
-
-use anyhow::Result;
+//! - state: Export/import live trading state snapshots
+//! - alerts: Watchlist indicator alerts without a strategy
+//! - runs: List/show/delete recorded backtest and optimize runs
+//! - data: Data retention (cold-tier compaction, pruning)
+//! - config: Show a resolved config with per-field provenance
+//! - regression-check: Compare pinned backtests against golden results
+
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use crypto_strategies::config_layers::ConfigOverrides;
+use crypto_strategies::runs::RunMeta;
+use crypto_strategies::service::{self, ConfigFault};
+use std::io::IsTerminal;
 use std::path::PathBuf;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
@@ -25,6 +35,14 @@ struct Cli {
     /// Verbose output
     #[arg(short, long, global = true)]
     verbose: bool,
+
+    /// Config environment overlay: applies `<config>.<env>.json` on top of the config
+    #[arg(long, global = true)]
+    env: Option<String>,
+
+    /// Override a config field (repeatable), e.g. --set trading.risk_per_trade=0.01
+    #[arg(long = "set", global = true, value_name = "PATH=VALUE")]
+    set: Vec<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -60,6 +78,22 @@ enum Commands {
         /// Default is false for realistic intra-candle execution
         #[arg(long)]
         use_t1_execution: bool,
+
+        /// Run bar-close and intrabar trailing stops side by side and report give-back
+        #[arg(long)]
+        compare_trailing: bool,
+
+        /// Run each same-bar stop/target policy side by side
+        #[arg(long)]
+        compare_same_bar: bool,
+
+        /// Attribute P&L to entries, exits and sizing via counterfactual re-runs
+        #[arg(long)]
+        attribution: bool,
+
+        /// Name recorded in the run registry (see `runs list`)
+        #[arg(long)]
+        name: Option<String>,
     },
 
     /// Optimize strategy parameters (grid search from JSON config)
@@ -116,6 +150,10 @@ enum Commands {
         /// Skip updating config file with best parameters (default: update if better)
         #[arg(long)]
         no_update: bool,
+
+        /// Name recorded in the run registry (see `runs list`)
+        #[arg(long)]
+        name: Option<String>,
     },
 
     /// Run live trading
@@ -135,6 +173,69 @@ enum Commands {
         /// State database path
         #[arg(long, default_value = "state.db")]
         state_db: String,
+
+        /// Print the first cycle's trade plan before trading (always on with --live)
+        #[arg(long)]
+        preflight: bool,
+
+        /// Accept the preflight plan without waiting for an acknowledgment
+        #[arg(long)]
+        confirm: bool,
+
+        /// Run under a service manager: PID file, SIGTERM/SIGHUP shutdown, and exit
+        /// code 78 for configuration errors (75 for failures worth a restart)
+        #[arg(long)]
+        daemon: bool,
+
+        /// PID file (default with --daemon: live.pid next to --state-db)
+        #[arg(long)]
+        pid_file: Option<String>,
+    },
+
+    /// Paper-trade several configs side by side (commission-free) and export a leaderboard
+    Compete {
+        /// Configuration files, one entrant each (comma-separated)
+        #[arg(short, long, value_delimiter = ',', required = true)]
+        configs: Vec<String>,
+
+        /// Directory holding each entrant's state (`<dir>/<config name>/state.db`)
+        #[arg(long, default_value = "compete")]
+        state_dir: String,
+
+        /// Leaderboard directory (default: `<results_dir>/compete` of the first config)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Minutes between leaderboard exports
+        #[arg(long, default_value = "15")]
+        export_mins: u64,
+
+        /// Seconds fetched candles are reused across entrants
+        #[arg(long, default_value = "5")]
+        feed_ttl_secs: u64,
+    },
+
+    /// Reconcile recorded live fills with the exchange's trade history and export for accounting
+    ReconcileTrades {
+        /// Path to configuration file (exchange credentials, `exchange.reconcile`)
+        #[arg(short, long, default_value = "../configs/regime_grid_config.json")]
+        config: String,
+
+        /// State database path (same as `live --state-db`)
+        #[arg(long, default_value = "state.db")]
+        state_db: String,
+
+        /// Start of the window (YYYY-MM-DD); defaults to `reconcile.lookback_hours` ago
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Export directory (default: `reconcile.output_dir` or results/reconciliation)
+        #[arg(long)]
+        output_dir: Option<String>,
+
+        /// Sign off the export under this name (refused if anything fails to reconcile)
+        #[arg(long)]
+        sign_off: Option<String>,
     },
 
     /// Download historical data from Binance (default) or CoinDCX
@@ -155,13 +256,361 @@ enum Commands {
         #[arg(short, long, default_value = "data")]
         output: String,
 
-        /// Data source: "binance" (default) or "coindcx"
+        /// Data source: "binance" (default), "coindcx", "kraken", "coinbase" or "bybit" (USDT
+        /// perpetuals)
+        #[arg(long, default_value = "binance")]
+        source: String,
+    },
+
+    /// Export or import live trading state (for moving a bot between machines)
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+
+    /// Generate service-manager integration for `live --daemon`
+    Service {
+        #[command(subcommand)]
+        action: ServiceAction,
+    },
+
+    /// Monitor indicator conditions and push notifications (no trading)
+    Alerts {
+        /// Path to alerts file
+        #[arg(short, long, default_value = "configs/alerts.json")]
+        file: String,
+
+        /// Evaluate once and exit instead of polling
+        #[arg(long)]
+        once: bool,
+    },
+
+    /// List, inspect or delete recorded backtest/optimize runs
+    Runs {
+        #[command(subcommand)]
+        action: RunsAction,
+    },
+
+    /// Manage downloaded market data (retention, cold-tier compaction, orderbook recording)
+    Data {
+        #[command(subcommand)]
+        action: DataAction,
+    },
+
+    /// Inspect resolved configs (inheritance, overlays, --set overrides)
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Re-run pinned reference backtests and fail if metrics drift from the golden results
+    RegressionCheck {
+        /// Suite file listing the reference cases and tolerances
+        #[arg(short, long, default_value = "regression/suite.json")]
+        suite: String,
+
+        /// Only run these cases (by name)
+        #[arg(long, value_delimiter = ',')]
+        case: Option<Vec<String>>,
+
+        /// Accept the current results as the new golden results
+        #[arg(long)]
+        update: bool,
+    },
+
+    /// Map where and when a strategy's entry signals fire across all downloaded data (no execution)
+    SignalHeatmap {
+        /// Path to configuration file (uses backtest.data_dir)
+        #[arg(short, long, default_value = "configs/btc_eth_sol_bnb_xrp_1d.json")]
+        config: String,
+
+        /// Strategy name (overrides config file)
+        #[arg(short, long)]
+        strategy: Option<String>,
+
+        /// Only these symbols (default: every downloaded symbol)
+        #[arg(long, value_delimiter = ',')]
+        symbols: Option<Vec<String>>,
+
+        /// Only these timeframes (default: every downloaded timeframe)
+        #[arg(short, long, value_delimiter = ',')]
+        timeframes: Option<Vec<String>>,
+
+        /// Start date (YYYY-MM-DD)
+        #[arg(long)]
+        start: Option<String>,
+
+        /// End date (YYYY-MM-DD)
+        #[arg(long)]
+        end: Option<String>,
+
+        /// Bucket for the "when" view: month, quarter or year
+        #[arg(long, default_value = "quarter")]
+        period: String,
+
+        /// Bars per series ignored while indicators warm up
+        #[arg(long, default_value = "50")]
+        warmup: usize,
+
+        /// CSV output (default: <results_dir>/signal_heatmap_<strategy>.csv)
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigAction {
+    /// Print every resolved field and the file (or --set) it came from
+    Show {
+        /// Path to configuration file
+        #[arg(short, long, default_value = "configs/btc_eth_sol_bnb_xrp_1d.json")]
+        config: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum RunsAction {
+    /// List recorded runs, newest first
+    List {
+        /// Results directory (same as `backtest.results_dir`)
+        #[arg(long, default_value = "results")]
+        results_dir: String,
+
+        /// Show at most this many runs
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Show a run's config, data range, metrics and artifacts
+    Show {
+        /// Run id, unique id prefix, or run name
+        id: String,
+
+        /// Results directory (same as `backtest.results_dir`)
+        #[arg(long, default_value = "results")]
+        results_dir: String,
+    },
+
+    /// Rank parameter regions by how consistently they were profitable across runs
+    Analyze {
+        /// Strategy to analyze (default: strategy of the latest run)
+        #[arg(short, long)]
+        strategy: Option<String>,
+
+        /// Results directory (same as `backtest.results_dir`)
+        #[arg(long, default_value = "results")]
+        results_dir: String,
+
+        /// Number of regions to show
+        #[arg(short, long, default_value = "10")]
+        top: usize,
+
+        /// Ignore regions covered by fewer data periods
+        #[arg(long, default_value = "2")]
+        min_periods: usize,
+    },
+
+    /// Delete runs from the registry along with their result artifacts
+    Delete {
+        /// Run ids, unique id prefixes, or run names
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// Results directory (same as `backtest.results_dir`)
+        #[arg(long, default_value = "results")]
+        results_dir: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DataAction {
+    /// Apply `backtest.data_retention`: compact old candles to parquet and prune expired ones
+    Prune {
+        /// Path to configuration file (uses backtest.data_dir and backtest.data_retention)
+        #[arg(short, long, default_value = "configs/btc_eth_sol_bnb_xrp_1d.json")]
+        config: String,
+
+        /// Show what would be compacted or removed without touching any files
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Record top-of-book snapshots per `exchange.orderbook_recorder` until Ctrl+C
+    RecordBooks {
+        /// Path to configuration file (symbols default to trading.symbols)
+        #[arg(short, long, default_value = "configs/btc_eth_sol_bnb_xrp_1d.json")]
+        config: String,
+
+        /// Symbols to record (overrides the config)
+        #[arg(short, long, value_delimiter = ',')]
+        symbols: Option<Vec<String>>,
+
+        /// Levels kept per side (overrides the config)
+        #[arg(long)]
+        depth: Option<usize>,
+
+        /// Seconds between snapshots (overrides the config)
+        #[arg(long)]
+        interval_secs: Option<u64>,
+
+        /// Stop after this many rounds
+        #[arg(long)]
+        rounds: Option<u64>,
+    },
+
+    /// Bundle candle series into a checksummed dataset archive
+    Export {
+        /// Path to configuration file (uses backtest.data_dir; symbols and timeframe default from it)
+        #[arg(short, long, default_value = "configs/btc_eth_sol_bnb_xrp_1d.json")]
+        config: String,
+
+        /// Symbols to export (overrides the config)
+        #[arg(short, long, value_delimiter = ',')]
+        symbols: Option<Vec<String>>,
+
+        /// Timeframes to export (defaults to the config's timeframe)
+        #[arg(short, long, value_delimiter = ',')]
+        timeframes: Option<Vec<String>>,
+
+        /// Output archive path
+        #[arg(short, long, default_value = "dataset.tar")]
+        output: String,
+
+        /// Where the candles came from, recorded in the manifest
         #[arg(long, default_value = "binance")]
         source: String,
+
+        /// License or terms the data is distributed under
+        #[arg(long)]
+        license: Option<String>,
+    },
+
+    /// Verify a dataset archive and write its series into the data directory
+    Import {
+        /// Dataset archive path
+        archive: String,
+
+        /// Path to configuration file (uses backtest.data_dir)
+        #[arg(short, long, default_value = "configs/btc_eth_sol_bnb_xrp_1d.json")]
+        config: String,
+
+        /// Replace existing series whose contents differ
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Check every series in a dataset archive against its checksum
+    Verify {
+        /// Dataset archive path
+        archive: String,
+    },
+
+    /// Download perp basis and funding history for the `positioning` combinator
+    FetchPositioning {
+        /// Path to configuration file (symbols default to trading.symbols)
+        #[arg(short, long, default_value = "configs/btc_eth_sol_bnb_xrp_1d.json")]
+        config: String,
+
+        /// Symbols to fetch (overrides the config)
+        #[arg(short, long, value_delimiter = ',')]
+        symbols: Option<Vec<String>>,
+
+        /// Days of history
+        #[arg(short, long, default_value = "365")]
+        days: u32,
+
+        /// Output directory (one CSV per symbol)
+        #[arg(short, long, default_value = "data/positioning")]
+        output: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ServiceAction {
+    /// Emit a systemd unit that runs `live --daemon` with restart-on-failure
+    Install {
+        /// Path to configuration file (relative to the working directory)
+        #[arg(short, long, default_value = "configs/regime_grid_config.json")]
+        config: String,
+
+        /// State database path (same as `live --state-db`)
+        #[arg(long, default_value = "state.db")]
+        state_db: String,
+
+        /// Trade real money (`--live --confirm`) instead of paper
+        #[arg(long)]
+        live: bool,
+
+        /// Unit name
+        #[arg(long, default_value = "crypto-strategies")]
+        name: String,
+
+        /// Account to run as
+        #[arg(long)]
+        user: Option<String>,
+
+        /// Working directory (default: current directory)
+        #[arg(long)]
+        working_dir: Option<String>,
+
+        /// EnvironmentFile with API keys (default: .env in the working directory, if present)
+        #[arg(long)]
+        env_file: Option<String>,
+
+        /// Write the unit here instead of printing it (e.g. /etc/systemd/system/<name>.service)
+        #[arg(short, long)]
+        output: Option<String>,
     },
 }
 
-fn setup_logging(verbose: bool, command_name: &str, file_only: bool) -> Result<()> {
+#[derive(Subcommand, Debug)]
+enum StateAction {
+    /// Write a snapshot archive of the state database, pending orders and captures
+    Export {
+        /// Output archive path
+        #[arg(default_value = "snapshot.tar")]
+        archive: String,
+
+        /// State database path (same as `live --state-db`)
+        #[arg(long, default_value = "state.db")]
+        state_db: String,
+    },
+
+    /// Restore a snapshot archive into the state directory
+    Import {
+        /// Snapshot archive path
+        archive: String,
+
+        /// State database path (same as `live --state-db`)
+        #[arg(long, default_value = "state.db")]
+        state_db: String,
+
+        /// Replace an existing state database (moved aside as *.pre-import)
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Show what changed between two checkpoints (positions, cash, realized P&L, risk)
+    Diff {
+        /// Earlier checkpoint: latest, ~N (N before latest), an ID, or a UTC time
+        /// (2025-06-01, 2025-06-01T08:00, RFC 3339)
+        from: String,
+
+        /// Later checkpoint, same forms as FROM
+        #[arg(default_value = "latest")]
+        to: String,
+
+        /// State database path (same as `live --state-db`)
+        #[arg(long, default_value = "state.db")]
+        state_db: String,
+
+        /// Print the diff as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+fn setup_logging(verbose: bool, command_name: &str, file_only: bool) -> Result<PathBuf> {
     // Create logs directory
     std::fs::create_dir_all("logs")?;
 
@@ -207,7 +656,8 @@ fn setup_logging(verbose: bool, command_name: &str, file_only: bool) -> Result<(
             .with_thread_names(false)
             .with_line_number(true)
             .with_file(true)
-            .with_ansi(true);
+            // Plain text under journald/service managers
+            .with_ansi(std::io::stdout().is_terminal());
 
         // File layer - same format but without ANSI colors
         let file_layer = tracing_subscriber::fmt::layer()
@@ -228,7 +678,7 @@ fn setup_logging(verbose: bool, command_name: &str, file_only: bool) -> Result<(
         info!("Log file: {}", log_path.display());
     }
 
-    Ok(())
+    Ok(log_path)
 }
 
 #[tokio::main]
@@ -241,17 +691,31 @@ async fn main() -> Result<()> {
     }
 
     let cli = Cli::parse();
+    crypto_strategies::Config::set_cli_overrides(ConfigOverrides {
+        env: cli.env.clone(),
+        set: cli.set.clone(),
+    });
 
     // Determine command name and whether to use file-only logging
     let (command_name, file_only) = match &cli.command {
         Commands::Backtest { .. } => ("backtest", false),
         Commands::Optimize { .. } => ("optimize", true), // File-only for clean progress bar
         Commands::Live { .. } => ("live", false),
+        Commands::Compete { .. } => ("compete", false),
+        Commands::ReconcileTrades { .. } => ("reconcile", false),
         Commands::Download { .. } => ("download", false),
+        Commands::State { .. } => ("state", false),
+        Commands::Service { .. } => ("service", true), // Keep stdout clean for the unit
+        Commands::Alerts { .. } => ("alerts", false),
+        Commands::Runs { .. } => ("runs", false),
+        Commands::Data { .. } => ("data", false),
+        Commands::Config { .. } => ("config", false),
+        Commands::RegressionCheck { .. } => ("regression", false),
+        Commands::SignalHeatmap { .. } => ("signal_heatmap", false),
     };
 
     // Setup logging
-    setup_logging(cli.verbose, command_name, file_only)?;
+    let log_file = setup_logging(cli.verbose, command_name, file_only)?;
 
     // Execute command
     match cli.command {
@@ -263,6 +727,10 @@ async fn main() -> Result<()> {
             end,
             no_risk_limits,
             use_t1_execution,
+            compare_trailing,
+            compare_same_bar,
+            attribution,
+            name,
         } => commands::backtest::run(
             config,
             strategy,
@@ -271,6 +739,13 @@ async fn main() -> Result<()> {
             end,
             no_risk_limits,
             use_t1_execution,
+            compare_trailing,
+            compare_same_bar,
+            attribution,
+            RunMeta {
+                name,
+                log_file: Some(log_file),
+            },
         ),
 
         Commands::Optimize {
@@ -287,9 +762,25 @@ async fn main() -> Result<()> {
             overrides,
             sequential,
             no_update,
+            name,
         } => commands::optimize::run(
-            config, sort_by, top, coins, symbols, min_combo, max_combo, timeframes, start, end,
-            overrides, sequential, no_update,
+            config,
+            sort_by,
+            top,
+            coins,
+            symbols,
+            min_combo,
+            max_combo,
+            timeframes,
+            start,
+            end,
+            overrides,
+            sequential,
+            no_update,
+            RunMeta {
+                name,
+                log_file: Some(log_file),
+            },
         ),
 
         Commands::Live {
@@ -297,15 +788,56 @@ async fn main() -> Result<()> {
             paper,
             live,
             state_db,
+            preflight,
+            confirm,
+            daemon,
+            pid_file,
         } => {
-            commands::live::run(
-                crypto_strategies::Config::from_file(&config)?,
-                state_db,
-                paper || !live,
-            )
-            .await
+            let result = async {
+                let config_path = config;
+                let (mut config, resolved) =
+                    crypto_strategies::Config::from_file_resolved(&config_path)
+                        .context(ConfigFault)?;
+                commands::config::print_layers(&resolved);
+                crypto_strategies::universe::apply_universe(&mut config).await?;
+                commands::live::run(
+                    config,
+                    config_path,
+                    state_db,
+                    paper || !live,
+                    preflight,
+                    confirm,
+                    daemon,
+                    pid_file,
+                )
+                .await
+            }
+            .await;
+            // Service managers restart on 75 but not on 78 (see `service install`)
+            if let Err(e) = result {
+                tracing::error!("Live trading stopped: {:#}", e);
+                eprintln!("Error: {:?}", e);
+                std::process::exit(service::exit_code(&e));
+            }
+            Ok(())
         }
 
+        Commands::Compete {
+            configs,
+            state_dir,
+            output,
+            export_mins,
+            feed_ttl_secs,
+        } => commands::compete::run(configs, state_dir, output, export_mins, feed_ttl_secs).await,
+
+        Commands::ReconcileTrades {
+            config,
+            state_db,
+            since,
+            output_dir,
+            sign_off,
+        } => commands::reconcile::run(config, state_db, since, output_dir, sign_off).await,
+
         Commands::Download {
             symbols,
             timeframes,
@@ -319,5 +851,111 @@ async fn main() -> Result<()> {
             });
             commands::download::run(symbols, timeframes, days, output, data_source)
         }
+
+        Commands::State { action } => match action {
+            StateAction::Export { archive, state_db } => commands::state::export(archive, state_db),
+            StateAction::Import {
+                archive,
+                state_db,
+                force,
+            } => commands::state::import(archive, state_db, force),
+            StateAction::Diff {
+                from,
+                to,
+                state_db,
+                json,
+            } => commands::state::diff(from, to, state_db, json),
+        },
+
+        Commands::Service { action } => match action {
+            ServiceAction::Install {
+                config,
+                state_db,
+                live,
+                name,
+                user,
+                working_dir,
+                env_file,
+                output,
+            } => commands::service::install(
+                config,
+                state_db,
+                live,
+                name,
+                user,
+                working_dir,
+                env_file,
+                output,
+            ),
+        },
+
+        Commands::Alerts { file, once } => commands::alerts::run(file, once).await,
+
+        Commands::Runs { action } => match action {
+            RunsAction::List { results_dir, limit } => commands::runs::list(results_dir, limit),
+            RunsAction::Show { id, results_dir } => commands::runs::show(results_dir, id),
+            RunsAction::Analyze {
+                strategy,
+                results_dir,
+                top,
+                min_periods,
+            } => commands::runs::analyze(results_dir, strategy, top, min_periods),
+            RunsAction::Delete { ids, results_dir } => commands::runs::delete(results_dir, ids),
+        },
+
+        Commands::Data { action } => match action {
+            DataAction::Prune { config, dry_run } => commands::data::prune(config, dry_run),
+            DataAction::RecordBooks {
+                config,
+                symbols,
+                depth,
+                interval_secs,
+                rounds,
+            } => commands::data::record_books(config, symbols, depth, interval_secs, rounds).await,
+            DataAction::Export {
+                config,
+                symbols,
+                timeframes,
+                output,
+                source,
+                license,
+            } => commands::data::export(config, symbols, timeframes, output, source, license),
+            DataAction::Import {
+                archive,
+                config,
+                force,
+            } => commands::data::import(archive, config, force),
+            DataAction::Verify { archive } => commands::data::verify(archive),
+            DataAction::FetchPositioning {
+                config,
+                symbols,
+                days,
+                output,
+            } => commands::data::fetch_positioning(config, symbols, days, output).await,
+        },
+
+        Commands::Config { action } => match action {
+            ConfigAction::Show { config } => commands::config::show(config),
+        },
+
+        Commands::RegressionCheck {
+            suite,
+            case,
+            update,
+        } => commands::regression::run(suite, case, update),
+
+        Commands::SignalHeatmap {
+            config,
+            strategy,
+            symbols,
+            timeframes,
+            start,
+            end,
+            period,
+            warmup,
+            output,
+        } => commands::signal_heatmap::run(
+            config, strategy, symbols, timeframes, start, end, period, warmup, output,
+        ),
     }
 }
@@ -0,0 +1,433 @@
+//! Scripted in-memory exchange for tests and simulations
+//!
+//! [`MockExchangeClient`] implements [`ExchangeClient`] without a network:
+//! tickers, candles and balances are whatever the test sets, orders fill at
+//! the ticker's last price unless an [`OrderOutcome`] is scripted for them,
+//! and any operation can be made to fail or to take a given latency. Clones
+//! share state, so a test can keep one handle to script and inspect while
+//! the code under test owns the other.
+//!
+//! ```no_run
+//! use crypto_strategies::exchange::ExchangeClient;
+//! use crypto_strategies::mock_exchange::{MockExchangeClient, OrderOutcome};
+//!
+//! # async fn demo(order: crypto_strategies::oms::Order) -> anyhow::Result<()> {
+//! let exchange = MockExchangeClient::new().with_price("BTCINR", 5_000_000.0);
+//! exchange.script_order(OrderOutcome::Fill { price: Some(5_001_000.0) });
+//! exchange.fail_next("place_order", "insufficient balance");
+//!
+//! assert!(exchange.place_order(&order).await.is_err());
+//! exchange.place_order(&order).await?; // fills at 5,001,000
+//! assert_eq!(exchange.orders()[0].fill_price, Some(5_001_000.0));
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{anyhow, Result};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::capabilities::{ExchangeCapabilities, PrecisionRule};
+use crate::coindcx::{OcoHandle, OcoOrder, OcoStatus};
+use crate::exchange::{Balance, ExchangeClient, Ticker};
+use crate::oms::{Order, OrderType};
+use crate::Candle;
+
+/// Accepts every order type and timeframe the strategies use
+pub const CAPABILITIES: ExchangeCapabilities = ExchangeCapabilities {
+    name: "mock",
+    order_types: &[
+        OrderType::Market,
+        OrderType::Limit,
+        OrderType::Stop,
+        OrderType::StopLimit,
+    ],
+    timeframes: &[
+        "1m", "5m", "15m", "30m", "1h", "2h", "4h", "6h", "8h", "1d", "1w",
+    ],
+    quote_currencies: &[],
+    precision: PrecisionRule::PerMarket,
+    min_notional: None,
+    sandbox: false,
+    // Lets tests run the live trader, which simulates fills without a stream
+    fill_reports: true,
+};
+
+/// What happens to the next placed order
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderOutcome {
+    /// Fill completely, at `price` or else the order's limit or the last price
+    Fill { price: Option<f64> },
+    /// Accept and leave resting until cancelled
+    Rest,
+    /// Refuse with this error
+    Reject(String),
+    /// Fill like `Fill`, but fail the call as if the response timed out
+    Lost { price: Option<f64> },
+}
+
+/// State of an order the mock accepted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MockOrderStatus {
+    Filled,
+    Open,
+    Cancelled,
+}
+
+/// An order the mock accepted
+#[derive(Debug, Clone)]
+pub struct MockOrder {
+    pub id: String,
+    pub order: Order,
+    pub status: MockOrderStatus,
+    pub fill_price: Option<f64>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    tickers: HashMap<String, Ticker>,
+    candles: HashMap<(String, String), Vec<Candle>>,
+    balances: Vec<Balance>,
+    outcomes: VecDeque<OrderOutcome>,
+    failures: HashMap<String, VecDeque<String>>,
+    orders: Vec<MockOrder>,
+    ocos: Vec<OcoOrder>,
+    calls: HashMap<String, usize>,
+    history: Vec<String>,
+}
+
+/// In-memory [`ExchangeClient`] with scripted fills, latency and failures
+///
+/// Operation names for [`fail_next`](Self::fail_next) and
+/// [`calls`](Self::calls): `ticker`, `candles`, `place_order`,
+/// `find_order`, `cancel_order`, `balances`, `place_oco`, `sync_oco` and
+/// `cancel_oco`. Parked OCO pairs rest until cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct MockExchangeClient {
+    state: Arc<Mutex<State>>,
+    latency: Duration,
+}
+
+impl MockExchangeClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delay every call by `latency`
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Quote `symbol` at `last`, with no spread
+    pub fn with_price(self, symbol: &str, last: f64) -> Self {
+        self.set_price(symbol, last);
+        self
+    }
+
+    /// Serve `candles` (oldest first) for `symbol` on `timeframe`
+    pub fn with_candles(self, symbol: &str, timeframe: &str, candles: Vec<Candle>) -> Self {
+        self.state()
+            .candles
+            .insert((symbol.to_string(), timeframe.to_string()), candles);
+        self
+    }
+
+    /// Report `free` of `asset` as available
+    pub fn with_balance(self, asset: &str, free: f64) -> Self {
+        self.state().balances.push(Balance {
+            asset: asset.to_string(),
+            free,
+            locked: 0.0,
+        });
+        self
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Move `symbol`'s last price (e.g. to trigger a stop)
+    pub fn set_price(&self, symbol: &str, last: f64) {
+        self.state().tickers.insert(
+            symbol.to_string(),
+            Ticker {
+                symbol: symbol.to_string(),
+                last,
+                bid: Some(last),
+                ask: Some(last),
+                volume: 0.0,
+            },
+        );
+    }
+
+    /// Append a candle to `symbol`'s `timeframe` series
+    pub fn push_candle(&self, symbol: &str, timeframe: &str, candle: Candle) {
+        self.state()
+            .candles
+            .entry((symbol.to_string(), timeframe.to_string()))
+            .or_default()
+            .push(candle);
+    }
+
+    /// Decide what happens to the next order placed without a failure queued
+    pub fn script_order(&self, outcome: OrderOutcome) {
+        self.state().outcomes.push_back(outcome);
+    }
+
+    /// Fail the next call to `op` with `error`
+    pub fn fail_next(&self, op: &str, error: impl Into<String>) {
+        self.state()
+            .failures
+            .entry(op.to_string())
+            .or_default()
+            .push_back(error.into());
+    }
+
+    /// Every order accepted so far, oldest first
+    pub fn orders(&self) -> Vec<MockOrder> {
+        self.state().orders.clone()
+    }
+
+    /// Every OCO pair parked so far, oldest first
+    pub fn ocos(&self) -> Vec<OcoOrder> {
+        self.state().ocos.clone()
+    }
+
+    /// Fill a resting order at `price`
+    pub fn fill(&self, order_id: &str, price: f64) -> Result<()> {
+        let mut state = self.state();
+        let order = state
+            .orders
+            .iter_mut()
+            .find(|o| o.id == order_id && o.status == MockOrderStatus::Open)
+            .ok_or_else(|| anyhow!("mock: no open order {}", order_id))?;
+        order.status = MockOrderStatus::Filled;
+        order.fill_price = Some(price);
+        Ok(())
+    }
+
+    /// Calls made to `op`, failed ones included
+    pub fn calls(&self, op: &str) -> usize {
+        self.state().calls.get(op).copied().unwrap_or_default()
+    }
+
+    /// Every operation called so far, oldest first
+    pub fn history(&self) -> Vec<String> {
+        self.state().history.clone()
+    }
+
+    /// Wait out the latency, count the call and pop a queued failure
+    async fn enter(&self, op: &str) -> Result<()> {
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+        let mut state = self.state();
+        *state.calls.entry(op.to_string()).or_default() += 1;
+        state.history.push(op.to_string());
+        match state.failures.get_mut(op).and_then(VecDeque::pop_front) {
+            Some(error) => Err(anyhow!("mock {}: {}", op, error)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ExchangeClient for MockExchangeClient {
+    fn capabilities(&self) -> &'static ExchangeCapabilities {
+        &CAPABILITIES
+    }
+
+    async fn get_ticker(&self, symbol: &str) -> Result<Ticker> {
+        self.enter("ticker").await?;
+        self.state()
+            .tickers
+            .get(symbol)
+            .cloned()
+            .ok_or_else(|| anyhow!("mock: no price for {}", symbol))
+    }
+
+    async fn get_candles(&self, symbol: &str, timeframe: &str, limit: u32) -> Result<Vec<Candle>> {
+        self.enter("candles").await?;
+        let state = self.state();
+        let candles = state
+            .candles
+            .get(&(symbol.to_string(), timeframe.to_string()))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let start = candles.len().saturating_sub(limit as usize);
+        Ok(candles[start..].to_vec())
+    }
+
+    async fn place_order(&self, order: &Order) -> Result<String> {
+        self.enter("place_order").await?;
+        let mut state = self.state();
+        let outcome = state
+            .outcomes
+            .pop_front()
+            .unwrap_or(OrderOutcome::Fill { price: None });
+        let lost = matches!(outcome, OrderOutcome::Lost { .. });
+        let (status, fill_price) = match outcome {
+            OrderOutcome::Reject(reason) => return Err(anyhow!("mock: rejected: {}", reason)),
+            OrderOutcome::Rest => (MockOrderStatus::Open, None),
+            OrderOutcome::Fill { price } | OrderOutcome::Lost { price } => {
+                let price = price
+                    .or(order.limit_price.map(|p| p.to_f64()))
+                    .or_else(|| state.tickers.get(order.symbol.as_str()).map(|t| t.last))
+                    .ok_or_else(|| anyhow!("mock: no price to fill {}", order.symbol))?;
+                (MockOrderStatus::Filled, Some(price))
+            }
+        };
+        let id = format!("mock-{}", state.orders.len() + 1);
+        state.orders.push(MockOrder {
+            id: id.clone(),
+            order: order.clone(),
+            status,
+            fill_price,
+        });
+        if lost {
+            return Err(anyhow!("mock place_order: operation timed out"));
+        }
+        Ok(id)
+    }
+
+    async fn find_order(&self, _symbol: &str, client_order_id: &str) -> Result<Option<String>> {
+        self.enter("find_order").await?;
+        Ok(self
+            .state()
+            .orders
+            .iter()
+            .find(|o| o.order.client_id.as_deref() == Some(client_order_id))
+            .map(|o| o.id.clone()))
+    }
+
+    async fn cancel_order(&self, _symbol: &str, order_id: &str) -> Result<()> {
+        self.enter("cancel_order").await?;
+        let mut state = self.state();
+        let order = state
+            .orders
+            .iter_mut()
+            .find(|o| o.id == order_id)
+            .ok_or_else(|| anyhow!("mock: unknown order {}", order_id))?;
+        if order.status != MockOrderStatus::Open {
+            return Err(anyhow!("mock: order {} is {:?}", order_id, order.status));
+        }
+        order.status = MockOrderStatus::Cancelled;
+        Ok(())
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        self.enter("balances").await?;
+        Ok(self.state().balances.clone())
+    }
+
+    fn supports_oco(&self) -> bool {
+        true
+    }
+
+    async fn place_oco(&self, oco: &OcoOrder) -> Result<OcoHandle> {
+        self.enter("place_oco").await?;
+        let mut state = self.state();
+        state.ocos.push(oco.clone());
+        let n = state.ocos.len();
+        Ok(OcoHandle {
+            take_profit_id: format!("mock-oco-{}-tp", n),
+            stop_loss_id: format!("mock-oco-{}-sl", n),
+        })
+    }
+
+    async fn sync_oco(&self, _handle: &OcoHandle) -> Result<OcoStatus> {
+        self.enter("sync_oco").await?;
+        Ok(OcoStatus::Open)
+    }
+
+    async fn cancel_oco(&self, _handle: &OcoHandle) -> Result<()> {
+        self.enter("cancel_oco").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oms::TimeInForce;
+    use crate::{Side, Symbol};
+
+    fn order(order_type: OrderType, limit: Option<f64>) -> Order {
+        Order::from_f64(
+            Symbol::new("BTCINR"),
+            Side::Buy,
+            order_type,
+            0.1,
+            limit,
+            None,
+            TimeInForce::GTC,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_scripted_fills_and_failures() {
+        let exchange = MockExchangeClient::new().with_price("BTCINR", 100.0);
+        let handle = exchange.clone();
+
+        let id = exchange
+            .place_order(&order(OrderType::Market, None))
+            .await
+            .unwrap();
+        assert_eq!(handle.orders()[0].fill_price, Some(100.0));
+
+        handle.fail_next("place_order", "503");
+        handle.script_order(OrderOutcome::Rest);
+        handle.script_order(OrderOutcome::Reject("post-only would cross".into()));
+        let err = exchange
+            .place_order(&order(OrderType::Limit, Some(99.0)))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("503"));
+
+        let resting = exchange
+            .place_order(&order(OrderType::Limit, Some(99.0)))
+            .await
+            .unwrap();
+        assert!(exchange
+            .place_order(&order(OrderType::Limit, Some(99.0)))
+            .await
+            .is_err());
+        exchange.cancel_order("BTCINR", &resting).await.unwrap();
+        assert!(exchange.cancel_order("BTCINR", &id).await.is_err());
+
+        let orders = handle.orders();
+        assert_eq!(orders.len(), 2);
+        assert_eq!(orders[1].status, MockOrderStatus::Cancelled);
+        assert_eq!(handle.calls("place_order"), 4);
+    }
+
+    #[tokio::test]
+    async fn test_latency_and_candles() {
+        let candle = |close: f64| Candle {
+            datetime: chrono::Utc::now(),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1.0,
+        };
+        let exchange = MockExchangeClient::new()
+            .with_latency(Duration::from_millis(20))
+            .with_candles("BTCINR", "1h", vec![candle(1.0), candle(2.0)]);
+        exchange.push_candle("BTCINR", "1h", candle(3.0));
+
+        let started = std::time::Instant::now();
+        let candles = exchange.get_candles("BTCINR", "1h", 2).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert_eq!(
+            candles.iter().map(|c| c.close).collect::<Vec<_>>(),
+            vec![2.0, 3.0]
+        );
+        assert!(exchange
+            .get_candles("ETHINR", "1h", 2)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
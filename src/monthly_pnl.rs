@@ -281,6 +281,10 @@ mod tests {
             pnl: Money::from_f64(net_pnl),
             commission: Money::ZERO,
             net_pnl: Money::from_f64(net_pnl),
+            fees: Default::default(),
+            rationale: None,
+            tranche: None,
+            r_multiple: None,
         }
     }
 
@@ -5,7 +5,7 @@
 
 use crate::{Candle, Symbol};
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// Multi-timeframe candle data for a single symbol
 #[derive(Debug, Clone)]
@@ -156,7 +156,8 @@ pub type MultiSymbolMultiTimeframeData = HashMap<Symbol, MultiTimeframeData>;
 /// Align multi-timeframe data to common datetime points
 ///
 /// Ensures all timeframes for all symbols have data for the same datetime range.
-/// Uses the primary timeframe as the reference.
+/// Uses the primary timeframe as the reference. Symbols listed late or delisted
+/// early shrink the range for everyone; see [`Timeline`] for ragged histories.
 pub fn align_multi_timeframe_data(
     data: &MultiSymbolMultiTimeframeData,
 ) -> Vec<(Symbol, MultiTimeframeData)> {
@@ -228,6 +229,104 @@ pub fn align_multi_timeframe_data(
     aligned
 }
 
+/// Symbols with primary data, sorted by name, histories left as they are
+pub fn sorted_multi_timeframe_data(
+    data: &MultiSymbolMultiTimeframeData,
+) -> Vec<(Symbol, MultiTimeframeData)> {
+    let mut sorted: Vec<(Symbol, MultiTimeframeData)> = data
+        .iter()
+        .filter(|(_, mtf)| !mtf.primary().is_empty())
+        .map(|(symbol, mtf)| (symbol.clone(), mtf.clone()))
+        .collect();
+    // Deterministic iteration order, as in `align_multi_timeframe_data`
+    sorted.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+    sorted
+}
+
+/// Shared bar clock for symbols whose histories start and end at different times
+///
+/// The timeline is the union of every symbol's primary datetimes. Each symbol
+/// maps a timeline bar to the index of its own candle at that datetime, or
+/// `None` before listing, after delisting and in gaps, so no candle is ever
+/// borrowed from another date.
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    dates: Vec<DateTime<Utc>>,
+    /// Per symbol (in input order), local candle index at each timeline bar
+    local: Vec<Vec<Option<usize>>>,
+}
+
+impl Timeline {
+    pub fn new(data: &[(Symbol, MultiTimeframeData)]) -> Self {
+        let dates: Vec<DateTime<Utc>> = data
+            .iter()
+            .flat_map(|(_, mtf)| mtf.primary().iter().map(|c| c.datetime))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let local = data
+            .iter()
+            .map(|(_, mtf)| {
+                let mut map = vec![None; dates.len()];
+                let mut bar = 0;
+                for (idx, candle) in mtf.primary().iter().enumerate() {
+                    while bar < dates.len() && dates[bar] < candle.datetime {
+                        bar += 1;
+                    }
+                    if bar < dates.len() && dates[bar] == candle.datetime {
+                        map[bar] = Some(idx);
+                    }
+                }
+                map
+            })
+            .collect();
+
+        Self { dates, local }
+    }
+
+    pub fn dates(&self) -> &[DateTime<Utc>] {
+        &self.dates
+    }
+
+    pub fn len(&self) -> usize {
+        self.dates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dates.is_empty()
+    }
+
+    /// Index of the symbol's own candle at timeline bar `bar`
+    pub fn local(&self, symbol: usize, bar: usize) -> Option<usize> {
+        self.local[symbol].get(bar).copied().flatten()
+    }
+
+    /// Index of the symbol's latest candle at or before `bar`
+    pub fn latest(&self, symbol: usize, bar: usize) -> Option<usize> {
+        let map = &self.local[symbol];
+        map[..=bar.min(map.len().saturating_sub(1))]
+            .iter()
+            .rev()
+            .find_map(|idx| *idx)
+    }
+
+    /// Timeline bar of the symbol's first candle
+    pub fn first_bar(&self, symbol: usize) -> Option<usize> {
+        self.local[symbol].iter().position(Option::is_some)
+    }
+
+    /// Timeline bar of the symbol's last candle
+    pub fn last_bar(&self, symbol: usize) -> Option<usize> {
+        self.local[symbol].iter().rposition(Option::is_some)
+    }
+
+    /// Symbol's history ends before the timeline does
+    pub fn delisted_at(&self, symbol: usize, bar: usize) -> bool {
+        self.last_bar(symbol) == Some(bar) && bar + 1 < self.dates.len()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,4 +346,45 @@ mod tests {
         assert!(!mtf.has_timeframe("1d"));
         assert_eq!(mtf.primary().len(), 1);
     }
+
+    #[test]
+    fn test_timeline_maps_ragged_histories() {
+        use chrono::TimeZone;
+        let day = |d: u32| chrono::Utc.with_ymd_and_hms(2024, 1, d, 0, 0, 0).unwrap();
+        let series = |days: &[u32]| {
+            let mut mtf = MultiTimeframeData::new("1d");
+            let candles = days
+                .iter()
+                .map(|&d| Candle::new(day(d), 100.0, 110.0, 90.0, 105.0, 1000.0).unwrap())
+                .collect();
+            mtf.add_timeframe("1d", candles);
+            mtf
+        };
+
+        // OLD runs through, NEW lists on day 3, GONE delists after day 2
+        // and ALT misses day 4
+        let mut data = MultiSymbolMultiTimeframeData::new();
+        data.insert(Symbol::new("OLD"), series(&[1, 2, 3, 4, 5]));
+        data.insert(Symbol::new("NEW"), series(&[3, 4, 5]));
+        data.insert(Symbol::new("GONE"), series(&[1, 2]));
+        data.insert(Symbol::new("ALT"), series(&[1, 2, 3, 5]));
+        let sorted = sorted_multi_timeframe_data(&data);
+        let names: Vec<&str> = sorted.iter().map(|(s, _)| s.as_str()).collect();
+        assert_eq!(names, ["ALT", "GONE", "NEW", "OLD"]);
+
+        let timeline = Timeline::new(&sorted);
+        assert_eq!(timeline.len(), 5);
+        assert_eq!(timeline.local(2, 1), None);
+        assert_eq!(timeline.local(2, 2), Some(0));
+        assert_eq!(timeline.first_bar(2), Some(2));
+        assert_eq!(timeline.last_bar(1), Some(1));
+        assert!(timeline.delisted_at(1, 1));
+        assert!(!timeline.delisted_at(3, 4));
+
+        // Gaps fall back to the latest earlier candle
+        assert_eq!(timeline.local(0, 3), None);
+        assert_eq!(timeline.latest(0, 3), Some(2));
+        assert_eq!(timeline.local(0, 4), Some(3));
+        assert_eq!(timeline.latest(1, 4), Some(1));
+    }
 }
@@ -0,0 +1,648 @@
+//! OKX API Client
+//!
+//! HTTP client for OKX spot and perpetual swaps with the same resilience as
+//! the CoinDCX client:
+//! - Automatic retry with exponential backoff
+//! - Rate limiting with priority-based shedding (order calls first)
+//! - Circuit breaker pattern for fault tolerance
+//!
+//! # Example
+//!
+//! ```no_run
+//! use crypto_strategies::okx::{Market, OkxClient};
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let client = OkxClient::new("api_key", "api_secret", "passphrase");
+//!
+//!     let ticker = client.get_ticker("BTCUSDT", Market::Spot).await?;
+//!     println!("BTC-USDT price: {:?}", ticker.last_price_f64());
+//!
+//!     for balance in client.get_balances().await? {
+//!         println!("{}: {}", balance.ccy, balance.available_f64());
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{Duration as ChronoDuration, SecondsFormat, Utc};
+use reqwest::Client;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tracing::info;
+
+use super::auth::Credentials;
+use super::to_okx_bar;
+use super::types::*;
+use crate::common::{
+    CircuitBreaker, CircuitBreakerConfig, NetworkConfig, RateLimiter, RateLimiterConfig,
+    RequestPriority,
+};
+use crate::symbols::{SymbolMapper, Venue};
+
+/// Base URL for the OKX v5 API
+pub const API_BASE_URL: &str = "https://www.okx.com";
+
+/// Most candles returned per request by `/market/candles`
+const MAX_CANDLES_PER_REQUEST: u32 = 300;
+
+/// Most candles returned per request by `/market/history-candles`
+const MAX_HISTORY_PER_REQUEST: u32 = 100;
+
+/// Convert a config symbol to an OKX instrument ID
+///
+/// `BTCUSDT` and `btc/usdt` become `BTC-USDT` on spot and `BTC-USDT-SWAP`
+/// on swaps; see [`crate::symbols::okx_inst_id`].
+pub fn symbol_to_inst_id(symbol: &str, market: Market) -> String {
+    crate::symbols::okx_inst_id(symbol, market == Market::Swap)
+}
+
+/// Client configuration
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Maximum number of retry attempts
+    pub max_retries: u32,
+    /// Request timeout duration
+    pub timeout: Duration,
+    /// Rate limiter configuration
+    pub rate_limiter: RateLimiterConfig,
+    /// Circuit breaker configuration
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Market that [`ExchangeClient`](crate::exchange::ExchangeClient) calls trade on
+    pub market: Market,
+    /// Trade in OKX's demo mode (needs keys created for the demo account)
+    pub sandbox: bool,
+    /// Proxy, extra root certificates and connect timeout
+    pub network: NetworkConfig,
+    /// Venue identifiers of config symbols, with overrides
+    pub symbols: SymbolMapper,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            timeout: Duration::from_secs(30),
+            // Market data allows 20 requests per 2 seconds per IP
+            rate_limiter: RateLimiterConfig::default().with_rate(10),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            market: Market::Spot,
+            sandbox: false,
+            network: NetworkConfig::default(),
+            symbols: SymbolMapper::default(),
+        }
+    }
+}
+
+impl ClientConfig {
+    /// Set maximum retry attempts
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Set request timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set proxy, extra root certificates and connect timeout
+    pub fn with_network(mut self, network: NetworkConfig) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Map symbols through `symbols`, e.g. for listings with overridden IDs
+    pub fn with_symbols(mut self, symbols: SymbolMapper) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Set rate limit (requests per second)
+    pub fn with_rate_limit(mut self, requests_per_second: usize) -> Self {
+        self.rate_limiter = self.rate_limiter.with_rate(requests_per_second);
+        self
+    }
+
+    /// Set circuit breaker failure threshold
+    pub fn with_circuit_breaker_threshold(mut self, threshold: u32) -> Self {
+        self.circuit_breaker = self.circuit_breaker.with_failure_threshold(threshold);
+        self
+    }
+
+    /// Trade spot pairs or perpetual swaps
+    pub fn with_market(mut self, market: Market) -> Self {
+        self.market = market;
+        self
+    }
+
+    /// Send private requests to the demo account instead of production
+    pub fn with_sandbox(mut self, sandbox: bool) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+}
+
+/// OKX Exchange API Client
+///
+/// Provides methods to interact with the OKX API including:
+/// - Public endpoints (ticker, candles)
+/// - Authenticated endpoints (orders, balances)
+#[derive(Clone)]
+pub struct OkxClient {
+    credentials: Credentials,
+    http_client: Client,
+    circuit_breaker: Arc<Mutex<CircuitBreaker>>,
+    rate_limiter: RateLimiter,
+    max_retries: u32,
+    market: Market,
+    sandbox: bool,
+    symbols: SymbolMapper,
+    #[cfg(feature = "fault-injection")]
+    faults: crate::common::FaultInjector,
+}
+
+impl OkxClient {
+    /// Create a new client with API credentials
+    pub fn new(
+        api_key: impl Into<String>,
+        api_secret: impl Into<String>,
+        passphrase: impl Into<String>,
+    ) -> Self {
+        Self::with_config(api_key, api_secret, passphrase, ClientConfig::default())
+    }
+
+    /// Create a new client with custom configuration
+    pub fn with_config(
+        api_key: impl Into<String>,
+        api_secret: impl Into<String>,
+        passphrase: impl Into<String>,
+        config: ClientConfig,
+    ) -> Self {
+        let builder = Client::builder()
+            .timeout(config.timeout)
+            .pool_max_idle_per_host(10)
+            .pool_idle_timeout(Duration::from_secs(90));
+        let http_client = config
+            .network
+            .apply(builder)
+            .and_then(|b| b.build())
+            .expect("invalid network settings (see NetworkConfig::validate)");
+
+        Self {
+            credentials: Credentials::new(api_key, api_secret, passphrase),
+            http_client,
+            circuit_breaker: Arc::new(Mutex::new(CircuitBreaker::new(config.circuit_breaker))),
+            rate_limiter: RateLimiter::new(config.rate_limiter),
+            max_retries: config.max_retries,
+            market: config.market,
+            sandbox: config.sandbox,
+            symbols: config.symbols,
+            #[cfg(feature = "fault-injection")]
+            faults: crate::common::FaultInjector::default(),
+        }
+    }
+
+    /// Symbol mapping used for every venue identifier
+    pub fn symbol_mapper(&self) -> &SymbolMapper {
+        &self.symbols
+    }
+
+    /// Instrument ID of `symbol` in `market` (`BTC-USDT` or `BTC-USDT-SWAP`)
+    pub fn inst_id(&self, symbol: &str, market: Market) -> String {
+        let venue = match market {
+            Market::Spot => Venue::Okx,
+            Market::Swap => Venue::OkxSwap,
+        };
+        self.symbols.id(symbol, venue)
+    }
+
+    /// Replace attempts with queued faults (see [`crate::common::faults`])
+    #[cfg(feature = "fault-injection")]
+    pub fn with_faults(mut self, faults: crate::common::FaultInjector) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    /// Create a client from environment variables
+    ///
+    /// Expects `OKX_API_KEY`, `OKX_API_SECRET` and `OKX_PASSPHRASE`
+    pub fn from_env() -> Result<Self> {
+        let credentials =
+            Credentials::from_env().context("Failed to load OKX credentials from environment")?;
+        Ok(Self::with_config(
+            credentials.api_key(),
+            credentials.api_secret(),
+            credentials.passphrase(),
+            ClientConfig::default(),
+        ))
+    }
+
+    /// Market this client trades on
+    pub fn market(&self) -> Market {
+        self.market
+    }
+
+    /// Execute a request with retry logic, rate limiting, and circuit breaker
+    ///
+    /// `Low`/`Normal` requests fail with [`RequestShed`](crate::common::RequestShed)
+    /// instead of waiting when the rate limit is under pressure.
+    async fn execute_with_retry<F, Fut, T>(
+        &self,
+        op: &str,
+        priority: RequestPriority,
+        operation: F,
+    ) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+        T: serde::de::DeserializeOwned,
+    {
+        {
+            let mut cb = self.circuit_breaker.lock().await;
+            if !cb.can_attempt() {
+                return Err(anyhow!("Circuit breaker is open, rejecting request"));
+            }
+        }
+
+        self.rate_limiter.acquire_for(op, priority).await?;
+
+        let mut last_error = None;
+
+        for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                let delay = Duration::from_secs(2u64.pow(attempt - 1));
+                tracing::debug!("Retrying after {}ms", delay.as_millis());
+                sleep(delay).await;
+            }
+
+            #[cfg(feature = "fault-injection")]
+            let outcome = match self.faults.take(op) {
+                Some(fault) => fault.into_result(),
+                None => operation().await,
+            };
+            #[cfg(not(feature = "fault-injection"))]
+            let outcome = operation().await;
+
+            match outcome {
+                Ok(result) => {
+                    let mut cb = self.circuit_breaker.lock().await;
+                    cb.record_success();
+                    return Ok(result);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "{} failed (attempt {}/{}): {}",
+                        op,
+                        attempt + 1,
+                        self.max_retries + 1,
+                        e
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        {
+            let mut cb = self.circuit_breaker.lock().await;
+            cb.record_failure();
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!("Request failed after retries")))
+    }
+
+    /// Read a response and unwrap the envelope
+    async fn parse_response<R>(&self, response: reqwest::Response) -> Result<Vec<R>>
+    where
+        R: serde::de::DeserializeOwned,
+    {
+        self.rate_limiter
+            .observe_response(response.status(), response.headers())
+            .await;
+
+        let status = response.status();
+        let text = response.text().await.context("Failed to read response")?;
+
+        // Rejected orders come back as 200 with a non-zero code, but some
+        // errors use 4xx with the same envelope; prefer its message
+        let envelope: Response = match serde_json::from_str(&text) {
+            Ok(envelope) => envelope,
+            Err(_) if !status.is_success() => {
+                return Err(anyhow!("API error ({}): {}", status, text))
+            }
+            Err(e) => return Err(e).context("Failed to parse response"),
+        };
+        envelope.into_result()
+    }
+
+    /// Add the `OK-ACCESS-*` headers for a private request
+    ///
+    /// `path` includes the query string, as OKX signs it. In sandbox mode the
+    /// request goes to the demo account.
+    fn signed_headers(
+        &self,
+        request: reqwest::RequestBuilder,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> reqwest::RequestBuilder {
+        let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+        // Demo trading shares the production host; this header selects it
+        let request = if self.sandbox {
+            request.header("x-simulated-trading", "1")
+        } else {
+            request
+        };
+        request
+            .header("OK-ACCESS-KEY", self.credentials.api_key())
+            .header("OK-ACCESS-PASSPHRASE", self.credentials.passphrase())
+            .header(
+                "OK-ACCESS-SIGN",
+                self.credentials.sign(&timestamp, method, path, body),
+            )
+            .header("OK-ACCESS-TIMESTAMP", timestamp)
+    }
+
+    /// Make a GET request, signed when `signed` is set
+    async fn get<R>(&self, endpoint: &str, query: &[(&str, String)], signed: bool) -> Result<Vec<R>>
+    where
+        R: serde::de::DeserializeOwned,
+    {
+        let query = serde_urlencoded::to_string(query).context("Failed to encode query")?;
+        let path = if query.is_empty() {
+            endpoint.to_string()
+        } else {
+            format!("{}?{}", endpoint, query)
+        };
+        let mut request = self.http_client.get(format!("{}{}", API_BASE_URL, path));
+        if signed {
+            request = self.signed_headers(request, "GET", &path, "");
+        }
+        let response = request.send().await.context("Failed to send request")?;
+        self.parse_response(response).await
+    }
+
+    /// Make an authenticated POST request with a JSON body
+    async fn authenticated_post<T, R>(&self, endpoint: &str, body: &T) -> Result<Vec<R>>
+    where
+        T: serde::Serialize,
+        R: serde::de::DeserializeOwned,
+    {
+        let url = format!("{}{}", API_BASE_URL, endpoint);
+        let json_body = serde_json::to_string(body)?;
+        let request = self
+            .http_client
+            .post(&url)
+            .header("Content-Type", "application/json");
+        let response = self
+            .signed_headers(request, "POST", endpoint, &json_body)
+            .body(json_body)
+            .send()
+            .await
+            .context("Failed to send request")?;
+        self.parse_response(response).await
+    }
+
+    // ==================== PUBLIC ENDPOINTS ====================
+
+    /// Get ticker for a symbol on a market
+    pub async fn get_ticker(&self, symbol: &str, market: Market) -> Result<Ticker> {
+        self.get_ticker_with_priority(symbol, market, RequestPriority::Normal)
+            .await
+    }
+
+    /// Get ticker with an explicit rate-limit priority
+    pub async fn get_ticker_with_priority(
+        &self,
+        symbol: &str,
+        market: Market,
+        priority: RequestPriority,
+    ) -> Result<Ticker> {
+        let inst_id = self.inst_id(symbol, market);
+        self.execute_with_retry("ticker", priority, || {
+            let query = [("instId", inst_id.clone())];
+            let this = self.clone();
+
+            async move {
+                let tickers: Vec<Ticker> = this.get("/api/v5/market/ticker", &query, false).await?;
+                tickers
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| anyhow!("Ticker not found for {}", query[0].1))
+            }
+        })
+        .await
+    }
+
+    /// Get up to 300 recent candles, oldest first
+    ///
+    /// `after` (Unix ms) returns candles that opened before it, for paging
+    /// back through the last few days; older history needs
+    /// [`fetch_full_history`](Self::fetch_full_history).
+    pub async fn get_candles(
+        &self,
+        symbol: &str,
+        market: Market,
+        interval: &str,
+        after: Option<i64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<OkxCandle>> {
+        self.candles(
+            "/api/v5/market/candles",
+            symbol,
+            market,
+            interval,
+            after,
+            limit.unwrap_or(MAX_CANDLES_PER_REQUEST),
+        )
+        .await
+    }
+
+    async fn candles(
+        &self,
+        endpoint: &str,
+        symbol: &str,
+        market: Market,
+        interval: &str,
+        after: Option<i64>,
+        limit: u32,
+    ) -> Result<Vec<OkxCandle>> {
+        let bar = to_okx_bar(interval)
+            .with_context(|| format!("OKX does not serve {} candles", interval))?;
+        let mut query = vec![
+            ("instId", self.inst_id(symbol, market)),
+            ("bar", bar.to_string()),
+            ("limit", limit.to_string()),
+        ];
+        if let Some(after) = after {
+            query.push(("after", after.to_string()));
+        }
+
+        let rows: Vec<Vec<String>> = self
+            .execute_with_retry("candles", RequestPriority::Normal, || {
+                let query = query.clone();
+                let this = self.clone();
+
+                async move { this.get(endpoint, &query, false).await }
+            })
+            .await?;
+
+        // Newest first on the wire
+        let mut candles: Vec<OkxCandle> = rows
+            .iter()
+            .filter_map(|row| OkxCandle::from_raw(row))
+            .collect();
+        candles.sort_by_key(|c| c.ts);
+        Ok(candles)
+    }
+
+    /// Fetch `days_back` days of closed candles, oldest first
+    pub async fn fetch_full_history(
+        &self,
+        symbol: &str,
+        market: Market,
+        interval: &str,
+        days_back: u32,
+    ) -> Result<Vec<OkxCandle>> {
+        let start = (Utc::now() - ChronoDuration::days(days_back as i64)).timestamp_millis();
+        let mut after = None;
+        info!(
+            "Fetching {} {} data from OKX ({} days back)",
+            self.inst_id(symbol, market),
+            interval,
+            days_back
+        );
+
+        let mut candles = Vec::new();
+        loop {
+            let batch = self
+                .candles(
+                    "/api/v5/market/history-candles",
+                    symbol,
+                    market,
+                    interval,
+                    after,
+                    MAX_HISTORY_PER_REQUEST,
+                )
+                .await?;
+            let Some(oldest) = batch.first().map(|c| c.ts) else {
+                break;
+            };
+            candles.extend(batch);
+            if oldest <= start {
+                break;
+            }
+            after = Some(oldest);
+        }
+
+        candles.retain(|c| c.ts >= start && c.confirmed);
+        candles.sort_by_key(|c| c.ts);
+        candles.dedup_by_key(|c| c.ts);
+        Ok(candles)
+    }
+
+    // ==================== AUTHENTICATED ENDPOINTS ====================
+
+    /// Get trading account balances
+    pub async fn get_balances(&self) -> Result<Vec<Balance>> {
+        let accounts: Vec<AccountBalance> = self
+            .execute_with_retry("balances", RequestPriority::Normal, || {
+                let this = self.clone();
+
+                async move { this.get("/api/v5/account/balance", &[], true).await }
+            })
+            .await?;
+        Ok(accounts.into_iter().flat_map(|a| a.details).collect())
+    }
+
+    /// Place a new order
+    pub async fn place_order(&self, order: &OrderRequest) -> Result<OrderResult> {
+        let order = order.clone();
+        let results: Vec<OrderResult> = self
+            .execute_with_retry("place_order", RequestPriority::Critical, || {
+                let ord = order.clone();
+                let this = self.clone();
+
+                async move { this.authenticated_post("/api/v5/trade/order", &ord).await }
+            })
+            .await?;
+        results
+            .into_iter()
+            .next()
+            .context("OKX accepted the order without an ID")
+    }
+
+    /// Cancel an order by ID
+    pub async fn cancel_order(&self, symbol: &str, market: Market, ord_id: &str) -> Result<()> {
+        let body = serde_json::json!({
+            "instId": self.inst_id(symbol, market),
+            "ordId": ord_id,
+        });
+        self.execute_with_retry("cancel_order", RequestPriority::Critical, || {
+            let body = body.clone();
+            let this = self.clone();
+
+            async move {
+                let _: Vec<OrderResult> = this
+                    .authenticated_post("/api/v5/trade/cancel-order", &body)
+                    .await?;
+                Ok(())
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_to_inst_id() {
+        assert_eq!(symbol_to_inst_id("BTCUSDT", Market::Spot), "BTC-USDT");
+        assert_eq!(symbol_to_inst_id("eth/usdc", Market::Spot), "ETH-USDC");
+        assert_eq!(symbol_to_inst_id("SOL", Market::Swap), "SOL-USDT-SWAP");
+        assert_eq!(
+            symbol_to_inst_id("BTC-USDT-SWAP", Market::Swap),
+            "BTC-USDT-SWAP"
+        );
+        assert_eq!(symbol_to_inst_id("BTC-USDT-SWAP", Market::Spot), "BTC-USDT");
+    }
+
+    #[test]
+    fn test_inst_ids_go_through_the_symbol_mapper() {
+        let symbols =
+            SymbolMapper::new().with_override(Venue::OkxSwap, "PEPEUSDT", "PEPE-USDT-SWAP2");
+        let client = OkxClient::with_config(
+            "key",
+            "secret",
+            "pass",
+            ClientConfig::default().with_symbols(symbols),
+        );
+        assert_eq!(client.inst_id("PEPEUSDT", Market::Swap), "PEPE-USDT-SWAP2");
+        assert_eq!(client.inst_id("PEPEUSDT", Market::Spot), "PEPE-USDT");
+        assert_eq!(client.inst_id("btc/usdt", Market::Swap), "BTC-USDT-SWAP");
+    }
+
+    #[test]
+    fn test_sandbox_selects_demo_trading() {
+        let simulated = |client: &OkxClient| {
+            let request = client.http_client.get(API_BASE_URL);
+            let request = client
+                .signed_headers(request, "GET", "/api/v5/account/balance", "")
+                .build()
+                .unwrap();
+            request.headers().get("x-simulated-trading").cloned()
+        };
+        assert_eq!(simulated(&OkxClient::new("key", "secret", "pass")), None);
+
+        let config = ClientConfig::default().with_sandbox(true);
+        let client = OkxClient::with_config("key", "secret", "pass", config);
+        assert_eq!(simulated(&client).unwrap(), "1");
+    }
+}
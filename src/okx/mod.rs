@@ -0,0 +1,98 @@
+//! OKX Exchange API Library
+//!
+//! Client for OKX spot markets and USDT perpetual swaps, mirroring
+//! [`crate::coindcx`]: retries with exponential backoff, token-bucket rate
+//! limiting, a circuit breaker and typed request/response models. A client
+//! trades one [`Market`]; candles and tickers can be fetched for either.
+//!
+//! # Quick Start
+//!
+//! ```no_run
+//! use crypto_strategies::okx::{ClientConfig, Market, OkxClient, OrderRequest, OrderSide};
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     // Expects OKX_API_KEY, OKX_API_SECRET and OKX_PASSPHRASE
+//!     let client = OkxClient::from_env()?;
+//!
+//!     let candles = client.get_candles("BTCUSDT", Market::Swap, "1h", None, None).await?;
+//!     println!("{} hourly perp candles", candles.len());
+//!
+//!     let order = OrderRequest::limit(OrderSide::Buy, "BTC-USDT", Market::Spot, 0.001, 25000.0);
+//!     let placed = client.place_order(&order).await?;
+//!     println!("Order placed: {}", placed.ord_id);
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! # Modules
+//!
+//! - [`auth`]: `OK-ACCESS-SIGN` generation (HMAC-SHA256, base64)
+//! - [`types`]: Request and response type definitions
+//! - [`client`]: Main API client implementation
+
+pub mod auth;
+pub mod client;
+pub mod types;
+
+pub use auth::Credentials;
+pub use client::{symbol_to_inst_id, ClientConfig, OkxClient, API_BASE_URL};
+pub use types::{
+    Balance, Market, OkxCandle, OrderRequest, OrderResult, OrderSide, OrderType, Response, Ticker,
+};
+
+use crate::capabilities::{ExchangeCapabilities, PrecisionRule};
+
+/// Bars served by `/api/v5/market/candles`
+pub const OKX_BARS: &[&str] = &[
+    "1m", "3m", "5m", "15m", "30m", "1h", "2h", "4h", "6h", "12h", "1d", "1w",
+];
+
+/// Candles `bar` parameter for a timeframe
+///
+/// Bars of 6h and longer default to Hong Kong time on OKX; the `utc`
+/// variants keep them aligned with every other venue.
+pub fn to_okx_bar(timeframe: &str) -> Option<&'static str> {
+    match timeframe {
+        "1m" => Some("1m"),
+        "3m" => Some("3m"),
+        "5m" => Some("5m"),
+        "15m" => Some("15m"),
+        "30m" => Some("30m"),
+        "1h" => Some("1H"),
+        "2h" => Some("2H"),
+        "4h" => Some("4H"),
+        "6h" => Some("6Hutc"),
+        "12h" => Some("12Hutc"),
+        "1d" => Some("1Dutc"),
+        "1w" => Some("1Wutc"),
+        _ => None,
+    }
+}
+
+/// Spot and USDT swaps: market and limit orders, per-instrument lot sizes
+pub const CAPABILITIES: ExchangeCapabilities = ExchangeCapabilities {
+    name: "okx",
+    order_types: &[crate::oms::OrderType::Market, crate::oms::OrderType::Limit],
+    timeframes: OKX_BARS,
+    quote_currencies: &["USDT", "USDC", "USD"],
+    precision: PrecisionRule::PerMarket,
+    min_notional: None,
+    sandbox: true,
+    fill_reports: false,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_timeframe_has_a_bar() {
+        for tf in OKX_BARS {
+            assert!(to_okx_bar(tf).is_some(), "{}", tf);
+        }
+        assert_eq!(to_okx_bar("1d"), Some("1Dutc"));
+        assert_eq!(to_okx_bar("1M"), None);
+    }
+}
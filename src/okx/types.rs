@@ -0,0 +1,293 @@
+//! Type definitions for OKX v5 API requests and responses
+//!
+//! Responses are wrapped in `{"code": "0", "msg": "", "data": [...]}` and
+//! carry prices, sizes and timestamps as strings.
+
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Response envelope shared by every endpoint
+///
+/// A failed order comes back with `code` `"1"` and the reason in
+/// `data[0].sMsg`, so that is reported in preference to the generic `msg`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Response {
+    pub code: String,
+    #[serde(default)]
+    pub msg: String,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+impl Response {
+    /// The data array, or the error OKX reported
+    pub fn into_result<T: DeserializeOwned>(self) -> Result<Vec<T>> {
+        if self.code != "0" {
+            let detail = self
+                .data
+                .get(0)
+                .and_then(|d| d.get("sMsg"))
+                .and_then(|m| m.as_str())
+                .filter(|m| !m.is_empty())
+                .unwrap_or(&self.msg);
+            return Err(anyhow!("OKX error {}: {}", self.code, detail));
+        }
+        serde_json::from_value(self.data).context("Failed to parse OKX data")
+    }
+}
+
+fn parse(value: &str) -> Option<f64> {
+    value.parse().ok()
+}
+
+/// Which of OKX's markets an instrument trades on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Market {
+    /// Spot pairs such as `BTC-USDT`
+    #[default]
+    Spot,
+    /// Perpetual swaps such as `BTC-USDT-SWAP`, sized in contracts
+    Swap,
+}
+
+impl Market {
+    /// `tdMode` of orders: cash for spot, cross margin for swaps
+    pub fn trade_mode(self) -> &'static str {
+        match self {
+            Market::Spot => "cash",
+            Market::Swap => "cross",
+        }
+    }
+}
+
+/// Ticker from `/api/v5/market/ticker`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ticker {
+    pub inst_id: String,
+    pub last: String,
+    #[serde(default)]
+    pub bid_px: String,
+    #[serde(default)]
+    pub ask_px: String,
+    /// 24h volume: base asset on spot, contracts on swaps
+    #[serde(default)]
+    pub vol24h: String,
+}
+
+impl Ticker {
+    pub fn last_price_f64(&self) -> Option<f64> {
+        parse(&self.last)
+    }
+
+    pub fn bid_f64(&self) -> Option<f64> {
+        parse(&self.bid_px)
+    }
+
+    pub fn ask_f64(&self) -> Option<f64> {
+        parse(&self.ask_px)
+    }
+
+    pub fn volume_f64(&self) -> Option<f64> {
+        parse(&self.vol24h)
+    }
+}
+
+/// One candle from `/api/v5/market/candles`
+#[derive(Debug, Clone, PartialEq)]
+pub struct OkxCandle {
+    /// Candle open time (Unix ms)
+    pub ts: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Volume in the base asset (spot) or contracts (swaps)
+    pub volume: f64,
+    /// Whether the candle has closed
+    pub confirmed: bool,
+}
+
+impl OkxCandle {
+    /// Parse from the raw `[ts, o, h, l, c, vol, volCcy, volCcyQuote, confirm]` row
+    pub fn from_raw(raw: &[String]) -> Option<Self> {
+        if raw.len() < 6 {
+            return None;
+        }
+
+        Some(OkxCandle {
+            ts: raw[0].parse().ok()?,
+            open: parse(&raw[1])?,
+            high: parse(&raw[2])?,
+            low: parse(&raw[3])?,
+            close: parse(&raw[4])?,
+            volume: parse(&raw[5])?,
+            // Older rows omit `confirm`; they are always closed
+            confirmed: raw.get(8).is_none_or(|c| c == "1"),
+        })
+    }
+}
+
+/// Order side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+/// Order type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderType {
+    Market,
+    Limit,
+}
+
+/// JSON body of `POST /api/v5/trade/order`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderRequest {
+    pub inst_id: String,
+    pub td_mode: String,
+    pub side: OrderSide,
+    pub ord_type: OrderType,
+    /// Base asset on spot, contracts on swaps
+    pub sz: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub px: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cl_ord_id: Option<String>,
+    /// Spot market orders size in the base asset instead of OKX's quote default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tgt_ccy: Option<String>,
+}
+
+impl OrderRequest {
+    /// Create a market order; `inst_id` is an OKX instrument (see
+    /// [`super::symbol_to_inst_id`])
+    pub fn market(side: OrderSide, inst_id: impl Into<String>, market: Market, sz: f64) -> Self {
+        Self {
+            inst_id: inst_id.into(),
+            td_mode: market.trade_mode().to_string(),
+            side,
+            ord_type: OrderType::Market,
+            sz: sz.to_string(),
+            px: None,
+            cl_ord_id: None,
+            tgt_ccy: (market == Market::Spot).then(|| "base_ccy".to_string()),
+        }
+    }
+
+    /// Create a limit order
+    pub fn limit(
+        side: OrderSide,
+        inst_id: impl Into<String>,
+        market: Market,
+        sz: f64,
+        price: f64,
+    ) -> Self {
+        Self {
+            ord_type: OrderType::Limit,
+            px: Some(price.to_string()),
+            tgt_ccy: None,
+            ..Self::market(side, inst_id, market, sz)
+        }
+    }
+
+    /// Set client order ID (alphanumeric, up to 32 characters)
+    pub fn with_client_order_id(mut self, id: impl Into<String>) -> Self {
+        self.cl_ord_id = Some(id.into());
+        self
+    }
+}
+
+/// Result of `/api/v5/trade/order` and `/api/v5/trade/cancel-order`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderResult {
+    pub ord_id: String,
+    #[serde(default)]
+    pub cl_ord_id: String,
+    /// `"0"` when the order was accepted
+    #[serde(default)]
+    pub s_code: String,
+    #[serde(default)]
+    pub s_msg: String,
+}
+
+/// Account summary from `/api/v5/account/balance`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountBalance {
+    #[serde(default)]
+    pub details: Vec<Balance>,
+}
+
+/// One currency of the trading account
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Balance {
+    pub ccy: String,
+    #[serde(default)]
+    pub avail_bal: String,
+    /// Held by open orders and margin
+    #[serde(default)]
+    pub frozen_bal: String,
+}
+
+impl Balance {
+    pub fn available_f64(&self) -> f64 {
+        parse(&self.avail_bal).unwrap_or_default()
+    }
+
+    pub fn frozen_f64(&self) -> f64 {
+        parse(&self.frozen_bal).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_reports_order_rejections() {
+        let ok: Response = serde_json::from_str(
+            r#"{"code":"0","msg":"","data":[{"ordId":"312269865356374016","clOrdId":"b1","sCode":"0","sMsg":""}]}"#,
+        )
+        .unwrap();
+        let placed: Vec<OrderResult> = ok.into_result().unwrap();
+        assert_eq!(placed[0].ord_id, "312269865356374016");
+
+        let rejected: Response = serde_json::from_str(
+            r#"{"code":"1","msg":"Operation failed.","data":[{"ordId":"","sCode":"51008","sMsg":"Order failed. Insufficient USDT balance in account."}]}"#,
+        )
+        .unwrap();
+        let err = rejected.into_result::<OrderResult>().unwrap_err();
+        assert!(err.to_string().contains("Insufficient USDT balance"));
+    }
+
+    #[test]
+    fn test_candle_and_order_body() {
+        let row: Vec<String> = serde_json::from_str(
+            r#"["1597026383085","3.721","3.743","3.677","3.708","8422410","22698348.04828491","12698348.04828491","0"]"#,
+        )
+        .unwrap();
+        let candle = OkxCandle::from_raw(&row).unwrap();
+        assert_eq!(candle.ts, 1597026383085);
+        assert_eq!(candle.high, 3.743);
+        assert!(!candle.confirmed);
+        assert!(OkxCandle::from_raw(&row[..5]).is_none());
+
+        let order = OrderRequest::market(OrderSide::Buy, "BTC-USDT", Market::Spot, 0.01)
+            .with_client_order_id("b1");
+        assert_eq!(
+            serde_json::to_string(&order).unwrap(),
+            r#"{"instId":"BTC-USDT","tdMode":"cash","side":"buy","ordType":"market","sz":"0.01","clOrdId":"b1","tgtCcy":"base_ccy"}"#
+        );
+        let swap =
+            OrderRequest::limit(OrderSide::Sell, "BTC-USDT-SWAP", Market::Swap, 2.0, 65000.0);
+        assert_eq!(swap.td_mode, "cross");
+        assert_eq!(swap.tgt_ccy, None);
+    }
+}
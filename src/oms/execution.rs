@@ -28,6 +28,15 @@ impl ExecutionEngine {
         }
     }
 
+    /// Slippage applied to stop fills, as a fraction of price
+    pub fn slippage(&self) -> f64 {
+        self.slippage
+    }
+
+    pub fn set_slippage(&mut self, slippage: f64) {
+        self.slippage = slippage;
+    }
+
     pub fn check_fill(
         &self,
         order: &Order,
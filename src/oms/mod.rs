@@ -14,6 +14,9 @@
 pub mod execution;
 pub mod orderbook;
 pub mod position_manager;
+pub mod post_only;
+pub mod pre_trade;
+pub mod spread_guard;
 pub mod strategy;
 pub mod types;
 
@@ -2,7 +2,7 @@
 //!
 //! Uses BTreeMap for efficient price-sorted storage and VecDeque for FIFO ordering.
 
-use crate::oms::types::{Order, OrderId, OrderState};
+use crate::oms::types::{Order, OrderId, OrderState, TimeInForce};
 use crate::Side;
 use ordered_float::OrderedFloat;
 use std::collections::{BTreeMap, HashMap, VecDeque};
@@ -88,6 +88,26 @@ impl OrderBook {
         Some(order)
     }
 
+    /// Remove GTD orders whose deadline has passed, returning them as `Expired`
+    pub fn expire_orders(&mut self, now: chrono::DateTime<chrono::Utc>) -> Vec<Order> {
+        let expired: Vec<OrderId> = self
+            .orders
+            .values()
+            .filter(|o| o.is_active())
+            .filter(|o| matches!(o.time_in_force, TimeInForce::GTD(deadline) if deadline <= now))
+            .map(|o| o.id)
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|id| self.cancel_order(id))
+            .map(|mut order| {
+                order.state = OrderState::Expired;
+                order
+            })
+            .collect()
+    }
+
     /// Get orders that would fill at given price
     /// Returns: Vec<OrderId> sorted by priority (price-time)
     pub fn get_fillable_orders(&self, price: f64, side: Side) -> Vec<OrderId> {
@@ -202,7 +222,7 @@ impl Default for OrderBook {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::oms::types::{OrderType, TimeInForce};
+    use crate::oms::types::OrderType;
     use crate::Symbol;
 
     #[test]
@@ -310,4 +330,41 @@ mod tests {
         assert_eq!(book.best_bid(), Some(50000.0)); // Highest buy
         assert_eq!(book.best_ask(), Some(51000.0)); // Lowest sell
     }
+
+    #[test]
+    fn test_expire_gtd_orders() {
+        let now = chrono::Utc::now();
+        let mut book = OrderBook::new();
+
+        let gtd = Order::from_f64(
+            Symbol::new("BTCINR"),
+            Side::Buy,
+            OrderType::Limit,
+            1.0,
+            Some(100.0),
+            None,
+            TimeInForce::GTD(now - chrono::Duration::seconds(1)),
+            None,
+        );
+        let gtd_id = gtd.id;
+        let gtc = Order::from_f64(
+            Symbol::new("BTCINR"),
+            Side::Buy,
+            OrderType::Limit,
+            1.0,
+            Some(100.0),
+            None,
+            TimeInForce::GTC,
+            None,
+        );
+        book.add_order(gtd);
+        book.add_order(gtc);
+
+        let expired = book.expire_orders(now);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].id, gtd_id);
+        assert_eq!(expired[0].state, OrderState::Expired);
+        assert_eq!(book.len(), 1);
+        assert_eq!(book.best_bid(), Some(100.0));
+    }
 }
@@ -42,6 +42,7 @@ impl PositionManager {
             } else {
                 // Opposite side - reduce or reverse position
                 let mut remaining_qty = fill.quantity;
+                let prev_qty = position.quantity;
 
                 while !remaining_qty.is_zero() && !position.fills.is_empty() {
                     let first_fill = &mut position.fills[0];
@@ -61,12 +62,21 @@ impl PositionManager {
                             Side::Sell => (first_fill.price - fill.price) * remaining_qty,
                         };
                         position.realized_pnl = position.realized_pnl + pnl - fill.commission;
+                        // The part left open keeps only its share of the entry commission
+                        first_fill.commission = first_fill.commission
+                            * (first_fill.quantity - remaining_qty)
+                            / first_fill.quantity;
                         first_fill.quantity -= remaining_qty;
                         position.quantity -= remaining_qty;
                         remaining_qty = Money::ZERO;
                     }
                 }
 
+                // Accrued borrow interest stays with the quantity still open
+                if prev_qty.is_positive() {
+                    position.borrow_cost = position.borrow_cost * position.quantity / prev_qty;
+                }
+
                 // If remaining, reverse position
                 if !remaining_qty.is_zero() {
                     position.side = match position.side {
@@ -254,4 +264,59 @@ mod tests {
         // Realized P&L = (53000-50000)*1.0 + (53000-51000)*0.5 = 3000 + 1000 = 4000
         assert_eq!(pos.realized_pnl.to_f64(), 4000.0);
     }
+
+    #[test]
+    fn test_partial_close_splits_entry_commission() {
+        let mut pm = PositionManager::new();
+        let symbol = Symbol::new("BTCUSDT");
+
+        let entry = Fill::from_f64(1, 50000.0, 2.0, Utc::now(), 40.0, false);
+        pm.add_fill(entry, symbol.clone(), Side::Buy);
+        pm.add_fill(create_fill(2, 52000.0, 0.5), symbol.clone(), Side::Sell);
+
+        // 1.5 of 2.0 still open: it carries 3/4 of the entry commission
+        let pos = pm.get_position(&symbol).unwrap();
+        assert_eq!(pos.fills[0].quantity.to_f64(), 1.5);
+        assert_eq!(pos.fills[0].commission.to_f64(), 30.0);
+
+        pm.add_fill(create_fill(3, 52000.0, 0.75), symbol.clone(), Side::Sell);
+        let pos = pm.get_position(&symbol).unwrap();
+        assert_eq!(pos.fills[0].commission.to_f64(), 15.0);
+    }
+
+    #[test]
+    fn test_borrow_cost_follows_open_short() {
+        use crate::config::BorrowConfig;
+        use chrono::Duration;
+
+        let borrow = BorrowConfig {
+            default_apr: 0.10,
+            assets: [("DOGE".to_string(), 0.365)].into_iter().collect(),
+        };
+        assert_eq!(borrow.apr("DOGEINR"), 0.365);
+        assert_eq!(borrow.apr("BTCUSDT"), 0.10);
+        let start = Utc::now();
+        // 10,000 borrowed for one day at 36.5% APR
+        let interest = borrow.interest("DOGEINR", 10_000.0, start, start + Duration::days(1));
+        assert!((interest - 10.0).abs() < 1e-9);
+        assert_eq!(borrow.interest("DOGEINR", 10_000.0, start, start), 0.0);
+
+        let mut pm = PositionManager::new();
+        let symbol = Symbol::new("DOGEINR");
+        pm.add_fill(create_fill(1, 10.0, 1000.0), symbol.clone(), Side::Sell);
+        pm.get_position_mut(&symbol)
+            .unwrap()
+            .accrue_borrow(interest);
+
+        // Covering 40% leaves 60% of the interest on the open quantity
+        pm.add_fill(create_fill(2, 9.0, 400.0), symbol.clone(), Side::Buy);
+        let pos = pm.get_position(&symbol).unwrap();
+        assert!((pos.borrow_cost.to_f64() - 6.0).abs() < 1e-9);
+
+        // Reversing into a long starts clean
+        pm.add_fill(create_fill(3, 9.0, 700.0), symbol.clone(), Side::Buy);
+        let pos = pm.get_position(&symbol).unwrap();
+        assert_eq!(pos.side, Side::Buy);
+        assert!(pos.borrow_cost.is_zero());
+    }
 }
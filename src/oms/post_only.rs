@@ -0,0 +1,119 @@
+//! Post-only (maker) limit orders
+//!
+//! Grid strategies place many limit orders, and every fill pays a fee: resting
+//! orders pay the maker rate where orders that take liquidity pay the taker
+//! rate. With `"fee_mode": "maker"` in the strategy config, limit orders are
+//! sent with [`TimeInForce::PostOnly`] and the exchange rejects any that would
+//! match on arrival. [`PostOnlyConfig::check`] catches that case first, against
+//! the current bid/ask (the bar close in backtests), and either re-prices the
+//! order just behind the opposite best quote or drops it.
+
+use crate::config::PostOnlyConfig;
+use crate::oms::types::{Order, OrderType, TimeInForce};
+use crate::{Money, Side};
+
+/// Outcome of the post-only check for one order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PostOnlyDecision {
+    /// Rests on the book as is (or isn't a post-only limit)
+    Rest,
+    /// Would cross; move the limit to `price`
+    Reprice { from: f64, price: f64 },
+    /// Would cross and re-pricing is disabled; drop the order
+    Reject { price: f64 },
+}
+
+impl PostOnlyConfig {
+    /// Decide what to do with `order` given the current best bid/ask
+    pub fn check(&self, order: &Order, bid: f64, ask: f64) -> PostOnlyDecision {
+        if !is_post_only(order) || bid <= 0.0 || ask < bid {
+            return PostOnlyDecision::Rest;
+        }
+        let Some(limit) = order.limit_price.map(|p| p.to_f64()) else {
+            return PostOnlyDecision::Rest;
+        };
+        let offset = self.offset_bps / 10_000.0;
+        let (crosses, price) = match order.side {
+            Side::Buy => (limit >= ask, limit.min(ask * (1.0 - offset))),
+            Side::Sell => (limit <= bid, limit.max(bid * (1.0 + offset))),
+        };
+        if !crosses {
+            PostOnlyDecision::Rest
+        } else if self.reprice {
+            PostOnlyDecision::Reprice { from: limit, price }
+        } else {
+            PostOnlyDecision::Reject { price: limit }
+        }
+    }
+}
+
+/// A limit order that must rest as a maker order
+pub fn is_post_only(order: &Order) -> bool {
+    order.order_type == OrderType::Limit && order.time_in_force == TimeInForce::PostOnly
+}
+
+/// Apply a [`PostOnlyDecision::Reprice`]
+pub fn reprice(order: &mut Order, price: f64) {
+    order.limit_price = Some(Money::from_f64(price));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Symbol;
+
+    fn limit(side: Side, price: f64, tif: TimeInForce) -> Order {
+        Order::from_f64(
+            Symbol::new("BTCINR"),
+            side,
+            OrderType::Limit,
+            1.0,
+            Some(price),
+            None,
+            tif,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_crossing_orders_are_repriced_behind_the_quote() {
+        let config = PostOnlyConfig::default();
+        let resting = limit(Side::Buy, 99.0, TimeInForce::PostOnly);
+        assert_eq!(config.check(&resting, 99.5, 100.0), PostOnlyDecision::Rest);
+
+        let buy = limit(Side::Buy, 100.5, TimeInForce::PostOnly);
+        match config.check(&buy, 99.5, 100.0) {
+            PostOnlyDecision::Reprice { from, price } => {
+                assert_eq!(from, 100.5);
+                assert!((price - 99.99).abs() < 1e-9);
+            }
+            other => panic!("expected reprice, got {:?}", other),
+        }
+
+        let sell = limit(Side::Sell, 99.0, TimeInForce::PostOnly);
+        match config.check(&sell, 99.5, 100.0) {
+            PostOnlyDecision::Reprice { price, .. } => assert!((price - 99.50995).abs() < 1e-9),
+            other => panic!("expected reprice, got {:?}", other),
+        }
+
+        // Plain limits and unusable quotes are left alone
+        let plain = limit(Side::Buy, 100.5, TimeInForce::GTC);
+        assert_eq!(config.check(&plain, 99.5, 100.0), PostOnlyDecision::Rest);
+        assert_eq!(config.check(&buy, 0.0, 100.0), PostOnlyDecision::Rest);
+    }
+
+    #[test]
+    fn test_crossing_orders_rejected_without_reprice() {
+        let config = PostOnlyConfig {
+            reprice: false,
+            ..Default::default()
+        };
+        let mut buy = limit(Side::Buy, 100.0, TimeInForce::PostOnly);
+        assert_eq!(
+            config.check(&buy, 99.5, 100.0),
+            PostOnlyDecision::Reject { price: 100.0 }
+        );
+        reprice(&mut buy, 99.0);
+        assert_eq!(config.check(&buy, 99.5, 100.0), PostOnlyDecision::Rest);
+    }
+}
@@ -0,0 +1,380 @@
+//! Pre-trade compliance checks
+//!
+//! Operational policies (what may be traded, how big, when, at what price)
+//! belong outside strategy code. A [`PreTradeChain`] runs every strategy
+//! order through a list of [`PreTradeCheck`]s before it reaches the order book
+//! or the exchange; the first check to object rejects the order.
+//!
+//! Chains are built from `trading.pre_trade_checks` and can be extended with
+//! custom checks via [`PreTradeChain::with_check`]. Built-in checks:
+//!
+//! - [`SymbolListCheck`]: allow/deny lists
+//! - [`MaxOrderValueCheck`]: notional cap per order
+//! - [`RestrictedHoursCheck`]: UTC windows without new exposure
+//! - [`PriceBandCheck`]: limit/stop price sanity versus the last close
+//!
+//! Symbol lists, the notional cap and restricted hours never block an order
+//! that reduces an open position, so a policy change can't trap a position.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveTime, Utc};
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::config::{PreTradeCheckConfig, TimeWindow};
+use crate::oms::types::Order;
+
+/// Market state an order is checked against
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreTradeContext {
+    /// Close of the latest bar for the order's symbol
+    pub last_close: f64,
+    pub now: DateTime<Utc>,
+    /// Order closes or shrinks an open position
+    pub reduces_position: bool,
+}
+
+impl PreTradeContext {
+    /// Price the order is expected to trade at: its limit, else the last close
+    pub fn order_price(&self, order: &Order) -> f64 {
+        order
+            .limit_price
+            .map(|p| p.to_f64())
+            .unwrap_or(self.last_close)
+    }
+}
+
+/// A single policy an order must satisfy
+pub trait PreTradeCheck: Send + Sync + fmt::Debug {
+    /// Short name used in rejection messages
+    fn name(&self) -> &str;
+
+    /// `Err(reason)` when the order must not be sent
+    fn check(&self, order: &Order, ctx: &PreTradeContext) -> Result<(), String>;
+}
+
+/// Why a chain rejected an order
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreTradeRejection {
+    pub check: String,
+    pub reason: String,
+}
+
+impl fmt::Display for PreTradeRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.check, self.reason)
+    }
+}
+
+/// Only trade allowed symbols, never denied ones
+#[derive(Debug, Clone, Default)]
+pub struct SymbolListCheck {
+    pub allow: HashSet<String>,
+    pub deny: HashSet<String>,
+}
+
+impl PreTradeCheck for SymbolListCheck {
+    fn name(&self) -> &str {
+        "symbols"
+    }
+
+    fn check(&self, order: &Order, ctx: &PreTradeContext) -> Result<(), String> {
+        let symbol = order.symbol.as_str();
+        if ctx.reduces_position {
+            return Ok(());
+        }
+        if self.deny.contains(symbol) {
+            return Err(format!("{} is on the deny list", symbol));
+        }
+        if !self.allow.is_empty() && !self.allow.contains(symbol) {
+            return Err(format!("{} is not on the allow list", symbol));
+        }
+        Ok(())
+    }
+}
+
+/// Cap on a single order's notional
+#[derive(Debug, Clone, Copy)]
+pub struct MaxOrderValueCheck {
+    pub max: f64,
+}
+
+impl PreTradeCheck for MaxOrderValueCheck {
+    fn name(&self) -> &str {
+        "max_order_value"
+    }
+
+    fn check(&self, order: &Order, ctx: &PreTradeContext) -> Result<(), String> {
+        if ctx.reduces_position {
+            return Ok(());
+        }
+        let value = order.quantity.to_f64() * ctx.order_price(order);
+        if value > self.max {
+            return Err(format!("order value {:.2} exceeds {:.2}", value, self.max));
+        }
+        Ok(())
+    }
+}
+
+/// UTC time-of-day windows without new exposure
+#[derive(Debug, Clone, Default)]
+pub struct RestrictedHoursCheck {
+    pub windows: Vec<(NaiveTime, NaiveTime)>,
+}
+
+impl RestrictedHoursCheck {
+    pub fn from_config(windows: &[TimeWindow]) -> Result<Self> {
+        let parse = |s: &str| {
+            NaiveTime::parse_from_str(s, "%H:%M")
+                .with_context(|| format!("Invalid time {:?} (expected HH:MM)", s))
+        };
+        Ok(Self {
+            windows: windows
+                .iter()
+                .map(|w| Ok((parse(&w.start)?, parse(&w.end)?)))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Window containing `time`, if any
+    pub fn window_at(&self, time: NaiveTime) -> Option<(NaiveTime, NaiveTime)> {
+        self.windows.iter().copied().find(|&(start, end)| {
+            if start <= end {
+                time >= start && time < end
+            } else {
+                time >= start || time < end
+            }
+        })
+    }
+}
+
+impl PreTradeCheck for RestrictedHoursCheck {
+    fn name(&self) -> &str {
+        "restricted_hours"
+    }
+
+    fn check(&self, _order: &Order, ctx: &PreTradeContext) -> Result<(), String> {
+        if ctx.reduces_position {
+            return Ok(());
+        }
+        match self.window_at(ctx.now.time()) {
+            Some((start, end)) => Err(format!(
+                "{} UTC is inside restricted window {}-{}",
+                ctx.now.format("%H:%M"),
+                start.format("%H:%M"),
+                end.format("%H:%M")
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Limit and stop prices must sit near the last close
+#[derive(Debug, Clone, Copy)]
+pub struct PriceBandCheck {
+    pub max_deviation_pct: f64,
+}
+
+impl PreTradeCheck for PriceBandCheck {
+    fn name(&self) -> &str {
+        "price_band"
+    }
+
+    fn check(&self, order: &Order, ctx: &PreTradeContext) -> Result<(), String> {
+        if ctx.last_close <= 0.0 {
+            return Ok(());
+        }
+        for price in [order.limit_price, order.stop_price].into_iter().flatten() {
+            let price = price.to_f64();
+            let deviation = (price - ctx.last_close).abs() / ctx.last_close;
+            if deviation > self.max_deviation_pct {
+                return Err(format!(
+                    "price {:.2} is {:.2}% from last close {:.2} (max {:.2}%)",
+                    price,
+                    deviation * 100.0,
+                    ctx.last_close,
+                    self.max_deviation_pct * 100.0
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ordered list of checks; the first objection rejects the order
+#[derive(Debug, Default)]
+pub struct PreTradeChain {
+    checks: Vec<Box<dyn PreTradeCheck>>,
+}
+
+impl PreTradeChain {
+    /// Chain of the built-in checks listed in `trading.pre_trade_checks`
+    pub fn from_config(configs: &[PreTradeCheckConfig]) -> Result<Self> {
+        let mut chain = Self::default();
+        for config in configs {
+            let check: Box<dyn PreTradeCheck> = match config {
+                PreTradeCheckConfig::Symbols { allow, deny } => Box::new(SymbolListCheck {
+                    allow: allow.iter().cloned().collect(),
+                    deny: deny.iter().cloned().collect(),
+                }),
+                PreTradeCheckConfig::MaxOrderValue { max } => {
+                    Box::new(MaxOrderValueCheck { max: *max })
+                }
+                PreTradeCheckConfig::RestrictedHours { windows } => {
+                    Box::new(RestrictedHoursCheck::from_config(windows)?)
+                }
+                PreTradeCheckConfig::PriceBand { max_deviation_pct } => Box::new(PriceBandCheck {
+                    max_deviation_pct: *max_deviation_pct,
+                }),
+            };
+            chain.checks.push(check);
+        }
+        Ok(chain)
+    }
+
+    /// Append a custom check (runs after the configured ones)
+    pub fn with_check(mut self, check: Box<dyn PreTradeCheck>) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    /// Run every check in order
+    pub fn check(&self, order: &Order, ctx: &PreTradeContext) -> Result<(), PreTradeRejection> {
+        for check in &self.checks {
+            check
+                .check(order, ctx)
+                .map_err(|reason| PreTradeRejection {
+                    check: check.name().to_string(),
+                    reason,
+                })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oms::types::{OrderType, TimeInForce};
+    use crate::{Side, Symbol};
+    use chrono::TimeZone;
+
+    fn order(symbol: &str, qty: f64, limit: Option<f64>) -> Order {
+        Order::from_f64(
+            Symbol::new(symbol),
+            Side::Buy,
+            if limit.is_some() {
+                OrderType::Limit
+            } else {
+                OrderType::Market
+            },
+            qty,
+            limit,
+            None,
+            TimeInForce::GTC,
+            None,
+        )
+    }
+
+    fn ctx(hour: u32, minute: u32, reduces_position: bool) -> PreTradeContext {
+        PreTradeContext {
+            last_close: 100.0,
+            now: Utc.with_ymd_and_hms(2024, 1, 2, hour, minute, 0).unwrap(),
+            reduces_position,
+        }
+    }
+
+    #[test]
+    fn test_configured_chain_rejects_in_order() {
+        let chain = PreTradeChain::from_config(&[
+            PreTradeCheckConfig::Symbols {
+                allow: vec![],
+                deny: vec!["DOGEINR".into()],
+            },
+            PreTradeCheckConfig::MaxOrderValue { max: 10_000.0 },
+            PreTradeCheckConfig::RestrictedHours {
+                windows: vec![TimeWindow {
+                    start: "23:30".into(),
+                    end: "00:30".into(),
+                }],
+            },
+            PreTradeCheckConfig::PriceBand {
+                max_deviation_pct: 0.05,
+            },
+        ])
+        .unwrap();
+        let noon = ctx(12, 0, false);
+
+        assert!(chain.check(&order("BTCINR", 50.0, None), &noon).is_ok());
+        let denied = chain
+            .check(&order("DOGEINR", 1.0, None), &noon)
+            .unwrap_err();
+        assert_eq!(denied.check, "symbols");
+        let big = chain
+            .check(&order("BTCINR", 101.0, None), &noon)
+            .unwrap_err();
+        assert_eq!(big.check, "max_order_value");
+        // Window wraps past midnight
+        for (h, m) in [(23, 45), (0, 15)] {
+            let err = chain
+                .check(&order("BTCINR", 1.0, None), &ctx(h, m, false))
+                .unwrap_err();
+            assert_eq!(err.check, "restricted_hours");
+        }
+        assert!(chain
+            .check(&order("BTCINR", 1.0, None), &ctx(0, 30, false))
+            .is_ok());
+        let far = chain
+            .check(&order("BTCINR", 1.0, Some(94.0)), &noon)
+            .unwrap_err();
+        assert_eq!(far.check, "price_band");
+
+        // Reducing orders get through everything but the price band
+        let exit = ctx(23, 45, true);
+        assert!(chain.check(&order("DOGEINR", 500.0, None), &exit).is_ok());
+        assert!(chain
+            .check(&order("DOGEINR", 1.0, Some(94.0)), &exit)
+            .is_err());
+    }
+
+    #[derive(Debug)]
+    struct EvenLotsOnly;
+
+    impl PreTradeCheck for EvenLotsOnly {
+        fn name(&self) -> &str {
+            "even_lots"
+        }
+
+        fn check(&self, order: &Order, _ctx: &PreTradeContext) -> Result<(), String> {
+            if order.quantity.to_f64() % 2.0 == 0.0 {
+                Ok(())
+            } else {
+                Err("odd lot".into())
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_checks_and_bad_config() {
+        let chain = PreTradeChain::default().with_check(Box::new(EvenLotsOnly));
+        assert!(chain
+            .check(&order("BTCINR", 2.0, None), &ctx(12, 0, false))
+            .is_ok());
+        let err = chain
+            .check(&order("BTCINR", 3.0, None), &ctx(12, 0, false))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "even_lots: odd lot");
+
+        let bad = PreTradeChain::from_config(&[PreTradeCheckConfig::RestrictedHours {
+            windows: vec![TimeWindow {
+                start: "25:00".into(),
+                end: "01:00".into(),
+            }],
+        }]);
+        assert!(bad.is_err());
+    }
+}
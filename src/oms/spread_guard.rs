@@ -0,0 +1,153 @@
+//! Max-spread guard for market orders
+//!
+//! Market orders in thin books pay the full spread plus whatever depth they
+//! eat. Before a market order is sent, the current bid/ask is checked against
+//! [`SpreadGuardConfig::max_spread_bps`]; wide books either skip the order or
+//! convert it into a short-lived limit order at mid.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::{SpreadAction, SpreadGuardConfig};
+use crate::oms::types::{Order, OrderType, TimeInForce};
+use crate::Money;
+
+/// Spread in basis points of mid, or `None` for an unusable quote
+pub fn spread_bps(bid: f64, ask: f64) -> Option<f64> {
+    if bid <= 0.0 || ask <= 0.0 || ask < bid {
+        return None;
+    }
+    let mid = (bid + ask) / 2.0;
+    Some((ask - bid) / mid * 10_000.0)
+}
+
+/// Outcome of the spread check for one order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpreadDecision {
+    /// Spread within limits (or order isn't a market order)
+    Proceed,
+    /// Spread too wide; drop the order
+    Skip { spread_bps: f64 },
+    /// Spread too wide; send a limit at `price` instead
+    LimitAtMid {
+        spread_bps: f64,
+        price: f64,
+        expires_at: DateTime<Utc>,
+    },
+}
+
+impl SpreadGuardConfig {
+    /// Decide what to do with `order` given the current best bid/ask.
+    ///
+    /// `is_exit` orders are never skipped: closing a position through a wide
+    /// spread beats leaving it open.
+    pub fn evaluate(
+        &self,
+        order: &Order,
+        bid: f64,
+        ask: f64,
+        is_exit: bool,
+        now: DateTime<Utc>,
+    ) -> SpreadDecision {
+        if order.order_type != OrderType::Market {
+            return SpreadDecision::Proceed;
+        }
+        let Some(spread_bps) = spread_bps(bid, ask) else {
+            return SpreadDecision::Proceed;
+        };
+        if spread_bps <= self.max_spread_bps {
+            return SpreadDecision::Proceed;
+        }
+
+        match self.action {
+            SpreadAction::Skip if is_exit => SpreadDecision::Proceed,
+            SpreadAction::Skip => SpreadDecision::Skip { spread_bps },
+            SpreadAction::LimitAtMid => SpreadDecision::LimitAtMid {
+                spread_bps,
+                price: (bid + ask) / 2.0,
+                expires_at: now + Duration::seconds(self.limit_ttl_secs as i64),
+            },
+        }
+    }
+}
+
+/// Turn a market order into a GTD limit order at `price`
+pub fn convert_to_limit(order: &mut Order, price: f64, expires_at: DateTime<Utc>) {
+    order.order_type = OrderType::Limit;
+    order.limit_price = Some(Money::from_f64(price));
+    order.time_in_force = TimeInForce::GTD(expires_at);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Side, Symbol};
+
+    fn market(side: Side) -> Order {
+        Order::from_f64(
+            Symbol::new("BTCINR"),
+            side,
+            OrderType::Market,
+            1.0,
+            None,
+            None,
+            TimeInForce::GTC,
+            None,
+        )
+    }
+
+    fn guard(action: SpreadAction) -> SpreadGuardConfig {
+        SpreadGuardConfig {
+            max_spread_bps: 20.0,
+            action,
+            limit_ttl_secs: 60,
+        }
+    }
+
+    #[test]
+    fn test_spread_bps() {
+        assert!((spread_bps(99.9, 100.1).unwrap() - 20.0).abs() < 1e-9);
+        assert_eq!(spread_bps(0.0, 100.0), None);
+        assert_eq!(spread_bps(101.0, 100.0), None);
+    }
+
+    #[test]
+    fn test_skip_entries_but_not_exits() {
+        let now = Utc::now();
+        let g = guard(SpreadAction::Skip);
+        let order = market(Side::Buy);
+
+        assert_eq!(
+            g.evaluate(&order, 99.95, 100.05, false, now),
+            SpreadDecision::Proceed
+        );
+        assert!(matches!(
+            g.evaluate(&order, 99.0, 101.0, false, now),
+            SpreadDecision::Skip { spread_bps } if (spread_bps - 200.0).abs() < 1e-9
+        ));
+        assert_eq!(
+            g.evaluate(&order, 99.0, 101.0, true, now),
+            SpreadDecision::Proceed
+        );
+    }
+
+    #[test]
+    fn test_limit_at_mid_with_ttl() {
+        let now = Utc::now();
+        let mut order = market(Side::Sell);
+        let decision = guard(SpreadAction::LimitAtMid).evaluate(&order, 99.0, 101.0, true, now);
+
+        let SpreadDecision::LimitAtMid {
+            price, expires_at, ..
+        } = decision
+        else {
+            panic!("expected limit at mid, got {:?}", decision);
+        };
+        assert_eq!(price, 100.0);
+        assert_eq!(expires_at, now + Duration::seconds(60));
+
+        convert_to_limit(&mut order, price, expires_at);
+        assert_eq!(order.order_type, OrderType::Limit);
+        assert_eq!(order.limit_price, Some(Money::from_f64(100.0)));
+        assert_eq!(order.time_in_force, TimeInForce::GTD(expires_at));
+    }
+}
@@ -3,7 +3,7 @@
 //! Defines orders, fills, positions, and related enumerations.
 //! Uses Money type for all monetary values to prevent floating-point drift.
 
-use crate::{Money, Side, Symbol};
+use crate::{add_fee, FeeLedger, FeeType, Money, Side, Symbol};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -53,6 +53,9 @@ pub enum TimeInForce {
 
     /// Fill or kill (fill completely or cancel)
     FOK,
+
+    /// Post-only: rest on the book as a maker order, rejected if it would match on arrival
+    PostOnly,
 }
 
 /// Order state machine
@@ -212,6 +215,23 @@ impl Fill {
             is_maker,
         }
     }
+
+    /// Split this fill's commission into exchange fee and GST.
+    ///
+    /// `gst_rate` is the GST charged on top of the exchange fee (already
+    /// included in `commission`, see [`crate::config::ExchangeConfig::gst_rate`]).
+    pub fn fee_ledger(&self, gst_rate: f64) -> FeeLedger {
+        let mut ledger = FeeLedger::new();
+        let base = self.commission / Money::from_f64(1.0 + gst_rate);
+        let kind = if self.is_maker {
+            FeeType::Maker
+        } else {
+            FeeType::Taker
+        };
+        add_fee(&mut ledger, kind, base);
+        add_fee(&mut ledger, FeeType::Gst, self.commission - base);
+        ledger
+    }
 }
 
 /// Position with FIFO accounting
@@ -227,6 +247,9 @@ pub struct Position {
     pub first_entry_time: DateTime<Utc>,
     pub last_update_time: DateTime<Utc>,
     pub risk_amount: Money,
+    /// Borrow interest accrued on the open quantity (margin shorts)
+    #[serde(default)]
+    pub borrow_cost: Money,
 }
 
 impl Position {
@@ -242,9 +265,15 @@ impl Position {
             first_entry_time: fill.timestamp,
             last_update_time: fill.timestamp,
             risk_amount: Money::ZERO,
+            borrow_cost: Money::ZERO,
         }
     }
 
+    /// Add borrow interest paid on this position
+    pub fn accrue_borrow(&mut self, amount: f64) {
+        self.borrow_cost += Money::from_f64(amount);
+    }
+
     pub fn set_risk_amount(&mut self, risk_amount: f64) {
         self.risk_amount = Money::from_f64(risk_amount);
     }
@@ -333,4 +362,17 @@ mod tests {
         position.update_unrealized_pnl(51000.0);
         assert_eq!(position.unrealized_pnl.to_f64(), 1000.0);
     }
+
+    #[test]
+    fn test_fill_fee_ledger_splits_gst() {
+        // 10 fee + 18% GST = 11.8 commission
+        let fill = Fill::from_f64(1, 10000.0, 1.0, Utc::now(), 11.8, false);
+        let ledger = fill.fee_ledger(0.18);
+        assert!((ledger[&FeeType::Taker].to_f64() - 10.0).abs() < 1e-9);
+        assert!((ledger[&FeeType::Gst].to_f64() - 1.8).abs() < 1e-9);
+        assert!(!ledger.contains_key(&FeeType::Maker));
+
+        let no_gst = fill.fee_ledger(0.0);
+        assert_eq!(no_gst.len(), 1);
+    }
 }
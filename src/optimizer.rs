@@ -2,18 +2,27 @@
 //!
 //! Provides abstractions for parallel grid search optimization across any strategy.
 //! Fully decoupled from strategy implementation - works with both single-TF and MTF.
+//!
+//! Large grids stream their results through a [`ResultStream`]: every result is
+//! appended to a CSV as it arrives and only the best `k` per objective stay in
+//! memory, so memory use doesn't grow with the grid.
 
+use anyhow::{Context, Result};
 use indicatif::ProgressBar;
 use rayon::prelude::*;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::fs::File;
+use std::path::{Path, PathBuf};
 
-use crate::backtest::Backtester;
+use crate::backtest::{Backtester, Truncation};
 use crate::multi_timeframe::MultiTimeframeData;
 use crate::Strategy;
 use crate::{Candle, Config, MultiSymbolMultiTimeframeData, Symbol};
 
 /// Optimization result for a single parameter combination
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OptimizationResult {
     pub params: HashMap<String, f64>,
     pub sharpe_ratio: f64,
@@ -24,6 +33,282 @@ pub struct OptimizationResult {
     pub calmar_ratio: f64,
     pub profit_factor: f64,
     pub expectancy: f64,
+    /// Set when `backtest.early_stop` cut the run short
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<Truncation>,
+}
+
+/// Objectives results can be ranked by (`optimize --sort-by`)
+pub const OBJECTIVES: [&str; 6] = [
+    "sharpe",
+    "calmar",
+    "return",
+    "win_rate",
+    "profit_factor",
+    "expectancy",
+];
+
+/// Canonical objective name; unknown names rank by Sharpe
+fn objective_name(name: &str) -> &'static str {
+    OBJECTIVES
+        .iter()
+        .copied()
+        .find(|o| *o == name)
+        .unwrap_or("sharpe")
+}
+
+impl OptimizationResult {
+    /// Value of a ranking objective (unknown names mean Sharpe)
+    pub fn objective(&self, name: &str) -> f64 {
+        match objective_name(name) {
+            "calmar" => self.calmar_ratio,
+            "return" => self.total_return,
+            "win_rate" => self.win_rate,
+            "profit_factor" => self.profit_factor,
+            "expectancy" => self.expectancy,
+            _ => self.sharpe_ratio,
+        }
+    }
+
+    /// Ranking order: complete runs before early-stopped ones, then the higher
+    /// objective first (NaN last)
+    pub fn rank_cmp(&self, other: &Self, objective: &str) -> Ordering {
+        let key = |r: &Self| {
+            let value = r.objective(objective);
+            if value.is_nan() {
+                f64::NEG_INFINITY
+            } else {
+                value
+            }
+        };
+        self.truncated
+            .is_some()
+            .cmp(&other.truncated.is_some())
+            .then_with(|| key(other).total_cmp(&key(self)))
+    }
+}
+
+/// Heap entry ordered worst-first, so the heap's top is the one to evict
+struct Ranked {
+    objective: &'static str,
+    result: OptimizationResult,
+}
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ranked {}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.result.rank_cmp(&other.result, self.objective)
+    }
+}
+
+/// Best `k` results for one objective, in O(k) memory
+pub struct TopK {
+    objective: &'static str,
+    k: usize,
+    heap: BinaryHeap<Ranked>,
+}
+
+impl TopK {
+    pub fn new(objective: &str, k: usize) -> Self {
+        Self {
+            objective: objective_name(objective),
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    pub fn objective(&self) -> &'static str {
+        self.objective
+    }
+
+    /// Keep `result` if it ranks among the best `k` so far
+    pub fn push(&mut self, result: &OptimizationResult) {
+        if self.k == 0 {
+            return;
+        }
+        if self.heap.len() == self.k {
+            match self.heap.peek() {
+                Some(worst) if result.rank_cmp(&worst.result, self.objective) == Ordering::Less => {
+                    self.heap.pop();
+                }
+                _ => return,
+            }
+        }
+        self.heap.push(Ranked {
+            objective: self.objective,
+            result: result.clone(),
+        });
+    }
+
+    /// Kept results, best first
+    pub fn sorted(&self) -> Vec<OptimizationResult> {
+        let mut results: Vec<OptimizationResult> =
+            self.heap.iter().map(|r| r.result.clone()).collect();
+        results.sort_by(|a, b| a.rank_cmp(b, self.objective));
+        results
+    }
+}
+
+/// Appends results to a CSV as they arrive; columns follow the first result's params
+struct CsvSink {
+    writer: csv::Writer<File>,
+    path: PathBuf,
+    params: Vec<String>,
+}
+
+impl CsvSink {
+    fn write(&mut self, result: &OptimizationResult) -> Result<()> {
+        if self.params.is_empty() && !result.params.is_empty() {
+            self.params = result.params.keys().cloned().collect();
+            self.params.sort();
+            let header = self.params.iter().map(String::as_str).chain([
+                "sharpe_ratio",
+                "total_return",
+                "max_drawdown",
+                "win_rate",
+                "total_trades",
+                "calmar_ratio",
+                "profit_factor",
+                "expectancy",
+                "truncated",
+            ]);
+            self.writer.write_record(header)?;
+        }
+        let params = self.params.iter().map(|k| {
+            result
+                .params
+                .get(k)
+                .map(|v| v.to_string())
+                .unwrap_or_default()
+        });
+        let metrics = [
+            result.sharpe_ratio.to_string(),
+            result.total_return.to_string(),
+            result.max_drawdown.to_string(),
+            result.win_rate.to_string(),
+            result.total_trades.to_string(),
+            result.calmar_ratio.to_string(),
+            result.profit_factor.to_string(),
+            result.expectancy.to_string(),
+            result
+                .truncated
+                .as_ref()
+                .map(|t| format!("{:?}", t.reason))
+                .unwrap_or_default(),
+        ];
+        self.writer.write_record(params.chain(metrics))?;
+        Ok(())
+    }
+}
+
+/// Bounded-memory sink for optimizer results
+///
+/// Keeps a [`TopK`] per objective and, with [`ResultStream::with_csv`], appends
+/// every result to a CSV as it arrives.
+pub struct ResultStream {
+    tops: Vec<TopK>,
+    seen: usize,
+    truncated: usize,
+    csv: Option<CsvSink>,
+}
+
+impl ResultStream {
+    /// Keep the best `k` results for every objective in [`OBJECTIVES`]
+    pub fn new(k: usize) -> Self {
+        Self {
+            tops: OBJECTIVES.iter().map(|o| TopK::new(o, k)).collect(),
+            seen: 0,
+            truncated: 0,
+            csv: None,
+        }
+    }
+
+    /// Also append every result to `path` (created, or truncated if present)
+    pub fn with_csv(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let writer = csv::Writer::from_path(&path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        self.csv = Some(CsvSink {
+            writer,
+            path,
+            params: Vec::new(),
+        });
+        Ok(self)
+    }
+
+    /// Record one result; a failed CSV write still ranks it
+    pub fn push(&mut self, result: OptimizationResult) -> Result<()> {
+        self.seen += 1;
+        if result.truncated.is_some() {
+            self.truncated += 1;
+        }
+        for top in &mut self.tops {
+            top.push(&result);
+        }
+        match &mut self.csv {
+            Some(csv) => csv.write(&result),
+            None => Ok(()),
+        }
+    }
+
+    /// Results seen so far
+    pub fn len(&self) -> usize {
+        self.seen
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen == 0
+    }
+
+    /// Results cut short by `backtest.early_stop`
+    pub fn truncated(&self) -> usize {
+        self.truncated
+    }
+
+    /// Best results for `objective`, best first
+    pub fn top(&self, objective: &str) -> Vec<OptimizationResult> {
+        let objective = objective_name(objective);
+        self.tops
+            .iter()
+            .find(|t| t.objective() == objective)
+            .map(TopK::sorted)
+            .unwrap_or_default()
+    }
+
+    /// Best results for every objective
+    pub fn top_by_objective(&self) -> BTreeMap<&'static str, Vec<OptimizationResult>> {
+        self.tops
+            .iter()
+            .map(|t| (t.objective(), t.sorted()))
+            .collect()
+    }
+
+    /// Flush the CSV and return its path
+    pub fn finish(&mut self) -> Result<Option<PathBuf>> {
+        match &mut self.csv {
+            Some(csv) => {
+                csv.writer.flush()?;
+                Ok(Some(csv.path.clone()))
+            }
+            None => Ok(None),
+        }
+    }
 }
 
 /// Generic optimizer that works with any strategy
@@ -57,7 +342,8 @@ impl Optimizer {
             .par_iter()
             .map(|config| {
                 let strategy = strategy_factory(config);
-                let mut backtester = Backtester::new(config.clone(), strategy);
+                let mut backtester = Backtester::new(config.clone(), strategy)
+                    .with_early_stop(config.backtest.early_stop.clone());
                 let result = backtester.run(data);
 
                 OptimizationResult {
@@ -70,6 +356,7 @@ impl Optimizer {
                     calmar_ratio: result.metrics.calmar_ratio,
                     profit_factor: result.metrics.profit_factor,
                     expectancy: result.metrics.expectancy,
+                    truncated: result.truncated,
                 }
             })
             .collect()
@@ -95,7 +382,8 @@ impl Optimizer {
             .par_iter()
             .map(|config| {
                 let strategy = strategy_factory(config);
-                let mut backtester = Backtester::new(config.clone(), strategy);
+                let mut backtester = Backtester::new(config.clone(), strategy)
+                    .with_early_stop(config.backtest.early_stop.clone());
                 let result = backtester.run(data);
                 progress_bar.inc(1);
 
@@ -109,6 +397,7 @@ impl Optimizer {
                     calmar_ratio: result.metrics.calmar_ratio,
                     profit_factor: result.metrics.profit_factor,
                     expectancy: result.metrics.expectancy,
+                    truncated: result.truncated,
                 }
             })
             .collect()
@@ -133,7 +422,8 @@ impl Optimizer {
             .iter()
             .map(|config| {
                 let strategy = strategy_factory(config);
-                let mut backtester = Backtester::new(config.clone(), strategy);
+                let mut backtester = Backtester::new(config.clone(), strategy)
+                    .with_early_stop(config.backtest.early_stop.clone());
                 let result = backtester.run(data);
 
                 OptimizationResult {
@@ -146,24 +436,17 @@ impl Optimizer {
                     calmar_ratio: result.metrics.calmar_ratio,
                     profit_factor: result.metrics.profit_factor,
                     expectancy: result.metrics.expectancy,
+                    truncated: result.truncated,
                 }
             })
             .collect()
     }
 
     /// Sort optimization results by specified metric
+    ///
+    /// Early-stopped runs always rank below complete ones.
     pub fn sort_results(results: &mut [OptimizationResult], sort_by: &str) {
-        results.sort_by(|a, b| {
-            let (va, vb) = match sort_by {
-                "calmar" => (a.calmar_ratio, b.calmar_ratio),
-                "return" => (a.total_return, b.total_return),
-                "win_rate" => (a.win_rate, b.win_rate),
-                "profit_factor" => (a.profit_factor, b.profit_factor),
-                "expectancy" => (a.expectancy, b.expectancy),
-                _ => (a.sharpe_ratio, b.sharpe_ratio),
-            };
-            vb.partial_cmp(&va).unwrap_or(std::cmp::Ordering::Equal)
-        });
+        results.sort_by(|a, b| a.rank_cmp(b, sort_by));
     }
 }
 
@@ -180,3 +463,67 @@ pub fn single_tf_to_mtf(
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::StopReason;
+    use chrono::Utc;
+
+    fn result(i: usize) -> OptimizationResult {
+        // Sharpe rises with i, return falls with it
+        OptimizationResult {
+            params: HashMap::from([("period".to_string(), i as f64)]),
+            sharpe_ratio: i as f64 / 10.0,
+            total_return: 100.0 - i as f64,
+            max_drawdown: 5.0,
+            win_rate: 50.0,
+            total_trades: 10,
+            calmar_ratio: 1.0,
+            profit_factor: 1.5,
+            expectancy: 2.0,
+            truncated: None,
+        }
+    }
+
+    #[test]
+    fn test_stream_keeps_top_k_and_writes_every_row() {
+        let path = std::env::temp_dir().join(format!("optimize_stream_{}.csv", std::process::id()));
+        let mut stream = ResultStream::new(3).with_csv(&path).unwrap();
+
+        let mut all: Vec<OptimizationResult> = (0..500).map(result).collect();
+        // The best Sharpe was stopped early, so it ranks below every complete run
+        all[499].truncated = Some(Truncation {
+            reason: StopReason::Drawdown,
+            at: Utc::now(),
+            bars_run: 10,
+            total_bars: 100,
+        });
+        all[7].sharpe_ratio = f64::NAN;
+        for r in all.iter().rev() {
+            stream.push(r.clone()).unwrap();
+        }
+        let csv = stream.finish().unwrap().unwrap();
+
+        let period = |rs: Vec<OptimizationResult>| -> Vec<f64> {
+            rs.iter().map(|r| r.params["period"]).collect()
+        };
+        assert_eq!(stream.len(), 500);
+        assert_eq!(stream.truncated(), 1);
+        assert_eq!(period(stream.top("sharpe")), [498.0, 497.0, 496.0]);
+        assert_eq!(period(stream.top("return")), [0.0, 1.0, 2.0]);
+        // Unknown objectives rank by Sharpe, as `--sort-by` always has
+        assert_eq!(period(stream.top("nonsense")), period(stream.top("sharpe")));
+        assert_eq!(stream.top_by_objective().len(), OBJECTIVES.len());
+
+        // Same order as sorting everything
+        Optimizer::sort_results(&mut all, "sharpe");
+        assert_eq!(period(all[..3].to_vec()), [498.0, 497.0, 496.0]);
+
+        let text = std::fs::read_to_string(&csv).unwrap();
+        let _ = std::fs::remove_file(&csv);
+        assert_eq!(text.lines().count(), 501);
+        assert!(text.starts_with("period,sharpe_ratio,"));
+        assert!(text.lines().nth(1).unwrap().ends_with("Drawdown"));
+    }
+}
@@ -0,0 +1,226 @@
+//! Perpetual futures positioning data (basis and funding)
+//!
+//! Crowded perp positioning shows up as a rich basis (mark above index) and
+//! high funding. The series here back the `positioning` strategy combinator
+//! (see [`crate::strategies::combinators`]), which skips entries in the
+//! crowded direction.
+//!
+//! One CSV per traded symbol under the positioning directory
+//! (`data/positioning/BTCINR.csv` by default), written by
+//! `data fetch-positioning` from the Binance USDⓈ-M futures feeds:
+//!
+//! ```text
+//! datetime,basis,funding_rate
+//! 2024-01-01 00:00:00,0.00042,0.0001
+//! ```
+//!
+//! `basis` is the premium index (mark / index - 1) and `funding_rate` the rate
+//! charged per funding interval, both as fractions.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::path::{Path, PathBuf};
+
+/// Default directory for positioning CSVs
+pub const DEFAULT_POSITIONING_DIR: &str = "data/positioning";
+
+/// Basis and funding at one point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositioningSample {
+    pub datetime: DateTime<Utc>,
+    /// Perp premium over the index, as a fraction
+    pub basis: f64,
+    /// Funding rate per interval, as a fraction
+    pub funding_rate: f64,
+}
+
+/// Where a sample sits in its recent history, as percentiles (0-100)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PositioningReading {
+    pub sample: PositioningSample,
+    pub basis_pct: f64,
+    pub funding_pct: f64,
+}
+
+/// Time-ordered samples for one symbol
+#[derive(Debug, Clone, Default)]
+pub struct PositioningSeries {
+    samples: Vec<PositioningSample>,
+}
+
+impl PositioningSeries {
+    /// Samples are sorted and de-duplicated by time
+    pub fn new(mut samples: Vec<PositioningSample>) -> Self {
+        samples.sort_by_key(|s| s.datetime);
+        samples.dedup_by_key(|s| s.datetime);
+        Self { samples }
+    }
+
+    pub fn samples(&self) -> &[PositioningSample] {
+        &self.samples
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Index of the last sample at or before `at`
+    fn index_at(&self, at: DateTime<Utc>) -> Option<usize> {
+        self.samples
+            .partition_point(|s| s.datetime <= at)
+            .checked_sub(1)
+    }
+
+    /// Latest sample at or before `at`, ranked against the `lookback` samples ending there.
+    ///
+    /// `None` until `lookback` samples are available, or when the latest one
+    /// is older than `max_age` (a stale feed says nothing about today).
+    pub fn reading_at(
+        &self,
+        at: DateTime<Utc>,
+        lookback: usize,
+        max_age: chrono::Duration,
+    ) -> Option<PositioningReading> {
+        let idx = self.index_at(at)?;
+        let sample = self.samples[idx];
+        if lookback == 0 || idx + 1 < lookback || at - sample.datetime > max_age {
+            return None;
+        }
+        let window = &self.samples[idx + 1 - lookback..=idx];
+        Some(PositioningReading {
+            sample,
+            basis_pct: percentile_rank(window.iter().map(|s| s.basis), sample.basis),
+            funding_pct: percentile_rank(
+                window.iter().map(|s| s.funding_rate),
+                sample.funding_rate,
+            ),
+        })
+    }
+
+    /// Parse the CSV layout described in the module docs
+    pub fn from_csv_str(content: &str) -> Result<Self> {
+        let mut samples = Vec::new();
+        for (i, line) in content.lines().enumerate().skip(1) {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let parse = || -> Option<PositioningSample> {
+                let mut fields = line.split(',');
+                let datetime =
+                    NaiveDateTime::parse_from_str(fields.next()?.trim(), "%Y-%m-%d %H:%M:%S")
+                        .ok()?
+                        .and_utc();
+                Some(PositioningSample {
+                    datetime,
+                    basis: fields.next()?.trim().parse().ok()?,
+                    funding_rate: fields.next()?.trim().parse().ok()?,
+                })
+            };
+            samples.push(parse().with_context(|| format!("Invalid positioning row {}", i + 1))?);
+        }
+        Ok(Self::new(samples))
+    }
+
+    pub fn to_csv_string(&self) -> String {
+        let mut out = String::from("datetime,basis,funding_rate\n");
+        for s in &self.samples {
+            out.push_str(&format!(
+                "{},{},{}\n",
+                s.datetime.format("%Y-%m-%d %H:%M:%S"),
+                s.basis,
+                s.funding_rate
+            ));
+        }
+        out
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_csv_str(&content)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_csv_string())
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// CSV path for `symbol` under `dir`
+pub fn positioning_path(dir: impl AsRef<Path>, symbol: &str) -> PathBuf {
+    dir.as_ref().join(format!("{}.csv", symbol))
+}
+
+/// Percent of `values` at or below `x`
+fn percentile_rank(values: impl Iterator<Item = f64>, x: f64) -> f64 {
+    let (mut below, mut total) = (0usize, 0usize);
+    for v in values {
+        total += 1;
+        if v <= x {
+            below += 1;
+        }
+    }
+    if total == 0 {
+        return 50.0;
+    }
+    below as f64 / total as f64 * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn series(values: &[(f64, f64)]) -> PositioningSeries {
+        let t0 = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        PositioningSeries::new(
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, &(basis, funding_rate))| PositioningSample {
+                    datetime: t0 + Duration::hours(8 * i as i64),
+                    basis,
+                    funding_rate,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_reading_ranks_latest_sample() {
+        let s = series(&[
+            (0.001, 0.0001),
+            (0.002, 0.0002),
+            (0.003, 0.0003),
+            (0.004, 0.0004),
+        ]);
+        let last = s.samples()[3].datetime;
+        let r = s
+            .reading_at(last + Duration::hours(1), 4, Duration::days(1))
+            .unwrap();
+        assert_eq!(r.basis_pct, 100.0);
+        assert_eq!(r.funding_pct, 100.0);
+
+        let r = s.reading_at(last, 2, Duration::days(1)).unwrap();
+        assert_eq!(r.basis_pct, 100.0);
+        // Not enough history, or too stale
+        assert!(s.reading_at(last, 5, Duration::days(1)).is_none());
+        assert!(s
+            .reading_at(last + Duration::days(3), 4, Duration::days(1))
+            .is_none());
+    }
+
+    #[test]
+    fn test_csv_roundtrip() {
+        let s = series(&[(0.001, -0.0001), (-0.002, 0.0003)]);
+        let parsed = PositioningSeries::from_csv_str(&s.to_csv_string()).unwrap();
+        assert_eq!(parsed.samples(), s.samples());
+        assert!(PositioningSeries::from_csv_str("datetime,basis,funding_rate\nbad").is_err());
+    }
+}
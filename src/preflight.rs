@@ -0,0 +1,373 @@
+//! Preflight trade plan
+//!
+//! Before its first cycle the live trader runs the strategy once over the
+//! bootstrapped candles without sending anything and prints what it would do:
+//! the signals, the sized orders with their stops and targets, and the levels
+//! protecting positions it already holds. Trading starts only once the plan is
+//! acknowledged, either up front with `live --confirm` or over HTTP with
+//! [`wait_for_ack`], so a new config can't surprise a funded account.
+//!
+//! The HTTP acknowledgment (`preflight.listen`) answers:
+//!
+//! - `GET /plan`: the plan as JSON
+//! - `POST /confirm?plan=<id>`: start trading; `id` must match the plan shown
+//! - `POST /abort`: stop without trading
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::oms::OrderType;
+use crate::Side;
+
+/// An order the first cycle would send
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedOrder {
+    pub symbol: String,
+    pub side: Side,
+    pub order_type: OrderType,
+    pub quantity: f64,
+    /// Limit price, else the last close
+    pub price: f64,
+    /// Protective levels for entries
+    pub stop_loss: Option<f64>,
+    pub take_profit: Option<f64>,
+    /// Order closes or shrinks an open position
+    pub reduces_position: bool,
+    /// Why the order would be held back (`None` = it would be placed)
+    pub blocked: Option<String>,
+    /// Strategy commentary
+    pub note: Option<String>,
+}
+
+impl PlannedOrder {
+    pub fn notional(&self) -> f64 {
+        self.quantity * self.price
+    }
+}
+
+/// A position carried into the session and the levels that manage it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedPosition {
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: f64,
+    pub entry_price: f64,
+    /// Trailing stop when set, else the stop from entry
+    pub stop_loss: f64,
+    pub take_profit: f64,
+}
+
+/// Everything the first cycle would do
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreflightPlan {
+    pub strategy: String,
+    pub paper: bool,
+    pub generated_at: DateTime<Utc>,
+    pub equity: f64,
+    pub cash: f64,
+    pub orders: Vec<PlannedOrder>,
+    pub positions: Vec<PlannedPosition>,
+}
+
+impl PreflightPlan {
+    /// Short fingerprint of the plan, echoed back by `POST /confirm`
+    pub fn id(&self) -> String {
+        let json = serde_json::to_string(self).unwrap_or_default();
+        hex::encode(Sha256::digest(json.as_bytes()))[..12].to_string()
+    }
+
+    /// Orders that would actually be placed
+    pub fn placed(&self) -> impl Iterator<Item = &PlannedOrder> {
+        self.orders.iter().filter(|o| o.blocked.is_none())
+    }
+
+    /// Notional of the orders that would open or add to positions
+    pub fn new_exposure(&self) -> f64 {
+        self.placed()
+            .filter(|o| !o.reduces_position)
+            .map(PlannedOrder::notional)
+            .sum()
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Human-readable plan for the console
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "Preflight plan {} - {} ({}) at {}\nEquity {:.2}, cash {:.2}\n",
+            self.id(),
+            self.strategy,
+            if self.paper { "paper" } else { "LIVE" },
+            self.generated_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.equity,
+            self.cash
+        );
+
+        if self.positions.is_empty() {
+            out.push_str("\nOpen positions: none\n");
+        } else {
+            out.push_str(&format!(
+                "\nOpen positions\n{:<12} {:<5} {:>14} {:>12} {:>12} {:>12}\n",
+                "Symbol", "Side", "Quantity", "Entry", "Stop", "Target"
+            ));
+            for p in &self.positions {
+                out.push_str(&format!(
+                    "{:<12} {:<5} {:>14.6} {:>12.4} {:>12.4} {:>12.4}\n",
+                    p.symbol,
+                    side_label(p.side),
+                    p.quantity,
+                    p.entry_price,
+                    p.stop_loss,
+                    p.take_profit
+                ));
+            }
+        }
+
+        if self.orders.is_empty() {
+            out.push_str("\nOrders: none (no signals on current data)\n");
+        } else {
+            out.push_str(&format!(
+                "\nOrders\n{:<12} {:<5} {:<10} {:>14} {:>12} {:>14} {:>12} {:>12}  Status\n",
+                "Symbol", "Side", "Type", "Quantity", "Price", "Notional", "Stop", "Target"
+            ));
+            let level = |v: Option<f64>| v.map_or_else(|| "-".to_string(), |v| format!("{:.4}", v));
+            for o in &self.orders {
+                out.push_str(&format!(
+                    "{:<12} {:<5} {:<10} {:>14.6} {:>12.4} {:>14.2} {:>12} {:>12}  {}\n",
+                    o.symbol,
+                    side_label(o.side),
+                    format!("{:?}", o.order_type),
+                    o.quantity,
+                    o.price,
+                    o.notional(),
+                    level(o.stop_loss),
+                    level(o.take_profit),
+                    match &o.blocked {
+                        Some(reason) => format!("held: {}", reason),
+                        None if o.reduces_position => "place (exit)".to_string(),
+                        None => "place".to_string(),
+                    }
+                ));
+                if let Some(note) = &o.note {
+                    out.push_str(&format!("  └─ {}\n", note));
+                }
+            }
+        }
+
+        out.push_str(&format!(
+            "\n{} order(s) to place, {:.2} of new exposure\n",
+            self.placed().count(),
+            self.new_exposure()
+        ));
+        out
+    }
+}
+
+fn side_label(side: Side) -> &'static str {
+    match side {
+        Side::Buy => "BUY",
+        Side::Sell => "SELL",
+    }
+}
+
+/// Operator's answer to a plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ack {
+    Confirmed,
+    Aborted,
+}
+
+/// Response to one HTTP request: status line, body, and the answer if it was one
+fn route(request_line: &str, plan: &PreflightPlan) -> (&'static str, String, Option<Ack>) {
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().unwrap_or_default();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let plan_param = query
+        .split('&')
+        .find_map(|kv| kv.strip_prefix("plan="))
+        .unwrap_or_default();
+
+    match (method, path) {
+        ("GET", "/plan") => ("200 OK", plan.to_json().unwrap_or_default(), None),
+        ("POST", "/confirm") if plan_param == plan.id() => {
+            ("200 OK", "confirmed\n".to_string(), Some(Ack::Confirmed))
+        }
+        ("POST", "/confirm") => (
+            "409 Conflict",
+            format!("plan id must be {}\n", plan.id()),
+            None,
+        ),
+        ("POST", "/abort") => ("200 OK", "aborted\n".to_string(), Some(Ack::Aborted)),
+        _ => ("404 Not Found", "not found\n".to_string(), None),
+    }
+}
+
+/// Serve `plan` on `listener` until it is confirmed or aborted
+///
+/// Errors when nobody answers within `timeout`.
+pub async fn wait_for_ack(
+    listener: TcpListener,
+    plan: &PreflightPlan,
+    timeout: Duration,
+) -> Result<Ack> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let Ok(accepted) = tokio::time::timeout_at(deadline, listener.accept()).await else {
+            bail!(
+                "Preflight plan {} was not acknowledged within {}s",
+                plan.id(),
+                timeout.as_secs()
+            );
+        };
+        let (mut socket, peer) = accepted?;
+        let mut buf = vec![0u8; 8192];
+        let n = match socket.read(&mut buf).await {
+            Ok(n) => n,
+            Err(e) => {
+                tracing::warn!("Preflight request from {} failed: {}", peer, e);
+                continue;
+            }
+        };
+        let request = String::from_utf8_lossy(&buf[..n]);
+        let (status, body, ack) = route(request.lines().next().unwrap_or_default(), plan);
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        if let Err(e) = socket.write_all(response.as_bytes()).await {
+            tracing::warn!("Preflight response to {} failed: {}", peer, e);
+        }
+        if let Some(ack) = ack {
+            tracing::info!("Preflight plan {} {:?} by {}", plan.id(), ack, peer);
+            return Ok(ack);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan() -> PreflightPlan {
+        PreflightPlan {
+            strategy: "volatility_regime".into(),
+            paper: false,
+            generated_at: "2024-03-01T00:00:00Z".parse().unwrap(),
+            equity: 100_000.0,
+            cash: 80_000.0,
+            orders: vec![
+                PlannedOrder {
+                    symbol: "BTCINR".into(),
+                    side: Side::Buy,
+                    order_type: OrderType::Market,
+                    quantity: 0.25,
+                    price: 40_000.0,
+                    stop_loss: Some(38_000.0),
+                    take_profit: Some(45_000.0),
+                    reduces_position: false,
+                    blocked: None,
+                    note: Some("breakout".into()),
+                },
+                PlannedOrder {
+                    symbol: "DOGEINR".into(),
+                    side: Side::Buy,
+                    order_type: OrderType::Market,
+                    quantity: 1_000.0,
+                    price: 10.0,
+                    stop_loss: Some(9.0),
+                    take_profit: Some(12.0),
+                    reduces_position: false,
+                    blocked: Some("symbols: DOGEINR is on the deny list".into()),
+                    note: None,
+                },
+            ],
+            positions: vec![PlannedPosition {
+                symbol: "ETHINR".into(),
+                side: Side::Buy,
+                quantity: 2.0,
+                entry_price: 2_000.0,
+                stop_loss: 1_900.0,
+                take_profit: 2_300.0,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_plan_summary_and_routes() {
+        let plan = plan();
+        assert_eq!(plan.placed().count(), 1);
+        assert_eq!(plan.new_exposure(), 10_000.0);
+        let text = plan.render();
+        assert!(text.contains(&plan.id()));
+        assert!(text.contains("held: symbols"));
+        assert!(text.contains("1 order(s) to place, 10000.00 of new exposure"));
+
+        // The id follows the content, so a stale confirmation can't start a changed plan
+        let mut changed = plan.clone();
+        changed.orders[0].quantity = 0.5;
+        assert_ne!(changed.id(), plan.id());
+
+        let (status, body, ack) = route("GET /plan HTTP/1.1", &plan);
+        assert_eq!((status, ack), ("200 OK", None));
+        let served: PreflightPlan = serde_json::from_str(&body).unwrap();
+        assert_eq!(served, plan);
+
+        let stale = format!("POST /confirm?plan={} HTTP/1.1", changed.id());
+        assert_eq!(route(&stale, &plan).0, "409 Conflict");
+        let confirm = format!("POST /confirm?plan={} HTTP/1.1", plan.id());
+        assert_eq!(route(&confirm, &plan).2, Some(Ack::Confirmed));
+        assert_eq!(route("POST /abort HTTP/1.1", &plan).2, Some(Ack::Aborted));
+        assert_eq!(route("GET /confirm HTTP/1.1", &plan).0, "404 Not Found");
+    }
+
+    #[tokio::test]
+    async fn test_ack_over_http() {
+        let plan = plan();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let operator = {
+            let id = plan.id();
+            tokio::spawn(async move {
+                let client = reqwest::Client::new();
+                let shown = client
+                    .get(format!("{}/plan", url))
+                    .send()
+                    .await
+                    .unwrap()
+                    .text()
+                    .await
+                    .unwrap();
+                assert!(shown.contains("BTCINR"));
+                client
+                    .post(format!("{}/confirm?plan={}", url, id))
+                    .send()
+                    .await
+                    .unwrap()
+                    .status()
+            })
+        };
+
+        let ack = wait_for_ack(listener, &plan, Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(ack, Ack::Confirmed);
+        assert!(operator.await.unwrap().is_success());
+
+        // Nobody answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        assert!(wait_for_ack(listener, &plan, Duration::from_millis(50))
+            .await
+            .is_err());
+    }
+}
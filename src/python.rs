@@ -0,0 +1,294 @@
+//! Python bindings via PyO3
+//!
+//! Exposes the backtester and indicators to Python research notebooks.
+//!
+//! Build and install into the active virtualenv with:
+//! ```text
+//! maturin develop --release
+//! ```
+//!
+//! ```python
+//! import crypto_strategies as cs
+//! result = cs.run_backtest(open("configs/volatility_regime_config.json").read(), df, symbol="BTCINR")
+//! print(result["metrics"]["sharpe_ratio"], len(result["trades"]))
+//! ema = cs.ema(df["close"].to_numpy(), 21)
+//! ```
+//!
+//! `candles_df` is a pandas DataFrame with `open`, `high`, `low`, `close`, `volume`
+//! columns and either a `datetime` column or a DatetimeIndex. A dict of
+//! `{symbol: DataFrame}` runs a multi-symbol backtest.
+
+use numpy::{IntoPyArray, PyArray1, PyReadonlyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::backtest::Backtester;
+use crate::indicators::{self, to_nan};
+use crate::multi_timeframe::{MultiSymbolMultiTimeframeData, MultiTimeframeData};
+use crate::{strategies, Candle, Config, Symbol, Trade};
+
+/// Names of all registered strategies
+#[pyfunction]
+fn available_strategies() -> Vec<&'static str> {
+    strategies::available_strategies()
+}
+
+// #[pyfunction] expands PyResult returns through `.into()`, which clippy flags in
+// the generated wrapper; an allow on the function itself doesn't reach it
+#[allow(clippy::useless_conversion)]
+mod fallible {
+    use super::*;
+
+    /// Run a backtest and return `{"metrics": dict, "trades": list[dict], "equity_curve": dict}`.
+    ///
+    /// `equity_curve` holds two NumPy arrays: `timestamp` (epoch milliseconds) and `equity`.
+    #[pyfunction]
+    #[pyo3(signature = (config_json, candles_df, symbol=None))]
+    pub(super) fn run_backtest<'py>(
+        py: Python<'py>,
+        config_json: &str,
+        candles_df: &Bound<'py, PyAny>,
+        symbol: Option<&str>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let config: Config = serde_json::from_str(config_json)
+            .map_err(|e| PyValueError::new_err(format!("Failed to parse config JSON: {}", e)))?;
+        let timeframe = config.timeframe();
+
+        let mut frames = Vec::new();
+        if let Ok(dict) = candles_df.downcast::<PyDict>() {
+            for (key, df) in dict.iter() {
+                frames.push((key.extract::<String>()?, candles_from_df(py, &df)?));
+            }
+        } else {
+            let symbol = symbol
+                .map(str::to_string)
+                .or_else(|| config.trading.symbols.first().cloned())
+                .ok_or_else(|| {
+                    PyValueError::new_err("symbol is required for a single DataFrame")
+                })?;
+            frames.push((symbol, candles_from_df(py, candles_df)?));
+        }
+
+        let data: MultiSymbolMultiTimeframeData = frames
+            .into_iter()
+            .map(|(symbol, candles)| {
+                let mut mtf = MultiTimeframeData::new(timeframe.as_str());
+                mtf.add_timeframe(timeframe.as_str(), candles);
+                (Symbol::new(&symbol), mtf)
+            })
+            .collect();
+
+        let strategy = strategies::create_strategy(&config)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let mut backtester = Backtester::try_new(config, strategy)
+            .map_err(|e| PyValueError::new_err(format!("{:#}", e)))?;
+        // Release the GIL while the engine runs
+        let result = py.allow_threads(|| backtester.run(&data));
+
+        let metrics_json = serde_json::to_string(&result.metrics)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let metrics = py
+            .import_bound("json")?
+            .call_method1("loads", (metrics_json,))?;
+
+        let trades = PyList::empty_bound(py);
+        for trade in &result.trades {
+            trades.append(trade_to_dict(py, trade)?)?;
+        }
+
+        let (timestamps, equity): (Vec<i64>, Vec<f64>) = result
+            .equity_curve
+            .iter()
+            .map(|(dt, value)| (dt.timestamp_millis(), *value))
+            .unzip();
+        let equity_curve = PyDict::new_bound(py);
+        equity_curve.set_item("timestamp", timestamps.into_pyarray_bound(py))?;
+        equity_curve.set_item("equity", equity.into_pyarray_bound(py))?;
+
+        let out = PyDict::new_bound(py);
+        out.set_item("metrics", metrics)?;
+        out.set_item("trades", trades)?;
+        out.set_item("equity_curve", equity_curve)?;
+        Ok(out)
+    }
+
+    #[pyfunction]
+    pub(super) fn sma<'py>(
+        py: Python<'py>,
+        values: PyReadonlyArray1<'py, f64>,
+        period: usize,
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        Ok(to_nan(indicators::sma(values.as_slice()?, period)).into_pyarray_bound(py))
+    }
+
+    #[pyfunction]
+    pub(super) fn ema<'py>(
+        py: Python<'py>,
+        values: PyReadonlyArray1<'py, f64>,
+        period: usize,
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        Ok(to_nan(indicators::ema(values.as_slice()?, period)).into_pyarray_bound(py))
+    }
+
+    #[pyfunction]
+    pub(super) fn rsi<'py>(
+        py: Python<'py>,
+        values: PyReadonlyArray1<'py, f64>,
+        period: usize,
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        Ok(to_nan(indicators::rsi(values.as_slice()?, period)).into_pyarray_bound(py))
+    }
+
+    #[pyfunction]
+    pub(super) fn atr<'py>(
+        py: Python<'py>,
+        high: PyReadonlyArray1<'py, f64>,
+        low: PyReadonlyArray1<'py, f64>,
+        close: PyReadonlyArray1<'py, f64>,
+        period: usize,
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        let out = indicators::atr(high.as_slice()?, low.as_slice()?, close.as_slice()?, period);
+        Ok(to_nan(out).into_pyarray_bound(py))
+    }
+
+    #[pyfunction]
+    pub(super) fn adx<'py>(
+        py: Python<'py>,
+        high: PyReadonlyArray1<'py, f64>,
+        low: PyReadonlyArray1<'py, f64>,
+        close: PyReadonlyArray1<'py, f64>,
+        period: usize,
+    ) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        let out = indicators::adx(high.as_slice()?, low.as_slice()?, close.as_slice()?, period);
+        Ok(to_nan(out).into_pyarray_bound(py))
+    }
+}
+use fallible::{adx, atr, ema, rsi, run_backtest, sma};
+
+#[pymodule]
+fn crypto_strategies(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(run_backtest, m)?)?;
+    m.add_function(wrap_pyfunction!(available_strategies, m)?)?;
+    m.add_function(wrap_pyfunction!(sma, m)?)?;
+    m.add_function(wrap_pyfunction!(ema, m)?)?;
+    m.add_function(wrap_pyfunction!(rsi, m)?)?;
+    m.add_function(wrap_pyfunction!(atr, m)?)?;
+    m.add_function(wrap_pyfunction!(adx, m)?)?;
+    Ok(())
+}
+
+/// Convert a pandas DataFrame into candles
+fn candles_from_df(py: Python<'_>, df: &Bound<'_, PyAny>) -> PyResult<Vec<Candle>> {
+    let pandas = py.import_bound("pandas")?;
+
+    let has_datetime_col = df.getattr("columns")?.contains("datetime")?;
+    let source = if has_datetime_col {
+        df.get_item("datetime")?
+    } else {
+        df.getattr("index")?
+    };
+    let kwargs = PyDict::new_bound(py);
+    kwargs.set_item("utc", true)?;
+    let ts = pandas.call_method("to_datetime", (source,), Some(&kwargs))?;
+
+    // Epoch milliseconds, independent of the column's datetime resolution
+    let epoch_kwargs = PyDict::new_bound(py);
+    epoch_kwargs.set_item("tz", "UTC")?;
+    let epoch = pandas.call_method("Timestamp", (0,), Some(&epoch_kwargs))?;
+    let one_ms = pandas.call_method1("Timedelta", (1, "ms"))?;
+    let millis = ts
+        .call_method1("__sub__", (epoch,))?
+        .call_method1("__floordiv__", (one_ms,))?
+        .call_method1("to_numpy", ("int64",))?;
+    let millis: PyReadonlyArray1<i64> = millis.extract()?;
+
+    let column = |name: &str| -> PyResult<Vec<f64>> {
+        let arr = df.get_item(name)?.call_method1("to_numpy", ("float64",))?;
+        Ok(arr.extract::<PyReadonlyArray1<f64>>()?.as_array().to_vec())
+    };
+    let (open, high, low, close, volume) = (
+        column("open")?,
+        column("high")?,
+        column("low")?,
+        column("close")?,
+        column("volume")?,
+    );
+
+    millis
+        .as_array()
+        .iter()
+        .enumerate()
+        .map(|(i, &ms)| {
+            let datetime = chrono::DateTime::from_timestamp_millis(ms)
+                .ok_or_else(|| PyValueError::new_err(format!("Invalid timestamp at row {}", i)))?;
+            Ok(Candle::new_unchecked(
+                datetime, open[i], high[i], low[i], close[i], volume[i],
+            ))
+        })
+        .collect()
+}
+
+fn trade_to_dict<'py>(py: Python<'py>, trade: &Trade) -> PyResult<Bound<'py, PyDict>> {
+    let d = PyDict::new_bound(py);
+    d.set_item("symbol", trade.symbol.as_str())?;
+    d.set_item("side", format!("{:?}", trade.side).to_lowercase())?;
+    d.set_item("entry_price", trade.entry_price.to_f64())?;
+    d.set_item("exit_price", trade.exit_price.to_f64())?;
+    d.set_item("quantity", trade.quantity.to_f64())?;
+    d.set_item("entry_time", trade.entry_time.to_rfc3339())?;
+    d.set_item("exit_time", trade.exit_time.to_rfc3339())?;
+    d.set_item("pnl", trade.pnl.to_f64())?;
+    d.set_item("commission", trade.commission.to_f64())?;
+    d.set_item("net_pnl", trade.net_pnl.to_f64())?;
+    let fees = PyDict::new_bound(py);
+    for (kind, amount) in &trade.fees {
+        fees.set_item(kind.as_str(), amount.to_f64())?;
+    }
+    d.set_item("fees", fees)?;
+    d.set_item("return_pct", trade.return_pct())?;
+    d.set_item("rationale", trade.rationale.as_deref())?;
+    Ok(d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_exposes_the_bindings() {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = pyo3::wrap_pymodule!(crypto_strategies)(py);
+            let module = module.bind(py);
+            for name in ["run_backtest", "sma", "ema", "rsi", "atr", "adx"] {
+                assert!(module.hasattr(name).unwrap(), "missing {}", name);
+            }
+
+            let names: Vec<String> = module
+                .call_method0("available_strategies")
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(names.iter().any(|n| n == "momentum_scalper"));
+
+            // Bad config is a ValueError before any DataFrame is touched
+            let err = module
+                .call_method1("run_backtest", ("not json", py.None()))
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+
+            // So is a config the engine rejects, rather than a panic
+            let mut config: serde_json::Value =
+                serde_json::from_str(include_str!("../configs/sample_config.json")).unwrap();
+            config["trading"]["pre_trade_checks"] = serde_json::json!([
+                { "type": "restricted_hours", "windows": [{ "start": "25:00", "end": "01:00" }] }
+            ]);
+            let candles = PyDict::new_bound(py);
+            let err = module
+                .call_method1("run_backtest", (config.to_string(), candles))
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+        });
+    }
+}
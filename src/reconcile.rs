@@ -0,0 +1,488 @@
+//! Trade reconciliation against the exchange's trade history
+//!
+//! The live loop logs every fill to the state database (see
+//! [`FillRecord`]). Reconciliation pulls the exchange's trade history for the
+//! same window, groups both sides by order ID and compares filled quantity:
+//!
+//! - **matched**: both sides agree
+//! - **quantity_mismatch**: same order, different filled quantity
+//! - **missing_locally**: the exchange filled an order the bot never recorded
+//! - **missing_on_exchange**: the bot recorded a fill the exchange doesn't know
+//!
+//! The report is exported as a CSV plus a JSON manifest carrying the CSV's
+//! SHA-256, totals, and (only for a clean report) who signed it off.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::coindcx::{self, CoinDCXClient};
+use crate::state_manager::{FillRecord, SqliteStateManager};
+
+/// Quantities closer than this (relative) count as equal
+const QTY_TOLERANCE: f64 = 1e-8;
+
+/// Timestamp format used for the `fills` table (sortable as text)
+pub fn fill_timestamp(t: DateTime<Utc>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// One fill from the exchange's trade history
+#[derive(Debug, Clone)]
+pub struct ExchangeTrade {
+    pub trade_id: String,
+    pub order_id: String,
+    pub symbol: String,
+    pub side: String,
+    pub quantity: f64,
+    pub price: f64,
+    pub fee: f64,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<&coindcx::Trade> for ExchangeTrade {
+    fn from(t: &coindcx::Trade) -> Self {
+        Self {
+            trade_id: t.id.to_string(),
+            order_id: t.order_id.clone(),
+            symbol: t.symbol.clone(),
+            side: t.side.to_lowercase(),
+            quantity: t.quantity,
+            price: t.price,
+            fee: t.fee_amount.parse().unwrap_or(0.0),
+            timestamp: Utc
+                .timestamp_millis_opt(t.timestamp)
+                .single()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Outcome for one order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchStatus {
+    Matched,
+    QuantityMismatch,
+    MissingLocally,
+    MissingOnExchange,
+}
+
+impl MatchStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchStatus::Matched => "matched",
+            MatchStatus::QuantityMismatch => "quantity_mismatch",
+            MatchStatus::MissingLocally => "missing_locally",
+            MatchStatus::MissingOnExchange => "missing_on_exchange",
+        }
+    }
+}
+
+/// One order's reconciled fills
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconLine {
+    pub order_id: String,
+    pub symbol: String,
+    pub side: String,
+    /// First fill of the order
+    pub time: DateTime<Utc>,
+    pub local_quantity: f64,
+    pub exchange_quantity: f64,
+    /// Volume-weighted fill price (exchange side when present)
+    pub price: f64,
+    /// Fees charged (exchange side when present)
+    pub fee: f64,
+    pub status: MatchStatus,
+}
+
+impl ReconLine {
+    /// Quantity used for accounting: the exchange's, since it is authoritative
+    pub fn quantity(&self) -> f64 {
+        if self.status == MatchStatus::MissingOnExchange {
+            self.local_quantity
+        } else {
+            self.exchange_quantity
+        }
+    }
+}
+
+/// Result of one reconciliation run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconReport {
+    pub generated_at: DateTime<Utc>,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub lines: Vec<ReconLine>,
+}
+
+impl ReconReport {
+    pub fn count(&self, status: MatchStatus) -> usize {
+        self.lines.iter().filter(|l| l.status == status).count()
+    }
+
+    /// Orders that did not match
+    pub fn discrepancies(&self) -> usize {
+        self.lines.len() - self.count(MatchStatus::Matched)
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies() == 0
+    }
+}
+
+#[derive(Default)]
+struct Side {
+    symbol: String,
+    side: String,
+    time: Option<DateTime<Utc>>,
+    quantity: f64,
+    notional: f64,
+    fee: f64,
+}
+
+impl Side {
+    fn add(
+        &mut self,
+        symbol: &str,
+        side: &str,
+        time: DateTime<Utc>,
+        qty: f64,
+        price: f64,
+        fee: f64,
+    ) {
+        if self.symbol.is_empty() {
+            self.symbol = symbol.to_string();
+            self.side = side.to_string();
+        }
+        self.time = Some(self.time.map_or(time, |t| t.min(time)));
+        self.quantity += qty;
+        self.notional += qty * price;
+        self.fee += fee;
+    }
+
+    fn avg_price(&self) -> f64 {
+        if self.quantity > 0.0 {
+            self.notional / self.quantity
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Match local fills to exchange trades by order ID and filled quantity.
+///
+/// Only fills inside `[period_start, period_end]` are compared; paper fills are
+/// ignored.
+pub fn reconcile(
+    local: &[FillRecord],
+    exchange: &[ExchangeTrade],
+    period_start: DateTime<Utc>,
+    period_end: DateTime<Utc>,
+) -> ReconReport {
+    let in_period = |t: DateTime<Utc>| t >= period_start && t <= period_end;
+    let mut orders: BTreeMap<&str, (Side, Side)> = BTreeMap::new();
+
+    for fill in local.iter().filter(|f| !f.paper) {
+        let Ok(time) = DateTime::parse_from_rfc3339(&fill.timestamp) else {
+            continue;
+        };
+        let time = time.with_timezone(&Utc);
+        if in_period(time) {
+            orders.entry(&fill.order_id).or_default().0.add(
+                &fill.symbol,
+                &fill.side,
+                time,
+                fill.quantity,
+                fill.price,
+                fill.fee,
+            );
+        }
+    }
+    for trade in exchange.iter().filter(|t| in_period(t.timestamp)) {
+        orders.entry(&trade.order_id).or_default().1.add(
+            &trade.symbol,
+            &trade.side,
+            trade.timestamp,
+            trade.quantity,
+            trade.price,
+            trade.fee,
+        );
+    }
+
+    let mut lines: Vec<ReconLine> = orders
+        .into_iter()
+        .map(|(order_id, (local, remote))| {
+            let status = match (local.time.is_some(), remote.time.is_some()) {
+                (false, _) => MatchStatus::MissingLocally,
+                (_, false) => MatchStatus::MissingOnExchange,
+                _ if (local.quantity - remote.quantity).abs()
+                    <= QTY_TOLERANCE * remote.quantity.abs().max(1.0) =>
+                {
+                    MatchStatus::Matched
+                }
+                _ => MatchStatus::QuantityMismatch,
+            };
+            let primary = if remote.time.is_some() {
+                &remote
+            } else {
+                &local
+            };
+            ReconLine {
+                order_id: order_id.to_string(),
+                symbol: primary.symbol.clone(),
+                side: primary.side.clone(),
+                time: primary.time.unwrap_or(period_start),
+                local_quantity: local.quantity,
+                exchange_quantity: remote.quantity,
+                price: primary.avg_price(),
+                fee: primary.fee,
+                status,
+            }
+        })
+        .collect();
+    lines.sort_by(|a, b| a.time.cmp(&b.time).then(a.order_id.cmp(&b.order_id)));
+
+    ReconReport {
+        generated_at: Utc::now(),
+        period_start,
+        period_end,
+        lines,
+    }
+}
+
+/// Pull trade history and local fills for `[since, now]` and reconcile them
+pub async fn run(
+    client: &CoinDCXClient,
+    state: &SqliteStateManager,
+    since: DateTime<Utc>,
+) -> Result<ReconReport> {
+    let history = client
+        .get_trade_history(None, since, Utc::now())
+        .await
+        .context("Failed to fetch exchange trade history")?;
+    let exchange: Vec<ExchangeTrade> = history.iter().map(ExchangeTrade::from).collect();
+    let local = state.load_fills(&fill_timestamp(since))?;
+    Ok(reconcile(&local, &exchange, since, Utc::now()))
+}
+
+/// Manifest written next to the CSV export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub generated_at: DateTime<Utc>,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub csv_file: String,
+    /// SHA-256 of the CSV, hex
+    pub csv_sha256: String,
+    pub orders: usize,
+    pub matched: usize,
+    pub quantity_mismatch: usize,
+    pub missing_locally: usize,
+    pub missing_on_exchange: usize,
+    pub buy_value: f64,
+    pub sell_value: f64,
+    pub fees: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_off_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_off_at: Option<DateTime<Utc>>,
+}
+
+/// Write `reconciliation_<end>.csv` and its manifest into `dir`.
+///
+/// `signed_off_by` marks the export as accepted for accounting; it is refused
+/// while the report has discrepancies.
+pub fn write_export(
+    report: &ReconReport,
+    dir: impl AsRef<Path>,
+    signed_off_by: Option<&str>,
+) -> Result<(PathBuf, ExportManifest)> {
+    if signed_off_by.is_some() && !report.is_clean() {
+        bail!(
+            "Cannot sign off: {} order(s) did not reconcile",
+            report.discrepancies()
+        );
+    }
+
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    let stem = format!(
+        "reconciliation_{}",
+        report.period_end.format("%Y%m%d_%H%M%S")
+    );
+    let csv_path = dir.join(format!("{}.csv", stem));
+
+    let mut writer = csv::Writer::from_path(&csv_path)
+        .context(format!("Failed to create {}", csv_path.display()))?;
+    writer.write_record([
+        "time",
+        "order_id",
+        "symbol",
+        "side",
+        "quantity",
+        "price",
+        "value",
+        "fee",
+        "local_quantity",
+        "exchange_quantity",
+        "status",
+    ])?;
+    for line in &report.lines {
+        writer.write_record([
+            line.time.to_rfc3339(),
+            line.order_id.clone(),
+            line.symbol.clone(),
+            line.side.clone(),
+            line.quantity().to_string(),
+            line.price.to_string(),
+            (line.quantity() * line.price).to_string(),
+            line.fee.to_string(),
+            line.local_quantity.to_string(),
+            line.exchange_quantity.to_string(),
+            line.status.as_str().to_string(),
+        ])?;
+    }
+    writer.flush()?;
+    drop(writer);
+
+    let value = |side: &str| -> f64 {
+        report
+            .lines
+            .iter()
+            .filter(|l| l.side == side)
+            .map(|l| l.quantity() * l.price)
+            .sum()
+    };
+    let manifest = ExportManifest {
+        generated_at: report.generated_at,
+        period_start: report.period_start,
+        period_end: report.period_end,
+        csv_file: csv_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        csv_sha256: hex::encode(Sha256::digest(fs::read(&csv_path)?)),
+        orders: report.lines.len(),
+        matched: report.count(MatchStatus::Matched),
+        quantity_mismatch: report.count(MatchStatus::QuantityMismatch),
+        missing_locally: report.count(MatchStatus::MissingLocally),
+        missing_on_exchange: report.count(MatchStatus::MissingOnExchange),
+        buy_value: value("buy"),
+        sell_value: value("sell"),
+        fees: report.lines.iter().map(|l| l.fee).sum(),
+        signed_off_by: signed_off_by.map(String::from),
+        signed_off_at: signed_off_by.map(|_| Utc::now()),
+    };
+    fs::write(
+        dir.join(format!("{}.json", stem)),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok((csv_path, manifest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn local(order_id: &str, qty: f64, t: DateTime<Utc>, paper: bool) -> FillRecord {
+        FillRecord {
+            id: None,
+            order_id: order_id.to_string(),
+            symbol: "BTCINR".to_string(),
+            side: "buy".to_string(),
+            quantity: qty,
+            price: 100.0,
+            fee: 0.1,
+            timestamp: fill_timestamp(t),
+            paper,
+            fill_id: None,
+        }
+    }
+
+    fn remote(order_id: &str, qty: f64, t: DateTime<Utc>) -> ExchangeTrade {
+        ExchangeTrade {
+            trade_id: format!("t-{}", order_id),
+            order_id: order_id.to_string(),
+            symbol: "BTCINR".to_string(),
+            side: "buy".to_string(),
+            quantity: qty,
+            price: 101.0,
+            fee: 0.2,
+            timestamp: t,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_classifies_orders() {
+        let t0 = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let t1 = t0 + Duration::hours(1);
+        let locals = vec![
+            // Partial fills on both sides sum to the same quantity
+            local("A", 0.4, t1, false),
+            local("A", 0.6, t1, false),
+            local("B", 1.0, t1, false),
+            local("C", 1.0, t1, false),
+            local("P", 1.0, t1, true),
+            local("OLD", 1.0, t0 - Duration::days(2), false),
+        ];
+        let remotes = vec![
+            remote("A", 1.0, t1),
+            remote("B", 0.5, t1),
+            remote("D", 2.0, t1),
+        ];
+
+        let report = reconcile(&locals, &remotes, t0, t0 + Duration::days(1));
+        let status = |id: &str| {
+            report
+                .lines
+                .iter()
+                .find(|l| l.order_id == id)
+                .map(|l| l.status)
+        };
+        assert_eq!(status("A"), Some(MatchStatus::Matched));
+        assert_eq!(status("B"), Some(MatchStatus::QuantityMismatch));
+        assert_eq!(status("C"), Some(MatchStatus::MissingOnExchange));
+        assert_eq!(status("D"), Some(MatchStatus::MissingLocally));
+        assert_eq!(status("P"), None); // paper
+        assert_eq!(status("OLD"), None); // outside window
+        assert_eq!(report.discrepancies(), 3);
+
+        // Exchange side is authoritative for price and fees
+        let a = report.lines.iter().find(|l| l.order_id == "A").unwrap();
+        assert_eq!(a.price, 101.0);
+        assert_eq!(a.fee, 0.2);
+    }
+
+    #[test]
+    fn test_export_sign_off_requires_clean_report() {
+        let t0 = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let dir = std::env::temp_dir().join(format!("recon_export_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let dirty = reconcile(&[], &[remote("X", 1.0, t0)], t0, t0 + Duration::hours(1));
+        assert!(write_export(&dirty, &dir, Some("auditor")).is_err());
+
+        let clean = reconcile(
+            &[local("X", 1.0, t0, false)],
+            &[remote("X", 1.0, t0)],
+            t0,
+            t0 + Duration::hours(1),
+        );
+        let (csv_path, manifest) = write_export(&clean, &dir, Some("auditor")).unwrap();
+        assert_eq!(manifest.matched, 1);
+        assert_eq!(manifest.buy_value, 101.0);
+        assert_eq!(manifest.signed_off_by.as_deref(), Some("auditor"));
+        assert_eq!(
+            manifest.csv_sha256,
+            hex::encode(Sha256::digest(fs::read(&csv_path).unwrap()))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
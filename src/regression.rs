@@ -0,0 +1,370 @@
+//! Golden-result regression checks
+//!
+//! A suite pins reference configs (and optionally dataset archives, see
+//! [`crate::dataset`]) whose backtest metrics are stored in a golden file.
+//! `regression-check` re-runs every case and fails when a metric drifts
+//! beyond its tolerance, so an engine change that alters results has to be
+//! acknowledged with `--update` instead of slipping through.
+//!
+//! ```json
+//! {
+//!   "golden": "golden.json",
+//!   "tolerances": { "total_return": 0.0001, "total_trades": 0 },
+//!   "cases": [
+//!     { "name": "vras_btc_1d", "config": "configs/volatility_regime_config.json",
+//!       "start": "2022-01-01", "end": "2025-12-31" }
+//!   ]
+//! }
+//! ```
+//!
+//! Each golden result also keeps the hashes of the data it ran on. When those
+//! differ the case is reported as [`CaseStatus::DataChanged`] rather than as
+//! an engine regression.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::PerformanceMetrics;
+
+/// Suite definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionSuite {
+    /// Golden file, relative to the suite file
+    #[serde(default = "default_golden")]
+    pub golden: String,
+    #[serde(default)]
+    pub tolerances: Tolerances,
+    pub cases: Vec<RegressionCase>,
+}
+
+fn default_golden() -> String {
+    "golden.json".to_string()
+}
+
+impl RegressionSuite {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read suite: {}", path.display()))?;
+        let suite: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse suite: {}", path.display()))?;
+        let mut names: Vec<&str> = suite.cases.iter().map(|c| c.name.as_str()).collect();
+        names.sort_unstable();
+        if let Some(dup) = names.windows(2).find(|w| w[0] == w[1]) {
+            anyhow::bail!("Duplicate regression case name: {}", dup[0]);
+        }
+        Ok(suite)
+    }
+
+    /// Golden file location for a suite loaded from `suite_path`
+    pub fn golden_path(&self, suite_path: impl AsRef<Path>) -> PathBuf {
+        suite_path
+            .as_ref()
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(&self.golden)
+    }
+}
+
+/// One pinned backtest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionCase {
+    pub name: String,
+    /// Config path, relative to the working directory
+    pub config: String,
+    /// Start date (YYYY-MM-DD)
+    #[serde(default)]
+    pub start: Option<String>,
+    /// End date (YYYY-MM-DD)
+    #[serde(default)]
+    pub end: Option<String>,
+    /// Dataset archive to run on instead of the config's `data_dir`
+    #[serde(default)]
+    pub dataset: Option<String>,
+}
+
+/// Largest accepted absolute difference per metric
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tolerances {
+    pub total_return: f64,
+    pub sharpe_ratio: f64,
+    pub max_drawdown: f64,
+    pub win_rate: f64,
+    pub total_commission: f64,
+    pub total_trades: usize,
+}
+
+impl Default for Tolerances {
+    fn default() -> Self {
+        Self {
+            total_return: 1e-6,
+            sharpe_ratio: 1e-6,
+            max_drawdown: 1e-6,
+            win_rate: 1e-6,
+            total_commission: 1e-6,
+            total_trades: 0,
+        }
+    }
+}
+
+/// Metrics kept per case
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoldenMetrics {
+    pub total_return: f64,
+    pub sharpe_ratio: f64,
+    pub max_drawdown: f64,
+    pub win_rate: f64,
+    pub total_commission: f64,
+    pub total_trades: usize,
+}
+
+impl From<&PerformanceMetrics> for GoldenMetrics {
+    fn from(m: &PerformanceMetrics) -> Self {
+        Self {
+            total_return: m.total_return,
+            sharpe_ratio: m.sharpe_ratio,
+            max_drawdown: m.max_drawdown,
+            win_rate: m.win_rate,
+            total_commission: m.total_commission,
+            total_trades: m.total_trades,
+        }
+    }
+}
+
+/// Stored result for one case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoldenResult {
+    pub recorded_at: DateTime<Utc>,
+    pub config_hash: String,
+    /// `SYMBOL_tf` -> SHA-256 of the series the case ran on
+    #[serde(default)]
+    pub data_hashes: BTreeMap<String, String>,
+    pub metrics: GoldenMetrics,
+}
+
+/// Golden results by case name
+pub type GoldenFile = BTreeMap<String, GoldenResult>;
+
+/// Missing file = no golden results yet
+pub fn load_golden(path: impl AsRef<Path>) -> Result<GoldenFile> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(GoldenFile::new());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read golden file: {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse golden file: {}", path.display()))
+}
+
+pub fn save_golden(path: impl AsRef<Path>, golden: &GoldenFile) -> Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(golden)? + "\n")
+        .with_context(|| format!("Failed to write golden file: {}", path.display()))
+}
+
+/// A metric outside its tolerance
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deviation {
+    pub metric: &'static str,
+    pub expected: f64,
+    pub actual: f64,
+    pub tolerance: f64,
+}
+
+impl std::fmt::Display for Deviation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: expected {:.6}, got {:.6} (Δ {:+.6}, tolerance {})",
+            self.metric,
+            self.expected,
+            self.actual,
+            self.actual - self.expected,
+            self.tolerance
+        )
+    }
+}
+
+/// Every metric in `actual` that differs from `expected` by more than its tolerance
+pub fn compare(
+    expected: &GoldenMetrics,
+    actual: &GoldenMetrics,
+    tolerances: &Tolerances,
+) -> Vec<Deviation> {
+    let checks = [
+        (
+            "total_return",
+            expected.total_return,
+            actual.total_return,
+            tolerances.total_return,
+        ),
+        (
+            "sharpe_ratio",
+            expected.sharpe_ratio,
+            actual.sharpe_ratio,
+            tolerances.sharpe_ratio,
+        ),
+        (
+            "max_drawdown",
+            expected.max_drawdown,
+            actual.max_drawdown,
+            tolerances.max_drawdown,
+        ),
+        (
+            "win_rate",
+            expected.win_rate,
+            actual.win_rate,
+            tolerances.win_rate,
+        ),
+        (
+            "total_commission",
+            expected.total_commission,
+            actual.total_commission,
+            tolerances.total_commission,
+        ),
+        (
+            "total_trades",
+            expected.total_trades as f64,
+            actual.total_trades as f64,
+            tolerances.total_trades as f64,
+        ),
+    ];
+    checks
+        .into_iter()
+        .filter(|(_, e, a, tol)| !((a - e).abs() <= *tol || (e.is_nan() && a.is_nan())))
+        .map(|(metric, expected, actual, tolerance)| Deviation {
+            metric,
+            expected,
+            actual,
+            tolerance,
+        })
+        .collect()
+}
+
+/// Outcome of one case
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaseStatus {
+    Pass,
+    /// Same data, different results
+    Regressed(Vec<Deviation>),
+    /// Input data differs from what the golden result was recorded on
+    DataChanged(Vec<String>),
+    /// No golden result for this case
+    Missing,
+}
+
+impl CaseStatus {
+    pub fn is_pass(&self) -> bool {
+        matches!(self, CaseStatus::Pass)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CaseStatus::Pass => "PASS",
+            CaseStatus::Regressed(_) => "FAIL",
+            CaseStatus::DataChanged(_) => "DATA CHANGED",
+            CaseStatus::Missing => "NO GOLDEN",
+        }
+    }
+}
+
+/// Check a fresh result against the golden one
+pub fn check_case(
+    golden: Option<&GoldenResult>,
+    data_hashes: &BTreeMap<String, String>,
+    actual: &GoldenMetrics,
+    tolerances: &Tolerances,
+) -> CaseStatus {
+    let Some(golden) = golden else {
+        return CaseStatus::Missing;
+    };
+    let mut changed: Vec<String> = golden
+        .data_hashes
+        .iter()
+        .filter(|(series, hash)| data_hashes.get(*series) != Some(*hash))
+        .map(|(series, _)| series.clone())
+        .collect();
+    changed.extend(
+        data_hashes
+            .keys()
+            .filter(|series| !golden.data_hashes.contains_key(*series))
+            .cloned(),
+    );
+    if !changed.is_empty() {
+        return CaseStatus::DataChanged(changed);
+    }
+    let deviations = compare(&golden.metrics, actual, tolerances);
+    if deviations.is_empty() {
+        CaseStatus::Pass
+    } else {
+        CaseStatus::Regressed(deviations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics() -> GoldenMetrics {
+        GoldenMetrics {
+            total_return: 12.5,
+            sharpe_ratio: 1.1,
+            max_drawdown: 8.0,
+            win_rate: 55.0,
+            total_commission: 120.0,
+            total_trades: 20,
+        }
+    }
+
+    fn golden() -> GoldenResult {
+        GoldenResult {
+            recorded_at: Utc::now(),
+            config_hash: "abc".to_string(),
+            data_hashes: BTreeMap::from([("BTCINR_1d".to_string(), "h1".to_string())]),
+            metrics: metrics(),
+        }
+    }
+
+    #[test]
+    fn test_check_case_statuses() {
+        let tol = Tolerances {
+            total_return: 0.01,
+            ..Tolerances::default()
+        };
+        let hashes = golden().data_hashes;
+
+        let mut within = metrics();
+        within.total_return += 0.005;
+        assert!(check_case(Some(&golden()), &hashes, &within, &tol).is_pass());
+
+        let mut drifted = metrics();
+        drifted.total_return += 0.5;
+        drifted.total_trades += 1;
+        match check_case(Some(&golden()), &hashes, &drifted, &tol) {
+            CaseStatus::Regressed(devs) => {
+                let names: Vec<_> = devs.iter().map(|d| d.metric).collect();
+                assert_eq!(names, vec!["total_return", "total_trades"]);
+            }
+            other => panic!("expected regression, got {:?}", other),
+        }
+
+        let other_data = BTreeMap::from([("BTCINR_1d".to_string(), "h2".to_string())]);
+        assert_eq!(
+            check_case(Some(&golden()), &other_data, &drifted, &tol),
+            CaseStatus::DataChanged(vec!["BTCINR_1d".to_string()])
+        );
+        assert_eq!(
+            check_case(None, &hashes, &metrics(), &tol),
+            CaseStatus::Missing
+        );
+    }
+}
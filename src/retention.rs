@@ -0,0 +1,432 @@
+//! Warm/cold data tiers and retention
+//!
+//! Downloaded candles live in `<data_dir>/SYMBOL_TF.csv` (warm tier). The
+//! retention policy in `backtest.data_retention` bounds how much of that is
+//! kept:
+//!
+//! - `compact_after_days`: candles older than this move from the CSV into
+//!   `<data_dir>/cold/SYMBOL_TF.parquet` (cold tier, snappy-compressed).
+//! - `max_age_days`: candles older than this are deleted from both tiers.
+//!
+//! Timeframes without an entry are kept forever. Loaders in [`crate::data`]
+//! merge both tiers transparently, so compaction never changes backtest results.
+//!
+//! Note: a backtest whose range reaches past `max_age_days` will re-download the
+//! pruned history, so keep the retention window wider than the backtest window.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use parquet::basic::Compression;
+use parquet::data_type::{DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::writer::SerializedFileWriter;
+use parquet::record::RowAccessor;
+use parquet::schema::parser::parse_message_type;
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::config::DataRetentionConfig;
+use crate::data::load_csv;
+use crate::Candle;
+
+/// Subdirectory of `data_dir` holding the cold tier
+pub const COLD_DIR: &str = "cold";
+
+const CANDLE_SCHEMA: &str = "
+    message candle {
+        REQUIRED INT64 timestamp_ms;
+        REQUIRED DOUBLE open;
+        REQUIRED DOUBLE high;
+        REQUIRED DOUBLE low;
+        REQUIRED DOUBLE close;
+        REQUIRED DOUBLE volume;
+    }
+";
+
+/// Path of the cold-tier file for a symbol/timeframe
+pub fn cold_path(data_dir: impl AsRef<Path>, symbol: &str, timeframe: &str) -> PathBuf {
+    data_dir
+        .as_ref()
+        .join(COLD_DIR)
+        .join(format!("{}_{}.parquet", symbol, timeframe))
+}
+
+/// Write candles to a parquet file, replacing it if present
+pub fn write_parquet(path: impl AsRef<Path>, candles: &[Candle]) -> Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let schema = Arc::new(parse_message_type(CANDLE_SCHEMA)?);
+    let props = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build(),
+    );
+    let file = File::create(path).context("Failed to create parquet file")?;
+    let mut writer = SerializedFileWriter::new(file, schema, props)?;
+
+    let timestamps: Vec<i64> = candles
+        .iter()
+        .map(|c| c.datetime.timestamp_millis())
+        .collect();
+    let columns: [Vec<f64>; 5] = [
+        candles.iter().map(|c| c.open).collect(),
+        candles.iter().map(|c| c.high).collect(),
+        candles.iter().map(|c| c.low).collect(),
+        candles.iter().map(|c| c.close).collect(),
+        candles.iter().map(|c| c.volume).collect(),
+    ];
+
+    let mut row_group = writer.next_row_group()?;
+    let mut column = row_group
+        .next_column()?
+        .context("Parquet schema missing timestamp column")?;
+    column
+        .typed::<Int64Type>()
+        .write_batch(&timestamps, None, None)?;
+    column.close()?;
+    for values in &columns {
+        let mut column = row_group
+            .next_column()?
+            .context("Parquet schema missing price column")?;
+        column
+            .typed::<DoubleType>()
+            .write_batch(values, None, None)?;
+        column.close()?;
+    }
+    row_group.close()?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Read candles from a parquet file written by [`write_parquet`]
+pub fn read_parquet(path: impl AsRef<Path>) -> Result<Vec<Candle>> {
+    let file = File::open(path.as_ref()).context("Failed to open parquet file")?;
+    let reader = SerializedFileReader::new(file)?;
+
+    let mut candles = Vec::new();
+    for row in reader.get_row_iter(None)? {
+        let row = row?;
+        let datetime = DateTime::<Utc>::from_timestamp_millis(row.get_long(0)?)
+            .context("Invalid timestamp in parquet file")?;
+        candles.push(Candle::new_unchecked(
+            datetime,
+            row.get_double(1)?,
+            row.get_double(2)?,
+            row.get_double(3)?,
+            row.get_double(4)?,
+            row.get_double(5)?,
+        ));
+    }
+    Ok(candles)
+}
+
+/// Write candles in the same CSV layout the downloaders produce
+fn write_csv(path: &Path, candles: &[Candle]) -> Result<()> {
+    let mut file = File::create(path).context("Failed to create CSV file")?;
+    writeln!(file, "datetime,open,high,low,close,volume")?;
+    for candle in candles {
+        writeln!(
+            file,
+            "{},{},{},{},{},{}",
+            candle.datetime.format("%Y-%m-%d %H:%M:%S"),
+            candle.open,
+            candle.high,
+            candle.low,
+            candle.close,
+            candle.volume
+        )?;
+    }
+    Ok(())
+}
+
+/// What the policy does to one symbol/timeframe
+#[derive(Debug, Clone)]
+pub struct TierAction {
+    pub symbol: String,
+    pub timeframe: String,
+    /// Rows in the CSV before the policy runs
+    pub warm_rows: usize,
+    /// Rows in the parquet file before the policy runs
+    pub cold_rows: usize,
+    /// Rows moved from CSV to parquet
+    pub compacted_rows: usize,
+    /// Rows deleted from either tier
+    pub pruned_rows: usize,
+    /// Bytes on disk across both tiers before the policy runs
+    pub bytes_before: u64,
+}
+
+impl TierAction {
+    pub fn is_noop(&self) -> bool {
+        self.compacted_rows == 0 && self.pruned_rows == 0
+    }
+}
+
+/// Split `SYMBOL_TF` file stems into (symbol, timeframe)
+fn split_stem(stem: &str) -> Option<(String, String)> {
+    let (symbol, timeframe) = stem.rsplit_once('_')?;
+    if symbol.is_empty() || !crate::data::INTERVALS.contains(&timeframe) {
+        return None;
+    }
+    Some((symbol.to_string(), timeframe.to_string()))
+}
+
+/// Symbol/timeframe pairs present in either tier
+pub fn tiered_series(data_dir: &Path) -> Result<BTreeSet<(String, String)>> {
+    let mut series = BTreeSet::new();
+    for (dir, ext) in [
+        (data_dir.to_path_buf(), "csv"),
+        (data_dir.join(COLD_DIR), "parquet"),
+    ] {
+        if !dir.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(ext) {
+                continue;
+            }
+            if let Some(pair) = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(split_stem)
+            {
+                series.insert(pair);
+            }
+        }
+    }
+    Ok(series)
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Apply the retention policy to every series under `data_dir`.
+///
+/// With `dry_run` nothing is written; the returned actions describe what
+/// would change. Series the policy leaves untouched are omitted.
+pub fn enforce(
+    data_dir: impl AsRef<Path>,
+    policy: &DataRetentionConfig,
+    now: DateTime<Utc>,
+    dry_run: bool,
+) -> Result<Vec<TierAction>> {
+    let data_dir = data_dir.as_ref();
+    let mut actions = Vec::new();
+    if policy.is_noop() {
+        return Ok(actions);
+    }
+
+    for (symbol, timeframe) in tiered_series(data_dir)? {
+        let prune_cutoff = policy
+            .max_age_days
+            .get(&timeframe)
+            .map(|d| now - Duration::days(*d as i64));
+        let compact_cutoff = policy
+            .compact_after_days
+            .get(&timeframe)
+            .map(|d| now - Duration::days(*d as i64));
+        if prune_cutoff.is_none() && compact_cutoff.is_none() {
+            continue;
+        }
+
+        let csv_path = data_dir.join(format!("{}_{}.csv", symbol, timeframe));
+        let parquet_path = cold_path(data_dir, &symbol, &timeframe);
+        let warm = if csv_path.exists() {
+            load_csv(&csv_path)?
+        } else {
+            Vec::new()
+        };
+        let cold = if parquet_path.exists() {
+            read_parquet(&parquet_path)?
+        } else {
+            Vec::new()
+        };
+
+        let keep = |c: &Candle| prune_cutoff.is_none_or(|cut| c.datetime >= cut);
+        let stays_warm = |c: &Candle| compact_cutoff.is_none_or(|cut| c.datetime >= cut);
+
+        let new_warm: Vec<Candle> = warm
+            .iter()
+            .filter(|c| keep(c) && stays_warm(c))
+            .cloned()
+            .collect();
+        let compacted: Vec<Candle> = warm
+            .iter()
+            .filter(|c| keep(c) && !stays_warm(c))
+            .cloned()
+            .collect();
+        let mut new_cold: Vec<Candle> = cold.iter().filter(|c| keep(c)).cloned().collect();
+        new_cold.extend(compacted.iter().cloned());
+        new_cold.sort_by_key(|c| c.datetime);
+        new_cold.dedup_by_key(|c| c.datetime);
+
+        let action = TierAction {
+            symbol: symbol.clone(),
+            timeframe: timeframe.clone(),
+            warm_rows: warm.len(),
+            cold_rows: cold.len(),
+            compacted_rows: compacted.len(),
+            pruned_rows: warm.iter().chain(cold.iter()).filter(|c| !keep(c)).count(),
+            bytes_before: file_size(&csv_path) + file_size(&parquet_path),
+        };
+        if action.is_noop() {
+            continue;
+        }
+
+        if !dry_run {
+            // Write the cold tier first so a crash never loses compacted rows
+            if new_cold.is_empty() {
+                if parquet_path.exists() {
+                    fs::remove_file(&parquet_path)?;
+                }
+            } else if new_cold.len() != cold.len() || action.compacted_rows > 0 {
+                write_parquet(&parquet_path, &new_cold)?;
+            }
+
+            if new_warm.is_empty() {
+                if csv_path.exists() {
+                    fs::remove_file(&csv_path)?;
+                }
+            } else if new_warm.len() != warm.len() {
+                write_csv(&csv_path, &new_warm)?;
+            }
+
+            info!(
+                "Retention {} {}: compacted {}, pruned {}",
+                symbol, timeframe, action.compacted_rows, action.pruned_rows
+            );
+        }
+        actions.push(action);
+    }
+
+    Ok(actions)
+}
+
+/// Apply `backtest.data_retention` if `auto_prune` is enabled
+pub fn auto_prune(config: &crate::Config) -> Result<()> {
+    let policy = &config.backtest.data_retention;
+    if !policy.auto_prune || policy.is_noop() {
+        return Ok(());
+    }
+    let actions = enforce(&config.backtest.data_dir, policy, Utc::now(), false)?;
+    if !actions.is_empty() {
+        let compacted: usize = actions.iter().map(|a| a.compacted_rows).sum();
+        let pruned: usize = actions.iter().map(|a| a.pruned_rows).sum();
+        info!(
+            "Data retention: compacted {} rows, pruned {} rows across {} series",
+            compacted,
+            pruned,
+            actions.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    fn hourly(start: DateTime<Utc>, n: usize) -> Vec<Candle> {
+        (0..n)
+            .map(|i| {
+                let p = 100.0 + i as f64;
+                Candle::new_unchecked(
+                    start + Duration::hours(i as i64),
+                    p,
+                    p + 1.0,
+                    p - 1.0,
+                    p,
+                    10.0,
+                )
+            })
+            .collect()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("retention_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_parquet_roundtrip() {
+        let dir = temp_dir("roundtrip");
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let candles = hourly(start, 48);
+
+        let path = cold_path(&dir, "BTCINR", "1h");
+        write_parquet(&path, &candles).unwrap();
+        let loaded = read_parquet(&path).unwrap();
+
+        assert_eq!(loaded.len(), 48);
+        assert_eq!(loaded[0].datetime, start);
+        assert_eq!(loaded[47].close, candles[47].close);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_enforce_compacts_and_prunes() {
+        let dir = temp_dir("enforce");
+        let now = Utc.with_ymd_and_hms(2024, 1, 11, 0, 0, 0).unwrap();
+        // 10 days of hourly bars, 240 rows
+        let candles = hourly(now - Duration::days(10), 240);
+        write_csv(&dir.join("BTCINR_1m.csv"), &candles).unwrap();
+        write_csv(&dir.join("BTCINR_1h.csv"), &candles).unwrap();
+
+        let policy = DataRetentionConfig {
+            max_age_days: HashMap::from([("1m".to_string(), 8)]),
+            compact_after_days: HashMap::from([("1m".to_string(), 3)]),
+            auto_prune: false,
+        };
+
+        // Dry run reports but leaves files alone; 1h has no policy
+        let plan = enforce(&dir, &policy, now, true).unwrap();
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].timeframe, "1m");
+        assert_eq!(plan[0].pruned_rows, 48);
+        assert_eq!(plan[0].compacted_rows, 120);
+        assert!(!cold_path(&dir, "BTCINR", "1m").exists());
+
+        let applied = enforce(&dir, &policy, now, false).unwrap();
+        assert_eq!(applied[0].pruned_rows, 48);
+        assert_eq!(load_csv(dir.join("BTCINR_1m.csv")).unwrap().len(), 72);
+        assert_eq!(
+            read_parquet(cold_path(&dir, "BTCINR", "1m")).unwrap().len(),
+            120
+        );
+        assert_eq!(load_csv(dir.join("BTCINR_1h.csv")).unwrap().len(), 240);
+
+        // Second pass is a no-op
+        assert!(enforce(&dir, &policy, now, false).unwrap().is_empty());
+
+        // Later, the oldest cold rows age out
+        let later = now + Duration::days(1);
+        let plan = enforce(&dir, &policy, later, false).unwrap();
+        assert_eq!(plan[0].pruned_rows, 24);
+        assert_eq!(plan[0].compacted_rows, 24);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_split_stem() {
+        assert_eq!(
+            split_stem("BTC_INR_5m"),
+            Some(("BTC_INR".to_string(), "5m".to_string()))
+        );
+        assert_eq!(split_stem("notes"), None);
+        assert_eq!(split_stem("BTCINR_7x"), None);
+    }
+}
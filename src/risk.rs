@@ -1,7 +1,12 @@
 //! Risk management framework
 //!
 //! Implements portfolio-level risk controls including position sizing,
-//! drawdown-based de-risking, and consecutive loss protection.
+//! drawdown-based de-risking, consecutive loss protection, optional
+//! anti-martingale (win-streak) size scaling, per-group exposure caps,
+//! an optional expected-shortfall (CVaR) cap on per-trade tail loss, an
+//! optional daily risk budget shared with other strategies, a prop-firm
+//! style trailing drawdown ([`TrailingDrawdown`]), and a cost-versus-target
+//! check for the minimum-edge gate ([`EdgeCheck`]).
 //!
 //! # Currency-Agnostic Design
 //!
@@ -19,7 +24,20 @@
 //! currency unit (USD, INR, EUR, etc.), as long as capital and prices
 //! are consistent.
 
-use crate::Position;
+use crate::config::{ActivityFloors, TrailingDrawdownConfig};
+use crate::indicators;
+use crate::risk_budget::SharedRiskBudget;
+use crate::tail_risk;
+use crate::{Candle, Position, Trade};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Group name reported for symbols that are not tagged with any group
+pub const UNGROUPED: &str = "ungrouped";
+
+/// Closed trades needed before the CVaR cap starts limiting size
+pub const CVAR_MIN_TRADES: usize = 20;
 
 /// Configuration for RiskManager using builder pattern
 #[derive(Debug, Clone)]
@@ -38,6 +56,16 @@ pub struct RiskManagerConfig {
     pub drawdown_critical_multiplier: f64,
     pub consecutive_loss_limit: usize,
     pub consecutive_loss_multiplier: f64,
+    /// Wins in a row before size is scaled up (0 = anti-martingale disabled)
+    pub win_streak_threshold: usize,
+    pub win_streak_step: f64,
+    pub win_streak_max_multiplier: f64,
+    /// Symbol -> group tag (e.g., "BTCINR" -> "L1")
+    pub symbol_groups: HashMap<String, String>,
+    /// Group -> max notional exposure as a fraction of capital
+    pub group_exposure_caps: HashMap<String, f64>,
+    /// Max 99% per-trade CVaR as a fraction of capital (None = disabled)
+    pub max_trade_cvar: Option<f64>,
 }
 
 impl Default for RiskManagerConfig {
@@ -55,6 +83,12 @@ impl Default for RiskManagerConfig {
             drawdown_critical_multiplier: 0.25,
             consecutive_loss_limit: 3,
             consecutive_loss_multiplier: 0.75,
+            win_streak_threshold: 0,
+            win_streak_step: 0.10,
+            win_streak_max_multiplier: 1.5,
+            symbol_groups: HashMap::new(),
+            group_exposure_caps: HashMap::new(),
+            max_trade_cvar: None,
         }
     }
 }
@@ -118,6 +152,41 @@ impl RiskManagerConfig {
         self
     }
 
+    /// Enable anti-martingale sizing: after `threshold` consecutive wins, size grows by
+    /// `step` per win, capped at `max_multiplier`. Any loss resets the streak.
+    pub fn with_win_streak_scaling(
+        mut self,
+        threshold: usize,
+        step: f64,
+        max_multiplier: f64,
+    ) -> Self {
+        self.win_streak_threshold = threshold;
+        self.win_streak_step = step;
+        self.win_streak_max_multiplier = max_multiplier;
+        self
+    }
+
+    /// Tag symbols with groups and set per-group exposure caps
+    ///
+    /// `groups` maps a group name to its symbols; `caps` maps a group name to the
+    /// maximum notional exposure as a fraction of capital (e.g., 0.5 = 50%).
+    pub fn with_group_limits(
+        mut self,
+        groups: &HashMap<String, Vec<String>>,
+        caps: &HashMap<String, f64>,
+    ) -> Self {
+        self.symbol_groups = invert_groups(groups);
+        self.group_exposure_caps = caps.clone();
+        self
+    }
+
+    /// Cap position size so the 99% expected shortfall of a trade, estimated
+    /// from the closed-trade return distribution, stays under `cap` of capital
+    pub fn with_cvar_cap(mut self, cap: Option<f64>) -> Self {
+        self.max_trade_cvar = cap;
+        self
+    }
+
     /// Build the RiskManager
     pub fn build(self) -> RiskManager {
         RiskManager::from_config(self)
@@ -139,10 +208,22 @@ pub struct RiskManager {
     pub consecutive_loss_multiplier: f64,
     pub consecutive_losses: usize,
     pub consecutive_wins: usize,
+    pub win_streak_threshold: usize,
+    pub win_streak_step: f64,
+    pub win_streak_max_multiplier: f64,
+    pub symbol_groups: HashMap<String, String>,
+    pub group_exposure_caps: HashMap<String, f64>,
     pub risk_per_trade: f64,
     pub max_positions: usize,
     pub max_portfolio_heat: f64,
     pub max_position_pct: f64,
+    pub max_trade_cvar: Option<f64>,
+    /// Net returns (on notional) of closed trades, oldest first
+    pub trade_returns: Vec<f64>,
+    /// Shared daily budget and the strategy name entries draw against
+    pub risk_budget: Option<(SharedRiskBudget, String)>,
+    /// Prop-firm trailing drawdown state (None = rule disabled)
+    pub trailing_drawdown: Option<TrailingDrawdown>,
 }
 
 impl RiskManager {
@@ -161,10 +242,19 @@ impl RiskManager {
             consecutive_loss_multiplier: config.consecutive_loss_multiplier,
             consecutive_losses: 0,
             consecutive_wins: 0,
+            win_streak_threshold: config.win_streak_threshold,
+            win_streak_step: config.win_streak_step,
+            win_streak_max_multiplier: config.win_streak_max_multiplier,
+            symbol_groups: config.symbol_groups,
+            group_exposure_caps: config.group_exposure_caps,
             risk_per_trade: config.risk_per_trade,
             max_positions: config.max_positions,
             max_portfolio_heat: config.max_portfolio_heat,
             max_position_pct: config.max_position_pct,
+            max_trade_cvar: config.max_trade_cvar,
+            trade_returns: Vec::new(),
+            risk_budget: None,
+            trailing_drawdown: None,
         }
     }
 
@@ -197,13 +287,80 @@ impl RiskManager {
             consecutive_loss_multiplier,
             consecutive_losses: 0,
             consecutive_wins: 0,
+            win_streak_threshold: 0,
+            win_streak_step: 0.0,
+            win_streak_max_multiplier: 1.0,
+            symbol_groups: HashMap::new(),
+            group_exposure_caps: HashMap::new(),
             risk_per_trade,
             max_positions,
             max_portfolio_heat,
             max_position_pct,
+            max_trade_cvar: None,
+            trade_returns: Vec::new(),
+            risk_budget: None,
+            trailing_drawdown: None,
         }
     }
 
+    /// Enable anti-martingale sizing on an existing manager (see
+    /// [`RiskManagerConfig::with_win_streak_scaling`])
+    pub fn with_win_streak_scaling(
+        mut self,
+        threshold: usize,
+        step: f64,
+        max_multiplier: f64,
+    ) -> Self {
+        self.win_streak_threshold = threshold;
+        self.win_streak_step = step;
+        self.win_streak_max_multiplier = max_multiplier;
+        self
+    }
+
+    /// Tag symbols with groups and set per-group exposure caps on an existing manager
+    /// (see [`RiskManagerConfig::with_group_limits`])
+    pub fn with_group_limits(
+        mut self,
+        groups: &HashMap<String, Vec<String>>,
+        caps: &HashMap<String, f64>,
+    ) -> Self {
+        self.symbol_groups = invert_groups(groups);
+        self.group_exposure_caps = caps.clone();
+        self
+    }
+
+    /// Set the per-trade CVaR cap on an existing manager
+    /// (see [`RiskManagerConfig::with_cvar_cap`])
+    pub fn with_cvar_cap(mut self, cap: Option<f64>) -> Self {
+        self.max_trade_cvar = cap;
+        self
+    }
+
+    /// Draw every entry's initial risk from a budget shared with other strategies
+    /// (see [`crate::risk_budget`])
+    pub fn with_risk_budget(mut self, budget: SharedRiskBudget, strategy: &str) -> Self {
+        self.risk_budget = Some((budget, strategy.to_string()));
+        self
+    }
+
+    /// Enforce a prop-firm trailing drawdown (see [`TrailingDrawdown`])
+    pub fn with_trailing_drawdown(mut self, config: Option<&TrailingDrawdownConfig>) -> Self {
+        self.trailing_drawdown = config.map(|c| TrailingDrawdown::new(c, self.initial_capital));
+        self
+    }
+
+    /// Feed mark-to-market equity to the trailing drawdown rule
+    ///
+    /// Returns the warning or breach this update crossed into, if any.
+    pub fn check_trailing_drawdown(&mut self, equity: f64) -> Option<TrailingDrawdownEvent> {
+        self.trailing_drawdown.as_mut()?.update(equity)
+    }
+
+    /// True once the trailing drawdown floor has been breached
+    pub fn trailing_drawdown_breached(&self) -> bool {
+        self.trailing_drawdown.as_ref().is_some_and(|t| t.breached)
+    }
+
     /// Update capital and track peak
     pub fn update_capital(&mut self, new_capital: f64) {
         self.current_capital = new_capital;
@@ -212,6 +369,21 @@ impl RiskManager {
         }
     }
 
+    /// Account for money deposited (positive) or withdrawn (negative)
+    ///
+    /// Shifts capital, its peak and the trailing drawdown's high-water mark
+    /// together, so a top-up is not mistaken for profit and a withdrawal
+    /// does not read as drawdown.
+    pub fn apply_cash_flow(&mut self, amount: f64) {
+        self.initial_capital += amount;
+        self.current_capital += amount;
+        self.peak_capital += amount;
+        if let Some(trailing) = self.trailing_drawdown.as_mut() {
+            trailing.high_water_mark += amount;
+            trailing.lock_level = trailing.lock_level.map(|lock| lock + amount);
+        }
+    }
+
     /// Get current peak capital
     pub fn peak_capital(&self) -> f64 {
         self.peak_capital
@@ -227,7 +399,7 @@ impl RiskManager {
 
     /// Check if trading should be halted due to excessive drawdown
     pub fn should_halt_trading(&self) -> bool {
-        self.current_drawdown() >= self.max_drawdown
+        self.current_drawdown() >= self.max_drawdown || self.trailing_drawdown_breached()
     }
 
     /// Get position size multiplier based on drawdown
@@ -252,6 +424,18 @@ impl RiskManager {
         }
     }
 
+    /// Get position size multiplier based on consecutive wins (anti-martingale)
+    ///
+    /// Returns 1.0 until the streak reaches `win_streak_threshold`, then grows by
+    /// `win_streak_step` per win, bounded to `[1.0, win_streak_max_multiplier]`.
+    pub fn win_streak_multiplier(&self) -> f64 {
+        if self.win_streak_threshold == 0 || self.consecutive_wins < self.win_streak_threshold {
+            return 1.0;
+        }
+        let steps = (self.consecutive_wins - self.win_streak_threshold + 1) as f64;
+        (1.0 + self.win_streak_step * steps).clamp(1.0, self.win_streak_max_multiplier.max(1.0))
+    }
+
     /// Calculate position size for a trade with regime score adjustment
     pub fn calculate_position_size_with_regime(
         &self,
@@ -276,8 +460,11 @@ impl RiskManager {
         // Apply consecutive loss multiplier
         let cl_multiplier = self.consecutive_loss_multiplier();
 
+        // Apply win streak multiplier (1.0 unless anti-martingale is enabled)
+        let ws_multiplier = self.win_streak_multiplier();
+
         // Combined risk amount
-        let adjusted_risk = regime_adjusted * dd_multiplier * cl_multiplier;
+        let adjusted_risk = regime_adjusted * dd_multiplier * cl_multiplier * ws_multiplier;
 
         // Calculate position size based on stop distance
         let stop_distance = (entry_price - stop_price).abs();
@@ -367,8 +554,11 @@ impl RiskManager {
         // Apply consecutive loss multiplier
         let cl_multiplier = self.consecutive_loss_multiplier();
 
+        // Apply win streak multiplier (1.0 unless anti-martingale is enabled)
+        let ws_multiplier = self.win_streak_multiplier();
+
         // Combined risk amount
-        let adjusted_risk = regime_adjusted * dd_multiplier * cl_multiplier;
+        let adjusted_risk = regime_adjusted * dd_multiplier * cl_multiplier * ws_multiplier;
 
         // Calculate position size based on stop distance
         let stop_distance = (entry_price - stop_price).abs();
@@ -407,6 +597,94 @@ impl RiskManager {
         position_size
     }
 
+    /// Group tag for a symbol, if any
+    pub fn symbol_group(&self, symbol: &str) -> Option<&str> {
+        self.symbol_groups.get(symbol).map(String::as_str)
+    }
+
+    /// Notional exposure per group as a fraction of current capital
+    ///
+    /// Positions are valued at entry (average entry price × quantity). Untagged
+    /// symbols are reported under [`UNGROUPED`]. Sorted by group name.
+    pub fn group_exposures<'a, I>(&self, positions: I) -> Vec<(String, f64)>
+    where
+        I: Iterator<Item = &'a Position>,
+    {
+        let mut exposures: HashMap<String, f64> = HashMap::new();
+        for pos in positions {
+            let group = self.symbol_group(pos.symbol.as_str()).unwrap_or(UNGROUPED);
+            let notional = (pos.average_entry_price * pos.quantity).to_f64();
+            *exposures.entry(group.to_string()).or_insert(0.0) += notional;
+        }
+
+        let mut result: Vec<(String, f64)> = exposures
+            .into_iter()
+            .map(|(group, notional)| {
+                let pct = if self.current_capital > 0.0 {
+                    notional / self.current_capital
+                } else {
+                    0.0
+                };
+                (group, pct)
+            })
+            .collect();
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+        result
+    }
+
+    /// Limit a proposed position size so the symbol's group stays within its cap
+    ///
+    /// Returns `size` unchanged for ungrouped symbols or groups without a cap.
+    pub fn apply_group_limit<'a, I>(
+        &self,
+        symbol: &str,
+        entry_price: f64,
+        size: f64,
+        positions: I,
+    ) -> f64
+    where
+        I: Iterator<Item = &'a Position>,
+    {
+        let Some(group) = self.symbol_group(symbol) else {
+            return size;
+        };
+        let Some(&cap) = self.group_exposure_caps.get(group) else {
+            return size;
+        };
+        if entry_price <= 0.0 {
+            return size;
+        }
+
+        let current: f64 = positions
+            .filter(|p| self.symbol_group(p.symbol.as_str()) == Some(group))
+            .map(|p| (p.average_entry_price * p.quantity).to_f64())
+            .sum();
+        let remaining = self.current_capital * cap - current;
+
+        if remaining <= 0.0 {
+            tracing::debug!(
+                "Group '{}' exposure cap reached ({:.2} of {:.0}%) - no room for {}",
+                group,
+                current,
+                cap * 100.0,
+                symbol
+            );
+            return 0.0;
+        }
+
+        let limited = remaining / entry_price;
+        if limited < size {
+            tracing::debug!(
+                "Group '{}' exposure cap limiting {}: size {:.6} -> {:.6}",
+                group,
+                symbol,
+                size,
+                limited
+            );
+        }
+        size.min(limited)
+    }
+
     /// Record a winning trade
     pub fn record_win(&mut self) {
         self.consecutive_wins += 1;
@@ -418,6 +696,282 @@ impl RiskManager {
         self.consecutive_losses += 1;
         self.consecutive_wins = 0;
     }
+
+    /// Record a closed trade: updates win/loss streaks and the return
+    /// distribution used by the CVaR cap
+    pub fn record_trade(&mut self, trade: &Trade) {
+        if trade.net_pnl.is_positive() {
+            self.record_win();
+        } else {
+            self.record_loss();
+        }
+        if let Some(r) = tail_risk::trade_return(trade) {
+            self.trade_returns.push(r);
+        }
+    }
+
+    /// Historical 99% CVaR of per-trade returns, once enough trades are recorded
+    pub fn trade_cvar_99(&self) -> Option<f64> {
+        (self.trade_returns.len() >= CVAR_MIN_TRADES)
+            .then(|| tail_risk::historical_cvar(&self.trade_returns, 0.99))
+    }
+
+    /// Limit a proposed position size so that its 99% CVaR loss
+    /// (`notional * cvar`) stays within `max_trade_cvar` of current capital
+    ///
+    /// Returns `size` unchanged when the cap is disabled, fewer than
+    /// [`CVAR_MIN_TRADES`] trades are recorded, or the tail holds no losses.
+    pub fn apply_cvar_limit(&self, entry_price: f64, size: f64) -> f64 {
+        let Some(cap) = self.max_trade_cvar else {
+            return size;
+        };
+        let Some(cvar) = self.trade_cvar_99().filter(|c| *c > 0.0) else {
+            return size;
+        };
+        if entry_price <= 0.0 {
+            return size;
+        }
+
+        let limited = self.current_capital * cap / (cvar * entry_price);
+        if limited < size {
+            tracing::debug!(
+                "CVaR cap limiting size {:.6} -> {:.6} (99% CVaR {:.2}%, cap {:.2}% of capital)",
+                size,
+                limited,
+                cvar * 100.0,
+                cap * 100.0
+            );
+        }
+        size.min(limited)
+    }
+
+    /// Scale a proposed entry down to the risk the shared budget grants
+    ///
+    /// The trade's risk is `size * |entry_price - stop_price|`; the grant is
+    /// reserved for the UTC day of `now`. Returns `size` unchanged without a
+    /// budget or a usable stop.
+    pub fn apply_risk_budget(
+        &self,
+        entry_price: f64,
+        stop_price: f64,
+        size: f64,
+        now: DateTime<Utc>,
+    ) -> f64 {
+        let Some((budget, strategy)) = &self.risk_budget else {
+            return size;
+        };
+        let distance = (entry_price - stop_price).abs();
+        if size <= 0.0 || distance <= 0.0 {
+            return size;
+        }
+
+        let risk = size * distance;
+        let granted = budget.lock().unwrap_or_else(|e| e.into_inner()).request(
+            strategy,
+            risk,
+            self.current_capital,
+            now,
+        );
+        if granted < risk {
+            tracing::debug!(
+                "Risk budget limiting {} entry risk {:.2} -> {:.2}",
+                strategy,
+                risk,
+                granted
+            );
+        }
+        size * granted / risk
+    }
+}
+
+/// Trailing max drawdown from the equity high-water mark, as prop firms apply it
+///
+/// The floor sits `allowance` below the highest equity seen (unrealized P&L
+/// included) and only ever rises; with `lock_level` it stops rising there.
+/// Equity at or below the floor is a breach, which is permanent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrailingDrawdown {
+    /// Loss allowed below the high-water mark, in capital units
+    pub allowance: f64,
+    /// Highest the floor may trail to
+    pub lock_level: Option<f64>,
+    /// Fraction of the allowance used that triggers a warning
+    pub warn_at: f64,
+    pub high_water_mark: f64,
+    pub warned: bool,
+    pub breached: bool,
+}
+
+/// Threshold crossed by a [`TrailingDrawdown`] update
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "level", rename_all = "snake_case")]
+pub enum TrailingDrawdownEvent {
+    /// `used` of the allowance is gone
+    Warning { equity: f64, floor: f64, used: f64 },
+    /// Equity reached the floor; the account is halted
+    Breach { equity: f64, floor: f64 },
+}
+
+impl TrailingDrawdown {
+    pub fn new(config: &TrailingDrawdownConfig, initial_capital: f64) -> Self {
+        Self {
+            allowance: initial_capital * config.max_loss_pct,
+            lock_level: config.lock_at_initial.then_some(initial_capital),
+            warn_at: config.warn_at,
+            high_water_mark: initial_capital,
+            warned: false,
+            breached: false,
+        }
+    }
+
+    /// Equity level that breaches the rule
+    pub fn floor(&self) -> f64 {
+        let trailing = self.high_water_mark - self.allowance;
+        match self.lock_level {
+            Some(lock) => trailing.min(lock),
+            None => trailing,
+        }
+    }
+
+    /// Fraction of the allowance used at `equity` (1.0 = at the floor)
+    pub fn used(&self, equity: f64) -> f64 {
+        if self.allowance <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - (equity - self.floor()) / self.allowance).clamp(0.0, 1.0)
+    }
+
+    /// Track `equity`; returns the event for a newly crossed threshold
+    ///
+    /// The warning re-arms once usage falls back below `warn_at`.
+    pub fn update(&mut self, equity: f64) -> Option<TrailingDrawdownEvent> {
+        if self.breached {
+            return None;
+        }
+        self.high_water_mark = self.high_water_mark.max(equity);
+        let floor = self.floor();
+        if equity <= floor {
+            self.breached = true;
+            return Some(TrailingDrawdownEvent::Breach { equity, floor });
+        }
+        let used = self.used(equity);
+        if used < self.warn_at {
+            self.warned = false;
+            None
+        } else if !self.warned {
+            self.warned = true;
+            Some(TrailingDrawdownEvent::Warning {
+                equity,
+                floor,
+                used,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Expected move to a strategy's target versus the round-trip cost of the trade,
+/// both as fractions of the entry price
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EdgeCheck {
+    pub expected_move: f64,
+    pub cost: f64,
+}
+
+impl EdgeCheck {
+    pub fn new(entry_price: f64, target_price: f64, cost: f64) -> Self {
+        let expected_move = if entry_price > 0.0 && target_price.is_finite() {
+            (target_price - entry_price).abs() / entry_price
+        } else {
+            0.0
+        };
+        Self {
+            expected_move,
+            cost,
+        }
+    }
+
+    /// Expected move per unit of cost
+    pub fn ratio(&self) -> f64 {
+        if self.cost <= 0.0 {
+            return f64::INFINITY;
+        }
+        self.expected_move / self.cost
+    }
+
+    /// Whether the move covers `multiple` round trips
+    pub fn passes(&self, multiple: f64) -> bool {
+        self.expected_move >= multiple * self.cost
+    }
+}
+
+/// How lively a symbol's market is on its latest bar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketActivity {
+    /// ATR as a percent of the close (`None` until the ATR has warmed up)
+    pub atr_pct: Option<f64>,
+    /// Traded value (close × volume) over the 24 hours up to the latest bar
+    pub volume_24h: f64,
+}
+
+impl MarketActivity {
+    /// Measure the last bar of `candles`
+    pub fn measure(candles: &[Candle], atr_period: usize) -> Self {
+        let Some(last) = candles.last() else {
+            return Self {
+                atr_pct: None,
+                volume_24h: 0.0,
+            };
+        };
+        // Wilder smoothing forgets old bars quickly; a few periods of history is plenty
+        let tail = &candles[candles.len().saturating_sub(atr_period * 5)..];
+        let high: Vec<f64> = tail.iter().map(|c| c.high).collect();
+        let low: Vec<f64> = tail.iter().map(|c| c.low).collect();
+        let close: Vec<f64> = tail.iter().map(|c| c.close).collect();
+        let atr_pct = indicators::atr_percent(&high, &low, &close, atr_period)
+            .last()
+            .copied()
+            .flatten();
+
+        let since = last.datetime - chrono::Duration::hours(24);
+        let volume_24h = candles
+            .iter()
+            .rev()
+            .take_while(|c| c.datetime > since)
+            .map(|c| c.close * c.volume)
+            .sum();
+        Self {
+            atr_pct,
+            volume_24h,
+        }
+    }
+
+    /// Why the market counts as dead under `floors`, if it does
+    pub fn dead_reason(&self, floors: &ActivityFloors) -> Option<String> {
+        // Activity can't be vouched for until the ATR has enough history
+        match (floors.min_atr_pct, self.atr_pct) {
+            (Some(_), None) => return Some("ATR warming up".to_string()),
+            (Some(min), Some(atr_pct)) if atr_pct < min => {
+                return Some(format!("ATR {:.2}% < {:.2}%", atr_pct, min));
+            }
+            _ => {}
+        }
+        match floors.min_volume_24h {
+            Some(min) if self.volume_24h < min => {
+                Some(format!("24h volume {:.0} < {:.0}", self.volume_24h, min))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Convert `group -> [symbols]` into `symbol -> group`
+fn invert_groups(groups: &HashMap<String, Vec<String>>) -> HashMap<String, String> {
+    groups
+        .iter()
+        .flat_map(|(group, symbols)| symbols.iter().map(move |s| (s.clone(), group.clone())))
+        .collect()
 }
 
 #[cfg(test)]
@@ -439,6 +993,32 @@ mod tests {
         assert_eq!(rm.current_drawdown(), 0.0);
     }
 
+    #[test]
+    fn test_cash_flows_are_not_drawdown() {
+        let config = TrailingDrawdownConfig {
+            max_loss_pct: 0.05,
+            lock_at_initial: true,
+            warn_at: 0.8,
+            flatten: true,
+        };
+        let mut rm = RiskManagerConfig::default()
+            .with_capital(100_000.0)
+            .build()
+            .with_trailing_drawdown(Some(&config));
+        rm.update_capital(97_000.0);
+
+        // Withdrawing 40k leaves the 3k drawdown at 3k
+        rm.apply_cash_flow(-40_000.0);
+        assert_eq!(rm.current_capital, 57_000.0);
+        assert!((rm.current_drawdown() - 3_000.0 / 60_000.0).abs() < 1e-12);
+        assert_eq!(rm.trailing_drawdown.as_ref().unwrap().floor(), 55_000.0);
+
+        // A deposit moves the floor with it rather than reading as profit
+        rm.apply_cash_flow(10_000.0);
+        assert_eq!(rm.initial_capital, 70_000.0);
+        assert_eq!(rm.trailing_drawdown.as_ref().unwrap().floor(), 65_000.0);
+    }
+
     #[test]
     fn test_should_halt_trading() {
         let mut rm = RiskManager::new(
@@ -779,4 +1359,334 @@ mod tests {
         let size = rm.calculate_position_size_with_regime(entry, stop, &positions, 1.0);
         assert_eq!(size, 400.0);
     }
+
+    /// Test: Anti-martingale is off unless configured
+    #[test]
+    fn test_win_streak_disabled_by_default() {
+        let mut rm = RiskManagerConfig::default().build();
+        for _ in 0..10 {
+            rm.record_win();
+        }
+        assert_eq!(rm.win_streak_multiplier(), 1.0);
+    }
+
+    /// Test: Win streak scales size in steps, is bounded, and resets on a loss
+    #[test]
+    fn test_win_streak_scaling_bounded_and_reset() {
+        let mut rm = RiskManagerConfig::default()
+            .with_capital(100_000.0)
+            .with_risk_per_trade(0.02)
+            .with_max_position_pct(0.50)
+            .with_win_streak_scaling(2, 0.10, 1.3)
+            .build();
+
+        rm.record_win();
+        assert_eq!(rm.win_streak_multiplier(), 1.0);
+
+        rm.record_win();
+        assert!((rm.win_streak_multiplier() - 1.1).abs() < 1e-9);
+
+        let positions: Vec<&Position> = vec![];
+        let size = rm.calculate_position_size_with_regime(100.0, 95.0, &positions, 1.0);
+        assert!((size - 440.0).abs() < 1e-9);
+
+        for _ in 0..5 {
+            rm.record_win();
+        }
+        assert!((rm.win_streak_multiplier() - 1.3).abs() < 1e-9);
+
+        rm.record_loss();
+        assert_eq!(rm.win_streak_multiplier(), 1.0);
+        let size = rm.calculate_position_size_with_regime(100.0, 95.0, &positions, 1.0);
+        assert_eq!(size, 400.0);
+    }
+
+    /// Test: Replaying a streaky trade sequence, anti-martingale compounds more
+    /// during win streaks while never risking more than base risk × max multiplier
+    #[test]
+    fn test_win_streak_trade_sequence() {
+        // Outcomes in R-multiples: two win streaks separated by losses
+        let outcomes = [
+            2.0, 2.0, 2.0, 2.0, 2.0, -1.0, -1.0, 2.0, 2.0, 2.0, 2.0, -1.0,
+        ];
+
+        let replay = |mut rm: RiskManager| -> (f64, f64) {
+            let positions: Vec<&Position> = vec![];
+            let mut max_risk_pct: f64 = 0.0;
+            for &r in &outcomes {
+                let size = rm.calculate_position_size_with_regime(100.0, 95.0, &positions, 1.0);
+                let risk = size * 5.0;
+                max_risk_pct = max_risk_pct.max(risk / rm.current_capital);
+                let capital = rm.current_capital + risk * r;
+                rm.update_capital(capital);
+                if r > 0.0 {
+                    rm.record_win();
+                } else {
+                    rm.record_loss();
+                }
+            }
+            (rm.current_capital, max_risk_pct)
+        };
+
+        let base = RiskManagerConfig::default()
+            .with_capital(100_000.0)
+            .with_risk_per_trade(0.01)
+            .with_max_position_pct(1.0);
+        let (flat_equity, flat_risk) = replay(base.clone().build());
+        let (am_equity, am_risk) = replay(base.with_win_streak_scaling(2, 0.10, 1.5).build());
+
+        assert!(am_equity > flat_equity);
+        assert!((flat_risk - 0.01).abs() < 1e-9);
+        assert!(am_risk <= 0.015 + 1e-9);
+    }
+
+    fn grouped_risk_manager() -> RiskManager {
+        let groups = HashMap::from([
+            (
+                "L1".to_string(),
+                vec![
+                    "BTCINR".to_string(),
+                    "ETHINR".to_string(),
+                    "SOLINR".to_string(),
+                ],
+            ),
+            ("meme".to_string(), vec!["DOGEINR".to_string()]),
+        ]);
+        let caps = HashMap::from([("L1".to_string(), 0.40)]);
+        RiskManagerConfig::default()
+            .with_capital(100_000.0)
+            .with_group_limits(&groups, &caps)
+            .build()
+    }
+
+    fn position(symbol: &str, price: f64, qty: f64) -> Position {
+        use crate::oms::types::Fill;
+        use crate::Side;
+        use chrono::Utc;
+
+        let fill = Fill::from_f64(1, price, qty, Utc::now(), 0.0, true);
+        Position::from_fill(fill, crate::Symbol::new(symbol), Side::Buy)
+    }
+
+    /// Test: Group cap limits new entries to the group's remaining room
+    #[test]
+    fn test_group_exposure_cap_limits_size() {
+        let rm = grouped_risk_manager();
+
+        // 30% of capital already in L1 (BTC), cap is 40% -> 10,000 of room
+        let btc = position("BTCINR", 100.0, 300.0);
+        let size = rm.apply_group_limit("ETHINR", 100.0, 500.0, [&btc].into_iter());
+        assert!((size - 100.0).abs() < 1e-9);
+
+        // Group full -> no room
+        let eth = position("ETHINR", 100.0, 100.0);
+        let size = rm.apply_group_limit("SOLINR", 100.0, 50.0, [&btc, &eth].into_iter());
+        assert_eq!(size, 0.0);
+    }
+
+    /// Test: Uncapped groups and untagged symbols are not limited
+    #[test]
+    fn test_group_limit_ignores_uncapped() {
+        let rm = grouped_risk_manager();
+        let btc = position("BTCINR", 100.0, 400.0);
+
+        let size = rm.apply_group_limit("DOGEINR", 1.0, 5_000.0, [&btc].into_iter());
+        assert_eq!(size, 5_000.0);
+
+        let size = rm.apply_group_limit("XRPINR", 50.0, 300.0, [&btc].into_iter());
+        assert_eq!(size, 300.0);
+    }
+
+    /// Test: Exposure report groups positions and includes untagged symbols
+    #[test]
+    fn test_group_exposures_report() {
+        let rm = grouped_risk_manager();
+        let btc = position("BTCINR", 100.0, 200.0);
+        let eth = position("ETHINR", 50.0, 200.0);
+        let xrp = position("XRPINR", 10.0, 500.0);
+
+        let report = rm.group_exposures([&btc, &eth, &xrp].into_iter());
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].0, "L1");
+        assert!((report[0].1 - 0.30).abs() < 1e-9);
+        assert_eq!(report[1].0, UNGROUPED);
+        assert!((report[1].1 - 0.05).abs() < 1e-9);
+    }
+
+    /// Test: CVaR cap bounds notional so the 99% tail loss fits the budget
+    #[test]
+    fn test_cvar_cap_limits_size() {
+        let mut rm = RiskManagerConfig::default()
+            .with_capital(100_000.0)
+            .with_cvar_cap(Some(0.01))
+            .build();
+
+        // Not enough history yet
+        rm.trade_returns = vec![-0.05; CVAR_MIN_TRADES - 1];
+        assert_eq!(rm.apply_cvar_limit(100.0, 1_000.0), 1_000.0);
+
+        // Worst trade -10% -> 99% CVaR 10%; 1% of 100k / 10% = 10k notional
+        rm.trade_returns = (0..100).map(|i| (i as f64 - 10.0) / 100.0).collect();
+        let size = rm.apply_cvar_limit(100.0, 1_000.0);
+        assert!((size - 100.0).abs() < 1e-9);
+        assert_eq!(rm.apply_cvar_limit(100.0, 50.0), 50.0);
+
+        // Disabled cap never limits
+        let rm = rm.with_cvar_cap(None);
+        assert_eq!(rm.apply_cvar_limit(100.0, 1_000.0), 1_000.0);
+    }
+
+    /// Test: two managers sharing a budget cannot together exceed the global cap
+    #[test]
+    fn test_shared_risk_budget_scales_entries() {
+        use crate::config::RiskBudgetConfig;
+        use crate::risk_budget::RiskBudget;
+        use chrono::TimeZone;
+
+        let budget = RiskBudget::in_memory(RiskBudgetConfig {
+            global_pct: 0.03,
+            default_strategy_pct: 0.02,
+            ..Default::default()
+        })
+        .shared();
+        let a = RiskManagerConfig::default()
+            .with_capital(100_000.0)
+            .build()
+            .with_risk_budget(budget.clone(), "a");
+        let b = RiskManagerConfig::default()
+            .with_capital(100_000.0)
+            .build()
+            .with_risk_budget(budget, "b");
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap();
+
+        // 100 units risking 10 each = 1,000 per entry
+        assert_eq!(a.apply_risk_budget(100.0, 90.0, 100.0, now), 100.0);
+        assert_eq!(a.apply_risk_budget(100.0, 90.0, 150.0, now), 100.0);
+        assert_eq!(b.apply_risk_budget(100.0, 110.0, 200.0, now), 100.0);
+        assert_eq!(b.apply_risk_budget(100.0, 90.0, 100.0, now), 0.0);
+
+        // No budget or no stop distance leaves the size alone
+        let plain = RiskManagerConfig::default().build();
+        assert_eq!(plain.apply_risk_budget(100.0, 90.0, 100.0, now), 100.0);
+        assert_eq!(a.apply_risk_budget(100.0, 100.0, 100.0, now), 100.0);
+    }
+
+    /// Test: the trailing floor follows the high-water mark, locks, and breaches for good
+    #[test]
+    fn test_trailing_drawdown() {
+        let config = TrailingDrawdownConfig {
+            max_loss_pct: 0.05,
+            lock_at_initial: true,
+            warn_at: 0.8,
+            flatten: true,
+        };
+        let mut rm = RiskManagerConfig::default()
+            .with_capital(100_000.0)
+            .build()
+            .with_trailing_drawdown(Some(&config));
+        assert_eq!(rm.trailing_drawdown.as_ref().unwrap().floor(), 95_000.0);
+
+        // Floor trails up with unrealized gains...
+        assert_eq!(rm.check_trailing_drawdown(102_000.0), None);
+        assert_eq!(rm.trailing_drawdown.as_ref().unwrap().floor(), 97_000.0);
+        // ...but never past initial capital
+        assert_eq!(rm.check_trailing_drawdown(108_000.0), None);
+        assert_eq!(rm.trailing_drawdown.as_ref().unwrap().floor(), 100_000.0);
+
+        // 80% of the 5k allowance gone warns once, re-arms after recovering
+        assert!(matches!(
+            rm.check_trailing_drawdown(101_000.0),
+            Some(TrailingDrawdownEvent::Warning { .. })
+        ));
+        assert_eq!(rm.check_trailing_drawdown(100_900.0), None);
+        assert_eq!(rm.check_trailing_drawdown(104_000.0), None);
+        assert!(matches!(
+            rm.check_trailing_drawdown(100_500.0),
+            Some(TrailingDrawdownEvent::Warning { .. })
+        ));
+
+        // Still above the static 20% max drawdown, but the trailing floor is hit
+        assert!(!rm.should_halt_trading());
+        assert_eq!(
+            rm.check_trailing_drawdown(99_999.0),
+            Some(TrailingDrawdownEvent::Breach {
+                equity: 99_999.0,
+                floor: 100_000.0
+            })
+        );
+        assert!(rm.should_halt_trading());
+        assert_eq!(rm.check_trailing_drawdown(120_000.0), None);
+        assert!(rm.trailing_drawdown_breached());
+
+        // Without the lock the floor keeps trailing
+        let unlocked = TrailingDrawdownConfig {
+            lock_at_initial: false,
+            ..config
+        };
+        let mut td = TrailingDrawdown::new(&unlocked, 100_000.0);
+        td.update(110_000.0);
+        assert_eq!(td.floor(), 105_000.0);
+    }
+
+    /// Test: entries need a target move of `multiple` x round-trip cost
+    #[test]
+    fn test_edge_check() {
+        // 0.5% round trip; 2% target on a long, 0.8% on a short
+        let long = EdgeCheck::new(100.0, 102.0, 0.005);
+        assert!((long.ratio() - 4.0).abs() < 1e-9);
+        assert!(long.passes(2.0));
+        assert!(!long.passes(5.0));
+
+        let short = EdgeCheck::new(100.0, 99.2, 0.005);
+        assert!(!short.passes(2.0));
+
+        assert!(!EdgeCheck::new(100.0, f64::NAN, 0.005).passes(1.0));
+        assert!(EdgeCheck::new(100.0, 100.1, 0.0).ratio().is_infinite());
+    }
+
+    /// Test: quiet or thin markets fall under their activity floors
+    #[test]
+    fn test_market_activity_floors() {
+        use crate::config::ActivityFilterConfig;
+
+        let t0 = Utc::now();
+        // Hourly bars, 1% range around 100, 10 units traded per bar
+        let candles: Vec<Candle> = (0..48)
+            .map(|h| Candle {
+                datetime: t0 + chrono::Duration::hours(h),
+                open: 100.0,
+                high: 100.5,
+                low: 99.5,
+                close: 100.0,
+                volume: 10.0,
+            })
+            .collect();
+        let activity = MarketActivity::measure(&candles, 14);
+        assert!((activity.atr_pct.unwrap() - 1.0).abs() < 1e-9);
+        // Only the last 24 bars fall inside the window
+        assert!((activity.volume_24h - 24_000.0).abs() < 1e-9);
+        let warming = MarketActivity::measure(&candles[..5], 14);
+        assert!(warming.atr_pct.is_none());
+
+        let config: ActivityFilterConfig = serde_json::from_str(
+            r#"{ "min_atr_pct": 1.5, "min_volume_24h": 10000, "symbols": { "BTCINR": { "min_atr_pct": 0.5 } } }"#,
+        )
+        .unwrap();
+        let reason = activity.dead_reason(&config.floors_for("DOGEINR")).unwrap();
+        assert_eq!(reason, "ATR 1.00% < 1.50%");
+        // The symbol's own ATR floor, the default volume floor
+        assert!(activity.dead_reason(&config.floors_for("BTCINR")).is_none());
+        let busy_only = ActivityFloors {
+            min_atr_pct: None,
+            min_volume_24h: Some(50_000.0),
+        };
+        assert!(activity
+            .dead_reason(&busy_only)
+            .unwrap()
+            .starts_with("24h volume"));
+        assert_eq!(
+            warming.dead_reason(&config.floors_for("BTCINR")).unwrap(),
+            "ATR warming up"
+        );
+    }
 }
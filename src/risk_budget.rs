@@ -0,0 +1,317 @@
+//! Daily risk budget shared across strategies
+//!
+//! Every strategy sizes its own entries through its [`RiskManager`], which
+//! knows nothing about the other strategies trading the same account. With
+//! several of them running at once, each can stay within its own limits while
+//! together they put far more capital at risk than intended.
+//!
+//! A [`RiskBudget`] sits above them: before an entry is placed, the strategy
+//! requests the trade's initial risk (quantity x distance to stop) and the
+//! budget grants what fits within both the strategy's share and the global
+//! share of capital for the current UTC day. The entry is scaled down to the
+//! grant, or skipped when nothing is left. Usage resets when the day changes.
+//!
+//! Grants are recorded in a [`BudgetLedger`]. [`MemoryLedger`] serves
+//! strategies sharing one process (clone the [`SharedRiskBudget`] into each
+//! backtester); the native `SqliteLedger` lets separate live processes draw
+//! from one file.
+//!
+//! [`RiskManager`]: crate::risk::RiskManager
+
+use crate::config::RiskBudgetConfig;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+/// Budget handle shared by every strategy drawing from it
+pub type SharedRiskBudget = Arc<Mutex<RiskBudget>>;
+
+/// Risk to grant given what the strategy and the account already used today
+///
+/// `amount` is capped by the room left under both caps and never negative.
+pub fn grant(
+    amount: f64,
+    used_strategy: f64,
+    used_total: f64,
+    strategy_cap: f64,
+    global_cap: f64,
+) -> f64 {
+    amount
+        .min(strategy_cap - used_strategy)
+        .min(global_cap - used_total)
+        .max(0.0)
+}
+
+/// Where granted risk is recorded
+pub trait BudgetLedger: Send + Debug {
+    /// Atomically grant up to `amount` for `strategy` on `day` and record it
+    fn reserve(
+        &mut self,
+        day: NaiveDate,
+        strategy: &str,
+        amount: f64,
+        strategy_cap: f64,
+        global_cap: f64,
+    ) -> Result<f64>;
+
+    /// Risk granted to each strategy on `day`
+    fn usage(&self, day: NaiveDate) -> Result<HashMap<String, f64>>;
+}
+
+/// In-process ledger that forgets everything when the day changes
+#[derive(Debug, Default)]
+pub struct MemoryLedger {
+    day: Option<NaiveDate>,
+    used: HashMap<String, f64>,
+}
+
+impl BudgetLedger for MemoryLedger {
+    fn reserve(
+        &mut self,
+        day: NaiveDate,
+        strategy: &str,
+        amount: f64,
+        strategy_cap: f64,
+        global_cap: f64,
+    ) -> Result<f64> {
+        if self.day != Some(day) {
+            self.day = Some(day);
+            self.used.clear();
+        }
+        let used_strategy = self.used.get(strategy).copied().unwrap_or(0.0);
+        let used_total: f64 = self.used.values().sum();
+        let granted = grant(amount, used_strategy, used_total, strategy_cap, global_cap);
+        if granted > 0.0 {
+            *self.used.entry(strategy.to_string()).or_default() += granted;
+        }
+        Ok(granted)
+    }
+
+    fn usage(&self, day: NaiveDate) -> Result<HashMap<String, f64>> {
+        Ok(if self.day == Some(day) {
+            self.used.clone()
+        } else {
+            HashMap::new()
+        })
+    }
+}
+
+/// Allocator granting per-strategy and global daily risk
+#[derive(Debug)]
+pub struct RiskBudget {
+    config: RiskBudgetConfig,
+    ledger: Box<dyn BudgetLedger>,
+}
+
+impl RiskBudget {
+    pub fn new(config: RiskBudgetConfig, ledger: Box<dyn BudgetLedger>) -> Self {
+        Self { config, ledger }
+    }
+
+    /// Budget kept in memory
+    pub fn in_memory(config: RiskBudgetConfig) -> Self {
+        Self::new(config, Box::<MemoryLedger>::default())
+    }
+
+    /// Budget recorded in the configured ledger file, in memory without one
+    #[cfg(feature = "native")]
+    pub fn open(config: RiskBudgetConfig) -> Result<Self> {
+        Ok(match config.ledger.clone() {
+            Some(path) => Self::new(config, Box::new(SqliteLedger::open(&path)?)),
+            None => Self::in_memory(config),
+        })
+    }
+
+    /// Wrap for sharing across strategies
+    pub fn shared(self) -> SharedRiskBudget {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// Daily share of capital `strategy` may put at risk
+    pub fn strategy_pct(&self, strategy: &str) -> f64 {
+        self.config
+            .strategies
+            .get(strategy)
+            .copied()
+            .unwrap_or(self.config.default_strategy_pct)
+    }
+
+    /// Request `amount` of risk for an entry; returns the amount granted
+    ///
+    /// Caps are fractions of `capital`. A ledger error grants nothing, so a
+    /// broken ledger can only make trading more conservative.
+    pub fn request(
+        &mut self,
+        strategy: &str,
+        amount: f64,
+        capital: f64,
+        now: DateTime<Utc>,
+    ) -> f64 {
+        if amount <= 0.0 {
+            return 0.0;
+        }
+        let strategy_cap = capital * self.strategy_pct(strategy);
+        let global_cap = capital * self.config.global_pct;
+        match self
+            .ledger
+            .reserve(now.date_naive(), strategy, amount, strategy_cap, global_cap)
+        {
+            Ok(granted) => granted,
+            Err(e) => {
+                tracing::warn!("Risk budget ledger error for {}: {:#}", strategy, e);
+                0.0
+            }
+        }
+    }
+
+    /// Risk granted to each strategy on the day of `now`
+    pub fn usage(&self, now: DateTime<Utc>) -> Result<HashMap<String, f64>> {
+        self.ledger.usage(now.date_naive())
+    }
+}
+
+#[cfg(feature = "native")]
+pub use sqlite::SqliteLedger;
+
+#[cfg(feature = "native")]
+mod sqlite {
+    use super::{grant, BudgetLedger};
+    use anyhow::{Context, Result};
+    use chrono::NaiveDate;
+    use rusqlite::{params, Connection, TransactionBehavior};
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    /// Ledger in a SQLite file, shared by every process that opens it
+    ///
+    /// Each reservation runs in an immediate transaction, so two processes
+    /// can never both spend the last of the budget.
+    #[derive(Debug)]
+    pub struct SqliteLedger {
+        conn: Connection,
+    }
+
+    impl SqliteLedger {
+        pub fn open(path: &str) -> Result<Self> {
+            if let Some(parent) = std::path::Path::new(path).parent() {
+                if !parent.as_os_str().is_empty() {
+                    std::fs::create_dir_all(parent)?;
+                }
+            }
+            let conn = Connection::open(path)
+                .with_context(|| format!("Failed to open risk budget ledger {}", path))?;
+            conn.busy_timeout(Duration::from_secs(5))?;
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS risk_budget (
+                    day TEXT NOT NULL,
+                    strategy TEXT NOT NULL,
+                    amount REAL NOT NULL,
+                    PRIMARY KEY (day, strategy)
+                )",
+            )?;
+            Ok(Self { conn })
+        }
+    }
+
+    impl BudgetLedger for SqliteLedger {
+        fn reserve(
+            &mut self,
+            day: NaiveDate,
+            strategy: &str,
+            amount: f64,
+            strategy_cap: f64,
+            global_cap: f64,
+        ) -> Result<f64> {
+            let day = day.to_string();
+            let tx = self
+                .conn
+                .transaction_with_behavior(TransactionBehavior::Immediate)?;
+            let (used_strategy, used_total): (f64, f64) = tx.query_row(
+                "SELECT COALESCE(SUM(CASE WHEN strategy = ?2 THEN amount END), 0),
+                        COALESCE(SUM(amount), 0)
+                 FROM risk_budget WHERE day = ?1",
+                params![day, strategy],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )?;
+            let granted = grant(amount, used_strategy, used_total, strategy_cap, global_cap);
+            if granted > 0.0 {
+                tx.execute(
+                    "INSERT INTO risk_budget (day, strategy, amount) VALUES (?1, ?2, ?3)
+                     ON CONFLICT(day, strategy) DO UPDATE SET amount = amount + ?3",
+                    params![day, strategy, granted],
+                )?;
+            }
+            tx.commit()?;
+            Ok(granted)
+        }
+
+        fn usage(&self, day: NaiveDate) -> Result<HashMap<String, f64>> {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT strategy, amount FROM risk_budget WHERE day = ?1")?;
+            let rows = stmt.query_map(params![day.to_string()], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+            })?;
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn config() -> RiskBudgetConfig {
+        RiskBudgetConfig {
+            global_pct: 0.05,
+            default_strategy_pct: 0.03,
+            strategies: HashMap::from([("grid".to_string(), 0.01)]),
+            ledger: None,
+        }
+    }
+
+    #[test]
+    fn test_grants_respect_strategy_and_global_caps() {
+        let mut budget = RiskBudget::in_memory(config());
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap();
+
+        // grid is capped at 1% of 100k
+        assert_eq!(budget.request("grid", 600.0, 100_000.0, now), 600.0);
+        assert_eq!(budget.request("grid", 600.0, 100_000.0, now), 400.0);
+        assert_eq!(budget.request("grid", 100.0, 100_000.0, now), 0.0);
+
+        // Others get 3% each, but only 4% is left globally
+        assert_eq!(budget.request("trend", 5_000.0, 100_000.0, now), 3_000.0);
+        assert_eq!(budget.request("breakout", 5_000.0, 100_000.0, now), 1_000.0);
+        assert_eq!(budget.request("breakout", 5_000.0, 100_000.0, now), 0.0);
+
+        let usage = budget.usage(now).unwrap();
+        assert_eq!(usage.values().sum::<f64>(), 5_000.0);
+
+        // A new UTC day starts from zero
+        let tomorrow = now + chrono::Duration::days(1);
+        assert!(budget.usage(tomorrow).unwrap().is_empty());
+        assert_eq!(budget.request("grid", 600.0, 100_000.0, tomorrow), 600.0);
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn test_sqlite_ledger_is_shared_between_handles() {
+        let file = std::env::temp_dir().join(format!("risk_budget_{}.db", std::process::id()));
+        std::fs::remove_file(&file).ok();
+        let path = file.to_str().unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap();
+
+        let mut a = RiskBudget::new(config(), Box::new(SqliteLedger::open(path).unwrap()));
+        let mut b = RiskBudget::new(config(), Box::new(SqliteLedger::open(path).unwrap()));
+        assert_eq!(a.request("trend", 3_000.0, 100_000.0, now), 3_000.0);
+        assert_eq!(b.request("breakout", 3_000.0, 100_000.0, now), 2_000.0);
+        assert_eq!(a.request("grid", 500.0, 100_000.0, now), 0.0);
+        assert_eq!(b.usage(now).unwrap().get("trend"), Some(&3_000.0));
+        drop((a, b));
+        std::fs::remove_file(&file).ok();
+    }
+}
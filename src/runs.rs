@@ -0,0 +1,435 @@
+//! Local run registry
+//!
+//! Every backtest and optimization run is recorded under
+//! `<results_dir>/runs/`:
+//!
+//! ```text
+//! results/runs/
+//! ├── index.json                  # RunRecord per run, oldest first
+//! └── 20240315-142233-9f3a1c/     # Artifacts for one run
+//!     └── result.json
+//! ```
+//!
+//! The `runs` command lists, shows and deletes entries.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::optimizer::OptimizationResult;
+use crate::stability::Observation;
+use crate::{Config, PerformanceMetrics};
+
+/// Subdirectory of `results_dir` holding the registry
+pub const RUNS_DIR: &str = "runs";
+const INDEX_FILE: &str = "index.json";
+
+/// What produced a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunKind {
+    Backtest,
+    Optimize,
+}
+
+impl RunKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunKind::Backtest => "backtest",
+            RunKind::Optimize => "optimize",
+        }
+    }
+}
+
+/// Headline metrics kept in the index (best result for optimize runs)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub total_return: f64,
+    pub sharpe_ratio: f64,
+    pub max_drawdown: f64,
+    pub win_rate: f64,
+    pub total_trades: usize,
+    /// Parameter combinations evaluated (optimize only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub combinations: Option<usize>,
+}
+
+impl From<&PerformanceMetrics> for RunSummary {
+    fn from(m: &PerformanceMetrics) -> Self {
+        Self {
+            total_return: m.total_return,
+            sharpe_ratio: m.sharpe_ratio,
+            max_drawdown: m.max_drawdown,
+            win_rate: m.win_rate,
+            total_trades: m.total_trades,
+            combinations: None,
+        }
+    }
+}
+
+impl From<&OptimizationResult> for RunSummary {
+    fn from(r: &OptimizationResult) -> Self {
+        Self {
+            total_return: r.total_return,
+            sharpe_ratio: r.sharpe_ratio,
+            max_drawdown: r.max_drawdown,
+            win_rate: r.win_rate,
+            total_trades: r.total_trades,
+            combinations: None,
+        }
+    }
+}
+
+/// One registry entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub kind: RunKind,
+    pub created_at: DateTime<Utc>,
+    pub config_path: String,
+    /// Hash of the effective config (after CLI overrides)
+    pub config_hash: String,
+    pub strategy: String,
+    pub symbols: Vec<String>,
+    pub timeframe: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_start: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_end: Option<DateTime<Utc>>,
+    /// SHA-256 of each input series, keyed `SYMBOL_tf` (see [`crate::dataset`])
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub data_hashes: BTreeMap<String, String>,
+    pub summary: RunSummary,
+    /// Numeric strategy parameters (optimize runs keep per-combination params in their artifact)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, f64>,
+    /// Files belonging to the run (log file, result JSON)
+    #[serde(default)]
+    pub artifacts: Vec<String>,
+}
+
+impl RunRecord {
+    /// Start a record for `config`; the id is derived from the time and config hash
+    pub fn new(kind: RunKind, config_path: &str, config: &Config, summary: RunSummary) -> Self {
+        let created_at = Utc::now();
+        let config_hash = config_hash(config);
+        Self {
+            id: format!(
+                "{}-{}",
+                created_at.format("%Y%m%d-%H%M%S"),
+                &config_hash[..6]
+            ),
+            name: None,
+            kind,
+            created_at,
+            config_path: config_path.to_string(),
+            config_hash,
+            strategy: config.strategy_name(),
+            symbols: config.trading.symbols.clone(),
+            timeframe: config.timeframe(),
+            data_start: None,
+            data_end: None,
+            data_hashes: BTreeMap::new(),
+            summary,
+            params: strategy_params(config),
+            artifacts: Vec::new(),
+        }
+    }
+
+    pub fn with_name(mut self, name: Option<String>) -> Self {
+        self.name = name;
+        self
+    }
+
+    pub fn with_data_range(
+        mut self,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.data_start = start;
+        self.data_end = end;
+        self
+    }
+
+    pub fn with_data_hashes(mut self, hashes: BTreeMap<String, String>) -> Self {
+        self.data_hashes = hashes;
+        self
+    }
+
+    pub fn with_artifact(mut self, path: impl AsRef<Path>) -> Self {
+        self.artifacts.push(path.as_ref().display().to_string());
+        self
+    }
+
+    /// Name if set, otherwise id
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+
+    /// Data period label used to group runs for stability analysis
+    pub fn period(&self) -> String {
+        match (self.data_start, self.data_end) {
+            (Some(start), Some(end)) => {
+                format!("{}..{}", start.format("%Y-%m-%d"), end.format("%Y-%m-%d"))
+            }
+            _ => self.id.clone(),
+        }
+    }
+}
+
+/// Name and log file passed from the CLI to a run
+#[derive(Debug, Clone, Default)]
+pub struct RunMeta {
+    pub name: Option<String>,
+    pub log_file: Option<PathBuf>,
+}
+
+/// Numeric fields of the `strategy` section
+fn strategy_params(config: &Config) -> BTreeMap<String, f64> {
+    config
+        .strategy
+        .as_object()
+        .map(|obj| {
+            obj.iter()
+                .filter_map(|(k, v)| v.as_f64().map(|n| (k.clone(), n)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Stable hash of a config: SHA-256 of its canonical JSON (sorted keys), hex
+pub fn config_hash(config: &Config) -> String {
+    // Going through Value sorts object keys, so HashMap ordering doesn't leak in
+    let canonical = serde_json::to_value(config)
+        .map(|v| v.to_string())
+        .unwrap_or_default();
+    hex::encode(Sha256::digest(canonical.as_bytes()))
+}
+
+/// Registry rooted at `<results_dir>/runs`
+#[derive(Debug, Clone)]
+pub struct RunRegistry {
+    root: PathBuf,
+}
+
+impl RunRegistry {
+    pub fn new(results_dir: impl AsRef<Path>) -> Self {
+        Self {
+            root: results_dir.as_ref().join(RUNS_DIR),
+        }
+    }
+
+    /// Artifact directory for a run
+    pub fn run_dir(&self, id: &str) -> PathBuf {
+        self.root.join(id)
+    }
+
+    /// All runs, oldest first
+    pub fn list(&self) -> Result<Vec<RunRecord>> {
+        let path = self.root.join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let contents = fs::read_to_string(&path).context("Failed to read run index")?;
+        serde_json::from_str(&contents).context("Failed to parse run index")
+    }
+
+    fn save(&self, runs: &[RunRecord]) -> Result<()> {
+        fs::create_dir_all(&self.root)?;
+        let path = self.root.join(INDEX_FILE);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_string_pretty(runs)?)?;
+        fs::rename(&tmp, &path).context("Failed to write run index")
+    }
+
+    /// Write a JSON artifact into the run's directory and return its path
+    pub fn write_artifact<T: Serialize>(
+        &self,
+        id: &str,
+        file_name: &str,
+        value: &T,
+    ) -> Result<PathBuf> {
+        let dir = self.run_dir(id);
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(file_name);
+        fs::write(&path, serde_json::to_string_pretty(value)?)
+            .context(format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Every evaluated parameter set of every run of `strategy`.
+    ///
+    /// Optimize runs contribute all combinations from their `results.json`;
+    /// backtest runs contribute their own params and summary.
+    pub fn observations(&self, strategy: &str) -> Result<Vec<Observation>> {
+        let mut out = Vec::new();
+        for run in self.list()?.iter().filter(|r| r.strategy == strategy) {
+            let period = run.period();
+            match run.kind {
+                RunKind::Optimize => {
+                    let path = self.run_dir(&run.id).join("results.json");
+                    if !path.exists() {
+                        continue;
+                    }
+                    let contents = fs::read_to_string(&path)
+                        .context(format!("Failed to read {}", path.display()))?;
+                    let results: Vec<OptimizationResult> = serde_json::from_str(&contents)
+                        .context(format!("Failed to parse {}", path.display()))?;
+                    out.extend(results.into_iter().map(|r| Observation {
+                        period: period.clone(),
+                        params: r.params.into_iter().collect(),
+                        sharpe_ratio: r.sharpe_ratio,
+                        total_return: r.total_return,
+                    }));
+                }
+                RunKind::Backtest if !run.params.is_empty() => out.push(Observation {
+                    period,
+                    params: run.params.clone(),
+                    sharpe_ratio: run.summary.sharpe_ratio,
+                    total_return: run.summary.total_return,
+                }),
+                RunKind::Backtest => {}
+            }
+        }
+        Ok(out)
+    }
+
+    /// Append a run to the index
+    pub fn register(&self, record: RunRecord) -> Result<RunRecord> {
+        let mut runs = self.list()?;
+        if runs.iter().any(|r| r.id == record.id) {
+            bail!("Run {} already registered", record.id);
+        }
+        runs.push(record.clone());
+        self.save(&runs)?;
+        Ok(record)
+    }
+
+    /// Find a run by id, unique id prefix, or name (latest run with that name)
+    pub fn find(&self, key: &str) -> Result<RunRecord> {
+        let runs = self.list()?;
+        if let Some(run) = runs.iter().find(|r| r.id == key) {
+            return Ok(run.clone());
+        }
+        if let Some(run) = runs.iter().rev().find(|r| r.name.as_deref() == Some(key)) {
+            return Ok(run.clone());
+        }
+        let matches: Vec<&RunRecord> = runs.iter().filter(|r| r.id.starts_with(key)).collect();
+        match matches.as_slice() {
+            [run] => Ok((*run).clone()),
+            [] => bail!("No run matches '{}'", key),
+            _ => bail!("'{}' matches {} runs, use a longer id", key, matches.len()),
+        }
+    }
+
+    /// Remove a run from the index and delete its artifact directory.
+    ///
+    /// Artifacts outside the run directory (log files) are left in place.
+    pub fn delete(&self, key: &str) -> Result<RunRecord> {
+        let run = self.find(key)?;
+        let mut runs = self.list()?;
+        runs.retain(|r| r.id != run.id);
+        self.save(&runs)?;
+
+        let dir = self.run_dir(&run.id);
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        Ok(run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: &str, name: Option<&str>) -> RunRecord {
+        RunRecord {
+            id: id.to_string(),
+            name: name.map(String::from),
+            kind: RunKind::Backtest,
+            created_at: Utc::now(),
+            config_path: "configs/test.json".to_string(),
+            config_hash: "abc".to_string(),
+            strategy: "volatility_regime".to_string(),
+            symbols: vec!["BTCINR".to_string()],
+            timeframe: "1d".to_string(),
+            data_start: None,
+            data_end: None,
+            data_hashes: BTreeMap::new(),
+            summary: RunSummary::default(),
+            params: BTreeMap::new(),
+            artifacts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_register_find_delete() {
+        let dir = std::env::temp_dir().join(format!("runs_registry_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let registry = RunRegistry::new(&dir);
+        assert!(registry.list().unwrap().is_empty());
+
+        registry
+            .register(record("20240101-000000-aaaaaa", Some("baseline")))
+            .unwrap();
+        registry
+            .register(record("20240101-000100-bbbbbb", None))
+            .unwrap();
+        assert!(registry
+            .register(record("20240101-000100-bbbbbb", None))
+            .is_err());
+
+        let artifact = registry
+            .write_artifact("20240101-000000-aaaaaa", "result.json", &vec![1, 2, 3])
+            .unwrap();
+        assert!(artifact.exists());
+
+        assert_eq!(
+            registry.find("baseline").unwrap().id,
+            "20240101-000000-aaaaaa"
+        );
+        assert_eq!(
+            registry.find("20240101-0001").unwrap().id,
+            "20240101-000100-bbbbbb"
+        );
+        assert!(registry.find("20240101").is_err()); // ambiguous prefix
+        assert!(registry.find("nope").is_err());
+
+        let mut optimize = record("20240102-000000-cccccc", None);
+        optimize.kind = RunKind::Optimize;
+        registry.register(optimize).unwrap();
+        let results = vec![OptimizationResult {
+            params: [("ema_fast".to_string(), 8.0)].into_iter().collect(),
+            sharpe_ratio: 1.2,
+            total_return: 15.0,
+            max_drawdown: 5.0,
+            win_rate: 50.0,
+            total_trades: 10,
+            calmar_ratio: 1.0,
+            profit_factor: 1.5,
+            expectancy: 0.1,
+            truncated: None,
+        }];
+        registry
+            .write_artifact("20240102-000000-cccccc", "results.json", &results)
+            .unwrap();
+        let observations = registry.observations("volatility_regime").unwrap();
+        assert_eq!(observations.len(), 1); // backtests without params are skipped
+        assert_eq!(observations[0].params["ema_fast"], 8.0);
+        assert_eq!(observations[0].period, "20240102-000000-cccccc");
+
+        let deleted = registry.delete("baseline").unwrap();
+        assert_eq!(deleted.label(), "baseline");
+        assert!(!artifact.exists());
+        assert_eq!(registry.list().unwrap().len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
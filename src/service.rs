@@ -0,0 +1,283 @@
+//! Running `live` under a service manager
+//!
+//! - Exit codes: [`EXIT_CONFIG`] for errors a restart cannot fix (bad config,
+//!   missing acknowledgment, another instance running), [`EXIT_TRANSIENT`] for
+//!   everything else. The systemd unit from [`SystemdUnit`] restarts on
+//!   failure but not on `EXIT_CONFIG`.
+//! - [`PidFile`] refuses to start a second instance on the same state and
+//!   removes itself on exit.
+//! - [`shutdown_signal`] resolves on Ctrl+C, SIGTERM or SIGHUP (Unix) and on
+//!   console close/logoff/shutdown events (Windows).
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Clean shutdown
+pub const EXIT_OK: i32 = 0;
+/// Failure worth a restart (network, exchange, I/O); `EX_TEMPFAIL`
+pub const EXIT_TRANSIENT: i32 = 75;
+/// Failure a restart will repeat; `EX_CONFIG`
+pub const EXIT_CONFIG: i32 = 78;
+
+/// Context marking an error as fatal to restarts
+///
+/// ```
+/// use anyhow::Context;
+/// use crypto_strategies::service::{exit_code, ConfigFault, EXIT_CONFIG, EXIT_TRANSIENT};
+///
+/// let bad: anyhow::Result<()> = Err(anyhow::anyhow!("unknown strategy")).context(ConfigFault);
+/// assert_eq!(exit_code(&bad.unwrap_err()), EXIT_CONFIG);
+/// assert_eq!(exit_code(&anyhow::anyhow!("connection reset")), EXIT_TRANSIENT);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigFault;
+
+impl fmt::Display for ConfigFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "configuration error (not retrying)")
+    }
+}
+
+/// Process exit code for an error that ended the run
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    if err.downcast_ref::<ConfigFault>().is_some() {
+        EXIT_CONFIG
+    } else {
+        EXIT_TRANSIENT
+    }
+}
+
+/// PID file held for the life of the process
+#[derive(Debug)]
+pub struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    /// Write this process's PID to `path`
+    ///
+    /// Fails with [`ConfigFault`] while the PID in an existing file belongs to a
+    /// running process; a stale file is replaced.
+    pub fn acquire(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if let Some(pid) = read_pid(&path) {
+            if pid != std::process::id() && is_running(pid) {
+                return Err(anyhow::anyhow!(
+                    "Another instance (PID {}) holds {}; stop it or remove the file",
+                    pid,
+                    path.display()
+                ))
+                .context(ConfigFault);
+            }
+            tracing::warn!("Replacing stale PID file {} (PID {})", path.display(), pid);
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, format!("{}\n", std::process::id()))
+            .with_context(|| format!("Failed to write PID file {}", path.display()))?;
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        // Only remove our own file; a replacement instance may have taken over
+        if read_pid(&self.path) == Some(std::process::id()) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(target_os = "linux")]
+fn is_running(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+/// Without a portable liveness check, an existing PID file is assumed live
+#[cfg(not(target_os = "linux"))]
+fn is_running(_pid: u32) -> bool {
+    true
+}
+
+/// Wait for the first shutdown request; returns its name for logging
+pub async fn shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match (
+            signal(SignalKind::terminate()),
+            signal(SignalKind::hangup()),
+        ) {
+            (Ok(mut term), Ok(mut hup)) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+                _ = term.recv() => "SIGTERM",
+                _ = hup.recv() => "SIGHUP",
+            },
+            _ => {
+                tokio::signal::ctrl_c().await.ok();
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(windows)]
+    {
+        use tokio::signal::windows;
+        match (
+            windows::ctrl_break(),
+            windows::ctrl_close(),
+            windows::ctrl_shutdown(),
+        ) {
+            (Ok(mut brk), Ok(mut close), Ok(mut shutdown)) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => "Ctrl+C",
+                _ = brk.recv() => "Ctrl+Break",
+                _ = close.recv() => "console close",
+                _ = shutdown.recv() => "system shutdown",
+            },
+            _ => {
+                tokio::signal::ctrl_c().await.ok();
+                "Ctrl+C"
+            }
+        }
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        tokio::signal::ctrl_c().await.ok();
+        "Ctrl+C"
+    }
+}
+
+/// Resolve once `flag` is set (checked every 250 ms)
+///
+/// Lets long sleeps between polls end as soon as shutdown is requested.
+pub async fn until_set(flag: &AtomicBool) {
+    while !flag.load(Ordering::Relaxed) {
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+}
+
+/// systemd unit for `live --daemon`
+#[derive(Debug, Clone)]
+pub struct SystemdUnit {
+    /// Unit name without `.service`
+    pub name: String,
+    pub executable: PathBuf,
+    pub working_dir: PathBuf,
+    pub config: String,
+    pub state_db: String,
+    /// Real money (`--live --confirm`) instead of paper
+    pub live: bool,
+    /// Account to run as (`User=`), if any
+    pub user: Option<String>,
+    /// Optional `EnvironmentFile=` (API keys)
+    pub env_file: Option<PathBuf>,
+}
+
+impl SystemdUnit {
+    pub fn render(&self) -> String {
+        let mode = if self.live {
+            "--live --confirm"
+        } else {
+            "--paper"
+        };
+        let mut out = String::new();
+        out.push_str("[Unit]\n");
+        out.push_str(&format!(
+            "Description=crypto-strategies live trader ({})\n",
+            self.config
+        ));
+        out.push_str("Wants=network-online.target\nAfter=network-online.target\n");
+        // Give up after repeated fast failures instead of hammering the exchange
+        out.push_str("StartLimitIntervalSec=600\nStartLimitBurst=5\n\n");
+
+        out.push_str("[Service]\nType=simple\n");
+        if let Some(user) = &self.user {
+            out.push_str(&format!("User={}\n", user));
+        }
+        out.push_str(&format!(
+            "WorkingDirectory={}\n",
+            self.working_dir.display()
+        ));
+        if let Some(env_file) = &self.env_file {
+            out.push_str(&format!("EnvironmentFile={}\n", env_file.display()));
+        }
+        out.push_str(&format!(
+            "ExecStart={} live --daemon {} --config {} --state-db {}\n",
+            self.executable.display(),
+            mode,
+            self.config,
+            self.state_db
+        ));
+        out.push_str("Restart=on-failure\nRestartSec=30\n");
+        out.push_str(&format!(
+            "# Exit {} = configuration error: fix it, then `systemctl restart {}`\n",
+            EXIT_CONFIG, self.name
+        ));
+        out.push_str(&format!("RestartPreventExitStatus={}\n", EXIT_CONFIG));
+        // SIGTERM starts a graceful shutdown; allow one cycle to finish
+        out.push_str("KillSignal=SIGTERM\nTimeoutStopSec=120\n\n");
+
+        out.push_str("[Install]\nWantedBy=multi-user.target\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pid_file_refuses_live_owner_and_cleans_up() {
+        let path = std::env::temp_dir().join(format!("cs-pid-{}.pid", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        {
+            let pid = PidFile::acquire(&path).unwrap();
+            assert_eq!(read_pid(pid.path()), Some(std::process::id()));
+        }
+        assert!(!path.exists());
+
+        // PID 1 is always running on Linux
+        if cfg!(target_os = "linux") {
+            std::fs::write(&path, "1\n").unwrap();
+            let err = PidFile::acquire(&path).unwrap_err();
+            assert_eq!(exit_code(&err), EXIT_CONFIG);
+            assert!(format!("{:#}", err).contains("PID 1"));
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_systemd_unit_skips_restart_on_config_errors() {
+        let unit = SystemdUnit {
+            name: "cs-live".to_string(),
+            executable: PathBuf::from("/opt/cs/crypto-strategies"),
+            working_dir: PathBuf::from("/opt/cs"),
+            config: "configs/live.json".to_string(),
+            state_db: "state/state.db".to_string(),
+            live: false,
+            user: Some("trader".to_string()),
+            env_file: None,
+        }
+        .render();
+
+        assert!(unit.contains(
+            "ExecStart=/opt/cs/crypto-strategies live --daemon --paper --config configs/live.json"
+        ));
+        assert!(unit.contains("Restart=on-failure"));
+        assert!(unit.contains("RestartPreventExitStatus=78"));
+        assert!(unit.contains("User=trader"));
+        assert!(!unit.contains("EnvironmentFile"));
+    }
+}
@@ -0,0 +1,384 @@
+//! Signal heatmap: where and when a strategy wants to enter
+//!
+//! Runs a strategy's signal generator bar by bar over a series while flat,
+//! with no execution, and counts the bars on which it asks for an entry.
+//! Counts are kept per symbol, timeframe and calendar period so the
+//! `signal-heatmap` command can show which markets and timeframes a strategy
+//! is naturally active on, and when.
+//!
+//! A bar counts once however many orders the strategy emits on it (grid
+//! strategies ladder several); `long`/`short` record the sides requested.
+
+use chrono::{DateTime, Datelike, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::oms::StrategyContext;
+use crate::strategies::Strategy;
+use crate::{MultiTimeframeCandles, MultiTimeframeData, Side, Symbol};
+
+/// Candles visible to the strategy per bar (same window as the backtester)
+pub const SCAN_LOOKBACK: usize = 300;
+
+/// Shades from no signals to the busiest cell
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Signal bars out of bars scanned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SignalCounts {
+    pub bars: usize,
+    pub signals: usize,
+    pub long: usize,
+    pub short: usize,
+}
+
+impl SignalCounts {
+    /// Signal bars per 100 bars scanned
+    pub fn per_100_bars(&self) -> f64 {
+        if self.bars == 0 {
+            return 0.0;
+        }
+        self.signals as f64 * 100.0 / self.bars as f64
+    }
+
+    fn add(&mut self, other: &SignalCounts) {
+        self.bars += other.bars;
+        self.signals += other.signals;
+        self.long += other.long;
+        self.short += other.short;
+    }
+}
+
+/// Calendar bucket for the "when" view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Period {
+    Month,
+    #[default]
+    Quarter,
+    Year,
+}
+
+impl std::str::FromStr for Period {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "month" => Ok(Period::Month),
+            "quarter" => Ok(Period::Quarter),
+            "year" => Ok(Period::Year),
+            other => anyhow::bail!("Unknown period '{}' (month, quarter, year)", other),
+        }
+    }
+}
+
+impl Period {
+    /// Sortable label, e.g. `2024-03`, `2024-Q1`, `2024`
+    pub fn label(&self, at: DateTime<Utc>) -> String {
+        match self {
+            Period::Month => format!("{}-{:02}", at.year(), at.month()),
+            Period::Quarter => format!("{}-Q{}", at.year(), (at.month() - 1) / 3 + 1),
+            Period::Year => at.year().to_string(),
+        }
+    }
+}
+
+/// One bar on which the strategy asked to enter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SignalBar {
+    pub datetime: DateTime<Utc>,
+    pub long: bool,
+    pub short: bool,
+}
+
+/// Entry signals `strategy` emits on `data` while flat, skipping the first `warmup` bars.
+///
+/// Higher timeframes in `data` are offered to the strategy up to each primary bar.
+pub fn scan_signals(
+    strategy: &mut dyn Strategy,
+    symbol: &Symbol,
+    data: &MultiTimeframeData,
+    capital: f64,
+    warmup: usize,
+) -> Vec<SignalBar> {
+    let primary_tf = data.primary_timeframe().to_string();
+    let candles = data.primary();
+    let is_mtf = !strategy.required_timeframes().is_empty();
+    let mut signals = Vec::new();
+
+    for (idx, candle) in candles.iter().enumerate() {
+        let slice = &candles[idx.saturating_sub(SCAN_LOOKBACK - 1)..=idx];
+        let mut mtf_view;
+        let ctx = if is_mtf {
+            mtf_view = MultiTimeframeCandles::new(&primary_tf, candle.datetime);
+            mtf_view.add_timeframe(&primary_tf, slice);
+            for tf in data.timeframes() {
+                if tf == primary_tf {
+                    continue;
+                }
+                let Some(tf_candles) = data.get(tf) else {
+                    continue;
+                };
+                let end = tf_candles.partition_point(|c| c.datetime <= candle.datetime);
+                if end > 0 {
+                    mtf_view.add_timeframe(tf, &tf_candles[end.saturating_sub(SCAN_LOOKBACK)..end]);
+                }
+            }
+            StrategyContext::multi_timeframe(symbol, &mtf_view, None, &[], capital, capital)
+        } else {
+            StrategyContext::single_timeframe(symbol, slice, None, &[], capital, capital)
+        };
+
+        strategy.on_bar(&ctx);
+        let requests = strategy.generate_orders(&ctx);
+        if idx < warmup || requests.is_empty() {
+            continue;
+        }
+        signals.push(SignalBar {
+            datetime: candle.datetime,
+            long: requests.iter().any(|r| r.side == Side::Buy),
+            short: requests.iter().any(|r| r.side == Side::Sell),
+        });
+    }
+    signals
+}
+
+/// Signal counts per (symbol, timeframe) and period
+#[derive(Debug, Clone, Default)]
+pub struct SignalHeatmap {
+    pub period: Period,
+    cells: BTreeMap<(String, String), BTreeMap<String, SignalCounts>>,
+}
+
+impl SignalHeatmap {
+    pub fn new(period: Period) -> Self {
+        Self {
+            period,
+            cells: BTreeMap::new(),
+        }
+    }
+
+    /// Bucket a scanned series: every bar after warmup counts toward `bars`
+    pub fn add_series(
+        &mut self,
+        symbol: &str,
+        timeframe: &str,
+        bar_times: impl IntoIterator<Item = DateTime<Utc>>,
+        signals: &[SignalBar],
+    ) {
+        let row = self
+            .cells
+            .entry((symbol.to_string(), timeframe.to_string()))
+            .or_default();
+        for at in bar_times {
+            row.entry(self.period.label(at)).or_default().bars += 1;
+        }
+        for s in signals {
+            let cell = row.entry(self.period.label(s.datetime)).or_default();
+            cell.signals += 1;
+            cell.long += usize::from(s.long);
+            cell.short += usize::from(s.short);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Whole-history counts for one series
+    pub fn totals(&self, symbol: &str, timeframe: &str) -> SignalCounts {
+        let mut total = SignalCounts::default();
+        if let Some(row) = self.cells.get(&(symbol.to_string(), timeframe.to_string())) {
+            row.values().for_each(|c| total.add(c));
+        }
+        total
+    }
+
+    fn symbols(&self) -> BTreeSet<&str> {
+        self.cells.keys().map(|(s, _)| s.as_str()).collect()
+    }
+
+    /// Timeframes in ascending bar length
+    fn timeframes(&self) -> Vec<&str> {
+        let mut tfs: Vec<&str> = self
+            .cells
+            .keys()
+            .map(|(_, tf)| tf.as_str())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        tfs.sort_by_key(|tf| {
+            crate::data::INTERVALS
+                .iter()
+                .position(|i| i == tf)
+                .unwrap_or(usize::MAX)
+        });
+        tfs
+    }
+
+    fn periods(&self) -> BTreeSet<&str> {
+        self.cells
+            .values()
+            .flat_map(|row| row.keys().map(String::as_str))
+            .collect()
+    }
+
+    /// "Where" (signals per 100 bars by symbol and timeframe) and "when"
+    /// (density by series and period) matrices
+    pub fn render(&self) -> String {
+        if self.is_empty() {
+            return "No series scanned.".to_string();
+        }
+        let mut out = String::new();
+        let timeframes = self.timeframes();
+
+        out.push_str(&format!("\n{}\n", "=".repeat(60)));
+        out.push_str("WHERE: signal bars per 100 bars\n");
+        out.push_str(&format!("{}\n", "=".repeat(60)));
+        out.push_str(&format!("{:<14}", "Symbol"));
+        for tf in &timeframes {
+            out.push_str(&format!(" │ {:>8}", tf));
+        }
+        out.push('\n');
+        let density_max = self
+            .cells
+            .keys()
+            .map(|(s, tf)| self.totals(s, tf).per_100_bars())
+            .fold(0.0, f64::max);
+        for symbol in self.symbols() {
+            out.push_str(&format!("{:<14}", symbol));
+            for tf in &timeframes {
+                let key = (symbol.to_string(), tf.to_string());
+                if self.cells.contains_key(&key) {
+                    let density = self.totals(symbol, tf).per_100_bars();
+                    out.push_str(&format!(
+                        " │ {} {:>6.2}",
+                        shade(density, density_max),
+                        density
+                    ));
+                } else {
+                    out.push_str(&format!(" │ {:>8}", "-"));
+                }
+            }
+            out.push('\n');
+        }
+
+        let periods = self.periods();
+        // Shaded by density so timeframes with more bars compare fairly
+        let density_max = self
+            .cells
+            .values()
+            .flat_map(|row| row.values().map(SignalCounts::per_100_bars))
+            .fold(0.0, f64::max);
+        out.push_str(&format!("\n{}\n", "=".repeat(60)));
+        out.push_str(&format!(
+            "WHEN: signal density per {} (total signal bars on the right)\n",
+            match self.period {
+                Period::Month => "month",
+                Period::Quarter => "quarter",
+                Period::Year => "year",
+            }
+        ));
+        out.push_str(&format!("{}\n", "=".repeat(60)));
+        out.push_str(&format!("{:<18} │ ", "Series"));
+        for p in &periods {
+            out.push_str(&format!("{:<2}", column_mark(p)));
+        }
+        out.push_str(&format!("│ {:>6}\n", "Total"));
+        for ((symbol, tf), row) in &self.cells {
+            out.push_str(&format!("{:<18} │ ", format!("{} {}", symbol, tf)));
+            for p in &periods {
+                let density = row.get(*p).map(|c| c.per_100_bars()).unwrap_or(0.0);
+                out.push(shade(density, density_max));
+                out.push(' ');
+            }
+            out.push_str(&format!("│ {:>6}\n", self.totals(symbol, tf).signals));
+        }
+        if let (Some(first), Some(last)) = (periods.first(), periods.last()) {
+            out.push_str(&format!(
+                "{:<18}   {} .. {}  ({} = {:.1} per 100 bars)\n",
+                "",
+                first,
+                last,
+                SHADES[SHADES.len() - 1],
+                density_max
+            ));
+        }
+        out.push_str(&format!("{}\n", "=".repeat(60)));
+        out
+    }
+
+    /// Long format: `symbol,timeframe,period,bars,signals,long,short`
+    pub fn to_csv_string(&self) -> String {
+        let mut out = String::from("symbol,timeframe,period,bars,signals,long,short\n");
+        for ((symbol, tf), row) in &self.cells {
+            for (period, c) in row {
+                out.push_str(&format!(
+                    "{},{},{},{},{},{},{}\n",
+                    symbol, tf, period, c.bars, c.signals, c.long, c.short
+                ));
+            }
+        }
+        out
+    }
+}
+
+fn shade(value: f64, max: f64) -> char {
+    if value <= 0.0 || max <= 0.0 {
+        return SHADES[0];
+    }
+    let idx = ((value / max) * (SHADES.len() - 1) as f64).ceil() as usize;
+    SHADES[idx.clamp(1, SHADES.len() - 1)]
+}
+
+/// One-character column header: the year's last digit at the start of each year
+fn column_mark(period: &str) -> String {
+    let year_start = period.len() == 4 || period.ends_with("-01") || period.ends_with("-Q1");
+    if year_start {
+        period[..4]
+            .chars()
+            .last()
+            .map(String::from)
+            .unwrap_or_default()
+    } else {
+        "·".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_heatmap_buckets_and_density() {
+        let t = |m: u32, d: u32| Utc.with_ymd_and_hms(2024, m, d, 0, 0, 0).unwrap();
+        let bars: Vec<_> = (1..=6).flat_map(|m| [t(m, 1), t(m, 15)]).collect();
+        let signals = [
+            SignalBar {
+                datetime: t(1, 15),
+                long: true,
+                short: false,
+            },
+            SignalBar {
+                datetime: t(5, 1),
+                long: false,
+                short: true,
+            },
+        ];
+
+        let mut map = SignalHeatmap::new(Period::Quarter);
+        map.add_series("BTCINR", "1d", bars, &signals);
+        let total = map.totals("BTCINR", "1d");
+        assert_eq!(
+            (total.bars, total.signals, total.long, total.short),
+            (12, 2, 1, 1)
+        );
+        assert!((total.per_100_bars() - 100.0 * 2.0 / 12.0).abs() < 1e-9);
+
+        let csv = map.to_csv_string();
+        assert!(csv.contains("BTCINR,1d,2024-Q1,6,1,1,0"));
+        assert!(csv.contains("BTCINR,1d,2024-Q2,6,1,0,1"));
+        assert_eq!(map.totals("ETHINR", "1d"), SignalCounts::default());
+        assert!(map.render().contains("BTCINR 1d"));
+    }
+}
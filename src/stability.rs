@@ -0,0 +1,296 @@
+//! Parameter-region stability across runs
+//!
+//! A single optimizer run finds the best point for one data period, which is
+//! often a lucky spike. Given results from many runs over different periods,
+//! this module scores each grid point by its *neighborhood* (the point plus
+//! adjacent grid values in every parameter) and asks how consistently that
+//! region was profitable period after period.
+//!
+//! Used by `runs analyze`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+/// One evaluated parameter set in one data period
+#[derive(Debug, Clone)]
+pub struct Observation {
+    /// Data period label; observations sharing a label are the same period
+    pub period: String,
+    pub params: BTreeMap<String, f64>,
+    pub sharpe_ratio: f64,
+    pub total_return: f64,
+}
+
+/// Stability of the region around one grid point
+#[derive(Debug, Clone)]
+pub struct RegionStats {
+    /// Center of the region
+    pub params: BTreeMap<String, f64>,
+    /// Grid points in the neighborhood that were evaluated
+    pub neighbors: usize,
+    /// Periods with at least one evaluated point in the region
+    pub periods: usize,
+    /// Periods where the region's mean return was positive
+    pub profitable_periods: usize,
+    /// Mean over periods of the region's mean Sharpe
+    pub mean_sharpe: f64,
+    /// Worst period's region-mean Sharpe
+    pub worst_sharpe: f64,
+    /// Mean over periods of the region's mean return, percent
+    pub mean_return: f64,
+}
+
+impl RegionStats {
+    /// Share of covered periods that were profitable
+    pub fn consistency(&self) -> f64 {
+        if self.periods == 0 {
+            0.0
+        } else {
+            self.profitable_periods as f64 / self.periods as f64
+        }
+    }
+}
+
+/// Per-value summary for one parameter (marginal view)
+#[derive(Debug, Clone)]
+pub struct ParamValueStats {
+    pub param: String,
+    pub value: f64,
+    pub periods: usize,
+    pub profitable_periods: usize,
+    pub mean_sharpe: f64,
+}
+
+/// Parameters present in every observation; metadata keys (`_timeframe` etc.) are ignored
+fn shared_params(observations: &[Observation]) -> Vec<String> {
+    let mut iter = observations.iter();
+    let Some(first) = iter.next() else {
+        return Vec::new();
+    };
+    let mut keys: BTreeSet<&String> = first
+        .params
+        .keys()
+        .filter(|k| !k.starts_with('_'))
+        .collect();
+    for obs in iter {
+        keys.retain(|k| obs.params.contains_key(*k));
+    }
+    keys.into_iter().cloned().collect()
+}
+
+/// Sharpe and return samples pooled into one cell
+type Samples = (Vec<f64>, Vec<f64>);
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+/// Score every evaluated grid point by its neighborhood, most stable first.
+///
+/// Points covered by fewer than `min_periods` periods are left out. Ranking is by
+/// consistency (share of profitable periods), then by worst-period Sharpe.
+pub fn region_stability(observations: &[Observation], min_periods: usize) -> Vec<RegionStats> {
+    let params = shared_params(observations);
+    if params.is_empty() {
+        return Vec::new();
+    }
+
+    // Grid axis per parameter: sorted distinct values
+    let axes: Vec<Vec<f64>> = params
+        .iter()
+        .map(|p| {
+            let mut values: Vec<f64> = observations.iter().map(|o| o.params[p]).collect();
+            values.sort_by(f64::total_cmp);
+            values.dedup();
+            values
+        })
+        .collect();
+    let coords = |obs: &Observation| -> Vec<usize> {
+        params
+            .iter()
+            .zip(&axes)
+            .map(|(p, axis)| {
+                axis.iter()
+                    .position(|v| *v == obs.params[p])
+                    .expect("value is on its axis")
+            })
+            .collect()
+    };
+
+    // period -> coordinate -> (sharpes, returns); repeated points (other symbols/timeframes) pool
+    let mut grid: BTreeMap<&str, BTreeMap<Vec<usize>, Samples>> = BTreeMap::new();
+    for obs in observations {
+        let cell = grid
+            .entry(obs.period.as_str())
+            .or_default()
+            .entry(coords(obs))
+            .or_default();
+        cell.0.push(obs.sharpe_ratio);
+        cell.1.push(obs.total_return);
+    }
+    let points: BTreeSet<Vec<usize>> = grid
+        .values()
+        .flat_map(|cells| cells.keys())
+        .cloned()
+        .collect();
+    let is_neighbor = |a: &[usize], b: &[usize]| a.iter().zip(b).all(|(x, y)| x.abs_diff(*y) <= 1);
+
+    let mut regions: Vec<RegionStats> = points
+        .iter()
+        .filter_map(|center| {
+            let neighbors = points.iter().filter(|p| is_neighbor(center, p)).count();
+            let mut sharpes = Vec::new();
+            let mut returns = Vec::new();
+            for cells in grid.values() {
+                let (s, r): (Vec<f64>, Vec<f64>) = cells
+                    .iter()
+                    .filter(|(p, _)| is_neighbor(center, p))
+                    .flat_map(|(_, (s, r))| s.iter().copied().zip(r.iter().copied()))
+                    .unzip();
+                if !s.is_empty() {
+                    sharpes.push(mean(&s));
+                    returns.push(mean(&r));
+                }
+            }
+            if sharpes.len() < min_periods.max(1) {
+                return None;
+            }
+            Some(RegionStats {
+                params: params
+                    .iter()
+                    .zip(&axes)
+                    .zip(center)
+                    .map(|((p, axis), &i)| (p.clone(), axis[i]))
+                    .collect(),
+                neighbors,
+                periods: sharpes.len(),
+                profitable_periods: returns.iter().filter(|r| **r > 0.0).count(),
+                mean_sharpe: mean(&sharpes),
+                worst_sharpe: sharpes.iter().copied().fold(f64::INFINITY, f64::min),
+                mean_return: mean(&returns),
+            })
+        })
+        .collect();
+
+    regions.sort_by(|a, b| {
+        b.consistency()
+            .total_cmp(&a.consistency())
+            .then(b.worst_sharpe.total_cmp(&a.worst_sharpe))
+    });
+    regions
+}
+
+/// Marginal stability of each value of each parameter
+pub fn param_value_stability(observations: &[Observation]) -> Vec<ParamValueStats> {
+    let mut out = Vec::new();
+    for param in shared_params(observations) {
+        // value -> period -> (sharpes, returns)
+        let mut by_value: BTreeMap<u64, BTreeMap<&str, Samples>> = BTreeMap::new();
+        for obs in observations {
+            let cell = by_value
+                .entry(obs.params[&param].to_bits())
+                .or_default()
+                .entry(obs.period.as_str())
+                .or_default();
+            cell.0.push(obs.sharpe_ratio);
+            cell.1.push(obs.total_return);
+        }
+        let mut rows: Vec<ParamValueStats> = by_value
+            .into_iter()
+            .map(|(bits, periods)| {
+                let sharpes: Vec<f64> = periods.values().map(|(s, _)| mean(s)).collect();
+                ParamValueStats {
+                    param: param.clone(),
+                    value: f64::from_bits(bits),
+                    periods: periods.len(),
+                    profitable_periods: periods.values().filter(|(_, r)| mean(r) > 0.0).count(),
+                    mean_sharpe: mean(&sharpes),
+                }
+            })
+            .collect();
+        rows.sort_by(|a, b| a.value.total_cmp(&b.value));
+        out.extend(rows);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obs(period: &str, fast: f64, slow: f64, sharpe: f64) -> Observation {
+        Observation {
+            period: period.to_string(),
+            params: BTreeMap::from([
+                ("ema_fast".to_string(), fast),
+                ("ema_slow".to_string(), slow),
+                ("_timeframe".to_string(), 24.0),
+            ]),
+            sharpe_ratio: sharpe,
+            total_return: sharpe * 10.0,
+        }
+    }
+
+    /// 3x3 grid over three periods. (13, 34) is the best point in period A but
+    /// isolated; the low-fast corner is solid in every period.
+    fn observations() -> Vec<Observation> {
+        let mut out = Vec::new();
+        for period in ["A", "B", "C"] {
+            for (i, fast) in [5.0, 8.0, 13.0].into_iter().enumerate() {
+                for (j, slow) in [21.0, 34.0, 55.0].into_iter().enumerate() {
+                    let sharpe = match (period, i, j) {
+                        ("A", 2, 1) => 3.0,
+                        (_, 2, _) | (_, _, 2) => -1.0,
+                        _ => 1.0,
+                    };
+                    out.push(obs(period, fast, slow, sharpe));
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_region_ranking_prefers_consistent_neighborhoods() {
+        let regions = region_stability(&observations(), 2);
+        assert_eq!(regions.len(), 9);
+
+        let best = &regions[0];
+        assert_eq!(best.params["ema_fast"], 5.0);
+        assert_eq!(best.params["ema_slow"], 21.0);
+        assert_eq!(best.neighbors, 4);
+        assert_eq!(best.periods, 3);
+        assert_eq!(best.profitable_periods, 3);
+        assert!(!best.params.contains_key("_timeframe"));
+
+        // The in-sample winner sits in a losing neighborhood
+        let spike = regions
+            .iter()
+            .find(|r| r.params["ema_fast"] == 13.0 && r.params["ema_slow"] == 34.0)
+            .unwrap();
+        assert!(spike.consistency() < 1.0);
+        assert!(spike.worst_sharpe < 0.0);
+    }
+
+    #[test]
+    fn test_min_periods_and_marginals() {
+        let single: Vec<_> = observations()
+            .into_iter()
+            .filter(|o| o.period == "A")
+            .collect();
+        assert!(region_stability(&single, 2).is_empty());
+        assert_eq!(region_stability(&single, 1).len(), 9);
+
+        let marginals = param_value_stability(&observations());
+        let fast13 = marginals
+            .iter()
+            .find(|m| m.param == "ema_fast" && m.value == 13.0)
+            .unwrap();
+        assert_eq!(fast13.periods, 3);
+        assert_eq!(fast13.profitable_periods, 1);
+        assert_eq!(marginals.len(), 6);
+    }
+}
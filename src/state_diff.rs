@@ -0,0 +1,437 @@
+//! Changes between two live-trading checkpoints
+//!
+//! Backs `state diff`: cash and equity movement, positions opened, closed or
+//! resized, P&L realized by trades closed in between, and the risk counters
+//! (drawdown, loss streak, trailing drawdown, hedge). Positions come from the
+//! `positions` snapshot each checkpoint records; checkpoints written before
+//! that snapshot existed only report the open-position count.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::hedge::HedgeLeg;
+use crate::risk::TrailingDrawdown;
+use crate::state_manager::{Checkpoint, CheckpointPosition, TradeRecord};
+
+/// A value at the earlier and later checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Delta<T> {
+    pub before: T,
+    pub after: T,
+}
+
+impl<T: PartialEq> Delta<T> {
+    pub fn new(before: T, after: T) -> Self {
+        Self { before, after }
+    }
+
+    pub fn changed(&self) -> bool {
+        self.before != self.after
+    }
+}
+
+impl Delta<f64> {
+    pub fn change(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionChangeKind {
+    Opened,
+    Closed,
+    Resized,
+    /// Closed and reopened on the other side
+    Reversed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PositionChange {
+    pub symbol: String,
+    pub kind: PositionChangeKind,
+    pub before: Option<CheckpointPosition>,
+    pub after: Option<CheckpointPosition>,
+}
+
+/// Trades whose exit falls between the two checkpoints
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RealizedPnl {
+    pub trades: usize,
+    pub winners: usize,
+    pub net_pnl: f64,
+    pub fees: f64,
+    /// Net P&L per symbol
+    pub by_symbol: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckpointDiff {
+    pub checkpoint_ids: Delta<i64>,
+    pub timestamps: Delta<String>,
+    pub cycles: Delta<i32>,
+    pub cash: Delta<f64>,
+    pub positions_value: Delta<f64>,
+    pub portfolio_value: Delta<f64>,
+    /// Cumulative deposits less withdrawals booked by the live loop
+    pub net_cash_flows: Delta<f64>,
+    pub realized: RealizedPnl,
+    /// `None` when either checkpoint predates position snapshots
+    pub positions: Option<Vec<PositionChange>>,
+    pub open_positions: Delta<i32>,
+    pub drawdown_pct: Delta<f64>,
+    pub consecutive_losses: Delta<i32>,
+    pub trailing_drawdown: Option<Delta<Option<TrailingDrawdown>>>,
+    /// Hedge short quantity (0 when flat)
+    pub hedge_quantity: Delta<f64>,
+    pub config_changed: bool,
+}
+
+impl CheckpointDiff {
+    /// Compare checkpoint `a` (earlier) with `b`, attributing `trades` closed
+    /// in `(a, b]` to realized P&L
+    pub fn between(
+        (a_id, a): (i64, &Checkpoint),
+        (b_id, b): (i64, &Checkpoint),
+        trades: &[TradeRecord],
+    ) -> Self {
+        let trailing = |c: &Checkpoint| -> Option<TrailingDrawdown> {
+            serde_json::from_value(c.metadata.get("trailing_drawdown")?.clone()).ok()
+        };
+        let hedge = |c: &Checkpoint| -> f64 {
+            c.metadata
+                .get("hedge_leg")
+                .and_then(|v| serde_json::from_value::<HedgeLeg>(v.clone()).ok())
+                .map_or(0.0, |leg| leg.quantity)
+        };
+        let flows = |c: &Checkpoint| -> f64 {
+            c.metadata
+                .get("net_cash_flows")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0)
+        };
+        let trailing_drawdown = Delta::new(trailing(a), trailing(b));
+
+        Self {
+            checkpoint_ids: Delta::new(a_id, b_id),
+            timestamps: Delta::new(a.timestamp.clone(), b.timestamp.clone()),
+            cycles: Delta::new(a.cycle_count, b.cycle_count),
+            cash: Delta::new(a.cash, b.cash),
+            positions_value: Delta::new(a.positions_value, b.positions_value),
+            portfolio_value: Delta::new(a.portfolio_value, b.portfolio_value),
+            net_cash_flows: Delta::new(flows(a), flows(b)),
+            realized: realized_between(&a.timestamp, &b.timestamp, trades),
+            positions: a
+                .positions()
+                .zip(b.positions())
+                .map(|(before, after)| position_changes(&before, &after)),
+            open_positions: Delta::new(a.open_positions, b.open_positions),
+            drawdown_pct: Delta::new(a.drawdown_pct, b.drawdown_pct),
+            consecutive_losses: Delta::new(a.consecutive_losses, b.consecutive_losses),
+            trailing_drawdown: (trailing_drawdown.before.is_some()
+                || trailing_drawdown.after.is_some())
+            .then_some(trailing_drawdown),
+            hedge_quantity: Delta::new(hedge(a), hedge(b)),
+            config_changed: a.config_hash != b.config_hash,
+        }
+    }
+
+    /// Human-readable report
+    pub fn render(&self) -> String {
+        let mut out = Vec::new();
+        let money = |d: &Delta<f64>| {
+            format!(
+                "{:>14.2} → {:>14.2}  ({:+.2})",
+                d.before,
+                d.after,
+                d.change()
+            )
+        };
+
+        out.push("=".repeat(60));
+        out.push(format!(
+            "CHECKPOINT DIFF  #{} → #{}",
+            self.checkpoint_ids.before, self.checkpoint_ids.after
+        ));
+        out.push("=".repeat(60));
+        out.push(format!("  From:            {}", self.timestamps.before));
+        out.push(format!("  To:              {}", self.timestamps.after));
+        out.push(format!(
+            "  Cycles:          {} → {} (+{})",
+            self.cycles.before,
+            self.cycles.after,
+            self.cycles.after - self.cycles.before
+        ));
+        if self.config_changed {
+            out.push("  Config:          CHANGED (config hash differs)".to_string());
+        }
+
+        out.push(String::new());
+        out.push(format!("  Cash:            {}", money(&self.cash)));
+        out.push(format!(
+            "  Positions value: {}",
+            money(&self.positions_value)
+        ));
+        out.push(format!(
+            "  Portfolio value: {}",
+            money(&self.portfolio_value)
+        ));
+        if self.net_cash_flows.changed() {
+            let flows = self.net_cash_flows.change();
+            out.push(format!("  Deposits (net):  {:+.2}", flows));
+            out.push(format!(
+                "  Trading change:  {:+.2}",
+                self.portfolio_value.change() - flows
+            ));
+        }
+
+        out.push(String::new());
+        let r = &self.realized;
+        out.push(format!(
+            "  Realized P&L:    {:+.2} over {} trade(s), {} winner(s), fees {:.2}",
+            r.net_pnl, r.trades, r.winners, r.fees
+        ));
+        for (symbol, pnl) in &r.by_symbol {
+            out.push(format!("    {:<14} {:+.2}", symbol, pnl));
+        }
+
+        out.push(String::new());
+        match &self.positions {
+            Some(changes) if changes.is_empty() => {
+                out.push("  Positions:       unchanged".to_string())
+            }
+            Some(changes) => {
+                out.push("  Positions:".to_string());
+                for c in changes {
+                    let describe = |p: &Option<CheckpointPosition>| {
+                        p.as_ref().map_or("flat".to_string(), |p| {
+                            format!("{} {} @ {:.2}", p.side, p.quantity, p.entry_price)
+                        })
+                    };
+                    out.push(format!(
+                        "    {:<14} {:<9} {} → {}",
+                        c.symbol,
+                        format!("{:?}", c.kind).to_lowercase(),
+                        describe(&c.before),
+                        describe(&c.after)
+                    ));
+                }
+            }
+            None => out.push(format!(
+                "  Positions:       {} → {} open (no position snapshot recorded)",
+                self.open_positions.before, self.open_positions.after
+            )),
+        }
+
+        out.push(String::new());
+        out.push("  Risk:".to_string());
+        out.push(format!(
+            "    Drawdown:           {:.2}% → {:.2}%",
+            self.drawdown_pct.before * 100.0,
+            self.drawdown_pct.after * 100.0
+        ));
+        out.push(format!(
+            "    Consecutive losses: {} → {}",
+            self.consecutive_losses.before, self.consecutive_losses.after
+        ));
+        if let Some(trailing) = &self.trailing_drawdown {
+            let hwm = |t: &Option<TrailingDrawdown>| {
+                t.as_ref()
+                    .map_or("-".to_string(), |t| format!("{:.2}", t.high_water_mark))
+            };
+            let breached = |t: &Option<TrailingDrawdown>| t.as_ref().is_some_and(|t| t.breached);
+            out.push(format!(
+                "    High-water mark:    {} → {}{}",
+                hwm(&trailing.before),
+                hwm(&trailing.after),
+                if !breached(&trailing.before) && breached(&trailing.after) {
+                    "  (trailing drawdown BREACHED)"
+                } else {
+                    ""
+                }
+            ));
+        }
+        if self.hedge_quantity.changed() || self.hedge_quantity.after > 0.0 {
+            out.push(format!(
+                "    Hedge short:        {} → {}",
+                self.hedge_quantity.before, self.hedge_quantity.after
+            ));
+        }
+        out.push("=".repeat(60));
+        out.join("\n")
+    }
+}
+
+fn realized_between(from: &str, to: &str, trades: &[TradeRecord]) -> RealizedPnl {
+    let parse = |t: &str| DateTime::parse_from_rfc3339(t).map(|t| t.with_timezone(&Utc));
+    let (Ok(from), Ok(to)) = (parse(from), parse(to)) else {
+        return RealizedPnl::default();
+    };
+
+    let mut realized = RealizedPnl::default();
+    for trade in trades {
+        let Ok(exit) = parse(&trade.exit_time) else {
+            continue;
+        };
+        if exit <= from || exit > to {
+            continue;
+        }
+        realized.trades += 1;
+        realized.winners += usize::from(trade.net_pnl > 0.0);
+        realized.net_pnl += trade.net_pnl;
+        realized.fees += trade.fees;
+        *realized.by_symbol.entry(trade.symbol.clone()).or_default() += trade.net_pnl;
+    }
+    realized
+}
+
+fn position_changes(
+    before: &[CheckpointPosition],
+    after: &[CheckpointPosition],
+) -> Vec<PositionChange> {
+    let mut symbols: BTreeMap<&str, (Option<&CheckpointPosition>, Option<&CheckpointPosition>)> =
+        BTreeMap::new();
+    for p in before {
+        symbols.entry(&p.symbol).or_default().0 = Some(p);
+    }
+    for p in after {
+        symbols.entry(&p.symbol).or_default().1 = Some(p);
+    }
+
+    symbols
+        .into_iter()
+        .filter_map(|(symbol, (b, a))| {
+            let kind = match (b, a) {
+                (None, Some(_)) => PositionChangeKind::Opened,
+                (Some(_), None) => PositionChangeKind::Closed,
+                (Some(b), Some(a)) if b.side != a.side => PositionChangeKind::Reversed,
+                (Some(b), Some(a))
+                    if (b.quantity - a.quantity).abs() > 1e-12
+                        || (b.entry_price - a.entry_price).abs() > 1e-12 =>
+                {
+                    PositionChangeKind::Resized
+                }
+                _ => return None,
+            };
+            Some(PositionChange {
+                symbol: symbol.to_string(),
+                kind,
+                before: b.cloned(),
+                after: a.cloned(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn position(symbol: &str, quantity: f64) -> CheckpointPosition {
+        CheckpointPosition {
+            symbol: symbol.to_string(),
+            side: "buy".to_string(),
+            quantity,
+            entry_price: 100.0,
+            unrealized_pnl: 0.0,
+        }
+    }
+
+    fn checkpoint(timestamp: &str, cash: f64, positions: &[CheckpointPosition]) -> Checkpoint {
+        let mut metadata = HashMap::new();
+        metadata.insert("positions".to_string(), serde_json::json!(positions));
+        Checkpoint {
+            timestamp: timestamp.to_string(),
+            cycle_count: 10,
+            portfolio_value: cash + 500.0,
+            cash,
+            positions_value: 500.0,
+            open_positions: positions.len() as i32,
+            last_processed_symbols: vec![],
+            drawdown_pct: 0.01,
+            consecutive_losses: 0,
+            paper_mode: true,
+            config_hash: "abc".to_string(),
+            metadata,
+        }
+    }
+
+    fn trade(symbol: &str, exit_time: &str, net_pnl: f64) -> TradeRecord {
+        TradeRecord {
+            symbol: symbol.to_string(),
+            exit_time: exit_time.to_string(),
+            net_pnl,
+            fees: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff_reports_position_changes_and_realized_pnl() {
+        let a = checkpoint(
+            "2025-06-01T00:00:00+00:00",
+            10_000.0,
+            &[position("BTCINR", 1.0), position("ETHINR", 2.0)],
+        );
+        let mut b = checkpoint(
+            "2025-06-02T00:00:00+00:00",
+            10_250.0,
+            &[position("BTCINR", 0.5), position("SOLINR", 3.0)],
+        );
+        b.consecutive_losses = 2;
+        let trades = [
+            trade("ETHINR", "2025-05-31T12:00:00.000Z", 99.0),
+            trade("ETHINR", "2025-06-01T08:00:00.000Z", 300.0),
+            trade("BTCINR", "2025-06-01T09:00:00.000Z", -50.0),
+            trade("BTCINR", "2025-06-02T00:00:01.000Z", 99.0),
+        ];
+
+        let diff = CheckpointDiff::between((1, &a), (7, &b), &trades);
+
+        assert_eq!(diff.cash.change(), 250.0);
+        assert_eq!(diff.realized.trades, 2);
+        assert_eq!(diff.realized.winners, 1);
+        assert_eq!(diff.realized.net_pnl, 250.0);
+        assert_eq!(diff.realized.by_symbol["BTCINR"], -50.0);
+        let kinds: Vec<_> = diff
+            .positions
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|c| (c.symbol.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("BTCINR", PositionChangeKind::Resized),
+                ("ETHINR", PositionChangeKind::Closed),
+                ("SOLINR", PositionChangeKind::Opened),
+            ]
+        );
+        assert!(diff.consecutive_losses.changed());
+        assert!(!diff.config_changed);
+        assert!(diff
+            .render()
+            .contains("Realized P&L:    +250.00 over 2 trade(s)"));
+        assert!(!diff.render().contains("Deposits"));
+
+        // A top-up between checkpoints is split out of the portfolio change
+        let mut topped_up = b.clone();
+        topped_up.portfolio_value += 5_000.0;
+        topped_up
+            .metadata
+            .insert("net_cash_flows".to_string(), serde_json::json!(5_000.0));
+        let diff = CheckpointDiff::between((1, &a), (7, &topped_up), &trades);
+        assert_eq!(diff.net_cash_flows.change(), 5_000.0);
+        assert!(diff.render().contains("Trading change:  +250.00"));
+
+        // Older checkpoints without a snapshot still diff the counters
+        let mut a = a;
+        a.metadata.clear();
+        let diff = CheckpointDiff::between((1, &a), (7, &b), &[]);
+        assert!(diff.positions.is_none());
+        assert!(diff.render().contains("no position snapshot recorded"));
+    }
+}
@@ -5,7 +5,7 @@
 // matching the Python implementation for production deployment.
 
 use anyhow::{Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -13,6 +13,9 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tracing::{debug, info};
 
+use crate::accounts::AccountBalance;
+use crate::state_writer::StateEvent;
+
 // =============================================================================
 // Data Models
 // =============================================================================
@@ -56,7 +59,77 @@ pub struct Checkpoint {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Checkpoint {
+    /// Open positions recorded with the checkpoint (metadata key `positions`)
+    ///
+    /// `None` for checkpoints written before positions were recorded.
+    pub fn positions(&self) -> Option<Vec<CheckpointPosition>> {
+        serde_json::from_value(self.metadata.get("positions")?.clone()).ok()
+    }
+}
+
+/// An open position as of a checkpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointPosition {
+    pub symbol: String,
+    pub side: String,
+    pub quantity: f64,
+    pub entry_price: f64,
+    #[serde(default)]
+    pub unrealized_pnl: f64,
+}
+
+/// Which checkpoint to load
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckpointRef {
+    /// Most recent checkpoint
+    Latest,
+    /// `n` checkpoints before the latest (`~n`)
+    Back(usize),
+    /// Row ID
+    Id(i64),
+    /// Latest checkpoint at or before this time
+    At(DateTime<Utc>),
+}
+
+impl std::str::FromStr for CheckpointRef {
+    type Err = anyhow::Error;
+
+    /// `latest`, `~N`, a row ID, or an RFC 3339 time, `YYYY-MM-DDTHH:MM` or
+    /// `YYYY-MM-DD` (UTC; a bare date means the end of that day)
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("latest") {
+            return Ok(Self::Latest);
+        }
+        if let Some(n) = s.strip_prefix('~') {
+            return Ok(Self::Back(
+                n.parse()
+                    .with_context(|| format!("Invalid checkpoint offset {:?}", s))?,
+            ));
+        }
+        if let Ok(id) = s.parse() {
+            return Ok(Self::Id(id));
+        }
+        if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+            return Ok(Self::At(t.with_timezone(&Utc)));
+        }
+        if let Ok(t) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M") {
+            return Ok(Self::At(t.and_utc()));
+        }
+        if let Ok(d) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Ok(Self::At(
+                d.and_hms_opt(23, 59, 59).unwrap_or_default().and_utc(),
+            ));
+        }
+        anyhow::bail!(
+            "Invalid checkpoint {:?} (expected latest, ~N, an ID or a UTC time)",
+            s
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TradeRecord {
     pub id: Option<i64>,
     pub symbol: String,
@@ -84,6 +157,9 @@ pub struct TradeRecord {
     pub take_profit: f64,
     pub risk_reward_actual: f64,
     pub metadata: HashMap<String, serde_json::Value>,
+    /// Key of the fill that closed the trade; a second row for the same fill is ignored
+    #[serde(default)]
+    pub fill_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -98,6 +174,99 @@ pub struct PendingOrder {
     pub client_id: Option<String>,
 }
 
+/// One fill as executed by the live loop, kept for reconciliation against the
+/// exchange's trade history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillRecord {
+    pub id: Option<i64>,
+    pub order_id: String,
+    pub symbol: String,
+    pub side: String,
+    pub quantity: f64,
+    pub price: f64,
+    pub fee: f64,
+    pub timestamp: String,
+    /// Paper fills never reach the exchange and are skipped by reconciliation
+    pub paper: bool,
+    /// Idempotency key (exchange trade ID, or `<intent>:<filled qty>` for simulated
+    /// fills); a second fill with the same key is ignored
+    #[serde(default)]
+    pub fill_id: Option<String>,
+}
+
+/// Lifecycle of an [`OrderIntent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntentStatus {
+    /// Written, not yet acknowledged by the exchange
+    Pending,
+    /// Acknowledged; may fill
+    Submitted,
+    Filled,
+    Cancelled,
+    /// Never reached the exchange (found pending at recovery with no order behind it)
+    Abandoned,
+}
+
+impl IntentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IntentStatus::Pending => "pending",
+            IntentStatus::Submitted => "submitted",
+            IntentStatus::Filled => "filled",
+            IntentStatus::Cancelled => "cancelled",
+            IntentStatus::Abandoned => "abandoned",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "pending" => IntentStatus::Pending,
+            "submitted" => IntentStatus::Submitted,
+            "filled" => IntentStatus::Filled,
+            "cancelled" => IntentStatus::Cancelled,
+            "abandoned" => IntentStatus::Abandoned,
+            _ => return None,
+        })
+    }
+
+    /// Still waiting for an outcome
+    pub fn is_open(&self) -> bool {
+        matches!(self, IntentStatus::Pending | IntentStatus::Submitted)
+    }
+}
+
+/// An order the bot is about to send, written before submission so a crash
+/// between submit and the fill write can be resolved at startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderIntent {
+    /// Also sent to the exchange as the client order ID
+    pub intent_id: String,
+    pub symbol: String,
+    pub side: String,
+    pub order_type: String,
+    pub quantity: f64,
+    pub limit_price: Option<f64>,
+    pub status: IntentStatus,
+    pub exchange_order_id: Option<String>,
+    pub created_at: String,
+}
+
+/// Money moved into or out of the account outside of trading (a deposit or
+/// withdrawal), kept so equity changes can be split into P&L and top-ups
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CashFlow {
+    /// Exchange transfer ID, prefixed by kind (`deposit:123`); recorded once
+    pub flow_id: String,
+    pub currency: String,
+    /// Signed change to cash: positive for deposits, negative for withdrawals
+    pub amount: f64,
+    /// `"deposit"` or `"withdrawal"`
+    pub kind: String,
+    /// RFC 3339 time the exchange created the transfer
+    pub timestamp: String,
+}
+
 // =============================================================================
 // State Manager Implementation
 // =============================================================================
@@ -246,36 +415,106 @@ impl SqliteStateManager {
             [],
         )?;
 
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS fills (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                order_id TEXT NOT NULL,
+                symbol TEXT NOT NULL,
+                side TEXT NOT NULL,
+                quantity REAL NOT NULL,
+                price REAL NOT NULL,
+                fee REAL DEFAULT 0,
+                timestamp TEXT NOT NULL,
+                paper INTEGER DEFAULT 0
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_fills_timestamp ON fills(timestamp)",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS order_intents (
+                intent_id TEXT PRIMARY KEY,
+                symbol TEXT NOT NULL,
+                side TEXT NOT NULL,
+                order_type TEXT NOT NULL,
+                quantity REAL NOT NULL,
+                limit_price REAL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                exchange_order_id TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS account_balances (
+                account TEXT PRIMARY KEY,
+                capital REAL NOT NULL,
+                realized_pnl REAL NOT NULL,
+                fees REAL NOT NULL,
+                trades INTEGER NOT NULL,
+                updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS cash_flows (
+                flow_id TEXT PRIMARY KEY,
+                currency TEXT NOT NULL,
+                amount REAL NOT NULL,
+                kind TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                recorded_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        // Idempotency keys (added after the first schema; older databases get the columns here)
+        Self::add_column_if_missing(&conn, "fills", "fill_id", "TEXT")?;
+        Self::add_column_if_missing(&conn, "trades", "fill_id", "TEXT")?;
+        conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_fills_fill_id ON fills(fill_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_trades_fill_id ON trades(fill_id)",
+            [],
+        )?;
+
         debug!("Database schema created/verified");
         Ok(())
     }
 
+    fn add_column_if_missing(
+        conn: &Connection,
+        table: &str,
+        column: &str,
+        decl: &str,
+    ) -> Result<()> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let exists = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(|r| r.ok())
+            .any(|name| name == column);
+        if !exists {
+            conn.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl),
+                [],
+            )?;
+            debug!("Added column {}.{}", table, column);
+        }
+        Ok(())
+    }
+
     pub fn save_position(&self, pos: &Position) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        let metadata_json = serde_json::to_string(&pos.metadata)?;
-
-        conn.execute(
-            "INSERT OR REPLACE INTO positions 
-             (symbol, side, quantity, entry_price, entry_time, stop_loss,
-              take_profit, status, order_id, pnl, exit_price, exit_time,
-              metadata, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, CURRENT_TIMESTAMP)",
-            params![
-                pos.symbol,
-                pos.side,
-                pos.quantity,
-                pos.entry_price,
-                pos.entry_time,
-                pos.stop_loss,
-                pos.take_profit,
-                pos.status,
-                pos.order_id,
-                pos.pnl,
-                pos.exit_price,
-                pos.exit_time,
-                metadata_json,
-            ],
-        )?;
+        Self::insert_position(&conn, pos)?;
 
         debug!(
             "Position saved: {} [{}] qty={:.6} @ {:.2}",
@@ -359,30 +598,7 @@ impl SqliteStateManager {
 
     pub fn save_checkpoint(&self, ckpt: &Checkpoint) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        let symbols_json = serde_json::to_string(&ckpt.last_processed_symbols)?;
-        let metadata_json = serde_json::to_string(&ckpt.metadata)?;
-
-        conn.execute(
-            "INSERT INTO checkpoints 
-             (timestamp, cycle_count, portfolio_value, cash, positions_value,
-              open_positions, last_processed_symbols, drawdown_pct,
-              consecutive_losses, paper_mode, config_hash, metadata)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
-            params![
-                ckpt.timestamp,
-                ckpt.cycle_count,
-                ckpt.portfolio_value,
-                ckpt.cash,
-                ckpt.positions_value,
-                ckpt.open_positions,
-                symbols_json,
-                ckpt.drawdown_pct,
-                ckpt.consecutive_losses,
-                if ckpt.paper_mode { 1 } else { 0 },
-                ckpt.config_hash,
-                metadata_json,
-            ],
-        )?;
+        Self::insert_checkpoint(&conn, ckpt)?;
 
         debug!(
             "Checkpoint saved: cycle={}, value={:.2}",
@@ -401,23 +617,7 @@ impl SqliteStateManager {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare("SELECT * FROM checkpoints ORDER BY id DESC LIMIT 1")?;
 
-        let ckpt = stmt.query_row([], |row| {
-            Ok(Checkpoint {
-                timestamp: row.get(1)?,
-                cycle_count: row.get(2)?,
-                portfolio_value: row.get(3)?,
-                cash: row.get(4)?,
-                positions_value: row.get(5)?,
-                open_positions: row.get(6)?,
-                last_processed_symbols: serde_json::from_str(&row.get::<_, String>(7)?)
-                    .unwrap_or_default(),
-                drawdown_pct: row.get::<_, Option<f64>>(8)?.unwrap_or(0.0),
-                consecutive_losses: row.get::<_, Option<i32>>(9)?.unwrap_or(0),
-                paper_mode: row.get::<_, i32>(10)? != 0,
-                config_hash: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
-                metadata: serde_json::from_str(&row.get::<_, String>(12)?).unwrap_or_default(),
-            })
-        });
+        let ckpt = stmt.query_row([], Self::checkpoint_from_row);
 
         match ckpt {
             Ok(c) => {
@@ -435,42 +635,129 @@ impl SqliteStateManager {
         }
     }
 
-    pub fn record_trade(&self, trade: &TradeRecord) -> Result<()> {
+    /// Look up a checkpoint and its row ID
+    pub fn find_checkpoint(&self, at: CheckpointRef) -> Result<Option<(i64, Checkpoint)>> {
         let conn = self.conn.lock().unwrap();
-        let metadata_json = serde_json::to_string(&trade.metadata)?;
+        let id = match at {
+            CheckpointRef::Id(id) => Some(id),
+            CheckpointRef::Latest | CheckpointRef::Back(_) => {
+                let back = match at {
+                    CheckpointRef::Back(n) => n as i64,
+                    _ => 0,
+                };
+                conn.query_row(
+                    "SELECT id FROM checkpoints ORDER BY id DESC LIMIT 1 OFFSET ?1",
+                    params![back],
+                    |row| row.get(0),
+                )
+                .map(Some)
+                .or_else(|e| match e {
+                    rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                    e => Err(e),
+                })?
+            }
+            CheckpointRef::At(t) => {
+                // Timestamps are compared parsed; older rows mix RFC 3339 offsets
+                let mut stmt = conn.prepare("SELECT id, timestamp FROM checkpoints ORDER BY id")?;
+                let rows = stmt.query_map([], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+                })?;
+                let mut found = None;
+                for (id, timestamp) in rows.filter_map(|r| r.ok()) {
+                    match DateTime::parse_from_rfc3339(&timestamp) {
+                        Ok(ts) if ts <= t => found = Some(id),
+                        Ok(_) => break,
+                        Err(_) => {}
+                    }
+                }
+                found
+            }
+        };
+        let Some(id) = id else {
+            return Ok(None);
+        };
+        match conn.query_row(
+            "SELECT * FROM checkpoints WHERE id = ?1",
+            params![id],
+            Self::checkpoint_from_row,
+        ) {
+            Ok(c) => Ok(Some((id, c))),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
 
-        conn.execute(
-            "INSERT INTO trades 
-             (symbol, side, quantity, entry_price, exit_price, entry_time,
-              exit_time, gross_pnl, fees, tax, net_pnl, pnl_pct, status,
-              exit_reason, strategy_signal, market_state_entry, market_state_exit,
-              atr_at_entry, stop_loss, take_profit, risk_reward_actual, metadata)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)",
-            params![
-                trade.symbol,
-                trade.side,
-                trade.quantity,
-                trade.entry_price,
-                trade.exit_price,
-                trade.entry_time,
-                trade.exit_time,
-                trade.gross_pnl,
-                trade.fees,
-                trade.tax,
-                trade.net_pnl,
-                trade.pnl_pct,
-                trade.status,
-                trade.exit_reason,
-                trade.strategy_signal,
-                trade.market_state_entry,
-                trade.market_state_exit,
-                trade.atr_at_entry,
-                trade.stop_loss,
-                trade.take_profit,
-                trade.risk_reward_actual,
-                metadata_json,
-            ],
+    fn checkpoint_from_row(row: &rusqlite::Row) -> rusqlite::Result<Checkpoint> {
+        Ok(Checkpoint {
+            timestamp: row.get(1)?,
+            cycle_count: row.get(2)?,
+            portfolio_value: row.get(3)?,
+            cash: row.get(4)?,
+            positions_value: row.get(5)?,
+            open_positions: row.get(6)?,
+            last_processed_symbols: serde_json::from_str(&row.get::<_, String>(7)?)
+                .unwrap_or_default(),
+            drawdown_pct: row.get::<_, Option<f64>>(8)?.unwrap_or(0.0),
+            consecutive_losses: row.get::<_, Option<i32>>(9)?.unwrap_or(0),
+            paper_mode: row.get::<_, i32>(10)? != 0,
+            config_hash: row.get::<_, Option<String>>(11)?.unwrap_or_default(),
+            metadata: serde_json::from_str(&row.get::<_, String>(12)?).unwrap_or_default(),
+        })
+    }
+
+    /// Every recorded trade, oldest first
+    pub fn load_trades(&self) -> Result<Vec<TradeRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, symbol, side, quantity, entry_price, exit_price, entry_time, exit_time,
+                    gross_pnl, fees, tax, net_pnl, pnl_pct, status, exit_reason, strategy_signal,
+                    market_state_entry, market_state_exit, atr_at_entry, stop_loss, take_profit,
+                    risk_reward_actual, metadata, fill_id
+             FROM trades ORDER BY id",
         )?;
+        let trades = stmt
+            .query_map([], |row| {
+                Ok(TradeRecord {
+                    id: row.get(0)?,
+                    symbol: row.get(1)?,
+                    side: row.get(2)?,
+                    quantity: row.get(3)?,
+                    entry_price: row.get(4)?,
+                    exit_price: row.get::<_, Option<f64>>(5)?.unwrap_or(0.0),
+                    entry_time: row.get(6)?,
+                    exit_time: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+                    gross_pnl: row.get::<_, Option<f64>>(8)?.unwrap_or(0.0),
+                    fees: row.get::<_, Option<f64>>(9)?.unwrap_or(0.0),
+                    tax: row.get::<_, Option<f64>>(10)?.unwrap_or(0.0),
+                    net_pnl: row.get::<_, Option<f64>>(11)?.unwrap_or(0.0),
+                    pnl_pct: row.get::<_, Option<f64>>(12)?.unwrap_or(0.0),
+                    status: row.get::<_, Option<String>>(13)?.unwrap_or_default(),
+                    exit_reason: row.get::<_, Option<String>>(14)?.unwrap_or_default(),
+                    strategy_signal: row.get::<_, Option<String>>(15)?.unwrap_or_default(),
+                    market_state_entry: row.get::<_, Option<String>>(16)?.unwrap_or_default(),
+                    market_state_exit: row.get::<_, Option<String>>(17)?.unwrap_or_default(),
+                    atr_at_entry: row.get::<_, Option<f64>>(18)?.unwrap_or(0.0),
+                    stop_loss: row.get::<_, Option<f64>>(19)?.unwrap_or(0.0),
+                    take_profit: row.get::<_, Option<f64>>(20)?.unwrap_or(0.0),
+                    risk_reward_actual: row.get::<_, Option<f64>>(21)?.unwrap_or(0.0),
+                    metadata: row
+                        .get::<_, Option<String>>(22)?
+                        .and_then(|m| serde_json::from_str(&m).ok())
+                        .unwrap_or_default(),
+                    fill_id: row.get(23)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(trades)
+    }
+
+    pub fn record_trade(&self, trade: &TradeRecord) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        if !Self::insert_trade(&conn, trade)? {
+            debug!("Trade for fill {:?} already recorded", trade.fill_id);
+            return Ok(());
+        }
 
         let result = if trade.net_pnl > 0.0 { "WIN" } else { "LOSS" };
         info!(
@@ -489,58 +776,233 @@ impl SqliteStateManager {
         Ok(())
     }
 
-    /// Save a pending order to the database
-    pub fn save_pending_order(&self, order: &PendingOrder) -> Result<()> {
+    /// Append a fill to the reconciliation log; `false` if its `fill_id` was already recorded
+    pub fn record_fill(&self, fill: &FillRecord) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        Self::insert_fill(&conn, fill)
+    }
+
+    /// Whether a fill with this idempotency key is on disk
+    pub fn has_fill(&self, fill_id: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM fills WHERE fill_id = ?1",
+            params![fill_id],
+            |row| row.get(0),
+        )?;
+        Ok(count > 0)
+    }
+
+    /// Number of trade rows closed by `fill_id`
+    pub fn trades_for_fill(&self, fill_id: &str) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM trades WHERE fill_id = ?1",
+            params![fill_id],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Write an intent immediately (not batched): it must be on disk before the order is sent
+    pub fn record_intent(&self, intent: &OrderIntent) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT OR REPLACE INTO pending_orders 
-             (order_id, symbol, side, order_type, quantity, limit_price, stop_price, client_id)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT OR REPLACE INTO order_intents
+             (intent_id, symbol, side, order_type, quantity, limit_price, status,
+              exchange_order_id, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, CURRENT_TIMESTAMP)",
             params![
-                order.order_id,
-                order.symbol,
-                order.side,
-                order.order_type,
-                order.quantity,
-                order.limit_price,
-                order.stop_price,
-                order.client_id,
+                intent.intent_id,
+                intent.symbol,
+                intent.side,
+                intent.order_type,
+                intent.quantity,
+                intent.limit_price,
+                intent.status.as_str(),
+                intent.exchange_order_id,
+                intent.created_at,
             ],
         )?;
-        debug!("Pending order saved: {} {}", order.side, order.symbol);
         Ok(())
     }
 
-    /// Load all pending orders from the database
-    pub fn load_pending_orders(&self) -> Result<Vec<PendingOrder>> {
+    /// Move an intent to `status`, keeping its exchange order ID unless a new one is given
+    pub fn update_intent(
+        &self,
+        intent_id: &str,
+        status: IntentStatus,
+        exchange_order_id: Option<&str>,
+    ) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare("SELECT * FROM pending_orders")?;
+        Self::set_intent_status(&conn, intent_id, status, exchange_order_id)
+    }
 
-        let orders = stmt
+    /// Intents still pending or submitted, oldest first
+    pub fn load_open_intents(&self) -> Result<Vec<OrderIntent>> {
+        Ok(self
+            .load_intents()?
+            .into_iter()
+            .filter(|i| i.status.is_open())
+            .collect())
+    }
+
+    /// Every intent, oldest first
+    pub fn load_intents(&self) -> Result<Vec<OrderIntent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT intent_id, symbol, side, order_type, quantity, limit_price, status,
+                    exchange_order_id, created_at
+             FROM order_intents ORDER BY created_at, intent_id",
+        )?;
+        let intents = stmt
             .query_map([], |row| {
-                Ok(PendingOrder {
-                    order_id: row.get(0)?,
+                Ok(OrderIntent {
+                    intent_id: row.get(0)?,
                     symbol: row.get(1)?,
                     side: row.get(2)?,
                     order_type: row.get(3)?,
                     quantity: row.get(4)?,
                     limit_price: row.get(5)?,
-                    stop_price: row.get(6)?,
-                    client_id: row.get(7)?,
+                    status: IntentStatus::parse(&row.get::<_, String>(6)?)
+                        .unwrap_or(IntentStatus::Pending),
+                    exchange_order_id: row.get(7)?,
+                    created_at: row.get(8)?,
                 })
             })?
             .filter_map(|r| r.ok())
             .collect();
-
-        Ok(orders)
+        Ok(intents)
     }
 
-    /// Clear all pending orders (called after orders are restored to orderbook)
-    pub fn clear_pending_orders(&self) -> Result<()> {
+    /// Fills at or after `since` (RFC 3339), oldest first
+    pub fn load_fills(&self, since: &str) -> Result<Vec<FillRecord>> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM pending_orders", [])?;
-        debug!("Pending orders cleared");
-        Ok(())
+        let mut stmt = conn.prepare(
+            "SELECT id, order_id, symbol, side, quantity, price, fee, timestamp, paper, fill_id
+             FROM fills WHERE timestamp >= ?1 ORDER BY timestamp",
+        )?;
+
+        let fills = stmt
+            .query_map(params![since], |row| {
+                Ok(FillRecord {
+                    id: row.get(0)?,
+                    order_id: row.get(1)?,
+                    symbol: row.get(2)?,
+                    side: row.get(3)?,
+                    quantity: row.get(4)?,
+                    price: row.get(5)?,
+                    fee: row.get(6)?,
+                    timestamp: row.get(7)?,
+                    paper: row.get(8)?,
+                    fill_id: row.get(9)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(fills)
+    }
+
+    /// Latest balance of every exchange account, by name
+    pub fn load_account_balances(&self) -> Result<Vec<AccountBalance>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT account, capital, realized_pnl, fees, trades
+             FROM account_balances ORDER BY account",
+        )?;
+        let balances = stmt
+            .query_map([], |row| {
+                Ok(AccountBalance {
+                    account: row.get(0)?,
+                    capital: row.get(1)?,
+                    realized_pnl: row.get(2)?,
+                    fees: row.get(3)?,
+                    trades: row.get(4)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(balances)
+    }
+
+    /// Record a deposit or withdrawal; `false` if its `flow_id` was already recorded
+    pub fn record_cash_flow(&self, flow: &CashFlow) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO cash_flows (flow_id, currency, amount, kind, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                flow.flow_id,
+                flow.currency,
+                flow.amount,
+                flow.kind,
+                flow.timestamp
+            ],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    /// Deposits and withdrawals at or after `since` (RFC 3339), oldest first
+    pub fn load_cash_flows(&self, since: &str) -> Result<Vec<CashFlow>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT flow_id, currency, amount, kind, timestamp
+             FROM cash_flows WHERE timestamp >= ?1 ORDER BY timestamp, flow_id",
+        )?;
+        let flows = stmt
+            .query_map(params![since], |row| {
+                Ok(CashFlow {
+                    flow_id: row.get(0)?,
+                    currency: row.get(1)?,
+                    amount: row.get(2)?,
+                    kind: row.get(3)?,
+                    timestamp: row.get(4)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(flows)
+    }
+
+    /// Save a pending order to the database
+    pub fn save_pending_order(&self, order: &PendingOrder) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        Self::insert_pending_order(&conn, order)?;
+        debug!("Pending order saved: {} {}", order.side, order.symbol);
+        Ok(())
+    }
+
+    /// Load all pending orders from the database
+    pub fn load_pending_orders(&self) -> Result<Vec<PendingOrder>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT * FROM pending_orders")?;
+
+        let orders = stmt
+            .query_map([], |row| {
+                Ok(PendingOrder {
+                    order_id: row.get(0)?,
+                    symbol: row.get(1)?,
+                    side: row.get(2)?,
+                    order_type: row.get(3)?,
+                    quantity: row.get(4)?,
+                    limit_price: row.get(5)?,
+                    stop_price: row.get(6)?,
+                    client_id: row.get(7)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(orders)
+    }
+
+    /// Clear all pending orders (called after orders are restored to orderbook)
+    pub fn clear_pending_orders(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM pending_orders", [])?;
+        debug!("Pending orders cleared");
+        Ok(())
     }
 
     /// Remove a specific pending order by ID
@@ -627,12 +1089,262 @@ impl SqliteStateManager {
             take_profit: 0.0,
             risk_reward_actual: 0.0,
             metadata: HashMap::new(),
+            fill_id: None,
         };
 
         let state_manager = self.clone_for_async();
         tokio::task::spawn_blocking(move || state_manager.record_trade(&trade_record)).await?
     }
 
+    /// Apply a batch of events in a single transaction
+    ///
+    /// Either every event is written or none is. The JSON backup (when
+    /// enabled) is refreshed once per batch rather than once per write.
+    pub fn write_batch(&self, events: &[StateEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for event in events {
+            match event {
+                StateEvent::Checkpoint(ckpt) => Self::insert_checkpoint(&tx, ckpt)?,
+                StateEvent::Position(pos) => Self::insert_position(&tx, pos)?,
+                StateEvent::Fill(fill) => {
+                    if !Self::insert_fill(&tx, fill)? {
+                        debug!("Duplicate fill {:?} ignored", fill.fill_id);
+                    }
+                }
+                StateEvent::Trade(trade) => {
+                    if !Self::insert_trade(&tx, trade)? {
+                        debug!("Duplicate trade for fill {:?} ignored", trade.fill_id);
+                    }
+                }
+                StateEvent::Intent {
+                    intent_id,
+                    status,
+                    exchange_order_id,
+                } => {
+                    Self::set_intent_status(&tx, intent_id, *status, exchange_order_id.as_deref())?
+                }
+                StateEvent::ReplacePendingOrders(orders) => {
+                    tx.execute("DELETE FROM pending_orders", [])?;
+                    for order in orders {
+                        Self::insert_pending_order(&tx, order)?;
+                    }
+                }
+                StateEvent::AccountBalance(balance) => {
+                    tx.execute(
+                        "INSERT OR REPLACE INTO account_balances
+                         (account, capital, realized_pnl, fees, trades, updated_at)
+                         VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP)",
+                        params![
+                            balance.account,
+                            balance.capital,
+                            balance.realized_pnl,
+                            balance.fees,
+                            balance.trades
+                        ],
+                    )?;
+                }
+            }
+        }
+        tx.commit()?;
+        debug!("State batch committed: {} events", events.len());
+
+        if self.auto_backup {
+            drop(conn);
+            self.export_json()?;
+        }
+        Ok(())
+    }
+
+    /// Open a second connection to the same database (e.g. for a writer task)
+    pub fn try_clone(&self) -> Result<Self> {
+        SqliteStateManager::new(
+            self.db_path.clone(),
+            self.json_backup_path.clone(),
+            self.auto_backup,
+        )
+    }
+
+    /// Write a transactionally consistent copy of the database to `dest`.
+    ///
+    /// Uses `VACUUM INTO`, so it is safe to call while the live loop is writing.
+    pub fn backup_to<P: AsRef<Path>>(&self, dest: P) -> Result<()> {
+        let dest = dest.as_ref();
+        if dest.exists() {
+            std::fs::remove_file(dest)?;
+        }
+        let conn = self.conn.lock().unwrap();
+        conn.execute("VACUUM INTO ?1", params![dest.to_string_lossy()])
+            .with_context(|| format!("Failed to back up database to {}", dest.display()))?;
+        debug!("Database backed up to: {}", dest.display());
+        Ok(())
+    }
+
+    /// Run SQLite's integrity check, returning an error if the database is damaged
+    pub fn integrity_check(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let result: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+        if result != "ok" {
+            anyhow::bail!("Database integrity check failed: {}", result);
+        }
+        Ok(())
+    }
+
+    fn insert_position(conn: &Connection, pos: &Position) -> Result<()> {
+        let metadata_json = serde_json::to_string(&pos.metadata)?;
+
+        conn.execute(
+            "INSERT OR REPLACE INTO positions 
+             (symbol, side, quantity, entry_price, entry_time, stop_loss,
+              take_profit, status, order_id, pnl, exit_price, exit_time,
+              metadata, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, CURRENT_TIMESTAMP)",
+            params![
+                pos.symbol,
+                pos.side,
+                pos.quantity,
+                pos.entry_price,
+                pos.entry_time,
+                pos.stop_loss,
+                pos.take_profit,
+                pos.status,
+                pos.order_id,
+                pos.pnl,
+                pos.exit_price,
+                pos.exit_time,
+                metadata_json,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn insert_checkpoint(conn: &Connection, ckpt: &Checkpoint) -> Result<()> {
+        let symbols_json = serde_json::to_string(&ckpt.last_processed_symbols)?;
+        let metadata_json = serde_json::to_string(&ckpt.metadata)?;
+
+        conn.execute(
+            "INSERT INTO checkpoints 
+             (timestamp, cycle_count, portfolio_value, cash, positions_value,
+              open_positions, last_processed_symbols, drawdown_pct,
+              consecutive_losses, paper_mode, config_hash, metadata)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                ckpt.timestamp,
+                ckpt.cycle_count,
+                ckpt.portfolio_value,
+                ckpt.cash,
+                ckpt.positions_value,
+                ckpt.open_positions,
+                symbols_json,
+                ckpt.drawdown_pct,
+                ckpt.consecutive_losses,
+                if ckpt.paper_mode { 1 } else { 0 },
+                ckpt.config_hash,
+                metadata_json,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// `false` when a trade for the same closing fill already exists
+    fn insert_trade(conn: &Connection, trade: &TradeRecord) -> Result<bool> {
+        let metadata_json = serde_json::to_string(&trade.metadata)?;
+
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO trades 
+             (symbol, side, quantity, entry_price, exit_price, entry_time,
+              exit_time, gross_pnl, fees, tax, net_pnl, pnl_pct, status,
+              exit_reason, strategy_signal, market_state_entry, market_state_exit,
+              atr_at_entry, stop_loss, take_profit, risk_reward_actual, metadata, fill_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
+            params![
+                trade.symbol,
+                trade.side,
+                trade.quantity,
+                trade.entry_price,
+                trade.exit_price,
+                trade.entry_time,
+                trade.exit_time,
+                trade.gross_pnl,
+                trade.fees,
+                trade.tax,
+                trade.net_pnl,
+                trade.pnl_pct,
+                trade.status,
+                trade.exit_reason,
+                trade.strategy_signal,
+                trade.market_state_entry,
+                trade.market_state_exit,
+                trade.atr_at_entry,
+                trade.stop_loss,
+                trade.take_profit,
+                trade.risk_reward_actual,
+                metadata_json,
+                trade.fill_id,
+            ],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    /// `false` when a fill with the same `fill_id` already exists
+    fn insert_fill(conn: &Connection, fill: &FillRecord) -> Result<bool> {
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO fills
+             (order_id, symbol, side, quantity, price, fee, timestamp, paper, fill_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                fill.order_id,
+                fill.symbol,
+                fill.side,
+                fill.quantity,
+                fill.price,
+                fill.fee,
+                fill.timestamp,
+                fill.paper,
+                fill.fill_id,
+            ],
+        )?;
+        Ok(inserted == 1)
+    }
+
+    fn set_intent_status(
+        conn: &Connection,
+        intent_id: &str,
+        status: IntentStatus,
+        exchange_order_id: Option<&str>,
+    ) -> Result<()> {
+        conn.execute(
+            "UPDATE order_intents
+             SET status = ?2, exchange_order_id = COALESCE(?3, exchange_order_id),
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE intent_id = ?1",
+            params![intent_id, status.as_str(), exchange_order_id],
+        )?;
+        Ok(())
+    }
+
+    fn insert_pending_order(conn: &Connection, order: &PendingOrder) -> Result<()> {
+        conn.execute(
+            "INSERT OR REPLACE INTO pending_orders 
+             (order_id, symbol, side, order_type, quantity, limit_price, stop_price, client_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                order.order_id,
+                order.symbol,
+                order.side,
+                order.order_type,
+                order.quantity,
+                order.limit_price,
+                order.stop_price,
+                order.client_id,
+            ],
+        )?;
+        Ok(())
+    }
+
     fn clone_for_async(&self) -> Self {
         // Create a new state manager with the same paths
         SqliteStateManager::new(
@@ -644,6 +1356,221 @@ impl SqliteStateManager {
     }
 }
 
+// =============================================================================
+// Snapshot Export / Import
+// =============================================================================
+//
+// A snapshot is a plain tar archive containing:
+//   manifest.json        - format version, creation time, state summary
+//   trading_state.db     - consistent copy of the SQLite database
+//   trading_state.json   - JSON backup (positions, checkpoint, pending orders)
+//   captures/...         - any capture files recorded under the state directory
+//
+// Pending orders (the open-order cache) live in the database, so they travel
+// with it. See docs/ARCHITECTURE.md for the migration/resume procedure.
+
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+const SNAPSHOT_MANIFEST: &str = "manifest.json";
+const STATE_DB_FILE: &str = "trading_state.db";
+const STATE_JSON_FILE: &str = "trading_state.json";
+const CAPTURES_DIR: &str = "captures";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub format_version: u32,
+    pub created_at: String,
+    pub source_dir: String,
+    pub files: Vec<String>,
+    pub open_positions: usize,
+    pub pending_orders: usize,
+    pub last_checkpoint: Option<String>,
+    pub cycle_count: Option<i32>,
+}
+
+/// Export the state directory into a tar archive at `archive_path`.
+///
+/// The bot can keep running while this runs; the database copy is taken
+/// atomically, so at worst the snapshot lags the live state by one cycle.
+pub fn export_snapshot<P: AsRef<Path>, Q: AsRef<Path>>(
+    state_dir: P,
+    archive_path: Q,
+) -> Result<SnapshotManifest> {
+    let state_dir = state_dir.as_ref();
+    let archive_path = archive_path.as_ref();
+
+    let db_path = state_dir.join(STATE_DB_FILE);
+    if !db_path.exists() {
+        anyhow::bail!("No state database found at {}", db_path.display());
+    }
+
+    let manager = create_state_manager(state_dir, "sqlite")?;
+    manager.export_json()?;
+
+    let tmp_db = std::env::temp_dir().join(format!(
+        "crypto-strategies-snapshot-{}-{}.db",
+        std::process::id(),
+        Utc::now().timestamp_millis()
+    ));
+    manager.backup_to(&tmp_db)?;
+
+    let checkpoint = manager.load_checkpoint()?;
+    let mut manifest = SnapshotManifest {
+        format_version: SNAPSHOT_FORMAT_VERSION,
+        created_at: Utc::now().to_rfc3339(),
+        source_dir: state_dir.display().to_string(),
+        files: vec![STATE_DB_FILE.to_string(), STATE_JSON_FILE.to_string()],
+        open_positions: manager.load_positions(Some("open"))?.len(),
+        pending_orders: manager.load_pending_orders()?.len(),
+        last_checkpoint: checkpoint.as_ref().map(|c| c.timestamp.clone()),
+        cycle_count: checkpoint.as_ref().map(|c| c.cycle_count),
+    };
+
+    let captures_dir = state_dir.join(CAPTURES_DIR);
+    let mut capture_files = Vec::new();
+    if captures_dir.is_dir() {
+        collect_files(&captures_dir, &mut capture_files)?;
+    }
+    for path in &capture_files {
+        let rel = path.strip_prefix(state_dir)?;
+        manifest
+            .files
+            .push(rel.to_string_lossy().replace('\\', "/"));
+    }
+
+    if let Some(parent) = archive_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = std::fs::File::create(archive_path)
+        .with_context(|| format!("Failed to create archive: {}", archive_path.display()))?;
+    let mut builder = tar::Builder::new(file);
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, SNAPSHOT_MANIFEST, manifest_json.as_slice())?;
+
+    builder.append_path_with_name(&tmp_db, STATE_DB_FILE)?;
+    builder.append_path_with_name(state_dir.join(STATE_JSON_FILE), STATE_JSON_FILE)?;
+    for path in &capture_files {
+        builder.append_path_with_name(path, path.strip_prefix(state_dir)?)?;
+    }
+    builder.finish()?;
+
+    std::fs::remove_file(&tmp_db).ok();
+
+    info!(
+        "Snapshot exported: {} ({} files)",
+        archive_path.display(),
+        manifest.files.len()
+    );
+    Ok(manifest)
+}
+
+/// Import a snapshot archive into `state_dir`.
+///
+/// Refuses to overwrite an existing database unless `force` is set, in which
+/// case the existing database is moved aside as `trading_state.db.pre-import`.
+pub fn import_snapshot<P: AsRef<Path>, Q: AsRef<Path>>(
+    archive_path: P,
+    state_dir: Q,
+    force: bool,
+) -> Result<SnapshotManifest> {
+    let archive_path = archive_path.as_ref();
+    let state_dir = state_dir.as_ref();
+
+    let manifest = read_snapshot_manifest(archive_path)?;
+    if manifest.format_version > SNAPSHOT_FORMAT_VERSION {
+        anyhow::bail!(
+            "Snapshot format version {} is newer than supported version {}",
+            manifest.format_version,
+            SNAPSHOT_FORMAT_VERSION
+        );
+    }
+
+    std::fs::create_dir_all(state_dir)?;
+    let db_path = state_dir.join(STATE_DB_FILE);
+    if db_path.exists() {
+        if !force {
+            anyhow::bail!(
+                "State database already exists at {} (use --force to replace it)",
+                db_path.display()
+            );
+        }
+        let backup = state_dir.join(format!("{}.pre-import", STATE_DB_FILE));
+        std::fs::rename(&db_path, &backup)?;
+        for suffix in ["-wal", "-shm"] {
+            std::fs::remove_file(state_dir.join(format!("{}{}", STATE_DB_FILE, suffix))).ok();
+        }
+        info!("Existing database moved to {}", backup.display());
+    }
+
+    let file = std::fs::File::open(archive_path)
+        .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
+    let mut archive = tar::Archive::new(file);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        if path == Path::new(SNAPSHOT_MANIFEST) {
+            continue;
+        }
+        let known = path == Path::new(STATE_DB_FILE)
+            || path == Path::new(STATE_JSON_FILE)
+            || path.starts_with(CAPTURES_DIR);
+        if !known {
+            debug!("Skipping unexpected snapshot entry: {}", path.display());
+            continue;
+        }
+        // unpack_in rejects entries that would escape the destination directory
+        entry.unpack_in(state_dir)?;
+    }
+
+    let manager = create_state_manager(state_dir, "sqlite")?;
+    manager.integrity_check()?;
+
+    info!(
+        "Snapshot imported into {} ({} open positions, {} pending orders)",
+        state_dir.display(),
+        manifest.open_positions,
+        manifest.pending_orders
+    );
+    Ok(manifest)
+}
+
+/// Read the manifest from a snapshot archive without extracting it
+pub fn read_snapshot_manifest<P: AsRef<Path>>(archive_path: P) -> Result<SnapshotManifest> {
+    let archive_path = archive_path.as_ref();
+    let file = std::fs::File::open(archive_path)
+        .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
+    let mut archive = tar::Archive::new(file);
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if entry.path()? == Path::new(SNAPSHOT_MANIFEST) {
+            return serde_json::from_reader(entry).context("Invalid snapshot manifest");
+        }
+    }
+    anyhow::bail!(
+        "{} is not a state snapshot (no manifest)",
+        archive_path.display()
+    )
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, out)?;
+        } else {
+            out.push(path);
+        }
+    }
+    out.sort();
+    Ok(())
+}
+
 // =============================================================================
 // Factory Function
 // =============================================================================
@@ -660,3 +1587,217 @@ pub fn create_state_manager<P: AsRef<Path>>(
 
     SqliteStateManager::new(db_path, json_path, true)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "cs-state-{}-{}-{}",
+            name,
+            std::process::id(),
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ))
+    }
+
+    fn sample_position(symbol: &str) -> Position {
+        Position {
+            symbol: symbol.to_string(),
+            side: "buy".to_string(),
+            quantity: 0.5,
+            entry_price: 50000.0,
+            entry_time: Some(Utc::now().to_rfc3339()),
+            stop_loss: 48000.0,
+            take_profit: 55000.0,
+            status: "open".to_string(),
+            order_id: None,
+            pnl: 0.0,
+            exit_price: 0.0,
+            exit_time: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let src = temp_path("src");
+        let dst = temp_path("dst");
+        let archive = temp_path("snap").with_extension("tar");
+
+        {
+            let manager = create_state_manager(&src, "sqlite").unwrap();
+            manager.save_position(&sample_position("BTCINR")).unwrap();
+            manager
+                .save_pending_order(&PendingOrder {
+                    order_id: "42".to_string(),
+                    symbol: "ETHINR".to_string(),
+                    side: "buy".to_string(),
+                    order_type: "limit".to_string(),
+                    quantity: 1.0,
+                    limit_price: Some(3000.0),
+                    stop_price: None,
+                    client_id: None,
+                })
+                .unwrap();
+        }
+        std::fs::create_dir_all(src.join(CAPTURES_DIR)).unwrap();
+        std::fs::write(src.join(CAPTURES_DIR).join("book.jsonl"), "{}\n").unwrap();
+
+        let manifest = export_snapshot(&src, &archive).unwrap();
+        assert_eq!(manifest.open_positions, 1);
+        assert_eq!(manifest.pending_orders, 1);
+        assert!(manifest.files.iter().any(|f| f == "captures/book.jsonl"));
+
+        let imported = import_snapshot(&archive, &dst, false).unwrap();
+        assert_eq!(imported.format_version, SNAPSHOT_FORMAT_VERSION);
+        assert!(dst.join(CAPTURES_DIR).join("book.jsonl").exists());
+
+        let manager = create_state_manager(&dst, "sqlite").unwrap();
+        assert_eq!(manager.load_positions(Some("open")).unwrap().len(), 1);
+        assert_eq!(manager.load_pending_orders().unwrap().len(), 1);
+
+        // Second import without --force must refuse to clobber the database
+        assert!(import_snapshot(&archive, &dst, false).is_err());
+        assert!(import_snapshot(&archive, &dst, true).is_ok());
+
+        std::fs::remove_dir_all(&src).ok();
+        std::fs::remove_dir_all(&dst).ok();
+        std::fs::remove_file(&archive).ok();
+    }
+
+    #[test]
+    fn test_fill_id_columns_added_to_old_database() {
+        let dir = temp_path("migrate");
+        std::fs::create_dir_all(&dir).unwrap();
+        {
+            let conn = Connection::open(dir.join("trading_state.db")).unwrap();
+            conn.execute(
+                "CREATE TABLE fills (id INTEGER PRIMARY KEY AUTOINCREMENT, order_id TEXT NOT NULL,
+                 symbol TEXT NOT NULL, side TEXT NOT NULL, quantity REAL NOT NULL,
+                 price REAL NOT NULL, fee REAL NOT NULL DEFAULT 0, timestamp TEXT NOT NULL,
+                 paper INTEGER NOT NULL DEFAULT 0)",
+                [],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO fills (order_id, symbol, side, quantity, price, timestamp)
+                 VALUES ('1', 'BTCINR', 'buy', 1.0, 100.0, '2024-01-01T00:00:00.000Z')",
+                [],
+            )
+            .unwrap();
+        }
+
+        let manager = create_state_manager(&dir, "sqlite").unwrap();
+        let fills = manager.load_fills("").unwrap();
+        assert_eq!(fills.len(), 1);
+        assert!(fills[0].fill_id.is_none());
+
+        let mut fill = fills[0].clone();
+        fill.fill_id = Some("cs-1-1:1".to_string());
+        assert!(manager.record_fill(&fill).unwrap());
+        assert!(!manager.record_fill(&fill).unwrap());
+        // Legacy rows without a key never collide
+        fill.fill_id = None;
+        assert!(manager.record_fill(&fill).unwrap());
+        assert!(manager.record_fill(&fill).unwrap());
+        assert_eq!(manager.load_fills("").unwrap().len(), 4);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_cash_flows_recorded_once() {
+        let dir = temp_path("flows");
+        let manager = create_state_manager(&dir, "sqlite").unwrap();
+        let deposit = CashFlow {
+            flow_id: "deposit:91".to_string(),
+            currency: "INR".to_string(),
+            amount: 50_000.0,
+            kind: "deposit".to_string(),
+            timestamp: "2024-03-01T10:00:00+00:00".to_string(),
+        };
+        let withdrawal = CashFlow {
+            flow_id: "withdrawal:91".to_string(),
+            amount: -20_010.0,
+            kind: "withdrawal".to_string(),
+            timestamp: "2024-03-05T10:00:00+00:00".to_string(),
+            ..deposit.clone()
+        };
+        assert!(manager.record_cash_flow(&deposit).unwrap());
+        assert!(manager.record_cash_flow(&withdrawal).unwrap());
+        assert!(!manager.record_cash_flow(&deposit).unwrap());
+
+        assert_eq!(
+            manager.load_cash_flows("").unwrap(),
+            [deposit, withdrawal.clone()]
+        );
+        assert_eq!(manager.load_cash_flows("2024-03-02").unwrap(), [withdrawal]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_export_requires_database() {
+        let src = temp_path("empty");
+        std::fs::create_dir_all(&src).unwrap();
+        assert!(export_snapshot(&src, src.join("snap.tar")).is_err());
+        std::fs::remove_dir_all(&src).ok();
+    }
+
+    #[test]
+    fn test_find_checkpoint_by_reference() {
+        let dir = temp_path("find");
+        let manager = create_state_manager(&dir, "sqlite").unwrap();
+        for (cycle, timestamp) in [
+            (1, "2025-06-01T08:00:00+00:00"),
+            (2, "2025-06-01T20:00:00+00:00"),
+            (3, "2025-06-02T08:00:00+00:00"),
+        ] {
+            manager
+                .save_checkpoint(&Checkpoint {
+                    timestamp: timestamp.to_string(),
+                    cycle_count: cycle,
+                    portfolio_value: 0.0,
+                    cash: 0.0,
+                    positions_value: 0.0,
+                    open_positions: 0,
+                    last_processed_symbols: vec![],
+                    drawdown_pct: 0.0,
+                    consecutive_losses: 0,
+                    paper_mode: true,
+                    config_hash: String::new(),
+                    metadata: HashMap::new(),
+                })
+                .unwrap();
+        }
+        manager
+            .record_trade(&TradeRecord {
+                symbol: "BTCINR".to_string(),
+                net_pnl: 12.5,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let cycle = |at: &str| {
+            let at: CheckpointRef = at.parse().unwrap();
+            manager
+                .find_checkpoint(at)
+                .unwrap()
+                .map(|(_, c)| c.cycle_count)
+        };
+        assert_eq!(cycle("latest"), Some(3));
+        assert_eq!(cycle("~2"), Some(1));
+        assert_eq!(cycle("~3"), None);
+        assert_eq!(cycle("2"), Some(2));
+        assert_eq!(cycle("2025-06-01"), Some(2));
+        assert_eq!(cycle("2025-06-01T12:00"), Some(1));
+        assert_eq!(cycle("2025-05-31"), None);
+        assert!("yesterday".parse::<CheckpointRef>().is_err());
+
+        let trades = manager.load_trades().unwrap();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].net_pnl, 12.5);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
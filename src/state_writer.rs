@@ -0,0 +1,264 @@
+//! Background state persistence
+//!
+//! The live loop sends [`StateEvent`]s over a bounded channel instead of
+//! writing SQLite inline. A dedicated blocking task collects events until the
+//! loop marks the end of a cycle ([`StateWriter::commit`]) and writes the whole
+//! cycle in one transaction.
+//!
+//! - **Backpressure**: when the writer falls `capacity` events behind,
+//!   [`StateWriter::send`] waits instead of queueing without bound
+//! - **Flush**: [`StateWriter::flush`] returns once everything sent before it
+//!   is on disk (e.g. before reading fills back for reconciliation)
+//! - **Shutdown**: [`StateWriter::shutdown`] drains and commits the queue, so
+//!   no event sent before shutdown is lost
+
+use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+
+use crate::accounts::AccountBalance;
+use crate::state_manager::{
+    Checkpoint, FillRecord, IntentStatus, PendingOrder, Position, SqliteStateManager, TradeRecord,
+};
+
+/// Default channel capacity, in events
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// Events buffered before a batch is written even without a commit marker
+pub const MAX_BATCH: usize = 512;
+
+/// One state change to persist
+#[derive(Debug, Clone)]
+pub enum StateEvent {
+    Checkpoint(Checkpoint),
+    Position(Position),
+    Fill(FillRecord),
+    Trade(TradeRecord),
+    /// Move an order intent along its lifecycle (commits with the fills it describes)
+    Intent {
+        intent_id: String,
+        status: IntentStatus,
+        exchange_order_id: Option<String>,
+    },
+    /// Replace the whole pending-order cache
+    ReplacePendingOrders(Vec<PendingOrder>),
+    /// Latest capital and results of one exchange account
+    AccountBalance(AccountBalance),
+}
+
+enum Message {
+    Event(Box<StateEvent>),
+    /// End of a batch; the sender (if any) gets the commit result
+    Commit(Option<oneshot::Sender<Result<()>>>),
+}
+
+/// Counters shared with the writer task
+#[derive(Debug, Default)]
+pub struct WriterStats {
+    pub events: AtomicU64,
+    pub batches: AtomicU64,
+    pub failed_batches: AtomicU64,
+    /// Sends that had to wait for room in the channel
+    pub backpressure_waits: AtomicU64,
+}
+
+/// Handle to the background writer
+pub struct StateWriter {
+    tx: mpsc::Sender<Message>,
+    task: JoinHandle<()>,
+    stats: Arc<WriterStats>,
+}
+
+impl StateWriter {
+    /// Start the writer task on its own connection
+    pub fn spawn(manager: SqliteStateManager, capacity: usize) -> Self {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        let stats = Arc::new(WriterStats::default());
+        let task_stats = stats.clone();
+        let task = tokio::task::spawn_blocking(move || writer_loop(manager, rx, &task_stats));
+        Self { tx, task, stats }
+    }
+
+    /// Queue an event, waiting if the writer is `capacity` events behind
+    pub async fn send(&self, event: StateEvent) -> Result<()> {
+        self.push(Message::Event(Box::new(event))).await
+    }
+
+    /// Mark the end of a cycle: everything queued so far is written in one transaction
+    pub async fn commit(&self) -> Result<()> {
+        self.push(Message::Commit(None)).await
+    }
+
+    /// Commit and wait until everything sent so far is on disk
+    pub async fn flush(&self) -> Result<()> {
+        let (ack, done) = oneshot::channel();
+        self.push(Message::Commit(Some(ack))).await?;
+        done.await.context("State writer stopped before flushing")?
+    }
+
+    /// Write everything still queued and stop the task
+    pub async fn shutdown(self) -> Result<()> {
+        let result = self.flush().await;
+        drop(self.tx);
+        self.task.await.context("State writer task panicked")?;
+        result
+    }
+
+    pub fn stats(&self) -> &WriterStats {
+        &self.stats
+    }
+
+    async fn push(&self, message: Message) -> Result<()> {
+        match self.tx.try_send(message) {
+            Ok(()) => Ok(()),
+            Err(mpsc::error::TrySendError::Full(message)) => {
+                self.stats
+                    .backpressure_waits
+                    .fetch_add(1, Ordering::Relaxed);
+                warn!("State writer is behind - waiting for the queue to drain");
+                self.tx
+                    .send(message)
+                    .await
+                    .map_err(|_| anyhow::anyhow!("State writer stopped"))
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => anyhow::bail!("State writer stopped"),
+        }
+    }
+}
+
+fn writer_loop(manager: SqliteStateManager, mut rx: mpsc::Receiver<Message>, stats: &WriterStats) {
+    let mut batch: Vec<StateEvent> = Vec::new();
+    let write = |batch: &mut Vec<StateEvent>| -> Result<()> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let result = manager.write_batch(batch);
+        stats.batches.fetch_add(1, Ordering::Relaxed);
+        match &result {
+            Ok(()) => {
+                stats
+                    .events
+                    .fetch_add(batch.len() as u64, Ordering::Relaxed);
+                debug!("State writer committed {} events", batch.len());
+            }
+            Err(e) => {
+                stats.failed_batches.fetch_add(1, Ordering::Relaxed);
+                error!("State batch of {} events failed: {}", batch.len(), e);
+            }
+        }
+        batch.clear();
+        result
+    };
+
+    while let Some(message) = rx.blocking_recv() {
+        match message {
+            Message::Event(event) => {
+                batch.push(*event);
+                if batch.len() >= MAX_BATCH {
+                    let _ = write(&mut batch);
+                }
+            }
+            Message::Commit(ack) => {
+                let result = write(&mut batch);
+                if let Some(ack) = ack {
+                    let _ = ack.send(result);
+                }
+            }
+        }
+    }
+    // Channel closed: nothing sent before shutdown is dropped
+    let _ = write(&mut batch);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn fill(i: usize) -> FillRecord {
+        FillRecord {
+            id: None,
+            order_id: format!("order-{}", i),
+            symbol: "BTCINR".to_string(),
+            side: "buy".to_string(),
+            quantity: 0.01,
+            price: 5_000_000.0,
+            fee: 10.0,
+            timestamp: format!("2024-01-01T00:00:{:02}.000Z", i % 60),
+            paper: true,
+            fill_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batched_writes_flush_and_shutdown() {
+        let dir = std::env::temp_dir().join(format!("state_writer_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let reader = crate::state_manager::create_state_manager(&dir, "sqlite").unwrap();
+        // Tiny channel to exercise backpressure
+        let writer = StateWriter::spawn(reader.try_clone().unwrap(), 2);
+
+        for i in 0..10 {
+            writer.send(StateEvent::Fill(fill(i))).await.unwrap();
+        }
+        writer
+            .send(StateEvent::ReplacePendingOrders(vec![PendingOrder {
+                order_id: "o1".to_string(),
+                symbol: "BTCINR".to_string(),
+                side: "sell".to_string(),
+                order_type: "limit".to_string(),
+                quantity: 0.01,
+                limit_price: Some(5_100_000.0),
+                stop_price: None,
+                client_id: None,
+            }]))
+            .await
+            .unwrap();
+        writer.flush().await.unwrap();
+        assert_eq!(reader.load_fills("2024-01-01").unwrap().len(), 10);
+        assert_eq!(reader.load_pending_orders().unwrap().len(), 1);
+
+        // Events queued without a commit are written on shutdown
+        writer
+            .send(StateEvent::Position(Position {
+                symbol: "BTCINR".to_string(),
+                side: "buy".to_string(),
+                quantity: 0.01,
+                entry_price: 5_000_000.0,
+                entry_time: None,
+                stop_loss: 4_900_000.0,
+                take_profit: 5_200_000.0,
+                status: "open".to_string(),
+                order_id: None,
+                pnl: 0.0,
+                exit_price: 0.0,
+                exit_time: None,
+                metadata: HashMap::new(),
+            }))
+            .await
+            .unwrap();
+        writer
+            .send(StateEvent::ReplacePendingOrders(vec![]))
+            .await
+            .unwrap();
+        let mut balance = AccountBalance::new("alts", 20_000.0);
+        for pnl in [150.0, -40.0] {
+            balance.realized_pnl += pnl;
+            writer
+                .send(StateEvent::AccountBalance(balance.clone()))
+                .await
+                .unwrap();
+        }
+        assert_eq!(writer.stats().events.load(Ordering::Relaxed), 11);
+        writer.shutdown().await.unwrap();
+        assert_eq!(reader.load_positions(Some("open")).unwrap().len(), 1);
+        assert!(reader.load_pending_orders().unwrap().is_empty());
+        // One row per account, holding its latest state
+        assert_eq!(reader.load_account_balances().unwrap(), [balance]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,944 @@
+//! Strategy combinators
+//!
+//! Wrap any registered strategy with an extra layer instead of writing a new
+//! strategy:
+//!
+//! - [`Filtered`]: entries only while a [`Condition`] holds (e.g. volatility band)
+//! - [`Gated`]: entries only while a regime [`Condition`] holds, optionally
+//!   flattening the position when the regime turns off (e.g. daily trend gate)
+//! - [`RiskOverlay`]: tighter stops, scaled size and a drawdown pause ([`RiskRules`])
+//! - [`PositioningFilter`]: no longs while perp basis and funding sit at extreme
+//!   positive percentiles, no shorts at extreme negative ones ([`PositioningRules`])
+//!
+//! Exits generated by the wrapped strategy always pass through. Layers are
+//! listed under `strategy.combinators` and applied in order (first = innermost):
+//!
+//! ```json
+//! "strategy": {
+//!     "name": "volatility_regime",
+//!     "combinators": [
+//!         { "type": "filter", "condition": { "kind": "atr_pct", "period": 14, "min": 0.5, "max": 6.0 } },
+//!         { "type": "gate", "regime": { "kind": "trend", "ema": 50, "timeframe": "1d" }, "flatten": true },
+//!         { "type": "risk_overlay", "rules": { "max_stop_pct": 5.0, "size_multiplier": 0.5 } },
+//!         { "type": "positioning", "rules": { "upper_pct": 90, "lower_pct": 10, "lookback": 90 } }
+//!     ]
+//! }
+//! ```
+//!
+//! Conditions read the traded symbol's candles, or another timeframe of it
+//! when `timeframe` is set (the wrapper then requests that timeframe).
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::indicators;
+use crate::oms::{Fill, Order, OrderRequest, OrderType, Position, StrategyContext};
+use crate::positioning::{PositioningReading, PositioningSeries, DEFAULT_POSITIONING_DIR};
+use crate::{Candle, Side, Trade};
+
+use super::{SignalContext, Strategy};
+
+/// Indicator warm-up multiple: EMA/ATR/ADX are computed on the last
+/// `period * WINDOW_MULTIPLE` candles rather than the full history
+const WINDOW_MULTIPLE: usize = 5;
+
+// =============================================================================
+// Conditions
+// =============================================================================
+
+/// Market condition evaluated on the latest candles
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Condition {
+    /// Close above its EMA (below when `below` is set)
+    Trend {
+        ema: usize,
+        #[serde(default)]
+        below: bool,
+        #[serde(default)]
+        timeframe: Option<String>,
+    },
+    /// ATR as a percent of close within `[min, max]`
+    AtrPct {
+        period: usize,
+        #[serde(default)]
+        min: Option<f64>,
+        #[serde(default)]
+        max: Option<f64>,
+        #[serde(default)]
+        timeframe: Option<String>,
+    },
+    /// Last bar's volume at least `min_ratio` times its `period` average
+    Volume {
+        period: usize,
+        min_ratio: f64,
+        #[serde(default)]
+        timeframe: Option<String>,
+    },
+    /// ADX at least `min` (trending market)
+    Adx {
+        period: usize,
+        min: f64,
+        #[serde(default)]
+        timeframe: Option<String>,
+    },
+}
+
+impl Condition {
+    pub fn timeframe(&self) -> Option<&str> {
+        match self {
+            Condition::Trend { timeframe, .. }
+            | Condition::AtrPct { timeframe, .. }
+            | Condition::Volume { timeframe, .. }
+            | Condition::Adx { timeframe, .. } => timeframe.as_deref(),
+        }
+    }
+
+    /// Evaluate on `candles` (oldest first); `None` until there is enough history
+    pub fn evaluate(&self, candles: &[Candle]) -> Option<bool> {
+        match self {
+            Condition::Trend { ema, below, .. } => {
+                let window = tail(candles, ema * WINDOW_MULTIPLE)?;
+                let closes: Vec<f64> = window.iter().map(|c| c.close).collect();
+                let ema = (*indicators::ema(&closes, *ema).last()?)?;
+                let close = *closes.last()?;
+                Some(if *below { close < ema } else { close > ema })
+            }
+            Condition::AtrPct {
+                period, min, max, ..
+            } => {
+                let window = tail(candles, period * WINDOW_MULTIPLE)?;
+                let (high, low, close) = hlc(window);
+                let pct = (*indicators::atr_percent(&high, &low, &close, *period).last()?)?;
+                Some(min.is_none_or(|m| pct >= m) && max.is_none_or(|m| pct <= m))
+            }
+            Condition::Volume {
+                period, min_ratio, ..
+            } => {
+                let window = tail(candles, period + 1)?;
+                let (last, history) = window.split_last()?;
+                let avg = history.iter().map(|c| c.volume).sum::<f64>() / history.len() as f64;
+                Some(avg > 0.0 && last.volume >= avg * min_ratio)
+            }
+            Condition::Adx { period, min, .. } => {
+                let window = tail(candles, period * WINDOW_MULTIPLE)?;
+                let (high, low, close) = hlc(window);
+                let adx = (*indicators::adx(&high, &low, &close, *period).last()?)?;
+                Some(adx >= *min)
+            }
+        }
+    }
+
+    /// Evaluate against the context; insufficient history counts as not holding
+    pub fn holds(&self, ctx: &StrategyContext) -> bool {
+        let candles = match self.timeframe() {
+            Some(tf) => match ctx.get_timeframe(tf) {
+                Some(c) => c,
+                None => return false,
+            },
+            None => ctx.candles,
+        };
+        self.evaluate(candles).unwrap_or(false)
+    }
+}
+
+/// Last `n` candles, or `None` if there are fewer
+fn tail(candles: &[Candle], n: usize) -> Option<&[Candle]> {
+    (n > 0 && candles.len() >= n).then(|| &candles[candles.len() - n..])
+}
+
+fn hlc(candles: &[Candle]) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    (
+        candles.iter().map(|c| c.high).collect(),
+        candles.iter().map(|c| c.low).collect(),
+        candles.iter().map(|c| c.close).collect(),
+    )
+}
+
+// =============================================================================
+// Layers
+// =============================================================================
+
+/// Behaviour a combinator adds on top of the wrapped strategy
+pub trait Layer: Clone + Send + Sync + 'static {
+    /// Extra timeframes the layer reads
+    fn timeframes(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    /// Post-process the wrapped strategy's orders
+    fn orders(&self, _ctx: &StrategyContext, orders: Vec<OrderRequest>) -> Vec<OrderRequest> {
+        orders
+    }
+
+    /// Adjust the wrapped strategy's stop loss
+    fn stop_loss(&self, _entry_price: f64, _side: Side, stop: f64) -> f64 {
+        stop
+    }
+
+    /// Adjust the wrapped strategy's take profit
+    fn take_profit(&self, _entry_price: f64, _side: Side, target: f64) -> f64 {
+        target
+    }
+
+    /// Adjust the wrapped strategy's trailing stop update
+    fn trailing_stop(&self, stop: Option<f64>) -> Option<f64> {
+        stop
+    }
+
+    /// Adjust the wrapped strategy's regime score (position size multiplier)
+    fn regime_score(&self, score: f64) -> f64 {
+        score
+    }
+}
+
+/// Orders that open or add to a position (anything else is an exit)
+fn is_entry(ctx: &StrategyContext, order: &OrderRequest) -> bool {
+    ctx.current_position.is_none_or(|p| p.side == order.side)
+}
+
+fn drop_entries(ctx: &StrategyContext, orders: Vec<OrderRequest>) -> Vec<OrderRequest> {
+    orders.into_iter().filter(|o| !is_entry(ctx, o)).collect()
+}
+
+/// `timeframe` as `&'static str` for [`Strategy::required_timeframes`]
+///
+/// Interned so rebuilding strategies (e.g. per optimizer combination) does not
+/// leak a copy per instance.
+fn intern(timeframe: &str) -> &'static str {
+    static INTERNED: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    let mut set = INTERNED.get_or_init(Default::default).lock().unwrap();
+    if let Some(existing) = set.get(timeframe) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(timeframe.to_string().into_boxed_str());
+    set.insert(leaked);
+    leaked
+}
+
+impl Layer for Condition {
+    fn timeframes(&self) -> Vec<&'static str> {
+        self.timeframe().map(intern).into_iter().collect()
+    }
+
+    fn orders(&self, ctx: &StrategyContext, orders: Vec<OrderRequest>) -> Vec<OrderRequest> {
+        if orders.iter().any(|o| is_entry(ctx, o)) && !self.holds(ctx) {
+            return drop_entries(ctx, orders);
+        }
+        orders
+    }
+}
+
+/// Regime gate: no entries while `regime` is off
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gate {
+    pub regime: Condition,
+    /// Also exit an open position when the regime turns off
+    #[serde(default)]
+    pub flatten: bool,
+}
+
+impl Layer for Gate {
+    fn timeframes(&self) -> Vec<&'static str> {
+        self.regime.timeframes()
+    }
+
+    fn orders(&self, ctx: &StrategyContext, orders: Vec<OrderRequest>) -> Vec<OrderRequest> {
+        if self.regime.holds(ctx) {
+            return orders;
+        }
+        let mut orders = drop_entries(ctx, orders);
+        if let Some(pos) = ctx.current_position.filter(|_| self.flatten) {
+            let (exit_side, exit) = match pos.side {
+                Side::Buy => (Side::Sell, OrderRequest::market_sell as fn(_, _) -> _),
+                Side::Sell => (Side::Buy, OrderRequest::market_buy as fn(_, _) -> _),
+            };
+            let exiting = orders.iter().any(|o| o.side == exit_side)
+                || ctx.open_orders.iter().any(|o| o.side == exit_side);
+            if !exiting {
+                orders.push(exit(ctx.symbol.clone(), pos.quantity.to_f64()));
+            }
+        }
+        orders
+    }
+}
+
+/// Extra risk limits on top of the wrapped strategy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskRules {
+    /// Stop no farther than this percent from entry
+    #[serde(default)]
+    pub max_stop_pct: Option<f64>,
+    /// Multiplier on the strategy's regime score (position size)
+    #[serde(default = "default_size_multiplier")]
+    pub size_multiplier: f64,
+    /// No entries while equity is more than this percent below its peak
+    #[serde(default)]
+    pub max_drawdown_pct: Option<f64>,
+}
+
+impl Default for RiskRules {
+    fn default() -> Self {
+        Self {
+            max_stop_pct: None,
+            size_multiplier: default_size_multiplier(),
+            max_drawdown_pct: None,
+        }
+    }
+}
+
+fn default_size_multiplier() -> f64 {
+    1.0
+}
+
+impl Layer for RiskRules {
+    fn orders(&self, ctx: &StrategyContext, orders: Vec<OrderRequest>) -> Vec<OrderRequest> {
+        let Some(limit) = self.max_drawdown_pct else {
+            return orders;
+        };
+        let drawdown = if ctx.peak_equity > 0.0 {
+            (ctx.peak_equity - ctx.equity) / ctx.peak_equity * 100.0
+        } else {
+            0.0
+        };
+        if drawdown > limit {
+            return drop_entries(ctx, orders);
+        }
+        orders
+    }
+
+    fn stop_loss(&self, entry_price: f64, side: Side, stop: f64) -> f64 {
+        let Some(pct) = self.max_stop_pct else {
+            return stop;
+        };
+        let max_distance = entry_price * pct / 100.0;
+        match side {
+            Side::Buy => stop.max(entry_price - max_distance),
+            Side::Sell => stop.min(entry_price + max_distance),
+        }
+    }
+
+    fn regime_score(&self, score: f64) -> f64 {
+        score * self.size_multiplier
+    }
+}
+
+/// When perp positioning counts as crowded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PositioningRules {
+    /// Directory with one positioning CSV per symbol (see [`crate::positioning`])
+    #[serde(default = "default_positioning_dir")]
+    pub dir: String,
+    /// Samples in the percentile window (90 = 30 days of 8h funding)
+    #[serde(default = "default_positioning_lookback")]
+    pub lookback: usize,
+    /// Longs are skipped with basis/funding positive and at or above this percentile
+    #[serde(default = "default_upper_pct")]
+    pub upper_pct: f64,
+    /// Shorts are skipped with basis/funding negative and at or below this percentile
+    #[serde(default = "default_lower_pct")]
+    pub lower_pct: f64,
+    /// Both basis and funding must be extreme (otherwise either is enough)
+    #[serde(default = "default_require_both")]
+    pub require_both: bool,
+    /// Samples older than this say nothing about the current bar
+    #[serde(default = "default_max_age_hours")]
+    pub max_age_hours: i64,
+}
+
+impl Default for PositioningRules {
+    fn default() -> Self {
+        Self {
+            dir: default_positioning_dir(),
+            lookback: default_positioning_lookback(),
+            upper_pct: default_upper_pct(),
+            lower_pct: default_lower_pct(),
+            require_both: default_require_both(),
+            max_age_hours: default_max_age_hours(),
+        }
+    }
+}
+
+fn default_positioning_dir() -> String {
+    DEFAULT_POSITIONING_DIR.to_string()
+}
+
+fn default_positioning_lookback() -> usize {
+    90
+}
+
+fn default_upper_pct() -> f64 {
+    90.0
+}
+
+fn default_lower_pct() -> f64 {
+    10.0
+}
+
+fn default_require_both() -> bool {
+    true
+}
+
+fn default_max_age_hours() -> i64 {
+    24
+}
+
+impl PositioningRules {
+    /// Entries on `side` would join a crowded trade
+    pub fn is_crowded(&self, reading: &PositioningReading, side: Side) -> bool {
+        let s = &reading.sample;
+        let (basis, funding) = match side {
+            Side::Buy => (
+                s.basis > 0.0 && reading.basis_pct >= self.upper_pct,
+                s.funding_rate > 0.0 && reading.funding_pct >= self.upper_pct,
+            ),
+            Side::Sell => (
+                s.basis < 0.0 && reading.basis_pct <= self.lower_pct,
+                s.funding_rate < 0.0 && reading.funding_pct <= self.lower_pct,
+            ),
+        };
+        if self.require_both {
+            basis && funding
+        } else {
+            basis || funding
+        }
+    }
+}
+
+/// Per-symbol series, reloaded when the file changes
+#[derive(Default)]
+struct SeriesCache {
+    entries: HashMap<String, CachedSeries>,
+}
+
+struct CachedSeries {
+    series: Option<Arc<PositioningSeries>>,
+    /// Supplied in code, never reloaded
+    #[cfg(feature = "native")]
+    pinned: bool,
+    #[cfg(feature = "native")]
+    modified: Option<std::time::SystemTime>,
+    #[cfg(feature = "native")]
+    checked: std::time::Instant,
+}
+
+/// Seconds between checks for an updated positioning file
+#[cfg(feature = "native")]
+const POSITIONING_RECHECK_SECS: u64 = 60;
+
+/// [`PositioningRules`] plus the loaded series
+#[derive(Clone)]
+pub struct Positioning {
+    pub rules: PositioningRules,
+    cache: Arc<Mutex<SeriesCache>>,
+    /// Time between checks for an updated file
+    #[cfg(feature = "native")]
+    recheck: std::time::Duration,
+}
+
+impl Positioning {
+    pub fn new(rules: PositioningRules) -> Self {
+        Self {
+            rules,
+            cache: Arc::default(),
+            #[cfg(feature = "native")]
+            recheck: std::time::Duration::from_secs(POSITIONING_RECHECK_SECS),
+        }
+    }
+
+    /// Series for `symbol`; `None` when there is no file for it
+    fn series(&self, symbol: &str) -> Option<Arc<PositioningSeries>> {
+        #[cfg(feature = "native")]
+        self.refresh(&mut self.cache.lock().unwrap(), symbol);
+        let cache = self.cache.lock().unwrap();
+        cache.entries.get(symbol).and_then(|c| c.series.clone())
+    }
+
+    /// (Re)load the file for `symbol` on first use and when it changes
+    #[cfg(feature = "native")]
+    fn refresh(&self, cache: &mut SeriesCache, symbol: &str) {
+        let path = crate::positioning::positioning_path(&self.rules.dir, symbol);
+        let modified = || std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+        if let Some(c) = cache.entries.get_mut(symbol) {
+            // Only a completed comparison restarts the clock, so frequent
+            // callers still see the file change once `recheck` has passed
+            if c.pinned || c.checked.elapsed() < self.recheck {
+                return;
+            }
+            c.checked = std::time::Instant::now();
+            if modified() == c.modified {
+                return;
+            }
+        }
+
+        let modified = modified();
+        let series = match modified {
+            Some(_) => match PositioningSeries::load(&path) {
+                Ok(series) => Some(Arc::new(series)),
+                Err(e) => {
+                    tracing::warn!("Positioning filter disabled for {}: {:#}", symbol, e);
+                    None
+                }
+            },
+            None => {
+                tracing::warn!(
+                    "No positioning data at {} - {} entries are not filtered",
+                    path.display(),
+                    symbol
+                );
+                None
+            }
+        };
+        cache.entries.insert(
+            symbol.to_string(),
+            CachedSeries {
+                series,
+                pinned: false,
+                modified,
+                checked: std::time::Instant::now(),
+            },
+        );
+    }
+
+    /// Use `series` for `symbol` instead of reading it from `dir`
+    pub fn with_series(self, symbol: &str, series: PositioningSeries) -> Self {
+        self.cache.lock().unwrap().entries.insert(
+            symbol.to_string(),
+            CachedSeries {
+                series: Some(Arc::new(series)),
+                #[cfg(feature = "native")]
+                pinned: true,
+                #[cfg(feature = "native")]
+                modified: None,
+                #[cfg(feature = "native")]
+                checked: std::time::Instant::now(),
+            },
+        );
+        self
+    }
+}
+
+impl Layer for Positioning {
+    fn orders(&self, ctx: &StrategyContext, orders: Vec<OrderRequest>) -> Vec<OrderRequest> {
+        if !orders.iter().any(|o| is_entry(ctx, o)) {
+            return orders;
+        }
+        let Some(bar) = ctx.candles.last() else {
+            return orders;
+        };
+        let Some(series) = self.series(ctx.symbol.as_str()) else {
+            return orders;
+        };
+        let Some(reading) = series.reading_at(
+            bar.datetime,
+            self.rules.lookback,
+            chrono::Duration::hours(self.rules.max_age_hours),
+        ) else {
+            return orders;
+        };
+        orders
+            .into_iter()
+            .filter(|o| !(is_entry(ctx, o) && self.rules.is_crowded(&reading, o.side)))
+            .collect()
+    }
+}
+
+// =============================================================================
+// Wrapper
+// =============================================================================
+
+/// A strategy with one [`Layer`] applied
+pub struct Combined<L: Layer> {
+    inner: Box<dyn Strategy>,
+    layer: L,
+}
+
+/// Entries only while a condition holds
+pub type Filtered = Combined<Condition>;
+/// Entries only in a regime, optionally flattening outside it
+pub type Gated = Combined<Gate>;
+/// Extra stop, size and drawdown limits
+pub type RiskOverlay = Combined<RiskRules>;
+/// No entries into crowded perp positioning
+pub type PositioningFilter = Combined<Positioning>;
+
+impl<L: Layer> Combined<L> {
+    pub fn new(inner: Box<dyn Strategy>, layer: L) -> Self {
+        Self { inner, layer }
+    }
+
+    pub fn layer(&self) -> &L {
+        &self.layer
+    }
+}
+
+impl<L: Layer> Strategy for Combined<L> {
+    /// The wrapped strategy's name, so configs, state and kill files still match
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn clone_boxed(&self) -> Box<dyn Strategy> {
+        Box::new(Self::new(self.inner.clone_boxed(), self.layer.clone()))
+    }
+
+    fn required_timeframes(&self) -> Vec<&'static str> {
+        let mut tfs = self.inner.required_timeframes();
+        for tf in self.layer.timeframes() {
+            if !tfs.contains(&tf) {
+                tfs.push(tf);
+            }
+        }
+        tfs
+    }
+
+    fn required_order_types(&self) -> Vec<OrderType> {
+        self.inner.required_order_types()
+    }
+
+    fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
+        self.layer.orders(ctx, self.inner.generate_signal(ctx))
+    }
+
+    fn commentary(&self, ctx: &SignalContext, order: &OrderRequest) -> Option<String> {
+        self.inner.commentary(ctx, order)
+    }
+
+    fn calculate_stop_loss(&self, candles: &[Candle], entry_price: f64, side: Side) -> f64 {
+        let stop = self.inner.calculate_stop_loss(candles, entry_price, side);
+        self.layer.stop_loss(entry_price, side, stop)
+    }
+
+    fn calculate_take_profit(&self, candles: &[Candle], entry_price: f64, side: Side) -> f64 {
+        let target = self.inner.calculate_take_profit(candles, entry_price, side);
+        self.layer.take_profit(entry_price, side, target)
+    }
+
+    fn update_trailing_stop(
+        &self,
+        position: &Position,
+        current_price: f64,
+        candles: &[Candle],
+    ) -> Option<f64> {
+        self.layer.trailing_stop(
+            self.inner
+                .update_trailing_stop(position, current_price, candles),
+        )
+    }
+
+    fn get_regime_score(&self, candles: &[Candle]) -> f64 {
+        self.layer
+            .regime_score(self.inner.get_regime_score(candles))
+    }
+
+    fn on_order_filled(&mut self, fill: &Fill, position: &Position) {
+        self.inner.on_order_filled(fill, position);
+    }
+
+    fn on_order_cancelled(&mut self, order: &Order) {
+        self.inner.on_order_cancelled(order);
+    }
+
+    fn on_trade_closed(&mut self, trade: &Trade) {
+        self.inner.on_trade_closed(trade);
+    }
+
+    fn on_bar(&mut self, ctx: &StrategyContext) {
+        self.inner.on_bar(ctx);
+    }
+
+    fn init(&mut self) {
+        self.inner.init();
+    }
+}
+
+// =============================================================================
+// Config
+// =============================================================================
+
+/// One entry of `strategy.combinators`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CombinatorConfig {
+    Filter {
+        condition: Condition,
+    },
+    Gate {
+        regime: Condition,
+        #[serde(default)]
+        flatten: bool,
+    },
+    RiskOverlay {
+        #[serde(default)]
+        rules: RiskRules,
+    },
+    Positioning {
+        #[serde(default)]
+        rules: PositioningRules,
+    },
+}
+
+impl CombinatorConfig {
+    pub fn wrap(&self, inner: Box<dyn Strategy>) -> Box<dyn Strategy> {
+        match self {
+            CombinatorConfig::Filter { condition } => {
+                Box::new(Filtered::new(inner, condition.clone()))
+            }
+            CombinatorConfig::Gate { regime, flatten } => Box::new(Gated::new(
+                inner,
+                Gate {
+                    regime: regime.clone(),
+                    flatten: *flatten,
+                },
+            )),
+            CombinatorConfig::RiskOverlay { rules } => {
+                Box::new(RiskOverlay::new(inner, rules.clone()))
+            }
+            CombinatorConfig::Positioning { rules } => Box::new(PositioningFilter::new(
+                inner,
+                Positioning::new(rules.clone()),
+            )),
+        }
+    }
+}
+
+/// Wrap `strategy` with the combinators listed in the strategy section, if any
+pub fn apply_from_config(
+    strategy: Box<dyn Strategy>,
+    strategy_section: &serde_json::Value,
+) -> Result<Box<dyn Strategy>> {
+    let Some(list) = strategy_section.get("combinators") else {
+        return Ok(strategy);
+    };
+    let combinators: Vec<CombinatorConfig> =
+        serde_json::from_value(list.clone()).context("Invalid strategy.combinators")?;
+    Ok(combinators
+        .iter()
+        .fold(strategy, |inner, combinator| combinator.wrap(inner)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Symbol;
+    use chrono::{Duration, TimeZone, Utc};
+
+    /// Always wants to buy; fixed 10% stop
+    struct AlwaysBuy;
+
+    impl Strategy for AlwaysBuy {
+        fn name(&self) -> &'static str {
+            "always_buy"
+        }
+        fn clone_boxed(&self) -> Box<dyn Strategy> {
+            Box::new(AlwaysBuy)
+        }
+        fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
+            vec![OrderRequest::market_buy(ctx.symbol.clone(), 1.0)]
+        }
+        fn calculate_stop_loss(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+            entry_price * 0.9
+        }
+        fn calculate_take_profit(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+            entry_price * 1.2
+        }
+        fn update_trailing_stop(&self, _: &Position, _: f64, _: &[Candle]) -> Option<f64> {
+            None
+        }
+    }
+
+    fn candles(closes: impl Iterator<Item = f64>) -> Vec<Candle> {
+        let t0 = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        closes
+            .enumerate()
+            .map(|(i, c)| Candle {
+                datetime: t0 + Duration::hours(i as i64),
+                open: c,
+                high: c * 1.01,
+                low: c * 0.99,
+                close: c,
+                volume: 100.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_trend_filter_blocks_entries_in_downtrend() {
+        let symbol = Symbol::new("BTCINR");
+        let filtered = Filtered::new(
+            Box::new(AlwaysBuy),
+            Condition::Trend {
+                ema: 10,
+                below: false,
+                timeframe: None,
+            },
+        );
+
+        let up = candles((0..60).map(|i| 100.0 + i as f64));
+        let ctx = StrategyContext::single_timeframe(&symbol, &up, None, &[], 1e5, 1e5);
+        assert_eq!(filtered.generate_orders(&ctx).len(), 1);
+
+        let down = candles((0..60).map(|i| 200.0 - i as f64));
+        let ctx = StrategyContext::single_timeframe(&symbol, &down, None, &[], 1e5, 1e5);
+        assert!(filtered.generate_orders(&ctx).is_empty());
+
+        // Not enough history -> blocked
+        let ctx = StrategyContext::single_timeframe(&symbol, &up[..5], None, &[], 1e5, 1e5);
+        assert!(filtered.generate_orders(&ctx).is_empty());
+    }
+
+    #[test]
+    fn test_generate_orders_shim_calls_generate_signal() {
+        let symbol = Symbol::new("BTCINR");
+        let bars = candles((0..5).map(|i| 100.0 + i as f64));
+        let base = StrategyContext::single_timeframe(&symbol, &bars, None, &[], 1e5, 1e5);
+        // Callers holding a bare StrategyContext get the same orders
+        let orders = AlwaysBuy.generate_orders(&base);
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].symbol, symbol);
+        assert_eq!(orders[0].side, Side::Buy);
+
+        let overlay = RiskOverlay::new(Box::new(AlwaysBuy), RiskRules::default());
+        assert_eq!(overlay.generate_orders(&base).len(), 1);
+    }
+
+    #[test]
+    fn test_risk_overlay_tightens_stop_and_scales_size() {
+        let overlay = RiskOverlay::new(
+            Box::new(AlwaysBuy),
+            RiskRules {
+                max_stop_pct: Some(5.0),
+                size_multiplier: 0.5,
+                max_drawdown_pct: Some(10.0),
+            },
+        );
+        assert!((overlay.calculate_stop_loss(&[], 100.0, Side::Buy) - 95.0).abs() < 1e-9);
+        assert_eq!(overlay.get_regime_score(&[]), 0.5);
+
+        let symbol = Symbol::new("BTCINR");
+        let bars = candles((0..5).map(|i| 100.0 + i as f64));
+        let ctx = StrategyContext::single_timeframe(&symbol, &bars, None, &[], 8e4, 8e4)
+            .with_peak_equity(1e5);
+        assert!(overlay.generate_orders(&ctx).is_empty());
+    }
+
+    #[test]
+    fn test_positioning_filter_skips_crowded_longs() {
+        use crate::positioning::PositioningSample;
+
+        let symbol = Symbol::new("BTCINR");
+        let bars = candles((0..10).map(|i| 100.0 + i as f64));
+        let last = bars.last().unwrap().datetime;
+        let history = |basis: f64, funding: f64| {
+            let mut samples: Vec<_> = (1..=20)
+                .map(|i| PositioningSample {
+                    datetime: last - Duration::hours(8 * i),
+                    basis: 0.0001 * i as f64,
+                    funding_rate: 0.00001 * i as f64,
+                })
+                .collect();
+            samples.push(PositioningSample {
+                datetime: last,
+                basis,
+                funding_rate: funding,
+            });
+            PositioningSeries::new(samples)
+        };
+        let filter = |series| {
+            PositioningFilter::new(
+                Box::new(AlwaysBuy),
+                Positioning::new(PositioningRules {
+                    lookback: 21,
+                    ..PositioningRules::default()
+                })
+                .with_series("BTCINR", series),
+            )
+        };
+        let ctx = StrategyContext::single_timeframe(&symbol, &bars, None, &[], 1e5, 1e5);
+
+        // Basis and funding both at the top of their range: euphoric, skip the long
+        assert!(filter(history(0.01, 0.001))
+            .generate_orders(&ctx)
+            .is_empty());
+        // Only basis is extreme: both are required by default
+        assert_eq!(filter(history(0.01, 0.0)).generate_orders(&ctx).len(), 1);
+        // Unknown symbol: no data, nothing filtered
+        let other = Symbol::new("ETHINR");
+        let ctx = StrategyContext::single_timeframe(&other, &bars, None, &[], 1e5, 1e5);
+        let no_data = PositioningFilter::new(
+            Box::new(AlwaysBuy),
+            Positioning::new(PositioningRules {
+                dir: "/nonexistent".to_string(),
+                ..PositioningRules::default()
+            }),
+        );
+        assert_eq!(no_data.generate_orders(&ctx).len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn test_positioning_reloads_a_changed_file_under_frequent_calls() {
+        use crate::positioning::{positioning_path, PositioningSample};
+
+        let dir = std::env::temp_dir().join(format!("positioning_reload_{}", std::process::id()));
+        let path = positioning_path(&dir, "BTCINR");
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let series = |n: i64| {
+            PositioningSeries::new(
+                (0..n)
+                    .map(|i| PositioningSample {
+                        datetime: start + Duration::hours(8 * i),
+                        basis: 0.001,
+                        funding_rate: 0.0001,
+                    })
+                    .collect(),
+            )
+        };
+        series(3).save(&path).unwrap();
+
+        let mut positioning = Positioning::new(PositioningRules {
+            dir: dir.to_string_lossy().into_owned(),
+            ..PositioningRules::default()
+        });
+        positioning.recheck = std::time::Duration::from_millis(20);
+        assert_eq!(positioning.series("BTCINR").unwrap().samples().len(), 3);
+
+        series(5).save(&path).unwrap();
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(5);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        // Called far more often than `recheck`, as a live loop over many bars would
+        let polling = std::time::Instant::now();
+        while polling.elapsed() < std::time::Duration::from_millis(200) {
+            positioning.series("BTCINR");
+        }
+        assert_eq!(positioning.series("BTCINR").unwrap().samples().len(), 5);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_combinators_from_config() {
+        let section = serde_json::json!({
+            "name": "always_buy",
+            "combinators": [
+                { "type": "gate", "regime": { "kind": "trend", "ema": 20, "timeframe": "1d" } },
+                { "type": "risk_overlay", "rules": { "size_multiplier": 0.25 } }
+            ]
+        });
+        let wrapped = apply_from_config(Box::new(AlwaysBuy), &section).unwrap();
+        assert_eq!(wrapped.name(), "always_buy");
+        assert_eq!(wrapped.required_timeframes(), vec!["1d"]);
+        assert_eq!(wrapped.get_regime_score(&[]), 0.25);
+
+        let bad = serde_json::json!({ "combinators": [{ "type": "nope" }] });
+        assert!(apply_from_config(Box::new(AlwaysBuy), &bad).is_err());
+    }
+}
@@ -0,0 +1,319 @@
+//! Signal context handed to [`Strategy::generate_signal`](super::Strategy::generate_signal)
+//!
+//! Wraps the engine's [`StrategyContext`] (candle window, higher-timeframe
+//! views, position, equity) and adds what every strategy used to rebuild on
+//! each call: indicator series computed once per bar through an
+//! [`IndicatorCache`] and shared by `generate_signal`, `commentary` and any
+//! combinator layers, plus the portfolio's open risk.
+//!
+//! ```
+//! use crypto_strategies::oms::StrategyContext;
+//! use crypto_strategies::strategies::SignalContext;
+//! use crypto_strategies::{Candle, Symbol};
+//! use chrono::{Duration, TimeZone, Utc};
+//!
+//! let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+//! let candles: Vec<Candle> = (0..50)
+//!     .map(|i| {
+//!         let close = 100.0 + i as f64;
+//!         Candle::new(start + Duration::hours(i), close, close + 1.0, close - 1.0, close, 1.0)
+//!             .unwrap()
+//!     })
+//!     .collect();
+//! let symbol = Symbol::new("BTCINR");
+//! let base = StrategyContext::single_timeframe(&symbol, &candles, None, &[], 1e5, 1e5);
+//! let ctx = SignalContext::new(&base);
+//!
+//! assert!(ctx.ema(20).unwrap() < ctx.close().unwrap());
+//! assert_eq!(ctx.atr(14), Some(2.0));
+//! assert!(ctx.timeframe("1d").is_none());
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use crate::indicators::IndicatorCache;
+use crate::oms::{Position, StrategyContext};
+use crate::Candle;
+
+/// Indicators the context computes and caches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Indicator {
+    Sma,
+    Ema,
+    Atr,
+    Rsi,
+    Adx,
+}
+
+/// Price columns and cached indicators for one timeframe
+struct Series {
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    cache: IndicatorCache,
+}
+
+impl Series {
+    fn new(candles: &[Candle]) -> Self {
+        Self {
+            high: candles.iter().map(|c| c.high).collect(),
+            low: candles.iter().map(|c| c.low).collect(),
+            close: candles.iter().map(|c| c.close).collect(),
+            cache: IndicatorCache::new(),
+        }
+    }
+
+    fn get(&mut self, indicator: Indicator, period: usize) -> &Vec<Option<f64>> {
+        let Series {
+            high,
+            low,
+            close,
+            cache,
+        } = self;
+        match indicator {
+            Indicator::Sma => cache.get_sma(close, period),
+            Indicator::Ema => cache.get_ema(close, period),
+            Indicator::Rsi => cache.get_rsi(close, period),
+            Indicator::Atr => cache.get_atr(high, low, close, period),
+            Indicator::Adx => cache.get_adx(high, low, close, period),
+        }
+    }
+}
+
+/// Per-bar strategy input: the engine's context plus shared indicators
+///
+/// Dereferences to [`StrategyContext`], so `ctx.symbol`, `ctx.candles`,
+/// `ctx.current_position` and `ctx.equity` read as before.
+pub struct SignalContext<'a> {
+    base: &'a StrategyContext<'a>,
+    open_risk: f64,
+    /// Keyed by timeframe; `None` is the primary window
+    series: RefCell<HashMap<Option<String>, Series>>,
+}
+
+impl<'a> SignalContext<'a> {
+    pub fn new(base: &'a StrategyContext<'a>) -> Self {
+        Self {
+            base,
+            open_risk: 0.0,
+            series: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Set the portfolio's open risk (see [`open_risk`])
+    pub fn with_open_risk(mut self, open_risk: f64) -> Self {
+        self.open_risk = open_risk;
+        self
+    }
+
+    /// The engine's context
+    pub fn base(&self) -> &'a StrategyContext<'a> {
+        self.base
+    }
+
+    /// Capital lost if every open position hits its stop
+    pub fn open_risk(&self) -> f64 {
+        self.open_risk
+    }
+
+    /// Open risk as a fraction of equity
+    pub fn open_risk_pct(&self) -> f64 {
+        if self.base.equity > 0.0 {
+            self.open_risk / self.base.equity
+        } else {
+            0.0
+        }
+    }
+
+    /// Latest close of the primary window
+    pub fn close(&self) -> Option<f64> {
+        self.base.candles.last().map(|c| c.close)
+    }
+
+    /// Indicators on a higher timeframe, if the engine supplied it
+    pub fn timeframe(&self, tf: &str) -> Option<TimeframeView<'_, 'a>> {
+        self.base.get_timeframe(tf)?;
+        Some(TimeframeView {
+            ctx: self,
+            timeframe: tf.to_string(),
+        })
+    }
+
+    /// Full series of `indicator` over the primary window (cached)
+    pub fn series(&self, indicator: Indicator, period: usize) -> Vec<Option<f64>> {
+        self.compute(None, indicator, period, |s| s.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Latest value of `indicator` over the primary window (cached)
+    pub fn latest(&self, indicator: Indicator, period: usize) -> Option<f64> {
+        self.compute(None, indicator, period, |s| s.last().copied().flatten())
+            .flatten()
+    }
+
+    pub fn sma(&self, period: usize) -> Option<f64> {
+        self.latest(Indicator::Sma, period)
+    }
+
+    pub fn ema(&self, period: usize) -> Option<f64> {
+        self.latest(Indicator::Ema, period)
+    }
+
+    pub fn atr(&self, period: usize) -> Option<f64> {
+        self.latest(Indicator::Atr, period)
+    }
+
+    pub fn rsi(&self, period: usize) -> Option<f64> {
+        self.latest(Indicator::Rsi, period)
+    }
+
+    pub fn adx(&self, period: usize) -> Option<f64> {
+        self.latest(Indicator::Adx, period)
+    }
+
+    fn compute<R>(
+        &self,
+        timeframe: Option<&str>,
+        indicator: Indicator,
+        period: usize,
+        read: impl FnOnce(&[Option<f64>]) -> R,
+    ) -> Option<R> {
+        let candles = match timeframe {
+            Some(tf) => self.base.get_timeframe(tf)?,
+            None => self.base.candles,
+        };
+        let mut series = self.series.borrow_mut();
+        let entry = series
+            .entry(timeframe.map(str::to_string))
+            .or_insert_with(|| Series::new(candles));
+        Some(read(entry.get(indicator, period)))
+    }
+}
+
+impl<'a> Deref for SignalContext<'a> {
+    type Target = StrategyContext<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.base
+    }
+}
+
+/// Cached indicators over one higher timeframe of a [`SignalContext`]
+pub struct TimeframeView<'c, 'a> {
+    ctx: &'c SignalContext<'a>,
+    timeframe: String,
+}
+
+impl TimeframeView<'_, '_> {
+    pub fn candles(&self) -> &[Candle] {
+        self.ctx
+            .base
+            .get_timeframe(&self.timeframe)
+            .unwrap_or_default()
+    }
+
+    pub fn series(&self, indicator: Indicator, period: usize) -> Vec<Option<f64>> {
+        self.ctx
+            .compute(Some(&self.timeframe), indicator, period, |s| s.to_vec())
+            .unwrap_or_default()
+    }
+
+    pub fn latest(&self, indicator: Indicator, period: usize) -> Option<f64> {
+        self.ctx
+            .compute(Some(&self.timeframe), indicator, period, |s| {
+                s.last().copied().flatten()
+            })
+            .flatten()
+    }
+
+    pub fn ema(&self, period: usize) -> Option<f64> {
+        self.latest(Indicator::Ema, period)
+    }
+
+    pub fn atr(&self, period: usize) -> Option<f64> {
+        self.latest(Indicator::Atr, period)
+    }
+
+    pub fn rsi(&self, period: usize) -> Option<f64> {
+        self.latest(Indicator::Rsi, period)
+    }
+
+    pub fn adx(&self, period: usize) -> Option<f64> {
+        self.latest(Indicator::Adx, period)
+    }
+}
+
+/// Open risk of `positions`: the sum of their stop distance × quantity
+pub fn open_risk<'p>(positions: impl IntoIterator<Item = &'p Position>) -> f64 {
+    positions.into_iter().map(|p| p.risk_amount.to_f64()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators;
+    use crate::multi_timeframe::MultiTimeframeCandles;
+    use crate::Symbol;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn candles(n: i64, step: Duration) -> Vec<Candle> {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        (0..n)
+            .map(|i| {
+                let close = 100.0 + (i as f64 * 0.7).sin() * 5.0 + i as f64 * 0.1;
+                Candle::new(
+                    start + step * i as i32,
+                    close,
+                    close + 1.5,
+                    close - 1.0,
+                    close,
+                    1.0,
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_indicators_match_batch_functions_per_timeframe() {
+        let hourly = candles(120, Duration::hours(1));
+        let daily = candles(40, Duration::days(1));
+        let mut mtf = MultiTimeframeCandles::new("1h", hourly.last().unwrap().datetime);
+        mtf.add_timeframe("1h", &hourly);
+        mtf.add_timeframe("1d", &daily);
+        let symbol = Symbol::new("BTCINR");
+        let base = StrategyContext::multi_timeframe(&symbol, &mtf, None, &[], 1e5, 1e5);
+        let ctx = SignalContext::new(&base).with_open_risk(2_500.0);
+
+        let (high, low, close): (Vec<f64>, Vec<f64>, Vec<f64>) = (
+            hourly.iter().map(|c| c.high).collect(),
+            hourly.iter().map(|c| c.low).collect(),
+            hourly.iter().map(|c| c.close).collect(),
+        );
+        assert_eq!(ctx.ema(21), *indicators::ema(&close, 21).last().unwrap());
+        assert_eq!(ctx.rsi(14), *indicators::rsi(&close, 14).last().unwrap());
+        assert_eq!(
+            ctx.series(Indicator::Atr, 14),
+            indicators::atr(&high, &low, &close, 14)
+        );
+        assert_eq!(
+            ctx.adx(14),
+            *indicators::adx(&high, &low, &close, 14).last().unwrap()
+        );
+
+        let daily_close: Vec<f64> = daily.iter().map(|c| c.close).collect();
+        let d = ctx.timeframe("1d").unwrap();
+        assert_eq!(d.candles().len(), 40);
+        assert_eq!(
+            d.ema(10),
+            *indicators::ema(&daily_close, 10).last().unwrap()
+        );
+        assert_ne!(d.ema(10), ctx.ema(10));
+        assert!(ctx.timeframe("4h").is_none());
+
+        assert_eq!(ctx.open_risk_pct(), 0.025);
+        assert_eq!(ctx.symbol.as_str(), "BTCINR");
+    }
+}
@@ -9,14 +9,21 @@
 //! - quick_flip: Quick reversal trading
 //! - range_breakout: Range breakout strategy
 //! - regime_grid: Grid trading with regime detection
+//!
+//! Any of these can be wrapped with filters, regime gates and risk overlays
+//! from config (see [`combinators`]).
 
+pub mod combinators;
+pub mod context;
 pub mod momentum_scalper;
 pub mod quick_flip;
 pub mod range_breakout;
 pub mod regime_grid;
 pub mod volatility_regime;
 
-use crate::oms::{Fill, Order, OrderRequest, Position, StrategyContext};
+pub use context::{Indicator, SignalContext};
+
+use crate::oms::{Fill, Order, OrderRequest, OrderType, Position, StrategyContext};
 use crate::{Candle, Config, Side, Trade};
 use anyhow::Result;
 use std::collections::HashMap;
@@ -69,11 +76,36 @@ pub trait Strategy: Send + Sync {
         vec![]
     }
 
+    /// Declare the order types `generate_orders` can emit (default: market only)
+    ///
+    /// Checked against the exchange's capabilities at startup.
+    fn required_order_types(&self) -> Vec<OrderType> {
+        vec![OrderType::Market]
+    }
+
     /// Generate orders based on current market context
     ///
     /// This is the primary interface for OMS-based strategies.
     /// Returns a vector of order requests to be validated and executed.
-    fn generate_orders(&self, ctx: &StrategyContext) -> Vec<OrderRequest>;
+    /// Indicators read through `ctx` are computed once per bar and shared
+    /// with [`commentary`](Strategy::commentary) and wrapping combinators.
+    fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest>;
+
+    /// Generate orders from a bare [`StrategyContext`]
+    ///
+    /// Shim for callers written before `SignalContext`: wraps `ctx` without
+    /// open risk and calls [`generate_signal`](Strategy::generate_signal).
+    /// Engines call `generate_signal` directly.
+    fn generate_orders(&self, ctx: &StrategyContext) -> Vec<OrderRequest> {
+        self.generate_signal(&SignalContext::new(ctx))
+    }
+
+    /// Short human-readable rationale for `order` (indicator values, regime,
+    /// filter states), stored with the resulting trade and shown in reports
+    /// and notifications. Called once per order at signal time (default: none).
+    fn commentary(&self, _ctx: &SignalContext, _order: &OrderRequest) -> Option<String> {
+        None
+    }
 
     /// Calculate stop loss price for entry
     /// For Buy positions: stop is below entry (sell to cut loss)
@@ -168,7 +200,7 @@ pub fn create_strategy(config: &Config) -> Result<Box<dyn Strategy>> {
         )
     })?;
 
-    factory(config)
+    combinators::apply_from_config(factory(config)?, &config.strategy)
 }
 
 /// Get list of available strategy names
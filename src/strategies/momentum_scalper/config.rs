@@ -63,7 +63,7 @@ pub struct MomentumScalperConfig {
     pub cooldown_bars: usize,
 
     // === Position ===
-    /// Allow short selling (default: false)
+    /// Also short bearish alignments, mirroring the long entry (default: false)
     pub allow_short: bool,
 }
 
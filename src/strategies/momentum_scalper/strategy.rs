@@ -7,6 +7,9 @@
 //! 2. MACD histogram positive (momentum confirmation)
 //! 3. ADX above threshold (trend strength)
 //!
+//! With `allow_short`, the mirror image (fast below slow, MACD not bullish)
+//! opens a short.
+//!
 //! ## Exit Logic
 //! 1. Take profit at target ATR multiple
 //! 2. Stop loss at entry - stop ATR multiple
@@ -16,7 +19,7 @@
 
 use crate::indicators::{adx, atr, ema, macd};
 use crate::oms::{Fill, OrderRequest, StrategyContext};
-use crate::strategies::Strategy;
+use crate::strategies::{SignalContext, Strategy};
 use crate::{Candle, Position, Side, Symbol, Trade};
 use std::collections::HashMap;
 
@@ -47,9 +50,37 @@ impl Indicators {
         // Batch ADX calculation
         let adx_values = adx(&high, &low, &close, config.adx_period);
 
+        Self::with_trend(
+            &close,
+            config,
+            ema_fast.last().and_then(|&x| x),
+            ema_slow_vals.last().and_then(|&x| x),
+            adx_values.last().and_then(|&x| x),
+        )
+    }
+
+    /// EMAs and ADX from the signal context's per-bar cache
+    fn from_context(ctx: &SignalContext, config: &MomentumScalperConfig) -> Self {
+        let close: Vec<f64> = ctx.candles.iter().map(|c| c.close).collect();
+        Self::with_trend(
+            &close,
+            config,
+            ctx.ema(config.ema_fast),
+            ctx.ema(config.ema_slow),
+            ctx.adx(config.adx_period),
+        )
+    }
+
+    fn with_trend(
+        close: &[f64],
+        config: &MomentumScalperConfig,
+        current_ema_fast: Option<f64>,
+        current_ema_slow: Option<f64>,
+        current_adx: Option<f64>,
+    ) -> Self {
         // Batch MACD calculation
         let (macd_line, signal_line, histogram) = macd(
-            &close,
+            close,
             config.macd_fast,
             config.macd_slow,
             config.macd_signal,
@@ -63,9 +94,9 @@ impl Indicators {
         };
 
         Self {
-            current_ema_fast: ema_fast.last().and_then(|&x| x),
-            current_ema_slow: ema_slow_vals.last().and_then(|&x| x),
-            current_adx: adx_values.last().and_then(|&x| x),
+            current_ema_fast,
+            current_ema_slow,
+            current_adx,
             hist_curr,
             hist_prev,
             macd_curr: macd_line.last().and_then(|&x| x).unwrap_or(0.0),
@@ -194,7 +225,7 @@ impl Strategy for MomentumScalperStrategy {
         }
     }
 
-    fn generate_orders(&self, ctx: &StrategyContext) -> Vec<OrderRequest> {
+    fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
         let mut orders = Vec::new();
 
         let min_bars = self
@@ -215,41 +246,45 @@ impl Strategy for MomentumScalperStrategy {
             }
         }
 
-        // Calculate all indicators ONCE using batch functions
-        let ind = Indicators::new(ctx.candles, &self.config);
+        // Calculate all indicators ONCE (EMAs and ADX shared via the context)
+        let ind = Indicators::from_context(ctx, &self.config);
 
         // If in position, check exit conditions
         if let Some(pos) = ctx.current_position {
+            let is_long = pos.side == Side::Buy;
+            let close = || match pos.side {
+                Side::Buy => OrderRequest::market_sell(ctx.symbol.clone(), pos.quantity.to_f64()),
+                Side::Sell => OrderRequest::market_buy(ctx.symbol.clone(), pos.quantity.to_f64()),
+            };
+
             // Exit on EMA cross
-            if self.should_exit_on_cross(&ind, true) {
-                orders.push(OrderRequest::market_sell(
-                    ctx.symbol.clone(),
-                    pos.quantity.to_f64(),
-                ));
+            if self.should_exit_on_cross(&ind, is_long) {
+                orders.push(close());
                 return orders;
             }
 
             // Exit on max hold bars
             if let Some(state) = self.get_state(ctx.symbol) {
                 if state.bars_in_position >= self.config.max_hold_bars {
-                    orders.push(OrderRequest::market_sell(
-                        ctx.symbol.clone(),
-                        pos.quantity.to_f64(),
-                    ));
+                    orders.push(close());
                     return orders;
                 }
             }
 
             // Exit on momentum reversal
             let momentum = self.get_momentum_state(&ind);
-            if matches!(
-                momentum,
-                MomentumState::WeakBearish | MomentumState::StrongBearish
-            ) {
-                orders.push(OrderRequest::market_sell(
-                    ctx.symbol.clone(),
-                    pos.quantity.to_f64(),
-                ));
+            let reversed = match pos.side {
+                Side::Buy => matches!(
+                    momentum,
+                    MomentumState::WeakBearish | MomentumState::StrongBearish
+                ),
+                Side::Sell => matches!(
+                    momentum,
+                    MomentumState::WeakBullish | MomentumState::StrongBullish
+                ),
+            };
+            if reversed {
+                orders.push(close());
                 return orders;
             }
 
@@ -263,8 +298,8 @@ impl Strategy for MomentumScalperStrategy {
             None => return orders,
         };
 
-        // Only take long entries
-        if alignment != Side::Buy {
+        // Shorts only when enabled
+        if alignment == Side::Sell && !self.config.allow_short {
             return orders;
         }
 
@@ -276,16 +311,29 @@ impl Strategy for MomentumScalperStrategy {
         // MACD momentum filter
         if self.config.use_macd {
             let momentum = self.get_momentum_state(&ind);
-            if !matches!(
-                momentum,
-                MomentumState::StrongBullish | MomentumState::WeakBullish | MomentumState::Neutral
-            ) {
+            let confirmed = match alignment {
+                Side::Buy => matches!(
+                    momentum,
+                    MomentumState::StrongBullish
+                        | MomentumState::WeakBullish
+                        | MomentumState::Neutral
+                ),
+                Side::Sell => matches!(
+                    momentum,
+                    MomentumState::StrongBearish
+                        | MomentumState::WeakBearish
+                        | MomentumState::Neutral
+                ),
+            };
+            if !confirmed {
                 return orders;
             }
         }
 
-        // Generate buy order
-        orders.push(OrderRequest::market_buy(ctx.symbol.clone(), 1.0));
+        match alignment {
+            Side::Buy => orders.push(OrderRequest::market_buy(ctx.symbol.clone(), 1.0)),
+            Side::Sell => orders.push(OrderRequest::market_sell(ctx.symbol.clone(), 1.0)),
+        }
         orders
     }
 
@@ -321,14 +369,19 @@ impl Strategy for MomentumScalperStrategy {
             Indicators::atr_only(candles, self.config.atr_period).unwrap_or(current_price * 0.01);
 
         let entry_price = position.average_entry_price.to_f64();
+        let (move_in_favour, direction) = match position.side {
+            Side::Buy => (current_price - entry_price, 1.0),
+            Side::Sell => (entry_price - current_price, -1.0),
+        };
         let profit_atr = if current_atr > 0.0 {
-            (current_price - entry_price) / current_atr
+            move_in_favour / current_atr
         } else {
             0.0
         };
 
         if profit_atr >= self.config.trailing_activation {
-            let new_stop = current_price - self.config.trailing_atr_multiple * current_atr;
+            let new_stop =
+                current_price - direction * self.config.trailing_atr_multiple * current_atr;
             Some(new_stop)
         } else {
             None
@@ -369,3 +422,48 @@ impl Strategy for MomentumScalperStrategy {
         tracing::info!("Momentum Scalper strategy initialized");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    /// Hourly bars whose decline speeds up, so MACD stays bearish
+    fn falling(n: usize) -> Vec<Candle> {
+        let t0 = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..n)
+            .map(|i| {
+                let c = 500.0 - 0.05 * (i * i) as f64;
+                Candle::new(
+                    t0 + Duration::hours(i as i64),
+                    c,
+                    c * 1.005,
+                    c * 0.995,
+                    c,
+                    100.0,
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_falling_series_signals_short_when_enabled() {
+        let symbol = Symbol::new("BTCINR");
+        let bars = falling(80);
+        let base = StrategyContext::single_timeframe(&symbol, &bars, None, &[], 1e5, 1e5);
+
+        let long_only = MomentumScalperStrategy::new(MomentumScalperConfig::default());
+        assert!(long_only
+            .generate_signal(&SignalContext::new(&base))
+            .is_empty());
+
+        let strategy = MomentumScalperStrategy::new(MomentumScalperConfig {
+            allow_short: true,
+            ..Default::default()
+        });
+        let orders = strategy.generate_signal(&SignalContext::new(&base));
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].side, Side::Sell);
+    }
+}
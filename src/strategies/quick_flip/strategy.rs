@@ -7,7 +7,7 @@
 
 use crate::indicators::atr;
 use crate::oms::{Fill, OrderRequest, StrategyContext};
-use crate::strategies::Strategy;
+use crate::strategies::{SignalContext, Strategy};
 use crate::{Candle, Position, Side, Symbol, Trade};
 use std::collections::HashMap;
 
@@ -118,7 +118,7 @@ impl Strategy for QuickFlipStrategy {
         vec![]
     }
 
-    fn generate_orders(&self, ctx: &StrategyContext) -> Vec<OrderRequest> {
+    fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
         let mut orders = Vec::new();
 
         let min_bars = self.config.range_bars + self.config.atr_period + 5;
@@ -147,7 +147,9 @@ impl Strategy for QuickFlipStrategy {
         };
 
         let range_size = range_high - range_low;
-        let current_atr = self.get_atr(ctx.candles);
+        let current_atr = ctx
+            .atr(self.config.atr_period)
+            .unwrap_or(ctx.close().unwrap_or(0.0) * 0.02);
 
         // Filter: range must be significant (not too tight)
         if self.config.min_range_pct > 0.0 && range_size < current_atr * self.config.min_range_pct {
@@ -11,9 +11,9 @@
 //!
 //! Production-grade implementation with per-symbol cooldown tracking.
 
-use crate::indicators::{adx, atr, ema};
+use crate::indicators::atr;
 use crate::oms::{Fill, OrderRequest, StrategyContext};
-use crate::strategies::Strategy;
+use crate::strategies::{Indicator, SignalContext, Strategy};
 use crate::{Candle, Position, Side, Symbol, Trade};
 use std::collections::HashMap;
 
@@ -64,16 +64,12 @@ impl RangeBreakoutStrategy {
     }
 
     /// Check if volatility is expanding (good for breakouts)
-    fn is_volatility_expanding(&self, candles: &[Candle]) -> bool {
-        if candles.len() < self.config.atr_period * 2 {
+    fn is_volatility_expanding(&self, ctx: &SignalContext) -> bool {
+        if ctx.candles.len() < self.config.atr_period * 2 {
             return true;
         }
 
-        let high: Vec<f64> = candles.iter().map(|c| c.high).collect();
-        let low: Vec<f64> = candles.iter().map(|c| c.low).collect();
-        let close: Vec<f64> = candles.iter().map(|c| c.close).collect();
-
-        let atr_vals = atr(&high, &low, &close, self.config.atr_period);
+        let atr_vals = ctx.series(Indicator::Atr, self.config.atr_period);
         let len = atr_vals.len();
         if len < 5 {
             return true;
@@ -109,19 +105,16 @@ impl RangeBreakoutStrategy {
 
     /// Check trend direction using EMA
     /// Returns: (is_bullish, is_bearish)
-    fn get_trend_direction(&self, candles: &[Candle]) -> (bool, bool) {
-        if self.config.trend_ema == 0 || candles.len() < self.config.trend_ema + 1 {
+    fn get_trend_direction(&self, ctx: &SignalContext) -> (bool, bool) {
+        if self.config.trend_ema == 0 || ctx.candles.len() < self.config.trend_ema + 1 {
             return (true, true); // No filter if disabled or insufficient data
         }
 
-        let close: Vec<f64> = candles.iter().map(|c| c.close).collect();
-        let ema_vals = ema(&close, self.config.trend_ema);
-
-        let current_close = match candles.last() {
-            Some(c) => c.close,
+        let current_close = match ctx.close() {
+            Some(close) => close,
             None => return (true, true),
         };
-        let current_ema = ema_vals.last().and_then(|&x| x).unwrap_or(current_close);
+        let current_ema = ctx.ema(self.config.trend_ema).unwrap_or(current_close);
 
         let is_bullish = current_close > current_ema;
         let is_bearish = current_close < current_ema;
@@ -130,21 +123,16 @@ impl RangeBreakoutStrategy {
     }
 
     /// Check if market is trending (ADX filter)
-    fn is_trending(&self, candles: &[Candle]) -> bool {
+    fn is_trending(&self, ctx: &SignalContext) -> bool {
         if self.config.min_adx == 0.0 {
             return true; // Disabled
         }
 
-        if candles.len() < self.config.adx_period * 2 {
+        if ctx.candles.len() < self.config.adx_period * 2 {
             return true; // Insufficient data
         }
 
-        let high: Vec<f64> = candles.iter().map(|c| c.high).collect();
-        let low: Vec<f64> = candles.iter().map(|c| c.low).collect();
-        let close: Vec<f64> = candles.iter().map(|c| c.close).collect();
-
-        let adx_vals = adx(&high, &low, &close, self.config.adx_period);
-        let current_adx = adx_vals.last().and_then(|&x| x).unwrap_or(0.0);
+        let current_adx = ctx.adx(self.config.adx_period).unwrap_or(0.0);
 
         current_adx >= self.config.min_adx
     }
@@ -172,7 +160,7 @@ impl Strategy for RangeBreakoutStrategy {
         Box::new(RangeBreakoutStrategy::new(self.config.clone()))
     }
 
-    fn generate_orders(&self, ctx: &StrategyContext) -> Vec<OrderRequest> {
+    fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
         let mut orders = Vec::new();
 
         let min_bars = self.config.lookback.max(self.config.trend_ema) + self.config.atr_period + 2;
@@ -207,10 +195,10 @@ impl Strategy for RangeBreakoutStrategy {
         };
 
         // Check all filters
-        let vol_expanding = self.is_volatility_expanding(ctx.candles);
+        let vol_expanding = self.is_volatility_expanding(ctx);
         let vol_confirming = self.is_volume_confirming(ctx.candles);
-        let is_trending = self.is_trending(ctx.candles);
-        let (is_bullish, is_bearish) = self.get_trend_direction(ctx.candles);
+        let is_trending = self.is_trending(ctx);
+        let (is_bullish, is_bearish) = self.get_trend_direction(ctx);
 
         // Long breakout: current close > range high AND previous close <= range high
         // Plus: trend is up (price > EMA), market is trending (ADX > min)
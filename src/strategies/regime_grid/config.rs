@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::config::FeeMode;
+
 /// Regime Grid Strategy Configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegimeGridConfig {
@@ -66,6 +68,11 @@ pub struct RegimeGridConfig {
     pub trailing_activation_pct: f64,
     /// Trailing stop ATR multiple (default: 1.0)
     pub trailing_atr_multiple: f64,
+
+    // Execution
+    /// "maker" sends grid limits post-only for the maker fee (default: "any")
+    #[serde(default)]
+    pub fee_mode: FeeMode,
 }
 
 impl Default for RegimeGridConfig {
@@ -105,6 +112,9 @@ impl Default for RegimeGridConfig {
             stop_atr_multiple: 2.0,
             trailing_activation_pct: 0.03,
             trailing_atr_multiple: 1.0,
+
+            // Execution
+            fee_mode: FeeMode::Any,
         }
     }
 }
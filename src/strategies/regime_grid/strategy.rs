@@ -7,9 +7,10 @@
 //! Performance optimized: Indicators are calculated once per signal generation
 //! and reused to avoid O(N²) complexity.
 
+use crate::config::FeeMode;
 use crate::indicators::{adx, atr, ema, rsi};
-use crate::oms::{OrderRequest, StrategyContext};
-use crate::strategies::Strategy;
+use crate::oms::{OrderRequest, OrderType, StrategyContext, TimeInForce};
+use crate::strategies::{SignalContext, Strategy};
 use crate::{Candle, Position, Side};
 use chrono::{DateTime, Utc};
 
@@ -44,7 +45,17 @@ impl Indicators {
         }
     }
 
-    /// Calculate ATR only (for stop/target/trailing methods and volatility check)
+    /// Read indicators from the signal context's per-bar cache
+    fn from_context(ctx: &SignalContext, config: &RegimeGridConfig) -> Self {
+        Self {
+            current_ema_short: ctx.ema(config.ema_short_period),
+            current_ema_long: ctx.ema(config.ema_long_period),
+            current_adx: ctx.adx(config.adx_period),
+            current_rsi: ctx.rsi(config.rsi_period),
+        }
+    }
+
+    /// Calculate ATR only (for stop/target/trailing methods)
     fn atr_only(candles: &[Candle], atr_period: usize) -> Option<f64> {
         let high: Vec<f64> = candles.iter().map(|c| c.high).collect();
         let low: Vec<f64> = candles.iter().map(|c| c.low).collect();
@@ -297,6 +308,18 @@ impl RegimeGridStrategy {
         }
     }
 
+    /// Grid limits go out post-only in maker fee mode
+    fn apply_fee_mode(&self, orders: &mut [OrderRequest]) {
+        if self.config.fee_mode == FeeMode::Maker {
+            for order in orders
+                .iter_mut()
+                .filter(|o| o.order_type == OrderType::Limit)
+            {
+                order.time_in_force = TimeInForce::PostOnly;
+            }
+        }
+    }
+
     /// Place sell-only orders when at max exposure (to take profits)
     fn place_sell_only_orders(
         &self,
@@ -345,7 +368,12 @@ impl Strategy for RegimeGridStrategy {
         "regime_grid"
     }
 
-    fn generate_orders(&self, ctx: &StrategyContext) -> Vec<OrderRequest> {
+    /// Grid levels rest as limit orders; exits are at market
+    fn required_order_types(&self) -> Vec<OrderType> {
+        vec![OrderType::Market, OrderType::Limit]
+    }
+
+    fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
         let mut orders = Vec::new();
 
         // Need minimum data for indicators
@@ -369,7 +397,7 @@ impl Strategy for RegimeGridStrategy {
             Some(c) => c.close,
             None => return orders,
         };
-        if let Some(current_atr) = Indicators::atr_only(ctx.candles, self.config.atr_period_1h) {
+        if let Some(current_atr) = ctx.atr(self.config.atr_period_1h) {
             let volatility_ratio = current_atr / current_price;
             if volatility_ratio > self.config.volatility_kill_threshold {
                 tracing::warn!(
@@ -496,7 +524,7 @@ impl Strategy for RegimeGridStrategy {
         let at_max_exposure = position_value >= max_position_value * 0.95;
 
         // 4. Calculate all indicators once
-        let ind = Indicators::new(ctx.candles, &self.config);
+        let ind = Indicators::from_context(ctx, &self.config);
 
         // 5. Classify market regime
         let regime = match self.classify_regime(ctx.candles, &ind) {
@@ -531,6 +559,7 @@ impl Strategy for RegimeGridStrategy {
                     // At max exposure - only place sell orders to take profit
                     self.place_sell_only_orders(ctx, current_price, pos, &mut orders);
                 }
+                self.apply_fee_mode(&mut orders);
                 orders
             }
             MarketRegime::Bullish => {
@@ -541,11 +570,31 @@ impl Strategy for RegimeGridStrategy {
                     // At max exposure - only place sell orders to take profit
                     self.place_sell_only_orders(ctx, current_price, pos, &mut orders);
                 }
+                self.apply_fee_mode(&mut orders);
                 orders
             }
         }
     }
 
+    fn commentary(&self, ctx: &SignalContext, order: &OrderRequest) -> Option<String> {
+        let ind = Indicators::from_context(ctx, &self.config);
+        let regime = self.classify_regime(ctx.candles, &ind)?;
+        let level = match order.limit_price {
+            Some(price) => format!("grid level {:.2}", price),
+            None => "market".to_string(),
+        };
+        Some(format!(
+            "{:?} {}: {:?} regime, ADX {:.1}, RSI {:.1}, EMA{} {:.2}",
+            order.side,
+            level,
+            regime,
+            ind.current_adx?,
+            ind.current_rsi?,
+            self.config.ema_short_period,
+            ind.current_ema_short?
+        ))
+    }
+
     fn calculate_stop_loss(&self, candles: &[Candle], entry_price: f64, side: Side) -> f64 {
         let atr =
             Indicators::atr_only(candles, self.config.adx_period).unwrap_or(entry_price * 0.02);
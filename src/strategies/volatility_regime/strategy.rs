@@ -3,11 +3,12 @@
 //! Strategy implementation for trading based on volatility regime classification.
 //!
 //! Uses batch indicator calculation per signal, matching the proven main branch
-//! implementation for consistent signal generation.
+//! implementation for consistent signal generation. Signal-time indicators come
+//! from the [`SignalContext`] cache, shared with `commentary`.
 
 use crate::indicators::{adx, atr, ema};
-use crate::oms::{OrderRequest, StrategyContext};
-use crate::strategies::Strategy;
+use crate::oms::OrderRequest;
+use crate::strategies::{Indicator, SignalContext, Strategy};
 use crate::{Candle, Position, Side};
 
 use super::config::VolatilityRegimeConfig;
@@ -43,6 +44,18 @@ impl Indicators {
         }
     }
 
+    /// Read indicators from the signal context's per-bar cache
+    fn from_context(ctx: &SignalContext, config: &VolatilityRegimeConfig) -> Self {
+        let atr_values = ctx.series(Indicator::Atr, config.atr_period);
+        Self {
+            current_atr: atr_values.last().and_then(|&x| x),
+            current_ema_fast: ctx.ema(config.ema_fast),
+            current_ema_slow: ctx.ema(config.ema_slow),
+            current_adx: ctx.adx(config.adx_period),
+            atr_values,
+        }
+    }
+
     /// Calculate ATR only (for stop/target/trailing methods)
     fn atr_only(candles: &[Candle], atr_period: usize) -> Option<f64> {
         let high: Vec<f64> = candles.iter().map(|c| c.high).collect();
@@ -147,7 +160,7 @@ impl Strategy for VolatilityRegimeStrategy {
         Box::new(VolatilityRegimeStrategy::new(self.config.clone()))
     }
 
-    fn generate_orders(&self, ctx: &StrategyContext) -> Vec<OrderRequest> {
+    fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
         let candles = ctx.candles;
         let mut orders = Vec::new();
 
@@ -160,8 +173,7 @@ impl Strategy for VolatilityRegimeStrategy {
             return orders;
         }
 
-        // Calculate all indicators ONCE
-        let ind = Indicators::new(candles, &self.config);
+        let ind = Indicators::from_context(ctx, &self.config);
         let current_price = match candles.last() {
             Some(c) => c.close,
             None => return orders,
@@ -214,6 +226,37 @@ impl Strategy for VolatilityRegimeStrategy {
         orders
     }
 
+    fn commentary(&self, ctx: &SignalContext, _order: &OrderRequest) -> Option<String> {
+        let ind = Indicators::from_context(ctx, &self.config);
+        let regime = self.classify_regime(ctx.candles, &ind)?;
+        let close = ctx.candles.last()?.close;
+        let ema_slow = ind.current_ema_slow?;
+
+        if ctx.current_position.is_some() {
+            return Some(if regime == VolatilityRegime::Extreme {
+                format!("Exit: {:?} volatility regime", regime)
+            } else {
+                format!(
+                    "Exit: close {:.2} below EMA{} {:.2} while in profit",
+                    close, self.config.ema_slow, ema_slow
+                )
+            });
+        }
+
+        Some(format!(
+            "{:?} regime, ATR {:.2}; EMA{} {:.2} > EMA{} {:.2}, ADX {:.1} > {:.1}; breakout at {:.2}",
+            regime,
+            ind.current_atr?,
+            self.config.ema_fast,
+            ind.current_ema_fast?,
+            self.config.ema_slow,
+            ema_slow,
+            ind.current_adx?,
+            self.config.adx_threshold,
+            close
+        ))
+    }
+
     fn calculate_stop_loss(&self, candles: &[Candle], entry_price: f64, side: Side) -> f64 {
         let current_atr =
             Indicators::atr_only(candles, self.config.atr_period).unwrap_or(entry_price * 0.05);
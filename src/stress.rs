@@ -0,0 +1,390 @@
+//! Synthetic stress scenarios for the backtest report
+//!
+//! Each [`StressScenario`] reruns the backtest on a shocked copy of the data
+//! and compares the run with the unshocked baseline:
+//!
+//! - **Gap**: every price from the shock bar on is scaled by `1 + pct`, so
+//!   open positions gap through their stops
+//! - **Volatility**: inside the window each bar's open, high, low and close
+//!   sit `multiplier` times as far from the previous close; later bars keep
+//!   their shape at the new price level
+//! - **Liquidity**: prices are untouched and slippage is scaled inside the
+//!   window
+//!
+//! All symbols are shocked together. Each timeframe is transformed on its
+//! own, so a higher-timeframe bar straddling the window only approximates
+//! the primary path.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{BacktestResult, Backtester, Truncation};
+use crate::config::{Shock, StressScenario};
+use crate::{strategies, Candle, Config, MultiSymbolMultiTimeframeData, Trade};
+
+/// Days after the shock window still counted as the strategy's response
+pub const RESPONSE_DAYS: i64 = 7;
+
+impl Shock {
+    /// How long the shock lasts (zero for an instant gap)
+    pub fn duration(&self) -> Duration {
+        match *self {
+            Shock::Gap { .. } => Duration::zero(),
+            Shock::Volatility { days, .. } | Shock::Liquidity { days, .. } => {
+                Duration::seconds((days * 86_400.0) as i64)
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            Shock::Gap { pct } if pct <= -1.0 || !pct.is_finite() => {
+                bail!("gap pct must be above -1.0, got {}", pct)
+            }
+            Shock::Volatility { multiplier, days }
+            | Shock::Liquidity {
+                slippage_multiplier: multiplier,
+                days,
+            } if multiplier <= 0.0 || days <= 0.0 => {
+                bail!(
+                    "multiplier and days must be positive, got {} and {}",
+                    multiplier,
+                    days
+                )
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match *self {
+            Shock::Gap { pct } => format!("{:+.0}% gap", pct * 100.0),
+            Shock::Volatility { multiplier, days } => {
+                format!("volatility x{:.1} for {}d", multiplier, days)
+            }
+            Shock::Liquidity {
+                slippage_multiplier,
+                days,
+            } => format!("slippage x{:.1} for {}d", slippage_multiplier, days),
+        }
+    }
+}
+
+/// How a run fared under one scenario, next to the baseline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StressOutcome {
+    pub name: String,
+    pub shock: String,
+    pub at: DateTime<Utc>,
+    /// Percent, like [`PerformanceMetrics`](crate::backtest::PerformanceMetrics)
+    pub total_return: f64,
+    pub baseline_return: f64,
+    pub max_drawdown: f64,
+    pub baseline_max_drawdown: f64,
+    /// Equity change from the bar before the shock to the end of its window
+    pub shock_pnl: f64,
+    pub baseline_shock_pnl: f64,
+    /// Worst net P&L of a trade closed during the response period
+    pub worst_trade: f64,
+    /// Entries from the shock to [`RESPONSE_DAYS`] after its window
+    pub entries: usize,
+    pub baseline_entries: usize,
+    /// Days until equity regained its pre-shock level (`None`: never)
+    pub recovery_days: Option<f64>,
+    /// Set when a trailing drawdown breach stopped the shocked run
+    pub truncated: Option<Truncation>,
+}
+
+/// The first bar after the baseline run had the most capital deployed
+///
+/// Falls back to the middle of the data when the baseline never traded.
+pub fn default_shock_time(
+    trades: &[Trade],
+    data: &MultiSymbolMultiTimeframeData,
+) -> Option<DateTime<Utc>> {
+    let mut bars: Vec<DateTime<Utc>> = data
+        .values()
+        .flat_map(|mtf| mtf.primary().iter().map(|c| c.datetime))
+        .collect();
+    bars.sort();
+    bars.dedup();
+
+    let deployed = |t: DateTime<Utc>| -> f64 {
+        trades
+            .iter()
+            .filter(|tr| tr.entry_time <= t && t < tr.exit_time)
+            .map(|tr| (tr.entry_price * tr.quantity).to_f64())
+            .sum()
+    };
+    let peak = trades
+        .iter()
+        .map(|tr| (tr.entry_time, deployed(tr.entry_time)))
+        .filter(|(_, value)| *value > 0.0)
+        .fold(
+            None,
+            |best: Option<(DateTime<Utc>, f64)>, (t, value)| match best {
+                Some((_, top)) if top >= value => best,
+                _ => Some((t, value)),
+            },
+        );
+
+    match peak {
+        Some((t, _)) => bars.into_iter().find(|bar| *bar > t),
+        None => bars.get(bars.len() / 2).copied(),
+    }
+}
+
+/// Copy of `data` with `shock` applied from `at`
+pub fn apply(
+    data: &MultiSymbolMultiTimeframeData,
+    shock: &Shock,
+    at: DateTime<Utc>,
+) -> MultiSymbolMultiTimeframeData {
+    let end = at + shock.duration();
+    let mut shocked = data.clone();
+    for mtf in shocked.values_mut() {
+        let timeframes: Vec<String> = mtf.timeframes().iter().map(|tf| tf.to_string()).collect();
+        for tf in timeframes {
+            if let Some(candles) = mtf.get_mut(&tf) {
+                shock_candles(candles, shock, at, end);
+            }
+        }
+    }
+    shocked
+}
+
+fn shock_candles(candles: &mut [Candle], shock: &Shock, start: DateTime<Utc>, end: DateTime<Utc>) {
+    match *shock {
+        Shock::Gap { pct } => {
+            for candle in candles.iter_mut().filter(|c| c.datetime >= start) {
+                scale(candle, 1.0 + pct);
+            }
+        }
+        Shock::Volatility { multiplier, .. } => {
+            // Shocked / original price level, carried past the window
+            let mut level = 1.0;
+            let mut prev: Option<(f64, f64)> = None;
+            for candle in candles.iter_mut() {
+                let original_close = candle.close;
+                match prev {
+                    Some((prev_close, shocked_close))
+                        if candle.datetime >= start && candle.datetime < end =>
+                    {
+                        // Monotonic in the price, so high >= open/close >= low still holds
+                        let stretch = |price: f64| {
+                            (shocked_close * (1.0 + multiplier * (price / prev_close - 1.0)))
+                                .max(shocked_close * 0.01)
+                        };
+                        candle.open = stretch(candle.open);
+                        candle.high = stretch(candle.high);
+                        candle.low = stretch(candle.low);
+                        candle.close = stretch(candle.close);
+                    }
+                    _ => scale(candle, level),
+                }
+                level = candle.close / original_close;
+                prev = Some((original_close, candle.close));
+            }
+        }
+        Shock::Liquidity { .. } => {}
+    }
+}
+
+fn scale(candle: &mut Candle, factor: f64) {
+    candle.open *= factor;
+    candle.high *= factor;
+    candle.low *= factor;
+    candle.close *= factor;
+}
+
+/// Rerun the backtest under `scenario`, shocked at `at`
+pub fn run(
+    config: &Config,
+    data: &MultiSymbolMultiTimeframeData,
+    scenario: &StressScenario,
+    at: DateTime<Utc>,
+    baseline: &BacktestResult,
+) -> Result<StressOutcome> {
+    scenario.shock.validate()?;
+    let end = at + scenario.shock.duration();
+
+    let shocked = apply(data, &scenario.shock, at);
+    let mut backtester = Backtester::new(config.clone(), strategies::create_strategy(config)?);
+    if let Shock::Liquidity {
+        slippage_multiplier,
+        ..
+    } = scenario.shock
+    {
+        backtester = backtester.with_slippage_window(at, end, slippage_multiplier);
+    }
+    let result = backtester.run(&shocked);
+
+    let initial = config.trading.initial_capital;
+    let response_end = end + Duration::days(RESPONSE_DAYS);
+    let entries = |trades: &[Trade]| {
+        trades
+            .iter()
+            .filter(|t| t.entry_time >= at && t.entry_time <= response_end)
+            .count()
+    };
+    let before = equity_before(&result.equity_curve, at, initial);
+
+    Ok(StressOutcome {
+        name: scenario.name.clone(),
+        shock: scenario.shock.describe(),
+        at,
+        total_return: result.metrics.total_return,
+        baseline_return: baseline.metrics.total_return,
+        max_drawdown: result.metrics.max_drawdown,
+        baseline_max_drawdown: baseline.metrics.max_drawdown,
+        shock_pnl: equity_through(&result.equity_curve, end, before) - before,
+        baseline_shock_pnl: shock_pnl(&baseline.equity_curve, at, end, initial),
+        worst_trade: result
+            .trades
+            .iter()
+            .filter(|t| t.exit_time >= at && t.exit_time <= response_end)
+            .map(|t| t.net_pnl.to_f64())
+            .fold(0.0, f64::min),
+        entries: entries(&result.trades),
+        baseline_entries: entries(&baseline.trades),
+        recovery_days: recovery_days(&result.equity_curve, at, before),
+        truncated: result.truncated,
+    })
+}
+
+fn equity_before(curve: &[(DateTime<Utc>, f64)], at: DateTime<Utc>, initial: f64) -> f64 {
+    curve
+        .iter()
+        .take_while(|(t, _)| *t < at)
+        .last()
+        .map_or(initial, |(_, equity)| *equity)
+}
+
+fn equity_through(curve: &[(DateTime<Utc>, f64)], end: DateTime<Utc>, fallback: f64) -> f64 {
+    curve
+        .iter()
+        .take_while(|(t, _)| *t <= end)
+        .last()
+        .map_or(fallback, |(_, equity)| *equity)
+}
+
+/// Days from `at` until equity is back at `before` after first dipping below it
+fn recovery_days(curve: &[(DateTime<Utc>, f64)], at: DateTime<Utc>, before: f64) -> Option<f64> {
+    let mut after = curve.iter().skip_while(|(t, _)| *t < at);
+    if after
+        .by_ref()
+        .find(|(_, equity)| *equity < before)
+        .is_none()
+    {
+        return Some(0.0);
+    }
+    after
+        .find(|(_, equity)| *equity >= before)
+        .map(|(t, _)| (*t - at).num_seconds() as f64 / 86_400.0)
+}
+
+fn shock_pnl(
+    curve: &[(DateTime<Utc>, f64)],
+    at: DateTime<Utc>,
+    end: DateTime<Utc>,
+    initial: f64,
+) -> f64 {
+    let before = equity_before(curve, at, initial);
+    equity_through(curve, end, before) - before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Money, MultiTimeframeData, Side, Symbol};
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    fn data(closes: &[f64]) -> MultiSymbolMultiTimeframeData {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let candles = closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| {
+                Candle::new(
+                    start + Duration::days(i as i64),
+                    close,
+                    close * 1.01,
+                    close * 0.99,
+                    close,
+                    1.0,
+                )
+                .unwrap()
+            })
+            .collect();
+        let mut mtf = MultiTimeframeData::new("1d");
+        mtf.add_timeframe("1d", candles);
+        HashMap::from([(Symbol::new("BTCINR"), mtf)])
+    }
+
+    fn closes(data: &MultiSymbolMultiTimeframeData) -> Vec<f64> {
+        data.values()
+            .next()
+            .unwrap()
+            .primary()
+            .iter()
+            .map(|c| c.close)
+            .collect()
+    }
+
+    #[test]
+    fn test_gap_and_volatility_shocks() {
+        let base = data(&[100.0, 100.0, 110.0, 99.0, 99.0, 100.0]);
+        let at = Utc.with_ymd_and_hms(2025, 1, 3, 0, 0, 0).unwrap();
+
+        let gapped = apply(&base, &Shock::Gap { pct: -0.3 }, at);
+        assert_eq!(closes(&gapped)[..2], [100.0, 100.0]);
+        assert!((closes(&gapped)[2] - 77.0).abs() < 1e-9);
+        assert!((closes(&gapped)[5] - 70.0).abs() < 1e-9);
+
+        let shock = Shock::Volatility {
+            multiplier: 3.0,
+            days: 2.0,
+        };
+        let volatile = apply(&base, &shock, at);
+        let c = closes(&volatile);
+        // +10% becomes +30%, then -10% becomes -30%
+        assert!((c[2] - 130.0).abs() < 1e-9);
+        assert!((c[3] - 91.0).abs() < 1e-9);
+        // Outside the window the original moves resume from the new level
+        assert!((c[4] - 91.0).abs() < 1e-9);
+        assert!((c[5] - 91.0 * 100.0 / 99.0).abs() < 1e-9);
+        let bar = &volatile.values().next().unwrap().primary()[2];
+        assert!(bar.high >= bar.close && bar.low <= bar.open);
+
+        assert!(Shock::Gap { pct: -1.0 }.validate().is_err());
+        assert_eq!(shock.describe(), "volatility x3.0 for 2d");
+    }
+
+    #[test]
+    fn test_default_shock_time_follows_peak_exposure() {
+        let data = data(&[100.0; 10]);
+        let day = |d: u32| Utc.with_ymd_and_hms(2025, 1, d, 0, 0, 0).unwrap();
+        let trade = |entry: u32, exit: u32, quantity: f64| Trade {
+            symbol: Symbol::new("BTCINR"),
+            side: Side::Buy,
+            entry_price: Money::from_f64(100.0),
+            exit_price: Money::from_f64(100.0),
+            quantity: Money::from_f64(quantity),
+            entry_time: day(entry),
+            exit_time: day(exit),
+            pnl: Money::ZERO,
+            commission: Money::ZERO,
+            net_pnl: Money::ZERO,
+            fees: Default::default(),
+            rationale: None,
+            tranche: None,
+            r_multiple: None,
+        };
+
+        assert_eq!(default_shock_time(&[], &data), Some(day(6)));
+        let trades = [trade(2, 8, 1.0), trade(4, 6, 2.0), trade(7, 9, 1.5)];
+        assert_eq!(default_shock_time(&trades, &data), Some(day(5)));
+    }
+}
@@ -0,0 +1,257 @@
+//! Exchange symbol mapping
+//!
+//! Config and strategies name a market by base asset then quote currency
+//! (`BTCINR`). Every venue wants its own identifier for it: CoinDCX
+//! `I-BTC_INR`, Binance `BTCUSDT`, Kraken `XBTEUR`, OKX `BTC-USDT`, Bybit
+//! `BTCUSDT`, Coinbase `BTC-USD`, Zerodha `NSE:RELIANCE` or a numeric
+//! instrument token. The conversions live here, one function per venue, and
+//! [`SymbolMapper`] puts them behind a single lookup with overrides for
+//! listings that don't follow a venue's pattern.
+
+use std::collections::HashMap;
+
+use crate::Symbol;
+
+/// Quote currencies recognised at the end of a symbol, longest first
+pub const QUOTES: [&str; 6] = ["USDT", "USDC", "INR", "USD", "EUR", "GBP"];
+
+/// Base asset and quote currency of `symbol` (`ETHUSDT` → `ETH`, `USDT`)
+pub fn split(symbol: &str) -> Option<(&str, &'static str)> {
+    QUOTES.into_iter().find_map(|quote| {
+        let base = symbol.strip_suffix(quote)?;
+        (!base.is_empty()).then_some((base, quote))
+    })
+}
+
+/// Quote currency of `symbol`, if it ends in a known one
+pub fn quote(symbol: &str) -> Option<&'static str> {
+    split(symbol).map(|(_, quote)| quote)
+}
+
+/// Base asset of `symbol`; the whole symbol if no quote is recognised
+pub fn base(symbol: &str) -> &str {
+    split(symbol).map_or(symbol, |(base, _)| base)
+}
+
+/// A venue with its own market identifiers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Venue {
+    CoinDCX,
+    Binance,
+    Kraken,
+    /// OKX spot
+    Okx,
+    /// OKX perpetual swaps
+    OkxSwap,
+    Bybit,
+    Coinbase,
+    Zerodha,
+}
+
+/// CoinDCX pair: `BTCINR` → `I-BTC_INR`, `BTCUSDT` → `B-BTC_USDT`
+///
+/// Symbols already in pair format, or with another quote, pass through
+/// upper-cased.
+pub fn coindcx_pair(symbol: &str) -> String {
+    let symbol = symbol.to_uppercase();
+    if symbol.contains('-') && symbol.contains('_') {
+        return symbol;
+    }
+    if let Some(base) = symbol.strip_suffix("INR") {
+        return format!("I-{}_INR", base);
+    }
+    if let Some(base) = symbol.strip_suffix("USDT") {
+        return format!("B-{}_USDT", base);
+    }
+    symbol
+}
+
+/// Binance pair: INR and USDT symbols and bare bases all map to USDT
+/// (`BTCINR` → `BTCUSDT`, `eth` → `ETHUSDT`)
+pub fn binance_pair(symbol: &str) -> String {
+    format!("{}USDT", binance_base(symbol))
+}
+
+/// Base asset Binance pairs are looked up by (`BTCINR` → `BTC`)
+pub fn binance_base(symbol: &str) -> String {
+    let symbol = symbol.trim().to_uppercase();
+    match symbol
+        .strip_suffix("INR")
+        .or_else(|| symbol.strip_suffix("USDT"))
+    {
+        Some(base) => base.to_string(),
+        None => symbol,
+    }
+}
+
+/// Kraken pair: bitcoin is `XBT` and dogecoin `XDG` (`BTCEUR` → `XBTEUR`,
+/// `ETH/USD` → `ETHUSD`)
+pub fn kraken_pair(symbol: &str) -> String {
+    let symbol = symbol.replace('/', "").to_uppercase();
+    if let Some(quote) = symbol.strip_prefix("BTC") {
+        return format!("XBT{}", quote);
+    }
+    if let Some(quote) = symbol.strip_prefix("DOGE") {
+        return format!("XDG{}", quote);
+    }
+    symbol
+}
+
+/// OKX instrument ID: `BTCUSDT` and `btc/usdt` become `BTC-USDT`, or
+/// `BTC-USDT-SWAP` with `swap`; bare bases trade against USDT
+pub fn okx_inst_id(symbol: &str, swap: bool) -> String {
+    const OKX_QUOTES: [&str; 3] = ["USDT", "USDC", "USD"];
+    let symbol = symbol.to_uppercase().replace('/', "-");
+    let pair = symbol.trim_end_matches("-SWAP");
+    let pair = if pair.contains('-') {
+        pair.to_string()
+    } else {
+        OKX_QUOTES
+            .iter()
+            .find_map(|quote| {
+                let base = pair.strip_suffix(quote)?;
+                (!base.is_empty()).then(|| format!("{}-{}", base, quote))
+            })
+            .unwrap_or_else(|| format!("{}-USDT", pair))
+    };
+    if swap {
+        format!("{}-SWAP", pair)
+    } else {
+        pair
+    }
+}
+
+/// Bybit linear contract: bare bases trade against USDT (`BTC` → `BTCUSDT`,
+/// `eth/usdt` → `ETHUSDT`)
+pub fn bybit_contract(symbol: &str) -> String {
+    let symbol = symbol.replace('/', "").to_uppercase();
+    if symbol.ends_with("USDT") || symbol.ends_with("USDC") {
+        symbol
+    } else {
+        format!("{}USDT", symbol)
+    }
+}
+
+/// Coinbase product ID: `BTCUSD`, `btc/usd` and `BTC-USD` become `BTC-USD`
+///
+/// Symbols whose quote isn't recognised pass through upper-cased.
+pub fn coinbase_product(symbol: &str) -> String {
+    const COINBASE_QUOTES: [&str; 5] = ["USDC", "USDT", "USD", "EUR", "GBP"];
+    let symbol = symbol.replace(['/', '-'], "").to_uppercase();
+    COINBASE_QUOTES
+        .iter()
+        .find_map(|quote| {
+            let base = symbol.strip_suffix(quote)?;
+            (!base.is_empty()).then(|| format!("{}-{}", base, quote))
+        })
+        .unwrap_or(symbol)
+}
+
+/// Kite exchange and trading symbol: `NSE:RELIANCE` → (`NSE`, `RELIANCE`);
+/// bare symbols trade on NSE
+pub fn kite_instrument(symbol: &str) -> (&str, &str) {
+    symbol.split_once(':').unwrap_or(("NSE", symbol))
+}
+
+/// Identifier of `symbol` on `venue` by the venue's own pattern
+pub fn venue_id(symbol: &str, venue: Venue) -> String {
+    match venue {
+        Venue::CoinDCX => coindcx_pair(symbol),
+        Venue::Binance => binance_pair(symbol),
+        Venue::Kraken => kraken_pair(symbol),
+        Venue::Okx => okx_inst_id(symbol, false),
+        Venue::OkxSwap => okx_inst_id(symbol, true),
+        Venue::Bybit => bybit_contract(symbol),
+        Venue::Coinbase => coinbase_product(symbol),
+        Venue::Zerodha => {
+            let (exchange, tradingsymbol) = kite_instrument(symbol);
+            format!("{}:{}", exchange, tradingsymbol)
+        }
+    }
+}
+
+/// Venue identifiers for internal symbols, with overrides
+#[derive(Debug, Clone, Default)]
+pub struct SymbolMapper {
+    overrides: HashMap<(Venue, String), String>,
+    kite_tokens: HashMap<String, u64>,
+}
+
+impl SymbolMapper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Map `symbol` to `id` on `venue` instead of the venue's pattern
+    pub fn with_override(mut self, venue: Venue, symbol: &str, id: impl Into<String>) -> Self {
+        self.overrides
+            .insert((venue, symbol.to_string()), id.into());
+        self
+    }
+
+    /// Kite instrument token of `symbol`, which historical data is requested by
+    ///
+    /// Tokens are keyed by Kite instrument, so `INFY` and `NSE:INFY` share one.
+    pub fn with_kite_token(mut self, symbol: &str, token: u64) -> Self {
+        let instrument = self.id(symbol, Venue::Zerodha);
+        self.kite_tokens.insert(instrument, token);
+        self
+    }
+
+    /// Identifier of `symbol` on `venue`
+    pub fn map(&self, symbol: &Symbol, venue: Venue) -> String {
+        self.id(symbol.as_str(), venue)
+    }
+
+    /// Identifier of a symbol given as a string on `venue`
+    pub fn id(&self, symbol: &str, venue: Venue) -> String {
+        self.overrides
+            .get(&(venue, symbol.to_string()))
+            .cloned()
+            .unwrap_or_else(|| venue_id(symbol, venue))
+    }
+
+    pub fn kite_token(&self, symbol: &str) -> Option<u64> {
+        self.kite_tokens
+            .get(&self.id(symbol, Venue::Zerodha))
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbols_map_per_venue() {
+        let mapper = SymbolMapper::new();
+        let btcinr = Symbol::new("BTCINR");
+        assert_eq!(mapper.map(&btcinr, Venue::CoinDCX), "I-BTC_INR");
+        assert_eq!(mapper.map(&btcinr, Venue::Binance), "BTCUSDT");
+        assert_eq!(mapper.id("ETHUSDT", Venue::CoinDCX), "B-ETH_USDT");
+        assert_eq!(mapper.id("I-BTC_INR", Venue::CoinDCX), "I-BTC_INR");
+        assert_eq!(mapper.id("BTCEUR", Venue::Kraken), "XBTEUR");
+        assert_eq!(mapper.id("btc/usdt", Venue::OkxSwap), "BTC-USDT-SWAP");
+        assert_eq!(mapper.id("SOL", Venue::Bybit), "SOLUSDT");
+        assert_eq!(mapper.id("ethusd", Venue::Coinbase), "ETH-USD");
+        assert_eq!(mapper.id("RELIANCE", Venue::Zerodha), "NSE:RELIANCE");
+
+        let mapper = mapper
+            .with_override(Venue::Binance, "PEPEINR", "1000PEPEUSDT")
+            .with_kite_token("NSE:INFY", 408065);
+        assert_eq!(mapper.id("PEPEINR", Venue::Binance), "1000PEPEUSDT");
+        assert_eq!(mapper.id("PEPEINR", Venue::CoinDCX), "I-PEPE_INR");
+        assert_eq!(mapper.kite_token("NSE:INFY"), Some(408065));
+        assert_eq!(mapper.kite_token("INFY"), Some(408065));
+        assert_eq!(mapper.kite_token("BSE:INFY"), None);
+    }
+
+    #[test]
+    fn test_split_base_and_quote() {
+        assert_eq!(split("ETHUSDT"), Some(("ETH", "USDT")));
+        assert_eq!(split("BTCUSD"), Some(("BTC", "USD")));
+        assert_eq!(base("SOLINR"), "SOL");
+        assert_eq!(base("BTC"), "BTC");
+        assert_eq!(quote("USDT"), None);
+    }
+}
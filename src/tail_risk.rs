@@ -0,0 +1,229 @@
+//! Value at Risk and expected shortfall (CVaR)
+//!
+//! Losses are reported as positive fractions of the base (equity for daily
+//! returns, position notional for per-trade returns): a 99% VaR of 0.04 means
+//! one period in a hundred is expected to lose at least 4%, and the 99% CVaR is
+//! the average loss across that worst 1%.
+//!
+//! Two estimators are provided:
+//!
+//! - **Historical**: empirical quantile of the observed returns
+//! - **Bootstrap**: mean of the historical estimate over resamples drawn with
+//!   replacement, which smooths small samples (deterministic seed)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::Trade;
+
+/// Resamples used by the bootstrap estimator
+pub const BOOTSTRAP_SAMPLES: usize = 1000;
+const BOOTSTRAP_SEED: u64 = 0x5EED_CAFE_F00D_D00D;
+
+/// VaR/CVaR at the two standard confidence levels, as loss fractions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct VarEstimate {
+    pub var_95: f64,
+    pub cvar_95: f64,
+    pub var_99: f64,
+    pub cvar_99: f64,
+}
+
+impl VarEstimate {
+    /// Historical estimate from `returns`
+    pub fn historical(returns: &[f64]) -> Self {
+        let mut sorted = returns.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        Self::from_sorted(&sorted)
+    }
+
+    /// Bootstrap estimate: historical VaR/CVaR averaged over resamples of `returns`
+    pub fn bootstrap(returns: &[f64], samples: usize) -> Self {
+        if returns.is_empty() || samples == 0 {
+            return Self::default();
+        }
+        let mut rng = SplitMix64(BOOTSTRAP_SEED);
+        let mut total = Self::default();
+        let mut resample = vec![0.0; returns.len()];
+        for _ in 0..samples {
+            for slot in resample.iter_mut() {
+                *slot = returns[(rng.next() % returns.len() as u64) as usize];
+            }
+            resample.sort_by(f64::total_cmp);
+            let est = Self::from_sorted(&resample);
+            total.var_95 += est.var_95;
+            total.cvar_95 += est.cvar_95;
+            total.var_99 += est.var_99;
+            total.cvar_99 += est.cvar_99;
+        }
+        let n = samples as f64;
+        Self {
+            var_95: total.var_95 / n,
+            cvar_95: total.cvar_95 / n,
+            var_99: total.var_99 / n,
+            cvar_99: total.cvar_99 / n,
+        }
+    }
+
+    fn from_sorted(sorted: &[f64]) -> Self {
+        let (var_95, cvar_95) = var_cvar_sorted(sorted, 0.95);
+        let (var_99, cvar_99) = var_cvar_sorted(sorted, 0.99);
+        Self {
+            var_95,
+            cvar_95,
+            var_99,
+            cvar_99,
+        }
+    }
+
+    /// Same estimate in percent, rounded for reporting
+    fn to_pct(self) -> Self {
+        let pct = |v: f64| (v * 10_000.0).round() / 100.0;
+        Self {
+            var_95: pct(self.var_95),
+            cvar_95: pct(self.cvar_95),
+            var_99: pct(self.var_99),
+            cvar_99: pct(self.cvar_99),
+        }
+    }
+}
+
+/// VaR and CVaR of ascending-sorted returns at `confidence`, as positive loss
+/// fractions (0 when the tail is a gain)
+fn var_cvar_sorted(sorted: &[f64], confidence: f64) -> (f64, f64) {
+    if sorted.is_empty() {
+        return (0.0, 0.0);
+    }
+    // Number of observations in the tail, at least one (the epsilon keeps
+    // e.g. 0.05 * 100 from rounding up to 6)
+    let tail_len = (1.0 - confidence) * sorted.len() as f64;
+    let tail = ((tail_len - 1e-9).ceil() as usize).clamp(1, sorted.len());
+    let var = -sorted[tail - 1];
+    let cvar = -sorted[..tail].iter().sum::<f64>() / tail as f64;
+    (var.max(0.0), cvar.max(0.0))
+}
+
+/// Historical VaR at `confidence` (e.g. 0.99)
+pub fn historical_var(returns: &[f64], confidence: f64) -> f64 {
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    var_cvar_sorted(&sorted, confidence).0
+}
+
+/// Historical CVaR (expected shortfall) at `confidence`
+pub fn historical_cvar(returns: &[f64], confidence: f64) -> f64 {
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    var_cvar_sorted(&sorted, confidence).1
+}
+
+/// Tail-risk section of the performance report, in percent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TailRiskMetrics {
+    /// Daily equity returns, historical
+    pub daily_historical: VarEstimate,
+    /// Daily equity returns, bootstrap
+    pub daily_bootstrap: VarEstimate,
+    /// Net per-trade returns on notional, historical
+    pub trade_historical: VarEstimate,
+    /// Net per-trade returns on notional, bootstrap
+    pub trade_bootstrap: VarEstimate,
+}
+
+impl TailRiskMetrics {
+    pub fn compute(equity_curve: &[(DateTime<Utc>, f64)], trades: &[Trade]) -> Self {
+        let daily = daily_returns(equity_curve);
+        let per_trade: Vec<f64> = trades.iter().filter_map(trade_return).collect();
+        Self {
+            daily_historical: VarEstimate::historical(&daily).to_pct(),
+            daily_bootstrap: VarEstimate::bootstrap(&daily, BOOTSTRAP_SAMPLES).to_pct(),
+            trade_historical: VarEstimate::historical(&per_trade).to_pct(),
+            trade_bootstrap: VarEstimate::bootstrap(&per_trade, BOOTSTRAP_SAMPLES).to_pct(),
+        }
+    }
+}
+
+/// Close-to-close returns of the last equity value on each UTC day
+pub fn daily_returns(equity_curve: &[(DateTime<Utc>, f64)]) -> Vec<f64> {
+    let mut closes: Vec<(chrono::NaiveDate, f64)> = Vec::new();
+    for (t, equity) in equity_curve {
+        match closes.last_mut() {
+            Some((day, value)) if *day == t.date_naive() => *value = *equity,
+            _ => closes.push((t.date_naive(), *equity)),
+        }
+    }
+    closes
+        .windows(2)
+        .filter(|w| w[0].1 > 0.0)
+        .map(|w| w[1].1 / w[0].1 - 1.0)
+        .collect()
+}
+
+/// Net P&L over entry notional
+pub fn trade_return(trade: &Trade) -> Option<f64> {
+    let notional = (trade.entry_price * trade.quantity).to_f64();
+    (notional > 0.0).then(|| trade.net_pnl.to_f64() / notional)
+}
+
+/// Small deterministic PRNG so bootstrap results are reproducible
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    /// -10%, -9%, ..., +89%
+    fn returns() -> Vec<f64> {
+        (0..100).map(|i| (i as f64 - 10.0) / 100.0).collect()
+    }
+
+    #[test]
+    fn test_historical_var_cvar() {
+        let r = returns();
+        // 5 worst: -10..-6 -> VaR 6%, CVaR 8%
+        assert!((historical_var(&r, 0.95) - 0.06).abs() < 1e-12);
+        assert!((historical_cvar(&r, 0.95) - 0.08).abs() < 1e-12);
+        // Worst 1: -10%
+        assert!((historical_var(&r, 0.99) - 0.10).abs() < 1e-12);
+        assert!((historical_cvar(&r, 0.99) - 0.10).abs() < 1e-12);
+        // All gains -> no loss
+        assert_eq!(historical_var(&[0.01, 0.02], 0.99), 0.0);
+        assert_eq!(historical_cvar(&[], 0.99), 0.0);
+    }
+
+    #[test]
+    fn test_bootstrap_is_deterministic_and_close_to_historical() {
+        let r = returns();
+        let a = VarEstimate::bootstrap(&r, 500);
+        let b = VarEstimate::bootstrap(&r, 500);
+        assert_eq!(a, b);
+        let hist = VarEstimate::historical(&r);
+        assert!((a.cvar_95 - hist.cvar_95).abs() < 0.02);
+        assert!(a.cvar_99 >= a.var_99);
+    }
+
+    #[test]
+    fn test_daily_returns_use_last_value_per_day() {
+        let t0 = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let curve = vec![
+            (t0, 100.0),
+            (t0 + Duration::hours(12), 120.0),
+            (t0 + Duration::days(1), 90.0),
+            (t0 + Duration::days(1) + Duration::hours(6), 108.0),
+        ];
+        let daily = daily_returns(&curve);
+        assert_eq!(daily.len(), 1);
+        assert!((daily[0] - (108.0 / 120.0 - 1.0)).abs() < 1e-12);
+    }
+}
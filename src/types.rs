@@ -2,6 +2,7 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use thiserror::Error;
 
 /// Validation errors for candle data
@@ -184,6 +185,56 @@ pub enum Side {
     Sell,
 }
 
+/// Fee category used in per-order and per-trade cost breakdowns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeType {
+    /// Exchange fee on maker (resting) fills
+    Maker,
+    /// Exchange fee on taker (aggressive) fills
+    Taker,
+    /// GST charged on exchange fees
+    Gst,
+    /// Tax deducted at source on sell consideration (withheld, not a cost)
+    Tds,
+    /// Interest on assets borrowed for margin shorts
+    Borrow,
+}
+
+impl FeeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeType::Maker => "maker",
+            FeeType::Taker => "taker",
+            FeeType::Gst => "gst",
+            FeeType::Tds => "tds",
+            FeeType::Borrow => "borrow",
+        }
+    }
+
+    /// Whether this fee reduces PnL. TDS is credited against the final tax
+    /// liability, so it is reported but not counted as commission.
+    pub fn is_cost(&self) -> bool {
+        !matches!(self, FeeType::Tds)
+    }
+}
+
+impl fmt::Display for FeeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Fee amounts by type
+pub type FeeLedger = BTreeMap<FeeType, Money>;
+
+/// Add an amount to a fee ledger, skipping zero entries
+pub fn add_fee(ledger: &mut FeeLedger, kind: FeeType, amount: Money) {
+    if !amount.is_zero() {
+        *ledger.entry(kind).or_insert(Money::ZERO) += amount;
+    }
+}
+
 /// Completed trade record with precise decimal arithmetic for monetary values
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
@@ -195,11 +246,48 @@ pub struct Trade {
     pub entry_time: DateTime<Utc>,
     pub exit_time: DateTime<Utc>,
     pub pnl: Money,
+    /// Total cost fees (every ledger entry except TDS)
     pub commission: Money,
     pub net_pnl: Money,
+    /// Fee breakdown by type
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fees: FeeLedger,
+    /// Strategy's rationale at entry (see `Strategy::commentary`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<String>,
+    /// Exit-ladder tranche this record closed (`"1R"`, `"2R"`, `"runner"`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tranche: Option<String>,
+    /// Exit distance in multiples of the initial risk (set when an exit ladder is active)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub r_multiple: Option<f64>,
 }
 
 impl Trade {
+    /// Attach a fee breakdown
+    pub fn with_fees(mut self, fees: FeeLedger) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Attach the entry rationale
+    pub fn with_rationale(mut self, rationale: Option<String>) -> Self {
+        self.rationale = rationale;
+        self
+    }
+
+    /// Attach exit-ladder attribution
+    pub fn with_tranche(mut self, tranche: impl Into<String>, r_multiple: f64) -> Self {
+        self.tranche = Some(tranche.into());
+        self.r_multiple = Some(r_multiple);
+        self
+    }
+
+    /// Amount of a single fee type
+    pub fn fee(&self, kind: FeeType) -> Money {
+        self.fees.get(&kind).copied().unwrap_or(Money::ZERO)
+    }
+
     /// Calculate return percentage
     pub fn return_pct(&self) -> f64 {
         if self.entry_price.is_zero() {
@@ -237,6 +325,10 @@ impl Trade {
             pnl: Money::from_f64(pnl),
             commission: Money::from_f64(commission),
             net_pnl: Money::from_f64(net_pnl),
+            fees: FeeLedger::new(),
+            rationale: None,
+            tranche: None,
+            r_multiple: None,
         }
     }
 }
@@ -268,6 +360,31 @@ pub struct PerformanceMetrics {
     pub largest_loss: f64,
     pub total_commission: f64,
     pub tax_amount: f64,
+    /// Fees summed across trades by type
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fees_by_type: BTreeMap<FeeType, f64>,
+    /// Exits on bars that touched both stop and target, resolved by `same_bar_policy`.
+    /// A high count means results are sensitive to the intrabar path.
+    #[serde(default)]
+    pub ambiguous_bars: usize,
+    /// Entry signals skipped by the minimum-edge gate (`trading.min_edge`)
+    #[serde(default)]
+    pub edge_rejections: usize,
+    /// Entries shrunk or skipped by the shared risk budget (`trading.risk_budget`)
+    #[serde(default)]
+    pub budget_limited: usize,
+    /// Strategy orders rejected by `trading.pre_trade_checks`
+    #[serde(default)]
+    pub pre_trade_rejections: usize,
+    /// Bars each symbol was left unsignalled by `trading.activity_filter`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub activity_skips: BTreeMap<String, usize>,
+    /// Activity of the `trading.hedge` leg (absent when hedging is off)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hedge: Option<crate::hedge::HedgeStats>,
+    /// VaR/CVaR on daily and per-trade returns, percent
+    #[serde(default)]
+    pub tail_risk: crate::tail_risk::TailRiskMetrics,
 }
 
 impl PerformanceMetrics {
@@ -310,6 +427,14 @@ impl PerformanceMetrics {
             largest_loss: round2(largest_loss),
             total_commission: round2(total_commission),
             tax_amount: round2(tax_amount),
+            fees_by_type: BTreeMap::new(),
+            ambiguous_bars: 0,
+            edge_rejections: 0,
+            budget_limited: 0,
+            pre_trade_rejections: 0,
+            activity_skips: BTreeMap::new(),
+            hedge: None,
+            tail_risk: Default::default(),
         }
     }
 }
@@ -540,6 +665,39 @@ impl<'a> std::iter::Sum<&'a Money> for Money {
 mod money_tests {
     use super::*;
 
+    #[test]
+    fn test_fee_ledger_serde() {
+        let mut fees = FeeLedger::new();
+        add_fee(&mut fees, FeeType::Taker, Money::from_f64(5.0));
+        add_fee(&mut fees, FeeType::Taker, Money::from_f64(2.5));
+        add_fee(&mut fees, FeeType::Tds, Money::ZERO);
+
+        let trade = Trade::from_f64(
+            Symbol::new("BTCINR"),
+            Side::Buy,
+            100.0,
+            110.0,
+            1.0,
+            Utc::now(),
+            Utc::now(),
+            10.0,
+            7.5,
+            2.5,
+        )
+        .with_fees(fees);
+        assert_eq!(trade.fee(FeeType::Taker).to_f64(), 7.5);
+        assert!(trade.fee(FeeType::Tds).is_zero());
+
+        let json = serde_json::to_value(&trade).unwrap();
+        assert_eq!(json["fees"]["taker"], "7.5");
+
+        // Trades serialized before the ledger existed still load
+        let mut legacy = json.clone();
+        legacy.as_object_mut().unwrap().remove("fees");
+        let parsed: Trade = serde_json::from_value(legacy).unwrap();
+        assert!(parsed.fees.is_empty());
+    }
+
     #[test]
     fn test_money_precision() {
         // Classic floating point problem: 0.1 + 0.2 != 0.3 in f64
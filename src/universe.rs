@@ -0,0 +1,265 @@
+//! Liquidity-tiered universe presets
+//!
+//! Instead of hardcoding a pair list that goes stale (delistings, dried-up
+//! volume), a config can name a preset:
+//!
+//! ```json
+//! "trading": { "universe": "inr_top10_liquid", ... }
+//! ```
+//!
+//! The preset is resolved at startup from CoinDCX market status and 24h ticker
+//! volume, and replaces `trading.symbols`. If the exchange can't be reached,
+//! the configured `symbols` are used as a fallback.
+//!
+//! Note: resolution reflects *today's* liquidity. Backtests over a resolved
+//! universe carry survivorship bias.
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+use crate::coindcx::{ClientConfig, CoinDCXClient};
+use crate::Config;
+
+/// A named, liquidity-filtered symbol universe
+#[derive(Debug, Clone, Copy)]
+pub struct UniversePreset {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Quote currency (e.g., "INR", "USDT")
+    pub quote: &'static str,
+    /// Restrict to these base assets (empty = any)
+    pub bases: &'static [&'static str],
+    /// Keep at most this many symbols, ranked by 24h quote volume
+    pub top_n: usize,
+    /// Minimum 24h volume in quote currency
+    pub min_quote_volume: f64,
+}
+
+/// Built-in presets
+pub const PRESETS: &[UniversePreset] = &[
+    UniversePreset {
+        name: "inr_top10_liquid",
+        description: "Top 10 INR pairs by 24h volume (min ₹10L)",
+        quote: "INR",
+        bases: &[],
+        top_n: 10,
+        min_quote_volume: 1_000_000.0,
+    },
+    UniversePreset {
+        name: "inr_top25_liquid",
+        description: "Top 25 INR pairs by 24h volume (min ₹2L)",
+        quote: "INR",
+        bases: &[],
+        top_n: 25,
+        min_quote_volume: 200_000.0,
+    },
+    UniversePreset {
+        name: "inr_majors",
+        description: "Large-cap INR pairs that are currently active and liquid",
+        quote: "INR",
+        bases: MAJORS,
+        top_n: 10,
+        min_quote_volume: 100_000.0,
+    },
+    UniversePreset {
+        name: "usdt_majors",
+        description: "Large-cap USDT pairs that are currently active and liquid",
+        quote: "USDT",
+        bases: MAJORS,
+        top_n: 10,
+        min_quote_volume: 10_000.0,
+    },
+    UniversePreset {
+        name: "usdt_top20_liquid",
+        description: "Top 20 USDT pairs by 24h volume (min 50k USDT)",
+        quote: "USDT",
+        bases: &[],
+        top_n: 20,
+        min_quote_volume: 50_000.0,
+    },
+];
+
+const MAJORS: &[&str] = &[
+    "BTC", "ETH", "SOL", "BNB", "XRP", "ADA", "DOGE", "AVAX", "LINK", "DOT",
+];
+
+/// Look up a preset by name
+pub fn preset(name: &str) -> Option<&'static UniversePreset> {
+    PRESETS.iter().find(|p| p.name == name)
+}
+
+/// Market status and liquidity used to rank symbols
+#[derive(Debug, Clone)]
+pub struct MarketLiquidity {
+    /// Symbol as used in configs (e.g., "BTCINR")
+    pub symbol: String,
+    pub base: String,
+    pub quote: String,
+    pub active: bool,
+    /// 24h volume in quote currency
+    pub quote_volume: f64,
+}
+
+impl UniversePreset {
+    /// Select symbols from a market snapshot, most liquid first
+    pub fn select(&self, markets: &[MarketLiquidity]) -> Vec<String> {
+        let mut eligible: Vec<&MarketLiquidity> = markets
+            .iter()
+            .filter(|m| m.active && m.quote == self.quote)
+            .filter(|m| self.bases.is_empty() || self.bases.contains(&m.base.as_str()))
+            .filter(|m| m.quote_volume >= self.min_quote_volume)
+            .collect();
+
+        eligible.sort_by(|a, b| b.quote_volume.total_cmp(&a.quote_volume));
+        eligible
+            .into_iter()
+            .take(self.top_n)
+            .map(|m| m.symbol.clone())
+            .collect()
+    }
+}
+
+/// Fetch market status and 24h volume for all CoinDCX spot markets
+pub async fn fetch_liquidity(client: &CoinDCXClient) -> Result<Vec<MarketLiquidity>> {
+    let markets = client.get_markets_details().await?;
+    let tickers = client.get_all_tickers().await?;
+
+    // Ticker volume is in base units; convert to quote with the last price
+    let volumes: HashMap<String, f64> = tickers
+        .into_iter()
+        .map(|t| {
+            let volume = t.volume.parse::<f64>().unwrap_or(0.0);
+            let price = t.last_price.parse::<f64>().unwrap_or(0.0);
+            (t.market, volume * price)
+        })
+        .collect();
+
+    Ok(markets
+        .into_iter()
+        .map(|m| {
+            // CoinDCX names the quote currency "base_currency"
+            let symbol = format!(
+                "{}{}",
+                m.target_currency_short_name, m.base_currency_short_name
+            );
+            MarketLiquidity {
+                quote_volume: volumes.get(&symbol).copied().unwrap_or(0.0),
+                active: m.status == "active",
+                base: m.target_currency_short_name,
+                quote: m.base_currency_short_name,
+                symbol,
+            }
+        })
+        .collect())
+}
+
+/// Resolve a preset name into a symbol list
+pub async fn resolve(name: &str, client: &CoinDCXClient) -> Result<Vec<String>> {
+    let Some(preset) = preset(name) else {
+        let known: Vec<&str> = PRESETS.iter().map(|p| p.name).collect();
+        bail!(
+            "Unknown universe preset '{}' (available: {:?})",
+            name,
+            known
+        );
+    };
+
+    let markets = fetch_liquidity(client)
+        .await
+        .context("Failed to fetch market liquidity")?;
+    let symbols = preset.select(&markets);
+    if symbols.is_empty() {
+        bail!("Universe '{}' resolved to no symbols", name);
+    }
+    Ok(symbols)
+}
+
+/// Replace `trading.symbols` with the configured universe, if any.
+///
+/// Falls back to the configured symbols when resolution fails and a list is present.
+pub async fn apply_universe(config: &mut Config) -> Result<()> {
+    let Some(name) = config.trading.universe.clone() else {
+        return Ok(());
+    };
+
+    let network = config.exchange.network()?;
+    let client = CoinDCXClient::with_config(
+        "",
+        "",
+        ClientConfig {
+            network,
+            ..Default::default()
+        },
+    );
+    match resolve(&name, &client).await {
+        Ok(symbols) => {
+            info!("Universe '{}' resolved to {:?}", name, symbols);
+            config.trading.symbols = symbols;
+            Ok(())
+        }
+        Err(e) if !config.trading.symbols.is_empty() => {
+            warn!(
+                "Universe '{}' could not be resolved ({:#}) - using configured symbols {:?}",
+                name, e, config.trading.symbols
+            );
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Blocking wrapper for [`apply_universe`], usable from sync commands
+pub fn apply_universe_blocking(config: &mut Config) -> Result<()> {
+    if config.trading.universe.is_none() {
+        return Ok(());
+    }
+    crate::common::block_on(apply_universe(config))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(symbol: &str, base: &str, quote: &str, active: bool, vol: f64) -> MarketLiquidity {
+        MarketLiquidity {
+            symbol: symbol.to_string(),
+            base: base.to_string(),
+            quote: quote.to_string(),
+            active,
+            quote_volume: vol,
+        }
+    }
+
+    fn snapshot() -> Vec<MarketLiquidity> {
+        vec![
+            market("BTCINR", "BTC", "INR", true, 50_000_000.0),
+            market("ETHINR", "ETH", "INR", true, 20_000_000.0),
+            market("PEPEINR", "PEPE", "INR", true, 30_000_000.0),
+            market("LUNAINR", "LUNA", "INR", false, 90_000_000.0),
+            market("DOTINR", "DOT", "INR", true, 50_000.0),
+            market("BTCUSDT", "BTC", "USDT", true, 900_000.0),
+        ]
+    }
+
+    #[test]
+    fn test_top_n_ranks_by_volume_and_skips_inactive() {
+        let symbols = preset("inr_top10_liquid").unwrap().select(&snapshot());
+        assert_eq!(symbols, vec!["BTCINR", "PEPEINR", "ETHINR"]);
+    }
+
+    #[test]
+    fn test_majors_whitelist_and_quote_filter() {
+        let inr = preset("inr_majors").unwrap().select(&snapshot());
+        assert_eq!(inr, vec!["BTCINR", "ETHINR"]);
+
+        let usdt = preset("usdt_majors").unwrap().select(&snapshot());
+        assert_eq!(usdt, vec!["BTCUSDT"]);
+    }
+
+    #[test]
+    fn test_unknown_preset() {
+        assert!(preset("nope").is_none());
+        assert!(PRESETS.iter().all(|p| preset(p.name).is_some()));
+    }
+}
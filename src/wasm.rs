@@ -0,0 +1,174 @@
+//! WebAssembly bindings for browser demos
+//!
+//! Exposes the indicators and the backtest core to JavaScript through
+//! `wasm-bindgen`. Everything crosses the boundary as JSON strings or
+//! `Float64Array`s so no extra glue crates are needed.
+//!
+//! Build with:
+//! ```text
+//! wasm-pack build --target web --no-default-features --features wasm
+//! ```
+//!
+//! ```js
+//! import init, { run_backtest, ema } from "./pkg/crypto_strategies.js";
+//! await init();
+//! const result = JSON.parse(run_backtest(configJson, JSON.stringify({ BTCINR: candles })));
+//! console.log(result.metrics.sharpe_ratio, result.trades.length);
+//! ```
+//!
+//! Candles use the same shape as [`Candle`]: `{ datetime, open, high, low, close, volume }`
+//! with `datetime` as an ISO-8601 string (`Date.prototype.toISOString()`).
+//! Indicator warm-up values are returned as `NaN`.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+use crate::backtest::Backtester;
+use crate::indicators::{self, to_nan};
+use crate::multi_timeframe::MultiTimeframeData;
+use crate::{strategies, Candle, Config, PerformanceMetrics, Symbol, Trade};
+
+/// Equity curve point in a JS-friendly shape
+#[derive(Debug, Serialize)]
+struct EquityPoint {
+    datetime: String,
+    equity: f64,
+}
+
+/// Backtest output returned to JavaScript
+#[derive(Debug, Serialize)]
+struct WasmBacktestResult {
+    metrics: PerformanceMetrics,
+    trades: Vec<Trade>,
+    equity_curve: Vec<EquityPoint>,
+}
+
+/// Run a backtest from a config JSON and a `{ symbol: [candles] }` JSON map.
+///
+/// Returns a JSON string with `metrics`, `trades` and `equity_curve`.
+#[wasm_bindgen]
+pub fn run_backtest(config_json: &str, candles_json: &str) -> Result<String, JsError> {
+    backtest_json(config_json, candles_json).map_err(|e| JsError::new(&format!("{:#}", e)))
+}
+
+/// Names of all registered strategies
+#[wasm_bindgen]
+pub fn available_strategies() -> Vec<String> {
+    strategies::available_strategies()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+#[wasm_bindgen]
+pub fn sma(values: &[f64], period: usize) -> Vec<f64> {
+    to_nan(indicators::sma(values, period))
+}
+
+#[wasm_bindgen]
+pub fn ema(values: &[f64], period: usize) -> Vec<f64> {
+    to_nan(indicators::ema(values, period))
+}
+
+#[wasm_bindgen]
+pub fn rsi(values: &[f64], period: usize) -> Vec<f64> {
+    to_nan(indicators::rsi(values, period))
+}
+
+#[wasm_bindgen]
+pub fn atr(high: &[f64], low: &[f64], close: &[f64], period: usize) -> Vec<f64> {
+    to_nan(indicators::atr(high, low, close, period))
+}
+
+#[wasm_bindgen]
+pub fn adx(high: &[f64], low: &[f64], close: &[f64], period: usize) -> Vec<f64> {
+    to_nan(indicators::adx(high, low, close, period))
+}
+
+fn backtest_json(config_json: &str, candles_json: &str) -> Result<String> {
+    let config: Config =
+        serde_json::from_str(config_json).context("Failed to parse config JSON")?;
+    let candles: HashMap<String, Vec<Candle>> =
+        serde_json::from_str(candles_json).context("Failed to parse candles JSON")?;
+
+    let timeframe = config.timeframe();
+    let data = candles
+        .into_iter()
+        .map(|(symbol, candles)| {
+            let mut mtf = MultiTimeframeData::new(timeframe.as_str());
+            mtf.add_timeframe(timeframe.as_str(), candles);
+            (Symbol::new(&symbol), mtf)
+        })
+        .collect();
+
+    let strategy = strategies::create_strategy(&config)?;
+    let mut backtester = Backtester::try_new(config, strategy)?;
+    let result = backtester.run(&data);
+
+    let output = WasmBacktestResult {
+        metrics: result.metrics,
+        trades: result.trades,
+        equity_curve: result
+            .equity_curve
+            .into_iter()
+            .map(|(dt, equity)| EquityPoint {
+                datetime: dt.to_rfc3339(),
+                equity,
+            })
+            .collect(),
+    };
+    Ok(serde_json::to_string(&output)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_to_nan_preserves_length() {
+        let out = sma(&[1.0, 2.0, 3.0, 4.0], 2);
+        assert_eq!(out.len(), 4);
+        assert!(out[0].is_nan());
+        assert_eq!(out[1], 1.5);
+    }
+
+    #[test]
+    fn test_backtest_json_roundtrip() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let candles: Vec<Candle> = (0..400)
+            .map(|i| {
+                let price = 100.0 + (i as f64 * 0.1).sin() * 5.0 + i as f64 * 0.05;
+                Candle::new_unchecked(
+                    start + Duration::days(i),
+                    price,
+                    price * 1.02,
+                    price * 0.98,
+                    price * 1.01,
+                    1000.0,
+                )
+            })
+            .collect();
+        let candles_json = serde_json::json!({ "BTCINR": candles }).to_string();
+
+        let config_json = include_str!("../configs/volatility_regime_config.json");
+
+        let out = backtest_json(config_json, &candles_json).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert!(parsed.get("metrics").is_some());
+        assert!(parsed["equity_curve"].as_array().is_some());
+    }
+
+    #[test]
+    fn test_backtest_json_reports_bad_pre_trade_checks() {
+        let mut config: serde_json::Value =
+            serde_json::from_str(include_str!("../configs/volatility_regime_config.json")).unwrap();
+        config["trading"]["pre_trade_checks"] = serde_json::json!([
+            { "type": "restricted_hours", "windows": [{ "start": "25:00", "end": "01:00" }] }
+        ]);
+        let err = backtest_json(&config.to_string(), r#"{"BTCINR": []}"#).unwrap_err();
+        assert!(format!("{:#}", err).contains("pre_trade_checks"));
+    }
+}
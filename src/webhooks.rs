@@ -0,0 +1,388 @@
+//! Fill/close webhooks for third-party integrations
+//!
+//! Live mode posts a normalized JSON [`WebhookEvent`] to every configured
+//! endpoint on each fill and position close, so portfolio trackers and tax
+//! tools can follow the bot in real time.
+//!
+//! Delivery runs on a background task and never blocks the trading loop.
+//! Failed deliveries go to a retry queue with exponential backoff and are
+//! dropped after `max_retries` attempts.
+//!
+//! # Headers
+//!
+//! - `X-Webhook-Event`: `fill`, `close` or `alert`
+//! - `X-Webhook-Id`: unique event id (use for idempotent processing)
+//! - `X-Webhook-Signature`: `sha256=<hex HMAC-SHA256 of the body>` (when a secret is set)
+//!
+//! Receivers verify by recomputing the HMAC over the raw request body, e.g.
+//! with [`crate::coindcx::auth::verify_signature`].
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::coindcx::auth::sign_request;
+use crate::config::WebhookConfig;
+use crate::oms::Fill;
+use crate::{Side, Symbol};
+
+/// Maximum deliveries held for retry before the oldest are dropped
+const MAX_QUEUED_DELIVERIES: usize = 1_000;
+
+/// Upper bound on the retry delay
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Webhook event type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventKind {
+    Fill,
+    Close,
+    /// Watchlist alert from the `alerts` command
+    Alert,
+    /// Account-level risk rule warning or breach (e.g. trailing drawdown)
+    Risk,
+}
+
+impl WebhookEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventKind::Fill => "fill",
+            WebhookEventKind::Close => "close",
+            WebhookEventKind::Alert => "alert",
+            WebhookEventKind::Risk => "risk",
+        }
+    }
+}
+
+/// Normalized fill/close event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub event: WebhookEventKind,
+    pub event_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub symbol: String,
+    /// "buy" or "sell" (for closes: the side of the position that was closed)
+    pub side: String,
+    pub price: f64,
+    pub quantity: f64,
+    pub commission: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_maker: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub realized_pnl: Option<f64>,
+    /// Strategy's rationale for the entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<String>,
+    pub paper: bool,
+}
+
+impl WebhookEvent {
+    /// Build a fill event
+    pub fn fill(fill: &Fill, symbol: &Symbol, side: Side, paper: bool) -> Self {
+        WebhookEvent {
+            event: WebhookEventKind::Fill,
+            event_id: format!(
+                "fill-{}-{}",
+                fill.order_id,
+                fill.timestamp.timestamp_millis()
+            ),
+            timestamp: fill.timestamp,
+            symbol: symbol.as_str().to_string(),
+            side: side_str(side).to_string(),
+            price: fill.price.to_f64(),
+            quantity: fill.quantity.to_f64(),
+            commission: fill.commission.to_f64(),
+            order_id: Some(fill.order_id),
+            is_maker: Some(fill.is_maker),
+            realized_pnl: None,
+            rationale: None,
+            paper,
+        }
+    }
+
+    /// Build a position-close event
+    #[allow(clippy::too_many_arguments)]
+    pub fn close(
+        symbol: &Symbol,
+        position_side: Side,
+        exit_price: f64,
+        quantity: f64,
+        realized_pnl: f64,
+        commission: f64,
+        timestamp: DateTime<Utc>,
+        paper: bool,
+    ) -> Self {
+        WebhookEvent {
+            event: WebhookEventKind::Close,
+            event_id: format!("close-{}-{}", symbol, timestamp.timestamp_millis()),
+            timestamp,
+            symbol: symbol.as_str().to_string(),
+            side: side_str(position_side).to_string(),
+            price: exit_price,
+            quantity,
+            commission,
+            order_id: None,
+            is_maker: None,
+            realized_pnl: Some(realized_pnl),
+            rationale: None,
+            paper,
+        }
+    }
+
+    /// Attach the strategy's entry rationale
+    pub fn with_rationale(mut self, rationale: Option<String>) -> Self {
+        self.rationale = rationale;
+        self
+    }
+}
+
+fn side_str(side: Side) -> &'static str {
+    match side {
+        Side::Buy => "buy",
+        Side::Sell => "sell",
+    }
+}
+
+/// Signature header value for a payload: `sha256=<hex>`
+pub fn sign_payload(body: &str, secret: &str) -> String {
+    format!("sha256={}", sign_request(body, secret))
+}
+
+/// Retry delay for a given attempt number (1-based), doubling from `base_ms`
+pub fn retry_delay(base_ms: u64, attempt: u32) -> Duration {
+    let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+    Duration::from_millis(base_ms.saturating_mul(factor)).min(MAX_RETRY_DELAY)
+}
+
+/// Handle used by the trading loop to emit events
+///
+/// Cloning is cheap; all clones feed the same background delivery task.
+#[derive(Debug, Clone)]
+pub struct WebhookDispatcher {
+    tx: mpsc::UnboundedSender<Outgoing>,
+}
+
+/// Serialized payload waiting to be fanned out to endpoints
+#[derive(Debug)]
+struct Outgoing {
+    kind: WebhookEventKind,
+    id: String,
+    body: String,
+}
+
+impl WebhookDispatcher {
+    /// Start the delivery task. Returns `None` when no endpoints are configured.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn spawn(endpoints: Vec<WebhookConfig>) -> Option<Self> {
+        if endpoints.is_empty() {
+            return None;
+        }
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(delivery_loop(endpoints, rx));
+        Some(Self { tx })
+    }
+
+    /// Queue a fill/close event for delivery (never blocks)
+    pub fn notify(&self, event: WebhookEvent) {
+        self.notify_payload(event.event, event.event_id.clone(), &event);
+    }
+
+    /// Queue any JSON payload for delivery under the given event kind
+    pub fn notify_payload<T: Serialize>(&self, kind: WebhookEventKind, id: String, payload: &T) {
+        let body = match serde_json::to_string(payload) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("Failed to serialize webhook {}: {}", id, e);
+                return;
+            }
+        };
+        if self.tx.send(Outgoing { kind, id, body }).is_err() {
+            warn!("Webhook delivery task stopped - event dropped");
+        }
+    }
+}
+
+/// A pending POST of one event to one endpoint
+struct Delivery {
+    endpoint: usize,
+    kind: WebhookEventKind,
+    event_id: String,
+    body: String,
+    attempt: u32,
+    next_attempt: Instant,
+}
+
+async fn delivery_loop(endpoints: Vec<WebhookConfig>, mut rx: mpsc::UnboundedReceiver<Outgoing>) {
+    let client = reqwest::Client::new();
+    let mut queue: VecDeque<Delivery> = VecDeque::new();
+    let mut open = true;
+
+    while open || !queue.is_empty() {
+        let next_due = queue.iter().map(|d| d.next_attempt).min();
+
+        tokio::select! {
+            event = rx.recv(), if open => match event {
+                Some(event) => {
+                    for endpoint in 0..endpoints.len() {
+                        if queue.len() >= MAX_QUEUED_DELIVERIES {
+                            if let Some(dropped) = queue.pop_front() {
+                                warn!("Webhook queue full - dropping event {}", dropped.event_id);
+                            }
+                        }
+                        queue.push_back(Delivery {
+                            endpoint,
+                            kind: event.kind,
+                            event_id: event.id.clone(),
+                            body: event.body.clone(),
+                            attempt: 0,
+                            next_attempt: Instant::now(),
+                        });
+                    }
+                }
+                None => open = false,
+            },
+            _ = tokio::time::sleep_until(next_due.unwrap_or_else(Instant::now)), if next_due.is_some() => {}
+        }
+
+        let now = Instant::now();
+        let mut pending = VecDeque::with_capacity(queue.len());
+        while let Some(mut delivery) = queue.pop_front() {
+            if delivery.next_attempt > now {
+                pending.push_back(delivery);
+                continue;
+            }
+
+            let endpoint = &endpoints[delivery.endpoint];
+            delivery.attempt += 1;
+            match deliver(&client, endpoint, &delivery).await {
+                Ok(()) => debug!(
+                    "Webhook {} delivered to {} (attempt {})",
+                    delivery.event_id, endpoint.url, delivery.attempt
+                ),
+                Err(e) if delivery.attempt <= endpoint.max_retries => {
+                    let delay = retry_delay(endpoint.retry_backoff_ms, delivery.attempt);
+                    warn!(
+                        "Webhook {} to {} failed (attempt {}): {} - retrying in {:?}",
+                        delivery.event_id, endpoint.url, delivery.attempt, e, delay
+                    );
+                    delivery.next_attempt = Instant::now() + delay;
+                    pending.push_back(delivery);
+                }
+                Err(e) => warn!(
+                    "Webhook {} to {} dropped after {} attempts: {}",
+                    delivery.event_id, endpoint.url, delivery.attempt, e
+                ),
+            }
+        }
+        queue = pending;
+    }
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    endpoint: &WebhookConfig,
+    delivery: &Delivery,
+) -> anyhow::Result<()> {
+    let mut request = client
+        .post(&endpoint.url)
+        .timeout(Duration::from_secs(endpoint.timeout_secs))
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Event", delivery.kind.as_str())
+        .header("X-Webhook-Id", &delivery.event_id);
+    if let Some(secret) = &endpoint.secret {
+        request = request.header("X-Webhook-Signature", sign_payload(&delivery.body, secret));
+    }
+
+    let response = request.body(delivery.body.clone()).send().await?;
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP {}", response.status());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coindcx::auth::verify_signature;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_retry_delay_doubles_and_caps() {
+        assert_eq!(retry_delay(1_000, 1), Duration::from_secs(1));
+        assert_eq!(retry_delay(1_000, 2), Duration::from_secs(2));
+        assert_eq!(retry_delay(1_000, 3), Duration::from_secs(4));
+        assert_eq!(retry_delay(1_000, 20), MAX_RETRY_DELAY);
+    }
+
+    #[test]
+    fn test_fill_event_shape() {
+        let fill = Fill::from_f64(7, 50_000.0, 0.5, Utc::now(), 25.0, true);
+        let event = WebhookEvent::fill(&fill, &Symbol::new("BTCINR"), Side::Buy, true);
+        let json: serde_json::Value = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(json["event"], "fill");
+        assert_eq!(json["side"], "buy");
+        assert_eq!(json["order_id"], 7);
+        assert!(json.get("realized_pnl").is_none());
+    }
+
+    #[test]
+    fn test_signature_verifies() {
+        let body = r#"{"event":"fill"}"#;
+        let header = sign_payload(body, "secret");
+        let hex = header.strip_prefix("sha256=").unwrap();
+        assert!(verify_signature(body, "secret", hex));
+        assert!(!verify_signature(body, "other", hex));
+    }
+
+    /// Endpoint fails once, then accepts: the event must arrive signed on the retry
+    #[tokio::test]
+    async fn test_delivery_retries_until_success() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for status in ["500 Internal Server Error", "200 OK"] {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 8192];
+                let n = socket.read(&mut buf).await.unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).to_string());
+                let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status);
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+            requests
+        });
+
+        let mut endpoint = WebhookConfig::new(url);
+        endpoint.secret = Some("s3cret".to_string());
+        endpoint.retry_backoff_ms = 10;
+        let dispatcher = WebhookDispatcher::spawn(vec![endpoint]).unwrap();
+
+        let fill = Fill::from_f64(1, 100.0, 2.0, Utc::now(), 0.1, false);
+        dispatcher.notify(WebhookEvent::fill(
+            &fill,
+            &Symbol::new("ETHINR"),
+            Side::Sell,
+            true,
+        ));
+
+        let requests = tokio::time::timeout(Duration::from_secs(10), server)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(requests.len(), 2);
+        let last = requests[1].to_lowercase();
+        assert!(last.contains("x-webhook-event: fill"));
+        assert!(last.contains("x-webhook-signature: sha256="));
+    }
+}
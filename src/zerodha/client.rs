@@ -16,7 +16,10 @@ use super::auth::Credentials;
 use super::error::{ZerodhaError, ZerodhaResult};
 use super::types::*;
 use super::API_BASE_URL;
-use crate::common::{CircuitBreaker, CircuitBreakerConfig, RateLimiter, RateLimiterConfig};
+use crate::common::{
+    CircuitBreaker, CircuitBreakerConfig, NetworkConfig, RateLimiter, RateLimiterConfig,
+};
+use crate::symbols::SymbolMapper;
 
 /// Client configuration
 #[derive(Debug, Clone)]
@@ -25,6 +28,10 @@ pub struct ClientConfig {
     pub timeout: StdDuration,
     pub rate_limiter: RateLimiterConfig,
     pub circuit_breaker: CircuitBreakerConfig,
+    /// Proxy, extra root certificates and connect timeout
+    pub network: NetworkConfig,
+    /// Instrument tokens and overrides for config symbols
+    pub symbols: SymbolMapper,
 }
 
 impl Default for ClientConfig {
@@ -34,6 +41,8 @@ impl Default for ClientConfig {
             timeout: StdDuration::from_secs(30),
             rate_limiter: RateLimiterConfig::default().with_rate(10),
             circuit_breaker: CircuitBreakerConfig::default(),
+            network: NetworkConfig::default(),
+            symbols: SymbolMapper::default(),
         }
     }
 }
@@ -49,17 +58,33 @@ impl ClientConfig {
         self
     }
 
+    pub fn with_network(mut self, network: NetworkConfig) -> Self {
+        self.network = network;
+        self
+    }
+
     pub fn with_rate_limit(mut self, requests_per_second: usize) -> Self {
         self.rate_limiter = self.rate_limiter.with_rate(requests_per_second);
         self
     }
+
+    pub fn with_symbols(mut self, symbols: SymbolMapper) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Request historical data for `symbol` (e.g. `NSE:INFY`) by its instrument token
+    pub fn with_instrument_token(mut self, symbol: &str, token: u64) -> Self {
+        self.symbols = self.symbols.with_kite_token(symbol, token);
+        self
+    }
 }
 
 /// Zerodha Kite API Client
 pub struct ZerodhaClient {
     client: Client,
     credentials: Credentials,
-    _config: ClientConfig,
+    config: ClientConfig,
     rate_limiter: RateLimiter,
     circuit_breaker: Arc<Mutex<CircuitBreaker>>,
 }
@@ -76,10 +101,12 @@ impl ZerodhaClient {
         api_secret: impl Into<String>,
         config: ClientConfig,
     ) -> Self {
-        let client = Client::builder()
-            .timeout(config.timeout)
-            .build()
-            .expect("Failed to create HTTP client");
+        let builder = Client::builder().timeout(config.timeout);
+        let client = config
+            .network
+            .apply(builder)
+            .and_then(|b| b.build())
+            .expect("invalid network settings (see NetworkConfig::validate)");
 
         let rate_limiter = RateLimiter::new(config.rate_limiter.clone());
         let circuit_breaker = Arc::new(Mutex::new(CircuitBreaker::new(
@@ -89,12 +116,17 @@ impl ZerodhaClient {
         Self {
             client,
             credentials: Credentials::new(api_key, api_secret),
-            _config: config.clone(),
+            config: config.clone(),
             rate_limiter,
             circuit_breaker,
         }
     }
 
+    /// Symbol mapping, including known instrument tokens
+    pub fn symbol_mapper(&self) -> &SymbolMapper {
+        &self.config.symbols
+    }
+
     /// Set access token after login
     pub fn with_access_token(mut self, token: String) -> Self {
         self.credentials = self.credentials.with_access_token(token);
@@ -104,12 +136,12 @@ impl ZerodhaClient {
     /// Get historical OHLCV data
     ///
     /// # Arguments
-    /// * `instrument` - Trading symbol (e.g., "NSE:RELIANCE", "NSE:NIFTY 50")
+    /// * `instrument_token` - Kite's numeric instrument token (see [`SymbolMapper::kite_token`])
     /// * `interval` - Kite interval ("minute", "5minute", "15minute", "60minute", "day")
     /// * `days` - Number of days of historical data
     pub async fn get_historical_data(
         &self,
-        instrument: &str,
+        instrument_token: u64,
         interval: &str,
         days: u32,
     ) -> ZerodhaResult<Vec<Candle>> {
@@ -129,7 +161,7 @@ impl ZerodhaClient {
 
         let url = format!(
             "{}/instruments/historical/{}/{}",
-            API_BASE_URL, instrument, interval
+            API_BASE_URL, instrument_token, interval
         );
 
         let response = self
@@ -358,6 +390,105 @@ impl ZerodhaClient {
             ))
         }
     }
+
+    /// Cancel a regular order
+    pub async fn cancel_order(&self, order_id: &str) -> ZerodhaResult<()> {
+        {
+            let mut cb = self.circuit_breaker.lock().await;
+            if !cb.can_attempt() {
+                return Err(ZerodhaError::CircuitBreakerOpen);
+            }
+        }
+
+        self.rate_limiter.acquire().await;
+
+        let url = format!("{}/orders/regular/{}", API_BASE_URL, order_id);
+        let response = self
+            .client
+            .delete(&url)
+            .header("X-Kite-Version", "3")
+            .header(
+                "Authorization",
+                format!(
+                    "token {}:{}",
+                    self.credentials.api_key,
+                    self.credentials
+                        .access_token
+                        .as_ref()
+                        .unwrap_or(&String::new())
+                ),
+            )
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            let mut cb = self.circuit_breaker.lock().await;
+            cb.record_success();
+            Ok(())
+        } else {
+            let mut cb = self.circuit_breaker.lock().await;
+            cb.record_failure();
+
+            Err(ZerodhaError::ApiError(
+                response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string()),
+            ))
+        }
+    }
+
+    /// Get funds available per segment
+    pub async fn get_margins(&self) -> ZerodhaResult<Margins> {
+        {
+            let mut cb = self.circuit_breaker.lock().await;
+            if !cb.can_attempt() {
+                return Err(ZerodhaError::CircuitBreakerOpen);
+            }
+        }
+
+        self.rate_limiter.acquire().await;
+
+        let url = format!("{}/user/margins", API_BASE_URL);
+        let response = self
+            .client
+            .get(&url)
+            .header("X-Kite-Version", "3")
+            .header(
+                "Authorization",
+                format!(
+                    "token {}:{}",
+                    self.credentials.api_key,
+                    self.credentials
+                        .access_token
+                        .as_ref()
+                        .unwrap_or(&String::new())
+                ),
+            )
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            let mut cb = self.circuit_breaker.lock().await;
+            cb.record_success();
+
+            let margins: Envelope<Margins> = response
+                .json()
+                .await
+                .map_err(|e| ZerodhaError::ParseError(e.to_string()))?;
+            Ok(margins.data)
+        } else {
+            let mut cb = self.circuit_breaker.lock().await;
+            cb.record_failure();
+
+            Err(ZerodhaError::ApiError(
+                response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string()),
+            ))
+        }
+    }
 }
 
 #[cfg(test)]
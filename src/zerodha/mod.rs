@@ -12,8 +12,30 @@ pub use client::{ClientConfig, ZerodhaClient};
 pub use error::{ZerodhaError, ZerodhaResult};
 pub use types::*;
 
+use crate::capabilities::{ExchangeCapabilities, PrecisionRule};
+
 pub const API_BASE_URL: &str = "https://api.kite.trade";
 
+/// NSE/BSE equities: whole-share quantities, paise prices, Kite's fixed intervals
+pub const CAPABILITIES: ExchangeCapabilities = ExchangeCapabilities {
+    name: "zerodha",
+    order_types: &[
+        crate::oms::OrderType::Market,
+        crate::oms::OrderType::Limit,
+        crate::oms::OrderType::Stop,
+        crate::oms::OrderType::StopLimit,
+    ],
+    timeframes: &["1m", "5m", "15m", "30m", "1h", "1d"],
+    quote_currencies: &[],
+    precision: PrecisionRule::Fixed {
+        quantity_decimals: 0,
+        price_decimals: 2,
+    },
+    min_notional: None,
+    sandbox: false,
+    fill_reports: false,
+};
+
 pub fn to_kite_interval(timeframe: &str) -> Option<&'static str> {
     match timeframe {
         "1m" => Some("minute"),
@@ -71,6 +71,36 @@ pub struct Holding {
     pub pnl: f64,
 }
 
+/// Kite response envelope: `{"status": "success", "data": ...}`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Envelope<T> {
+    pub data: T,
+}
+
+/// Funds per segment (`/user/margins`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Margins {
+    #[serde(default)]
+    pub equity: Option<SegmentMargin>,
+    #[serde(default)]
+    pub commodity: Option<SegmentMargin>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SegmentMargin {
+    /// Cash available to trade
+    pub net: f64,
+    #[serde(default)]
+    pub utilised: UtilisedMargin,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UtilisedMargin {
+    /// Margin blocked by open orders and positions
+    #[serde(default)]
+    pub debits: f64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
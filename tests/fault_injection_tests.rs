@@ -0,0 +1,362 @@
+//! Exchange error paths, driven through injected faults
+//!
+//! Each test stages an order intent, injects a failure into the CoinDCX
+//! client and runs startup recovery against it, asserting the intent lands in
+//! the right state and no fill is booked twice. Run with
+//! `cargo test --features fault-injection`.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crypto_strategies::binance::BinanceClient;
+use crypto_strategies::coindcx::{ClientConfig, CoinDCXClient};
+use crypto_strategies::common::{Fault, FaultInjector};
+use crypto_strategies::intents;
+use crypto_strategies::oms::{Order, OrderType, TimeInForce};
+use crypto_strategies::state_manager::{IntentStatus, OrderIntent, SqliteStateManager};
+use crypto_strategies::{Side, Symbol};
+
+fn temp_state(name: &str) -> (SqliteStateManager, PathBuf) {
+    let path = std::env::temp_dir().join(format!("faults_{}_{}.db", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let backup = path.with_extension("json");
+    (
+        SqliteStateManager::new(&path, &backup, false).unwrap(),
+        path,
+    )
+}
+
+/// A submitted 1.0 BTCINR buy, as left behind by a crash
+fn submitted_intent(state: &SqliteStateManager) -> OrderIntent {
+    let mut order = Order::from_f64(
+        Symbol::new("BTCINR"),
+        Side::Buy,
+        OrderType::Market,
+        1.0,
+        None,
+        None,
+        TimeInForce::GTC,
+        None,
+    );
+    let intent = intents::stage(state, &mut order).unwrap();
+    state
+        .update_intent(&intent.intent_id, IntentStatus::Submitted, Some("ex-1"))
+        .unwrap();
+    intent
+}
+
+/// One retry, so a single fault is recoverable and two exhaust the budget
+fn client(faults: &FaultInjector) -> CoinDCXClient {
+    let config = ClientConfig::default()
+        .with_max_retries(1)
+        .with_timeout(Duration::from_secs(1));
+    CoinDCXClient::with_config("key", "secret", config).with_faults(faults.clone())
+}
+
+fn trade(id: i64, quantity: f64) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "order_id": "ex-1",
+        "side": "buy",
+        "fee_amount": "0.1",
+        "ecode": "I",
+        "quantity": quantity,
+        "price": 100.0,
+        "symbol": "BTCINR",
+        "timestamp": chrono::Utc::now().timestamp_millis() + id,
+    })
+}
+
+fn filled_order(intent: &OrderIntent) -> Fault {
+    Fault::Respond(serde_json::json!({
+        "id": "ex-1",
+        "client_order_id": intent.intent_id,
+        "status": "filled",
+        "total_quantity": 1.0,
+        "remaining_quantity": 0.0,
+        "avg_price": 100.0,
+    }))
+}
+
+#[tokio::test]
+async fn test_rate_limited_lookup_is_retried() {
+    let (state, path) = temp_state("429");
+    let intent = submitted_intent(&state);
+    let faults = FaultInjector::new();
+    faults.push(
+        "trade_history",
+        Fault::Respond(serde_json::json!([trade(1, 1.0)])),
+    );
+    faults
+        .push("order_status", Fault::RateLimited)
+        .push("order_status", filled_order(&intent));
+
+    let resolutions = intents::recover(&state, Some(&client(&faults)), &HashSet::new())
+        .await
+        .unwrap();
+
+    assert_eq!(resolutions.len(), 1);
+    assert_eq!(resolutions[0].status, IntentStatus::Filled);
+    assert_eq!(resolutions[0].missing_fills.len(), 1);
+    assert_eq!(faults.injected(), 3);
+    assert!(state.load_open_intents().unwrap().is_empty());
+    assert_eq!(state.load_fills("").unwrap().len(), 1);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_server_errors_leave_intent_for_next_start() {
+    let (state, path) = temp_state("5xx");
+    let intent = submitted_intent(&state);
+    let faults = FaultInjector::new();
+    faults.push("trade_history", Fault::Respond(serde_json::json!([])));
+    faults.push_n("order_status", Fault::ServerError(503), 2);
+
+    // Retries exhausted: the lookup is skipped, not guessed
+    let resolutions = intents::recover(&state, Some(&client(&faults)), &HashSet::new())
+        .await
+        .unwrap();
+    assert!(resolutions.is_empty());
+    assert_eq!(state.load_open_intents().unwrap().len(), 1);
+
+    // The exchange is back and the order filled in the meantime
+    faults.push(
+        "trade_history",
+        Fault::Respond(serde_json::json!([trade(1, 1.0)])),
+    );
+    faults.push("order_status", filled_order(&intent));
+    let resolutions = intents::recover(&state, Some(&client(&faults)), &HashSet::new())
+        .await
+        .unwrap();
+    assert_eq!(resolutions[0].status, IntentStatus::Filled);
+    assert_eq!(state.load_fills("").unwrap().len(), 1);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_timed_out_history_aborts_recovery() {
+    let (state, path) = temp_state("timeout");
+    let intent = submitted_intent(&state);
+    let faults = FaultInjector::new();
+    faults.push_n("trade_history", Fault::Timeout, 2);
+
+    let err = intents::recover(&state, Some(&client(&faults)), &HashSet::new())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("timed out"));
+    // Nothing decided without the exchange's fills
+    let open = state.load_open_intents().unwrap();
+    assert_eq!(open[0].status, IntentStatus::Submitted);
+    assert_eq!(faults.pending("order_status"), 0);
+
+    faults.push(
+        "trade_history",
+        Fault::Respond(serde_json::json!([trade(1, 1.0)])),
+    );
+    faults.push("order_status", filled_order(&intent));
+    let resolutions = intents::recover(&state, Some(&client(&faults)), &HashSet::new())
+        .await
+        .unwrap();
+    assert_eq!(resolutions[0].status, IntentStatus::Filled);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_partial_fill_is_booked_once_and_completed_later() {
+    let (state, path) = temp_state("partial");
+    let intent = submitted_intent(&state);
+    let faults = FaultInjector::new();
+    faults.push(
+        "trade_history",
+        Fault::Respond(serde_json::json!([trade(1, 0.4)])),
+    );
+    faults.push(
+        "order_status",
+        Fault::partial_fill("ex-1", &intent.intent_id, 1.0, 0.4, 100.0),
+    );
+
+    let resolutions = intents::recover(&state, Some(&client(&faults)), &HashSet::new())
+        .await
+        .unwrap();
+    // Still resting for the remainder
+    assert_eq!(resolutions[0].status, IntentStatus::Submitted);
+    assert_eq!(resolutions[0].missing_fills.len(), 1);
+    assert_eq!(state.load_open_intents().unwrap().len(), 1);
+
+    // The rest fills; the first slice is not booked again
+    faults.push(
+        "trade_history",
+        Fault::Respond(serde_json::json!([trade(1, 0.4), trade(2, 0.6)])),
+    );
+    faults.push("order_status", filled_order(&intent));
+    let resolutions = intents::recover(&state, Some(&client(&faults)), &HashSet::new())
+        .await
+        .unwrap();
+    assert_eq!(resolutions[0].status, IntentStatus::Filled);
+    assert_eq!(resolutions[0].missing_fills.len(), 1);
+    let fills = state.load_fills("").unwrap();
+    let booked: f64 = fills.iter().map(|f| f.quantity).sum();
+    assert_eq!(fills.len(), 2);
+    assert!((booked - 1.0).abs() < 1e-9);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_binance_faults_replace_requests() {
+    let faults = FaultInjector::new();
+    let client = BinanceClient::new().with_faults(faults.clone());
+    faults.push("klines", Fault::ServerError(502));
+    let err = client
+        .get_klines("BTCUSDT", "1h", None, None, Some(1))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("502 Bad Gateway"));
+
+    faults.push(
+        "klines",
+        Fault::Respond(serde_json::json!([[
+            1_735_689_600_000i64,
+            "100.0",
+            "101.0",
+            "99.0",
+            "100.5",
+            "12.0",
+            1_735_693_199_999i64,
+            "1206.0",
+            42,
+            "6.0",
+            "603.0"
+        ]])),
+    );
+    let klines = client
+        .get_klines("BTCUSDT", "1h", None, None, Some(1))
+        .await
+        .unwrap();
+    assert_eq!(klines.len(), 1);
+    assert_eq!(klines[0].close, 100.5);
+}
+
+#[tokio::test]
+async fn test_trade_history_pages_until_short_page() {
+    use crypto_strategies::coindcx::client::TRADE_HISTORY_PAGE;
+
+    let faults = FaultInjector::new();
+    let full: Vec<_> = (1..=TRADE_HISTORY_PAGE as i64)
+        .map(|id| trade(id, 0.1))
+        .collect();
+    faults.push("trade_history", Fault::Respond(serde_json::json!(full)));
+    // A retried page overlaps the last one; the overlap is dropped
+    faults.push("trade_history", Fault::ServerError(503));
+    faults.push(
+        "trade_history",
+        Fault::Respond(serde_json::json!([trade(5000, 0.1), trade(5001, 0.1)])),
+    );
+
+    let to = chrono::Utc::now();
+    let trades = client(&faults)
+        .get_trade_history(None, to - chrono::Duration::days(1), to)
+        .await
+        .unwrap();
+    assert_eq!(trades.len(), TRADE_HISTORY_PAGE as usize + 1);
+    assert_eq!(trades.last().unwrap().id, 5001);
+    assert_eq!(faults.injected(), 3);
+}
+
+#[tokio::test]
+async fn test_order_placement_not_retried_after_server_error() {
+    use crypto_strategies::coindcx::types::{OrderRequest, OrderSide};
+
+    // The 503'd order may have been accepted; sending it again could double it
+    let faults = FaultInjector::new();
+    faults.push("place_order", Fault::ServerError(503));
+    let request = OrderRequest::market(OrderSide::Buy, "BTCINR", 0.1);
+    assert!(client(&faults).place_order(&request).await.is_err());
+    assert_eq!(faults.injected(), 1);
+
+    // A rate limit means it was never processed, so it is retried
+    faults
+        .push("place_order", Fault::RateLimited)
+        .push("place_order", Fault::ServerError(503));
+    assert!(client(&faults).place_order(&request).await.is_err());
+    assert_eq!(faults.injected(), 3);
+}
+
+#[tokio::test]
+async fn test_timed_out_order_is_found_by_client_id() {
+    use crypto_strategies::coindcx::types::{OrderRequest, OrderSide};
+
+    let request =
+        OrderRequest::market(OrderSide::Buy, "BTCINR", 0.1).with_client_order_id("cs-1-abc-1");
+    let placed = |id: &str| {
+        serde_json::json!({
+            "id": id,
+            "client_order_id": "cs-1-abc-1",
+            "status": "open",
+            "total_quantity": 0.1,
+            "remaining_quantity": 0.1,
+        })
+    };
+
+    // The request timed out but the exchange took it: no second order
+    let faults = FaultInjector::new();
+    faults
+        .push("place_order", Fault::Timeout)
+        .push("order_status", Fault::Respond(placed("ex-1")));
+    let response = client(&faults).place_order(&request).await.unwrap();
+    assert_eq!(response.orders[0].id, "ex-1");
+    assert_eq!(faults.injected(), 2);
+
+    // The exchange never saw it: sent again under the same ID
+    faults
+        .push("place_order", Fault::ServerError(503))
+        .push("order_status", Fault::ServerError(404))
+        .push(
+            "place_order",
+            Fault::Respond(serde_json::json!({ "orders": [placed("ex-2")] })),
+        );
+    let response = client(&faults).place_order(&request).await.unwrap();
+    assert_eq!(response.orders[0].id, "ex-2");
+    assert_eq!(faults.injected(), 5);
+}
+
+#[tokio::test]
+async fn test_wait_for_order_polls_until_final() {
+    use crypto_strategies::coindcx::CoinDCXError;
+
+    let status = |status: &str, remaining: f64| {
+        Fault::Respond(serde_json::json!({
+            "id": "ex-1",
+            "status": status,
+            "total_quantity": 1.0,
+            "remaining_quantity": remaining,
+        }))
+    };
+    let faults = FaultInjector::new();
+    faults
+        .push("order_status", status("open", 1.0))
+        .push("order_status", Fault::ServerError(503))
+        .push("order_status", status("partially_filled", 0.4))
+        .push("order_status", status("filled", 0.0));
+
+    // The 503 is retried within the second poll
+    let order = client(&faults)
+        .wait_for_order("ex-1", Duration::from_secs(5))
+        .await
+        .unwrap();
+    assert_eq!(order.status, "filled");
+    assert_eq!(faults.injected(), 4);
+
+    // Still resting when the time is up
+    faults.push_n("order_status", status("open", 1.0), 4);
+    let err = client(&faults)
+        .wait_for_order("ex-1", Duration::from_millis(300))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, CoinDCXError::Timeout(ref m) if m.contains("still open")));
+    faults.clear();
+}
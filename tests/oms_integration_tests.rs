@@ -19,7 +19,7 @@ use crypto_strategies::oms::{
 use crypto_strategies::strategies::volatility_regime::{
     VolatilityRegimeConfig, VolatilityRegimeStrategy,
 };
-use crypto_strategies::strategies::Strategy;
+use crypto_strategies::strategies::{SignalContext, Strategy};
 use crypto_strategies::{Candle, Config, Money, Side, Symbol};
 
 // =============================================================================
@@ -59,6 +59,40 @@ fn generate_realistic_candles(count: usize, base_price: f64, volatility_pct: f64
     candles
 }
 
+/// Daily bars from `t0`, one per close, spanning 1% either side of it
+fn daily_bars(t0: chrono::DateTime<Utc>, closes: impl IntoIterator<Item = f64>) -> Vec<Candle> {
+    closes
+        .into_iter()
+        .enumerate()
+        .map(|(d, close)| Candle {
+            datetime: t0 + Duration::days(d as i64),
+            open: close,
+            high: close * 1.01,
+            low: close * 0.99,
+            close,
+            volume: 1_000.0,
+        })
+        .collect()
+}
+
+/// `candles` as data with a single `1d` timeframe
+fn daily_mtf(candles: Vec<Candle>) -> MultiTimeframeData {
+    let mut mtf = MultiTimeframeData::new("1d");
+    mtf.add_timeframe("1d", candles);
+    mtf
+}
+
+/// A year of realistic BTCINR daily bars
+fn btc_year() -> HashMap<Symbol, MultiTimeframeData> {
+    let candles = generate_realistic_candles(365, 4500000.0, 0.03);
+    HashMap::from([(Symbol::new("BTCINR"), daily_mtf(candles))])
+}
+
+/// The bundled sample config
+fn sample_config() -> Config {
+    serde_json::from_str(include_str!("../configs/sample_config.json")).unwrap()
+}
+
 // =============================================================================
 // OMS Component Tests
 // =============================================================================
@@ -184,6 +218,9 @@ fn test_position_manager_fifo_accounting() {
     assert_eq!(pos.quantity, Money::from_f64(1.5)); // 3.0 - 1.5
     assert_eq!(pos.fills.len(), 1); // Only the partial fill remains
 
+    // The remaining 1.5 of fill2 keeps 3/4 of its commission
+    assert_eq!(pos.fills[0].commission, Money::from_f64(96.0));
+
     // Realized P&L should be calculated
     // First fill: (165000 - 150000) * 1.0 = 15000
     // Second fill: (165000 - 160000) * 0.5 = 2500
@@ -290,14 +327,8 @@ fn test_backtest_with_real_strategy() {
     // Create backtester
     let mut backtester = Backtester::new(config, strategy);
 
-    // Generate test data
-    let btc_candles = generate_realistic_candles(365, 4500000.0, 0.03);
-
-    // Build multi-timeframe data structure
-    let mut mtf_data = HashMap::new();
-    let mut btc_mtf = MultiTimeframeData::new("1d");
-    btc_mtf.add_timeframe("1d", btc_candles);
-    mtf_data.insert(Symbol::new("BTCINR"), btc_mtf);
+    // A year of BTCINR daily data
+    let mtf_data = btc_year();
 
     // Run backtest
     let result = backtester.run(&mtf_data);
@@ -306,6 +337,12 @@ fn test_backtest_with_real_strategy() {
     assert!(!result.equity_curve.is_empty());
     assert!(result.metrics.total_return.is_finite());
 
+    // Every trade carries the strategy's entry commentary
+    for trade in &result.trades {
+        let rationale = trade.rationale.as_deref().expect("missing rationale");
+        assert!(rationale.contains("regime"), "{}", rationale);
+    }
+
     // Metrics should be calculated
     println!("Backtest Results:");
     println!("  Total Return: {:.2}%", result.metrics.total_return);
@@ -315,6 +352,675 @@ fn test_backtest_with_real_strategy() {
     println!("  Max Drawdown: {:.2}%", result.metrics.max_drawdown);
 }
 
+#[test]
+fn test_early_stop_truncates_run() {
+    use crypto_strategies::backtest::StopReason;
+    use crypto_strategies::config::EarlyStopConfig;
+
+    let stop = EarlyStopConfig {
+        max_drawdown: Some(0.30),
+        equity_floor: Some(0.80),
+    };
+    assert_eq!(stop.check(100_000.0, 120_000.0, 100_000.0), None);
+    assert_eq!(
+        stop.check(80_000.0, 120_000.0, 100_000.0),
+        Some(StopReason::Drawdown)
+    );
+    assert_eq!(
+        stop.check(79_000.0, 100_000.0, 100_000.0),
+        Some(StopReason::EquityFloor)
+    );
+
+    let config = sample_config();
+    let strategy = Box::new(VolatilityRegimeStrategy::new(
+        VolatilityRegimeConfig::default(),
+    ));
+    // A floor above starting capital trips on the first bar
+    let mut backtester = Backtester::new(config, strategy).with_early_stop(Some(EarlyStopConfig {
+        max_drawdown: None,
+        equity_floor: Some(2.0),
+    }));
+
+    let mtf_data = btc_year();
+
+    let result = backtester.run(&mtf_data);
+    let truncation = result.truncated.expect("run should stop early");
+    assert_eq!(truncation.reason, StopReason::EquityFloor);
+    assert_eq!(truncation.bars_run, 1);
+    assert_eq!(result.equity_curve.len(), 1);
+    assert!(truncation.progress() < 0.01);
+}
+
+#[test]
+fn test_trailing_drawdown_breach_halts_run() {
+    use crypto_strategies::backtest::StopReason;
+    use crypto_strategies::config::TrailingDrawdownConfig;
+
+    let mtf_data = btc_year();
+
+    let run = |max_loss_pct: f64| {
+        let mut config = sample_config();
+        config.trading.trailing_drawdown = Some(TrailingDrawdownConfig {
+            max_loss_pct,
+            lock_at_initial: true,
+            warn_at: 0.8,
+            flatten: true,
+        });
+        let strategy = Box::new(VolatilityRegimeStrategy::new(
+            VolatilityRegimeConfig::default(),
+        ));
+        Backtester::new(config, strategy).run(&mtf_data)
+    };
+
+    // No allowance: flat equity already sits on the floor
+    let truncation = run(0.0).truncated.expect("breach should stop the run");
+    assert_eq!(truncation.reason, StopReason::TrailingDrawdown);
+    assert_eq!(truncation.bars_run, 1);
+
+    // An allowance the account can never use up leaves the run alone
+    assert!(run(0.99).truncated.is_none());
+}
+
+/// Buys whenever flat; wide fixed stop and target
+#[derive(Clone)]
+struct BuyWhenFlat;
+
+impl Strategy for BuyWhenFlat {
+    fn name(&self) -> &'static str {
+        "buy_when_flat"
+    }
+    fn clone_boxed(&self) -> Box<dyn Strategy> {
+        Box::new(self.clone())
+    }
+    fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
+        if ctx.current_position.is_some() {
+            return vec![];
+        }
+        vec![OrderRequest::market_buy(ctx.symbol.clone(), 10.0)]
+    }
+    fn calculate_stop_loss(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+        entry_price * 0.5
+    }
+    fn calculate_take_profit(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+        entry_price * 2.0
+    }
+    fn update_trailing_stop(
+        &self,
+        _: &crypto_strategies::oms::Position,
+        _: f64,
+        _: &[Candle],
+    ) -> Option<f64> {
+        None
+    }
+}
+
+#[test]
+fn test_ragged_histories_list_and_delist() {
+    let t0 = Utc::now() - Duration::days(100);
+    let flat = |days: std::ops::Range<i64>| {
+        let first = t0 + Duration::days(days.start);
+        daily_mtf(daily_bars(first, days.map(|_| 100.0)))
+    };
+    let mut mtf_data = HashMap::new();
+    mtf_data.insert(Symbol::new("OLDINR"), flat(0..100));
+    mtf_data.insert(Symbol::new("GONEINR"), flat(0..40));
+    mtf_data.insert(Symbol::new("NEWINR"), flat(20..100));
+
+    let mut config = sample_config();
+    config.trading.max_positions = 3;
+    config.backtest.listing_warmup_bars = 10;
+    let result = Backtester::new(config, Box::new(BuyWhenFlat)).run(&mtf_data);
+
+    // The run covers the whole union of histories
+    assert_eq!(result.equity_curve.len(), 100);
+
+    // The delisted symbol is closed on its last candle, not at the end
+    let gone: Vec<_> = result
+        .trades
+        .iter()
+        .filter(|t| t.symbol.as_str() == "GONEINR")
+        .collect();
+    assert_eq!(gone.len(), 1);
+    assert_eq!(gone[0].exit_time, t0 + Duration::days(39));
+
+    // The late listing waits out its warmup before entering
+    let new = result
+        .trades
+        .iter()
+        .find(|t| t.symbol.as_str() == "NEWINR")
+        .expect("late listing should trade");
+    assert!(new.entry_time >= t0 + Duration::days(29));
+}
+
+#[test]
+fn test_slippage_window_scales_fills() {
+    let t0 = Utc::now() - Duration::days(40);
+    let candles = daily_bars(t0, [100.0; 40]);
+    let mtf_data = HashMap::from([(Symbol::new("BTCINR"), daily_mtf(candles))]);
+    let config = sample_config();
+
+    let entry_premium = |mut backtester: Backtester| {
+        let result = backtester.run(&mtf_data);
+        result.trades[0].entry_price.to_f64() / 100.0 - 1.0
+    };
+    let base = entry_premium(Backtester::new(config.clone(), Box::new(BuyWhenFlat)));
+    let drought = entry_premium(
+        Backtester::new(config.clone(), Box::new(BuyWhenFlat)).with_slippage_window(
+            t0,
+            t0 + Duration::days(7),
+            2.0,
+        ),
+    );
+    let later = entry_premium(
+        Backtester::new(config, Box::new(BuyWhenFlat)).with_slippage_window(
+            t0 + Duration::days(30),
+            t0 + Duration::days(37),
+            2.0,
+        ),
+    );
+
+    assert!(base > 0.0);
+    assert!((drought - 2.0 * base).abs() < 1e-9);
+    assert!((later - base).abs() < 1e-12);
+}
+
+#[test]
+fn test_attribution_buckets_add_up_to_baseline() {
+    use crypto_strategies::attribution::Attribution;
+
+    let mtf_data = btc_year();
+    let mut config = sample_config();
+    config.strategy = serde_json::json!({ "name": "buy_when_flat" });
+    crypto_strategies::strategies::register_strategy("buy_when_flat", |_| {
+        Ok(Box::new(BuyWhenFlat))
+    });
+
+    let baseline = Backtester::new(config.clone(), Box::new(BuyWhenFlat)).run(&mtf_data);
+    assert!(!baseline.trades.is_empty());
+    let attribution = Attribution::run(&config, &mtf_data, &baseline).unwrap();
+
+    let pnl = baseline.equity_curve.last().unwrap().1 - config.trading.initial_capital;
+    assert!((attribution.total.total() - pnl).abs() < 1e-6);
+    let by_period: f64 = attribution.periods.iter().map(|p| p.total()).sum();
+    assert!((by_period - pnl).abs() < 1e-6);
+    assert!(attribution.profile.hold_bars >= 1);
+}
+
+#[test]
+fn test_activity_filter_skips_dead_markets() {
+    use crypto_strategies::config::ActivityFilterConfig;
+
+    let t0 = Utc::now() - Duration::days(60);
+    // LIVELYINR swings 4% a day, SLEEPYINR 0.2%
+    let series = |range: f64| {
+        let candles = (0..60)
+            .map(|d| Candle {
+                datetime: t0 + Duration::days(d),
+                open: 100.0,
+                high: 100.0 + range / 2.0,
+                low: 100.0 - range / 2.0,
+                close: 100.0,
+                volume: 1_000.0,
+            })
+            .collect();
+        daily_mtf(candles)
+    };
+    let mut mtf_data = HashMap::new();
+    mtf_data.insert(Symbol::new("LIVELYINR"), series(4.0));
+    mtf_data.insert(Symbol::new("SLEEPYINR"), series(0.2));
+
+    let mut config = sample_config();
+    config.trading.max_positions = 2;
+    config.trading.activity_filter = Some(ActivityFilterConfig {
+        floors: crypto_strategies::config::ActivityFloors {
+            min_atr_pct: Some(1.0),
+            min_volume_24h: None,
+        },
+        ..Default::default()
+    });
+    let result = Backtester::new(config, Box::new(BuyWhenFlat)).run(&mtf_data);
+
+    assert!(result
+        .trades
+        .iter()
+        .all(|t| t.symbol.as_str() == "LIVELYINR"));
+    assert!(!result.trades.is_empty());
+    // The lively market only waits for its ATR to warm up
+    let skips = &result.metrics.activity_skips;
+    assert_eq!(skips["SLEEPYINR"], 60);
+    assert!(skips["LIVELYINR"] < 15);
+}
+
+#[test]
+fn test_hedge_shorts_btc_in_downturn() {
+    use crypto_strategies::config::HedgeConfig;
+
+    let t0 = Utc::now() - Duration::days(120);
+    // BTC rallies for 60 days then bleeds for 60; ALT moves twice as much
+    let btc_returns: Vec<f64> = (0..120)
+        .map(|d| {
+            let drift = if d < 60 { 0.005 } else { -0.005 };
+            drift + if d % 2 == 0 { 0.01 } else { -0.01 }
+        })
+        .collect();
+    let series = |beta: f64| {
+        let mut close = 100.0;
+        let candles = btc_returns
+            .iter()
+            .enumerate()
+            .map(|(d, r)| {
+                let open = close;
+                close *= 1.0 + beta * r;
+                Candle {
+                    datetime: t0 + Duration::days(d as i64),
+                    open,
+                    high: open.max(close) * 1.001,
+                    low: open.min(close) * 0.999,
+                    close,
+                    volume: 1_000.0,
+                }
+            })
+            .collect();
+        daily_mtf(candles)
+    };
+    let mut mtf_data = HashMap::new();
+    mtf_data.insert(Symbol::new("BTCINR"), series(1.0));
+    mtf_data.insert(Symbol::new("ALTINR"), series(2.0));
+
+    let mut config = sample_config();
+    config.trading.max_positions = 2;
+    config.trading.hedge = Some(HedgeConfig {
+        symbol: "BTCINR".into(),
+        max_net_exposure: 0.05,
+        unwind_below: 0.02,
+        beta_lookback: 20,
+        regime_ema: 10,
+        ..Default::default()
+    });
+    let result = Backtester::new(config, Box::new(BuyWhenFlat)).run(&mtf_data);
+
+    let stats = result.metrics.hedge.expect("hedge stats");
+    assert!(stats.opened >= 1);
+    assert!(stats.hedged_bars > 0);
+    let hedges: Vec<_> = result
+        .trades
+        .iter()
+        .filter(|t| t.rationale.as_deref() == Some("hedge"))
+        .collect();
+    assert!(!hedges.is_empty());
+    assert!(hedges
+        .iter()
+        .all(|t| t.symbol.as_str() == "BTCINR" && t.side == Side::Sell));
+    // Shorting into the decline pays off
+    let hedge_pnl: f64 = hedges.iter().map(|t| t.net_pnl.to_f64()).sum();
+    assert!(hedge_pnl > 0.0);
+    assert!((hedge_pnl - stats.net_pnl).abs() < 1e-6);
+}
+
+/// Shorts whenever flat and the close is at least 100; trails `trail`
+/// above the price when set
+#[derive(Clone)]
+struct ShortWhenFlat {
+    trail: Option<f64>,
+}
+
+impl Strategy for ShortWhenFlat {
+    fn name(&self) -> &'static str {
+        "short_when_flat"
+    }
+    fn clone_boxed(&self) -> Box<dyn Strategy> {
+        Box::new(self.clone())
+    }
+    fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
+        let close = ctx.candles.last().map_or(0.0, |c| c.close);
+        if ctx.current_position.is_some() || close < 100.0 {
+            return vec![];
+        }
+        vec![OrderRequest::market_sell(ctx.symbol.clone(), 10.0)]
+    }
+    fn calculate_stop_loss(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+        entry_price * 1.5
+    }
+    fn calculate_take_profit(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+        entry_price * 0.5
+    }
+    fn update_trailing_stop(
+        &self,
+        _: &crypto_strategies::oms::Position,
+        current_price: f64,
+        _: &[Candle],
+    ) -> Option<f64> {
+        self.trail.map(|trail| current_price + trail)
+    }
+}
+
+#[test]
+fn test_margin_short_pays_borrow_interest() {
+    use crypto_strategies::config::BorrowConfig;
+    use crypto_strategies::FeeType;
+
+    let t0 = Utc::now() - Duration::days(60);
+    let candles = daily_bars(t0, [100.0; 60]);
+    let mtf_data = HashMap::from([(Symbol::new("DOGEINR"), daily_mtf(candles))]);
+
+    let run = |borrow: Option<BorrowConfig>| {
+        let mut config = sample_config();
+        config.exchange.maker_fee = 0.0;
+        config.exchange.taker_fee = 0.0;
+        config.exchange.assumed_slippage = 0.0;
+        config.tax.tds_rate = 0.0;
+        config.trading.borrow = borrow;
+        Backtester::new(config, Box::new(ShortWhenFlat { trail: None })).run(&mtf_data)
+    };
+
+    let free = run(None);
+    let borrowed = run(Some(BorrowConfig {
+        default_apr: 0.365,
+        ..Default::default()
+    }));
+    assert_eq!(borrowed.trades.len(), 1);
+    let trade = &borrowed.trades[0];
+    assert_eq!(trade.side, Side::Sell);
+
+    // Borrowed at a flat 100.00, 36.5% APR costs 0.1% of the notional a day
+    let days = (trade.exit_time - trade.entry_time).num_days() as f64;
+    let expected = trade.quantity.to_f64() * 100.0 * 0.001 * days;
+    let interest = trade.fees[&FeeType::Borrow].to_f64();
+    assert!(expected > 0.0);
+    assert!((interest - expected).abs() < 1e-6);
+    assert!((trade.commission.to_f64() - interest).abs() < 1e-6);
+
+    let final_equity =
+        |r: &crypto_strategies::backtest::BacktestResult| r.equity_curve.last().unwrap().1;
+    assert!((final_equity(&free) - final_equity(&borrowed) - interest).abs() < 1e-6);
+}
+
+#[test]
+fn test_short_trailing_stop_follows_price_down() {
+    // Flat at 100, down to 81, then back up to 93
+    let closes: Vec<f64> = std::iter::repeat_n(100.0, 10)
+        .chain((1..=19).map(|d| 100.0 - d as f64))
+        .chain((1..=6).map(|d| 81.0 + 2.0 * d as f64))
+        .collect();
+    let t0 = Utc::now() - Duration::days(closes.len() as i64);
+    let candles = daily_bars(t0, closes);
+    let mtf_data = HashMap::from([(Symbol::new("BTCINR"), daily_mtf(candles))]);
+
+    let mut config = sample_config();
+    config.exchange.assumed_slippage = 0.0;
+    let strategy = ShortWhenFlat { trail: Some(5.0) };
+    let result = Backtester::new(config, Box::new(strategy)).run(&mtf_data);
+
+    // The stop ratchets down to 86 and is hit on the way back up
+    assert_eq!(result.trades.len(), 1);
+    let trade = &result.trades[0];
+    assert_eq!(trade.side, Side::Sell);
+    assert!(trade.exit_price.to_f64() < 90.0);
+    assert!(trade.net_pnl.to_f64() > 0.0);
+}
+
+/// Rests one limit buy at 95, placed on the bar at `place_at`
+#[derive(Clone)]
+struct RestingBid {
+    place_at: chrono::DateTime<Utc>,
+    expires: Option<chrono::DateTime<Utc>>,
+}
+
+impl Strategy for RestingBid {
+    fn name(&self) -> &'static str {
+        "resting_bid"
+    }
+    fn clone_boxed(&self) -> Box<dyn Strategy> {
+        Box::new(self.clone())
+    }
+    fn generate_signal(&self, ctx: &SignalContext) -> Vec<OrderRequest> {
+        if ctx.candles.last().map(|c| c.datetime) != Some(self.place_at) {
+            return vec![];
+        }
+        let bid = OrderRequest::limit_buy(ctx.symbol.clone(), 1.0, 95.0);
+        vec![match self.expires {
+            Some(deadline) => bid.with_time_in_force(TimeInForce::GTD(deadline)),
+            None => bid,
+        }]
+    }
+    fn calculate_stop_loss(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+        entry_price * 0.9
+    }
+    fn calculate_take_profit(&self, _: &[Candle], entry_price: f64, _: Side) -> f64 {
+        entry_price * 1.03
+    }
+    fn update_trailing_stop(
+        &self,
+        _: &crypto_strategies::oms::Position,
+        _: f64,
+        _: &[Candle],
+    ) -> Option<f64> {
+        None
+    }
+}
+
+#[test]
+fn test_resting_limit_fills_intrabar_until_expiry() {
+    // Flat at 100 with one bar dipping to 94 on day 10
+    let t0 = Utc::now() - Duration::days(20);
+    let candles: Vec<Candle> = (0..20)
+        .map(|d| Candle {
+            datetime: t0 + Duration::days(d),
+            open: 100.0,
+            high: 100.5,
+            low: if d == 10 { 94.0 } else { 99.5 },
+            close: if d == 10 { 96.0 } else { 100.0 },
+            volume: 1_000.0,
+        })
+        .collect();
+    let mtf_data = HashMap::from([(Symbol::new("BTCINR"), daily_mtf(candles))]);
+
+    let run = |expires: Option<chrono::DateTime<Utc>>| {
+        let config = sample_config();
+        let strategy = RestingBid {
+            place_at: t0 + Duration::days(2),
+            expires,
+        };
+        Backtester::new(config, Box::new(strategy)).run(&mtf_data)
+    };
+
+    // The bid rests for eight bars and fills at its limit when the dip reaches it
+    let filled = run(None);
+    assert_eq!(filled.trades.len(), 1);
+    assert_eq!(filled.trades[0].entry_price.to_f64(), 95.0);
+    assert!(filled.trades[0].entry_time >= t0 + Duration::days(10));
+
+    // Expired before the dip: never filled
+    let expired = run(Some(t0 + Duration::days(5)));
+    assert!(expired.trades.is_empty());
+}
+
+#[test]
+fn test_trailing_reference_and_give_back() {
+    use crypto_strategies::backtest::{give_back_stats, trailing_reference_price};
+    use crypto_strategies::Trade;
+
+    let candles = generate_realistic_candles(10, 100.0, 0.02);
+    let bar = &candles[3];
+    assert_eq!(trailing_reference_price(Side::Buy, bar, false), bar.close);
+    assert_eq!(trailing_reference_price(Side::Buy, bar, true), bar.high);
+    assert_eq!(trailing_reference_price(Side::Sell, bar, true), bar.low);
+
+    let peak = candles[2..=6]
+        .iter()
+        .map(|c| c.high)
+        .fold(f64::MIN, f64::max);
+    let entry = candles[2].close;
+    let exit = candles[6].close;
+    let trade = Trade {
+        symbol: Symbol::new("BTCINR"),
+        side: Side::Buy,
+        entry_price: Money::from_f64(entry),
+        exit_price: Money::from_f64(exit),
+        quantity: Money::from_f64(1.0),
+        entry_time: candles[2].datetime,
+        exit_time: candles[6].datetime,
+        pnl: Money::from_f64(exit - entry),
+        commission: Money::ZERO,
+        net_pnl: Money::from_f64(exit - entry),
+        fees: Default::default(),
+        rationale: None,
+        tranche: None,
+        r_multiple: None,
+    };
+
+    let mtf_data = HashMap::from([(Symbol::new("BTCINR"), daily_mtf(candles.clone()))]);
+
+    let stats = give_back_stats(&[trade], &mtf_data);
+    assert_eq!(stats.trades, 1);
+    assert!((stats.avg_give_back_pct - (peak - exit) / entry * 100.0).abs() < 1e-6);
+    assert!((stats.avg_mfe_pct - (peak - entry) / entry * 100.0).abs() < 1e-6);
+    assert!(stats.capture_ratio <= 1.0);
+}
+
+#[test]
+fn test_same_bar_stop_target_policy() {
+    use crypto_strategies::backtest::check_exit;
+    use crypto_strategies::config::SameBarPolicy;
+
+    let t = Utc::now();
+    // Long with stop 95 / target 110; bar spans both, opens near the high
+    let wide = Candle::new_unchecked(t, 108.0, 111.0, 94.0, 100.0, 1.0);
+
+    let worst = check_exit(Side::Buy, &wide, 95.0, 110.0, SameBarPolicy::StopFirst);
+    assert!(worst.ambiguous && worst.stopped && !worst.target_hit);
+
+    let best = check_exit(Side::Buy, &wide, 95.0, 110.0, SameBarPolicy::TargetFirst);
+    assert!(best.ambiguous && best.target_hit && !best.stopped);
+
+    // O→H→L→C: high prints first, so the long takes profit and the short stops out
+    let path = check_exit(Side::Buy, &wide, 95.0, 110.0, SameBarPolicy::Path);
+    assert!(path.target_hit);
+    let short = check_exit(Side::Sell, &wide, 111.0, 94.0, SameBarPolicy::Path);
+    assert!(short.ambiguous && short.stopped);
+
+    // Only the target is inside the bar: no ambiguity, policy irrelevant
+    let clean = Candle::new_unchecked(t, 100.0, 111.0, 99.0, 109.0, 1.0);
+    let exit = check_exit(Side::Buy, &clean, 95.0, 110.0, SameBarPolicy::StopFirst);
+    assert!(!exit.ambiguous && exit.target_hit && !exit.stopped);
+}
+
+#[test]
+fn test_exit_ladder_tranches() {
+    use crypto_strategies::backtest::{tranche_attribution, LadderState};
+    use crypto_strategies::config::{ExitLadderConfig, LadderRung};
+    use crypto_strategies::oms::Position;
+
+    let ladder = ExitLadderConfig {
+        rungs: vec![
+            LadderRung {
+                r: 1.0,
+                fraction: 0.5,
+            },
+            LadderRung {
+                r: 2.0,
+                fraction: 0.75,
+            },
+        ],
+        breakeven_after_first: true,
+        keep_target: false,
+    };
+    let fill = Fill::from_f64(1, 100.0, 2.0, Utc::now(), 0.0, false);
+    let pos = Position::from_fill(fill, Symbol::new("BTCINR"), Side::Buy);
+    let mut state = LadderState::new(&pos, 95.0).unwrap();
+    assert_eq!(state.price_at(Side::Buy, 2.0), 110.0);
+    assert_eq!(state.price_at(Side::Sell, 1.0), 95.0);
+    assert_eq!(state.r_multiple(Side::Buy, 107.5), 1.5);
+
+    let (rung, qty) = state.next_rung(&ladder, 2.0).unwrap();
+    assert_eq!((rung.r, qty), (1.0, 1.0));
+    state.filled = 1;
+    // Later rungs are capped at what is still open
+    assert_eq!(state.next_rung(&ladder, 1.0).unwrap().1, 1.0);
+    state.filled = 2;
+    assert!(state.next_rung(&ladder, 1.0).is_none());
+    assert!(LadderState::new(&pos, 100.0).is_none());
+
+    // Every record of a laddered run is attributed to a tranche
+    let mut config = sample_config();
+    config.trading.exit_ladder = Some(ladder);
+    let strategy = Box::new(VolatilityRegimeStrategy::new(
+        VolatilityRegimeConfig::default(),
+    ));
+    let mtf_data = btc_year();
+
+    let result = Backtester::new(config, strategy).run(&mtf_data);
+    assert!(result.trades.iter().all(|t| t.tranche.is_some()));
+    let stats = tranche_attribution(&result.trades);
+    assert_eq!(
+        stats.iter().map(|s| s.trades).sum::<usize>(),
+        result.trades.len()
+    );
+    for s in stats.iter().filter(|s| s.tranche.ends_with('R')) {
+        assert!(s.avg_r > 0.5, "{:?}", s);
+    }
+}
+
+#[tokio::test]
+async fn test_backtest_progress_stream() {
+    use crypto_strategies::backtest_stream::BacktestStream;
+
+    let config = sample_config();
+    let mtf_data = btc_year();
+    let new_backtester = || {
+        Backtester::new(
+            config.clone(),
+            Box::new(VolatilityRegimeStrategy::new(
+                VolatilityRegimeConfig::default(),
+            )),
+        )
+    };
+    let expected = new_backtester().run(&mtf_data);
+
+    let mut stream = BacktestStream::spawn(new_backtester(), mtf_data, 50);
+    let mut snapshots = Vec::new();
+    while let Some(progress) = stream.next().await {
+        snapshots.push(progress);
+    }
+    let result = stream.finish().await.unwrap();
+
+    let last = snapshots.last().unwrap();
+    assert_eq!(last.bar, last.total_bars);
+    assert_eq!(last.percent(), 100.0);
+    assert!(snapshots.windows(2).all(|w| w[0].bar < w[1].bar));
+    assert!(snapshots[..snapshots.len() - 1]
+        .iter()
+        .all(|p| p.bar % 50 == 0));
+    assert_eq!(
+        last.equity,
+        expected.equity_curve.last().unwrap().1,
+        "streamed run matches a plain run"
+    );
+    // Trades arrive once each; positions closed at the end only show in the result
+    let streamed: usize = snapshots.iter().map(|p| p.new_trades.len()).sum();
+    assert_eq!(streamed, last.trades);
+    assert!(streamed <= result.trades.len());
+    assert_eq!(result.trades.len(), expected.trades.len());
+}
+
+#[test]
+fn test_backtester_try_new_rejects_bad_pre_trade_checks() {
+    use crypto_strategies::config::{PreTradeCheckConfig, TimeWindow};
+
+    let mut config = sample_config();
+    config.trading.pre_trade_checks = vec![PreTradeCheckConfig::RestrictedHours {
+        windows: vec![TimeWindow {
+            start: "25:00".to_string(),
+            end: "01:00".to_string(),
+        }],
+    }];
+    let strategy = Box::new(VolatilityRegimeStrategy::new(
+        VolatilityRegimeConfig::default(),
+    ));
+    let err = Backtester::try_new(config, strategy).err().unwrap();
+    assert!(format!("{:#}", err).contains("25:00"));
+}
+
 #[test]
 fn test_order_request_builders() {
     let symbol = Symbol::new("ETHINR");